@@ -5,7 +5,7 @@ use cargo_metadata::{
 use git_repository as git;
 
 use crate::{
-    changelog::{section::segment, Section},
+    changelog::{merge, section::segment, Section, Template},
     commit, ChangeLog,
 };
 
@@ -34,6 +34,17 @@ pub struct Outcome {
     pub state: State,
     pub lock: git::lock::File,
     pub previous_content: Option<String>,
+    /// What happened to user segments while merging into an existing changelog.
+    pub merge: merge::Outcome,
+}
+
+/// Like [`Outcome`], but obtained without locking the changelog for writing.
+pub struct Merged {
+    pub log: ChangeLog,
+    pub state: State,
+    pub previous_content: Option<String>,
+    /// What happened to user segments while merging into an existing changelog.
+    pub merge: merge::Outcome,
 }
 
 impl ChangeLog {
@@ -42,7 +53,46 @@ impl ChangeLog {
         history: &commit::History,
         ctx: &'a crate::Context,
         selection: segment::Selection,
+        strategy: merge::Strategy,
     ) -> anyhow::Result<Outcome> {
+        let generated = Self::generated_for_package(package, history, ctx, selection)?;
+        let changelog_path = path_from_manifest(&package.manifest_path);
+        let lock =
+            git::lock::File::acquire_to_update_resource(&changelog_path, git::lock::acquire::Fail::Immediately, None)?;
+        let Merged {
+            log,
+            state,
+            previous_content,
+            merge,
+        } = Self::merge_with_existing(generated, &changelog_path, ctx, strategy);
+        Ok(Outcome {
+            log,
+            state,
+            lock,
+            previous_content,
+            merge,
+        })
+    }
+
+    /// Produce the changelog of `package` as it would be written, without acquiring a lock.
+    pub fn for_package<'a>(
+        package: &'a Package,
+        history: &commit::History,
+        ctx: &'a crate::Context,
+        selection: segment::Selection,
+        strategy: merge::Strategy,
+    ) -> anyhow::Result<Merged> {
+        let generated = Self::generated_for_package(package, history, ctx, selection)?;
+        let changelog_path = path_from_manifest(&package.manifest_path);
+        Ok(Self::merge_with_existing(generated, &changelog_path, ctx, strategy))
+    }
+
+    fn generated_for_package(
+        package: &Package,
+        history: &commit::History,
+        ctx: &crate::Context,
+        selection: segment::Selection,
+    ) -> anyhow::Result<ChangeLog> {
         let mut generated = ChangeLog::from_history_segments(
             package,
             &crate::git::history::crate_ref_segments(
@@ -53,6 +103,7 @@ impl ChangeLog {
             )?,
             &ctx.repo,
             selection,
+            &ctx.changelog_template,
         );
         generated.sections.insert(
             0,
@@ -61,28 +112,35 @@ impl ChangeLog {
                 generated: true,
             },
         );
-        let changelog_path = path_from_manifest(&package.manifest_path);
-        let lock =
-            git::lock::File::acquire_to_update_resource(&changelog_path, git::lock::acquire::Fail::Immediately, None)?;
-        let (log, state, previous_content) = if let Ok(markdown) = std::fs::read_to_string(changelog_path) {
-            let existing_log = ChangeLog::from_markdown(&markdown);
+        Ok(generated)
+    }
+
+    fn merge_with_existing(
+        generated: ChangeLog,
+        changelog_path: &Utf8Path,
+        ctx: &crate::Context,
+        strategy: merge::Strategy,
+    ) -> Merged {
+        let (log, state, previous_content, merge) = if let Ok(markdown) = std::fs::read_to_string(changelog_path) {
+            let existing_log = ChangeLog::from_markdown_with_template(&markdown, &ctx.changelog_template);
             let copy_of_existing = existing_log.clone();
-            let merged = existing_log.merge_generated(generated);
+            let (merged, merge) = existing_log.merge_generated_with(generated, strategy);
             let changed = merged != copy_of_existing;
             (
                 merged,
                 if changed { State::Modified } else { State::Unchanged },
                 Some(markdown),
+                merge,
             )
         } else {
-            (generated, State::Created, None)
+            (generated, State::Created, None, merge::Outcome::default())
         };
-        Ok(Outcome {
+        Merged {
             log,
             state,
-            lock,
             previous_content,
-        })
+            merge,
+        }
     }
 
     pub fn for_crate_by_name_with_write_lock<'a>(
@@ -90,8 +148,9 @@ impl ChangeLog {
         history: &commit::History,
         ctx: &'a crate::Context,
         selection: segment::Selection,
+        strategy: merge::Strategy,
     ) -> anyhow::Result<(Outcome, &'a Package)> {
-        let out = Self::for_package_with_write_lock(package, history, ctx, selection)?;
+        let out = Self::for_package_with_write_lock(package, history, ctx, selection, strategy)?;
         Ok((out, package))
     }
 
@@ -100,21 +159,26 @@ impl ChangeLog {
         segments: &[commit::history::Segment<'_>],
         repo: &git::Repository,
         selection: segment::Selection,
+        template: &Template,
+    ) -> Self {
+        Self::from_history_segments_with(segments, |segment, prev_segment| {
+            Section::from_history_segment(package, segment, repo, selection, template, prev_segment)
+        })
+    }
+
+    /// Create a changelog with one section per segment, each created with `section(segment, previous_segment)`.
+    pub(crate) fn from_history_segments_with<'a>(
+        segments: &'a [commit::history::Segment<'a>],
+        mut section: impl FnMut(&'a commit::history::Segment<'a>, Option<&'a commit::history::Segment<'a>>) -> Section,
     ) -> Self {
         ChangeLog {
             sections: {
                 let mut s = segments.windows(2).fold(Vec::new(), |mut acc, segments| {
-                    acc.push(Section::from_history_segment(
-                        package,
-                        &segments[0],
-                        repo,
-                        selection,
-                        (&segments[1]).into(),
-                    ));
+                    acc.push(section(&segments[0], (&segments[1]).into()));
                     acc
                 });
                 if let Some(segment) = segments.last() {
-                    s.push(Section::from_history_segment(package, segment, repo, selection, None))
+                    s.push(section(segment, None))
                 }
                 s
             },
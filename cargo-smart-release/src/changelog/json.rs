@@ -0,0 +1,149 @@
+//! Serialize a parsed or generated [`ChangeLog`] into JSON for consumption by tools, as alternative to its markdown.
+use serde_json::{json, Value};
+
+use crate::{
+    changelog::{
+        section,
+        section::{segment, segment::details::Category, Segment},
+        Section, Template, Version,
+    },
+    ChangeLog,
+};
+
+impl ChangeLog {
+    /// Return all sections of this changelog as JSON object, with releases and their segments represented by their data
+    /// instead of their markdown.
+    pub fn to_json(&self) -> Value {
+        self.to_json_with_template(&Template::default())
+    }
+
+    /// Like [`to_json()`][ChangeLog::to_json()], but use the headlines of `template` for sections of git-conventional messages.
+    pub fn to_json_with_template(&self, template: &Template) -> Value {
+        json!({ "sections": self.sections.iter().map(|section| section.to_json_with_template(template)).collect::<Vec<_>>() })
+    }
+}
+
+impl Section {
+    /// Return this section as JSON object, with its kind in the `type` field.
+    pub fn to_json(&self) -> Value {
+        self.to_json_with_template(&Template::default())
+    }
+
+    /// Like [`to_json()`][Section::to_json()], but use the headlines of `template` for sections of git-conventional messages.
+    pub fn to_json_with_template(&self, template: &Template) -> Value {
+        match self {
+            Section::Verbatim { text, generated } => json!({
+                "type": "verbatim",
+                "text": text,
+                "generated": generated,
+            }),
+            Section::Release {
+                name,
+                date,
+                version_prefix,
+                removed_messages,
+                segments,
+                unknown,
+                heading_level: _,
+            } => json!({
+                "type": "release",
+                "version": match name {
+                    Version::Unreleased => Value::Null,
+                    Version::Semantic(version) => version.to_string().into(),
+                },
+                "version_prefix": version_prefix,
+                "date": date.map(|date| format!("{:04}-{:02}-{:02}", date.year(), date.month() as u32, date.day())),
+                "timestamp": date.map(|date| date.unix_timestamp()),
+                "removed_messages": removed_messages.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                "segments": segments.iter().map(|segment| segment.to_json(template)).collect::<Vec<_>>(),
+                "unknown": if unknown.is_empty() { None } else { Some(unknown) },
+            }),
+        }
+    }
+}
+
+impl Segment {
+    /// Return this segment as JSON object, with its kind in the `type` field.
+    ///
+    /// Read-only segments keep their generated content in `data`, which is `null` if it was parsed from markdown.
+    /// Sections of git-conventional messages have the headline of their kind according to `template`.
+    pub fn to_json(&self, template: &Template) -> Value {
+        match self {
+            Segment::User { markdown } => json!({ "type": "user", "markdown": markdown }),
+            Segment::Conventional(segment::Conventional {
+                kind,
+                is_breaking,
+                scope,
+                removed,
+                messages,
+            }) => json!({
+                "type": "conventional",
+                "kind": kind,
+                "headline": template.headline(kind),
+                "is_breaking": is_breaking,
+                "scope": scope,
+                "removed": removed.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                "messages": messages.iter().map(|message| match message {
+                    segment::conventional::Message::User { markdown } => json!({ "type": "user", "markdown": markdown }),
+                    segment::conventional::Message::Generated { id, title, body } => json!({
+                        "type": "generated",
+                        "id": id.to_string(),
+                        "title": title,
+                        "body": body,
+                    }),
+                }).collect::<Vec<_>>(),
+            }),
+            Segment::Details(data) => json!({
+                "type": "details",
+                "data": generated(data, |segment::Details { commits_by_category }| {
+                    json!({
+                        "commits_by_category": commits_by_category.iter().map(|(category, messages)| json!({
+                            "issue": issue(category),
+                            "messages": messages.iter().map(|message| json!({
+                                "id": message.id.to_string(),
+                                "title": message.title,
+                            })).collect::<Vec<_>>(),
+                        })).collect::<Vec<_>>()
+                    })
+                }),
+            }),
+            Segment::Statistics(data) => json!({
+                "type": "statistics",
+                "data": generated(data, |segment::CommitStatistics {
+                    count,
+                    duration,
+                    conventional_count,
+                    unique_issues,
+                    time_passed_since_last_release,
+                }| {
+                    json!({
+                        "count": count,
+                        "conventional_count": conventional_count,
+                        "duration_in_days": duration.map(|d| d.whole_days()),
+                        "days_passed_since_last_release": time_passed_since_last_release.map(|d| d.whole_days()),
+                        "unique_issues": unique_issues.iter().map(issue).collect::<Vec<_>>(),
+                    })
+                }),
+            }),
+            Segment::Clippy(data) => json!({
+                "type": "clippy",
+                "data": generated(data, |segment::ThanksClippy { count }| json!({ "count": count })),
+            }),
+        }
+    }
+}
+
+fn generated<T>(data: &section::Data<T>, to_json: impl FnOnce(&T) -> Value) -> Value {
+    match data {
+        section::Data::Parsed => Value::Null,
+        section::Data::Generated(data) => to_json(data),
+    }
+}
+
+/// The issue name of `category`, or `null` for uncategorized commits.
+fn issue(category: &Category) -> Value {
+    match category {
+        Category::Issue(issue) => issue.as_str().into(),
+        Category::Uncategorized => Value::Null,
+    }
+}
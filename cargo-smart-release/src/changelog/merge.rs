@@ -1,5 +1,6 @@
 use std::{collections::VecDeque, iter::FromIterator};
 
+use anyhow::bail;
 use git_repository::hash::ObjectId;
 
 use crate::{
@@ -11,12 +12,66 @@ use crate::{
     ChangeLog,
 };
 
+/// Determines how generated content is merged into a changelog that may have been edited by hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Keep everything written by hand and only add or update generated content, placing new releases by their version.
+    PreferUser,
+    /// Replace the segments of releases present in both changelogs with their generated counterparts, discarding
+    /// what was written by hand in these releases. Text that couldn't be parsed is still kept.
+    PreferGenerated,
+    /// Like [`PreferUser`][Strategy::PreferUser], but place new releases by their date instead of their version,
+    /// falling back to the version if there is no date to compare.
+    InterleaveByDate,
+}
+
+impl std::str::FromStr for Strategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "prefer-user" => Strategy::PreferUser,
+            "prefer-generated" => Strategy::PreferGenerated,
+            "interleave-by-date" => Strategy::InterleaveByDate,
+            _ => bail!(
+                "Invalid merge strategy '{}', expected one of 'prefer-user', 'prefer-generated' or 'interleave-by-date'",
+                s
+            ),
+        })
+    }
+}
+
+/// Markdown written by hand within a release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserSegment {
+    /// The release the markdown belongs to.
+    pub release: Version,
+    /// The markdown itself, either a whole segment or a message in a conventional segment.
+    pub markdown: String,
+}
+
+/// Information about what happened to content written by hand during a merge.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// User segments that are part of the merged changelog.
+    pub preserved: Vec<UserSegment>,
+    /// User segments that were dropped in favor of generated content.
+    pub discarded: Vec<UserSegment>,
+}
+
 impl ChangeLog {
     /// Bring `generated` into `self` in such a way that `self` preserves everything while enriching itself from `generated`.
     /// Thus we clearly assume that `self` is parsed and `generated` is generated.
-    pub fn merge_generated(mut self, rhs: Self) -> Self {
+    pub fn merge_generated(self, rhs: Self) -> Self {
+        self.merge_generated_with(rhs, Strategy::PreferUser).0
+    }
+
+    /// Like [`merge_generated()`][ChangeLog::merge_generated()], but resolve conflicts according to `strategy` and
+    /// report which user segments were preserved or discarded.
+    pub fn merge_generated_with(mut self, rhs: Self, strategy: Strategy) -> (Self, Outcome) {
+        let mut outcome = Outcome::default();
         if self.sections.is_empty() {
-            return rhs;
+            return (rhs, outcome);
         }
 
         let mut sections_to_merge = VecDeque::from_iter(rhs.sections);
@@ -36,7 +91,7 @@ impl ChangeLog {
                 Some((idx, level, prefix)) => (idx, *level, prefix.to_owned()),
                 None => {
                     sections.extend(sections_to_merge);
-                    return self;
+                    return (self, outcome);
                 }
             };
 
@@ -45,25 +100,39 @@ impl ChangeLog {
                 Section::Verbatim { .. } => {
                     unreachable!("BUG: generated logs may only have verbatim sections at the beginning")
                 }
-                Section::Release { ref name, .. } => match find_target_section(name, sections, first_release_pos) {
-                    Insertion::MergeWith(pos) => sections[pos].merge(section_to_merge),
-                    Insertion::At(pos) => {
-                        if let Section::Release {
-                            heading_level,
-                            version_prefix,
-                            ..
-                        } = &mut section_to_merge
-                        {
-                            *heading_level = first_release_indentation;
-                            *version_prefix = first_version_prefix.clone();
+                Section::Release { ref name, ref date, .. } => {
+                    let insertion = match find_target_section(name, sections, first_release_pos) {
+                        Insertion::At(pos) if strategy == Strategy::InterleaveByDate => {
+                            Insertion::At(find_position_by_date(date, sections).unwrap_or(pos))
+                        }
+                        insertion => insertion,
+                    };
+                    match insertion {
+                        Insertion::MergeWith(pos) => match strategy {
+                            Strategy::PreferGenerated => {
+                                sections[pos].replace_with_generated(section_to_merge, &mut outcome.discarded)
+                            }
+                            Strategy::PreferUser | Strategy::InterleaveByDate => sections[pos].merge(section_to_merge),
+                        },
+                        Insertion::At(pos) => {
+                            if let Section::Release {
+                                heading_level,
+                                version_prefix,
+                                ..
+                            } = &mut section_to_merge
+                            {
+                                *heading_level = first_release_indentation;
+                                *version_prefix = first_version_prefix.clone();
+                            }
+                            sections.insert(pos, section_to_merge);
                         }
-                        sections.insert(pos, section_to_merge);
                     }
-                },
+                }
             }
         }
 
-        self
+        outcome.preserved = user_segments(sections);
+        (self, outcome)
     }
 }
 
@@ -121,6 +190,75 @@ impl Section {
             }
         }
     }
+
+    /// Replace all segments with the ones of the generated `src`, collecting everything written by hand into `discarded`.
+    fn replace_with_generated(&mut self, src: Section, discarded: &mut Vec<UserSegment>) {
+        match (self, src) {
+            (Section::Verbatim { .. }, _) | (_, Section::Verbatim { .. }) => {
+                unreachable!("BUG: we should never try to merge into or from a verbatim section")
+            }
+            (
+                Section::Release {
+                    name,
+                    date: dest_date,
+                    segments: dest_segments,
+                    removed_messages,
+                    ..
+                },
+                Section::Release {
+                    date: src_date,
+                    segments: src_segments,
+                    ..
+                },
+            ) => {
+                for segment in dest_segments.drain(..) {
+                    discarded.extend(user_markdown(segment).into_iter().map(|markdown| UserSegment {
+                        release: name.clone(),
+                        markdown,
+                    }));
+                }
+                *dest_segments = src_segments;
+                removed_messages.clear();
+                *dest_date = src_date;
+            }
+        }
+    }
+}
+
+/// Return all markdown written by hand in `segment`.
+fn user_markdown(segment: Segment) -> Vec<String> {
+    match segment {
+        Segment::User { markdown } => vec![markdown],
+        Segment::Conventional(conventional) => conventional
+            .messages
+            .into_iter()
+            .filter_map(|m| match m {
+                conventional::Message::User { markdown } => Some(markdown),
+                conventional::Message::Generated { .. } => None,
+            })
+            .collect(),
+        Segment::Details(_) | Segment::Statistics(_) | Segment::Clippy(_) => Vec::new(),
+    }
+}
+
+fn user_segments(sections: &[Section]) -> Vec<UserSegment> {
+    sections
+        .iter()
+        .filter_map(|s| match s {
+            Section::Release { name, segments, .. } => Some((name, segments)),
+            Section::Verbatim { .. } => None,
+        })
+        .flat_map(|(name, segments)| {
+            segments
+                .iter()
+                .cloned()
+                .flat_map(user_markdown)
+                .map(move |markdown| UserSegment {
+                    release: name.clone(),
+                    markdown,
+                })
+        })
+        .collect()
 }
 
 #[derive(Clone, Copy)]
@@ -155,15 +293,18 @@ fn merge_conventional(
         "generated sections never contains removed items"
     );
     let mut found_one = false;
-    for dest_segment in dest_segments.iter_mut().filter(
-        |s| matches!(s, Segment::Conventional(rhs) if rhs.kind == src.kind && rhs.is_breaking == src.is_breaking),
-    ) {
+    let is_same_kind = |s: &Segment| matches!(s, Segment::Conventional(rhs) if rhs.kind == src.kind && rhs.is_breaking == src.is_breaking);
+    for dest_segment in dest_segments
+        .iter_mut()
+        .filter(|s| is_same_kind(s) && matches!(s, Segment::Conventional(rhs) if rhs.scope == src.scope))
+    {
         match dest_segment {
             Segment::Conventional(section::segment::Conventional {
                 removed,
                 messages,
                 kind: _,
                 is_breaking: _,
+                scope: _,
             }) => {
                 for src_message in src.messages.clone() {
                     match src_message {
@@ -198,17 +339,33 @@ fn merge_conventional(
         && (has_user_messages(&src.messages)
             || at_least_one_generated_message_visible(removed_in_release, &src.messages))
     {
-        dest_segments.insert(
-            dest_segments
+        let scoped_segment_pos = || {
+            // keep scoped segments below the heading of their kind, after all segments of the same kind that sort before them
+            src.scope.as_ref()?;
+            let mut same_kind = dest_segments
                 .iter()
                 .enumerate()
-                .find_map(|(pos, item)| {
-                    if matches!(item, Segment::User { .. }) {
-                        // we know that the segment that follows (if one) is generated, so this won't be between two user segments
-                        Some(pos + 1)
-                    } else {
-                        None
-                    }
+                .filter(|(_, s)| is_same_kind(s))
+                .peekable();
+            let first = same_kind.peek().map(|(pos, _)| *pos)?;
+            Some(
+                same_kind
+                    .take_while(|(_, s)| matches!(s, Segment::Conventional(rhs) if rhs.scope < src.scope))
+                    .last()
+                    .map_or(first, |(pos, _)| pos + 1),
+            )
+        };
+        dest_segments.insert(
+            scoped_segment_pos()
+                .or_else(|| {
+                    dest_segments.iter().enumerate().find_map(|(pos, item)| {
+                        if matches!(item, Segment::User { .. }) {
+                            // we know that the segment that follows (if one) is generated, so this won't be between two user segments
+                            Some(pos + 1)
+                        } else {
+                            None
+                        }
+                    })
                 })
                 .unwrap_or(0),
             {
@@ -276,7 +433,16 @@ fn find_target_section(wanted: &Version, sections: &[Section], first_release_ind
                     // We had nothing to compare against, append to the end
                     pos = sections.len();
                 }
-                if min_distance < (0, 0, 0) {
+                if min_distance == (0, 0, 0) {
+                    // Pre-releases share their numbers with the release, so order them by their full version.
+                    let same_release = |s: &Section| matches!(s, Section::Release { name, .. } if version_distance(name, version) == (0, 0, 0));
+                    let newer_versions = sections[pos..]
+                        .iter()
+                        .take_while(|s| same_release(s))
+                        .filter(|s| matches!(s, Section::Release { name: Version::Semantic(existing), .. } if existing > version))
+                        .count();
+                    Insertion::At(pos + newer_versions)
+                } else if min_distance < (0, 0, 0) {
                     Insertion::At(pos + 1)
                 } else {
                     Insertion::At(pos)
@@ -286,6 +452,25 @@ fn find_target_section(wanted: &Version, sections: &[Section], first_release_ind
     }
 }
 
+/// Return the position of the first release older than `date`, or the position after the last release with a date
+/// if all are newer. Return `None` if there is nothing to compare against.
+fn find_position_by_date(date: &Option<time::OffsetDateTime>, sections: &[Section]) -> Option<usize> {
+    let date = (*date)?;
+    let mut last_dated_release = None;
+    for (idx, section) in sections.iter().enumerate() {
+        if let Section::Release {
+            date: Some(existing), ..
+        } = section
+        {
+            if *existing < date {
+                return Some(idx);
+            }
+            last_dated_release = Some(idx);
+        }
+    }
+    last_dated_release.map(|idx| idx + 1)
+}
+
 type Distance = (i64, i64, i64);
 
 const MAX_DISTANCE: Distance = (i64::MAX, i64::MAX, i64::MAX);
@@ -3,9 +3,14 @@ use std::cmp::Ordering;
 use crate::{changelog::section::segment::conventional::as_headline, ChangeLog};
 
 pub mod init;
-mod merge;
+pub mod json;
+pub mod merge;
 mod parse;
+pub mod project;
 pub mod section;
+pub mod template;
+pub use template::Template;
+pub mod workspace;
 pub mod write;
 
 pub const DEFAULT_HEADING_LEVEL: usize = 2;
@@ -74,6 +79,24 @@ impl ChangeLog {
             .expect("we never have an entirely empty changelog");
         self.sections.remove(pos)
     }
+    /// Return a changelog with only the release sections of versions newer than `from` up to and including `to`, in their
+    /// original order, to obtain the changes that were made between two releases.
+    pub fn releases_between(&self, from: &semver::Version, to: &semver::Version) -> ChangeLog {
+        ChangeLog {
+            sections: self
+                .sections
+                .iter()
+                .filter(|section| match section {
+                    Section::Release {
+                        name: Version::Semantic(version),
+                        ..
+                    } => version > from && version <= to,
+                    _ => false,
+                })
+                .cloned()
+                .collect(),
+        }
+    }
     pub fn most_recent_release_section_mut(&mut self) -> (usize, &mut Section) {
         self.sections
             .iter_mut()
@@ -106,7 +129,7 @@ impl Section {
                     return false;
                 };
                 segments.iter().any(
-                    |s| matches!(s, section::Segment::Conventional(section::segment::Conventional {kind, removed, is_breaking, ..}) if *is_breaking && removed.is_empty() && as_headline(*kind).is_none()),
+                    |s| matches!(s, section::Segment::Conventional(section::segment::Conventional {kind, removed, is_breaking, ..}) if *is_breaking && removed.is_empty() && as_headline(kind).is_none()),
                 )
             }
         }
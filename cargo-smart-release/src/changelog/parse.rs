@@ -20,11 +20,8 @@ use crate::{
     changelog,
     changelog::{
         section,
-        section::{
-            segment::{conventional::as_headline, Conventional},
-            Segment,
-        },
-        Section,
+        section::{segment::Conventional, Segment},
+        Section, Template,
     },
     ChangeLog,
 };
@@ -32,6 +29,12 @@ use crate::{
 impl ChangeLog {
     /// Obtain as much information as possible from `input` and keep everything we didn't understand in respective sections.
     pub fn from_markdown(input: &str) -> ChangeLog {
+        Self::from_markdown_with_template(input, &Template::default())
+    }
+
+    /// Like [`from_markdown()`][ChangeLog::from_markdown()], but recognize sections of git-conventional messages by the
+    /// headlines of `template`.
+    pub fn from_markdown_with_template(input: &str, template: &Template) -> ChangeLog {
         let mut sections = Vec::new();
         let mut section_body = String::new();
         let mut previous_headline = None::<Headline>;
@@ -47,6 +50,7 @@ impl ChangeLog {
                             sections.push(Section::from_headline_and_body(
                                 headline,
                                 std::mem::take(&mut section_body),
+                                template,
                             ));
                         }
                         None => {
@@ -71,6 +75,7 @@ impl ChangeLog {
                 sections.push(Section::from_headline_and_body(
                     headline,
                     std::mem::take(&mut section_body),
+                    template,
                 ));
             }
             None => sections.push(Section::Verbatim {
@@ -114,6 +119,7 @@ impl Section {
             date,
         }: Headline,
         body: String,
+        template: &Template,
     ) -> Self {
         let mut events = pulldown_cmark::Parser::new_ext(&body, pulldown_cmark::Options::all())
             .into_offset_iter()
@@ -143,7 +149,7 @@ impl Section {
                 Event::Start(Tag::Heading(indent, _, _)) => {
                     record_unknown_range(&mut segments, unknown_range.take(), &body);
                     enum State {
-                        ParseConventional { title: String },
+                        ParseConventional { title: String, kind: String },
                         SkipGenerated,
                         ConsiderUserAuthored,
                     }
@@ -164,20 +170,9 @@ impl Section {
                             segments.push(Segment::Details(section::Data::Parsed));
                             State::SkipGenerated
                         }
-                        Some((Event::Text(title), _range))
-                            if title.starts_with(as_headline("feat").expect("valid"))
-                                || title.starts_with(as_headline("add").expect("valid"))
-                                || title.starts_with(as_headline("revert").expect("valid"))
-                                || title.starts_with(as_headline("remove").expect("valid"))
-                                || title.starts_with(as_headline("change").expect("valid"))
-                                || title.starts_with(as_headline("docs").expect("valid"))
-                                || title.starts_with(as_headline("perf").expect("valid"))
-                                || title.starts_with("refactor")
-                                || title.starts_with("other")
-                                || title.starts_with("style")
-                                || title.starts_with(as_headline("fix").expect("valid")) =>
-                        {
+                        Some((Event::Text(title), _range)) if template.kind_by_title(&title).is_some() => {
                             State::ParseConventional {
+                                kind: template.kind_by_title(&title).expect("checked before").to_owned(),
                                 title: title.into_string(),
                             }
                         }
@@ -199,10 +194,11 @@ impl Section {
                         })
                         .count();
                     match state {
-                        State::ParseConventional { title } => {
-                            segments.push(parse_conventional_to_next_section_title(
+                        State::ParseConventional { title, kind } => {
+                            segments.extend(parse_conventional_to_next_section_title(
                                 &body,
                                 title,
+                                kind,
                                 &mut events,
                                 indent,
                                 &mut unknown,
@@ -236,34 +232,46 @@ impl Section {
 fn parse_conventional_to_next_section_title(
     markdown: &str,
     title: String,
+    kind: String,
     events: &mut Peekable<OffsetIter<'_, '_>>,
     level: HeadingLevel,
     unknown: &mut String,
-) -> Segment {
+) -> Vec<Segment> {
     let is_breaking = title.ends_with(section::segment::Conventional::BREAKING_TITLE_ENCLOSED);
-    let kind = [
-        "fix", "add", "feat", "revert", "remove", "change", "docs", "perf", "refactor", "other", "style",
-    ]
-    .iter()
-    .find(|kind| {
-        let headline = section::segment::conventional::as_headline(*kind).unwrap_or(*kind);
-        let common_len = headline.len().min(title.len());
-        title
-            .get(..common_len)
-            .and_then(|t| headline.get(..common_len).map(|h| t.eq_ignore_ascii_case(h)))
-            .unwrap_or(false)
-    })
-    .expect("BUG: this list needs an update too if new kinds of conventional messages are added");
-
+    let mut segments = Vec::new();
     let mut conventional = section::segment::Conventional {
-        kind: *kind,
+        kind,
         is_breaking,
+        scope: None,
         removed: vec![],
         messages: vec![],
     };
     while let Some((event, _range)) = events.peek() {
         match event {
             Event::Start(Tag::Heading(indent, _, _)) if *indent == level => break,
+            Event::Start(Tag::Heading(indent, _, _)) if *indent > level => {
+                let scope = events
+                    .by_ref()
+                    .skip(1)
+                    .take_while(|(e, _range)| !matches!(e, Event::End(Tag::Heading(_, _, _))))
+                    .filter_map(|(e, _range)| match e {
+                        Event::Text(text) | Event::Code(text) => Some(text.into_string()),
+                        _ => None,
+                    })
+                    .collect::<String>();
+                let next = section::segment::Conventional {
+                    kind: conventional.kind.clone(),
+                    is_breaking,
+                    scope: Some(scope.trim().to_owned()),
+                    removed: vec![],
+                    messages: vec![],
+                };
+                let previous = std::mem::replace(&mut conventional, next);
+                // An empty segment without scope only carried the heading of the kind, which the scoped segments write as well.
+                if previous.scope.is_some() || !(previous.messages.is_empty() && previous.removed.is_empty()) {
+                    segments.push(Segment::Conventional(previous));
+                }
+            }
             _ => {
                 let (event, _range) = events.next().expect("peeked before so event is present");
                 match event {
@@ -331,7 +339,8 @@ fn parse_conventional_to_next_section_title(
             }
         }
     }
-    section::Segment::Conventional(conventional)
+    segments.push(Segment::Conventional(conventional));
+    segments
 }
 
 fn parse_id_fallback_to_user_message(
@@ -0,0 +1,186 @@
+use std::{path::Path, str::FromStr};
+
+use anyhow::bail;
+use cargo_metadata::camino::Utf8PathBuf;
+use git_repository as git;
+use git_repository::bstr::{BStr, ByteSlice};
+
+use crate::{
+    changelog::{
+        init::State,
+        merge,
+        section::segment,
+        write::{Components, Linkables},
+        Section, Template,
+    },
+    ChangeLog,
+};
+
+/// A pattern to find the tags of releases, like `v*` or `release-*-final`, where `*` is the semantic version of the release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagPattern {
+    prefix: String,
+    suffix: String,
+}
+
+impl FromStr for TagPattern {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('*') {
+            Some((prefix, suffix)) if !suffix.contains('*') => Ok(TagPattern {
+                prefix: prefix.into(),
+                suffix: suffix.into(),
+            }),
+            _ => bail!(
+                "Tag pattern '{}' must contain exactly one '*' to indicate the position of the version",
+                s
+            ),
+        }
+    }
+}
+
+impl TagPattern {
+    /// Return the version of the release tagged with `tag_name`, or `None` if it doesn't match this pattern.
+    pub fn version(&self, tag_name: &BStr) -> Option<semver::Version> {
+        tag_name
+            .strip_prefix(self.prefix.as_bytes())
+            .and_then(|rest| rest.strip_suffix(self.suffix.as_bytes()))
+            .and_then(|version| version.to_str().ok())
+            .and_then(|version| semver::Version::parse(version).ok())
+    }
+}
+
+/// Options for creating the changelog of a project, which doesn't have to be a cargo project.
+#[derive(Clone)]
+pub struct Options {
+    /// The pattern to find the tags of releases with.
+    pub tag_pattern: TagPattern,
+    /// If set, only commits changing files within this directory, relative to the root of the repository, are considered.
+    pub dir: Option<Utf8PathBuf>,
+    /// The segments to generate.
+    pub selection: segment::Selection,
+    /// How to merge generated content into an existing changelog.
+    pub strategy: merge::Strategy,
+    /// How to write links to commits and issues.
+    pub linkables: Linkables,
+    /// How to organize git-conventional commits into sections.
+    pub template: Template,
+}
+
+impl Options {
+    /// Create options to find releases with `tag_pattern` and otherwise default values.
+    pub fn new(tag_pattern: TagPattern) -> Self {
+        Options {
+            tag_pattern,
+            dir: None,
+            selection: segment::Selection::all(),
+            strategy: merge::Strategy::PreferUser,
+            linkables: Linkables::AsText,
+            template: Template::default(),
+        }
+    }
+}
+
+/// Creating changelogs without cargo
+impl ChangeLog {
+    /// Generate a changelog from the history of `HEAD` in `repo`, with one release section per tag matching the tag pattern
+    /// of `options` and a section of unreleased changes.
+    ///
+    /// Return `None` if `HEAD` doesn't point to a commit yet.
+    pub fn for_project(repo: &git::Repository, options: &Options) -> anyhow::Result<Option<ChangeLog>> {
+        let history = match crate::git::history::collect(repo, &options.template)? {
+            Some(history) => history,
+            None => return Ok(None),
+        };
+        let segments = crate::git::history::tag_segments(
+            repo,
+            &history,
+            |tag_name| options.tag_pattern.version(tag_name).is_some(),
+            options.dir.as_deref(),
+        )?;
+        let mut log = ChangeLog::from_history_segments_with(&segments, |segment, prev_segment| {
+            Section::from_history_segment_with_version(
+                segment,
+                repo,
+                options.selection,
+                &options.template,
+                prev_segment,
+                |tag_name| options.tag_pattern.version(tag_name),
+            )
+        });
+        log.sections.insert(
+            0,
+            Section::Verbatim {
+                text: include_str!("header.md").to_owned(),
+                generated: true,
+            },
+        );
+        Ok(Some(log))
+    }
+
+    /// Generate a changelog like [`for_project()`][ChangeLog::for_project()] and merge it into the one at `path`, or create
+    /// it if it doesn't exist, to write it back to `path`.
+    ///
+    /// Return the merged changelog along with information about what changed, or `None` if `HEAD` doesn't point to a commit yet.
+    pub fn write_for_project(
+        repo: &git::Repository,
+        path: impl AsRef<Path>,
+        options: &Options,
+    ) -> anyhow::Result<Option<(ChangeLog, State, merge::Outcome)>> {
+        let path = path.as_ref();
+        let generated = match Self::for_project(repo, options)? {
+            Some(log) => log,
+            None => return Ok(None),
+        };
+        let mut lock = git::lock::File::acquire_to_update_resource(path, git::lock::acquire::Fail::Immediately, None)?;
+        let (log, state, merge) = match std::fs::read_to_string(path) {
+            Ok(markdown) => {
+                let existing = ChangeLog::from_markdown_with_template(&markdown, &options.template);
+                let (merged, merge) = existing.clone().merge_generated_with(generated, options.strategy);
+                let state = if merged != existing {
+                    State::Modified
+                } else {
+                    State::Unchanged
+                };
+                (merged, state, merge)
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => (generated, State::Created, Default::default()),
+            Err(err) => return Err(err.into()),
+        };
+        let mut buf = String::new();
+        log.write_to_with_template(&mut buf, &options.linkables, Components::all(), &options.template)?;
+        std::io::Write::write_all(&mut lock, buf.as_bytes())?;
+        lock.commit()?;
+        Ok(Some((log, state, merge)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use git_repository::bstr::ByteSlice;
+
+    use super::TagPattern;
+
+    #[test]
+    fn tag_patterns_need_exactly_one_placeholder() {
+        assert!("v".parse::<TagPattern>().is_err());
+        assert!("v*-*".parse::<TagPattern>().is_err());
+        assert!("*".parse::<TagPattern>().is_ok());
+    }
+
+    #[test]
+    fn tag_patterns_extract_the_version() {
+        let pattern: TagPattern = "release-*-final".parse().unwrap();
+        assert_eq!(
+            pattern.version(b"release-1.2.3-final".as_bstr()),
+            Some(semver::Version::new(1, 2, 3))
+        );
+        assert_eq!(pattern.version(b"release-1.2.3".as_bstr()), None);
+        assert_eq!(pattern.version(b"release-one-final".as_bstr()), None);
+        assert_eq!(
+            "v*".parse::<TagPattern>().unwrap().version(b"v0.1.0-alpha.1".as_bstr()),
+            Some("0.1.0-alpha.1".parse().unwrap())
+        );
+    }
+}
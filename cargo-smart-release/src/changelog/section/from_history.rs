@@ -2,7 +2,7 @@ use std::{collections::BTreeMap, ops::Sub};
 
 use cargo_metadata::Package;
 use git_repository as git;
-use git_repository::prelude::ObjectIdExt;
+use git_repository::{bstr::BStr, prelude::ObjectIdExt};
 use time::OffsetDateTime;
 
 use crate::{
@@ -10,7 +10,7 @@ use crate::{
     changelog::{
         section,
         section::{segment::Selection, Segment},
-        Section,
+        Section, Template,
     },
     commit, utils,
     utils::{is_top_level_package, time_to_offset_date_time},
@@ -24,7 +24,45 @@ impl Section {
         segment: &commit::history::Segment<'_>,
         repo: &git::Repository,
         selection: section::segment::Selection,
+        template: &Template,
         prev_segment: Option<&commit::history::Segment<'_>>,
+    ) -> Self {
+        let package_name = (!is_top_level_package(&package.manifest_path, repo)).then(|| package.name.as_str());
+        Self::from_history_segment_inner(
+            segment,
+            repo,
+            selection,
+            template,
+            prev_segment,
+            |tag_name| utils::parse_possibly_prefixed_tag_version(package_name, tag_name),
+            Some(package.name.as_str()),
+        )
+    }
+
+    /// Like [`from_history_segment()`][Section::from_history_segment()], but obtain the version of the release from the name
+    /// of the tag at the head of `segment` with `tag_version(tag_name)` instead of deriving it from a package.
+    ///
+    /// Git-conventional commits are organized into sections and ordered according to `template`.
+    pub fn from_history_segment_with_version(
+        segment: &commit::history::Segment<'_>,
+        repo: &git::Repository,
+        selection: section::segment::Selection,
+        template: &Template,
+        prev_segment: Option<&commit::history::Segment<'_>>,
+        tag_version: impl FnOnce(&BStr) -> Option<semver::Version>,
+    ) -> Self {
+        Self::from_history_segment_inner(segment, repo, selection, template, prev_segment, tag_version, None)
+    }
+
+    /// If `crate_name` is set, git-conventional commits are only listed if their scope isn't mapped to other crates by `template`.
+    fn from_history_segment_inner(
+        segment: &commit::history::Segment<'_>,
+        repo: &git::Repository,
+        selection: section::segment::Selection,
+        template: &Template,
+        prev_segment: Option<&commit::history::Segment<'_>>,
+        tag_version: impl FnOnce(&BStr) -> Option<semver::Version>,
+        crate_name: Option<&str>,
     ) -> Self {
         let date_time = segment_head_time(segment, repo);
         let prev_date_time = prev_segment.map(|segment| segment_head_time(segment, repo));
@@ -34,31 +72,44 @@ impl Section {
         if !history.is_empty() {
             if selection.contains(Selection::GIT_CONVENTIONAL) {
                 let mut mapping = BTreeMap::default();
-                for (id, kind, title, is_breaking, body) in history.iter().filter_map(|i| {
-                    i.message.kind.as_ref().map(|kind| {
-                        (
-                            i.id,
-                            kind,
-                            i.message.title.clone(),
-                            i.message.breaking,
-                            i.message.body.clone(),
-                        )
+                for (id, kind, scope, title, is_breaking, body) in history
+                    .iter()
+                    .filter(|i| match crate_name {
+                        Some(crate_name) => template.scopes_include_crate(&i.message.scopes, crate_name),
+                        None => true,
+                    })
+                    .filter_map(|i| {
+                        i.message.conventional_type.as_deref().map(|ty| {
+                            (
+                                i.id,
+                                template.kind_of(ty),
+                                (template.group_by_scope && !i.message.scopes.is_empty())
+                                    .then(|| i.message.scopes.join(", ").to_ascii_lowercase()),
+                                i.message.title.clone(),
+                                i.message.breaking,
+                                i.message.body.clone(),
+                            )
+                        })
                     })
-                }) {
+                {
                     mapping
-                        .entry((is_breaking, kind))
+                        .entry((is_breaking, template.order(kind), kind, scope))
                         .or_insert_with(Vec::new)
                         .push(section::segment::conventional::Message::Generated { id, title, body })
                 }
-                // TODO: proper sorting
-                segments.extend(mapping.into_iter().map(|((is_breaking, kind), messages)| {
-                    Segment::Conventional(section::segment::Conventional {
-                        kind,
-                        is_breaking,
-                        removed: Vec::new(),
-                        messages,
-                    })
-                }));
+                segments.extend(
+                    mapping
+                        .into_iter()
+                        .map(|((is_breaking, _order, kind, scope), messages)| {
+                            Segment::Conventional(section::segment::Conventional {
+                                kind: kind.to_owned(),
+                                is_breaking,
+                                scope,
+                                removed: Vec::new(),
+                                messages,
+                            })
+                        }),
+                );
             }
             let message_by_category = selection
                 .intersects(Selection::COMMIT_STATISTICS | Selection::COMMIT_DETAILS)
@@ -133,9 +184,8 @@ impl Section {
 
         let version = crate::git::try_strip_tag_path(segment.head.name.as_ref())
             .map(|tag_name| {
-                let package_name = (!is_top_level_package(&package.manifest_path, repo)).then(|| package.name.as_str());
                 changelog::Version::Semantic(
-                    utils::parse_possibly_prefixed_tag_version(package_name, tag_name)
+                    tag_version(tag_name)
                         .expect("here we always have a valid version as it passed a filter when creating it"),
                 )
             })
@@ -46,10 +46,13 @@ pub mod conventional {
 
 #[derive(Eq, PartialEq, Debug, Clone)]
 pub struct Conventional {
-    /// The git-conventional kind
-    pub kind: &'static str,
+    /// The kind of section, which is a git-conventional type or the kind it is mapped to by a [`Template`][crate::changelog::Template].
+    pub kind: String,
     /// Whether or not the segment contains only breaking changes
     pub is_breaking: bool,
+    /// The git-conventional scope of all messages in this segment, like `parser` in `feat(parser): …`, written as heading
+    /// nested below the heading of its kind. Segments of the same kind are written below a single heading.
+    pub scope: Option<String>,
     /// object IDs parsed from markdown with no surrounding text. These are considered removed, so we shouldn't repopulate them.
     pub removed: Vec<git::ObjectId>,
     /// The messages to convey
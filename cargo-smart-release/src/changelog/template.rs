@@ -0,0 +1,412 @@
+//! Configure how git-conventional commits are organized in changelogs, with a `.smart-release.toml` file like the following.
+//!
+//! ```toml
+//! [changelog]
+//! # Commits with these git-conventional types are listed in the section of the given kind.
+//! types = { optimize = "perf", sec = "security" }
+//! # Group the commits within each section by their git-conventional scope, like `parser` in `feat(parser): …`,
+//! # listing each scope below a heading of its own.
+//! group-by-scope = true
+//!
+//! # Sections of these kinds are written first and in this order, with the given headline.
+//! # A kind that isn't known yet adds a section, which also receives all commits of the type of the same name.
+//! [[changelog.sections]]
+//! kind = "security"
+//! headline = "Security"
+//!
+//! [[changelog.sections]]
+//! kind = "perf"
+//! headline = "Faster"
+//!
+//! # Rewrite commit messages before they are parsed as git-conventional, in order, replacing the first match of each pattern.
+//! # Capture groups can be referenced in the replacement like `$1` or `${name}`.
+//! # This one turns `JIRA-123: feat: …` into `feat: … (#JIRA-123)`, which lists the ticket like an issue.
+//! [[changelog.rewrites]]
+//! pattern = '^(?P<ticket>[A-Z][A-Z0-9]*-\d+):\s*(?P<rest>.*)'
+//! replacement = '${rest} (#${ticket})'
+//!
+//! # Commits with these scopes are only listed in the changelogs of the given crates, even if they change other crates, too.
+//! [changelog.scopes]
+//! parser = ["git-config"]
+//! cli = ["gitoxide", "gitoxide-core"]
+//!
+//! # Link commits and issues to the forge hosting the repository, which is otherwise detected from the `origin` remote.
+//! [changelog.links]
+//! forge = "gitlab"
+//! url = "https://git.example.com/group/project"
+//! # Link issue references like `(#123)` to this issue tracker instead, with `{id}` replaced by the issue number.
+//! issues = "https://tracker.example.com/issues/{id}"
+//!
+//! # Keep the 'Commit Details' of releases with many commits readable.
+//! [changelog.details]
+//! # List at most this many commits, and summarize the remaining ones as 'and N more'.
+//! max-commits = 50
+//! # Only collapse the details into a `<details>` block if they list more than this many commits, instead of always.
+//! collapse-above = 10
+//! ```
+use std::{borrow::Cow, collections::BTreeMap, convert::TryFrom, path::Path, str::FromStr};
+
+use anyhow::{bail, Context};
+
+use crate::changelog::{
+    section::segment::{conventional::as_headline, Conventional},
+    write::Forge,
+};
+
+/// The name of the file in the root of the repository or workspace to read the [`Template`] from.
+pub const FILE_NAME: &str = ".smart-release.toml";
+
+/// The kind of section to list commits in if their git-conventional type isn't known.
+pub const OTHER_KIND: &str = "other";
+
+/// The headline of a section of git-conventional commits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Headline {
+    /// The kind of the section, like `feat`.
+    pub kind: String,
+    /// The text of the heading, like `New Features`.
+    pub title: String,
+}
+
+/// Determines how commits are organized into sections of git-conventional messages, and how these sections are called.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    /// The headlines of all known kinds of sections, in the order in which the sections are written.
+    pub headlines: Vec<Headline>,
+    /// A mapping of git-conventional types to the kind of section their commits are listed in, which takes precedence over
+    /// sections of the same kind as the type.
+    pub kinds_by_type: BTreeMap<String, String>,
+    /// Configures how commits and issues are linked.
+    pub links: Links,
+    /// If true, git-conventional commits are grouped by their scope within each section.
+    pub group_by_scope: bool,
+    /// A mapping of lower-case git-conventional scopes to the only crates in whose changelogs commits with that scope are listed.
+    pub crates_by_scope: BTreeMap<String, Vec<String>>,
+    /// Configures how the commit details segment is written.
+    pub details: Details,
+    /// Rewrites applied to commit messages in order before they are parsed.
+    pub rewrites: Vec<Rewrite>,
+}
+
+/// A rewrite of commit messages to bring unusual styles into git-conventional form before they are parsed.
+#[derive(Debug, Clone)]
+pub struct Rewrite {
+    /// The pattern whose first match is replaced.
+    pub pattern: regex::Regex,
+    /// The replacement, which may refer to capture groups of `pattern` like `$1` or `${name}`.
+    pub replacement: String,
+}
+
+impl PartialEq for Rewrite {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern.as_str() == other.pattern.as_str() && self.replacement == other.replacement
+    }
+}
+
+impl Eq for Rewrite {}
+
+/// Limits for the commit details segment, which can get unwieldy in releases with many commits.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Details {
+    /// If set, list at most this many commits and summarize the remaining ones by their amount.
+    pub max_commits: Option<usize>,
+    /// If set, only collapse the details into a `<details>` HTML block if they list more than this many commits.
+    /// Otherwise they are always collapsed if HTML is written.
+    pub collapse_above: Option<usize>,
+}
+
+/// Overrides for linking commits and issues, in place of what's detected from the `origin` remote.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Links {
+    /// The kind of forge the repository is hosted on.
+    pub forge: Option<Forge>,
+    /// The URL of the repository on its forge, like `https://gitlab.com/group/project`.
+    pub url: Option<String>,
+    /// The URL to link issues to, with `{id}` being replaced by the issue number.
+    pub issues: Option<String>,
+}
+
+impl Default for Template {
+    /// The built-in sections, in alphabetical order of their kind.
+    fn default() -> Self {
+        Template {
+            headlines: [
+                "add", "change", "chore", "docs", "feat", "fix", "other", "perf", "refactor", "remove", "revert",
+                "style", "test",
+            ]
+            .iter()
+            .map(|kind| Headline {
+                kind: (*kind).into(),
+                title: as_headline(kind).expect("all built-in kinds have a headline").into(),
+            })
+            .collect(),
+            kinds_by_type: [("add", "feat"), ("added", "feat"), ("remove", "revert")]
+                .iter()
+                .map(|(ty, kind)| ((*ty).into(), (*kind).into()))
+                .collect(),
+            links: Links::default(),
+            group_by_scope: false,
+            crates_by_scope: BTreeMap::default(),
+            details: Details::default(),
+            rewrites: Vec::new(),
+        }
+    }
+}
+
+impl FromStr for Template {
+    type Err = anyhow::Error;
+
+    /// Parse the `[changelog]` table of a `.smart-release.toml` file and apply it to the default template.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let doc = toml_edit::Document::from_str(s)?;
+        let mut template = Template::default();
+        let changelog = match doc.get("changelog") {
+            Some(changelog) => changelog.as_table_like().context("'changelog' must be a table")?,
+            None => return Ok(template),
+        };
+
+        if let Some(types) = changelog.get("types") {
+            let types = types
+                .as_table_like()
+                .context("'changelog.types' must be a table of git-conventional types and section kinds")?;
+            for (ty, kind) in types.iter() {
+                let kind = kind
+                    .as_str()
+                    .with_context(|| format!("The section kind of type '{}' must be a string", ty))?;
+                template
+                    .kinds_by_type
+                    .insert(ty.to_ascii_lowercase(), kind.to_ascii_lowercase());
+            }
+        }
+
+        if let Some(sections) = changelog.get("sections") {
+            let sections = sections
+                .as_array_of_tables()
+                .context("'changelog.sections' must be an array of tables, like [[changelog.sections]]")?;
+            let mut ordered = Vec::with_capacity(template.headlines.len());
+            for section in sections.iter() {
+                let kind = section
+                    .get("kind")
+                    .and_then(|kind| kind.as_str())
+                    .context("Each section in 'changelog.sections' needs a 'kind' string")?
+                    .to_ascii_lowercase();
+                let existing = template
+                    .headlines
+                    .iter()
+                    .position(|headline| headline.kind == kind)
+                    .map(|pos| template.headlines.remove(pos));
+                let title = match section.get("headline") {
+                    Some(title) => title
+                        .as_str()
+                        .with_context(|| format!("The headline of section '{}' must be a string", kind))?
+                        .to_owned(),
+                    None => match existing {
+                        Some(existing) => existing.title,
+                        None => bail!("The section '{}' isn't built-in and needs a 'headline'", kind),
+                    },
+                };
+                if ordered.iter().any(|headline: &Headline| headline.kind == kind) {
+                    bail!("The section '{}' is configured more than once", kind);
+                }
+                ordered.push(Headline { kind, title });
+            }
+            ordered.append(&mut template.headlines);
+            template.headlines = ordered;
+        }
+
+        if let Some(links) = changelog.get("links") {
+            let links = links.as_table_like().context("'changelog.links' must be a table")?;
+            let string = |key: &str| -> anyhow::Result<Option<String>> {
+                links
+                    .get(key)
+                    .map(|value| {
+                        value
+                            .as_str()
+                            .map(ToOwned::to_owned)
+                            .with_context(|| format!("'changelog.links.{}' must be a string", key))
+                    })
+                    .transpose()
+            };
+            template.links = Links {
+                forge: string("forge")?.map(|forge| forge.parse()).transpose()?,
+                url: string("url")?,
+                issues: string("issues")?,
+            };
+            if let Some(issues) = &template.links.issues {
+                if !issues.contains(Links::ISSUE_ID) {
+                    bail!(
+                        "The issue URL '{}' must contain '{}' to be replaced with the issue number",
+                        issues,
+                        Links::ISSUE_ID
+                    );
+                }
+            }
+        }
+
+        if let Some(group_by_scope) = changelog.get("group-by-scope") {
+            template.group_by_scope = group_by_scope
+                .as_bool()
+                .context("'changelog.group-by-scope' must be a boolean")?;
+        }
+
+        if let Some(scopes) = changelog.get("scopes") {
+            let scopes = scopes
+                .as_table_like()
+                .context("'changelog.scopes' must be a table of git-conventional scopes and crate names")?;
+            for (scope, crates) in scopes.iter() {
+                let crates = crates
+                    .as_array()
+                    .and_then(|crates| {
+                        crates
+                            .iter()
+                            .map(|name| name.as_str().map(ToOwned::to_owned))
+                            .collect::<Option<Vec<_>>>()
+                    })
+                    .with_context(|| format!("The crates of scope '{}' must be an array of strings", scope))?;
+                template.crates_by_scope.insert(scope.to_ascii_lowercase(), crates);
+            }
+        }
+
+        if let Some(details) = changelog.get("details") {
+            let details = details.as_table_like().context("'changelog.details' must be a table")?;
+            let count = |key: &str| -> anyhow::Result<Option<usize>> {
+                details
+                    .get(key)
+                    .map(|value| {
+                        value
+                            .as_integer()
+                            .and_then(|count| usize::try_from(count).ok())
+                            .with_context(|| format!("'changelog.details.{}' must be a positive integer", key))
+                    })
+                    .transpose()
+            };
+            template.details = Details {
+                max_commits: count("max-commits")?,
+                collapse_above: count("collapse-above")?,
+            };
+        }
+
+        if let Some(rewrites) = changelog.get("rewrites") {
+            let rewrites = rewrites
+                .as_array_of_tables()
+                .context("'changelog.rewrites' must be an array of tables, like [[changelog.rewrites]]")?;
+            for rewrite in rewrites.iter() {
+                let pattern = rewrite
+                    .get("pattern")
+                    .and_then(|pattern| pattern.as_str())
+                    .context("Each rewrite in 'changelog.rewrites' needs a 'pattern' string")?;
+                let replacement = rewrite
+                    .get("replacement")
+                    .and_then(|replacement| replacement.as_str())
+                    .with_context(|| format!("The rewrite of '{}' needs a 'replacement' string", pattern))?;
+                template.rewrites.push(Rewrite {
+                    pattern: regex::Regex::new(pattern)
+                        .with_context(|| format!("Invalid rewrite pattern '{}'", pattern))?,
+                    replacement: replacement.to_owned(),
+                });
+            }
+        }
+
+        for (ty, kind) in &template.kinds_by_type {
+            if template.headline(kind).is_none() {
+                bail!("The type '{}' is listed in section '{}' which doesn't exist", ty, kind);
+            }
+        }
+        Ok(template)
+    }
+}
+
+impl Links {
+    /// The placeholder in the issue URL to replace with the issue number.
+    pub const ISSUE_ID: &'static str = "{id}";
+}
+
+impl Template {
+    /// Read the template from the [`FILE_NAME`] file in `dir`, or return the default template if it doesn't exist.
+    pub fn at(dir: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = dir.as_ref().join(FILE_NAME);
+        match std::fs::read_to_string(&path) {
+            Ok(content) => content
+                .parse()
+                .with_context(|| format!("Could not read changelog template from '{}'", path.display())),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Template::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Apply all rewrites to the commit `message`, in order.
+    pub fn rewrite<'a>(&self, message: &'a str) -> Cow<'a, str> {
+        self.rewrites.iter().fold(Cow::Borrowed(message), |message, rewrite| {
+            match rewrite.pattern.replace(&message, rewrite.replacement.as_str()) {
+                Cow::Borrowed(_) => message,
+                Cow::Owned(rewritten) => Cow::Owned(rewritten),
+            }
+        })
+    }
+
+    /// Return the kind of section commits with the git-conventional type `ty` are listed in.
+    pub fn kind_of(&self, ty: &str) -> &str {
+        let ty = ty.to_ascii_lowercase();
+        match self.kinds_by_type.get(&ty) {
+            Some(kind) => kind,
+            None => self
+                .headlines
+                .iter()
+                .find(|headline| headline.kind == ty)
+                .map(|headline| headline.kind.as_str())
+                .unwrap_or(OTHER_KIND),
+        }
+    }
+
+    /// Return the headline of sections of the given `kind`, or `None` if it is unknown.
+    pub fn headline(&self, kind: &str) -> Option<&str> {
+        self.headlines
+            .iter()
+            .find(|headline| headline.kind == kind)
+            .map(|headline| headline.title.as_str())
+    }
+
+    /// Return the kind of section with a heading of `title`, which is either its headline or its kind, ignoring case
+    /// and a possible breaking-change marker.
+    pub fn kind_by_title(&self, title: &str) -> Option<&str> {
+        let title = title
+            .trim()
+            .trim_end_matches(Conventional::BREAKING_TITLE_ENCLOSED)
+            .trim_end();
+        self.headlines
+            .iter()
+            .find(|headline| headline.title.eq_ignore_ascii_case(title))
+            .or_else(|| {
+                self.headlines
+                    .iter()
+                    .find(|headline| headline.kind.eq_ignore_ascii_case(title))
+            })
+            .map(|headline| headline.kind.as_str())
+    }
+
+    /// Return true if commits with the git-conventional `scope` belong into the changelog of `crate_name`, which is the case
+    /// unless the scope is mapped to other crates.
+    pub fn scope_includes_crate(&self, scope: Option<&str>, crate_name: &str) -> bool {
+        scope
+            .and_then(|scope| self.crates_by_scope.get(&scope.to_ascii_lowercase()))
+            .map(|crates| crates.iter().any(|name| name == crate_name))
+            .unwrap_or(true)
+    }
+
+    /// Like [`scope_includes_crate()`][Template::scope_includes_crate()], but for commits with any amount of `scopes`,
+    /// which belong into the changelog of `crate_name` if at least one of their scopes does.
+    pub fn scopes_include_crate(&self, scopes: &[String], crate_name: &str) -> bool {
+        scopes.is_empty()
+            || scopes
+                .iter()
+                .any(|scope| self.scope_includes_crate(Some(scope), crate_name))
+    }
+
+    /// Return the position of sections of `kind` among all sections, with unknown kinds placed last.
+    pub fn order(&self, kind: &str) -> usize {
+        self.headlines
+            .iter()
+            .position(|headline| headline.kind == kind)
+            .unwrap_or(self.headlines.len())
+    }
+}
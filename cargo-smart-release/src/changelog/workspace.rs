@@ -0,0 +1,101 @@
+//! A changelog at the root of the workspace which aggregates the release sections of all crates released together.
+use std::fmt::Write;
+
+use crate::changelog::{
+    write::{Components, Linkables},
+    Section, Template,
+};
+
+/// The preamble of a newly created workspace changelog.
+pub const HEADER: &str = "# Changelog\n\nThis file aggregates the changelogs of all crates released together, with the most recent release first.\nPlease see the changelog of each crate for its complete history.\n\n";
+
+/// The heading level of each coordinated release.
+const RELEASE_HEADING_LEVEL: usize = 2;
+
+/// The release of a single crate as part of a coordinated release.
+pub struct Entry<'a> {
+    /// The name of the released crate.
+    pub name: &'a str,
+    /// The version the crate was released with.
+    pub version: &'a semver::Version,
+    /// The path to the changelog of the crate relative to the workspace changelog, using forward slashes.
+    pub changelog_path: String,
+    /// The release section of the crate's changelog.
+    pub section: &'a Section,
+}
+
+/// Return the headline of the release of all `entries` at `date`, which identifies the release in the workspace changelog.
+pub fn headline(entries: &[Entry<'_>], date: time::OffsetDateTime) -> String {
+    format!(
+        "{} {} ({:04}-{:02}-{:02})",
+        "#".repeat(RELEASE_HEADING_LEVEL),
+        entries
+            .iter()
+            .map(|e| format!("{} v{}", e.name, e.version))
+            .collect::<Vec<_>>()
+            .join(", "),
+        date.year(),
+        date.month() as u32,
+        date.day()
+    )
+}
+
+/// Add the release of all `entries` at `date` to the `existing` workspace changelog, or create a new one if it is `None`,
+/// and return the new changelog.
+///
+/// Each crate receives its own sub-section linking to its changelog. The release is placed above all prior releases,
+/// and if a release with the same headline exists already it is replaced to allow repeating a release after making changes.
+pub fn merge(
+    existing: Option<&str>,
+    entries: &[Entry<'_>],
+    date: time::OffsetDateTime,
+    link_mode: &Linkables,
+    template: &Template,
+) -> Result<String, std::fmt::Error> {
+    let headline = headline(entries, date);
+    let mut release = String::new();
+    writeln!(release, "{}\n", headline)?;
+    for entry in entries {
+        writeln!(
+            release,
+            "{} [{} v{}]({})\n",
+            "#".repeat(RELEASE_HEADING_LEVEL + 1),
+            entry.name,
+            entry.version,
+            entry.changelog_path
+        )?;
+        let mut section = entry.section.clone();
+        if let Section::Release { heading_level, .. } = &mut section {
+            *heading_level = RELEASE_HEADING_LEVEL + 1;
+        }
+        section.write_to_with_template(&mut release, link_mode, Components::DETAIL_TAGS, template)?;
+    }
+
+    let existing = existing.unwrap_or(HEADER);
+    let release_start = |line: &str| line.starts_with("## ");
+    let mut out = String::with_capacity(existing.len() + release.len());
+    let mut inserted = false;
+    let mut skipping_replaced_release = false;
+    for line in existing.split_inclusive('\n') {
+        if release_start(line) {
+            skipping_replaced_release = line.trim_end() == headline;
+            if !inserted {
+                out.push_str(&release);
+                inserted = true;
+            }
+        }
+        if !skipping_replaced_release {
+            out.push_str(line);
+        }
+    }
+    if !inserted {
+        if !(out.is_empty() || out.ends_with("\n\n")) {
+            out.push('\n');
+            if !out.ends_with("\n\n") {
+                out.push('\n');
+            }
+        }
+        out.push_str(&release);
+    }
+    Ok(out)
+}
@@ -1,3 +1,6 @@
+use std::{convert::TryFrom, str::FromStr};
+
+use anyhow::Context;
 use git_repository as git;
 use git_repository::{bstr::ByteSlice, url::Scheme, Url};
 
@@ -6,7 +9,7 @@ use crate::{
     changelog::{
         section,
         section::{segment, segment::details::Category, Segment},
-        Section,
+        template, Section, Template,
     },
     ChangeLog,
 };
@@ -37,40 +40,147 @@ pub enum Linkables {
     AsText,
 }
 
+impl Linkables {
+    /// Link to the repository configured in `links`, or to the one the `origin` remote points to, or leave items as text
+    /// if there is none.
+    pub fn from_remote(links: &template::Links) -> anyhow::Result<Self> {
+        let url = match &links.url {
+            Some(url) => Some(
+                git::Url::try_from(url.as_bytes().as_bstr())
+                    .with_context(|| format!("Could not parse 'changelog.links.url' {:?}", url))?,
+            ),
+            None => crate::git::remote_url()?,
+        };
+        Ok(url
+            .map(|url| {
+                let mut repository_url = RepositoryUrl::from(url);
+                if links.forge.is_some() {
+                    repository_url.forge = links.forge;
+                }
+                repository_url.issue_url = links.issues.clone();
+                Linkables::AsLinks { repository_url }
+            })
+            .unwrap_or(Linkables::AsText))
+    }
+}
+
+/// A platform hosting git repositories, which determines how to link to commits and issues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Forge {
+    GitHub,
+    GitLab,
+    Gitea,
+}
+
+impl FromStr for Forge {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "github" => Forge::GitHub,
+            "gitlab" => Forge::GitLab,
+            "gitea" => Forge::Gitea,
+            _ => anyhow::bail!("Unknown forge '{}', expected one of 'github', 'gitlab' or 'gitea'", s),
+        })
+    }
+}
+
+impl Forge {
+    /// Guess the forge by the `host` name of its repositories, or return `None` if it isn't known.
+    pub fn from_host(host: &str) -> Option<Self> {
+        let host = host.to_ascii_lowercase();
+        if host == "github.com" {
+            Some(Forge::GitHub)
+        } else if host == "gitlab.com" || host.starts_with("gitlab.") {
+            Some(Forge::GitLab)
+        } else if host == "gitea.com" || host == "codeberg.org" || host.starts_with("gitea.") {
+            Some(Forge::Gitea)
+        } else {
+            None
+        }
+    }
+
+    fn commit_path(&self) -> &'static str {
+        match self {
+            Forge::GitHub | Forge::Gitea => "commit",
+            Forge::GitLab => "-/commit",
+        }
+    }
+
+    fn issue_path(&self) -> &'static str {
+        match self {
+            Forge::GitHub | Forge::Gitea => "issues",
+            Forge::GitLab => "-/issues",
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct RepositoryUrl {
     pub inner: git::Url,
+    /// The forge hosting the repository, or `None` if it is unknown and nothing should be linked to it.
+    pub forge: Option<Forge>,
+    /// The URL to link issues to with `{id}` being replaced by the issue number, to use instead of the forge's issue tracker.
+    pub issue_url: Option<String>,
 }
 
 impl From<git::Url> for RepositoryUrl {
     fn from(v: Url) -> Self {
-        RepositoryUrl { inner: v }
+        RepositoryUrl {
+            forge: v.host().and_then(Forge::from_host),
+            inner: v,
+            issue_url: None,
+        }
     }
 }
 
 impl RepositoryUrl {
     pub fn is_github(&self) -> bool {
-        self.inner.host().map(|h| h == "github.com").unwrap_or(false)
+        self.forge == Some(Forge::GitHub)
     }
 
-    fn cleaned_path(&self) -> String {
+    /// The path of the repository without `.git` suffix, starting with a slash.
+    pub(crate) fn cleaned_path(&self) -> String {
         let path = self.inner.path.to_str_lossy().into_owned();
-        path.strip_suffix(".git").map(ToOwned::to_owned).unwrap_or(path)
+        let path = path.strip_suffix(".git").map(ToOwned::to_owned).unwrap_or(path);
+        if path.starts_with('/') {
+            path
+        } else {
+            format!("/{}", path)
+        }
     }
 
-    pub fn github_https(&self) -> Option<String> {
-        match &self.inner.host() {
-            Some(host) if *host == "github.com" => match self.inner.scheme {
-                Scheme::Http | Scheme::Https | Scheme::Git => {
-                    format!("https://github.com{}", self.cleaned_path()).into()
-                }
-                Scheme::Ssh => self
-                    .inner
-                    .user()
-                    .map(|user| format!("https://github.com{}/{}", user, self.cleaned_path())),
-                Scheme::Radicle | Scheme::File => None,
-            },
-            None | Some(_) => None,
+    /// The https URL to the repository, which is the base for all links to it.
+    pub fn https(&self) -> Option<String> {
+        let host = self.inner.host()?;
+        match self.inner.scheme {
+            Scheme::Http | Scheme::Https | Scheme::Git | Scheme::Ssh => {
+                let port = match (self.inner.scheme, self.inner.port) {
+                    (Scheme::Http | Scheme::Https, Some(port)) => format!(":{}", port),
+                    _ => String::new(),
+                };
+                format!("https://{}{}{}", host, port, self.cleaned_path()).into()
+            }
+            Scheme::Radicle | Scheme::File => None,
+        }
+    }
+
+    /// The URL to the commit with `id`, if the forge is known.
+    pub fn commit(&self, id: &git::oid) -> Option<String> {
+        let forge = self.forge?;
+        self.https()
+            .map(|base| format!("{}/{}/{}", base, forge.commit_path(), id))
+    }
+
+    /// The URL to the issue with `id`, either in the configured issue tracker or on the forge.
+    pub fn issue(&self, id: &str) -> Option<String> {
+        match &self.issue_url {
+            Some(url) => url.replace(template::Links::ISSUE_ID, id).into(),
+            None => {
+                let forge = self.forge?;
+                self.https()
+                    .map(|base| format!("{}/{}/{}", base, forge.issue_path(), id))
+            }
         }
     }
 }
@@ -95,10 +205,21 @@ impl Section {
     /// Note that `headline` should be enabled by default as it will break parsing to some extend. It's a special case for tag
     /// objects.
     pub fn write_to(
+        &self,
+        out: impl std::fmt::Write,
+        link_mode: &Linkables,
+        components: Components,
+    ) -> std::fmt::Result {
+        self.write_to_with_template(out, link_mode, components, &Template::default())
+    }
+
+    /// Like [`write_to()`][Section::write_to()], but use the headlines of `template` for sections of git-conventional messages.
+    pub fn write_to_with_template(
         &self,
         mut out: impl std::fmt::Write,
         link_mode: &Linkables,
         components: Components,
+        template: &Template,
     ) -> std::fmt::Result {
         match self {
             Section::Verbatim { text, .. } => {
@@ -140,8 +261,16 @@ impl Section {
                 }
 
                 let section_level = *heading_level + 1;
+                let mut previous_segment: Option<&Segment> = None;
                 for segment in segments {
-                    segment.write_to(section_level, link_mode, components, &mut out)?;
+                    let continues_kind = match (previous_segment, segment) {
+                        (Some(Segment::Conventional(prev)), Segment::Conventional(cur)) => {
+                            cur.scope.is_some() && prev.kind == cur.kind && prev.is_breaking == cur.is_breaking
+                        }
+                        _ => false,
+                    };
+                    segment.write_to_inner(section_level, link_mode, components, template, continues_kind, &mut out)?;
+                    previous_segment = Some(segment);
                 }
                 if !unknown.is_empty() && components.contains(Components::HTML_TAGS) {
                     writeln!(out, "{}", Section::UNKNOWN_TAG_START)?;
@@ -169,13 +298,24 @@ fn heading(level: usize) -> String {
 
 impl ChangeLog {
     pub fn write_to(
+        &self,
+        out: impl std::fmt::Write,
+        link_mode: &Linkables,
+        components: Components,
+    ) -> std::fmt::Result {
+        self.write_to_with_template(out, link_mode, components, &Template::default())
+    }
+
+    /// Like [`write_to()`][ChangeLog::write_to()], but use the headlines of `template` for sections of git-conventional messages.
+    pub fn write_to_with_template(
         &self,
         mut out: impl std::fmt::Write,
         link_mode: &Linkables,
         components: Components,
+        template: &Template,
     ) -> std::fmt::Result {
         for section in &self.sections {
-            section.write_to(&mut out, link_mode, components)?;
+            section.write_to_with_template(&mut out, link_mode, components, template)?;
         }
         Ok(())
     }
@@ -187,6 +327,20 @@ impl section::Segment {
         section_level: usize,
         link_mode: &Linkables,
         components: Components,
+        template: &Template,
+        out: impl std::fmt::Write,
+    ) -> std::fmt::Result {
+        self.write_to_inner(section_level, link_mode, components, template, false, out)
+    }
+
+    /// If `continues_kind` is true, the heading of the kind of a conventional segment was already written by the segment before it.
+    fn write_to_inner(
+        &self,
+        section_level: usize,
+        link_mode: &Linkables,
+        components: Components,
+        template: &Template,
+        continues_kind: bool,
         mut out: impl std::fmt::Write,
     ) -> std::fmt::Result {
         let write_html = components.contains(Components::HTML_TAGS);
@@ -198,21 +352,27 @@ impl section::Segment {
             Segment::Conventional(segment::Conventional {
                 kind,
                 is_breaking,
+                scope,
                 removed,
                 messages,
-            }) => match segment::conventional::as_headline(kind).or_else(|| is_breaking.then(|| *kind)) {
+            }) => match template.headline(kind).or_else(|| is_breaking.then(|| kind.as_str())) {
                 Some(headline) => {
-                    writeln!(
-                        out,
-                        "{} {}{}\n",
-                        heading(section_level),
-                        headline,
-                        if *is_breaking {
-                            format!(" {}", segment::Conventional::BREAKING_TITLE_ENCLOSED)
-                        } else {
-                            "".into()
-                        },
-                    )?;
+                    if !continues_kind {
+                        writeln!(
+                            out,
+                            "{} {}{}\n",
+                            heading(section_level),
+                            headline,
+                            if *is_breaking {
+                                format!(" {}", segment::Conventional::BREAKING_TITLE_ENCLOSED)
+                            } else {
+                                "".into()
+                            },
+                        )?;
+                    }
+                    if let Some(scope) = scope {
+                        writeln!(out, "{} {}\n", heading(section_level + 1), scope)?;
+                    }
 
                     if !removed.is_empty() && write_html {
                         for id in removed {
@@ -265,18 +425,37 @@ impl section::Segment {
                 if !commits_by_category.is_empty() =>
             {
                 let write_details_tags = components.contains(Components::DETAIL_TAGS);
+                let total = commits_by_category.values().map(Vec::len).sum::<usize>();
+                let listed = template.details.max_commits.map_or(total, |max| total.min(max));
+                let collapse = write_details_tags && template.details.collapse_above.map_or(true, |min| listed > min);
                 writeln!(out, "{} {}\n", heading(section_level), segment::Details::TITLE)?;
                 if write_details_tags {
                     writeln!(out, "{}", Section::READONLY_TAG)?;
+                }
+                if collapse {
                     writeln!(out, "{}\n", segment::Details::HTML_PREFIX)?;
                 }
+                let mut remaining = listed;
                 for (category, messages) in commits_by_category.iter() {
+                    if remaining == 0 {
+                        break;
+                    }
                     writeln!(out, " * **{}**", format_category(category, link_mode))?;
-                    for message in messages {
+                    for message in messages.iter().take(remaining) {
                         writeln!(out, "    - {} ({})", message.title, format_oid(&message.id, link_mode))?;
                     }
+                    remaining = remaining.saturating_sub(messages.len());
                 }
-                if write_details_tags {
+                if listed < total {
+                    let omitted = total - listed;
+                    writeln!(
+                        out,
+                        " * *and {} more {}*",
+                        omitted,
+                        if omitted == 1 { "commit" } else { "commits" }
+                    )?;
+                }
+                if collapse {
                     writeln!(out, "{}", segment::Details::HTML_PREFIX_END)?;
                 }
                 writeln!(out)?;
@@ -364,9 +543,9 @@ impl section::Segment {
 
 fn format_category(cat: &Category, link_mode: &Linkables) -> String {
     match (cat, link_mode) {
-        (Category::Issue(id), Linkables::AsLinks { repository_url }) => match repository_url.github_https() {
-            Some(base_url) => {
-                format!("[#{}]({}/issues/{})", id, base_url, id)
+        (Category::Issue(id), Linkables::AsLinks { repository_url }) => match repository_url.issue(id) {
+            Some(url) => {
+                format!("[#{}]({})", id, url)
             }
             None => format_category(cat, &Linkables::AsText),
         },
@@ -377,9 +556,9 @@ fn format_category(cat: &Category, link_mode: &Linkables) -> String {
 fn format_oid(id: &git::oid, link_mode: &Linkables) -> String {
     match link_mode {
         Linkables::AsText => id.to_hex_with_len(7).to_string(),
-        Linkables::AsLinks { repository_url } => match repository_url.github_https() {
-            Some(base_url) => {
-                format!("[`{}`]({}/commit/{})", id.to_hex_with_len(7), base_url, id)
+        Linkables::AsLinks { repository_url } => match repository_url.commit(id) {
+            Some(url) => {
+                format!("[`{}`]({})", id.to_hex_with_len(7), url)
             }
             None => format_oid(id, &Linkables::AsText),
         },
@@ -1,4 +1,5 @@
 mod options;
+use anyhow::Context;
 use clap::Parser;
 use options::{Args, SubCommands};
 
@@ -17,6 +18,10 @@ fn main() -> anyhow::Result<()> {
             no_links,
             without,
             allow_dirty,
+            diff,
+            merge_strategy,
+            format,
+            verify_only,
         } => {
             init_logging(false);
             command::changelog(
@@ -27,20 +32,29 @@ fn main() -> anyhow::Result<()> {
                     preview: !no_preview,
                     dependencies: !no_dependencies,
                     generator_segments: names_to_segment_selection(&without)?,
+                    diff: diff.as_deref().map(to_version_range).transpose()?,
+                    merge_strategy: merge_strategy.parse()?,
+                    format: format.parse()?,
+                    verify_only,
                 },
                 crates,
             )?
         }
         SubCommands::SmartRelease {
             execute,
+            yes,
+            plan_json,
             verbose,
             bump,
             bump_dependencies,
             crates,
             allow_dirty,
+            allow_unpushed,
+            require_green_ci,
             ignore_instability,
             no_publish,
             no_tag,
+            signed_tags,
             no_push,
             changelog_without,
             dangerously_pass_no_verify,
@@ -53,9 +67,11 @@ fn main() -> anyhow::Result<()> {
             no_changelog_links,
             no_changelog_preview,
             no_changelog_github_release,
+            no_workspace_changelog,
             allow_fully_generated_changelogs,
             no_dependencies,
             no_isolate_dependencies_from_breaking_changes,
+            lint_commits,
         } => {
             let verbose = execute || verbose;
             init_logging(verbose);
@@ -67,10 +83,13 @@ fn main() -> anyhow::Result<()> {
                     bump_when_needed: !no_bump_on_demand,
                     isolate_dependencies_from_breaking_changes: !no_isolate_dependencies_from_breaking_changes,
                     allow_dirty,
+                    allow_unpushed,
+                    require_green_ci,
                     ignore_instability,
                     skip_publish: no_publish,
                     changelog: !no_changelog,
                     skip_tag: no_tag,
+                    signed_tags,
                     skip_push: no_push,
                     dependencies: !no_dependencies,
                     dry_run_cargo_publish,
@@ -82,6 +101,10 @@ fn main() -> anyhow::Result<()> {
                     allow_fully_generated_changelogs,
                     changelog_links: !no_changelog_links,
                     allow_changelog_github_release: !no_changelog_github_release,
+                    workspace_changelog: !no_workspace_changelog,
+                    commit_lint: lint_commits.as_deref().map(to_commit_lint).transpose()?,
+                    yes,
+                    plan_json,
                 },
                 crates,
                 to_bump_spec(bump.as_deref().unwrap_or(DEFAULT_BUMP_SPEC))?,
@@ -103,10 +126,33 @@ fn to_bump_spec(spec: &str) -> anyhow::Result<cargo_smart_release::version::Bump
         "major" | "Major" => Major,
         "keep" | "Keep" => Keep,
         "auto" | "Auto" => Auto,
-        unknown_spec => anyhow::bail!("Unknown bump specification: {:?}", unknown_spec),
+        pre_release => PreRelease(
+            pre_release
+                .parse()
+                .with_context(|| format!("Unknown bump specification: {:?}", pre_release))?,
+        ),
     })
 }
 
+fn to_commit_lint(level: &str) -> anyhow::Result<cargo_smart_release::command::release::CommitLint> {
+    use cargo_smart_release::command::release::CommitLint::*;
+    Ok(match level {
+        "warn" | "Warn" => Warn,
+        "deny" | "Deny" => Deny,
+        unknown => anyhow::bail!("Unknown commit lint level: {:?}", unknown),
+    })
+}
+
+fn to_version_range(spec: &str) -> anyhow::Result<(semver::Version, semver::Version)> {
+    fn to_version(version: &str) -> anyhow::Result<semver::Version> {
+        Ok(version.strip_prefix('v').unwrap_or(version).parse()?)
+    }
+    let (from, to) = spec
+        .split_once("..")
+        .ok_or_else(|| anyhow::anyhow!("Expected a version range like 'v0.1.0..v0.2.0', got {:?}", spec))?;
+    Ok((to_version(from)?, to_version(to)?))
+}
+
 fn names_to_segment_selection(
     names: &[String],
 ) -> anyhow::Result<cargo_smart_release::changelog::section::segment::Selection> {
@@ -18,10 +18,20 @@ pub enum SubCommands {
     ///
     /// Use --execute to actually perform the operation.
     SmartRelease {
-        /// Actually perform a release. Dry-run mode is the default
+        /// Actually perform a release after confirming the release plan. Dry-run mode is the default
         #[clap(long, short = 'e', help_heading = Some("MAJOR"))]
         execute: bool,
 
+        /// Execute the release without asking for confirmation of the release plan, which is required if stdin isn't a terminal.
+        #[clap(long, short = 'y', help_heading = Some("MAJOR"))]
+        yes: bool,
+
+        /// Print the release plan as JSON to stdout, listing crates and their versions, tags, changelogs and the publish order.
+        ///
+        /// Useful to keep an audit trail of releases performed in pipelines.
+        #[clap(long, help_heading = Some("CUSTOMIZATION"))]
+        plan_json: bool,
+
         /// Always update the crates-index beforehand. It is used to determine if the computed version to be published was
         /// already published.
         #[clap(long, short = 'u', help_heading = Some("MAJOR"))]
@@ -37,6 +47,9 @@ pub enum SubCommands {
         ///
         /// Can be 'major', 'minor' or 'patch', 'keep' and 'auto'.
         /// With 'keep', the current version will be kept, useful if versions are specified by hand in the manifest.
+        /// Pre-releases are created with 'alpha', 'beta' or 'rc', optionally followed by the pre-release number like 'rc.2',
+        /// which turns 1.0.0 into 1.0.1-rc.1 and 1.0.1-rc.1 into 1.0.1-rc.2. Bumping a pre-release like 1.0.1-rc.2 with
+        /// 'patch' releases it as 1.0.1.
         ///
         /// The default is 'auto', which derives the necessary information from the git commit history and occasional
         /// conventional messages.
@@ -47,6 +60,9 @@ pub enum SubCommands {
         ///
         /// Can be 'major', 'minor' or 'patch', 'keep' and 'auto'.
         /// With 'keep', the current version will be kept, useful if versions are specified by hand in the manifest.
+        /// Pre-releases are created with 'alpha', 'beta' or 'rc', optionally followed by the pre-release number like 'rc.2',
+        /// which turns 1.0.0 into 1.0.1-rc.1 and 1.0.1-rc.1 into 1.0.1-rc.2. Bumping a pre-release like 1.0.1-rc.2 with
+        /// 'patch' releases it as 1.0.1.
         ///
         /// The default is 'auto', which derives the necessary information from the git commit history and occasional
         /// conventional messages.
@@ -78,11 +94,21 @@ pub enum SubCommands {
         #[clap(long, help_heading = Some("CUSTOMIZATION"))]
         no_bump_on_demand: bool,
 
-        /// Do not create a github release (if the repo is located on github) reflecting the content of the changelogs most recent
+        /// Do not create a release on GitHub or GitLab (if the repo is located there) reflecting the content of the changelogs most recent
         /// release section.
+        ///
+        /// Releases are created through the REST API of the forge, authenticated with a token from GITHUB_TOKEN, GH_TOKEN or GITLAB_TOKEN,
+        /// or from the git credential helper. On GitHub, the 'gh' program is used if no token is available.
+        /// Existing releases of the same tag are updated.
         #[clap(long, help_heading = Some("CHANGELOG"))]
         no_changelog_github_release: bool,
 
+        /// Do not add the release sections of all crates released together to the 'CHANGELOG.md' file at the root of the workspace.
+        ///
+        /// Only takes effect if more than one crate is released, and if the workspace root isn't itself the directory of a released crate.
+        #[clap(long, help_heading = Some("CHANGELOG"))]
+        no_workspace_changelog: bool,
+
         /// If changelogs are generated from git-conventional comments extracted from the commit history exclusively, publishes
         /// will fail as in order to give opportunity to author at least a portion of the upcoming release.
         ///
@@ -95,6 +121,13 @@ pub enum SubCommands {
         #[clap(long, help_heading = Some("CHANGELOG"))]
         no_changelog_links: bool,
 
+        /// Check that all commits in the unreleased history of the provided crates have git-conventional messages
+        /// before computing the release, listing the offending commits along with their author and crate directory.
+        ///
+        /// Can be 'warn' to only log offending commits, or 'deny' to also abort the release if there is at least one.
+        #[clap(long, help_heading = Some("CHANGELOG"))]
+        lint_commits: Option<String>,
+
         /// Omits these kinds of generated changelog content, values are 'clippy', 'commit-statistics' and 'commit-details'
         #[clap(long, help_heading = Some("CHANGELOG"))]
         changelog_without: Vec<String>,
@@ -109,6 +142,15 @@ pub enum SubCommands {
         #[clap(long, help_heading = Some("EXPERT"))]
         allow_dirty: bool,
 
+        /// Allow publishes to take place even though HEAD has commits that aren't pushed to its upstream branch, or if there
+        /// is no upstream branch.
+        #[clap(long, help_heading = Some("EXPERT"))]
+        allow_unpushed: bool,
+
+        /// Only publish if all CI check runs on HEAD completed successfully, as reported by the GitHub API via the 'gh' program.
+        #[clap(long, help_heading = Some("CUSTOMIZATION"))]
+        require_green_ci: bool,
+
         /// Disallow to also publish stable crates when discovering changed crates, bumping their version according to -d <spec>.
         #[clap(long, help_heading = Some("CUSTOMIZATION"))]
         no_auto_publish_of_stable_crates: bool,
@@ -135,6 +177,13 @@ pub enum SubCommands {
         #[clap(long, help_heading = Some("CUSTOMIZATION"))]
         no_tag: bool,
 
+        /// Create tags as annotated tag objects signed with the GPG or SSH key configured for `git`, as with `git tag --sign`.
+        ///
+        /// This is also the default if `tag.gpgSign` is true in the git configuration. The tag message is the changelog
+        /// section of the release, or the name of the tag if there is none.
+        #[clap(long, help_heading = Some("CUSTOMIZATION"), conflicts_with = "no-tag")]
+        signed_tags: bool,
+
         /// Don't push tags and the HEAD branch after any successful run of `cargo publish`.
         #[clap(long, help_heading = Some("CUSTOMIZATION"))]
         no_push: bool,
@@ -201,5 +250,39 @@ pub enum SubCommands {
         /// Do not generate links to commits and issues when writing the changelogs. This currently only works for GitHub.
         #[clap(long, help_heading = Some("CUSTOMIZATION"))]
         no_links: bool,
+
+        /// Print only the release sections between two released versions like `v0.1.0..v0.2.0` to stdout, excluding the
+        /// first and including the last version, without changing any changelog.
+        ///
+        /// This is useful to paste the changes of a crate into an announcement.
+        #[clap(long, value_name = "FROM..TO", help_heading = Some("CUSTOMIZATION"))]
+        diff: Option<String>,
+
+        /// How to merge generated content into existing changelogs, one of 'prefer-user', 'prefer-generated' or
+        /// 'interleave-by-date'.
+        ///
+        /// 'prefer-user' keeps everything written by hand, 'prefer-generated' replaces the content of existing releases
+        /// with generated content and 'interleave-by-date' keeps user content but places new releases by their date.
+        #[clap(long, default_value = "prefer-user", help_heading = Some("CUSTOMIZATION"))]
+        merge_strategy: String,
+
+        /// The format to print changelogs in, one of 'markdown' or 'json'.
+        ///
+        /// With 'json', the changelogs of all crates are printed to stdout as a JSON array instead of being previewed,
+        /// holding releases, dates, segments and commit ids for consumption by tools. --write still writes markdown.
+        #[clap(long, default_value = "markdown", help_heading = Some("CUSTOMIZATION"))]
+        format: String,
+
+        /// Print a diff between the changelogs as they would be written and the `CHANGELOG.md` files in the worktree,
+        /// and exit with a non-zero code if they differ, without changing anything.
+        ///
+        /// This is useful in CI to assure changelogs are up to date before merging.
+        #[clap(
+            long,
+            visible_alias = "preview-diff",
+            conflicts_with_all = &["write", "execute", "diff"],
+            help_heading = Some("MAJOR")
+        )]
+        verify_only: bool,
     },
 }
@@ -2,8 +2,12 @@ use std::io::Write;
 
 use crate::{
     bat,
-    changelog::write::{Components, Linkables},
-    command::changelog::Options,
+    changelog::{
+        merge,
+        write::{Components, Linkables},
+        Template,
+    },
+    command::changelog::{Format, Options},
     git,
     traverse::dependency,
     utils::will,
@@ -18,11 +22,18 @@ pub fn changelog(opts: Options, crates: Vec<String>) -> anyhow::Result<()> {
         dry_run,
         preview,
         no_links,
+        ref diff,
+        merge_strategy,
+        format,
+        verify_only,
         ..
     } = opts;
     let bump_spec = dependencies.then(|| BumpSpec::Auto).unwrap_or(BumpSpec::Keep);
     let force_history_segmentation = false;
-    let ctx = crate::Context::new(crates.clone(), force_history_segmentation, bump_spec, bump_spec)?;
+    let ctx = crate::Context::new(crates.clone(), force_history_segmentation, bump_spec.clone(), bump_spec)?;
+    if let Some((from, to)) = diff {
+        return print_diff(&ctx, from, to, generator_segments, no_links, format);
+    }
     let crates: Vec<_> = {
         crate::traverse::dependencies(
             &ctx,
@@ -51,30 +62,34 @@ pub fn changelog(opts: Options, crates: Vec<String>) -> anyhow::Result<()> {
         })
         .collect()
     };
+    if verify_only {
+        return verify(&ctx, &crates, generator_segments, no_links, merge_strategy);
+    }
     assure_working_tree_is_unchanged(opts)?;
-    let history = match git::history::collect(&ctx.repo)? {
+    let history = match git::history::collect(&ctx.repo, &ctx.changelog_template)? {
         None => return Ok(()),
         Some(history) => history,
     };
 
-    let bat = (dry_run && preview).then(bat::Support::new);
+    let bat = (dry_run && preview && format == Format::Markdown).then(bat::Support::new);
 
     let mut pending_changes = Vec::new();
+    let mut json_logs = Vec::new();
     let linkables = if dry_run || no_links {
         Linkables::AsText
     } else {
-        crate::git::remote_url()?
-            .map(|url| Linkables::AsLinks {
-                repository_url: url.into(),
-            })
-            .unwrap_or(Linkables::AsText)
+        Linkables::from_remote(&ctx.changelog_template.links)?
     };
     let mut num_crates = 0;
     for (idx, package) in crates.iter().enumerate() {
         num_crates += 1;
         let crate::changelog::init::Outcome {
-            log, mut lock, state, ..
-        } = ChangeLog::for_package_with_write_lock(package, &history, &ctx, generator_segments)?;
+            log,
+            mut lock,
+            state,
+            merge,
+            ..
+        } = ChangeLog::for_package_with_write_lock(package, &history, &ctx, generator_segments, merge_strategy)?;
         log::info!(
             "{} write {} sections to {} ({})",
             will(dry_run),
@@ -85,9 +100,23 @@ pub fn changelog(opts: Options, crates: Vec<String>) -> anyhow::Result<()> {
                 .display(),
             state.as_str(),
         );
+        if !merge.preserved.is_empty() {
+            log::info!("Preserved {} user segment(s)", merge.preserved.len());
+        }
+        for discarded in &merge.discarded {
+            log::warn!(
+                "{} discard user segment in release {}: {:?}",
+                will(dry_run),
+                match &discarded.release {
+                    crate::changelog::Version::Unreleased => "Unreleased".into(),
+                    crate::changelog::Version::Semantic(version) => format!("v{}", version),
+                },
+                discarded.markdown.lines().next().unwrap_or_default()
+            );
+        }
         lock.with_mut(|file| {
             let mut buf = String::new();
-            log.write_to(
+            log.write_to_with_template(
                 &mut buf,
                 &linkables,
                 if dry_run {
@@ -95,6 +124,7 @@ pub fn changelog(opts: Options, crates: Vec<String>) -> anyhow::Result<()> {
                 } else {
                     Components::all()
                 },
+                &ctx.changelog_template,
             )
             .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
             file.write_all(buf.as_bytes())
@@ -106,6 +136,9 @@ pub fn changelog(opts: Options, crates: Vec<String>) -> anyhow::Result<()> {
                 format!("PREVIEW {} / {}, press Ctrl+C to cancel", idx + 1, crates.len()),
             )?;
         }
+        if format == Format::Json {
+            json_logs.push(crate_json(&package.name, &log, &ctx.changelog_template));
+        }
         if !dry_run {
             pending_changes.push(lock);
         }
@@ -130,7 +163,156 @@ pub fn changelog(opts: Options, crates: Vec<String>) -> anyhow::Result<()> {
     if num_changes != 0 {
         log::info!("Wrote {} changelogs", num_changes);
     }
+    if format == Format::Json {
+        write_json(json_logs)?;
+    }
+
+    Ok(())
+}
+
+fn crate_json(crate_name: &str, log: &ChangeLog, template: &Template) -> serde_json::Value {
+    serde_json::json!({ "crate": crate_name, "changelog": log.to_json_with_template(template) })
+}
+
+fn write_json(logs: Vec<serde_json::Value>) -> anyhow::Result<()> {
+    let mut out = std::io::stdout();
+    serde_json::to_writer_pretty(&mut out, &logs)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Print the release sections of all requested crates with versions in `from..=to`, excluding `from`, without writing anything.
+fn print_diff(
+    ctx: &crate::Context,
+    from: &semver::Version,
+    to: &semver::Version,
+    generator_segments: crate::changelog::section::segment::Selection,
+    no_links: bool,
+    format: Format,
+) -> anyhow::Result<()> {
+    let history = match git::history::collect(&ctx.repo, &ctx.changelog_template)? {
+        None => return Ok(()),
+        Some(history) => history,
+    };
+    let linkables = if no_links {
+        Linkables::AsText
+    } else {
+        Linkables::from_remote(&ctx.changelog_template.links)?
+    };
+    let mut buf = String::new();
+    let mut json_logs = Vec::new();
+    for crate_name in &ctx.crate_names {
+        let package = crate::utils::package_by_name(&ctx.meta, crate_name)?;
+        let crate::changelog::init::Merged { log, .. } =
+            ChangeLog::for_package(package, &history, ctx, generator_segments, merge::Strategy::PreferUser)?;
+        let log = log.releases_between(from, to);
+        if log.sections.is_empty() {
+            log::warn!(
+                "'{}' has no releases after v{} up to and including v{}",
+                crate_name,
+                from,
+                to
+            );
+            continue;
+        }
+        if format == Format::Json {
+            json_logs.push(crate_json(crate_name, &log, &ctx.changelog_template));
+            continue;
+        }
+        if ctx.crate_names.len() > 1 {
+            buf.push_str(&format!("# {}\n\n", crate_name));
+        }
+        log.write_to_with_template(
+            &mut buf,
+            &linkables,
+            Components::SECTION_TITLE | Components::DETAIL_TAGS,
+            &ctx.changelog_template,
+        )?;
+    }
+    match format {
+        Format::Markdown => std::io::stdout().write_all(buf.as_bytes())?,
+        Format::Json => write_json(json_logs)?,
+    }
+    Ok(())
+}
+
+/// Compare the changelogs of `crates` as they would be written with the ones in the worktree and print a diff for each
+/// one that differs, failing if there is at least one without changing anything.
+fn verify(
+    ctx: &crate::Context,
+    crates: &[&cargo_metadata::Package],
+    generator_segments: crate::changelog::section::segment::Selection,
+    no_links: bool,
+    merge_strategy: merge::Strategy,
+) -> anyhow::Result<()> {
+    let history = match git::history::collect(&ctx.repo, &ctx.changelog_template)? {
+        None => return Ok(()),
+        Some(history) => history,
+    };
+    let linkables = if no_links {
+        Linkables::AsText
+    } else {
+        Linkables::from_remote(&ctx.changelog_template.links)?
+    };
+    let colored = atty::is(atty::Stream::Stdout);
+    let mut out = std::io::stdout();
+    let mut outdated = Vec::new();
+    for package in crates {
+        let crate::changelog::init::Merged {
+            log, previous_content, ..
+        } = ChangeLog::for_package(package, &history, ctx, generator_segments, merge_strategy)?;
+        let mut expected = String::new();
+        log.write_to_with_template(&mut expected, &linkables, Components::all(), &ctx.changelog_template)?;
+        let actual = previous_content.unwrap_or_default();
+        if actual == expected {
+            continue;
+        }
+        let path = package
+            .manifest_path
+            .parent()
+            .expect("parent for Cargo.toml")
+            .join("CHANGELOG.md");
+        let path = path.strip_prefix(&ctx.root).unwrap_or(&path);
+        write_diff(&mut out, &actual, &expected, path.as_str(), colored)?;
+        outdated.push(package.name.as_str());
+    }
+
+    if outdated.is_empty() {
+        log::info!("All {} changelog(s) are up to date", crates.len());
+        Ok(())
+    } else {
+        anyhow::bail!(
+            "{} changelog(s) are out of date, run 'cargo changelog --write {}' to update them",
+            outdated.len(),
+            outdated.join(" ")
+        )
+    }
+}
 
+fn write_diff(
+    mut out: impl std::io::Write,
+    actual: &str,
+    expected: &str,
+    path: &str,
+    colored: bool,
+) -> std::io::Result<()> {
+    let diff = similar::TextDiff::from_lines(actual, expected)
+        .unified_diff()
+        .header(&format!("a/{}", path), &format!("b/{}", path))
+        .to_string();
+    for line in diff.lines() {
+        let color = match line.as_bytes().first() {
+            _ if !colored => None,
+            Some(b'+') if !line.starts_with("+++") => Some("32"),
+            Some(b'-') if !line.starts_with("---") => Some("31"),
+            Some(b'@') => Some("36"),
+            _ => None,
+        };
+        match color {
+            Some(color) => writeln!(out, "\x1b[{}m{}\x1b[0m", color, line)?,
+            None => writeln!(out, "{}", line)?,
+        }
+    }
     Ok(())
 }
 
@@ -1,10 +1,23 @@
 pub mod release {
     use crate::changelog::section::segment;
 
+    /// What to do about commit messages that aren't git-conventional in the unreleased history of crates to release.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CommitLint {
+        /// Log each offending commit, but continue the release.
+        Warn,
+        /// Log each offending commit and abort the release if there is at least one.
+        Deny,
+    }
+
     #[derive(Debug, Clone, Copy)]
     pub struct Options {
         pub dry_run: bool,
         pub allow_dirty: bool,
+        /// If false, the release will fail if HEAD has commits that aren't contained in its upstream branch.
+        pub allow_unpushed: bool,
+        /// If true, the release will fail unless all CI check runs on HEAD completed successfully.
+        pub require_green_ci: bool,
         pub ignore_instability: bool,
         pub skip_publish: bool,
         pub dry_run_cargo_publish: bool,
@@ -12,6 +25,8 @@ pub mod release {
         /// Pass --no-verify unconditionally to cargo publish. Really just for fixing things
         pub no_verify: bool,
         pub skip_tag: bool,
+        /// If true, create tags as signed tag objects as configured in `gpg.format`, which is also the case if `tag.gpgSign` is set.
+        pub signed_tags: bool,
         pub allow_auto_publish_of_stable_crates: bool,
         pub update_crates_index: bool,
         pub bump_when_needed: bool,
@@ -25,6 +40,14 @@ pub mod release {
         pub allow_fully_generated_changelogs: bool,
         pub changelog_links: bool,
         pub allow_changelog_github_release: bool,
+        /// If true, aggregate the release sections of all crates released together into the changelog at the workspace root.
+        pub workspace_changelog: bool,
+        /// If set, check commit messages in the unreleased range of the requested crates before the release is computed.
+        pub commit_lint: Option<CommitLint>,
+        /// If true, execute the release plan without asking for confirmation.
+        pub yes: bool,
+        /// If true, print the release plan as JSON to stdout before executing it.
+        pub plan_json: bool,
     }
 }
 #[path = "release/mod.rs"]
@@ -32,9 +55,9 @@ mod release_impl;
 pub use release_impl::release;
 
 pub mod changelog {
-    use crate::changelog::section::segment;
+    use crate::changelog::{merge, section::segment};
 
-    #[derive(Debug, Clone, Copy)]
+    #[derive(Debug, Clone)]
     pub struct Options {
         pub dry_run: bool,
         pub dependencies: bool,
@@ -43,6 +66,37 @@ pub mod changelog {
         // All the segments to generate
         pub generator_segments: segment::Selection,
         pub no_links: bool,
+        /// If set, print the release sections of versions newer than the first and up to and including the second version
+        /// to stdout instead of updating changelogs.
+        pub diff: Option<(semver::Version, semver::Version)>,
+        /// How to merge generated content into existing changelogs.
+        pub merge_strategy: merge::Strategy,
+        /// The format in which changelogs are printed to stdout.
+        pub format: Format,
+        /// If true, print a diff between the changelogs as they would be written and the ones in the worktree,
+        /// and fail if they differ, without changing anything.
+        pub verify_only: bool,
+    }
+
+    /// The format in which changelogs are printed.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Format {
+        /// Print markdown, exactly as it is written into changelog files.
+        Markdown,
+        /// Print the parsed changelog structure of each crate as JSON, to be consumed by tools.
+        Json,
+    }
+
+    impl std::str::FromStr for Format {
+        type Err = anyhow::Error;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Ok(match s {
+                "markdown" => Format::Markdown,
+                "json" => Format::Json,
+                _ => anyhow::bail!("Invalid changelog format '{}', expected one of 'markdown' or 'json'", s),
+            })
+        }
     }
 }
 #[path = "changelog.rs"]
@@ -0,0 +1,406 @@
+//! Create or update releases on the forge hosting the repository through its REST API, by means of the `curl` program.
+use std::{
+    borrow::Cow,
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use anyhow::{bail, Context as _};
+use cargo_metadata::Package;
+use serde_json::json;
+
+use crate::{
+    changelog::write::{Forge, Linkables, RepositoryUrl},
+    command::{release::Options, release_impl::github},
+    utils::{will, Program},
+    Context,
+};
+
+/// The way releases are created.
+pub enum Method {
+    /// Use the REST API of the forge.
+    Api(Api),
+    /// Use the `gh` program, which brings its own authentication.
+    GhProgram,
+}
+
+impl Method {
+    /// Determine how to create releases for the repository the `origin` remote points to, or return `None` after
+    /// explaining why there is no way to do so.
+    pub fn new(ctx: &Context) -> anyhow::Result<Option<Self>> {
+        let repository_url = match Linkables::from_remote(&ctx.changelog_template.links)? {
+            Linkables::AsLinks { repository_url } => Some(repository_url),
+            Linkables::AsText => None,
+        };
+        let api = repository_url.as_ref().and_then(Api::new);
+        let gh_found = Program::named("gh").found;
+        Ok(match api {
+            Some(api) => match api.token()? {
+                Some(token) => Some(Method::Api(Api { token, ..api })),
+                None if api.forge == Forge::GitHub && gh_found => Some(Method::GhProgram),
+                None => {
+                    log::warn!(
+                        "To create releases on {}, please set {} or configure a git credential helper for '{}'{}",
+                        api.host,
+                        api.token_variables().join(" or "),
+                        api.host,
+                        if api.forge == Forge::GitHub {
+                            ", or install the 'gh' program"
+                        } else {
+                            ""
+                        }
+                    );
+                    None
+                }
+            },
+            None if gh_found
+                && repository_url
+                    .as_ref()
+                    .map(|url| url.forge.is_none() || url.is_github())
+                    .unwrap_or(true) =>
+            {
+                Some(Method::GhProgram)
+            }
+            None => {
+                log::warn!(
+                    "To create releases, please host the repository on GitHub or GitLab, or configure 'changelog.links.forge' in '{}'",
+                    crate::changelog::template::FILE_NAME
+                );
+                None
+            }
+        })
+    }
+
+    /// Create the release for `new_version` of `publishee` with `notes` as its body, or update it if it already exists.
+    pub fn create_release(
+        &self,
+        publishee: &Package,
+        new_version: &semver::Version,
+        notes: &str,
+        options: Options,
+        ctx: &Context,
+    ) -> anyhow::Result<()> {
+        match self {
+            Method::Api(api) => {
+                let tag_name = crate::utils::tag_name(publishee, new_version, &ctx.repo);
+                let title = release_title(publishee, new_version, ctx);
+                if let Err(err) = api.create_or_update_release(&tag_name, &title, notes, options.dry_run) {
+                    log::warn!(
+                        "Creating the release for tag '{}' on {} failed - considering this non-critical, and you may try to create it by hand: {:#}",
+                        tag_name,
+                        api.host,
+                        err
+                    );
+                }
+                Ok(())
+            }
+            Method::GhProgram => github::create_release(publishee, new_version, notes, options, ctx),
+        }
+    }
+}
+
+/// The title of the release of `new_version` of `publishee`.
+pub fn release_title(publishee: &Package, new_version: &semver::Version, ctx: &Context) -> String {
+    format!(
+        "{}v{}",
+        match crate::utils::tag_prefix(publishee, &ctx.repo) {
+            Some(prefix) => Cow::Owned(format!("{} ", prefix)),
+            None => "".into(),
+        },
+        new_version
+    )
+}
+
+/// Access to the REST API of a forge for a particular repository.
+pub struct Api {
+    forge: Forge,
+    host: String,
+    /// The endpoint of the repository, without trailing slash.
+    repository: String,
+    token: String,
+}
+
+impl Api {
+    /// Create an instance for the repository at `url`, or `None` if its forge has no supported release API.
+    fn new(url: &RepositoryUrl) -> Option<Self> {
+        let forge = url.forge?;
+        let host = url.inner.host()?.to_owned();
+        let path = url.cleaned_path();
+        let repository = match forge {
+            Forge::GitHub if host == "github.com" => format!("https://api.github.com/repos{}", path),
+            Forge::GitHub => format!("{}/api/v3/repos{}", base_url(url)?, path),
+            Forge::GitLab => format!(
+                "{}/api/v4/projects/{}",
+                base_url(url)?,
+                percent_encode(path.trim_start_matches('/'))
+            ),
+            Forge::Gitea => return None,
+        };
+        Some(Api {
+            forge,
+            host,
+            repository,
+            token: String::new(),
+        })
+    }
+
+    fn token_variables(&self) -> &'static [&'static str] {
+        match self.forge {
+            Forge::GitHub => &["GITHUB_TOKEN", "GH_TOKEN"],
+            Forge::GitLab => &["GITLAB_TOKEN"],
+            Forge::Gitea => &[],
+        }
+    }
+
+    /// Obtain the token from the environment, or from the git credential helper configured for our host.
+    fn token(&self) -> anyhow::Result<Option<String>> {
+        if let Some(token) = self
+            .token_variables()
+            .iter()
+            .find_map(|name| std::env::var(name).ok().filter(|token| !token.is_empty()))
+        {
+            return Ok(Some(token));
+        }
+        let mut child = Command::new("git")
+            .args(["credential", "fill"])
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        child
+            .stdin
+            .take()
+            .expect("configured")
+            .write_all(format!("protocol=https\nhost={}\n\n", self.host).as_bytes())?;
+        let output = child.wait_with_output()?;
+        Ok(if output.status.success() {
+            password_from_credentials(&String::from_utf8_lossy(&output.stdout))
+        } else {
+            None
+        })
+    }
+
+    fn create_or_update_release(&self, tag_name: &str, title: &str, notes: &str, dry_run: bool) -> anyhow::Result<()> {
+        let (release_url, releases_url) = match self.forge {
+            Forge::GitHub => (
+                format!("{}/releases/tags/{}", self.repository, percent_encode(tag_name)),
+                format!("{}/releases", self.repository),
+            ),
+            Forge::GitLab => (
+                format!("{}/releases/{}", self.repository, percent_encode(tag_name)),
+                format!("{}/releases", self.repository),
+            ),
+            Forge::Gitea => unreachable!("Gitea APIs aren't created"),
+        };
+        if dry_run {
+            log::info!(
+                "{} create or update release '{}' for tag '{}' on {} with notes \"{}…\"",
+                will(dry_run),
+                title,
+                tag_name,
+                self.host,
+                notes.chars().take(40).collect::<String>().replace('\n', "\\n")
+            );
+            return Ok(());
+        }
+
+        let (status, body) = self.request("GET", &release_url, None)?;
+        let (method, url, payload) = match (self.forge, status) {
+            (Forge::GitHub, 200) => {
+                let release: serde_json::Value = serde_json::from_str(&body)?;
+                let id = release["id"].as_u64().context("GitHub release lacks its 'id'")?;
+                (
+                    "PATCH",
+                    format!("{}/releases/{}", self.repository, id),
+                    json!({ "name": title, "body": notes }),
+                )
+            }
+            (Forge::GitHub, 404) => (
+                "POST",
+                releases_url,
+                json!({ "tag_name": tag_name, "name": title, "body": notes }),
+            ),
+            (Forge::GitLab, 200) => ("PUT", release_url, json!({ "name": title, "description": notes })),
+            (Forge::GitLab, 404) => (
+                "POST",
+                releases_url,
+                json!({ "tag_name": tag_name, "name": title, "description": notes }),
+            ),
+            (_, status) => bail!(
+                "Could not query release of tag '{}': HTTP {}: {}",
+                tag_name,
+                status,
+                body
+            ),
+        };
+        let (status, body) = self.request(method, &url, Some(&payload))?;
+        if !(200..300).contains(&status) {
+            bail!("HTTP {} {} failed with status {}: {}", method, url, status, body);
+        }
+        log::info!(
+            "{} release '{}' for tag '{}' on {}",
+            if method == "POST" { "Created" } else { "Updated" },
+            title,
+            tag_name,
+            self.host
+        );
+        Ok(())
+    }
+
+    /// Perform an HTTP request and return the status code along with the response body.
+    ///
+    /// All parameters are passed as configuration through stdin to keep the token out of the list of processes.
+    fn request(&self, method: &str, url: &str, payload: Option<&serde_json::Value>) -> anyhow::Result<(u32, String)> {
+        let mut config = format!("url = {}\nrequest = {}\n", curl_quote(url), curl_quote(method));
+        let auth_header = match self.forge {
+            Forge::GitLab => format!("PRIVATE-TOKEN: {}", self.token),
+            Forge::GitHub | Forge::Gitea => format!("Authorization: Bearer {}", self.token),
+        };
+        for header in [
+            auth_header.as_str(),
+            "Accept: application/json",
+            "User-Agent: cargo-smart-release",
+        ] {
+            config.push_str(&format!("header = {}\n", curl_quote(header)));
+        }
+        if let Some(payload) = payload {
+            config.push_str(&format!(
+                "header = {}\ndata-binary = {}\n",
+                curl_quote("Content-Type: application/json"),
+                curl_quote(&payload.to_string())
+            ));
+        }
+
+        let mut cmd = Command::new("curl");
+        cmd.args([
+            "--silent",
+            "--show-error",
+            "--write-out",
+            "\n%{http_code}",
+            "--config",
+            "-",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped());
+        log::trace!("Will run {:?} for {} {}", cmd, method, url);
+        let mut child = cmd
+            .spawn()
+            .context("The 'curl' program is required to create releases")?;
+        child.stdin.take().expect("configured").write_all(config.as_bytes())?;
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            bail!("'curl' failed to {} {}", method, url);
+        }
+        parse_response(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+fn base_url(url: &RepositoryUrl) -> Option<String> {
+    let https = url.https()?;
+    Some(https.strip_suffix(&url.cleaned_path()).unwrap_or(&https).to_owned())
+}
+
+/// Split the output of curl into the status code it appends and the body.
+fn parse_response(output: &str) -> anyhow::Result<(u32, String)> {
+    let (body, status) = output
+        .rsplit_once('\n')
+        .context("curl didn't append the HTTP status code")?;
+    Ok((status.trim().parse()?, body.to_owned()))
+}
+
+fn password_from_credentials(credentials: &str) -> Option<String> {
+    credentials
+        .lines()
+        .find_map(|line| line.strip_prefix("password="))
+        .filter(|password| !password.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+/// Quote `value` for use in a curl configuration file.
+fn curl_quote(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Encode everything but unreserved characters for use as a single path component.
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use git_repository::bstr::ByteSlice;
+
+    use super::*;
+
+    fn api(url: &str) -> Option<Api> {
+        Api::new(
+            &git_repository::Url::try_from(url.as_bytes().as_bstr())
+                .expect("valid")
+                .into(),
+        )
+    }
+
+    #[test]
+    fn repository_endpoints_depend_on_the_forge() {
+        assert_eq!(
+            api("git@github.com:user/repo.git").expect("supported").repository,
+            "https://api.github.com/repos/user/repo"
+        );
+        assert_eq!(
+            api("https://gitlab.com/group/sub/project.git")
+                .expect("supported")
+                .repository,
+            "https://gitlab.com/api/v4/projects/group%2Fsub%2Fproject"
+        );
+        assert!(
+            api("https://codeberg.org/user/repo").is_none(),
+            "gitea releases aren't supported"
+        );
+        assert!(api("https://example.com/user/repo").is_none(), "unknown forge");
+    }
+
+    #[test]
+    fn curl_config_values_are_quoted() {
+        assert_eq!(
+            curl_quote(&json!({"body": "a \"quote\"\nand\\more"}).to_string()),
+            r#""{\"body\":\"a \\\"quote\\\"\\nand\\\\more\"}""#
+        );
+    }
+
+    #[test]
+    fn responses_end_with_the_status_code() {
+        assert_eq!(parse_response("{\"id\": 1}\n200").unwrap(), (200, "{\"id\": 1}".into()));
+        assert_eq!(parse_response("\n404").unwrap(), (404, "".into()));
+    }
+
+    #[test]
+    fn the_password_is_the_token() {
+        assert_eq!(
+            password_from_credentials("protocol=https\nhost=github.com\nusername=me\npassword=secret\n").as_deref(),
+            Some("secret")
+        );
+        assert_eq!(password_from_credentials("protocol=https\nhost=github.com\n"), None);
+    }
+}
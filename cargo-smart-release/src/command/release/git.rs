@@ -2,6 +2,7 @@ use std::{convert::TryInto, process::Command};
 
 use anyhow::bail;
 use cargo_metadata::Package;
+use git_repository as git;
 use git_repository::{bstr::ByteSlice, refs, refs::transaction::PreviousValue, Id};
 
 use super::{tag_name, Options};
@@ -36,21 +37,42 @@ pub(in crate::command::release_impl) fn create_version_tag<'repo>(
     commit_id: Option<Id<'repo>>,
     tag_message: Option<String>,
     ctx: &'repo crate::Context,
-    Options { dry_run, skip_tag, .. }: Options,
+    Options {
+        dry_run,
+        skip_tag,
+        signed_tags,
+        ..
+    }: Options,
 ) -> anyhow::Result<Option<refs::FullName>> {
     if skip_tag {
         return Ok(None);
     }
     let tag_name = tag_name(publishee, new_version, &ctx.repo);
+    create_tag(&ctx.repo, tag_name, commit_id, tag_message, dry_run, signed_tags)
+}
+
+fn create_tag(
+    repo: &git::Repository,
+    tag_name: String,
+    commit_id: Option<Id<'_>>,
+    tag_message: Option<String>,
+    dry_run: bool,
+    signed_tags: bool,
+) -> anyhow::Result<Option<refs::FullName>> {
+    let sign = signed_tags || repo.config_snapshot().boolean("tag.gpgSign").unwrap_or(false);
     if dry_run {
         match tag_message {
             Some(message) => {
                 log::trace!(
-                    "WOULD create tag object {} with changelog message, first line is: '{}'",
+                    "WOULD create {}tag object {} with changelog message, first line is: '{}'",
+                    if sign { "signed " } else { "" },
                     tag_name,
                     message.lines().next().unwrap_or("")
                 );
             }
+            None if sign => {
+                log::trace!("WOULD create signed tag object {}", tag_name);
+            }
             None => {
                 log::trace!("WOULD create tag {}", tag_name);
             }
@@ -60,11 +82,13 @@ pub(in crate::command::release_impl) fn create_version_tag<'repo>(
         let target = commit_id.expect("set in --execute mode");
         let constraint = PreviousValue::Any;
         let tag = match tag_message {
+            Some(message) if sign => create_signed_tag(repo, tag_name, target, message, constraint)?,
+            None if sign => create_signed_tag(repo, tag_name.clone(), target, tag_name, constraint)?,
             Some(message) => {
-                let tag = ctx.repo.tag(
+                let tag = repo.tag(
                     tag_name,
                     target,
-                    git_repository::objs::Kind::Commit,
+                    git::objs::Kind::Commit,
                     Some(crate::git::author()?.to_ref()),
                     message,
                     constraint,
@@ -73,7 +97,7 @@ pub(in crate::command::release_impl) fn create_version_tag<'repo>(
                 tag
             }
             None => {
-                let tag = ctx.repo.tag_reference(tag_name, target, constraint)?;
+                let tag = repo.tag_reference(tag_name, target, constraint)?;
                 log::info!("Created tag {}", tag.name().as_bstr());
                 tag
             }
@@ -82,6 +106,32 @@ pub(in crate::command::release_impl) fn create_version_tag<'repo>(
     }
 }
 
+/// Like [`git::Repository::tag()`], but sign the tag object as configured in `repo` before writing it.
+fn create_signed_tag<'repo>(
+    repo: &'repo git::Repository,
+    tag_name: String,
+    target: Id<'_>,
+    message: String,
+    constraint: PreviousValue,
+) -> anyhow::Result<git::Reference<'repo>> {
+    let tagger = crate::git::author()?;
+    let mut tag = git::objs::Tag {
+        target: target.detach(),
+        target_kind: git::objs::Kind::Commit,
+        name: tag_name.as_str().into(),
+        tagger: Some(tagger.clone()),
+        // The newline separating message and signature is implied, and it's part of what's signed.
+        message: message.strip_suffix('\n').unwrap_or(&message).into(),
+        pgp_signature: None,
+    };
+    tag.sign(|payload| crate::git::sign(repo, payload, &tagger))?;
+
+    let tag_id = repo.write_object(&tag)?;
+    let tag = repo.tag_reference(tag_name, tag_id, constraint)?;
+    log::info!("Created signed tag object {} with release notes.", tag.name().as_bstr());
+    Ok(tag)
+}
+
 // TODO: Use gitoxide here
 pub fn push_tags_and_head(
     tag_names: &[refs::FullName],
@@ -104,3 +154,111 @@ pub fn push_tags_and_head(
         bail!("'git push' invocation failed. Try to push manually and repeat the smart-release invocation to resume, possibly with --skip-push.");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use git_repository as git;
+    use git_repository::bstr::ByteSlice;
+
+    use super::create_tag;
+
+    fn repo_with_commit(dir: &std::path::Path, config: &[(&str, &str)]) -> git::Repository {
+        let git = |args: &[&str]| {
+            assert!(Command::new("git")
+                .args(args)
+                .current_dir(dir)
+                .env("GIT_AUTHOR_NAME", "author")
+                .env("GIT_AUTHOR_EMAIL", "author@example.com")
+                .env("GIT_COMMITTER_NAME", "committer")
+                .env("GIT_COMMITTER_EMAIL", "committer@example.com")
+                .status()
+                .expect("git can be run")
+                .success())
+        };
+        git(&["init", "-q"]);
+        git(&["commit", "-q", "--allow-empty", "--no-gpg-sign", "-m", "initial"]);
+        for (key, value) in config {
+            git(&["config", key, value]);
+        }
+        git::open(dir).expect("valid repository")
+    }
+
+    #[test]
+    fn dry_run_creates_nothing_even_if_tags_are_signed_by_configuration() -> anyhow::Result<()> {
+        let dir = git_testtools::tempfile::tempdir()?;
+        let repo = repo_with_commit(dir.path(), &[("tag.gpgSign", "true"), ("gpg.program", "false")]);
+        let head = repo.head_id()?;
+
+        let name = create_tag(&repo, "v1.0.0".into(), Some(head), Some("notes".into()), true, false)?;
+        assert_eq!(name.expect("tag name").as_bstr(), "refs/tags/v1.0.0");
+        assert!(
+            repo.try_find_reference("refs/tags/v1.0.0")?.is_none(),
+            "the signing program isn't run and no tag is created in dry-run mode"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tag_gpg_sign_creates_signed_tag_objects() -> anyhow::Result<()> {
+        // `git verify-tag` can verify SSH signatures as of git 2.34.
+        if git_testtools::should_skip_as_git_version_is_smaller_than(2, 34, 0) {
+            return Ok(());
+        }
+        let dir = git_testtools::tempfile::tempdir()?;
+        let key = dir.path().join("key");
+        let keygen = Command::new("ssh-keygen")
+            .args(["-q", "-t", "ed25519", "-N", "", "-C", "test", "-f"])
+            .arg(&key)
+            .status();
+        match keygen {
+            Ok(status) => assert!(status.success()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err.into()),
+        }
+        let repo_dir = dir.path().join("repo");
+        std::fs::create_dir(&repo_dir)?;
+        let repo = repo_with_commit(
+            &repo_dir,
+            &[
+                ("tag.gpgSign", "true"),
+                ("gpg.format", "ssh"),
+                ("user.signingKey", key.to_str().expect("valid UTF-8")),
+            ],
+        );
+        let head = repo.head_id()?;
+
+        let name = create_tag(
+            &repo,
+            "v1.0.0".into(),
+            Some(head),
+            Some("# notes\n".into()),
+            false,
+            false,
+        )?;
+        assert_eq!(name.expect("tag name").as_bstr(), "refs/tags/v1.0.0");
+        let tag = repo.find_reference("refs/tags/v1.0.0")?.id().object()?;
+        assert_eq!(tag.kind, git::objs::Kind::Tag);
+        assert!(
+            tag.data.find("\n\n# notes\n-----BEGIN SSH SIGNATURE-----\n").is_some(),
+            "the message is kept verbatim and directly followed by the signature"
+        );
+        assert_eq!(git::objs::TagRefIter::from_bytes(&tag.data).target_id()?, head);
+
+        let public_key = std::fs::read_to_string(key.with_extension("pub"))?;
+        let allowed_signers = dir.path().join("allowed_signers");
+        std::fs::write(&allowed_signers, format!("* {}", public_key))?;
+        assert!(
+            Command::new("git")
+                .arg("-c")
+                .arg(format!("gpg.ssh.allowedSignersFile={}", allowed_signers.display()))
+                .args(["verify-tag", "v1.0.0"])
+                .current_dir(&repo_dir)
+                .status()?
+                .success(),
+            "git can verify the signature"
+        );
+        Ok(())
+    }
+}
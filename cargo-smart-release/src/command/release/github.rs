@@ -1,8 +1,10 @@
 #![allow(dead_code)]
 
-use std::{borrow::Cow, process::Command};
+use std::process::Command;
 
+use anyhow::bail;
 use cargo_metadata::Package;
+use git_repository as git;
 
 use crate::{
     command::release::Options,
@@ -40,14 +42,7 @@ pub fn create_release(
     cmd.args(["release", "create"])
         .arg(&tag_name)
         .arg("--title")
-        .arg(format!(
-            "{}v{}",
-            match crate::utils::tag_prefix(publishee, &ctx.repo) {
-                Some(prefix) => Cow::Owned(format!("{} ", prefix)),
-                None => "".into(),
-            },
-            new_version
-        ))
+        .arg(super::forge::release_title(publishee, new_version, ctx))
         .arg("--notes");
     log::trace!(
         "{} run {:?} \"{}…\" [note truncated]",
@@ -70,3 +65,74 @@ pub fn create_release(
     }
     Ok(())
 }
+
+/// Fail unless all CI check runs of the GitHub repository for commit `id` completed successfully, as queried with the `gh` tool.
+pub fn assure_ci_is_green(id: &git::oid) -> anyhow::Result<()> {
+    if !Program::named("gh").found {
+        bail!("The 'gh' program is required to query the CI status, please install it or do not use --require-green-ci")
+    }
+    let output = Command::new("gh")
+        .arg("api")
+        .arg(format!("repos/{{owner}}/{{repo}}/commits/{}/check-runs", id))
+        .args(["--jq", r#".check_runs[] | [.name, .status, .conclusion // ""] | @tsv"#])
+        .output()?;
+    if !output.status.success() {
+        bail!(
+            "Could not query the CI status of {}: {}",
+            id,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    }
+    check_runs_verdict(&String::from_utf8_lossy(&output.stdout)).map_err(|err| {
+        anyhow::anyhow!(
+            "CI on {} isn't green: {}. Wait for it to pass, or release without --require-green-ci.",
+            id,
+            err
+        )
+    })
+}
+
+/// Judge the check runs given as lines of tab-separated name, status and conclusion.
+fn check_runs_verdict(runs: &str) -> Result<(), String> {
+    let mut num_runs = 0;
+    let mut failures = Vec::new();
+    for line in runs.lines().filter(|line| !line.trim().is_empty()) {
+        num_runs += 1;
+        let mut fields = line.split('\t');
+        let name = fields.next().unwrap_or_default();
+        let status = fields.next().unwrap_or_default();
+        let conclusion = fields.next().unwrap_or_default();
+        if status != "completed" {
+            failures.push(format!("'{}' is {}", name, status));
+        } else if !matches!(conclusion, "success" | "neutral" | "skipped") {
+            failures.push(format!("'{}' concluded with {}", name, conclusion));
+        }
+    }
+    if num_runs == 0 {
+        return Err("there are no check runs".into());
+    }
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(failures.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_runs_verdict;
+
+    #[test]
+    fn all_completed_runs_must_succeed() {
+        assert!(check_runs_verdict("test\tcompleted\tsuccess\nlint\tcompleted\tskipped\n").is_ok());
+        assert_eq!(
+            check_runs_verdict("test\tcompleted\tfailure\nlint\tin_progress\t\n").unwrap_err(),
+            "'test' concluded with failure, 'lint' is in_progress"
+        );
+    }
+
+    #[test]
+    fn no_runs_are_not_green() {
+        assert_eq!(check_runs_verdict("").unwrap_err(), "there are no check runs");
+    }
+}
@@ -10,7 +10,7 @@ use cargo_metadata::{camino::Utf8PathBuf, Package};
 use git_repository::{lock::File, Id};
 use semver::{Version, VersionReq};
 
-use super::{cargo, git, Context, Options};
+use super::{cargo, git, plan::Plan, Context, Options};
 use crate::{
     changelog,
     changelog::{write::Linkables, Section},
@@ -40,6 +40,7 @@ pub(in crate::command::release_impl) fn edit_version_and_fixup_dependent_crates_
         changelog_ids_with_statistical_segments_only,
         changelog_ids_probably_lacking_user_edits,
         release_section_by_publishee,
+        workspace_changelog_lock,
         mut made_change,
     } = changelog
         .then(|| gather_changelog_data(ctx, &crates_and_versions_to_be_published, opts))
@@ -92,10 +93,20 @@ pub(in crate::command::release_impl) fn edit_version_and_fixup_dependent_crates_
     );
 
     preview_changelogs(ctx, &pending_changelogs, opts)?;
+    Plan::new(
+        crates,
+        &pending_changelogs,
+        workspace_changelog_lock.as_ref(),
+        &commit_message,
+        ctx,
+        opts,
+    )
+    .present_and_confirm(opts)?;
 
     let bail_message = commit_locks_and_generate_bail_message(
         ctx,
         pending_changelogs,
+        workspace_changelog_lock,
         locks_by_manifest_path,
         changelog_ids_with_statistical_segments_only,
         changelog_ids_probably_lacking_user_edits,
@@ -116,6 +127,7 @@ pub(in crate::command::release_impl) fn edit_version_and_fixup_dependent_crates_
 fn commit_locks_and_generate_bail_message(
     ctx: &Context,
     pending_changelogs: Vec<(&Package, bool, File)>,
+    workspace_changelog_lock: Option<File>,
     locks_by_manifest_path: BTreeMap<&Utf8PathBuf, File>,
     changelog_ids_with_statistical_segments_only: Vec<usize>,
     changelog_ids_probably_lacking_user_edits: Vec<usize>,
@@ -148,6 +160,12 @@ fn commit_locks_and_generate_bail_message(
                     .push(package);
             }
         }
+        if let Some(lock) = workspace_changelog_lock {
+            // Only aggregate releases that are expected to go through as they are.
+            if changelog_ids_with_statistical_segments_only.is_empty() {
+                lock.commit()?;
+            }
+        }
         for manifest_lock in locks_by_manifest_path.into_values() {
             manifest_lock.commit()?;
         }
@@ -368,6 +386,8 @@ pub struct GatherOutcome<'meta> {
     changelog_ids_with_statistical_segments_only: Vec<usize>,
     changelog_ids_probably_lacking_user_edits: Vec<usize>,
     release_section_by_publishee: BTreeMap<&'meta str, Section>,
+    /// The aggregated changelog at the root of the workspace, if there is a coordinated release of multiple crates.
+    workspace_changelog_lock: Option<File>,
     made_change: bool,
 }
 
@@ -377,6 +397,7 @@ fn gather_changelog_data<'a, 'meta>(
     Options {
         dry_run,
         generator_segments,
+        workspace_changelog,
         ..
     }: Options,
 ) -> anyhow::Result<GatherOutcome<'meta>> {
@@ -387,6 +408,7 @@ fn gather_changelog_data<'a, 'meta>(
         changelog_ids_with_statistical_segments_only,
         changelog_ids_probably_lacking_user_edits,
         release_section_by_publishee,
+        workspace_changelog_lock,
         made_change,
     } = &mut out;
     let next_commit_date = crate::utils::time_to_offset_date_time(crate::git::author()?.time);
@@ -404,7 +426,14 @@ fn gather_changelog_data<'a, 'meta>(
                 state: log_init_state,
                 previous_content,
                 mut lock,
-            } = ChangeLog::for_package_with_write_lock(publishee, history, &ctx.base, generator_segments)?;
+                ..
+            } = ChangeLog::for_package_with_write_lock(
+                publishee,
+                history,
+                &ctx.base,
+                generator_segments,
+                changelog::merge::Strategy::PreferUser,
+            )?;
 
             log::info!(
                 "{} {} changelog for '{}'.",
@@ -472,7 +501,7 @@ fn gather_changelog_data<'a, 'meta>(
                 }
             }
             let mut write_buf = String::new();
-            log.write_to(
+            log.write_to_with_template(
                 &mut write_buf,
                 if dry_run {
                     &Linkables::AsText
@@ -484,6 +513,7 @@ fn gather_changelog_data<'a, 'meta>(
                 } else {
                     changelog::write::Components::all()
                 },
+                &ctx.base.changelog_template,
             )?;
             lock.with_mut(|file| file.write_all(write_buf.as_bytes()))?;
             *made_change |= previous_content.map(|previous| write_buf != previous).unwrap_or(true);
@@ -491,9 +521,99 @@ fn gather_changelog_data<'a, 'meta>(
             release_section_by_publishee.insert(publishee.name.as_str(), log.take_recent_release_section());
         }
     }
+    if workspace_changelog {
+        *workspace_changelog_lock = write_workspace_changelog(
+            ctx,
+            crates_and_versions_to_be_published,
+            release_section_by_publishee,
+            next_commit_date,
+            dry_run,
+            made_change,
+        )?;
+    }
     Ok(out)
 }
 
+/// Aggregate the release sections of all `crates_and_versions_to_be_published` into the changelog at the root of the workspace,
+/// and return the lock holding its new content, or `None` if there is no coordinated release or the workspace root
+/// is the directory of one of the published crates.
+fn write_workspace_changelog(
+    ctx: &Context,
+    crates_and_versions_to_be_published: &[(&Package, &Version)],
+    release_section_by_publishee: &BTreeMap<&str, Section>,
+    date: time::OffsetDateTime,
+    dry_run: bool,
+    made_change: &mut bool,
+) -> anyhow::Result<Option<File>> {
+    let root = &ctx.base.root;
+    let path = root.join("CHANGELOG.md");
+    let publishees_with_sections: Vec<_> = crates_and_versions_to_be_published
+        .iter()
+        .filter_map(|(p, v)| {
+            release_section_by_publishee
+                .get(p.name.as_str())
+                .map(|section| (*p, *v, section))
+        })
+        .collect();
+    if publishees_with_sections.len() < 2
+        || publishees_with_sections
+            .iter()
+            .any(|(p, _, _)| p.manifest_path.parent() == Some(root.as_path()))
+    {
+        return Ok(None);
+    }
+
+    let entries: Vec<_> = publishees_with_sections
+        .into_iter()
+        .map(|(p, v, section)| changelog::workspace::Entry {
+            name: p.name.as_str(),
+            version: v,
+            changelog_path: p
+                .manifest_path
+                .parent()
+                .expect("parent for Cargo.toml")
+                .join("CHANGELOG.md")
+                .strip_prefix(root)
+                .expect("workspace members are relative to the root directory")
+                .components()
+                .map(|c| c.as_str())
+                .collect::<Vec<_>>()
+                .join("/"),
+            section,
+        })
+        .collect();
+    let mut lock = git_repository::lock::File::acquire_to_update_resource(
+        &path,
+        git_repository::lock::acquire::Fail::Immediately,
+        None,
+    )?;
+    let previous_content = std::fs::read_to_string(&path).ok();
+    let content = changelog::workspace::merge(
+        previous_content.as_deref(),
+        &entries,
+        date,
+        if dry_run {
+            &Linkables::AsText
+        } else {
+            &ctx.changelog_links
+        },
+        &ctx.base.changelog_template,
+    )?;
+    log::info!(
+        "{} {} workspace changelog with release of {} crates.",
+        will(dry_run),
+        if previous_content.is_some() {
+            "modify existing"
+        } else {
+            "create a new"
+        },
+        entries.len()
+    );
+    lock.with_mut(|file| file.write_all(content.as_bytes()))?;
+    *made_change |= previous_content.map(|previous| content != previous).unwrap_or(true);
+    Ok(Some(lock))
+}
+
 fn set_version_and_update_package_dependency(
     package_to_update: &Package,
     new_package_version: Option<&semver::Version>,
@@ -5,21 +5,24 @@ use anyhow::bail;
 use crate::{
     changelog,
     changelog::{write::Linkables, Section},
-    command::release::Options,
+    command::release::{CommitLint, Options},
+    commit,
     traverse::{
         self, dependency,
         dependency::{ManifestAdjustment, VersionAdjustment},
         Dependency,
     },
-    utils::{tag_name, try_to_published_crate_and_new_version, will, Program},
+    utils::{package_by_name, tag_name, try_to_published_crate_and_new_version, will},
     version,
     version::BumpSpec,
 };
 
 mod cargo;
+mod forge;
 mod git;
 mod github;
 mod manifest;
+mod plan;
 
 pub(crate) struct Context {
     base: crate::Context,
@@ -34,19 +37,13 @@ impl Context {
         changelog: bool,
         changelog_links: bool,
     ) -> anyhow::Result<Self> {
+        let base = crate::Context::new(crate_names, changelog, bump, bump_dependencies)?;
         let changelog_links = if changelog_links {
-            crate::git::remote_url()?
-                .map(|url| Linkables::AsLinks {
-                    repository_url: url.into(),
-                })
-                .unwrap_or(Linkables::AsText)
+            Linkables::from_remote(&base.changelog_template.links)?
         } else {
             Linkables::AsText
         };
-        Ok(Context {
-            base: crate::Context::new(crate_names, changelog, bump, bump_dependencies)?,
-            changelog_links,
-        })
+        Ok(Context { base, changelog_links })
     }
 }
 
@@ -73,7 +70,13 @@ pub fn release(opts: Options, crates: Vec<String>, bump: BumpSpec, bump_dependen
         );
     }
 
-    let ctx = Context::new(crates, bump, bump_dependencies, allow_changelog, opts.changelog_links)?;
+    let ctx = Context::new(
+        crates,
+        bump,
+        bump_dependencies,
+        allow_changelog || opts.commit_lint.is_some(),
+        opts.changelog_links,
+    )?;
     if !ctx.base.crates_index.exists() {
         log::warn!("Crates.io index doesn't exist. Consider using --update-crates-index to help determining if release versions are published already");
     }
@@ -94,6 +97,9 @@ impl From<Options> for crate::traverse::Options {
 }
 
 fn release_depth_first(ctx: Context, opts: Options) -> anyhow::Result<()> {
+    if let Some(lint) = opts.commit_lint {
+        lint_commit_messages(&ctx.base, lint)?;
+    }
     let crates = {
         crate::traverse::dependencies(&ctx.base, opts.into())
             .and_then(|crates| assure_crates_index_is_uptodate(crates, &ctx.base, opts.into()))
@@ -103,11 +109,39 @@ fn release_depth_first(ctx: Context, opts: Options) -> anyhow::Result<()> {
     };
 
     assure_working_tree_is_unchanged(opts)?;
+    assure_head_is_pushed_and_ci_is_green(&ctx.base, opts)?;
     perform_release(&ctx, opts, &crates)?;
 
     Ok(())
 }
 
+fn lint_commit_messages(ctx: &crate::Context, lint: CommitLint) -> anyhow::Result<()> {
+    let packages = ctx
+        .crate_names
+        .iter()
+        .map(|name| package_by_name(&ctx.meta, name))
+        .collect::<Result<Vec<_>, _>>()?;
+    let violations = commit::lint::unreleased_violations(ctx, &packages)?;
+    for violation in &violations {
+        log::warn!(
+            "{}: commit {} by {} touching '{}' is not git-conventional: {:?}",
+            violation.crate_name,
+            violation.id.to_hex_with_len(7),
+            violation.author,
+            violation.crate_dir.as_deref().unwrap_or("."),
+            violation.title
+        );
+    }
+    if lint == CommitLint::Deny && !violations.is_empty() {
+        bail!(
+            "{} unreleased commit{} without git-conventional message. Reword them or use --lint-commits warn to continue nonetheless.",
+            violations.len(),
+            if violations.len() == 1 { "" } else { "s" }
+        );
+    }
+    Ok(())
+}
+
 fn assure_crates_index_is_uptodate<'meta>(
     crates: Vec<Dependency<'meta>>,
     ctx: &'meta crate::Context,
@@ -211,8 +245,8 @@ fn present_and_validate_dependencies(
     let mut error = false;
     for dep in crates {
         let (bump_spec, kind) = match dep.kind {
-            Kind::UserSelection => (ctx.base.bump, "provided"),
-            Kind::DependencyOrDependentOfUserSelection => (ctx.base.bump_dependencies, "dependent"),
+            Kind::UserSelection => (&ctx.base.bump, "provided"),
+            Kind::DependencyOrDependentOfUserSelection => (&ctx.base.bump_dependencies, "dependent"),
         };
         match &dep.mode {
             dependency::Mode::ToBePublished { adjustment } => {
@@ -399,19 +433,38 @@ fn assure_working_tree_is_unchanged(options: Options) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn assure_head_is_pushed_and_ci_is_green(ctx: &crate::Context, options: Options) -> anyhow::Result<()> {
+    let mut checks = Vec::new();
+    if !options.allow_unpushed {
+        checks.push(crate::git::assure_head_is_pushed());
+    }
+    if options.require_green_ci {
+        let head_id = ctx.repo.head_id()?;
+        checks.push(github::assure_ci_is_green(&head_id));
+    }
+    for check in checks {
+        if let Err(err) = check {
+            if options.dry_run {
+                log::warn!("{:#}. This will prevent a release with --execute.", err)
+            } else {
+                return Err(err);
+            }
+        }
+    }
+    Ok(())
+}
+
 fn perform_release(ctx: &Context, options: Options, crates: &[traverse::Dependency<'_>]) -> anyhow::Result<()> {
     let manifest::Outcome {
         commit_id,
         section_by_package: release_section_by_publishee,
     } = manifest::edit_version_and_fixup_dependent_crates_and_handle_changelog(crates, options, ctx)?;
 
-    let should_publish_to_github = options.allow_changelog_github_release
-        && if Program::named("gh").found {
-            true
-        } else {
-            log::warn!("To create github releases, please install the 'gh' program and try again");
-            false
-        };
+    let release_method = if options.allow_changelog_github_release {
+        forge::Method::new(&ctx.base)?
+    } else {
+        None
+    };
     let mut tag_names = Vec::new();
     let mut successful_publishees_and_version = Vec::<(&cargo_metadata::Package, &semver::Version)>::new();
     let mut publish_err = None;
@@ -439,7 +492,7 @@ fn perform_release(ctx: &Context, options: Options, crates: &[traverse::Dependen
             commit_id,
             release_section_by_publishee
                 .get(&publishee.name.as_str())
-                .and_then(|s| section_to_string(s, WriteMode::Tag)),
+                .and_then(|s| section_to_string(s, WriteMode::Tag, &ctx.base.changelog_template)),
             &ctx.base,
             options,
         )? {
@@ -447,12 +500,14 @@ fn perform_release(ctx: &Context, options: Options, crates: &[traverse::Dependen
         }
     }
     git::push_tags_and_head(&tag_names, options)?;
-    if should_publish_to_github {
+    if let Some(release_method) = release_method {
         for (publishee, new_version) in successful_publishees_and_version {
             release_section_by_publishee
                 .get(&publishee.name.as_str())
-                .and_then(|s| section_to_string(s, WriteMode::GitHubRelease))
-                .map(|release_notes| github::create_release(publishee, new_version, &release_notes, options, &ctx.base))
+                .and_then(|s| section_to_string(s, WriteMode::ForgeRelease, &ctx.base.changelog_template))
+                .map(|release_notes| {
+                    release_method.create_release(publishee, new_version, &release_notes, options, &ctx.base)
+                })
                 .transpose()?;
         }
     }
@@ -511,19 +566,20 @@ fn wait_for_release(
 
 enum WriteMode {
     Tag,
-    GitHubRelease,
+    ForgeRelease,
 }
 
-fn section_to_string(section: &Section, mode: WriteMode) -> Option<String> {
+fn section_to_string(section: &Section, mode: WriteMode, template: &changelog::Template) -> Option<String> {
     let mut b = String::new();
     section
-        .write_to(
+        .write_to_with_template(
             &mut b,
             &changelog::write::Linkables::AsText,
             match mode {
                 WriteMode::Tag => changelog::write::Components::empty(),
-                WriteMode::GitHubRelease => changelog::write::Components::DETAIL_TAGS,
+                WriteMode::ForgeRelease => changelog::write::Components::DETAIL_TAGS,
             },
+            template,
         )
         .ok()
         .map(|_| b)
@@ -0,0 +1,168 @@
+use std::io::Write;
+
+use anyhow::bail;
+use cargo_metadata::{
+    camino::{Utf8Path, Utf8PathBuf},
+    Package,
+};
+use git_repository::lock::File;
+use semver::Version;
+
+use super::{Context, Options};
+use crate::{
+    traverse::{dependency, Dependency},
+    utils::tag_name,
+};
+
+/// Everything a release is going to do, presented before anything is written so it can be confirmed.
+pub struct Plan<'a> {
+    crates: Vec<Crate<'a>>,
+    workspace_changelog: Option<Utf8PathBuf>,
+    commit_message: &'a str,
+    publish: bool,
+    push: bool,
+    forge_release: bool,
+}
+
+struct Crate<'a> {
+    package: &'a Package,
+    next_version: Option<&'a Version>,
+    publish: bool,
+    tag: Option<String>,
+    changelog: Option<Utf8PathBuf>,
+}
+
+impl<'a> Plan<'a> {
+    pub fn new(
+        crates: &'a [Dependency<'a>],
+        pending_changelogs: &'a [(&'a Package, bool, File)],
+        workspace_changelog: Option<&'a File>,
+        commit_message: &'a str,
+        ctx: &'a Context,
+        opts: Options,
+    ) -> Self {
+        let relative = |path: std::path::PathBuf| {
+            let path = path.strip_prefix(&ctx.base.root).map(ToOwned::to_owned).unwrap_or(path);
+            Utf8PathBuf::from_path_buf(path).expect("cargo paths are UTF-8")
+        };
+        let crates = crates
+            .iter()
+            .filter(|dep| dep.mode.manifest_will_change())
+            .map(|dep| {
+                let next_version = dep.mode.version_adjustment_bump().map(|bump| &bump.next_release);
+                let publish = matches!(dep.mode, dependency::Mode::ToBePublished { .. });
+                Crate {
+                    package: dep.package,
+                    next_version,
+                    publish,
+                    tag: next_version
+                        .filter(|_| publish && !opts.skip_tag)
+                        .map(|version| tag_name(dep.package, version, &ctx.base.repo)),
+                    changelog: pending_changelogs
+                        .iter()
+                        .find(|(package, has_changes, _)| *has_changes && package.id == dep.package.id)
+                        .map(|(_, _, lock)| relative(lock.resource_path())),
+                }
+            })
+            .collect();
+        Plan {
+            crates,
+            workspace_changelog: workspace_changelog.map(|lock| relative(lock.resource_path())),
+            commit_message,
+            publish: !opts.skip_publish,
+            push: !opts.skip_push,
+            forge_release: opts.allow_changelog_github_release,
+        }
+    }
+
+    /// Log the plan, print it as JSON to stdout if configured, and ask the user to confirm it before executing.
+    pub fn present_and_confirm(&self, opts: Options) -> anyhow::Result<()> {
+        if opts.plan_json {
+            let mut out = std::io::stdout();
+            serde_json::to_writer_pretty(&mut out, &self.to_json())?;
+            writeln!(out)?;
+        }
+        if opts.dry_run {
+            return Ok(());
+        }
+
+        log::info!("Release plan:");
+        for krate in &self.crates {
+            let version = match krate.next_version {
+                Some(next) if *next != krate.package.version => format!("{} ➡ {}", krate.package.version, next),
+                _ => krate.package.version.to_string(),
+            };
+            log::info!(
+                " - {} '{}' {}{}{}",
+                match (krate.publish, self.publish) {
+                    (true, true) => "publish",
+                    (true, false) => "release without publishing",
+                    (false, _) => "adjust manifest of",
+                },
+                krate.package.name,
+                version,
+                krate
+                    .tag
+                    .as_ref()
+                    .map(|tag| format!(", tag '{}'", tag))
+                    .unwrap_or_default(),
+                krate
+                    .changelog
+                    .as_ref()
+                    .map(|path| format!(", update '{}'", path))
+                    .unwrap_or_default()
+            );
+        }
+        if let Some(path) = &self.workspace_changelog {
+            log::info!(" - update workspace changelog '{}'", path);
+        }
+        log::info!(" - commit '{}'", self.commit_message.lines().next().unwrap_or_default());
+        if self.push {
+            log::info!(" - push HEAD and tags");
+        }
+        if self.forge_release {
+            log::info!(" - create releases from changelogs if the repository is hosted on a known forge");
+        }
+
+        if opts.yes {
+            return Ok(());
+        }
+        confirm()
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "crates": self.crates.iter().map(|krate| serde_json::json!({
+                "name": krate.package.name,
+                "version": krate.package.version.to_string(),
+                "next_version": krate.next_version.map(ToString::to_string),
+                "publish": self.publish && krate.publish,
+                "tag": krate.tag,
+                "changelog": krate.changelog.as_deref().map(Utf8Path::as_str),
+            })).collect::<Vec<_>>(),
+            "publish_order": self.crates
+                .iter()
+                .filter(|krate| self.publish && krate.publish)
+                .map(|krate| krate.package.name.as_str())
+                .collect::<Vec<_>>(),
+            "workspace_changelog": self.workspace_changelog.as_deref().map(Utf8Path::as_str),
+            "commit_message": self.commit_message,
+            "push": self.push,
+            "forge_release": self.forge_release,
+        })
+    }
+}
+
+fn confirm() -> anyhow::Result<()> {
+    if !atty::is(atty::Stream::Stdin) {
+        bail!("Cannot confirm the release plan as stdin isn't a terminal. Use --yes to release without confirmation.");
+    }
+    eprint!("Proceed with the release? [y/N] ");
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    match answer.trim().to_ascii_lowercase().as_str() {
+        "y" | "yes" => Ok(()),
+        _ => bail!("Release aborted as the plan wasn't confirmed"),
+    }
+}
@@ -25,7 +25,7 @@ mod tests {
     fn size_of_item() {
         assert_eq!(
             std::mem::size_of::<Item>(),
-            200,
+            280,
             "there are plenty of these loaded at a time and we should not let it grow unnnoticed."
         )
     }
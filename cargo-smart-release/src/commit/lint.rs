@@ -0,0 +1,77 @@
+use cargo_metadata::Package;
+use git_repository as git;
+
+use crate::{commit::Message, git::history::SegmentScope};
+
+/// A commit in the unreleased range of a crate whose message doesn't follow the git-conventional format.
+pub struct Violation {
+    /// The commit with the offending message.
+    pub id: git::ObjectId,
+    /// The title of the commit message.
+    pub title: String,
+    /// The author as `name <email>`.
+    pub author: String,
+    /// The name of the crate whose unreleased changes include the commit.
+    pub crate_name: String,
+    /// The repository relative directory of the crate, or `None` if it is located at the repository root.
+    pub crate_dir: Option<String>,
+}
+
+/// Return all commits in the unreleased history of `packages` whose message is not git-conventional, in order of the `packages`
+/// and their history, newest first.
+///
+/// Merge commits are ignored as their messages are typically generated.
+pub fn unreleased_violations(ctx: &crate::Context, packages: &[&Package]) -> anyhow::Result<Vec<Violation>> {
+    let history = match ctx.history.as_ref() {
+        Some(history) => history,
+        None => return Ok(Vec::new()),
+    };
+    let mut out = Vec::new();
+    for package in packages {
+        let segments = crate::git::history::crate_ref_segments(package, ctx, history, SegmentScope::Unreleased)?;
+        let unreleased = match segments.first() {
+            Some(segment) => segment,
+            None => continue,
+        };
+        for item in unreleased.history.iter().copied() {
+            let commit = ctx.repo.find_object(item.id)?.try_into_commit()?;
+            let num_parents = commit.parent_ids().count();
+            if !is_violation(&item.message, num_parents) {
+                continue;
+            }
+            let author = commit.author()?;
+            out.push(Violation {
+                id: item.id,
+                title: item.message.title.clone(),
+                author: format!("{} <{}>", author.name, author.email),
+                crate_name: package.name.clone(),
+                crate_dir: ctx.repo_relative_path(package).map(ToString::to_string),
+            });
+        }
+    }
+    Ok(out)
+}
+
+fn is_violation(message: &Message, num_parents: usize) -> bool {
+    num_parents < 2 && message.kind.is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_conventional_messages_are_violations_unless_they_belong_to_merges() {
+        let message = Message::from("fixed a thing");
+        assert!(is_violation(&message, 1));
+        assert!(is_violation(&message, 0), "root commits are linted as well");
+        assert!(!is_violation(&message, 2), "merge commits are generated");
+    }
+
+    #[test]
+    fn conventional_messages_are_fine() {
+        for message in ["fix: a thing", "feat!: breaking", "chore(deps): update"] {
+            assert!(!is_violation(&Message::from(message), 1), "{}", message);
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use git_repository as git;
 use git_repository::bstr::ByteSlice;
 
-use crate::commit::Message;
+use crate::{changelog::Template, commit::Message};
 
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq, Eq))]
@@ -85,11 +85,22 @@ mod additions {
 
 impl From<&'_ str> for Message {
     fn from(m: &str) -> Self {
-        let (title, kind, body, breaking, breaking_description) = git_conventional::Commit::parse(m)
+        let (title, kind, scopes, body, breaking, breaking_description) = git_conventional::Commit::parse(m)
             .map(|c: git_conventional::Commit<'_>| {
                 (
                     c.description().into(),
                     Some(c.type_()),
+                    c.scope()
+                        .map(|scope| {
+                            scope
+                                .as_str()
+                                .split(',')
+                                .map(str::trim)
+                                .filter(|scope| !scope.is_empty())
+                                .map(ToOwned::to_owned)
+                                .collect()
+                        })
+                        .unwrap_or_default(),
                     c.body().map(Into::into),
                     c.breaking(),
                     c.breaking_description()
@@ -101,15 +112,19 @@ impl From<&'_ str> for Message {
                 (
                     m.summary().as_ref().to_string().into(),
                     None,
+                    Vec::new(),
                     m.body().map(|b| b.without_trailer().to_str_lossy()),
                     false,
                     None,
                 )
             });
         let (title, additions) = additions::strip(title);
+        let conventional_type = kind.as_ref().map(|kind| kind.as_str().to_ascii_lowercase());
         Message {
             title: title.into_owned(),
             kind: as_static_str(kind),
+            conventional_type,
+            scopes,
             body: body.map(|b| b.into_owned()),
             breaking,
             breaking_description: breaking_description.map(ToOwned::to_owned),
@@ -118,6 +133,13 @@ impl From<&'_ str> for Message {
     }
 }
 
+impl Message {
+    /// Parse `message` after applying the rewrites of `template` to it.
+    pub fn from_rewritten(message: &str, template: &Template) -> Self {
+        Message::from(template.rewrite(message).as_ref())
+    }
+}
+
 /// Note that this depends on `crate::changelog::section::segment::Conventional::as_headline_name()`,
 fn as_static_str(kind: Option<git_conventional::Type<'_>>) -> Option<&'static str> {
     kind.map(|kind| match kind.as_str() {
@@ -147,6 +169,8 @@ mod tests {
                 title: "hi".into(),
                 body: None,
                 kind: None,
+                conventional_type: None,
+                scopes: Vec::new(),
                 breaking: false,
                 breaking_description: None,
                 additions: vec![]
@@ -162,6 +186,8 @@ mod tests {
                 title: "hi ho foo".into(),
                 body: Some("body".into()),
                 kind: None,
+                conventional_type: None,
+                scopes: Vec::new(),
                 breaking: false,
                 breaking_description: None,
                 additions: vec![]
@@ -177,6 +203,8 @@ mod tests {
                 title: "hi".into(),
                 body: Some("body\nother".into()),
                 kind: None,
+                conventional_type: None,
+                scopes: Vec::new(),
                 breaking: false,
                 breaking_description: None,
                 additions: vec![Addition::IssueId("14123".into())]
@@ -184,6 +212,52 @@ mod tests {
         )
     }
 
+    #[test]
+    fn conventional_with_scope() {
+        let message = Message::from("fix(cli): hi");
+        assert_eq!(message.kind, Some("fix"));
+        assert_eq!(message.conventional_type.as_deref(), Some("fix"));
+        assert_eq!(message.scopes, ["cli"]);
+        assert_eq!(message.title, "hi");
+    }
+
+    #[test]
+    fn conventional_with_multiple_scopes() {
+        let message = Message::from("feat(api, cli,): hi");
+        assert_eq!(message.kind, Some("feat"));
+        assert_eq!(message.scopes, ["api", "cli"]);
+        assert_eq!(message.title, "hi");
+    }
+
+    #[test]
+    fn rewrites_apply_before_parsing() {
+        let template: Template = r#"
+[[changelog.rewrites]]
+pattern = '^(?P<ticket>[A-Z][A-Z0-9]*-\d+):\s*(?P<rest>.*)'
+replacement = '${rest} (#${ticket})'
+"#
+        .parse()
+        .expect("valid");
+        assert_eq!(
+            Message::from_rewritten("JIRA-123: feat(api,cli): hi\n\nthe body", &template),
+            Message {
+                title: "hi".into(),
+                body: Some("the body".into()),
+                kind: Some("feat"),
+                conventional_type: Some("feat".into()),
+                scopes: vec!["api".into(), "cli".into()],
+                breaking: false,
+                breaking_description: None,
+                additions: vec![Addition::IssueId("JIRA-123".into())]
+            }
+        );
+        assert_eq!(
+            Message::from_rewritten("fix: hi", &template),
+            Message::from("fix: hi"),
+            "messages that don't match are unchanged"
+        );
+    }
+
     #[test]
     fn conventional_with_additions() {
         assert_eq!(
@@ -192,6 +266,8 @@ mod tests {
                 title: "hi".into(),
                 body: Some("the body".into()),
                 kind: Some("feat"),
+                conventional_type: Some("feat".into()),
+                scopes: Vec::new(),
                 breaking: true,
                 breaking_description: Some("breaks".into()),
                 additions: vec![Addition::IssueId("123".into())]
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use git_repository as git;
 
 pub mod history;
+pub mod lint;
 
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq, Eq))]
@@ -13,6 +14,10 @@ pub struct Message {
     pub body: Option<String>,
     /// If set, the git-conventional scope to help organizing changes.
     pub kind: Option<&'static str>,
+    /// If set, the git-conventional type as written in the message, like `perf` or a type that isn't known to us.
+    pub conventional_type: Option<String>,
+    /// The git-conventional scopes in parentheses after the kind, like `cli` in `feat(cli): …` or `api` and `cli` in `feat(api,cli): …`.
+    pub scopes: Vec<String>,
     /// If set, this is a breaking change as indicated git-conventional.
     pub breaking: bool,
     /// If set, this commit message body contains a specific description of the breaking change.
@@ -15,6 +15,8 @@ pub struct Context {
     pub history: Option<crate::commit::History>,
     pub bump: BumpSpec,
     pub bump_dependencies: BumpSpec,
+    /// How to organize git-conventional commits in changelogs, as configured in the root of the workspace.
+    pub changelog_template: crate::changelog::Template,
 }
 
 impl Context {
@@ -28,10 +30,11 @@ impl Context {
         let root = meta.workspace_root.clone();
         let repo = git::discover(&root)?.apply_environment();
         let crates_index = crate::crates_index::Index::new_cargo_default()?;
+        let changelog_template = crate::changelog::Template::at(&root)?;
         let history = (force_history_segmentation
             || matches!(bump, BumpSpec::Auto)
             || matches!(bump_dependencies, BumpSpec::Auto))
-        .then(|| crate::git::history::collect(&repo))
+        .then(|| crate::git::history::collect(&repo, &changelog_template))
         .transpose()?
         .flatten();
         Ok(Context {
@@ -43,6 +46,7 @@ impl Context {
             history,
             bump,
             bump_dependencies,
+            changelog_template,
         })
     }
 
@@ -4,20 +4,22 @@ use std::{
     path::PathBuf,
 };
 
-use anyhow::bail;
+use anyhow::{bail, Context as _};
+use cargo_metadata::camino::Utf8Path;
 use cargo_metadata::Package;
 use git_repository as git;
 use git_repository::{
-    bstr::ByteSlice,
+    bstr::{BStr, BString, ByteSlice},
     head,
-    prelude::{ObjectIdExt, ReferenceExt},
+    prelude::{FindExt, ObjectIdExt, ReferenceExt},
 };
 
 use crate::{
+    changelog::Template,
     commit,
     commit::history::{Item, Segment},
     git::strip_tag_path,
-    utils::{component_to_bytes, is_tag_name, is_tag_version, tag_prefix},
+    utils::{component_to_bytes, is_tag_name, is_tag_version, package_by_id, tag_prefix},
     Context,
 };
 
@@ -28,7 +30,8 @@ pub enum SegmentScope {
     EntireHistory,
 }
 
-pub fn collect(repo: &git::Repository) -> anyhow::Result<Option<commit::History>> {
+/// Collect the history of `HEAD`, parsing commit messages after applying the rewrites of `template`.
+pub fn collect(repo: &git::Repository, template: &Template) -> anyhow::Result<Option<commit::History>> {
     let mut handle = repo.clone();
     handle.object_cache_size(64 * 1024);
     let reference = match handle.head()?.peeled()?.kind {
@@ -82,7 +85,7 @@ pub fn collect(repo: &git::Repository) -> anyhow::Result<Option<commit::History>
         items.push(commit::history::Item {
             id: commit_id.detach(),
             commit_time,
-            message: commit::Message::from(message),
+            message: commit::Message::from_rewritten(message, template),
             tree_id,
             parent_tree_id,
         });
@@ -129,23 +132,10 @@ pub fn crate_ref_segments<'h>(
         }
     };
 
-    let mut segments = Vec::new();
-    let mut segment = commit::history::Segment {
-        head: history.head.to_owned(),
-        history: vec![],
-    };
-
     let dir = ctx.repo_relative_path(package);
-    let filter = dir
-        .map(|dir| {
-            let mut components = dir.components().collect::<Vec<_>>();
-            match components.len() {
-                0 => unreachable!("BUG: it's None if empty"),
-                1 => Filter::Fast(components.pop().map(component_to_bytes).expect("exactly one")),
-                _ => Filter::Slow(components.into_iter().map(component_to_bytes).collect()),
-            }
-        })
-        .unwrap_or_else(|| {
+    let filter = match package_pathspecs(package, ctx)? {
+        Some(patterns) => Filter::Pathspecs(patterns),
+        None => dir.map(filter_for_dir).unwrap_or_else(|| {
             if ctx.meta.workspace_members.len() == 1 {
                 Filter::None
             } else {
@@ -156,11 +146,146 @@ pub fn crate_ref_segments<'h>(
                 // TODO: analyse .targets to find actual source directory.
                 Filter::Fast(b"src")
             }
-        });
+        }),
+    };
+
+    let is_entire_history = matches!(scope, SegmentScope::EntireHistory);
+    let segments = segments_by_tags(&ctx.repo, history, &mut tags_by_commit, &filter, scope)?;
+
+    if is_entire_history && !tags_by_commit.is_empty() {
+        log::warn!(
+            "{}: The following tags were not encountered during commit graph traversal: {}",
+            package.name,
+            tags_by_commit
+                .into_values()
+                .map(|v| v.name.as_bstr().to_str_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+
+    Ok(segments)
+}
+
+/// Return the head reference followed by all tags for which `is_release_tag(tag_name)` is true, ordered by ancestry.
+/// If `dir` is set, only commits changing files within it, relative to the repository root, are part of the segments.
+pub fn tag_segments<'h>(
+    repo: &git::Repository,
+    history: &'h commit::History,
+    is_release_tag: impl Fn(&BStr) -> bool,
+    dir: Option<&Utf8Path>,
+) -> anyhow::Result<Vec<commit::history::Segment<'h>>> {
+    let mut tags_by_commit = BTreeMap::from_iter(
+        repo.references()?
+            .prefixed("refs/tags")?
+            .peeled()
+            .filter_map(|r| r.ok().map(|r| r.detach()))
+            .filter(|r| is_release_tag(strip_tag_path(r.name.as_ref())))
+            .map(|r| {
+                let t = r.peeled.expect("already peeled");
+                (t, r)
+            }),
+    );
+    let filter = dir.map(filter_for_dir).unwrap_or(Filter::None);
+    segments_by_tags(repo, history, &mut tags_by_commit, &filter, SegmentScope::EntireHistory)
+}
+
+/// Return the pathspecs selecting the files of `package` relative to the repository root, or `None` if its directory
+/// can be tracked as a whole.
+///
+/// Pathspecs are needed if they are configured with `pathspecs` in `[package.metadata.smart-release]`, relative to the crate
+/// directory unless they use `:(top)`, or if other workspace members are located within the crate directory, which are excluded.
+fn package_pathspecs(package: &Package, ctx: &Context) -> anyhow::Result<Option<Vec<git_pathspec::Pattern>>> {
+    let configured = match package.metadata.get("smart-release").and_then(|m| m.get("pathspecs")) {
+        Some(specs) => specs
+            .as_array()
+            .and_then(|specs| specs.iter().map(|spec| spec.as_str()).collect::<Option<Vec<_>>>())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "'pathspecs' in [package.metadata.smart-release] of {} must be an array of strings",
+                    package.manifest_path
+                )
+            })?,
+        None => Vec::new(),
+    };
+    let dir = ctx.repo_relative_path(package);
+    let nested_members: Vec<_> = ctx
+        .meta
+        .workspace_members
+        .iter()
+        .filter(|id| **id != package.id)
+        .filter_map(|id| ctx.repo_relative_path(package_by_id(&ctx.meta, id)))
+        .filter(|member_dir| dir.map_or(true, |dir| member_dir.starts_with(dir)))
+        .collect();
+    if configured.is_empty() && (dir.is_none() || nested_members.is_empty()) {
+        return Ok(None);
+    }
+
+    let dir = dir.map(|dir| dir.as_str()).unwrap_or_default();
+    let mut patterns = configured
+        .into_iter()
+        .map(|spec| {
+            let mut pattern = git_pathspec::parse(spec.as_bytes())
+                .with_context(|| format!("Invalid pathspec '{}' of {}", spec, package.manifest_path))?;
+            if !pattern.signature.contains(git_pathspec::MagicSignature::TOP) && !dir.is_empty() {
+                pattern.path = match pattern.path.as_bytes() {
+                    b"" | b"." => dir.into(),
+                    path => {
+                        let mut prefixed = BString::from(format!("{}/", dir));
+                        prefixed.extend_from_slice(path);
+                        prefixed
+                    }
+                };
+            }
+            Ok(pattern)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    if !dir.is_empty() && patterns.iter().all(|pattern| pattern.is_excluded()) {
+        patterns.push(literal_pattern(dir, git_pathspec::MagicSignature::empty()));
+    }
+    patterns.extend(
+        nested_members
+            .into_iter()
+            .map(|member_dir| literal_pattern(member_dir.as_str(), git_pathspec::MagicSignature::EXCLUDE)),
+    );
+    Ok(Some(patterns))
+}
+
+fn literal_pattern(path: &str, signature: git_pathspec::MagicSignature) -> git_pathspec::Pattern {
+    git_pathspec::Pattern {
+        path: path.into(),
+        signature,
+        search_mode: git_pathspec::MatchMode::Literal,
+        attributes: Vec::new(),
+    }
+}
+
+fn filter_for_dir(dir: &Utf8Path) -> Filter<'_> {
+    let mut components = dir.components().collect::<Vec<_>>();
+    match components.len() {
+        0 => Filter::None,
+        1 => Filter::Fast(components.pop().map(component_to_bytes).expect("exactly one")),
+        _ => Filter::Slow(components.into_iter().map(component_to_bytes).collect()),
+    }
+}
+
+/// Split `history` into segments at each commit in `tags_by_commit`, removing the tags that were encountered.
+fn segments_by_tags<'h>(
+    repo: &git::Repository,
+    history: &'h commit::History,
+    tags_by_commit: &mut BTreeMap<git::ObjectId, git::refs::Reference>,
+    filter: &Filter<'_>,
+    scope: SegmentScope,
+) -> anyhow::Result<Vec<commit::history::Segment<'h>>> {
+    let mut segments = Vec::new();
+    let mut segment = commit::history::Segment {
+        head: history.head.to_owned(),
+        history: vec![],
+    };
 
     for item in history.items.iter() {
         match tags_by_commit.remove(&item.id) {
-            None => add_item_if_package_changed(ctx, &mut segment, &filter, item, &history.data_by_tree_id)?,
+            None => add_item_if_package_changed(repo, &mut segment, filter, item, &history.data_by_tree_id)?,
             Some(next_ref) => {
                 match scope {
                     SegmentScope::EntireHistory => {
@@ -177,35 +302,85 @@ pub fn crate_ref_segments<'h>(
                         return Ok(segments);
                     }
                 }
-                add_item_if_package_changed(ctx, &mut segment, &filter, item, &history.data_by_tree_id)?
+                add_item_if_package_changed(repo, &mut segment, filter, item, &history.data_by_tree_id)?
             }
         }
     }
     segments.push(segment);
+    Ok(segments)
+}
 
-    if matches!(scope, SegmentScope::EntireHistory) && !tags_by_commit.is_empty() {
-        log::warn!(
-            "{}: The following tags were not encountered during commit graph traversal: {}",
-            package.name,
-            tags_by_commit
-                .into_values()
-                .map(|v| v.name.as_bstr().to_str_lossy().into_owned())
-                .collect::<Vec<_>>()
-                .join(", ")
+/// Return the paths of all files changed by the commit `item` within `package`, relative to the root of `package`.
+pub fn changed_paths_in_package(
+    package: &Package,
+    ctx: &Context,
+    item: &Item,
+    data_by_tree_id: &HashMap<git::ObjectId, Vec<u8>>,
+) -> anyhow::Result<Vec<BString>> {
+    let components: Vec<_> = ctx
+        .repo_relative_path(package)
+        .map(|dir| dir.components().map(component_to_bytes).collect())
+        .unwrap_or_default();
+    let package_tree_id = |tree_id: git::ObjectId| -> anyhow::Result<Option<git::ObjectId>> {
+        if components.is_empty() {
+            return Ok(Some(tree_id));
+        }
+        Ok(
+            git::Tree::from_data(tree_id, data_by_tree_id[&tree_id].to_owned(), &ctx.repo)
+                .lookup_path(components.iter().copied())?
+                .filter(|entry| entry.mode.is_tree())
+                .map(|entry| entry.oid),
         )
+    };
+    let current = package_tree_id(item.tree_id)?;
+    let parent = item.parent_tree_id.map(package_tree_id).transpose()?.flatten();
+    if current == parent {
+        return Ok(Vec::new());
     }
 
-    Ok(segments)
+    let (mut buf, mut parent_buf) = (Vec::new(), Vec::new());
+    let tree = match current {
+        Some(id) => ctx.repo.objects.find_tree_iter(id, &mut buf)?,
+        None => git::objs::TreeRefIter::from_bytes(&[], ctx.repo.object_hash()),
+    };
+    let parent = parent
+        .map(|id| ctx.repo.objects.find_tree_iter(id, &mut parent_buf))
+        .transpose()?;
+    let mut recorder = git::diff::tree::Recorder::default();
+    git::diff::tree::Changes::from(parent).needed_to_obtain(
+        tree,
+        &mut git::diff::tree::State::default(),
+        |oid, buf| ctx.repo.objects.find_tree_iter(oid, buf),
+        &mut recorder,
+    )?;
+
+    use git::diff::tree::recorder::Change;
+    Ok(recorder
+        .records
+        .into_iter()
+        .filter_map(|change| match change {
+            Change::Addition { entry_mode, path, .. }
+            | Change::Deletion { entry_mode, path, .. }
+            | Change::Modification { entry_mode, path, .. }
+                if !entry_mode.is_tree() =>
+            {
+                Some(path)
+            }
+            _ => None,
+        })
+        .collect())
 }
 
 enum Filter<'a> {
     None,
     Fast(&'a [u8]),
     Slow(Vec<&'a [u8]>),
+    /// Patterns relative to the repository root, matched against all paths changed by a commit.
+    Pathspecs(Vec<git_pathspec::Pattern>),
 }
 
 fn add_item_if_package_changed<'a>(
-    ctx: &Context,
+    repo: &git::Repository,
     segment: &mut Segment<'a>,
     filter: &Filter<'_>,
     item: &'a Item,
@@ -215,11 +390,11 @@ fn add_item_if_package_changed<'a>(
     match filter {
         Filter::None => history.push(item),
         Filter::Fast(comp) => {
-            let current = git::objs::TreeRefIter::from_bytes(&data_by_tree_id[&item.tree_id])
+            let current = git::objs::TreeRefIter::from_bytes(&data_by_tree_id[&item.tree_id], repo.object_hash())
                 .filter_map(Result::ok)
                 .find(|e| e.filename == comp);
             let parent = item.parent_tree_id.and_then(|parent| {
-                git::objs::TreeRefIter::from_bytes(&data_by_tree_id[&parent])
+                git::objs::TreeRefIter::from_bytes(&data_by_tree_id[&parent], repo.object_hash())
                     .filter_map(Result::ok)
                     .find(|e| e.filename == comp)
             });
@@ -234,12 +409,12 @@ fn add_item_if_package_changed<'a>(
             };
         }
         Filter::Slow(ref components) => {
-            let mut repo = ctx.repo.clone();
+            let mut repo = repo.clone();
             repo.object_cache_size(1024 * 1024);
-            let current = git::Tree::from_data(item.id, data_by_tree_id[&item.tree_id].to_owned(), &ctx.repo)
+            let current = git::Tree::from_data(item.id, data_by_tree_id[&item.tree_id].to_owned(), &repo)
                 .lookup_path(components.iter().copied())?;
             let parent = match item.parent_tree_id {
-                Some(tree_id) => git::Tree::from_data(tree_id, data_by_tree_id[&tree_id].to_owned(), &ctx.repo)
+                Some(tree_id) => git::Tree::from_data(tree_id, data_by_tree_id[&tree_id].to_owned(), &repo)
                     .lookup_path(components.iter().copied())?,
                 None => None,
             };
@@ -253,6 +428,30 @@ fn add_item_if_package_changed<'a>(
                 (None, Some(_)) | (None, None) => {}
             };
         }
+        Filter::Pathspecs(patterns) => {
+            let tree = git::objs::TreeRefIter::from_bytes(&data_by_tree_id[&item.tree_id], repo.object_hash());
+            let parent = item
+                .parent_tree_id
+                .map(|tree_id| git::objs::TreeRefIter::from_bytes(&data_by_tree_id[&tree_id], repo.object_hash()));
+            let mut recorder = git::diff::tree::Recorder::default();
+            git::diff::tree::Changes::from(parent).needed_to_obtain(
+                tree,
+                &mut git::diff::tree::State::default(),
+                |oid, buf| repo.objects.find_tree_iter(oid, buf),
+                &mut recorder,
+            )?;
+
+            use git::diff::tree::recorder::Change;
+            if recorder.records.iter().any(|change| match change {
+                Change::Addition { entry_mode, path, .. }
+                | Change::Deletion { entry_mode, path, .. }
+                | Change::Modification { entry_mode, path, .. } => {
+                    !entry_mode.is_tree() && git_pathspec::is_included(patterns, path.as_bstr())
+                }
+            }) {
+                history.push(item)
+            }
+        }
     };
     Ok(())
 }
@@ -1,10 +1,14 @@
-use std::{convert::TryInto, process::Command};
+use std::{
+    convert::TryInto,
+    io::Write,
+    process::{Command, Stdio},
+};
 
 use anyhow::{anyhow, bail};
 use cargo_metadata::{camino::Utf8Path, Package};
 use git_repository as git;
 use git_repository::{
-    bstr::{BStr, ByteSlice},
+    bstr::{BStr, BString, ByteSlice},
     object,
     refs::FullNameRef,
 };
@@ -91,6 +95,37 @@ pub fn assure_clean_working_tree() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Fail if the current branch has no upstream or if it has commits that aren't contained in its upstream.
+///
+/// Note that the upstream is known only as of the last fetch.
+pub fn assure_head_is_pushed() -> anyhow::Result<()> {
+    let upstream = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{upstream}"])
+        .output()?;
+    if !upstream.status.success() {
+        bail!("The current branch doesn't track an upstream branch, so it's unknown if HEAD was pushed. Push it with --set-upstream, or use --allow-unpushed to force it.")
+    }
+    let upstream = upstream.stdout.trim().to_str_lossy().into_owned();
+
+    let unpushed = Command::new("git")
+        .args(["rev-list", "--count"])
+        .arg(format!("{}..HEAD", upstream))
+        .output()?;
+    if !unpushed.status.success() {
+        bail!("Could not count the commits that aren't yet pushed to '{}'", upstream);
+    }
+    let num_unpushed: usize = unpushed.stdout.trim().to_str()?.parse()?;
+    if num_unpushed != 0 {
+        bail!(
+            "Found {} commit{} not pushed to '{}'. Please push beforehand as otherwise the release would be based on unpublished history, or use --allow-unpushed to force it.",
+            num_unpushed,
+            if num_unpushed == 1 { "" } else { "s" },
+            upstream
+        )
+    }
+    Ok(())
+}
+
 // TODO: actually derive this from the repository by doing a lot of git-config work, better to add that to git-repository
 pub fn head_remote_symbol() -> &'static str {
     "origin"
@@ -117,6 +152,53 @@ pub fn author() -> anyhow::Result<git_repository::actor::Signature> {
     .to_owned())
 }
 
+/// Sign `payload` like `git` would, using the program configured for `gpg.format` along with `user.signingKey`, and
+/// return the armored signature. Without a signing key, `signer` selects the key for OpenPGP and X.509 signatures.
+///
+/// Meant to be passed to the `sign()` methods of objects, which provide the payload and store the signature.
+pub fn sign(repo: &git::Repository, payload: &[u8], signer: &git::actor::Signature) -> anyhow::Result<BString> {
+    let config = repo.config_snapshot();
+    let format = config
+        .string("gpg.format")
+        .map(|format| format.to_string())
+        .unwrap_or_else(|| "openpgp".into());
+    let (program, default_program) = match format.as_str() {
+        "openpgp" => (
+            config
+                .string("gpg.openpgp.program")
+                .or_else(|| config.string("gpg.program")),
+            "gpg",
+        ),
+        "x509" => (config.string("gpg.x509.program"), "gpgsm"),
+        "ssh" => (config.string("gpg.ssh.program"), "ssh-keygen"),
+        _ => bail!("Signature format {:?} in 'gpg.format' is unsupported", format),
+    };
+    let program = program.map_or_else(|| default_program.into(), |program| program.to_string());
+    let key = config.string("user.signingKey").map(|key| key.to_string());
+
+    let mut cmd = Command::new(&program);
+    if format == "ssh" {
+        let key = key.ok_or_else(|| anyhow!("'user.signingKey' must be set to the path of a key to sign with SSH"))?;
+        cmd.args(["-Y", "sign", "-n", "git", "-f"]).arg(key);
+    } else {
+        let key = key.unwrap_or_else(|| format!("{} <{}>", signer.name, signer.email));
+        cmd.args(["--status-fd=2", "-bsau"]).arg(key);
+    }
+    cmd.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    log::trace!("Will run {:?}", cmd);
+    let mut child = cmd.spawn()?;
+    child.stdin.take().expect("configured").write_all(payload)?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() || output.stdout.is_empty() {
+        bail!(
+            "Failed to sign with '{}': {}",
+            program,
+            output.stderr.to_str_lossy().trim()
+        );
+    }
+    Ok(output.stdout.into())
+}
+
 pub fn strip_tag_path(name: &FullNameRef) -> &BStr {
     try_strip_tag_path(name).expect("prefix iteration works")
 }
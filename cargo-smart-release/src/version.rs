@@ -1,15 +1,19 @@
 use cargo_metadata::Package;
-use semver::{Prerelease, Version};
+use semver::{BuildMetadata, Prerelease, Version};
 
 use crate::Context;
 
-#[derive(Copy, Clone)]
+pub(crate) mod hints;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum BumpSpec {
     Auto,
     Keep,
     Patch,
     Minor,
     Major,
+    /// Bump to the given pre-release, like `1.0.0` to `1.0.1-rc.1` or `1.0.1-rc.1` to `1.0.1-rc.2`.
+    PreRelease(PreRelease),
 }
 
 impl std::fmt::Display for BumpSpec {
@@ -20,6 +24,58 @@ impl std::fmt::Display for BumpSpec {
             BumpSpec::Patch => "patch",
             BumpSpec::Minor => "minor",
             BumpSpec::Major => "major",
+            BumpSpec::PreRelease(pre) => return pre.fmt(f),
+        })
+    }
+}
+
+/// A pre-release like `rc` or `alpha.1` to bump to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreRelease {
+    /// One of `alpha`, `beta` or `rc`.
+    pub label: String,
+    /// The number of the pre-release, or `None` to use the next one.
+    pub number: Option<u64>,
+}
+
+impl PreRelease {
+    const LABELS: &'static [&'static str] = &["alpha", "beta", "rc"];
+}
+
+impl std::fmt::Display for PreRelease {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.number {
+            Some(number) => write!(f, "{}.{}", self.label, number),
+            None => f.write_str(&self.label),
+        }
+    }
+}
+
+impl std::str::FromStr for PreRelease {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (label, number) = match s.split_once('.') {
+            Some((label, number)) => (
+                label,
+                Some(
+                    number
+                        .parse()
+                        .map_err(|_| anyhow::anyhow!("Pre-release number in {:?} must be a positive integer", s))?,
+                ),
+            ),
+            None => (s, None),
+        };
+        if !Self::LABELS.contains(&label) {
+            anyhow::bail!(
+                "Unknown pre-release {:?}, expected one of {}, optionally followed by '.<number>'",
+                label,
+                Self::LABELS.join(", ")
+            );
+        }
+        Ok(PreRelease {
+            label: label.to_owned(),
+            number,
         })
     }
 }
@@ -27,38 +83,84 @@ impl std::fmt::Display for BumpSpec {
 #[allow(clippy::ptr_arg)]
 pub(crate) fn select_publishee_bump_spec(name: &String, ctx: &Context) -> BumpSpec {
     if ctx.crate_names.contains(name) {
-        ctx.bump
+        ctx.bump.clone()
     } else {
-        ctx.bump_dependencies
+        ctx.bump_dependencies.clone()
     }
 }
 
 /// Returns true if this would be a breaking change for `v`.
+///
+/// If `v` is a pre-release of the level to bump to, like `2.0.0-rc.1` for `Major`, it is released as is instead.
 fn bump_major_minor_patch(v: &mut semver::Version, bump_spec: BumpSpec) -> bool {
     use BumpSpec::*;
+    let is_pre_release_of_level = !v.pre.is_empty()
+        && match bump_spec {
+            Major => v.minor == 0 && v.patch == 0,
+            Minor => v.patch == 0,
+            _ => true,
+        };
+    v.build = BuildMetadata::EMPTY;
     match bump_spec {
         Major => {
-            v.major += 1;
-            v.minor = 0;
-            v.patch = 0;
+            if !is_pre_release_of_level {
+                v.major += 1;
+                v.minor = 0;
+                v.patch = 0;
+            }
             v.pre = Prerelease::EMPTY;
             true
         }
         Minor => {
-            v.minor += 1;
-            v.patch = 0;
+            if !is_pre_release_of_level {
+                v.minor += 1;
+                v.patch = 0;
+            }
             v.pre = Prerelease::EMPTY;
             is_pre_release(v)
         }
         Patch => {
-            v.patch += 1;
+            if !is_pre_release_of_level {
+                v.patch += 1;
+            }
             v.pre = Prerelease::EMPTY;
             false
         }
-        Keep | Auto => unreachable!("BUG: auto mode or keep are unsupported"),
+        Keep | Auto | PreRelease(_) => unreachable!("BUG: auto mode, keep or pre-releases are unsupported"),
     }
 }
 
+/// Bump `v` to the pre-release `spec`, continuing the numbering of an existing pre-release with the same label.
+/// Release versions are bumped to a pre-release of the next patch.
+fn bump_pre_release(v: &mut semver::Version, spec: &PreRelease) -> anyhow::Result<()> {
+    let previous = v.clone();
+    let number = if v.pre.is_empty() {
+        v.patch += 1;
+        spec.number.unwrap_or(1)
+    } else {
+        let (label, number) = match v.pre.as_str().rsplit_once('.') {
+            Some((label, number)) => (label, number.parse::<u64>().ok()),
+            None => (v.pre.as_str(), None),
+        };
+        match spec.number {
+            Some(number) => number,
+            None if label == spec.label => number.map_or(1, |n| n + 1),
+            None => 1,
+        }
+    };
+    v.pre = Prerelease::new(&format!("{}.{}", spec.label, number))?;
+    v.build = BuildMetadata::EMPTY;
+    if *v <= previous {
+        anyhow::bail!(
+            "Cannot bump {} to pre-release '{}' as {} would not be a newer version",
+            previous,
+            spec,
+            v
+        );
+    }
+    Ok(())
+}
+
 #[derive(Clone, Debug)]
 pub struct Bump {
     pub next_release: semver::Version,
@@ -86,12 +188,17 @@ pub(crate) fn bump_package_with_spec(
     use BumpSpec::*;
     let package_version_must_be_breaking = match bump_spec {
         Major | Minor | Patch => bump_major_minor_patch(&mut v, bump_spec),
+        PreRelease(spec) => {
+            bump_pre_release(&mut v, &spec)?;
+            rhs_is_breaking_bump_for_lhs(&package.version, &v)
+        }
         Keep => false,
         Auto => {
+            let history = ctx.history.as_ref().expect("BUG: assure history is set here");
             let segments = crate::git::history::crate_ref_segments(
                 package,
                 ctx,
-                ctx.history.as_ref().expect("BUG: assure history is set here"),
+                history,
                 crate::git::history::SegmentScope::Unreleased,
             )?;
             assert_eq!(
@@ -100,32 +207,51 @@ pub(crate) fn bump_package_with_spec(
                 "there should be exactly one section, the 'unreleased' one"
             );
             let unreleased = &segments[0];
-            if unreleased.history.is_empty() {
-                false
-            } else if unreleased.history.iter().any(|item| item.message.breaking) {
-                let is_breaking = if is_pre_release(&v) {
-                    bump_major_minor_patch(&mut v, Minor)
-                } else {
-                    bump_major_minor_patch(&mut v, Major)
-                };
-                assert!(is_breaking, "BUG: breaking changes are…breaking :D");
-                is_breaking
-            } else if unreleased
-                .history
-                .iter()
-                .any(|item| item.message.kind.map(|kind| kind == "feat").unwrap_or(false))
-            {
-                let is_breaking = if is_pre_release(&v) {
-                    bump_major_minor_patch(&mut v, Patch)
-                } else {
-                    bump_major_minor_patch(&mut v, Minor)
-                };
-                assert!(!is_breaking, "BUG: new features are never breaking");
-                is_breaking
-            } else {
-                let is_breaking = bump_major_minor_patch(&mut v, Patch);
-                assert!(!is_breaking, "BUG: patch releases are never breaking");
-                false
+            let hints = hints::BumpHints::from_metadata(package, &ctx.meta.workspace_metadata)?;
+            let mut level = hints::Level::None;
+            for item in &unreleased.history {
+                if !hints.no_bump_paths.is_empty()
+                    && hints.only_no_bump_paths(&crate::git::history::changed_paths_in_package(
+                        package,
+                        ctx,
+                        item,
+                        &history.data_by_tree_id,
+                    )?)
+                {
+                    log::debug!(
+                        "{}: commit {} changes no-bump paths only and won't cause a version bump",
+                        package.name,
+                        item.id
+                    );
+                    continue;
+                }
+                level = level.max(hints.level_of(item));
+            }
+            match level {
+                hints::Level::None => false,
+                hints::Level::Major => {
+                    let is_breaking = if is_pre_release(&v) {
+                        bump_major_minor_patch(&mut v, Minor)
+                    } else {
+                        bump_major_minor_patch(&mut v, Major)
+                    };
+                    assert!(is_breaking, "BUG: breaking changes are…breaking :D");
+                    is_breaking
+                }
+                hints::Level::Minor => {
+                    let is_breaking = if is_pre_release(&v) {
+                        bump_major_minor_patch(&mut v, Patch)
+                    } else {
+                        bump_major_minor_patch(&mut v, Minor)
+                    };
+                    assert!(!is_breaking, "BUG: new features are never breaking");
+                    is_breaking
+                }
+                hints::Level::Patch => {
+                    let is_breaking = bump_major_minor_patch(&mut v, Patch);
+                    assert!(!is_breaking, "BUG: patch releases are never breaking");
+                    false
+                }
             }
         }
     };
@@ -180,3 +306,68 @@ pub(crate) fn is_pre_release(semver: &Version) -> bool {
 pub(crate) fn rhs_is_breaking_bump_for_lhs(lhs: &Version, rhs: &Version) -> bool {
     rhs.major > lhs.major || rhs.minor > lhs.minor
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{bump_major_minor_patch, bump_pre_release, BumpSpec, PreRelease};
+
+    fn pre_release(spec: &str, version: &str) -> anyhow::Result<String> {
+        let mut v = semver::Version::parse(version)?;
+        bump_pre_release(&mut v, &spec.parse()?)?;
+        Ok(v.to_string())
+    }
+
+    fn bump(spec: BumpSpec, version: &str) -> String {
+        let mut v = semver::Version::parse(version).unwrap();
+        bump_major_minor_patch(&mut v, spec);
+        v.to_string()
+    }
+
+    #[test]
+    fn pre_release_specs_are_parsed_and_displayed() {
+        let spec: PreRelease = "alpha.1".parse().unwrap();
+        assert_eq!(
+            spec,
+            PreRelease {
+                label: "alpha".into(),
+                number: Some(1)
+            }
+        );
+        assert_eq!(spec.to_string(), "alpha.1");
+        assert_eq!("rc".parse::<PreRelease>().unwrap().to_string(), "rc");
+        assert!("gamma".parse::<PreRelease>().is_err());
+        assert!("rc.x".parse::<PreRelease>().is_err());
+    }
+
+    #[test]
+    fn releases_become_pre_releases_of_the_next_patch() -> anyhow::Result<()> {
+        assert_eq!(pre_release("rc", "1.0.0")?, "1.0.1-rc.1");
+        assert_eq!(pre_release("alpha.3", "0.5.2+build.1")?, "0.5.3-alpha.3");
+        Ok(())
+    }
+
+    #[test]
+    fn pre_releases_continue_their_numbering_or_switch_labels() -> anyhow::Result<()> {
+        assert_eq!(pre_release("rc", "1.0.0-rc.1")?, "1.0.0-rc.2");
+        assert_eq!(pre_release("rc", "1.0.0-rc")?, "1.0.0-rc.1");
+        assert_eq!(pre_release("rc.5", "1.0.0-rc.1")?, "1.0.0-rc.5");
+        assert_eq!(pre_release("beta", "1.0.0-alpha.4")?, "1.0.0-beta.1");
+        Ok(())
+    }
+
+    #[test]
+    fn pre_releases_never_go_backwards() {
+        assert!(pre_release("alpha", "1.0.0-beta.1").is_err());
+        assert!(pre_release("rc.1", "1.0.0-rc.2").is_err());
+    }
+
+    #[test]
+    fn pre_releases_are_released_if_they_are_of_the_bumped_level() {
+        assert_eq!(bump(BumpSpec::Patch, "1.0.1-rc.2"), "1.0.1");
+        assert_eq!(bump(BumpSpec::Minor, "1.1.0-beta.1"), "1.1.0");
+        assert_eq!(bump(BumpSpec::Minor, "1.1.1-beta.1"), "1.2.0");
+        assert_eq!(bump(BumpSpec::Major, "2.0.0-rc.1+build.3"), "2.0.0");
+        assert_eq!(bump(BumpSpec::Major, "2.1.0-rc.1"), "3.0.0");
+        assert_eq!(bump(BumpSpec::Patch, "1.0.1"), "1.0.2");
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context};
+use cargo_metadata::Package;
+use git_repository as git;
+
+use crate::commit::history::Item;
+
+/// The amount of change a commit causes, ordered from no change to a breaking change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    None,
+    Patch,
+    Minor,
+    Major,
+}
+
+impl std::str::FromStr for Level {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "none" => Level::None,
+            "patch" => Level::Patch,
+            "minor" => Level::Minor,
+            "major" => Level::Major,
+            _ => bail!(
+                "Invalid bump level '{}', expected one of 'none', 'patch', 'minor' or 'major'",
+                s
+            ),
+        })
+    }
+}
+
+/// Configuration to make bump inference more accurate, read from `[package.metadata.smart-release]` and falling back
+/// to `[workspace.metadata.smart-release]` for each key that isn't set.
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct BumpHints {
+    /// Paths relative to the crate root, like `docs` or `tests/fixtures`. Commits changing only files within these never
+    /// cause a version bump.
+    pub no_bump_paths: Vec<String>,
+    /// Conventional commit scopes mapped to the level they bump, overriding the level inferred from the commit type.
+    ///
+    /// Breaking changes always cause a major bump, no matter their scope.
+    pub scope_bumps: BTreeMap<String, Level>,
+}
+
+impl BumpHints {
+    pub fn from_metadata(package: &Package, workspace_metadata: &serde_json::Value) -> anyhow::Result<Self> {
+        let mut hints = Self::default();
+        for (source, metadata) in [("workspace", workspace_metadata), ("package", &package.metadata)]
+            .iter()
+            .filter_map(|(source, metadata)| metadata.get("smart-release").map(|m| (*source, m)))
+        {
+            hints.apply(metadata).with_context(|| {
+                format!(
+                    "Invalid [{}.metadata.smart-release] in {}",
+                    source, package.manifest_path
+                )
+            })?;
+        }
+        Ok(hints)
+    }
+
+    fn apply(&mut self, metadata: &serde_json::Value) -> anyhow::Result<()> {
+        if let Some(paths) = metadata.get("no-bump-paths") {
+            self.no_bump_paths = paths
+                .as_array()
+                .context("'no-bump-paths' must be an array of paths")?
+                .iter()
+                .map(|path| {
+                    path.as_str()
+                        .map(|path| path.trim_end_matches('/').to_owned())
+                        .context("'no-bump-paths' must only contain strings")
+                })
+                .collect::<Result<_, _>>()?;
+        }
+        if let Some(scopes) = metadata.get("scope-bumps") {
+            self.scope_bumps = scopes
+                .as_object()
+                .context("'scope-bumps' must be a table of scopes and levels")?
+                .iter()
+                .map(|(scope, level)| {
+                    let level = level
+                        .as_str()
+                        .with_context(|| format!("The level of scope '{}' must be a string", scope))?
+                        .parse()?;
+                    Ok((scope.to_owned(), level))
+                })
+                .collect::<anyhow::Result<_>>()?;
+        }
+        Ok(())
+    }
+
+    /// Return the level by which the commit `item` bumps the version.
+    pub fn level_of(&self, item: &Item) -> Level {
+        if item.message.breaking {
+            return Level::Major;
+        }
+        match item
+            .message
+            .scopes
+            .iter()
+            .filter_map(|scope| self.scope_bumps.get(scope))
+            .max()
+        {
+            Some(level) => *level,
+            None if item.message.kind == Some("feat") => Level::Minor,
+            None => Level::Patch,
+        }
+    }
+
+    /// Return true if all `changed_paths`, relative to the crate root, are within our `no_bump_paths`.
+    pub fn only_no_bump_paths(&self, changed_paths: &[git::bstr::BString]) -> bool {
+        !changed_paths.is_empty()
+            && changed_paths.iter().all(|path| {
+                self.no_bump_paths.iter().any(|prefix| {
+                    matches!(path.strip_prefix(prefix.as_bytes()), Some(rest) if rest.is_empty() || rest.starts_with(b"/"))
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use git_repository as git;
+
+    use super::{BumpHints, Level};
+    use crate::commit::{history::Item, Message};
+
+    fn item(message: &str) -> Item {
+        Item {
+            id: git::hash::ObjectId::null(git::hash::Kind::Sha1),
+            message: Message::from(message),
+            commit_time: Default::default(),
+            tree_id: git::hash::ObjectId::empty_tree(git::hash::Kind::Sha1),
+            parent_tree_id: None,
+        }
+    }
+
+    fn hints(metadata: serde_json::Value) -> BumpHints {
+        let mut hints = BumpHints::default();
+        hints.apply(&metadata).expect("valid");
+        hints
+    }
+
+    #[test]
+    fn scopes_override_the_level_of_the_kind_unless_breaking() {
+        let hints = hints(serde_json::json!({"scope-bumps": {"docs": "none", "api": "minor"}}));
+        assert_eq!(hints.level_of(&item("feat(docs): a new guide")), Level::None);
+        assert_eq!(hints.level_of(&item("fix(api): add a method")), Level::Minor);
+        assert_eq!(hints.level_of(&item("feat(other): thing")), Level::Minor);
+        assert_eq!(hints.level_of(&item("fix: thing")), Level::Patch);
+        assert_eq!(hints.level_of(&item("not conventional")), Level::Patch);
+        assert_eq!(hints.level_of(&item("fix(docs)!: breaking")), Level::Major);
+    }
+
+    #[test]
+    fn no_bump_paths_match_directories_and_files_as_a_whole() {
+        let hints = hints(serde_json::json!({"no-bump-paths": ["docs/", "tests", "README.md"]}));
+        assert_eq!(hints.no_bump_paths, ["docs", "tests", "README.md"]);
+        assert!(hints.only_no_bump_paths(&["docs/guide.md".into(), "tests/a/b.rs".into(), "README.md".into()]));
+        assert!(!hints.only_no_bump_paths(&["docs/guide.md".into(), "src/lib.rs".into()]));
+        assert!(!hints.only_no_bump_paths(&["testsuite/a.rs".into()]));
+        assert!(
+            !hints.only_no_bump_paths(&[]),
+            "commits without changes are decided elsewhere"
+        );
+    }
+
+    #[test]
+    fn invalid_configuration_is_an_error() {
+        let mut hints = BumpHints::default();
+        assert!(hints.apply(&serde_json::json!({"no-bump-paths": "docs"})).is_err());
+        assert!(hints
+            .apply(&serde_json::json!({"scope-bumps": {"docs": "tiny"}}))
+            .is_err());
+    }
+}
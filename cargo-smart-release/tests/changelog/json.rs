@@ -0,0 +1,130 @@
+use cargo_smart_release::{
+    changelog,
+    changelog::{section, section::segment::conventional, Section},
+    ChangeLog,
+};
+use git_testtools::hex_to_id;
+use serde_json::json;
+
+use crate::Result;
+
+#[test]
+fn releases_and_segments_are_represented_by_their_data() -> Result {
+    let log = ChangeLog {
+        sections: vec![
+            Section::Verbatim {
+                text: "# Changelog\n\n".into(),
+                generated: false,
+            },
+            Section::Release {
+                heading_level: 2,
+                removed_messages: vec![],
+                date: None,
+                name: changelog::Version::Unreleased,
+                version_prefix: Section::DEFAULT_PREFIX.into(),
+                segments: vec![section::Segment::User {
+                    markdown: "* hello world\n".into(),
+                }],
+                unknown: String::new(),
+            },
+            Section::Release {
+                heading_level: 2,
+                removed_messages: vec![hex_to_id("0000000000000000000000000000000000000001")],
+                date: Some(time::OffsetDateTime::from_unix_timestamp(86400)?),
+                name: changelog::Version::Semantic("1.0.2-beta.2".parse()?),
+                version_prefix: Section::DEFAULT_PREFIX.into(),
+                segments: vec![
+                    section::Segment::Conventional(section::segment::Conventional {
+                        kind: "feat".into(),
+                        is_breaking: true,
+                        scope: None,
+                        removed: vec![],
+                        messages: vec![
+                            conventional::Message::User {
+                                markdown: " - by hand".into(),
+                            },
+                            conventional::Message::Generated {
+                                id: hex_to_id("0000000000000000000000000000000000000002"),
+                                title: "generated".into(),
+                                body: None,
+                            },
+                        ],
+                    }),
+                    section::Segment::Clippy(section::Data::Generated(section::segment::ThanksClippy { count: 42 })),
+                    section::Segment::Statistics(section::Data::Generated(section::segment::CommitStatistics {
+                        count: 100,
+                        duration: time::Duration::days(32).into(),
+                        conventional_count: 20,
+                        time_passed_since_last_release: None,
+                        unique_issues: vec![
+                            section::segment::details::Category::Issue("1".into()),
+                            section::segment::details::Category::Uncategorized,
+                        ],
+                    })),
+                    section::Segment::Details(section::Data::Parsed),
+                ],
+                unknown: "hello\n".into(),
+            },
+        ],
+    };
+
+    assert_eq!(
+        log.to_json(),
+        json!({
+            "sections": [
+                { "type": "verbatim", "text": "# Changelog\n\n", "generated": false },
+                {
+                    "type": "release",
+                    "version": null,
+                    "version_prefix": "v",
+                    "date": null,
+                    "timestamp": null,
+                    "removed_messages": [],
+                    "segments": [{ "type": "user", "markdown": "* hello world\n" }],
+                    "unknown": null
+                },
+                {
+                    "type": "release",
+                    "version": "1.0.2-beta.2",
+                    "version_prefix": "v",
+                    "date": "1970-01-02",
+                    "timestamp": 86400,
+                    "removed_messages": ["0000000000000000000000000000000000000001"],
+                    "segments": [
+                        {
+                            "type": "conventional",
+                            "kind": "feat",
+                            "headline": "New Features",
+                            "is_breaking": true,
+                            "scope": null,
+                            "removed": [],
+                            "messages": [
+                                { "type": "user", "markdown": " - by hand" },
+                                {
+                                    "type": "generated",
+                                    "id": "0000000000000000000000000000000000000002",
+                                    "title": "generated",
+                                    "body": null
+                                }
+                            ]
+                        },
+                        { "type": "clippy", "data": { "count": 42 } },
+                        {
+                            "type": "statistics",
+                            "data": {
+                                "count": 100,
+                                "conventional_count": 20,
+                                "duration_in_days": 32,
+                                "days_passed_since_last_release": null,
+                                "unique_issues": ["1", null]
+                            }
+                        },
+                        { "type": "details", "data": null }
+                    ],
+                    "unknown": "hello\n"
+                }
+            ]
+        })
+    );
+    Ok(())
+}
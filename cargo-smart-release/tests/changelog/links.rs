@@ -0,0 +1,117 @@
+use std::{collections::BTreeMap, convert::TryFrom};
+
+use cargo_smart_release::changelog::{
+    section,
+    section::segment::details::{Category, Message},
+    write::{Components, Forge, Linkables, RepositoryUrl},
+    Template,
+};
+use git_testtools::{bstr::ByteSlice, hex_to_id};
+
+use crate::Result;
+
+fn details_with_links(repository_url: RepositoryUrl) -> String {
+    let mut commits_by_category = BTreeMap::default();
+    commits_by_category.insert(
+        Category::Issue("42".into()),
+        vec![Message {
+            title: "the title".into(),
+            id: hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+        }],
+    );
+    let segment = section::Segment::Details(section::Data::Generated(section::segment::Details {
+        commits_by_category,
+    }));
+    let mut md = String::new();
+    segment
+        .write_to(
+            3,
+            &Linkables::AsLinks { repository_url },
+            Components::empty(),
+            &Template::default(),
+            &mut md,
+        )
+        .expect("writing to a string never fails");
+    md
+}
+
+fn url(url: &str) -> RepositoryUrl {
+    git_repository::Url::try_from(url.as_bytes().as_bstr())
+        .expect("valid url")
+        .into()
+}
+
+#[test]
+fn forges_are_detected_by_host_and_link_commits_and_issues_in_their_own_way() -> Result {
+    let github = url("git@github.com:user/repo.git");
+    assert_eq!(github.forge, Some(Forge::GitHub));
+    assert_eq!(
+        details_with_links(github),
+        "### Commit Details\n\n * **[#42](https://github.com/user/repo/issues/42)**\n    - the title ([`e69de29`](https://github.com/user/repo/commit/e69de29bb2d1d6434b8b29ae775ad8c2e48c5391))\n\n"
+    );
+
+    let gitlab = url("https://gitlab.com/group/sub/project.git");
+    assert_eq!(gitlab.forge, Some(Forge::GitLab));
+    assert_eq!(
+        details_with_links(gitlab),
+        "### Commit Details\n\n * **[#42](https://gitlab.com/group/sub/project/-/issues/42)**\n    - the title ([`e69de29`](https://gitlab.com/group/sub/project/-/commit/e69de29bb2d1d6434b8b29ae775ad8c2e48c5391))\n\n"
+    );
+
+    let gitea = url("ssh://git@codeberg.org/user/repo");
+    assert_eq!(gitea.forge, Some(Forge::Gitea));
+    assert_eq!(
+        details_with_links(gitea),
+        "### Commit Details\n\n * **[#42](https://codeberg.org/user/repo/issues/42)**\n    - the title ([`e69de29`](https://codeberg.org/user/repo/commit/e69de29bb2d1d6434b8b29ae775ad8c2e48c5391))\n\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn unknown_forges_are_not_linked_unless_configured() -> Result {
+    let mut unknown = url("https://git.example.com/user/repo");
+    assert_eq!(unknown.forge, None);
+    assert_eq!(
+        details_with_links(unknown.clone()),
+        "### Commit Details\n\n * **#42**\n    - the title (e69de29)\n\n",
+        "without knowing the forge we don't know how to form links"
+    );
+
+    unknown.forge = Some(Forge::Gitea);
+    unknown.issue_url = Some("https://tracker.example.com/browse/PROJ-{id}".into());
+    assert_eq!(
+        details_with_links(unknown),
+        "### Commit Details\n\n * **[#42](https://tracker.example.com/browse/PROJ-42)**\n    - the title ([`e69de29`](https://git.example.com/user/repo/commit/e69de29bb2d1d6434b8b29ae775ad8c2e48c5391))\n\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn links_are_read_from_the_template() -> Result {
+    let template: Template = r#"
+[changelog.links]
+forge = "GitLab"
+url = "https://git.example.com/group/project"
+issues = "https://tracker.example.com/issues/{id}"
+"#
+    .parse()?;
+    assert_eq!(template.links.forge, Some(Forge::GitLab));
+    assert_eq!(
+        template.links.url.as_deref(),
+        Some("https://git.example.com/group/project")
+    );
+
+    let err = "[changelog.links]\nforge = \"sourcehut\""
+        .parse::<Template>()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unknown forge 'sourcehut', expected one of 'github', 'gitlab' or 'gitea'"
+    );
+    assert!(
+        "[changelog.links]\nissues = \"https://tracker.example.com/issues\""
+            .parse::<Template>()
+            .is_err(),
+        "the issue URL needs a placeholder for the issue number"
+    );
+    Ok(())
+}
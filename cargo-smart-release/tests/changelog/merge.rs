@@ -179,8 +179,9 @@ fn segments() {
                 removed_messages: vec![],
                 segments: vec![
                     section::Segment::Conventional(section::segment::Conventional {
-                        kind: "feat",
+                        kind: "feat".into(),
                         is_breaking: false,
+                        scope: None,
                         removed: vec![removed_message_id],
                         messages: vec![
                             section::segment::conventional::Message::User {
@@ -248,8 +249,9 @@ fn segments() {
     }));
     let added_message_id = hex_to_id("0000000000000000000000000000000000000003");
     let feat_conventional = section::Segment::Conventional(section::segment::Conventional {
-        kind: "feat",
+        kind: "feat".into(),
         is_breaking: false,
+        scope: None,
         removed: vec![],
         messages: vec![
             section::segment::conventional::Message::Generated {
@@ -341,8 +343,9 @@ fn segments() {
                     removed_messages: vec![],
                     segments: vec![
                         section::Segment::Conventional(section::segment::Conventional {
-                            kind: "feat",
+                            kind: "feat".into(),
                             is_breaking: false,
+                            scope: None,
                             removed: vec![removed_message_id],
                             messages: vec![
                                 section::segment::conventional::Message::User {
@@ -409,6 +412,251 @@ fn segments() {
     )
 }
 
+mod strategy {
+    use cargo_smart_release::{
+        changelog,
+        changelog::{
+            merge::{Outcome, Strategy, UserSegment},
+            section, Section,
+        },
+        ChangeLog,
+    };
+
+    use super::date_m_d;
+
+    fn release(version: &str, date: time::OffsetDateTime, segments: Vec<section::Segment>) -> Section {
+        Section::Release {
+            name: changelog::Version::Semantic(version.parse().unwrap()),
+            date: date.into(),
+            heading_level: 3,
+            version_prefix: Section::DEFAULT_PREFIX.into(),
+            unknown: "".into(),
+            removed_messages: vec![],
+            segments,
+        }
+    }
+
+    fn user(markdown: &str) -> section::Segment {
+        section::Segment::User {
+            markdown: markdown.into(),
+        }
+    }
+
+    fn clippy(count: usize) -> section::Segment {
+        section::Segment::Clippy(section::Data::Generated(section::segment::ThanksClippy { count }))
+    }
+
+    fn user_segment(version: &str, markdown: &str) -> UserSegment {
+        UserSegment {
+            release: changelog::Version::Semantic(version.parse().unwrap()),
+            markdown: markdown.into(),
+        }
+    }
+
+    fn names(log: &ChangeLog) -> Vec<String> {
+        log.sections
+            .iter()
+            .filter_map(|s| match s {
+                Section::Release {
+                    name: changelog::Version::Semantic(v),
+                    ..
+                } => Some(v.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_from_names() {
+        assert_eq!("prefer-user".parse::<Strategy>().unwrap(), Strategy::PreferUser);
+        assert_eq!(
+            "prefer-generated".parse::<Strategy>().unwrap(),
+            Strategy::PreferGenerated
+        );
+        assert_eq!(
+            "interleave-by-date".parse::<Strategy>().unwrap(),
+            Strategy::InterleaveByDate
+        );
+        assert!("other".parse::<Strategy>().is_err());
+    }
+
+    #[test]
+    fn prefer_user_keeps_and_reports_user_segments() {
+        let parsed = ChangeLog {
+            sections: vec![release(
+                "1.0.0",
+                date_m_d(time::Month::January, 1),
+                vec![user("hand-written"), clippy(1)],
+            )],
+        };
+        let generated = ChangeLog {
+            sections: vec![release("1.0.0", date_m_d(time::Month::January, 1), vec![clippy(2)])],
+        };
+        let (merged, outcome) = parsed.merge_generated_with(generated, Strategy::PreferUser);
+        assert_eq!(
+            merged.sections,
+            vec![release(
+                "1.0.0",
+                date_m_d(time::Month::January, 1),
+                vec![user("hand-written"), clippy(2)]
+            )]
+        );
+        assert_eq!(
+            outcome,
+            Outcome {
+                preserved: vec![user_segment("1.0.0", "hand-written")],
+                discarded: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn prefer_generated_replaces_existing_releases_and_reports_discarded_user_segments() {
+        let parsed = ChangeLog {
+            sections: vec![
+                release(
+                    "1.0.0",
+                    date_m_d(time::Month::February, 1),
+                    vec![user("hand-written"), clippy(1)],
+                ),
+                release("0.9.0", date_m_d(time::Month::January, 1), vec![user("untouched")]),
+            ],
+        };
+        let generated = ChangeLog {
+            sections: vec![release("1.0.0", date_m_d(time::Month::February, 2), vec![clippy(2)])],
+        };
+        let (merged, outcome) = parsed.merge_generated_with(generated, Strategy::PreferGenerated);
+        assert_eq!(
+            merged.sections,
+            vec![
+                release("1.0.0", date_m_d(time::Month::February, 2), vec![clippy(2)]),
+                release("0.9.0", date_m_d(time::Month::January, 1), vec![user("untouched")]),
+            ]
+        );
+        assert_eq!(
+            outcome,
+            Outcome {
+                preserved: vec![user_segment("0.9.0", "untouched")],
+                discarded: vec![user_segment("1.0.0", "hand-written")],
+            },
+            "releases not present in the generated log are left alone"
+        );
+    }
+
+    #[test]
+    fn interleave_by_date_places_new_releases_by_date_instead_of_version() {
+        let parsed = || ChangeLog {
+            sections: vec![
+                release("2.0.0", date_m_d(time::Month::September, 1), vec![]),
+                release("1.0.0", date_m_d(time::Month::January, 1), vec![]),
+            ],
+        };
+        let backport = || ChangeLog {
+            sections: vec![release("1.1.0", date_m_d(time::Month::October, 1), vec![])],
+        };
+
+        let (merged, _) = parsed().merge_generated_with(backport(), Strategy::PreferUser);
+        assert_eq!(names(&merged), ["2.0.0", "1.1.0", "1.0.0"], "placement by version");
+
+        let (merged, _) = parsed().merge_generated_with(backport(), Strategy::InterleaveByDate);
+        assert_eq!(names(&merged), ["1.1.0", "2.0.0", "1.0.0"], "placement by date");
+
+        let mut undated = release("0.1.0", date_m_d(time::Month::December, 1), vec![]);
+        if let Section::Release { date, .. } = &mut undated {
+            *date = None;
+        }
+        let (merged, _) = parsed().merge_generated_with(
+            ChangeLog {
+                sections: vec![undated],
+            },
+            Strategy::InterleaveByDate,
+        );
+        assert_eq!(
+            names(&merged),
+            ["2.0.0", "1.0.0", "0.1.0"],
+            "without date we fall back to the version"
+        );
+    }
+
+    #[test]
+    fn pre_releases_are_placed_by_their_semantic_version() {
+        let parsed = ChangeLog {
+            sections: vec![
+                release("1.0.0-rc.2", date_m_d(time::Month::March, 1), vec![]),
+                release("0.9.0", date_m_d(time::Month::January, 1), vec![]),
+            ],
+        };
+        let generated = ChangeLog {
+            sections: vec![
+                release("1.0.0", date_m_d(time::Month::April, 1), vec![]),
+                release("1.0.0-rc.1+build.5", date_m_d(time::Month::February, 1), vec![]),
+            ],
+        };
+        let (merged, _) = parsed.merge_generated_with(generated, Strategy::PreferUser);
+        assert_eq!(
+            names(&merged),
+            ["1.0.0", "1.0.0-rc.2", "1.0.0-rc.1+build.5", "0.9.0"],
+            "releases come before their pre-releases, which are ordered by their number"
+        );
+    }
+}
+
+#[test]
+fn scoped_segments_are_merged_below_their_kind_ordered_by_scope() {
+    let conventional = |scope: Option<&str>, id: &str| {
+        section::Segment::Conventional(section::segment::Conventional {
+            kind: "feat".into(),
+            is_breaking: false,
+            scope: scope.map(Into::into),
+            removed: vec![],
+            messages: vec![section::segment::conventional::Message::Generated {
+                id: hex_to_id(id),
+                title: id.into(),
+                body: None,
+            }],
+        })
+    };
+    let release = |segments: Vec<section::Segment>| ChangeLog {
+        sections: vec![Section::Release {
+            date: None,
+            name: changelog::Version::Unreleased,
+            heading_level: 3,
+            version_prefix: Section::DEFAULT_PREFIX.into(),
+            removed_messages: vec![],
+            segments,
+            unknown: Default::default(),
+        }],
+    };
+    let (unscoped, cli, parser) = (
+        "0000000000000000000000000000000000000001",
+        "0000000000000000000000000000000000000002",
+        "0000000000000000000000000000000000000003",
+    );
+    let user = section::Segment::User {
+        markdown: "hello\n".into(),
+    };
+
+    let parsed = release(vec![
+        user.clone(),
+        conventional(None, unscoped),
+        conventional(Some("parser"), parser),
+    ]);
+    let generated = release(vec![
+        conventional(Some("cli"), cli),
+        conventional(Some("parser"), parser),
+    ]);
+    assert_eq!(
+        parsed.merge_generated(generated),
+        release(vec![
+            user,
+            conventional(None, unscoped),
+            conventional(Some("cli"), cli),
+            conventional(Some("parser"), parser)
+        ]),
+        "new scopes are inserted among the segments of their kind, and existing ones are merged by scope"
+    );
+}
+
 fn date_m_d(month: time::Month, day: u8) -> OffsetDateTime {
     time::Date::from_calendar_date(2021, month, day) // generated, correct date
         .unwrap()
@@ -2,4 +2,16 @@ mod parse;
 
 mod write_and_parse;
 
+mod json;
+
+mod template;
+
+mod links;
+
 mod merge;
+
+mod releases_between;
+
+mod project;
+
+mod workspace;
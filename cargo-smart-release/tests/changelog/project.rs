@@ -0,0 +1,78 @@
+use cargo_smart_release::{
+    changelog,
+    changelog::{init::State, project},
+    ChangeLog,
+};
+use git_repository as git;
+
+fn options() -> project::Options {
+    project::Options::new("release-*".parse().unwrap())
+}
+
+fn releases(log: &ChangeLog) -> Vec<String> {
+    log.sections
+        .iter()
+        .filter_map(|s| match s {
+            changelog::Section::Release { name, .. } => Some(match name {
+                changelog::Version::Unreleased => "Unreleased".into(),
+                changelog::Version::Semantic(v) => v.to_string(),
+            }),
+            changelog::Section::Verbatim { .. } => None,
+        })
+        .collect()
+}
+
+fn repo() -> git::Repository {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_project_with_tags.sh").unwrap();
+    git::open(dir).unwrap()
+}
+
+#[test]
+fn releases_are_found_by_tag_pattern() {
+    let log = ChangeLog::for_project(&repo(), &options())
+        .unwrap()
+        .expect("HEAD points to a commit");
+    assert_eq!(
+        releases(&log),
+        ["Unreleased", "1.1.0", "1.0.0"],
+        "v9.0.0 doesn't match the pattern and is part of the unreleased changes"
+    );
+}
+
+#[test]
+fn commits_can_be_limited_to_a_directory() {
+    let mut options = options();
+    options.dir = Some("sub".into());
+    let log = ChangeLog::for_project(&repo(), &options).unwrap().expect("born HEAD");
+    let mut markdown = String::new();
+    log.write_to(
+        &mut markdown,
+        &changelog::write::Linkables::AsText,
+        changelog::write::Components::all(),
+    )
+    .unwrap();
+    assert!(markdown.contains("add sub-directory"), "{}", markdown);
+    assert!(
+        !markdown.contains("add a file") && !markdown.contains("change the file"),
+        "only the last commit touched the directory: {}",
+        markdown
+    );
+}
+
+#[test]
+fn changelogs_are_created_and_left_unchanged_if_nothing_happened() {
+    let repo = repo();
+    let tmp = git_testtools::tempfile::tempdir().unwrap();
+    let path = tmp.path().join("CHANGELOG.md");
+
+    let (_log, state, _) = ChangeLog::write_for_project(&repo, &path, &options()).unwrap().unwrap();
+    assert!(matches!(state, State::Created));
+    let markdown = std::fs::read_to_string(&path).unwrap();
+    assert!(markdown.contains("## v1.1.0"), "{}", markdown);
+    assert!(markdown.contains("add a file"), "{}", markdown);
+    assert!(markdown.contains("change the file"), "{}", markdown);
+
+    let (_log, state, _) = ChangeLog::write_for_project(&repo, &path, &options()).unwrap().unwrap();
+    assert!(matches!(state, State::Unchanged));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), markdown);
+}
@@ -0,0 +1,64 @@
+use cargo_smart_release::{changelog, changelog::Section, ChangeLog};
+
+fn release(version: &str) -> Section {
+    Section::Release {
+        heading_level: 2,
+        version_prefix: Section::DEFAULT_PREFIX.into(),
+        removed_messages: vec![],
+        date: None,
+        name: changelog::Version::Semantic(version.parse().unwrap()),
+        segments: Vec::new(),
+        unknown: String::new(),
+    }
+}
+
+fn versions(log: &ChangeLog) -> Vec<String> {
+    log.sections
+        .iter()
+        .map(|s| match s {
+            Section::Release {
+                name: changelog::Version::Semantic(v),
+                ..
+            } => v.to_string(),
+            _ => unreachable!("only semantic releases are returned"),
+        })
+        .collect()
+}
+
+#[test]
+fn only_releases_after_from_up_to_and_including_to_are_kept() {
+    let log = ChangeLog {
+        sections: vec![
+            Section::Verbatim {
+                text: "preamble".into(),
+                generated: false,
+            },
+            Section::Release {
+                heading_level: 2,
+                version_prefix: "".into(),
+                removed_messages: vec![],
+                date: None,
+                name: changelog::Version::Unreleased,
+                segments: Vec::new(),
+                unknown: String::new(),
+            },
+            release("0.3.0"),
+            release("0.2.0"),
+            release("0.1.1"),
+            release("0.1.0"),
+        ],
+    };
+    assert_eq!(
+        versions(&log.releases_between(&"0.1.0".parse().unwrap(), &"0.2.0".parse().unwrap())),
+        vec!["0.2.0", "0.1.1"]
+    );
+    assert_eq!(
+        versions(&log.releases_between(&"0.0.1".parse().unwrap(), &"0.4.0".parse().unwrap())),
+        vec!["0.3.0", "0.2.0", "0.1.1", "0.1.0"],
+        "unreleased and verbatim sections are never part of it"
+    );
+    assert!(log
+        .releases_between(&"0.2.0".parse().unwrap(), &"0.1.0".parse().unwrap())
+        .sections
+        .is_empty());
+}
@@ -0,0 +1,299 @@
+use cargo_smart_release::{
+    changelog,
+    changelog::{section, section::segment::conventional, Section, Template},
+    ChangeLog,
+};
+use git_testtools::hex_to_id;
+
+use crate::Result;
+
+const CONFIG: &str = r#"
+[changelog]
+types = { optimize = "perf", sec = "security" }
+
+[[changelog.sections]]
+kind = "security"
+headline = "Security"
+
+[[changelog.sections]]
+kind = "perf"
+headline = "Faster"
+
+[[changelog.sections]]
+kind = "fix"
+"#;
+
+#[test]
+fn configured_sections_come_first_and_map_types() -> Result {
+    let template: Template = CONFIG.parse()?;
+    let kinds: Vec<_> = template.headlines.iter().map(|h| h.kind.as_str()).take(4).collect();
+    assert_eq!(
+        kinds,
+        ["security", "perf", "fix", "add"],
+        "the remaining built-in sections follow"
+    );
+
+    assert_eq!(template.headline("security"), Some("Security"));
+    assert_eq!(template.headline("perf"), Some("Faster"));
+    assert_eq!(
+        template.headline("fix"),
+        Some("Bug Fixes"),
+        "built-in headlines are kept"
+    );
+
+    assert_eq!(template.kind_of("optimize"), "perf");
+    assert_eq!(template.kind_of("SEC"), "security");
+    assert_eq!(template.kind_of("security"), "security");
+    assert_eq!(template.kind_of("added"), "feat", "built-in mappings are kept");
+    assert_eq!(template.kind_of("ci"), "other");
+
+    assert_eq!(template.kind_by_title("Faster (BREAKING)"), Some("perf"));
+    assert_eq!(
+        template.kind_by_title("Performance"),
+        None,
+        "the built-in headline was replaced"
+    );
+    assert_eq!(template.kind_by_title("Security notes"), None);
+    Ok(())
+}
+
+#[test]
+fn invalid_configuration_is_rejected() {
+    for config in [
+        "[[changelog.sections]]\nkind = \"security\"",
+        "[changelog]\ntypes = { sec = \"security\" }",
+        "[[changelog.sections]]\nheadline = \"Security\"",
+        "[changelog]\nsections = 1",
+        "[changelog]\ngroup-by-scope = \"yes\"",
+        "[changelog.scopes]\nparser = \"git-config\"",
+        "[changelog.details]\nmax-commits = -1",
+        "[changelog.details]\ncollapse-above = true",
+        "[[changelog.rewrites]]\npattern = \"(\"\nreplacement = \"\"",
+        "[[changelog.rewrites]]\npattern = \"^x\"",
+    ] {
+        assert!(config.parse::<Template>().is_err(), "{}", config);
+    }
+    assert_eq!("".parse::<Template>().expect("valid"), Template::default());
+}
+
+#[test]
+fn custom_sections_round_trip_with_their_template() -> Result {
+    let template: Template = CONFIG.parse()?;
+    let log = ChangeLog {
+        sections: vec![Section::Release {
+            heading_level: 2,
+            version_prefix: "".into(),
+            date: None,
+            name: changelog::Version::Unreleased,
+            removed_messages: vec![],
+            segments: vec![
+                section::Segment::Conventional(section::segment::Conventional {
+                    kind: "security".into(),
+                    is_breaking: false,
+                    scope: None,
+                    removed: vec![],
+                    messages: vec![conventional::Message::Generated {
+                        id: hex_to_id("0000000000000000000000000000000000000001"),
+                        title: "sanitize input".into(),
+                        body: None,
+                    }],
+                }),
+                section::Segment::Conventional(section::segment::Conventional {
+                    kind: "perf".into(),
+                    is_breaking: true,
+                    scope: None,
+                    removed: vec![],
+                    messages: vec![conventional::Message::Generated {
+                        id: hex_to_id("0000000000000000000000000000000000000002"),
+                        title: "cache everything".into(),
+                        body: None,
+                    }],
+                }),
+            ],
+            unknown: String::new(),
+        }],
+    };
+
+    let mut md = String::new();
+    log.write_to_with_template(
+        &mut md,
+        &changelog::write::Linkables::AsText,
+        changelog::write::Components::all(),
+        &template,
+    )?;
+    assert!(md.contains("### Security\n"), "{}", md);
+    assert!(md.contains("### Faster (BREAKING)\n"), "{}", md);
+    assert_eq!(ChangeLog::from_markdown_with_template(&md, &template), log);
+    assert_ne!(
+        ChangeLog::from_markdown(&md),
+        log,
+        "without the template, custom sections are user content"
+    );
+    Ok(())
+}
+
+#[test]
+fn scopes_can_be_grouped_and_mapped_to_crates() -> Result {
+    let template: Template =
+        "[changelog]\ngroup-by-scope = true\n[changelog.scopes]\nParser = [\"a\", \"b\"]".parse()?;
+    assert!(template.group_by_scope);
+    assert!(template.scope_includes_crate(Some("parser"), "a"));
+    assert!(
+        template.scope_includes_crate(Some("PARSER"), "b"),
+        "scopes are case-insensitive"
+    );
+    assert!(!template.scope_includes_crate(Some("parser"), "c"));
+    assert!(
+        template.scope_includes_crate(Some("cli"), "c"),
+        "unmapped scopes go into all changelogs"
+    );
+    assert!(template.scope_includes_crate(None, "c"));
+    assert!(
+        template.scopes_include_crate(&["parser".into(), "cli".into()], "c"),
+        "any scope that includes the crate is enough"
+    );
+    assert!(!template.scopes_include_crate(&["parser".into()], "c"));
+    assert!(template.scopes_include_crate(&[], "c"));
+    assert!(!Template::default().group_by_scope);
+    Ok(())
+}
+
+#[test]
+fn scoped_segments_are_nested_below_their_kind_and_round_trip() -> Result {
+    let conventional = |scope: Option<&str>, id: &str, title: &str| {
+        section::Segment::Conventional(section::segment::Conventional {
+            kind: "feat".into(),
+            is_breaking: false,
+            scope: scope.map(Into::into),
+            removed: vec![],
+            messages: vec![conventional::Message::Generated {
+                id: hex_to_id(id),
+                title: title.into(),
+                body: None,
+            }],
+        })
+    };
+    let log = ChangeLog {
+        sections: vec![Section::Release {
+            heading_level: 2,
+            version_prefix: "".into(),
+            date: None,
+            name: changelog::Version::Unreleased,
+            removed_messages: vec![],
+            segments: vec![
+                conventional(None, "0000000000000000000000000000000000000001", "unscoped"),
+                conventional(Some("cli"), "0000000000000000000000000000000000000002", "a flag"),
+                conventional(Some("parser"), "0000000000000000000000000000000000000003", "faster"),
+            ],
+            unknown: String::new(),
+        }],
+    };
+
+    let mut md = String::new();
+    log.write_to(
+        &mut md,
+        &changelog::write::Linkables::AsText,
+        changelog::write::Components::SECTION_TITLE,
+    )?;
+    assert_eq!(
+        md,
+        "## Unreleased\n\n### New Features\n\n - unscoped\n\n#### cli\n\n - a flag\n\n#### parser\n\n - faster\n\n"
+    );
+
+    let mut md = String::new();
+    log.write_to(
+        &mut md,
+        &changelog::write::Linkables::AsText,
+        changelog::write::Components::all(),
+    )?;
+    assert_eq!(ChangeLog::from_markdown(&md), log);
+
+    let mut only_scoped = log.clone();
+    if let Section::Release { segments, .. } = &mut only_scoped.sections[0] {
+        segments.remove(0);
+    }
+    let mut md = String::new();
+    only_scoped.write_to(
+        &mut md,
+        &changelog::write::Linkables::AsText,
+        changelog::write::Components::all(),
+    )?;
+    assert_eq!(md.matches("### New Features").count(), 1, "{}", md);
+    assert_eq!(
+        ChangeLog::from_markdown(&md),
+        only_scoped,
+        "the heading of the kind doesn't turn into a segment of its own"
+    );
+    Ok(())
+}
+
+#[test]
+fn details_can_be_capped_and_collapsed_only_when_large() -> Result {
+    let template: Template = "[changelog.details]\nmax-commits = 3\ncollapse-above = 2".parse()?;
+    assert_eq!(
+        template.details,
+        changelog::template::Details {
+            max_commits: Some(3),
+            collapse_above: Some(2),
+        }
+    );
+    assert_eq!(Template::default().details, Default::default());
+
+    let log_with_commits = |count: u8| {
+        let message = |n: u8| section::segment::details::Message {
+            title: format!("commit {}", n),
+            id: hex_to_id(&format!("00000000000000000000000000000000000000{:02x}", n)),
+        };
+        let mut commits_by_category = std::collections::BTreeMap::default();
+        commits_by_category.insert(
+            section::segment::details::Category::Issue("1".into()),
+            (0..count.min(2)).map(message).collect(),
+        );
+        commits_by_category.insert(
+            section::segment::details::Category::Uncategorized,
+            (count.min(2)..count).map(message).collect(),
+        );
+        ChangeLog {
+            sections: vec![Section::Release {
+                heading_level: 2,
+                version_prefix: "".into(),
+                date: None,
+                name: changelog::Version::Unreleased,
+                removed_messages: vec![],
+                segments: vec![section::Segment::Details(section::Data::Generated(
+                    section::segment::Details { commits_by_category },
+                ))],
+                unknown: String::new(),
+            }],
+        }
+    };
+    let write = |log: &ChangeLog| -> std::result::Result<String, std::fmt::Error> {
+        let mut md = String::new();
+        log.write_to_with_template(
+            &mut md,
+            &changelog::write::Linkables::AsText,
+            changelog::write::Components::all(),
+            &template,
+        )?;
+        Ok(md)
+    };
+
+    let md = write(&log_with_commits(5))?;
+    assert_eq!(
+        md,
+        "## Unreleased\n\n### Commit Details\n\n<csr-read-only-do-not-edit/>\n\n<details><summary>view details</summary>\n\n \
+         * **#1**\n    - commit 0 (0000000)\n    - commit 1 (0000000)\n \
+         * **Uncategorized**\n    - commit 2 (0000000)\n \
+         * *and 2 more commits*\n</details>\n\n"
+    );
+    assert_eq!(
+        ChangeLog::from_markdown(&md).sections,
+        ChangeLog::from_markdown(&write(&log_with_commits(3))?).sections,
+        "the details are read-only and aren't parsed back"
+    );
+
+    let md = write(&log_with_commits(2))?;
+    assert!(!md.contains("<details>"), "small details aren't collapsed:\n{}", md);
+    assert!(!md.contains("more commit"), "{}", md);
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use cargo_smart_release::changelog::{self, section, workspace, Section, Template};
+
+use crate::Result;
+
+fn release(version: &str, markdown: &str) -> Section {
+    Section::Release {
+        name: changelog::Version::Semantic(version.parse().unwrap()),
+        date: Some(time::OffsetDateTime::from_unix_timestamp(0).unwrap()),
+        heading_level: changelog::DEFAULT_HEADING_LEVEL,
+        version_prefix: Section::DEFAULT_PREFIX.into(),
+        unknown: String::new(),
+        removed_messages: vec![],
+        segments: vec![section::Segment::User {
+            markdown: markdown.into(),
+        }],
+    }
+}
+
+fn merge(existing: Option<&str>, entries: &[workspace::Entry<'_>], timestamp: i64) -> String {
+    workspace::merge(
+        existing,
+        entries,
+        time::OffsetDateTime::from_unix_timestamp(timestamp).unwrap(),
+        &changelog::write::Linkables::AsText,
+        &Template::default(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn releases_are_grouped_by_crate_and_link_to_their_changelogs() -> Result {
+    let (a_version, b_version) = ("1.0.0".parse()?, "0.2.0".parse()?);
+    let (a_section, b_section) = (release("1.0.0", "a notes\n"), release("0.2.0", "b notes\n"));
+    let entries = [
+        workspace::Entry {
+            name: "a",
+            version: &a_version,
+            changelog_path: "a/CHANGELOG.md".into(),
+            section: &a_section,
+        },
+        workspace::Entry {
+            name: "b",
+            version: &b_version,
+            changelog_path: "nested/b/CHANGELOG.md".into(),
+            section: &b_section,
+        },
+    ];
+
+    let first = merge(None, &entries, 0);
+    assert_eq!(
+        first,
+        format!(
+            "{}## a v1.0.0, b v0.2.0 (1970-01-01)\n\n### [a v1.0.0](a/CHANGELOG.md)\n\na notes\n\n### [b v0.2.0](nested/b/CHANGELOG.md)\n\nb notes\n\n",
+            workspace::HEADER
+        )
+    );
+    assert_eq!(
+        merge(Some(&first), &entries, 0),
+        first,
+        "repeating the same release replaces it"
+    );
+
+    let second = merge(Some(&first), &entries[1..], 86400);
+    assert_eq!(
+        second,
+        format!(
+            "{}## b v0.2.0 (1970-01-02)\n\n### [b v0.2.0](nested/b/CHANGELOG.md)\n\nb notes\n\n{}",
+            workspace::HEADER,
+            &first[workspace::HEADER.len()..]
+        ),
+        "newer releases are placed above older ones, leaving the preamble intact"
+    );
+    Ok(())
+}
@@ -22,8 +22,9 @@ fn conventional_write_empty_messages() -> Result {
             name: changelog::Version::Semantic("1.0.2-beta.2".parse()?),
             removed_messages: vec![second_message],
             segments: vec![section::Segment::Conventional(section::segment::Conventional {
-                kind: "feat",
+                kind: "feat".into(),
                 is_breaking: true,
+                scope: None,
                 removed: vec![first_message],
                 messages: vec![
                     conventional::Message::User {
@@ -184,3 +185,39 @@ fn all_section_types_round_trips_lossy() -> Result {
     }
     Ok(())
 }
+
+#[test]
+fn pre_release_and_build_metadata_round_trip() -> Result {
+    let log = ChangeLog {
+        sections: ["2.0.0-rc.1+build.5", "2.0.0-alpha.2", "1.0.0+20220101"]
+            .iter()
+            .map(|version| {
+                Ok(Section::Release {
+                    heading_level: 2,
+                    version_prefix: Section::DEFAULT_PREFIX.into(),
+                    date: Some(time::OffsetDateTime::from_unix_timestamp(0)?),
+                    name: changelog::Version::Semantic(version.parse()?),
+                    removed_messages: vec![],
+                    segments: vec![section::Segment::User {
+                        markdown: "- notes\n\n".into(),
+                    }],
+                    unknown: String::new(),
+                })
+            })
+            .collect::<std::result::Result<_, Box<dyn std::error::Error>>>()?,
+    };
+
+    let mut md = String::new();
+    log.write_to(
+        &mut md,
+        &changelog::write::Linkables::AsText,
+        changelog::write::Components::all(),
+    )?;
+    assert!(md.contains("## v2.0.0-rc.1+build.5 (1970-01-01)"), "{}", md);
+    assert_eq!(
+        ChangeLog::from_markdown(&md),
+        log,
+        "pre-releases and build metadata are parsed back"
+    );
+    Ok(())
+}
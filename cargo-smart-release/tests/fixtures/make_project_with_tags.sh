@@ -0,0 +1,24 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo "# a project" > README.md
+git add README.md
+git commit -q -m "initial commit"
+git tag release-1.0.0
+
+echo content > file
+git add file
+git commit -q -m "feat: add a file"
+git tag -a -m "the second release" release-1.1.0
+
+echo changed > file
+git commit -q -am "fix: change the file"
+
+mkdir sub
+echo content > sub/file
+git add sub
+git commit -q -m "feat(sub): add sub-directory"
+git tag v9.0.0
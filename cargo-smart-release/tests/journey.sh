@@ -12,7 +12,7 @@ snapshot="$root/snapshots"
 fixtures="$root/fixtures"
 
 SUCCESSFULLY=0
-# WITH_FAILURE=1
+WITH_FAILURE=1
 
 function set-static-git-environment() {
   set -a
@@ -51,6 +51,19 @@ title "changelog"
       it "succeeds" && {
         expect_run $SUCCESSFULLY "$exe" changelog a --write
       }
+      (with '--verify-only'
+        it "succeeds as all changelogs are up to date" && {
+          expect_run $SUCCESSFULLY "$exe" changelog a --verify-only
+        }
+        (when "the changelog of 'a' is outdated"
+          cp a/CHANGELOG.md a/CHANGELOG.md.orig
+          echo "outdated" >> a/CHANGELOG.md
+          it "fails" && {
+            expect_run $WITH_FAILURE "$exe" changelog a --verify-only
+          }
+          mv a/CHANGELOG.md.orig a/CHANGELOG.md
+        )
+      )
       (with ".git and target/ directories removed"
         rm -Rf .git/ target/
         it "managed to write a changelog" && {
@@ -164,7 +177,7 @@ title "smart-release"
     )
     (with '--execute but without side-effects'
       it "succeeds" && {
-        expect_run $SUCCESSFULLY "$exe" smart-release a -b keep -d keep --no-push --no-publish --execute --allow-dirty --no-changelog-preview
+        expect_run $SUCCESSFULLY "$exe" smart-release a -b keep -d keep --no-push --no-publish --execute --yes --allow-dirty --no-changelog-preview
       }
       (with ".git and target/ directories removed"
         rm -Rf .git/ target/
@@ -174,7 +187,7 @@ title "smart-release"
         (with 'unconditional version minor bumping'
           init-git-repo &>/dev/null
           it "succeeds" && {
-            expect_run $SUCCESSFULLY "$exe" smart-release -b minor a --no-push --no-publish --no-bump-on-demand --execute --allow-dirty --no-changelog-preview
+            expect_run $SUCCESSFULLY "$exe" smart-release -b minor a --no-push --no-publish --no-bump-on-demand --execute --yes --allow-dirty --no-changelog-preview
           }
           rm -Rf .git/
           it "managed additionally bumped b but not c as it's not pre-release" && {
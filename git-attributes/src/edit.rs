@@ -0,0 +1,136 @@
+use bstr::{BStr, BString, ByteSlice};
+use git_glob::pattern::{Case, Mode};
+
+/// The error returned by [`Ignore::add()`] and [`Ignore::remove()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("{pattern:?} is not a valid ignore pattern, it may be empty, a comment or span multiple lines")]
+    InvalidPattern { pattern: BString },
+    #[error("The pattern {pattern:?} does not match the path {path:?} it was supposed to match")]
+    NoMatch { pattern: BString, path: BString },
+}
+
+/// The content of an ignore file like `.gitignore` or `.git/info/exclude` to add patterns to or remove patterns from,
+/// while keeping all other lines, like comments, empty lines and their line endings, exactly as they are.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Ignore {
+    buf: BString,
+}
+
+impl From<Vec<u8>> for Ignore {
+    fn from(buf: Vec<u8>) -> Self {
+        Ignore { buf: buf.into() }
+    }
+}
+
+impl From<BString> for Ignore {
+    fn from(buf: BString) -> Self {
+        Ignore { buf }
+    }
+}
+
+/// Access
+impl Ignore {
+    /// Return the possibly edited content of the ignore file.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Return the possibly edited content of the ignore file.
+    pub fn into_bytes(self) -> BString {
+        self.buf
+    }
+
+    /// Return `true` if `pattern` is in effect, that is, it is contained in the file and not overridden by a later negation
+    /// of the same pattern, or the other way around for negated patterns.
+    ///
+    /// Invalid patterns are never contained.
+    pub fn contains(&self, pattern: &BStr) -> bool {
+        parse(pattern).filter(|pattern| self.is_in_effect(pattern)).is_some()
+    }
+}
+
+/// Mutation
+impl Ignore {
+    /// Append `pattern` as line to the end of the file, unless it's [contained][Ignore::contains()] already.
+    ///
+    /// Return `true` if the pattern was added, or `false` if it was a duplicate.
+    /// The line ending of the first line is used for the new line as well.
+    pub fn add(&mut self, pattern: &BStr) -> Result<bool, Error> {
+        let parsed = parse(pattern).ok_or_else(|| Error::InvalidPattern {
+            pattern: pattern.to_owned(),
+        })?;
+        if self.is_in_effect(&parsed) {
+            return Ok(false);
+        }
+        let newline: &[u8] = match self.buf.find_byte(b'\n') {
+            Some(pos) if pos > 0 && self.buf[pos - 1] == b'\r' => b"\r\n",
+            _ => b"\n",
+        };
+        if !self.buf.is_empty() && !self.buf.ends_with(b"\n") {
+            self.buf.extend_from_slice(newline);
+        }
+        self.buf.extend_from_slice(pattern);
+        self.buf.extend_from_slice(newline);
+        Ok(true)
+    }
+
+    /// Like [`add()`][Ignore::add()], but fail if `pattern` doesn't match `path`, which is relative to the directory containing
+    /// the ignore file, and is a directory if `is_dir` is `Some(true)`.
+    ///
+    /// That way it's assured that the pattern has the intended effect of matching `path`.
+    pub fn add_matching(&mut self, pattern: &BStr, path: &BStr, is_dir: Option<bool>) -> Result<bool, Error> {
+        let parsed = parse(pattern).ok_or_else(|| Error::InvalidPattern {
+            pattern: pattern.to_owned(),
+        })?;
+        let basename_start_pos = path.rfind_byte(b'/').map(|pos| pos + 1);
+        if !parsed.matches_repo_relative_path(path, basename_start_pos, is_dir, Case::Sensitive) {
+            return Err(Error::NoMatch {
+                pattern: pattern.to_owned(),
+                path: path.to_owned(),
+            });
+        }
+        self.add(pattern)
+    }
+
+    /// Remove all lines which are equivalent to `pattern`, and return the number of removed lines.
+    ///
+    /// Negations of `pattern` are left untouched.
+    pub fn remove(&mut self, pattern: &BStr) -> Result<usize, Error> {
+        let parsed = parse(pattern).ok_or_else(|| Error::InvalidPattern {
+            pattern: pattern.to_owned(),
+        })?;
+        let bom_len = unicode_bom::Bom::from(self.buf.as_slice()).len();
+        let mut out = BString::from(&self.buf[..bom_len]);
+        let mut removed = 0;
+        for line_with_terminator in self.buf[bom_len..].lines_with_terminator() {
+            let line = line_with_terminator.trim_end_with(|c| c == '\n' || c == '\r');
+            if line.first() != Some(&b'#') && git_glob::Pattern::from_bytes(line).as_ref() == Some(&parsed) {
+                removed += 1;
+            } else {
+                out.extend_from_slice(line_with_terminator);
+            }
+        }
+        self.buf = out;
+        Ok(removed)
+    }
+}
+
+impl Ignore {
+    fn is_in_effect(&self, pattern: &git_glob::Pattern) -> bool {
+        crate::parse::ignore(&self.buf)
+            .map(|(p, _line_number)| p)
+            .filter(|p| p.text == pattern.text && p.mode - Mode::NEGATIVE == pattern.mode - Mode::NEGATIVE)
+            .last()
+            .map(|p| p.mode)
+            == Some(pattern.mode)
+    }
+}
+
+fn parse(pattern: &BStr) -> Option<git_glob::Pattern> {
+    if pattern.first() == Some(&b'#') || pattern.contains(&b'\n') || pattern.contains(&b'\r') {
+        return None;
+    }
+    git_glob::Pattern::from_bytes(pattern)
+}
@@ -6,7 +6,7 @@ use std::{
 
 use bstr::{BStr, BString, ByteSlice, ByteVec};
 
-use crate::{Assignment, MatchGroup, PatternList, PatternMapping};
+use crate::{Assignment, MatchGroup, PatternList, PatternMapping, StateRef};
 
 fn into_owned_assignments<'a>(
     attrs: impl Iterator<Item = Result<crate::AssignmentRef<'a>, crate::name::Error>>,
@@ -50,10 +50,12 @@ impl Pattern for Ignore {
     }
 }
 
-/// A value of an attribute pattern, which is either a macro definition or
+/// A value of an attribute pattern, which is either a macro definition or a list of assignments.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 pub enum Value {
+    /// The attributes the macro of the pattern expands to.
     MacroAttributes(Vec<Assignment>),
+    /// The attributes to assign to the paths matching the pattern.
     Assignments(Vec<Assignment>),
 }
 
@@ -131,6 +133,27 @@ where
     }
 }
 
+impl MatchGroup<Attributes> {
+    /// Return the state of the attribute `name` for `relative_path` as assigned by the last matching pattern which mentions it,
+    /// with pattern lists added later taking precedence, or `None` if no pattern assigns it.
+    ///
+    /// See [`PatternList::attribute_matching_relative_path()`] for how macros are handled.
+    pub fn attribute_matching_relative_path<'a>(
+        &self,
+        relative_path: impl Into<&'a BStr>,
+        name: &str,
+        is_dir: Option<bool>,
+        case: git_glob::pattern::Case,
+    ) -> Option<StateRef<'_>> {
+        let relative_path = relative_path.into();
+        let basename_pos = relative_path.rfind(b"/").map(|p| p + 1);
+        self.patterns
+            .iter()
+            .rev()
+            .find_map(|pl| pl.attribute_matching_relative_path(relative_path, basename_pos, name, is_dir, case))
+    }
+}
+
 impl MatchGroup<Ignore> {
     /// Given `git_dir`, a `.git` repository, load ignore patterns from `info/exclude` and from `excludes_file` if it
     /// is provided.
@@ -330,6 +353,51 @@ where
     }
 }
 
+impl PatternList<Attributes> {
+    /// Return the state of the attribute `name` for `relative_path` as assigned by the last matching pattern which mentions it,
+    /// or `None` if no pattern assigns it. See [`pattern_matching_relative_path()`][Self::pattern_matching_relative_path()]
+    /// for the meaning of the other arguments.
+    ///
+    /// The built-in `binary` macro is expanded to `-diff -merge -text`, but macros defined in pattern files are not.
+    pub fn attribute_matching_relative_path(
+        &self,
+        relative_path: &BStr,
+        basename_pos: Option<usize>,
+        name: &str,
+        is_dir: Option<bool>,
+        case: git_glob::pattern::Case,
+    ) -> Option<StateRef<'_>> {
+        let (relative_path, basename_start_pos) =
+            self.strip_base_handle_recompute_basename_pos(relative_path, basename_pos)?;
+        let implied_by_binary = matches!(name, "diff" | "merge" | "text");
+        self.patterns
+            .iter()
+            .rev()
+            .filter(|pm| Attributes::may_use_glob_pattern(&pm.pattern))
+            .filter_map(|pm| match &pm.value {
+                Value::Assignments(assignments) => Some((&pm.pattern, assignments)),
+                Value::MacroAttributes(_) => None,
+            })
+            .find_map(|(pattern, assignments)| {
+                let state = assignments.iter().rev().find_map(|assignment| {
+                    if assignment.name.as_str() == name {
+                        Some(assignment.state.as_ref())
+                    } else if implied_by_binary
+                        && assignment.name.as_str() == "binary"
+                        && assignment.state == crate::State::Set
+                    {
+                        Some(StateRef::Unset)
+                    } else {
+                        None
+                    }
+                })?;
+                pattern
+                    .matches_repo_relative_path(relative_path, basename_start_pos, is_dir, case)
+                    .then(|| state)
+            })
+    }
+}
+
 impl PatternList<Ignore> {
     /// Parse a list of patterns, using slashes as path separators
     pub fn from_overrides(patterns: impl IntoIterator<Item = impl Into<OsString>>) -> Self {
@@ -1,3 +1,4 @@
 pub use git_testtools::Result;
+mod edit;
 mod match_group;
 mod parse;
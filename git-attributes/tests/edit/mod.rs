@@ -0,0 +1,79 @@
+mod ignore {
+    use bstr::ByteSlice;
+    use git_attributes::edit::{Error, Ignore};
+
+    fn ignore(content: &str) -> Ignore {
+        Ignore::from(content.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn add_appends_pattern_and_keeps_all_other_lines() {
+        let mut file = ignore("# comment\n\n*.o\n  # not a comment\n!keep.o");
+        assert!(file.add("target/".into()).unwrap());
+        assert_eq!(
+            file.as_bytes().as_bstr(),
+            "# comment\n\n*.o\n  # not a comment\n!keep.o\ntarget/\n"
+        );
+
+        let mut file = Ignore::default();
+        assert!(file.add("a".into()).unwrap());
+        assert_eq!(file.into_bytes(), "a\n");
+    }
+
+    #[test]
+    fn add_uses_windows_line_endings_if_the_file_does() {
+        let mut file = ignore("a\r\nb");
+        assert!(file.add("c".into()).unwrap());
+        assert_eq!(file.as_bytes().as_bstr(), "a\r\nb\r\nc\r\n");
+    }
+
+    #[test]
+    fn add_skips_duplicates_unless_they_were_negated() {
+        let mut file = ignore("*.o\n/target\n");
+        assert!(!file.add("*.o".into()).unwrap());
+        assert!(!file.add("/target".into()).unwrap());
+        assert!(file.add("target".into()).unwrap(), "different modes are different patterns");
+
+        let mut file = ignore("*.o\n!*.o\n");
+        assert!(!file.contains("*.o".into()));
+        assert!(file.contains("!*.o".into()));
+        assert!(file.add("*.o".into()).unwrap(), "ignoring is in effect again");
+        assert_eq!(file.as_bytes().as_bstr(), "*.o\n!*.o\n*.o\n");
+    }
+
+    #[test]
+    fn add_rejects_invalid_patterns() {
+        for pattern in ["", "# comment", "a\nb", "   "] {
+            assert!(
+                matches!(Ignore::default().add(pattern.into()), Err(Error::InvalidPattern { .. })),
+                "{:?}",
+                pattern
+            );
+        }
+    }
+
+    #[test]
+    fn add_matching_verifies_the_pattern_matches_the_intended_path() {
+        let mut file = Ignore::default();
+        assert!(file.add_matching("*.log".into(), "dir/file.log".into(), Some(false)).unwrap());
+        assert!(file.add_matching("build/".into(), "build".into(), Some(true)).unwrap());
+        assert!(matches!(
+            file.add_matching("build/".into(), "build".into(), Some(false)),
+            Err(Error::NoMatch { .. })
+        ));
+        assert!(matches!(
+            file.add_matching("/file.log".into(), "dir/file.log".into(), None),
+            Err(Error::NoMatch { .. })
+        ));
+        assert_eq!(file.as_bytes().as_bstr(), "*.log\nbuild/\n");
+    }
+
+    #[test]
+    fn remove_deletes_all_equivalent_lines_only() {
+        let mut file = ignore("\u{feff}*.o\n# *.o\n!*.o\r\n*.o\r\nother\n*.o");
+        assert_eq!(file.remove("*.o".into()).unwrap(), 3);
+        assert_eq!(file.as_bytes().as_bstr(), "\u{feff}# *.o\n!*.o\r\nother\n");
+        assert_eq!(file.remove("*.o".into()).unwrap(), 0);
+        assert!(matches!(file.remove("#".into()), Err(Error::InvalidPattern { .. })));
+    }
+}
@@ -116,3 +116,47 @@ mod ignore {
         }
     }
 }
+
+mod attributes {
+    use git_attributes::{MatchGroup, PatternList, StateRef};
+    use git_glob::pattern::Case;
+
+    #[test]
+    fn attribute_matching_relative_path() {
+        let root = PatternList::from_bytes(
+            b"* text=auto\n*.bin binary\n*.sh eol=lf\n[attr]custom text\n",
+            "/repo/.gitattributes",
+            Some("/repo".as_ref()),
+        );
+        let dir = PatternList::from_bytes(
+            b"*.txt -text eol=crlf\nunrelated.txt diff\n",
+            "/repo/dir/.gitattributes",
+            Some("/repo".as_ref()),
+        );
+        let group = MatchGroup {
+            patterns: vec![root, dir],
+        };
+        let text = |path: &str| group.attribute_matching_relative_path(path, "text", Some(false), Case::Sensitive);
+        assert_eq!(text("a.sh"), Some(StateRef::Value("auto".into())));
+        assert_eq!(text("a.bin"), Some(StateRef::Unset), "the binary macro implies -text");
+        assert_eq!(text("a.txt"), Some(StateRef::Value("auto".into())));
+        assert_eq!(
+            text("dir/unrelated.txt"),
+            Some(StateRef::Unset),
+            "later patterns that don't mention the attribute are skipped"
+        );
+        assert_eq!(
+            group.attribute_matching_relative_path("a.sh", "eol", Some(false), Case::Sensitive),
+            Some(StateRef::Value("lf".into()))
+        );
+        assert_eq!(
+            group.attribute_matching_relative_path("a.txt", "eol", Some(false), Case::Sensitive),
+            None
+        );
+        assert_eq!(
+            group.attribute_matching_relative_path("custom", "text", Some(false), Case::Sensitive),
+            Some(StateRef::Value("auto".into())),
+            "macros definitions don't match paths"
+        );
+    }
+}
@@ -98,6 +98,12 @@ impl File {
         self.fan[255]
     }
 
+    /// Returns true if this file contains changed-path bloom filters for its commits, as written by
+    /// `git commit-graph write --changed-paths`.
+    pub fn has_bloom_filters(&self) -> bool {
+        self.has_bloom_filters
+    }
+
     /// Returns the path to this file.
     pub fn path(&self) -> &Path {
         &self.path
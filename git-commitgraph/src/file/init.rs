@@ -7,8 +7,9 @@ use bstr::ByteSlice;
 use memmap2::Mmap;
 
 use crate::file::{
-    ChunkId, File, BASE_GRAPHS_LIST_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
-    EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, HEADER_LEN, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+    ChunkId, File, BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_INDEX_CHUNK_ID,
+    COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH, EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, HEADER_LEN,
+    OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
 };
 
 /// The error used in [`File::at()`].
@@ -195,6 +196,8 @@ impl TryFrom<&Path> for File {
             })??;
 
         let extra_edges_list_range = chunks.usize_offset_by_id(EXTENDED_EDGES_LIST_CHUNK_ID).ok();
+        let has_bloom_filters = chunks.offset_by_id(BLOOM_FILTER_INDEX_CHUNK_ID).is_ok()
+            && chunks.offset_by_id(BLOOM_FILTER_DATA_CHUNK_ID).is_ok();
 
         let trailer = &data[chunks.highest_offset() as usize..];
         if trailer.len() != object_hash.len_in_bytes() {
@@ -232,6 +235,7 @@ impl TryFrom<&Path> for File {
         Ok(File {
             base_graph_count,
             base_graphs_list_offset,
+            has_bloom_filters,
             commit_data_offset,
             data,
             extra_edges_list_range,
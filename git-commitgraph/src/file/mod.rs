@@ -14,6 +14,8 @@ mod access;
 pub mod commit;
 mod init;
 pub mod verify;
+///
+pub mod write;
 
 const COMMIT_DATA_ENTRY_SIZE_SANS_HASH: usize = 16;
 const FAN_LEN: usize = 256;
@@ -23,6 +25,8 @@ const SIGNATURE: &[u8] = b"CGPH";
 
 type ChunkId = git_chunk::Id;
 const BASE_GRAPHS_LIST_CHUNK_ID: ChunkId = *b"BASE";
+const BLOOM_FILTER_DATA_CHUNK_ID: ChunkId = *b"BDAT";
+const BLOOM_FILTER_INDEX_CHUNK_ID: ChunkId = *b"BIDX";
 const COMMIT_DATA_CHUNK_ID: ChunkId = *b"CDAT";
 const EXTENDED_EDGES_LIST_CHUNK_ID: ChunkId = *b"EDGE";
 const OID_FAN_CHUNK_ID: ChunkId = *b"OIDF";
@@ -41,6 +45,7 @@ const LAST_EXTENDED_EDGE_MASK: u32 = 0x8000_0000;
 pub struct File {
     base_graph_count: u8,
     base_graphs_list_offset: Option<usize>,
+    has_bloom_filters: bool,
     commit_data_offset: usize,
     data: Mmap,
     extra_edges_list_range: Option<Range<usize>>,
@@ -0,0 +1,234 @@
+use std::{convert::TryInto, io::Write};
+
+use crate::{
+    file::{
+        File, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH, EXTENDED_EDGES_LIST_CHUNK_ID,
+        EXTENDED_EDGES_MASK, FAN_LEN, HEADER_LEN, LAST_EXTENDED_EDGE_MASK, NO_PARENT, OID_FAN_CHUNK_ID,
+        OID_LOOKUP_CHUNK_ID, SIGNATURE,
+    },
+    GENERATION_NUMBER_MAX, MAX_COMMITS,
+};
+
+mod error {
+    /// The error returned by [`File::write_to()`][crate::file::File::write_to()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error("Commit {id} has parent {parent} which isn't part of the commit-graph")]
+        MissingParent {
+            id: git_hash::ObjectId,
+            parent: git_hash::ObjectId,
+        },
+        #[error("A commit-graph can hold at most {} commits, got {0}", crate::MAX_COMMITS)]
+        TooManyCommits(usize),
+    }
+}
+pub use error::Error;
+
+/// A commit to be written into a commit-graph file with [`File::write_to()`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Entry {
+    /// The id of the commit.
+    pub id: git_hash::ObjectId,
+    /// The id of the commit's root tree.
+    pub root_tree_id: git_hash::ObjectId,
+    /// The ids of all parents of the commit, in order. Each of them must be written into the same file.
+    pub parents: Vec<git_hash::ObjectId>,
+    /// The committer timestamp in seconds since the unix epoch.
+    pub committer_timestamp: u64,
+}
+
+impl Entry {
+    /// Create an entry from `commit` as stored in `graph`, which makes it possible to rewrite or extend existing commit-graphs.
+    pub fn from_graph(
+        graph: &crate::Graph,
+        commit: &crate::file::Commit<'_>,
+    ) -> Result<Self, crate::file::commit::Error> {
+        Ok(Entry {
+            id: commit.id().to_owned(),
+            root_tree_id: commit.root_tree_id().to_owned(),
+            parents: commit
+                .iter_parents()
+                .map(|pos| pos.map(|pos| graph.id_at(pos).to_owned()))
+                .collect::<Result<_, _>>()?,
+            committer_timestamp: commit.committer_timestamp(),
+        })
+    }
+}
+
+/// Options for use in [`File::write_to()`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// The kind of hash used by all commits and trees.
+    pub object_hash: git_hash::Kind,
+}
+
+/// The result of [`File::write_to()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// The checksum of the commit-graph file, which is also its name when it's part of a split commit-graph.
+    pub checksum: git_hash::ObjectId,
+    /// The amount of commits in the written file.
+    pub num_commits: u32,
+}
+
+impl File {
+    /// Write a commit-graph file containing all `entries` to `out`, which has to be placed at `.git/objects/info/commit-graph`
+    /// to be picked up.
+    ///
+    /// Duplicate entries are written only once, and the parents of all entries have to be contained in `entries` as well.
+    /// Generation numbers are computed as part of the write.
+    pub fn write_to(
+        entries: impl IntoIterator<Item = Entry>,
+        out: impl std::io::Write,
+        Options { object_hash }: Options,
+    ) -> Result<Outcome, Error> {
+        let mut entries: Vec<_> = entries.into_iter().collect();
+        entries.sort_by_key(|entry| entry.id);
+        entries.dedup_by(|a, b| a.id == b.id);
+        if entries.len() > MAX_COMMITS as usize {
+            return Err(Error::TooManyCommits(entries.len()));
+        }
+
+        let parent_positions = entries
+            .iter()
+            .map(|entry| {
+                entry
+                    .parents
+                    .iter()
+                    .map(|parent| {
+                        entries
+                            .binary_search_by(|e| e.id.cmp(parent))
+                            .map(|pos| pos as u32)
+                            .map_err(|_| Error::MissingParent {
+                                id: entry.id,
+                                parent: *parent,
+                            })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let generations = generations(&parent_positions);
+
+        let mut extra_edges = Vec::new();
+        let hash_len = object_hash.len_in_bytes();
+        let mut cf = git_chunk::file::Index::for_writing();
+        cf.plan_chunk(OID_FAN_CHUNK_ID, (FAN_LEN * 4) as u64);
+        cf.plan_chunk(OID_LOOKUP_CHUNK_ID, (entries.len() * hash_len) as u64);
+        cf.plan_chunk(
+            COMMIT_DATA_CHUNK_ID,
+            (entries.len() * (hash_len + COMMIT_DATA_ENTRY_SIZE_SANS_HASH)) as u64,
+        );
+        let num_extra_edges: usize = parent_positions
+            .iter()
+            .filter(|parents| parents.len() > 2)
+            .map(|parents| parents.len() - 1)
+            .sum();
+        if num_extra_edges != 0 {
+            cf.plan_chunk(EXTENDED_EDGES_LIST_CHUNK_ID, (num_extra_edges * 4) as u64);
+        }
+
+        let mut out = git_features::hash::Write::new(out, object_hash);
+        out.write_all(SIGNATURE)?;
+        out.write_all(&[1 /* version */, object_hash as u8])?;
+        out.write_all(&[
+            cf.num_chunks().try_into().expect("BUG: wrote more than 256 chunks"),
+            0, /* base graphs */
+        ])?;
+
+        let mut chunk_write = cf.into_write(&mut out, HEADER_LEN)?;
+        while let Some(chunk_to_write) = chunk_write.next_chunk() {
+            match chunk_to_write {
+                OID_FAN_CHUNK_ID => {
+                    let mut fan = [0u32; FAN_LEN];
+                    for entry in &entries {
+                        fan[entry.id.first_byte() as usize] += 1;
+                    }
+                    let mut total = 0;
+                    for count in fan.iter() {
+                        total += count;
+                        chunk_write.write_all(&total.to_be_bytes())?;
+                    }
+                }
+                OID_LOOKUP_CHUNK_ID => {
+                    for entry in &entries {
+                        chunk_write.write_all(entry.id.as_slice())?;
+                    }
+                }
+                COMMIT_DATA_CHUNK_ID => {
+                    for ((entry, parents), generation) in entries.iter().zip(&parent_positions).zip(&generations) {
+                        chunk_write.write_all(entry.root_tree_id.as_slice())?;
+                        let (parent1, parent2) = match parents.as_slice() {
+                            [] => (NO_PARENT, NO_PARENT),
+                            [first] => (*first, NO_PARENT),
+                            [first, second] => (*first, *second),
+                            [first, rest @ ..] => {
+                                let index = extra_edges.len() as u32;
+                                extra_edges.extend_from_slice(rest);
+                                *extra_edges.last_mut().expect("at least two") |= LAST_EXTENDED_EDGE_MASK;
+                                (*first, EXTENDED_EDGES_MASK | index)
+                            }
+                        };
+                        chunk_write.write_all(&parent1.to_be_bytes())?;
+                        chunk_write.write_all(&parent2.to_be_bytes())?;
+                        let timestamp = entry.committer_timestamp & 0x0003_ffff_ffff;
+                        chunk_write.write_all(&((generation << 2) | (timestamp >> 32) as u32).to_be_bytes())?;
+                        chunk_write.write_all(&(timestamp as u32).to_be_bytes())?;
+                    }
+                }
+                EXTENDED_EDGES_LIST_CHUNK_ID => {
+                    for edge in &extra_edges {
+                        chunk_write.write_all(&edge.to_be_bytes())?;
+                    }
+                }
+                unknown => unreachable!("BUG: forgot to implement chunk {:?}", std::str::from_utf8(&unknown)),
+            }
+        }
+
+        let checksum: git_hash::ObjectId = out.hash.digest();
+        out.inner.write_all(checksum.as_slice())?;
+        Ok(Outcome {
+            checksum,
+            num_commits: entries.len() as u32,
+        })
+    }
+}
+
+/// Compute the generation number of each commit from the positions of their parents, with root commits having generation 1.
+fn generations(parent_positions: &[Vec<u32>]) -> Vec<u32> {
+    const UNVISITED: u32 = 0;
+    const IN_PROGRESS: u32 = u32::MAX;
+    let mut generations = vec![UNVISITED; parent_positions.len()];
+    let mut stack = Vec::new();
+    for start in 0..parent_positions.len() {
+        if generations[start] != UNVISITED {
+            continue;
+        }
+        stack.push(start);
+        while let Some(&pos) = stack.last() {
+            if generations[pos] == UNVISITED {
+                generations[pos] = IN_PROGRESS;
+                stack.extend(
+                    parent_positions[pos]
+                        .iter()
+                        .map(|parent| *parent as usize)
+                        .filter(|parent| generations[*parent] == UNVISITED),
+                );
+            } else {
+                stack.pop();
+                if generations[pos] == IN_PROGRESS {
+                    let max_parent_generation = parent_positions[pos]
+                        .iter()
+                        .map(|parent| generations[*parent as usize])
+                        .filter(|generation| *generation != IN_PROGRESS)
+                        .max()
+                        .unwrap_or(0);
+                    generations[pos] = (max_parent_generation + 1).min(GENERATION_NUMBER_MAX);
+                }
+            }
+        }
+    }
+    generations
+}
@@ -34,6 +34,13 @@ impl Graph {
         self.files.iter().flat_map(|file| file.iter_commits())
     }
 
+    /// Iterate over the files making up this graph, starting with the base layer of a split commit-graph chain.
+    ///
+    /// Graph positions are assigned in this order, so the commits of each file follow the ones of the file before it.
+    pub fn iter_files(&self) -> impl Iterator<Item = &File> {
+        self.files.iter()
+    }
+
     /// Iterate over commit IDs in unsorted order.
     pub fn iter_ids(&self) -> impl Iterator<Item = &git_hash::oid> {
         self.files.iter().flat_map(|file| file.iter_ids())
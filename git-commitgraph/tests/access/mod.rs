@@ -69,3 +69,27 @@ fn two_parents() -> crate::Result {
 
     Ok(())
 }
+
+#[test]
+fn split_chain_layers() -> crate::Result {
+    let repo_dir = make_readonly_repo("split_chain.sh");
+    let cg = Graph::from_info_dir(repo_dir.join(".git").join("objects").join("info"))?;
+    assert_eq!(
+        cg.iter_files()
+            .map(|file| (file.base_graph_count(), file.num_commits()))
+            .collect::<Vec<_>>(),
+        vec![(0, 1), (1, 1), (2, 1)],
+        "files are ordered from the base layer upwards"
+    );
+    assert!(cg.iter_files().all(|file| !file.has_bloom_filters()));
+    Ok(())
+}
+
+#[test]
+fn bloom_filters() -> crate::Result {
+    let repo_dir = make_readonly_repo("bloom_filters.sh");
+    let cg = Graph::from_info_dir(repo_dir.join(".git").join("objects").join("info"))?;
+    assert_eq!(cg.iter_files().count(), 1);
+    assert!(cg.iter_files().all(|file| file.has_bloom_filters()));
+    Ok(())
+}
@@ -0,0 +1,15 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+git checkout -q -b main
+echo a > a
+git add a
+git commit -q -m a
+echo b > b
+git add b
+git commit -q -m b
+
+git commit-graph write --no-progress --reachable --changed-paths
+git repack -adq
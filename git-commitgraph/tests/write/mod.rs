@@ -0,0 +1,82 @@
+use git_commitgraph::{
+    file::{self, write},
+    Graph,
+};
+
+use crate::make_readonly_repo;
+
+#[test]
+fn rewriting_graphs_written_by_git_yields_equivalent_graphs() -> crate::Result {
+    for fixture in [
+        "single_commit.sh",
+        "single_parent.sh",
+        "two_parents.sh",
+        "octopus_merges.sh",
+        "split_chain.sh",
+    ] {
+        let repo_dir = make_readonly_repo(fixture);
+        let expected = Graph::from_info_dir(repo_dir.join(".git").join("objects").join("info"))?;
+        let entries = expected
+            .iter_commits()
+            .map(|commit| write::Entry::from_graph(&expected, &commit))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let dir = git_testtools::tempfile::tempdir()?;
+        let path = dir.path().join("commit-graph");
+        let outcome = file::File::write_to(
+            entries.into_iter().rev(),
+            std::fs::File::create(&path)?,
+            write::Options::default(),
+        )?;
+        assert_eq!(outcome.num_commits, expected.num_commits(), "{}", fixture);
+
+        let actual = Graph::from_file(&path)?;
+        assert_eq!(actual.num_commits(), expected.num_commits());
+        for expected_commit in expected.iter_commits() {
+            let actual_commit = actual
+                .commit_by_id(expected_commit.id())
+                .expect("all commits are present");
+            assert_eq!(actual_commit.root_tree_id(), expected_commit.root_tree_id());
+            assert_eq!(
+                actual_commit.committer_timestamp(),
+                expected_commit.committer_timestamp()
+            );
+            assert_eq!(
+                actual_commit.generation(),
+                expected_commit.generation(),
+                "{}: generations are computed like git does",
+                fixture
+            );
+            let parent_ids = |graph: &Graph, commit: &file::Commit<'_>| {
+                commit
+                    .iter_parents()
+                    .map(|pos| graph.id_at(pos.expect("valid")).to_owned())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                parent_ids(&actual, &actual_commit),
+                parent_ids(&expected, &expected_commit)
+            );
+        }
+        actual.verify_integrity(|_| Ok::<_, std::convert::Infallible>(()))?;
+    }
+    Ok(())
+}
+
+#[test]
+fn parents_must_be_part_of_the_graph() {
+    let id = git_hash::ObjectId::from_hex(b"0000000000000000000000000000000000000001").unwrap();
+    let parent = git_hash::ObjectId::from_hex(b"0000000000000000000000000000000000000002").unwrap();
+    let err = file::File::write_to(
+        Some(write::Entry {
+            id,
+            root_tree_id: id,
+            parents: vec![parent],
+            committer_timestamp: 0,
+        }),
+        Vec::new(),
+        write::Options::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, write::Error::MissingParent { .. }));
+}
@@ -5,7 +5,7 @@ use bstr::BStr;
 use smallvec::ToSmallVec;
 
 use crate::{
-    file::{mutable::multi_value::EntryData, Index, MetadataFilter, MultiValueMut, Size, ValueMut},
+    file::{mutable::multi_value::EntryData, Index, MetadataFilter, MultiValueMut, Origin, Size, ValueMut},
     lookup,
     parse::{section, Event},
     File,
@@ -56,6 +56,47 @@ impl<'event> File<'event> {
         Err(lookup::existing::Error::KeyMissing)
     }
 
+    /// Like [`raw_value()`][Self::raw_value()], but also returns information about the value's [origin][Origin],
+    /// like the file it was read from, the position of its key within that file and the chain of includes that lead to it.
+    pub fn raw_value_with_origin(
+        &self,
+        section_name: impl AsRef<str>,
+        subsection_name: Option<&str>,
+        key: impl AsRef<str>,
+    ) -> Result<(Cow<'_, BStr>, Origin<'_>), lookup::existing::Error> {
+        self.raw_value_with_origin_filter(section_name, subsection_name, key, &mut |_| true)
+    }
+
+    /// Like [`raw_value_filter()`][Self::raw_value_filter()], but also returns information about the value's [origin][Origin].
+    pub fn raw_value_with_origin_filter(
+        &self,
+        section_name: impl AsRef<str>,
+        subsection_name: Option<&str>,
+        key: impl AsRef<str>,
+        filter: &mut MetadataFilter,
+    ) -> Result<(Cow<'_, BStr>, Origin<'_>), lookup::existing::Error> {
+        let section_ids = self.section_ids_by_name_and_subname(section_name.as_ref(), subsection_name)?;
+        let key = key.as_ref();
+        for section_id in section_ids.rev() {
+            let section = self.sections.get(&section_id).expect("known section id");
+            if !filter(section.meta()) {
+                continue;
+            }
+            if let Some(v) = section.value(key) {
+                let (key_range, _) = section
+                    .key_and_value_range_by(&section::Key::from_str_unchecked(key))
+                    .expect("key present if value is present");
+                let origin = Origin {
+                    meta: section.meta(),
+                    position: section.key_position(key_range.start),
+                };
+                return Ok((v, origin));
+            }
+        }
+
+        Err(lookup::existing::Error::KeyMissing)
+    }
+
     /// Returns a mutable reference to an uninterpreted value given a section,
     /// an optional subsection and key.
     ///
@@ -210,6 +251,54 @@ impl<'event> File<'event> {
         }
     }
 
+    /// Like [`raw_values()`][Self::raw_values()], but also returns information about the [origin][Origin] of each value.
+    pub fn raw_values_with_origin(
+        &self,
+        section_name: impl AsRef<str>,
+        subsection_name: Option<&str>,
+        key: impl AsRef<str>,
+    ) -> Result<Vec<(Cow<'_, BStr>, Origin<'_>)>, lookup::existing::Error> {
+        self.raw_values_with_origin_filter(section_name, subsection_name, key, &mut |_| true)
+    }
+
+    /// Like [`raw_values_filter()`][Self::raw_values_filter()], but also returns information about the [origin][Origin]
+    /// of each value.
+    pub fn raw_values_with_origin_filter(
+        &self,
+        section_name: impl AsRef<str>,
+        subsection_name: Option<&str>,
+        key: impl AsRef<str>,
+        filter: &mut MetadataFilter,
+    ) -> Result<Vec<(Cow<'_, BStr>, Origin<'_>)>, lookup::existing::Error> {
+        let mut values = Vec::new();
+        let section_ids = self.section_ids_by_name_and_subname(section_name.as_ref(), subsection_name)?;
+        let key = key.as_ref();
+        for section_id in section_ids {
+            let section = self.sections.get(&section_id).expect("known section id");
+            if !filter(section.meta()) {
+                continue;
+            }
+            values.extend(
+                section
+                    .values_and_key_indices(key)
+                    .into_iter()
+                    .map(|(key_index, value)| {
+                        let origin = Origin {
+                            meta: section.meta(),
+                            position: section.key_position(key_index),
+                        };
+                        (value, origin)
+                    }),
+            );
+        }
+
+        if values.is_empty() {
+            Err(lookup::existing::Error::KeyMissing)
+        } else {
+            Ok(values)
+        }
+    }
+
     /// Returns mutable references to all uninterpreted values given a section,
     /// an optional subsection and key.
     ///
@@ -14,8 +14,9 @@ impl FromStr for File<'static> {
     type Err = parse::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parse::Events::from_bytes_owned(s.as_bytes(), None)
-            .map(|events| File::from_parse_events_no_includes(events, Metadata::api()))
+        parse::Events::from_bytes_owned_with_key_positions(s.as_bytes(), None).map(|(events, key_positions)| {
+            File::from_parse_events_and_key_positions(events, key_positions, Metadata::api())
+        })
     }
 }
 
@@ -25,7 +26,9 @@ impl<'a> TryFrom<&'a str> for File<'a> {
     /// Convenience constructor. Attempts to parse the provided string into a
     /// [`File`]. See [`Events::from_str()`][crate::parse::Events::from_str()] for more information.
     fn try_from(s: &'a str) -> Result<File<'a>, Self::Error> {
-        parse::Events::from_str(s).map(|events| Self::from_parse_events_no_includes(events, Metadata::api()))
+        parse::Events::from_bytes_with_key_positions(s.as_bytes(), None).map(|(events, key_positions)| {
+            Self::from_parse_events_and_key_positions(events, key_positions, Metadata::api())
+        })
     }
 }
 
@@ -35,8 +38,9 @@ impl<'a> TryFrom<&'a BStr> for File<'a> {
     /// Convenience constructor. Attempts to parse the provided byte string into
     /// a [`File`]. See [`Events::from_bytes()`][parse::Events::from_bytes()] for more information.
     fn try_from(value: &'a BStr) -> Result<File<'a>, Self::Error> {
-        parse::Events::from_bytes(value, None)
-            .map(|events| Self::from_parse_events_no_includes(events, Metadata::api()))
+        parse::Events::from_bytes_with_key_positions(value, None).map(|(events, key_positions)| {
+            Self::from_parse_events_and_key_positions(events, key_positions, Metadata::api())
+        })
     }
 }
 
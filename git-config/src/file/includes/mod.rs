@@ -105,6 +105,7 @@ fn append_followed_includes_recursively(
             trust: meta.trust,
             level: meta.level + 1,
             source: meta.source,
+            include_chain: meta.include_chain.iter().chain(meta.path.as_ref()).cloned().collect(),
         };
         let no_follow_options = init::Options {
             includes: includes::Options::no_follow(),
@@ -39,6 +39,7 @@ impl File<'static> {
                     source: *source,
                     level: 0,
                     trust: git_sec::Trust::Full,
+                    include_chain: Vec::new(),
                 }
                 .into()
             });
@@ -48,6 +48,7 @@ impl File<'static> {
             source: crate::Source::Env,
             level: 0,
             trust: git_sec::Trust::Full,
+            include_chain: Vec::new(),
         };
         let mut config = File::new(meta);
         for i in 0..count {
@@ -36,16 +36,21 @@ impl<'a> File<'a> {
         options: Options<'_>,
     ) -> Result<Self, Error> {
         let meta = meta.into();
-        Ok(Self::from_parse_events_no_includes(
-            parse::Events::from_bytes(input, options.to_event_filter())?,
-            meta,
-        ))
+        let (events, key_positions) = parse::Events::from_bytes_with_key_positions(input, options.to_event_filter())?;
+        Ok(Self::from_parse_events_and_key_positions(events, key_positions, meta))
     }
 
     /// Instantiate a new `File` from given `events`, associating each section and their values with
     /// `meta`-data.
-    pub fn from_parse_events_no_includes(
+    pub fn from_parse_events_no_includes(events: parse::Events<'a>, meta: impl Into<OwnShared<Metadata>>) -> Self {
+        Self::from_parse_events_and_key_positions(events, Vec::new(), meta)
+    }
+
+    /// Like [`from_parse_events_no_includes()`][Self::from_parse_events_no_includes()], but associates each section
+    /// with the positions of its keys, if available.
+    pub(crate) fn from_parse_events_and_key_positions(
         parse::Events { frontmatter, sections }: parse::Events<'a>,
+        key_positions: parse::KeyPositions,
         meta: impl Into<OwnShared<Metadata>>,
     ) -> Self {
         let meta = meta.into();
@@ -53,12 +58,17 @@ impl<'a> File<'a> {
 
         this.frontmatter_events = frontmatter;
 
+        let mut key_positions = key_positions.into_iter();
         for section in sections {
-            this.push_section_internal(crate::file::Section {
-                header: section.header,
-                body: section::Body(section.events),
-                meta: OwnShared::clone(&meta),
-            });
+            this.push_section_internal(
+                crate::file::Section {
+                    header: section.header,
+                    body: section::Body(section.events),
+                    meta: OwnShared::clone(&meta),
+                    key_positions: Default::default(),
+                }
+                .with_key_positions(key_positions.next()),
+            );
         }
 
         this
@@ -74,10 +84,10 @@ impl File<'static> {
         meta: impl Into<OwnShared<Metadata>>,
         options: Options<'_>,
     ) -> Result<Self, Error> {
-        let mut config = Self::from_parse_events_no_includes(
-            parse::Events::from_bytes_owned(input_and_buf, options.to_event_filter()).map_err(Error::from)?,
-            meta,
-        );
+        let (events, key_positions) =
+            parse::Events::from_bytes_owned_with_key_positions(input_and_buf, options.to_event_filter())
+                .map_err(Error::from)?;
+        let mut config = Self::from_parse_events_and_key_positions(events, key_positions, meta);
 
         includes::resolve(&mut config, input_and_buf, options).map_err(Error::from)?;
         Ok(config)
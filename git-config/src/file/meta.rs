@@ -11,6 +11,7 @@ impl Metadata {
             source: Source::Api,
             level: 0,
             trust: git_sec::Trust::Full,
+            include_chain: Vec::new(),
         }
     }
 
@@ -23,6 +24,7 @@ impl Metadata {
             source,
             level: 0,
             trust,
+            include_chain: Vec::new(),
         })
     }
 
@@ -54,6 +56,7 @@ impl From<Source> for Metadata {
             source,
             level: 0,
             trust: git_sec::Trust::Full,
+            include_chain: Vec::new(),
         }
     }
 }
@@ -64,6 +64,29 @@ pub struct Metadata {
     pub level: u8,
     /// The trust-level for the section this meta-data is associated with.
     pub trust: git_sec::Trust,
+    /// The paths of all files that lead to this one being included, starting with the file loaded directly.
+    ///
+    /// It's empty if this file wasn't included.
+    pub include_chain: Vec<PathBuf>,
+}
+
+/// The location of a key within the file it was parsed from.
+#[derive(Clone, Copy, Debug, PartialOrd, PartialEq, Ord, Eq, Hash)]
+pub struct Position {
+    /// The line on which the key is located, starting at 1.
+    pub line: usize,
+    /// The offset in bytes from the beginning of the file at which the key starts.
+    pub byte_offset: usize,
+}
+
+/// Information about where a value is coming from, as returned by [`File::raw_value_with_origin()`][crate::File::raw_value_with_origin()].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Origin<'a> {
+    /// The metadata of the section containing the value, providing its path, source and include chain.
+    pub meta: &'a Metadata,
+    /// The position of the key of the value in its file, or `None` if it wasn't parsed from a file or
+    /// its section was mutated after parsing.
+    pub position: Option<Position>,
 }
 
 /// A section in a git-config file, like `[core]` or `[remote "origin"]`, along with all of its keys.
@@ -72,6 +95,7 @@ pub struct Section<'a> {
     header: crate::parse::section::Header<'a>,
     body: section::Body<'a>,
     meta: OwnShared<Metadata>,
+    key_positions: section::KeyPositions,
 }
 
 /// A function to filter metadata, returning `true` if the corresponding but omitted value can be used.
@@ -45,31 +45,10 @@ impl<'event> Body<'event> {
     /// an empty vec, which implies there were no values with the provided key.
     #[must_use]
     pub fn values(&self, key: impl AsRef<str>) -> Vec<Cow<'_, BStr>> {
-        let key = &Key::from_str_unchecked(key.as_ref());
-        let mut values = Vec::new();
-        let mut expect_value = false;
-        let mut concatenated_value = BString::default();
-
-        for event in &self.0 {
-            match event {
-                Event::SectionKey(event_key) if event_key == key => expect_value = true,
-                Event::Value(v) if expect_value => {
-                    expect_value = false;
-                    values.push(normalize_bstr(v.as_ref()));
-                }
-                Event::ValueNotDone(v) if expect_value => {
-                    concatenated_value.push_str(v.as_ref());
-                }
-                Event::ValueDone(v) if expect_value => {
-                    expect_value = false;
-                    concatenated_value.push_str(v.as_ref());
-                    values.push(normalize_bstring(std::mem::take(&mut concatenated_value)));
-                }
-                _ => (),
-            }
-        }
-
-        values
+        self.values_and_key_indices(key.as_ref())
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect()
     }
 
     /// Returns an iterator visiting all keys in order.
@@ -113,6 +92,37 @@ impl<'event> Body<'event> {
     /// Returns the the range containing the value events for the `key`, with value range being `None` if there is no key-value separator
     /// and only a 'fake' Value event with an empty string in side.
     /// If the value is not found, `None` is returned.
+    /// Like [`values()`][Self::values()], but also returns the index of the event of the key of each value.
+    pub(crate) fn values_and_key_indices(&self, key: &str) -> Vec<(usize, Cow<'_, BStr>)> {
+        let key = &Key::from_str_unchecked(key);
+        let mut values = Vec::new();
+        let mut key_index = None;
+        let mut concatenated_value = BString::default();
+
+        for (index, event) in self.0.iter().enumerate() {
+            match event {
+                Event::SectionKey(event_key) if event_key == key => key_index = Some(index),
+                Event::Value(v) => {
+                    if let Some(key_index) = key_index.take() {
+                        values.push((key_index, normalize_bstr(v.as_ref())));
+                    }
+                }
+                Event::ValueNotDone(v) if key_index.is_some() => {
+                    concatenated_value.push_str(v.as_ref());
+                }
+                Event::ValueDone(v) => {
+                    if let Some(key_index) = key_index.take() {
+                        concatenated_value.push_str(v.as_ref());
+                        values.push((key_index, normalize_bstring(std::mem::take(&mut concatenated_value))));
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        values
+    }
+
     pub(crate) fn key_and_value_range_by(&self, key: &Key<'_>) -> Option<(Range<usize>, Option<Range<usize>>)> {
         let mut value_range = Range::default();
         let mut key_start = None;
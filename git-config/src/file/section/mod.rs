@@ -36,6 +36,7 @@ impl<'a> Section<'a> {
             header: parse::section::Header::new(name, subsection)?,
             body: Default::default(),
             meta: meta.into(),
+            key_positions: Default::default(),
         })
     }
 }
@@ -129,7 +130,54 @@ impl<'a> Section<'a> {
     }
 
     /// Returns a mutable version of this section for adjustment of values.
+    ///
+    /// Note that this forgets the positions of all keys as they can't be kept accurate after mutation.
     pub fn to_mut(&mut self, newline: SmallVec<[u8; 2]>) -> SectionMut<'_, 'a> {
+        self.key_positions = KeyPositions::default();
         SectionMut::new(self, newline)
     }
+
+    /// Return the position in the originating file of the key at `event_index` in our body, if known.
+    pub(crate) fn key_position(&self, event_index: usize) -> Option<file::Position> {
+        let key_index = self.body.0[..event_index]
+            .iter()
+            .filter(|e| matches!(e, Event::SectionKey(_)))
+            .count();
+        self.key_positions.0.as_ref()?.get(key_index).copied()
+    }
+
+    pub(crate) fn with_key_positions(mut self, positions: Option<Vec<file::Position>>) -> Self {
+        self.key_positions = KeyPositions(positions);
+        self
+    }
+}
+
+/// The positions of all keys of a section in the file it was parsed from, in order.
+///
+/// They don't bear any value and are thus ignored when comparing or hashing sections.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct KeyPositions(Option<Vec<file::Position>>);
+
+impl PartialEq for KeyPositions {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for KeyPositions {}
+
+impl PartialOrd for KeyPositions {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for KeyPositions {
+    fn cmp(&self, _other: &Self) -> std::cmp::Ordering {
+        std::cmp::Ordering::Equal
+    }
+}
+
+impl std::hash::Hash for KeyPositions {
+    fn hash<H: std::hash::Hasher>(&self, _state: &mut H) {}
 }
@@ -1,9 +1,9 @@
-use std::convert::TryFrom;
+use std::{borrow::Cow, convert::TryFrom};
 
 use smallvec::SmallVec;
 
 use crate::{
-    parse,
+    file, parse,
     parse::{section, Event, Section},
 };
 
@@ -229,7 +229,17 @@ impl Events<'static> {
         input: &'a [u8],
         filter: Option<fn(&Event<'a>) -> bool>,
     ) -> Result<Events<'static>, parse::Error> {
-        from_bytes(input, |e| e.to_owned(), filter)
+        from_bytes(input, |e| e.to_owned(), filter, None)
+    }
+
+    /// Like [`from_bytes_owned()`][Self::from_bytes_owned()], but also returns the positions of all keys, one list per section.
+    pub(crate) fn from_bytes_owned_with_key_positions<'a>(
+        input: &'a [u8],
+        filter: Option<fn(&Event<'a>) -> bool>,
+    ) -> Result<(Events<'static>, KeyPositions), parse::Error> {
+        let mut positions = Vec::new();
+        let events = from_bytes(input, |e| e.to_owned(), filter, Some(&mut positions))?;
+        Ok((events, positions))
     }
 }
 
@@ -241,7 +251,17 @@ impl<'a> Events<'a> {
     ///
     /// Use `filter` to only include those events for which it returns true.
     pub fn from_bytes(input: &'a [u8], filter: Option<fn(&Event<'a>) -> bool>) -> Result<Events<'a>, parse::Error> {
-        from_bytes(input, std::convert::identity, filter)
+        from_bytes(input, std::convert::identity, filter, None)
+    }
+
+    /// Like [`from_bytes()`][Self::from_bytes()], but also returns the positions of all keys, one list per section.
+    pub(crate) fn from_bytes_with_key_positions(
+        input: &'a [u8],
+        filter: Option<fn(&Event<'a>) -> bool>,
+    ) -> Result<(Events<'a>, KeyPositions), parse::Error> {
+        let mut positions = Vec::new();
+        let events = from_bytes(input, std::convert::identity, filter, Some(&mut positions))?;
+        Ok((events, positions))
     }
 
     /// Attempt to zero-copy parse the provided `input` string.
@@ -286,17 +306,28 @@ impl<'a> TryFrom<&'a [u8]> for Events<'a> {
     }
 }
 
+/// The positions of all keys of each section, in order, with one entry per section.
+pub(crate) type KeyPositions = Vec<Vec<file::Position>>;
+
 fn from_bytes<'a, 'b>(
     input: &'a [u8],
     convert: impl Fn(Event<'a>) -> Event<'b>,
     filter: Option<fn(&Event<'a>) -> bool>,
+    mut key_positions: Option<&mut KeyPositions>,
 ) -> Result<Events<'b>, parse::Error> {
+    let mut cursor = file::Position {
+        line: 1,
+        byte_offset: 0,
+    };
     let mut header = None;
     let mut events = section::Events::default();
     let mut frontmatter = FrontMatterEvents::default();
     let mut sections = Vec::new();
     parse::from_bytes(input, |e: Event<'_>| match e {
         Event::SectionHeader(next_header) => {
+            if let Some(positions) = key_positions.as_deref_mut() {
+                positions.push(Vec::new());
+            }
             match header.take() {
                 None => {
                     frontmatter = std::mem::take(&mut events).into_iter().collect();
@@ -315,6 +346,19 @@ fn from_bytes<'a, 'b>(
             .into();
         }
         event => {
+            if let (Some(positions), Event::SectionKey(section::Key(Cow::Borrowed(key)))) =
+                (key_positions.as_deref_mut(), &event)
+            {
+                let byte_offset = key.as_ptr() as usize - input.as_ptr() as usize;
+                cursor.line += input[cursor.byte_offset..byte_offset]
+                    .iter()
+                    .filter(|b| **b == b'\n')
+                    .count();
+                cursor.byte_offset = byte_offset;
+                if let Some(section_positions) = positions.last_mut() {
+                    section_positions.push(cursor);
+                }
+            }
             if filter.map_or(true, |f| f(&event)) {
                 events.push(convert(event))
             }
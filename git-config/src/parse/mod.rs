@@ -19,6 +19,7 @@ pub use self::nom::from_bytes;
 mod event;
 #[path = "events.rs"]
 mod events_type;
+pub(crate) use events_type::KeyPositions;
 pub use events_type::{Events, FrontMatterEvents};
 mod comment;
 mod error;
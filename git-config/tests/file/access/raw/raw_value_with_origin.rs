@@ -0,0 +1,100 @@
+use std::convert::TryFrom;
+
+use git_config::{
+    file::{includes, init, Metadata, Position},
+    parse::section,
+    File, Source,
+};
+
+fn position(line: usize, byte_offset: usize) -> Option<Position> {
+    Some(Position { line, byte_offset })
+}
+
+#[test]
+fn positions_point_to_the_key_of_the_value() -> crate::Result {
+    let input = "# comment\n[core]\n  a = b\n\tmulti = one\\\n  two\n[core]\r\n a = c";
+    let config = File::try_from(input)?;
+
+    let (value, origin) = config.raw_value_with_origin("core", None, "a")?;
+    assert_eq!(value.as_ref(), "c");
+    assert_eq!(origin.position, position(7, input.rfind('a').expect("present")));
+    assert_eq!(origin.meta, &Metadata::api());
+
+    let (value, origin) = config.raw_value_with_origin("core", None, "multi")?;
+    assert_eq!(value.as_ref(), "one  two");
+    assert_eq!(origin.position, position(4, input.find("multi").expect("present")));
+
+    let values: Vec<_> = config
+        .raw_values_with_origin("core", None, "a")?
+        .into_iter()
+        .map(|(value, origin)| (value.into_owned(), origin.position))
+        .collect();
+    assert_eq!(
+        values,
+        [("b".into(), position(3, 19)), ("c".into(), position(7, 54))],
+        "each value has its own position"
+    );
+    Ok(())
+}
+
+#[test]
+fn positions_are_retained_in_lossy_mode() -> crate::Result {
+    let input = "[core]\n; comment\n\n  a = b\n";
+    let config = File::from_bytes_no_includes(
+        input.as_bytes(),
+        Metadata::api(),
+        init::Options {
+            lossy: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        config.raw_value_with_origin("core", None, "a")?.1.position,
+        position(4, 20)
+    );
+    Ok(())
+}
+
+#[test]
+fn positions_are_unknown_after_mutation() -> crate::Result {
+    let mut config = File::try_from("[core]\na=b")?;
+    config
+        .section_mut("core", None)?
+        .push(section::Key::try_from("c")?, Some("d".into()));
+    let (value, origin) = config.raw_value_with_origin("core", None, "a")?;
+    assert_eq!(value.as_ref(), "b");
+    assert_eq!(origin.position, None);
+    Ok(())
+}
+
+#[test]
+fn includes_provide_their_path_and_the_chain_that_lead_to_them() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let inner = dir.path().join("inner");
+    std::fs::write(&inner, "[core]\n\n  a = inner")?;
+    let middle = dir.path().join("middle");
+    std::fs::write(&middle, "[include]\n  path = inner\n")?;
+    let outer = dir.path().join("outer");
+    std::fs::write(&outer, "[include]\n  path = middle\n[core]\n  b = outer")?;
+
+    let config = File::from_paths_metadata(
+        Some(Metadata::try_from_path(&outer, Source::Local)?),
+        init::Options {
+            includes: includes::Options::follow(Default::default(), Default::default()),
+            ..Default::default()
+        },
+    )?
+    .expect("non-empty");
+
+    let (value, origin) = config.raw_value_with_origin("core", None, "a")?;
+    assert_eq!(value.as_ref(), "inner");
+    assert_eq!(origin.meta.path.as_deref(), Some(inner.as_path()));
+    assert_eq!(origin.meta.include_chain, [outer.clone(), middle]);
+    assert_eq!(origin.position, position(3, 10));
+
+    let (_, origin) = config.raw_value_with_origin("core", None, "b")?;
+    assert_eq!(origin.meta.path.as_deref(), Some(outer.as_path()));
+    assert!(origin.meta.include_chain.is_empty());
+    assert_eq!(origin.position, position(4, 35));
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bstr::BString;
+
+use crate::{
+    helper::{Action, Error, NextAction, Outcome, Result},
+    program::{self, Program},
+    prompt,
+    protocol::Context,
+};
+
+/// Invoke credential helper [programs][Program] in order, similar to how `git credential` does it, to obtain, store or
+/// erase credentials without the need for a `git` installation.
+///
+/// Its [`invoke()`][Cascade::invoke()] method can be used wherever [`action()`][crate::helper::action()] is used.
+#[derive(Default, Debug, Clone)]
+pub struct Cascade {
+    /// The programs to ask in order, typically from all `credential.helper` values that apply to the url.
+    pub programs: Vec<Program>,
+    /// If `true`, the path of `http` and `https` urls is passed to helpers as well, as configured with `credential.useHttpPath`.
+    pub use_http_path: bool,
+    /// The username to use if no helper provides one, as configured with `credential.username`.
+    pub username: Option<String>,
+    /// How to ask the user if no helper provides a username or password.
+    pub prompt: prompt::Options,
+}
+
+impl Cascade {
+    /// Perform `action` like [`action()`][crate::helper::action()], but by invoking our [programs][Cascade::programs] directly.
+    ///
+    /// When filling, programs are asked in order until username and password are known, with later programs seeing what earlier
+    /// ones provided. Expired passwords are ignored. Whatever is still missing is [asked][prompt::ask()] for.
+    /// When approving or rejecting, the credential is stored in or erased from all programs, and failures are ignored.
+    pub fn invoke(&mut self, action: Action<'_>) -> Result {
+        match action {
+            Action::Fill(url) => {
+                let mut ctx = Context::from_url(url, self.use_http_path)?;
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|duration| duration.as_secs())
+                    .unwrap_or_default();
+                for program in &self.programs {
+                    if ctx.username.is_some() && ctx.password.is_some() {
+                        break;
+                    }
+                    // Like git, ignore programs that fail to provide anything.
+                    if let Ok(Some(provided)) = program.invoke(program::Action::Get, &ctx) {
+                        ctx.update(provided);
+                        ctx.clear_expired_password(now);
+                    }
+                    if ctx.quit {
+                        return Err(Error::Quit);
+                    }
+                }
+                if ctx.username.is_none() {
+                    ctx.username = self.username.clone();
+                }
+                if ctx.username.is_none() {
+                    ctx.username = prompt::ask(&format!("Username for '{}': ", ctx.to_url()), false, &self.prompt)?;
+                }
+                if ctx.password.is_none() {
+                    let prompt = format!(
+                        "Password for '{}': ",
+                        ctx.to_url().replacen(
+                            "://",
+                            &format!("://{}@", ctx.username.as_deref().unwrap_or_default()),
+                            1
+                        )
+                    );
+                    ctx.password = prompt::ask(&prompt, true, &self.prompt)?;
+                }
+
+                let mut previous_output = Vec::new();
+                ctx.write_to(&mut previous_output)?;
+                Ok(Some(Outcome {
+                    identity: git_sec::identity::Account {
+                        username: ctx.username.ok_or_else(|| Error::KeyNotFound("username".into()))?,
+                        password: ctx.password.ok_or_else(|| Error::KeyNotFound("password".into()))?,
+                    },
+                    next: NextAction {
+                        previous_output: previous_output.into(),
+                    },
+                }))
+            }
+            Action::Approve(previous) => self.store_or_erase(program::Action::Store, previous),
+            Action::Reject(previous) => self.store_or_erase(program::Action::Erase, previous),
+        }
+    }
+
+    fn store_or_erase(&self, action: program::Action, previous: BString) -> Result {
+        let ctx = Context::from_bytes(&previous)?;
+        if action == program::Action::Store && (ctx.username.is_none() || ctx.password.is_none()) {
+            return Ok(None);
+        }
+        for program in &self.programs {
+            program.invoke(action, &ctx).ok();
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn cascade(definitions: &[&str]) -> Cascade {
+        Cascade {
+            programs: definitions
+                .iter()
+                .copied()
+                .map(Program::from_custom_definition)
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn fill(cascade: &mut Cascade) -> std::result::Result<Outcome, Error> {
+        Ok(cascade
+            .invoke(Action::Fill("https://example.com/repo.git".into()))?
+            .expect("fill always returns an outcome"))
+    }
+
+    #[test]
+    fn programs_are_asked_in_order_until_credentials_are_complete() -> std::result::Result<(), Error> {
+        let outcome = fill(&mut cascade(&[
+            "!false",
+            "!echo username=first;:",
+            "!f() { grep -q '^username=first$' && echo password=second; }; f",
+            "!echo password=never-asked;:",
+        ]))?;
+        assert_eq!(outcome.identity.username, "first");
+        assert_eq!(outcome.identity.password, "second");
+        Ok(())
+    }
+
+    #[test]
+    fn expired_passwords_are_ignored() -> std::result::Result<(), Error> {
+        let outcome = fill(&mut cascade(&[
+            "!printf 'username=bob\\npassword=old\\npassword_expiry_utc=1\\n';:",
+            "!printf 'password=fresh\\noauth_refresh_token=refresh\\n';:",
+        ]))?;
+        assert_eq!(outcome.identity.password, "fresh");
+        let ctx = Context::from_bytes(&outcome.next.previous_output)?;
+        assert_eq!(ctx.oauth_refresh_token.as_deref(), Some("refresh"));
+        assert_eq!(ctx.password_expiry_utc, None);
+        Ok(())
+    }
+
+    #[test]
+    fn quit_stops_everything() {
+        assert!(matches!(
+            fill(&mut cascade(&["!echo quit=1;:", "!echo username=bob;:"])),
+            Err(Error::Quit)
+        ));
+    }
+
+    #[test]
+    fn missing_credentials_without_prompt_are_an_error() {
+        assert!(matches!(
+            fill(&mut cascade(&["!echo username=bob;:"])),
+            Err(Error::KeyNotFound(key)) if key == "password"
+        ));
+    }
+
+    #[test]
+    fn approve_and_reject_store_and_erase_in_all_programs() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let log = std::env::temp_dir().join(format!("git-credentials-cascade-{}.log", std::process::id()));
+        let record = format!(r#"!f() {{ echo "$1 $(grep password)" >> '{}'; }}; f"#, log.display());
+        let mut cascade = cascade(&["!printf 'username=bob\\npassword=pass\\n';:", &record, &record]);
+        let outcome = fill(&mut cascade)?;
+        assert!(cascade.invoke(outcome.next.clone().approve())?.is_none());
+        assert!(cascade.invoke(outcome.next.reject())?.is_none());
+        let recorded = std::fs::read_to_string(&log)?;
+        std::fs::remove_file(&log)?;
+        assert_eq!(
+            recorded,
+            "store password=pass\nstore password=pass\nerase password=pass\nerase password=pass\n"
+        );
+        Ok(())
+    }
+}
@@ -6,6 +6,11 @@ use std::{
 
 use quick_error::quick_error;
 
+use crate::protocol::validate;
+
+mod cascade;
+pub use cascade::Cascade;
+
 /// The result used in [`action()`].
 pub type Result = std::result::Result<Option<Outcome>, Error>;
 
@@ -25,6 +30,9 @@ quick_error! {
         CredentialsHelperFailed(code: Option<i32>) {
             display("Credentials helper program failed with status code {:?}", code)
         }
+        Quit {
+            display("A credentials helper asked to not ask any other helper nor the user")
+        }
     }
 }
 
@@ -55,7 +63,7 @@ impl<'a> Action<'a> {
 /// A handle to [approve][NextAction::approve()] or [reject][NextAction::reject()] the outcome of the initial action.
 #[derive(Clone, Debug)]
 pub struct NextAction {
-    previous_output: BString,
+    pub(crate) previous_output: BString,
 }
 
 impl NextAction {
@@ -137,16 +145,6 @@ pub fn encode_message(url: &BStr, mut out: impl io::Write) -> io::Result<()> {
     writeln!(out, "url={}\n", url)
 }
 
-fn validate(url: &BStr) -> io::Result<()> {
-    if url.contains(&0) || url.contains(&b'\n') {
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            "token to encode must not contain newlines or null bytes",
-        ));
-    }
-    Ok(())
-}
-
 /// Decode all lines in `input` as key-value pairs produced by a `git credentials` helper program.
 pub fn decode_message(mut input: impl io::Read) -> io::Result<Vec<(String, String)>> {
     let mut buf = String::new();
@@ -11,4 +11,11 @@
 
 ///
 pub mod helper;
+///
+pub mod program;
+pub use program::Program;
+///
+pub mod prompt;
+///
+pub mod protocol;
 pub use helper::action as helper;
@@ -0,0 +1,140 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use bstr::{BString, ByteSlice};
+
+use crate::{helper::Error, protocol::Context};
+
+/// The action to ask a credential helper [`Program`] to perform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Return a matching credential, if there is one.
+    Get,
+    /// Store the credential for later retrieval.
+    Store,
+    /// Remove the credential, if it was stored before.
+    Erase,
+}
+
+impl Action {
+    /// Return the name of the action as passed to the helper program.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::Get => "get",
+            Action::Store => "store",
+            Action::Erase => "erase",
+        }
+    }
+}
+
+/// A credential helper program as configured with `credential.helper`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Program {
+    /// The shell script to execute, with the action to perform appended as argument.
+    script: BString,
+}
+
+/// Initialization
+impl Program {
+    /// Create a new program from the value of `credential.helper`, which is one of
+    ///
+    /// * `!<shell script>` - run the shell script as is.
+    /// * `/absolute/path/to/helper [args…]` - run the given program with arguments.
+    /// * `<name> [args…]` - run `git credential-<name>` with arguments.
+    pub fn from_custom_definition(definition: impl Into<BString>) -> Self {
+        let definition = definition.into();
+        let script = if let Some(script) = definition.strip_prefix(b"!") {
+            script.into()
+        } else if is_absolute(&definition) {
+            definition
+        } else {
+            let mut script = BString::from("git credential-");
+            script.extend_from_slice(&definition);
+            script
+        };
+        Program { script }
+    }
+}
+
+/// Execution
+impl Program {
+    /// Ask this program to perform `action` with the credential in `ctx`, and return the credential it provided
+    /// if the action is [`Action::Get`] and it printed something.
+    pub fn invoke(&self, action: Action, ctx: &Context) -> Result<Option<Context>, Error> {
+        let mut script = self.script.to_str_lossy().into_owned();
+        script.push(' ');
+        script.push_str(action.as_str());
+        let mut cmd = Command::new(if cfg!(windows) { "sh.exe" } else { "sh" });
+        cmd.arg("-c")
+            .arg(script)
+            .stdin(Stdio::piped())
+            .stdout(if action == Action::Get {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            });
+        let mut child = cmd.spawn()?;
+        {
+            let mut stdin = child.stdin.take().expect("stdin to be configured");
+            // Like git, ignore programs that exit without reading their input.
+            match ctx.write_to(&mut stdin).and_then(|_| stdin.write_all(b"\n")) {
+                Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => {}
+                res => res?,
+            }
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(Error::CredentialsHelperFailed(output.status.code()));
+        }
+        Ok(if output.stdout.is_empty() {
+            None
+        } else {
+            Some(Context::from_bytes(&output.stdout)?)
+        })
+    }
+}
+
+fn is_absolute(path: &[u8]) -> bool {
+    path.first() == Some(&b'/')
+        || (cfg!(windows)
+            && (path.first() == Some(&b'\\') || path.get(1..3) == Some(b":\\") || path.get(1..3) == Some(b":/")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_custom_definition() {
+        for (input, expected) in [
+            ("!f() { echo hi; }; f", "f() { echo hi; }; f"),
+            ("/usr/bin/helper --flag", "/usr/bin/helper --flag"),
+            ("store --file ~/creds", "git credential-store --file ~/creds"),
+        ] {
+            assert_eq!(Program::from_custom_definition(input).script, expected);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn invoke_passes_action_and_context() -> Result<(), Error> {
+        let program = Program::from_custom_definition(
+            r#"!f() { test "$1" = get && grep -q '^host=example.com$' && echo username=bob && echo password=pass; }; f"#,
+        );
+        let ctx = Context {
+            protocol: Some("https".into()),
+            host: Some("example.com".into()),
+            ..Default::default()
+        };
+        let out = program.invoke(Action::Get, &ctx)?.expect("output");
+        assert_eq!(out.username.as_deref(), Some("bob"));
+        assert_eq!(out.password.as_deref(), Some("pass"));
+        assert!(matches!(
+            program.invoke(Action::Store, &ctx),
+            Err(Error::CredentialsHelperFailed(Some(1)))
+        ));
+        Ok(())
+    }
+}
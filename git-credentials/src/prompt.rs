@@ -0,0 +1,90 @@
+//! Ask the user for information like usernames and passwords, either with an `askpass` program or on the terminal.
+use std::{
+    io,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+/// Configure how to ask the user.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// The program to run with the prompt as only argument, which prints the answer to stdout, like `$GIT_ASKPASS`.
+    ///
+    /// It is preferred over the terminal.
+    pub askpass: Option<PathBuf>,
+    /// If `true`, and if there is no [askpass][Options::askpass] program, ask on the terminal.
+    ///
+    /// This is only supported on unix, and could be disabled with `GIT_TERMINAL_PROMPT=0`.
+    pub terminal: bool,
+}
+
+/// Ask the user using `prompt`, and return the line they entered or `None` if there is no way to ask.
+///
+/// If `secret` is `true`, input won't be echoed on the terminal.
+pub fn ask(prompt: &str, secret: bool, options: &Options) -> io::Result<Option<String>> {
+    if let Some(askpass) = &options.askpass {
+        let output = Command::new(askpass)
+            .arg(prompt)
+            .stdin(Stdio::null())
+            .stderr(Stdio::inherit())
+            .output()?;
+        if !output.status.success() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("askpass program {:?} failed with {}", askpass, output.status),
+            ));
+        }
+        let answer = String::from_utf8(output.stdout).map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        return Ok(Some(answer.lines().next().unwrap_or_default().to_owned()));
+    }
+    if options.terminal {
+        return terminal::ask(prompt, secret);
+    }
+    Ok(None)
+}
+
+#[cfg(unix)]
+mod terminal {
+    use std::{
+        fs::{File, OpenOptions},
+        io::{self, BufRead, Write},
+        process::{Command, Stdio},
+    };
+
+    fn stty(tty: &File, arg: &str) -> io::Result<()> {
+        Command::new("stty")
+            .arg(arg)
+            .stdin(tty.try_clone()?)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|_| ())
+    }
+
+    pub fn ask(prompt: &str, secret: bool) -> io::Result<Option<String>> {
+        let mut tty = match OpenOptions::new().read(true).write(true).open("/dev/tty") {
+            Ok(tty) => tty,
+            Err(_) => return Ok(None),
+        };
+        tty.write_all(prompt.as_bytes())?;
+        tty.flush()?;
+        if secret {
+            stty(&tty, "-echo")?;
+        }
+        let mut answer = String::new();
+        let res = io::BufReader::new(tty.try_clone()?).read_line(&mut answer);
+        if secret {
+            stty(&tty, "echo")?;
+            tty.write_all(b"\n")?;
+        }
+        res?;
+        Ok(Some(answer.trim_end_matches(&['\r', '\n'][..]).to_owned()))
+    }
+}
+
+#[cfg(not(unix))]
+mod terminal {
+    pub fn ask(_prompt: &str, _secret: bool) -> std::io::Result<Option<String>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,78 @@
+use std::{convert::TryInto, time::SystemTime};
+
+use crate::{
+    time::{format, Sign},
+    Time,
+};
+
+mod relative;
+
+/// Parse `input` as date the way git does, or return `None` if it couldn't be understood.
+///
+/// Strict formats are tried first, which are the ones git can produce like [`RFC2822`][format::RFC2822],
+/// [`ISO8601`][format::ISO8601], [`ISO8601_STRICT`][format::ISO8601_STRICT], [`DEFAULT`][format::DEFAULT],
+/// [`RAW`][format::RAW] and [`UNIX`][format::UNIX], along with `@<seconds since epoch>`.
+///
+/// If `now` is set, git's _approxidate_ is used as fallback to understand relative and approximate dates like
+/// `2 weeks ago`, `yesterday`, `noon last tuesday`, `5pm`, `last month` or `1979-02-26 18:30:00`, which are
+/// interpreted relative to `now` in the local timezone.
+pub fn parse(input: &str, now: Option<SystemTime>) -> Option<Time> {
+    let input = input.trim();
+    strict(input).or_else(|| now.and_then(|now| relative::parse(input, now)))
+}
+
+fn strict(input: &str) -> Option<Time> {
+    if let Some(time) = raw(input.strip_prefix('@').unwrap_or(input)) {
+        return Some(time);
+    }
+    if let Some(seconds) = input.strip_prefix('@') {
+        return number(seconds).map(|seconds| Time::new(seconds, 0));
+    }
+    // Like git, only numbers large enough not to be confused with a year or day are seconds since epoch.
+    if let Some(seconds) = number(input).filter(|seconds| *seconds >= 100_000_000) {
+        return Some(Time::new(seconds, 0));
+    }
+    [
+        format::RFC2822,
+        format::ISO8601,
+        format::ISO8601_STRICT,
+        format::DEFAULT,
+    ]
+    .iter()
+    .find_map(|format| time::OffsetDateTime::parse(input, format).ok())
+    .and_then(|time| {
+        Some(Time::new(
+            time.unix_timestamp().try_into().ok()?,
+            time.offset().whole_seconds(),
+        ))
+    })
+}
+
+fn number(input: &str) -> Option<u32> {
+    if input.is_empty() || !input.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    input.parse().ok()
+}
+
+/// Parse `<seconds> <+|-><HHMM>` as used in commits and tags.
+fn raw(input: &str) -> Option<Time> {
+    let (seconds, offset) = input.split_once(' ')?;
+    let seconds = number(seconds)?;
+    let (sign, offset) = match offset.as_bytes().first()? {
+        b'+' => (Sign::Plus, &offset[1..]),
+        b'-' => (Sign::Minus, &offset[1..]),
+        _ => return None,
+    };
+    if offset.len() != 4 {
+        return None;
+    }
+    let hours: i32 = number(&offset[..2])?.try_into().ok()?;
+    let minutes: i32 = number(&offset[2..])?.try_into().ok()?;
+    let offset_in_seconds = (hours * 3600 + minutes * 60) * if sign == Sign::Minus { -1 } else { 1 };
+    Some(Time {
+        seconds_since_unix_epoch: seconds,
+        offset_in_seconds,
+        sign,
+    })
+}
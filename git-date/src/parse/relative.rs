@@ -0,0 +1,403 @@
+//! A port of git's `approxidate()`, which understands relative and approximate dates by looking at one word or number
+//! at a time, ignoring everything it doesn't know.
+use std::{
+    convert::TryInto,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+
+use crate::Time;
+
+const DAY: i64 = 24 * 60 * 60;
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "sundays",
+    "mondays",
+    "tuesdays",
+    "wednesdays",
+    "thursdays",
+    "fridays",
+    "saturdays",
+];
+
+const NUMBERS: [&str; 11] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+];
+
+const UNITS: [(&str, i64); 5] = [
+    ("seconds", 1),
+    ("minutes", 60),
+    ("hours", 60 * 60),
+    ("days", DAY),
+    ("weeks", 7 * DAY),
+];
+
+pub(crate) fn parse(input: &str, now: SystemTime) -> Option<Time> {
+    let now: i64 = now.duration_since(UNIX_EPOCH).ok()?.as_secs().try_into().ok()?;
+    let offset = OffsetDateTime::from_unix_timestamp(now)
+        .ok()
+        .and_then(|now| UtcOffset::local_offset_at(now).ok())
+        .unwrap_or(UtcOffset::UTC);
+    let now = Tm::at(now, offset)?;
+    let mut state = State {
+        tm: Tm {
+            year: None,
+            month: None,
+            day: None,
+            ..now
+        },
+        now,
+        offset,
+        number: 0,
+        touched: false,
+    };
+
+    let bytes = input.as_bytes();
+    let mut pos = 0;
+    while let Some(byte) = bytes.get(pos) {
+        if byte.is_ascii_digit() {
+            state.pending_number();
+            pos += state.digits(&input[pos..])?;
+            state.touched = true;
+        } else if byte.is_ascii_alphabetic() {
+            let end = bytes[pos..]
+                .iter()
+                .position(|b| !b.is_ascii_alphabetic())
+                .map_or(bytes.len(), |len| pos + len);
+            state.word(&input[pos..end])?;
+            pos = end;
+        } else {
+            pos += 1;
+        }
+    }
+    state.pending_number();
+    if !state.touched {
+        return None;
+    }
+    let seconds = state.update(0)?;
+    Some(Time::new(seconds.try_into().ok()?, offset.whole_seconds()))
+}
+
+/// A broken-down time like `struct tm`, with date fields that may not be set yet.
+#[derive(Clone, Copy)]
+struct Tm {
+    year: Option<i32>,
+    /// The month from 1 to 12.
+    month: Option<u8>,
+    /// The day of the month, which may be larger than the amount of days in the month to overflow into the next one.
+    day: Option<u8>,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    /// The days since sunday.
+    weekday: u8,
+}
+
+impl Tm {
+    fn at(seconds: i64, offset: UtcOffset) -> Option<Self> {
+        let time = OffsetDateTime::from_unix_timestamp(seconds).ok()?.to_offset(offset);
+        Some(Tm {
+            year: Some(time.year()),
+            month: Some(time.month() as u8),
+            day: Some(time.day()),
+            hour: time.hour(),
+            minute: time.minute(),
+            second: time.second(),
+            weekday: time.weekday().number_days_from_sunday(),
+        })
+    }
+
+    /// Like `mktime()`, return the seconds since epoch, normalizing days and times beyond their range.
+    fn seconds(&self, offset: UtcOffset) -> Option<i64> {
+        let month: Month = self.month?.try_into().ok()?;
+        let midnight = PrimitiveDateTime::new(
+            Date::from_calendar_date(self.year?, month, 1).ok()?,
+            time::Time::MIDNIGHT,
+        )
+        .assume_offset(offset)
+        .unix_timestamp();
+        Some(
+            midnight
+                + (self.day? as i64 - 1) * DAY
+                + self.hour as i64 * 60 * 60
+                + self.minute as i64 * 60
+                + self.second as i64,
+        )
+    }
+}
+
+struct State {
+    tm: Tm,
+    now: Tm,
+    offset: UtcOffset,
+    /// A number we have seen but didn't use yet, or 0.
+    number: i64,
+    /// If true, we understood at least one part of the input.
+    touched: bool,
+}
+
+impl State {
+    /// Fill in unset date fields from `now` and go back `seconds` in time, returning the seconds since epoch.
+    fn update(&mut self, seconds: i64) -> Option<i64> {
+        let (tm, now) = (&mut self.tm, &self.now);
+        if tm.day.is_none() {
+            tm.day = now.day;
+        }
+        if tm.month.is_none() {
+            tm.month = now.month;
+        }
+        if tm.year.is_none() {
+            tm.year = now.year;
+            if tm.month > now.month {
+                tm.year = tm.year.map(|year| year - 1);
+            }
+        }
+        let seconds = tm.seconds(self.offset)? - seconds;
+        self.tm = Tm::at(seconds, self.offset)?;
+        Some(seconds)
+    }
+
+    /// Use a number we didn't know what to do with as day, month or year, whichever isn't set yet.
+    fn pending_number(&mut self) {
+        let number = std::mem::take(&mut self.number);
+        if number == 0 {
+            return;
+        }
+        let tm = &mut self.tm;
+        if tm.day.is_none() && number < 32 {
+            tm.day = Some(number as u8);
+        } else if tm.month.is_none() && number < 13 {
+            tm.month = Some(number as u8);
+        } else if tm.year.is_none() {
+            if number > 1969 && number < 2100 {
+                tm.year = Some(number as i32);
+            } else if number > 69 && number < 100 {
+                tm.year = Some(1900 + number as i32);
+            } else if number < 38 {
+                tm.year = Some(2000 + number as i32);
+            }
+        }
+    }
+
+    /// Set the time of day to `hour` o'clock, yesterday if it would otherwise be in the future.
+    fn time_of_day(&mut self, hour: u8) -> Option<()> {
+        self.pending_number();
+        if self.tm.hour < hour {
+            self.update(DAY)?;
+        }
+        self.tm.hour = hour;
+        self.tm.minute = 0;
+        self.tm.second = 0;
+        Some(())
+    }
+
+    /// Switch to the hour before or after noon, using a pending number as hour if there is one.
+    fn half_day(&mut self, after_noon: bool) {
+        let mut hour = self.tm.hour as i64;
+        let number = std::mem::take(&mut self.number);
+        if number != 0 {
+            hour = number;
+            self.tm.minute = 0;
+            self.tm.second = 0;
+        }
+        self.tm.hour = (hour % 12) as u8 + if after_noon { 12 } else { 0 };
+    }
+
+    fn special(&mut self, word: &str) -> Option<bool> {
+        match word {
+            "yesterday" => {
+                self.number = 0;
+                self.update(DAY)?;
+            }
+            "noon" => self.time_of_day(12)?,
+            "midnight" => self.time_of_day(0)?,
+            "tea" => self.time_of_day(17)?,
+            "pm" => self.half_day(true),
+            "am" => self.half_day(false),
+            "never" => {
+                self.tm = Tm::at(0, self.offset)?;
+                self.number = 0;
+            }
+            "now" => {
+                self.number = 0;
+                self.update(0)?;
+            }
+            _ => return Some(false),
+        }
+        Some(true)
+    }
+
+    fn word(&mut self, word: &str) -> Option<()> {
+        let word = word.to_ascii_lowercase();
+        let word = word.as_str();
+        if let Some(month) = MONTHS.iter().position(|name| prefix_len(word, name) >= 3) {
+            self.tm.month = Some(month as u8 + 1);
+            self.touched = true;
+            return Some(());
+        }
+        if self.special(word)? {
+            self.touched = true;
+            return Some(());
+        }
+
+        if self.number == 0 {
+            if let Some(number) = NUMBERS
+                .iter()
+                .position(|name| *name == word)
+                .filter(|number| *number > 0)
+            {
+                self.number = number as i64;
+                self.touched = true;
+            } else if word == "last" {
+                self.number = 1;
+                self.touched = true;
+            }
+            return Some(());
+        }
+
+        if let Some((_, seconds)) = UNITS.iter().find(|(name, _)| prefix_len(word, name) >= name.len() - 1) {
+            let number = std::mem::take(&mut self.number);
+            self.update(seconds * number)?;
+            self.touched = true;
+            return Some(());
+        }
+
+        if let Some(weekday) = WEEKDAYS.iter().position(|name| prefix_len(word, name) >= 3) {
+            let mut weeks = std::mem::take(&mut self.number) - 1;
+            let mut days = self.tm.weekday as i64 - weekday as i64;
+            if days <= 0 {
+                weeks += 1;
+            }
+            days += 7 * weeks;
+            self.update(days * DAY)?;
+            self.touched = true;
+            return Some(());
+        }
+
+        if prefix_len(word, "months") >= 5 {
+            self.update(0)?;
+            let mut month = self.tm.month? as i64 - 1 - std::mem::take(&mut self.number);
+            while month < 0 {
+                month += 12;
+                self.tm.year = self.tm.year.map(|year| year - 1);
+            }
+            self.tm.month = Some(month as u8 + 1);
+            self.touched = true;
+        } else if prefix_len(word, "years") >= 4 {
+            self.update(0)?;
+            let number = std::mem::take(&mut self.number);
+            self.tm.year = self.tm.year.map(|year| year - number as i32);
+            self.touched = true;
+        }
+        Some(())
+    }
+
+    /// Parse the number at the start of `input`, which might be part of a date or time, and return the amount of bytes used.
+    fn digits(&mut self, input: &str) -> Option<usize> {
+        let (number, len) = leading_number(input)?;
+        let bytes = input.as_bytes();
+        if let Some(separator) = bytes
+            .get(len)
+            .copied()
+            .filter(|b| matches!(b, b':' | b'.' | b'/' | b'-'))
+        {
+            if matches!(bytes.get(len + 1), Some(b) if b.is_ascii_digit()) {
+                if let Some(len) = self.date_or_time(number, separator, input, len) {
+                    return Some(len);
+                }
+            }
+        }
+        // Accept zero-padding only for small numbers, like in "Dec 02", but never "Dec 0002".
+        if !input.starts_with('0') || len <= 2 {
+            self.number = number;
+        }
+        Some(len)
+    }
+
+    /// Parse `hh:mm[:ss]` or dates like `yyyy-mm-dd`, `mm/dd/yy` or `dd.mm.yyyy` where the first number was already
+    /// parsed into `num` with `len` bytes, followed by `separator`.
+    /// Return the amount of bytes used if it was a valid time or date.
+    fn date_or_time(&mut self, num: i64, separator: u8, input: &str, len: usize) -> Option<usize> {
+        let (num2, len2) = leading_number(&input[len + 1..])?;
+        let mut end = len + 1 + len2;
+        let mut num3 = -1;
+        let bytes = input.as_bytes();
+        if bytes.get(end) == Some(&separator) && matches!(bytes.get(end + 1), Some(b) if b.is_ascii_digit()) {
+            let (number, len3) = leading_number(&input[end + 1..])?;
+            num3 = number;
+            end += 1 + len3;
+        }
+        let is_valid = if separator == b':' {
+            let num3 = num3.max(0);
+            let is_valid = num < 25 && num2 < 60 && num3 <= 60;
+            if is_valid {
+                self.tm.hour = num as u8;
+                self.tm.minute = num2 as u8;
+                self.tm.second = num3 as u8;
+            }
+            is_valid
+        } else {
+            (num > 70 && (self.date(num, num2, num3) || self.date(num, num3, num2)))
+                // Our eastern European friends say dd.mm.yy[yy] is the norm there, so try that first.
+                || (separator == b'.' && self.date(num3, num2, num))
+                || self.date(num3, num, num2)
+                || self.date(num3, num2, num)
+        };
+        if is_valid {
+            Some(end)
+        } else {
+            None
+        }
+    }
+
+    /// Set the given date if it is valid, with `year` being -1 if it wasn't specified.
+    fn date(&mut self, year: i64, month: i64, day: i64) -> bool {
+        if !(1..13).contains(&month) || !(1..32).contains(&day) {
+            return false;
+        }
+        let year = match year {
+            -1 => None,
+            1970..=2099 => Some(year),
+            71..=99 => Some(1900 + year),
+            0..=37 => Some(2000 + year),
+            _ => return false,
+        };
+        self.tm.month = Some(month as u8);
+        self.tm.day = Some(day as u8);
+        if let Some(year) = year {
+            self.tm.year = Some(year as i32);
+        }
+        true
+    }
+}
+
+/// Return the number at the beginning of `input` along with the amount of its digits.
+fn leading_number(input: &str) -> Option<(i64, usize)> {
+    let len = input.bytes().take_while(u8::is_ascii_digit).count();
+    Some((input[..len].parse().ok()?, len))
+}
+
+/// Return the length of `word` if it is a prefix of `name`, or 0.
+fn prefix_len(word: &str, name: &str) -> usize {
+    if name.starts_with(word) {
+        word.len()
+    } else {
+        0
+    }
+}
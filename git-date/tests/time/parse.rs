@@ -1,14 +1,120 @@
-use git_date::time::Sign;
-use git_date::Time;
+use std::time::{Duration, SystemTime};
+
+use git_date::{time::Sign, Time};
 
 #[test]
-fn special_time_is_ok_for_now() {
+fn strict_formats() {
+    for (input, expected) in [
+        ("1660874655 +0800", Time::new(1660874655, 28800)),
+        ("@1660874655", Time::new(1660874655, 0)),
+        ("1660874655", Time::new(1660874655, 0)),
+        ("Thu, 18 Aug 2022 12:45:06 +0800", Time::new(1660797906, 28800)),
+        ("2022-08-17 22:04:58 +0200", Time::new(1660766698, 7200)),
+        ("2022-08-17T21:43:13+08:00", Time::new(1660743793, 28800)),
+        ("Thu Sep 04 2022 10:45:06 -0400", Time::new(1662302706, -14400)),
+    ] {
+        assert_eq!(git_date::parse(input, None), Some(expected), "{}", input);
+    }
+}
+
+#[test]
+fn raw_format_keeps_negative_zero_offsets() {
     assert_eq!(
-        git_date::parse("1979-02-26 18:30:00").unwrap(),
+        git_date::parse("500 -0000", None).unwrap(),
         Time {
-            seconds_since_unix_epoch: 42,
-            offset_in_seconds: 1800,
-            sign: Sign::Plus,
+            seconds_since_unix_epoch: 500,
+            offset_in_seconds: 0,
+            sign: Sign::Minus,
         }
     );
 }
+
+#[test]
+fn relative_dates_need_the_current_time() {
+    assert_eq!(git_date::parse("2 weeks ago", None), None);
+    assert_eq!(git_date::parse("1979-02-26 18:30:00", None), None);
+}
+
+mod relative {
+    use super::*;
+
+    /// Wednesday, 2022-08-17 15:30:00 UTC
+    fn now() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1660750200)
+    }
+
+    fn parse(input: &str) -> Option<String> {
+        git_date::parse(input, Some(now())).map(|time| time.format(git_date::time::format::ISO8601))
+    }
+
+    #[test]
+    fn durations_ago() {
+        for (input, expected) in [
+            ("now", "2022-08-17 15:30:00"),
+            ("2 weeks ago", "2022-08-03 15:30:00"),
+            ("2.weeks.ago", "2022-08-03 15:30:00"),
+            ("two weeks ago", "2022-08-03 15:30:00"),
+            ("1 day ago", "2022-08-16 15:30:00"),
+            ("90 minutes ago", "2022-08-17 14:00:00"),
+            ("3 months ago", "2022-05-17 15:30:00"),
+            ("last year", "2021-08-17 15:30:00"),
+            ("last week", "2022-08-10 15:30:00"),
+        ] {
+            assert_eq!(
+                parse(input).as_deref(),
+                Some(&*format!("{} +0000", expected)),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn named_days_and_times() {
+        for (input, expected) in [
+            ("yesterday", "2022-08-16 15:30:00"),
+            ("noon yesterday", "2022-08-16 12:00:00"),
+            ("midnight", "2022-08-17 00:00:00"),
+            ("tea", "2022-08-16 17:00:00"),
+            ("5pm", "2022-08-17 17:00:00"),
+            ("10am", "2022-08-17 10:00:00"),
+            ("last tuesday", "2022-08-16 15:30:00"),
+            ("noon last tuesday", "2022-08-16 12:00:00"),
+            ("last wednesday", "2022-08-10 15:30:00"),
+            ("never", "1970-01-01 00:00:00"),
+        ] {
+            assert_eq!(
+                parse(input).as_deref(),
+                Some(&*format!("{} +0000", expected)),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn approximate_dates() {
+        for (input, expected) in [
+            ("1979-02-26 18:30:00", "1979-02-26 18:30:00"),
+            ("aug 1", "2022-08-01 15:30:00"),
+            ("1 August", "2022-08-01 15:30:00"),
+            ("12/25", "2021-12-25 15:30:00"),
+            ("17.08.2022 9:15", "2022-08-17 09:15:00"),
+            ("2022-08-01 noon", "2022-08-01 12:00:00"),
+        ] {
+            assert_eq!(
+                parse(input).as_deref(),
+                Some(&*format!("{} +0000", expected)),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn nothing_understood_is_an_error() {
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("garbage"), None);
+        assert_eq!(parse("today"), None, "just like in git, this isn't a date");
+    }
+}
@@ -90,6 +90,91 @@ pub fn open_options_no_follow() -> std::fs::OpenOptions {
     options
 }
 
+///
+pub mod fsync {
+    use std::path::Path;
+
+    /// The way data is flushed to permanent storage, similar to `core.fsyncMethod`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Method {
+        /// Flush file data and metadata, equivalent to calling `fsync()`.
+        Fsync,
+        /// Flush file data only, equivalent to `fdatasync()`, which may be faster but can leave metadata like the
+        /// modification time behind.
+        WriteoutOnly,
+    }
+
+    impl Default for Method {
+        fn default() -> Self {
+            Method::Fsync
+        }
+    }
+
+    /// Which kinds of files to flush to permanent storage after writing them, similar to `core.fsync`.
+    ///
+    /// Flushing a file also flushes the directory it was placed in so its name survives as well.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Policy {
+        /// Flush newly written loose objects.
+        pub loose_objects: bool,
+        /// Flush newly written packs and their indices.
+        pub packs: bool,
+        /// Flush loose references, reflogs and the packed-refs file after changing them.
+        pub refs: bool,
+        /// Flush the index after writing it.
+        pub index: bool,
+        /// The way to flush data.
+        pub method: Method,
+    }
+
+    impl Policy {
+        /// A policy which never flushes, leaving it to the operating system to write data eventually. This is the default.
+        pub fn none() -> Self {
+            Policy::default()
+        }
+
+        /// A policy which flushes everything we write.
+        pub fn everything() -> Self {
+            Policy {
+                loose_objects: true,
+                packs: true,
+                refs: true,
+                index: true,
+                method: Method::default(),
+            }
+        }
+
+        /// Return `Some(method)` if `enabled` is `true`, useful to pass one of our fields to writers.
+        pub fn method_if(&self, enabled: bool) -> Option<Method> {
+            enabled.then(|| self.method)
+        }
+    }
+
+    /// Flush the data of `file` to permanent storage using `method`.
+    pub fn file(file: &std::fs::File, method: Method) -> std::io::Result<()> {
+        match method {
+            Method::Fsync => file.sync_all(),
+            Method::WriteoutOnly => file.sync_data(),
+        }
+    }
+
+    /// Flush the entries of the directory at `path` to permanent storage, so that files created, renamed or removed in it
+    /// survive a power loss.
+    ///
+    /// Note: only effective on unix, as other platforms don't support opening directories like this.
+    pub fn directory(path: &Path) -> std::io::Result<()> {
+        #[cfg(unix)]
+        {
+            std::fs::File::open(path)?.sync_all()
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = path;
+            Ok(())
+        }
+    }
+}
+
 mod snapshot {
     use std::ops::Deref;
 
@@ -3,6 +3,8 @@
 //! With the `fast-sha1` feature, the `Sha1` hash type will use a more elaborate implementation utilizing hardware support
 //! in case it is available. Otherwise the `rustsha1` feature should be set. `fast-sha1` will take precedence.
 //! Otherwise, a minimal yet performant implementation is used instead for a decent trade-off between compile times and run-time performance.
+//!
+//! The `Sha256` hash type is available along with `Sha1`, using a pure Rust implementation.
 #[cfg(all(feature = "rustsha1", not(feature = "fast-sha1")))]
 mod _impl {
     use super::Sha1Digest;
@@ -52,6 +54,55 @@ mod _impl {
 #[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
 pub use _impl::Sha1;
 
+/// A 32 bytes digest produced by a [`Sha256`] hash implementation.
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+pub type Sha256Digest = [u8; 32];
+
+/// A implementation of the Sha256 hash, which can be used once.
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+#[derive(Default, Clone)]
+pub struct Sha256(sha2::Sha256);
+
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+impl Sha256 {
+    /// Digest the given `bytes`.
+    pub fn update(&mut self, bytes: &[u8]) {
+        sha2::Digest::update(&mut self.0, bytes)
+    }
+    /// Finalize the hash and produce a digest.
+    pub fn digest(self) -> Sha256Digest {
+        sha2::Digest::finalize(self.0).into()
+    }
+}
+
+/// A hash implementation for any of the supported [kinds of hashes][git_hash::Kind], as produced by [`hasher()`].
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+#[derive(Clone)]
+pub enum Hasher {
+    /// The Sha1 hash.
+    Sha1(Sha1),
+    /// The Sha256 hash.
+    Sha256(Sha256),
+}
+
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+impl Hasher {
+    /// Digest the given `bytes`.
+    pub fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Hasher::Sha1(h) => h.update(bytes),
+            Hasher::Sha256(h) => h.update(bytes),
+        }
+    }
+    /// Finalize the hash and produce an object id of the corresponding kind.
+    pub fn digest(self) -> git_hash::ObjectId {
+        match self {
+            Hasher::Sha1(h) => h.digest().into(),
+            Hasher::Sha256(h) => git_hash::ObjectId::from(&h.digest()[..]),
+        }
+    }
+}
+
 /// Compute a CRC32 hash from the given `bytes`, returning the CRC32 hash.
 ///
 /// When calling this function for the first time, `previous_value` should be `0`. Otherwise it
@@ -76,9 +127,13 @@ pub fn crc32(bytes: &[u8]) -> u32 {
 
 /// Produce a hasher suitable for the given kind of hash.
 #[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
-pub fn hasher(kind: git_hash::Kind) -> Sha1 {
+pub fn hasher(kind: git_hash::Kind) -> Hasher {
     match kind {
-        git_hash::Kind::Sha1 => Sha1::default(),
+        git_hash::Kind::Sha1 => Hasher::Sha1(Sha1::default()),
+        // `git-hash` may have been built with `sha256` support by another crate without enabling it here, so
+        // we can't name the variant.
+        #[allow(unreachable_patterns)]
+        _ => Hasher::Sha256(Sha256::default()),
     }
 }
 
@@ -139,19 +194,19 @@ pub fn bytes(
         }
     }
 
-    let id = git_hash::ObjectId::from(hasher.digest());
+    let id = hasher.digest();
     progress.show_throughput(start);
     Ok(id)
 }
 
 #[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
 mod write {
-    use crate::hash::Sha1;
+    use crate::hash::Hasher;
 
     /// A utility to automatically generate a hash while writing into an inner writer.
     pub struct Write<T> {
         /// The hash implementation.
-        pub hash: Sha1,
+        pub hash: Hasher,
         /// The inner writer.
         pub inner: T,
     }
@@ -177,11 +232,9 @@ mod write {
     {
         /// Create a new hash writer which hashes all bytes written to `inner` with a hash of `kind`.
         pub fn new(inner: T, object_hash: git_hash::Kind) -> Self {
-            match object_hash {
-                git_hash::Kind::Sha1 => Write {
-                    inner,
-                    hash: Sha1::default(),
-                },
+            Write {
+                inner,
+                hash: super::hasher(object_hash),
             }
         }
     }
@@ -11,3 +11,25 @@ fn size_of_sha1() {
 fn size_of_sha1() {
     assert_eq!(std::mem::size_of::<Sha1>(), 104)
 }
+
+#[test]
+fn hasher_sha1() {
+    assert_digest(git_hash::Kind::Sha1, "a9993e364706816aba3e25717850c26c9cd0d89d");
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn hasher_sha256() {
+    assert_digest(
+        git_hash::Kind::Sha256,
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+    );
+}
+
+fn assert_digest(kind: git_hash::Kind, expected: &str) {
+    let mut hasher = git_features::hash::hasher(kind);
+    hasher.update(b"abc");
+    let id = hasher.digest();
+    assert_eq!(id.kind(), kind);
+    assert_eq!(id.to_string(), expected);
+}
@@ -1,5 +1,7 @@
 use std::{convert::TryInto, fmt};
 
+#[cfg(feature = "sha256")]
+use crate::SIZE_OF_SHA256_DIGEST;
 use crate::{ObjectId, SIZE_OF_SHA1_DIGEST};
 
 /// A borrowed reference to a hash identifying objects.
@@ -45,6 +47,8 @@ impl fmt::Debug for oid {
             "{}({})",
             match self.kind() {
                 crate::Kind::Sha1 => "Sha1",
+                #[cfg(feature = "sha256")]
+                crate::Kind::Sha256 => "Sha256",
             },
             self.to_hex(),
         )
@@ -68,7 +72,7 @@ impl oid {
     #[inline]
     pub fn try_from_bytes(digest: &[u8]) -> Result<&Self, Error> {
         match digest.len() {
-            20 => Ok(
+            len if len == 20 || (cfg!(feature = "sha256") && len == 32) => Ok(
                 #[allow(unsafe_code)]
                 unsafe {
                     &*(digest as *const [u8] as *const oid)
@@ -158,6 +162,13 @@ impl oid {
     pub(crate) fn null_sha1() -> &'static Self {
         oid::from_bytes([0u8; SIZE_OF_SHA1_DIGEST].as_ref())
     }
+
+    #[cfg(feature = "sha256")]
+    /// Returns a Sha256 digest with all bytes being initialized to zero.
+    #[inline]
+    pub(crate) fn null_sha256() -> &'static Self {
+        oid::from_bytes([0u8; SIZE_OF_SHA256_DIGEST].as_ref())
+    }
 }
 
 impl AsRef<oid> for &oid {
@@ -172,6 +183,8 @@ impl ToOwned for oid {
     fn to_owned(&self) -> Self::Owned {
         match self.kind() {
             crate::Kind::Sha1 => crate::ObjectId::Sha1(self.bytes.try_into().expect("no bug in hash detection")),
+            #[cfg(feature = "sha256")]
+            crate::Kind::Sha256 => crate::ObjectId::Sha256(self.bytes.try_into().expect("no bug in hash detection")),
         }
     }
 }
@@ -39,7 +39,7 @@ pub mod decode {
 
     /// Hash decoding
     impl ObjectId {
-        /// Create an instance from a `buffer` of 40 bytes encoded with hexadecimal notation.
+        /// Create an instance from a `buffer` of 40 bytes (Sha1) or 64 bytes (Sha256) encoded with hexadecimal notation.
         ///
         /// Such a buffer can be obtained using [`oid::write_hex_to(buffer)`][super::oid::write_hex_to()]
         pub fn from_hex(buffer: &[u8]) -> Result<ObjectId, Error> {
@@ -48,6 +48,10 @@ pub mod decode {
                 40 => Ok(ObjectId::Sha1(
                     <[u8; 20]>::from_hex(buffer).expect("our length check is correct thus we can decode hex"),
                 )),
+                #[cfg(feature = "sha256")]
+                64 => Ok(ObjectId::Sha256(
+                    <[u8; 32]>::from_hex(buffer).expect("our length check is correct thus we can decode hex"),
+                )),
                 len => Err(Error::InvalidHexEncodingLength(len)),
             }
         }
@@ -64,6 +68,9 @@ pub mod decode {
 
 /// The size of a SHA1 hash digest in bytes
 const SIZE_OF_SHA1_DIGEST: usize = 20;
+#[cfg(feature = "sha256")]
+/// The size of a SHA256 hash digest in bytes
+const SIZE_OF_SHA256_DIGEST: usize = 32;
 
 /// Denotes the kind of function to produce a `Id`
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
@@ -71,6 +78,9 @@ const SIZE_OF_SHA1_DIGEST: usize = 20;
 pub enum Kind {
     /// The Sha1 hash with 160 bits.
     Sha1 = 1,
+    #[cfg(feature = "sha256")]
+    /// The Sha256 hash with 256 bits.
+    Sha256 = 2,
 }
 
 impl Default for Kind {
@@ -85,6 +95,8 @@ impl TryFrom<u8> for Kind {
     fn try_from(value: u8) -> Result<Self, Self::Error> {
         Ok(match value {
             1 => Kind::Sha1,
+            #[cfg(feature = "sha256")]
+            2 => Kind::Sha256,
             unknown => return Err(unknown),
         })
     }
@@ -96,6 +108,8 @@ impl FromStr for Kind {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
             "sha1" | "SHA1" => Kind::Sha1,
+            #[cfg(feature = "sha256")]
+            "sha256" | "SHA256" => Kind::Sha256,
             other => return Err(other.into()),
         })
     }
@@ -105,6 +119,8 @@ impl std::fmt::Display for Kind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Kind::Sha1 => f.write_str("SHA1"),
+            #[cfg(feature = "sha256")]
+            Kind::Sha256 => f.write_str("SHA256"),
         }
     }
 }
@@ -119,7 +135,10 @@ impl Kind {
     /// Returns the longest hash we support
     #[inline]
     pub const fn longest() -> Self {
-        Self::Sha1
+        #[cfg(feature = "sha256")]
+        return Self::Sha256;
+        #[cfg(not(feature = "sha256"))]
+        return Self::Sha1;
     }
 
     /// Returns a buffer suitable to hold the longest possible hash in hex.
@@ -139,6 +158,8 @@ impl Kind {
     pub const fn len_in_hex(&self) -> usize {
         match self {
             Kind::Sha1 => 40,
+            #[cfg(feature = "sha256")]
+            Kind::Sha256 => 64,
         }
     }
     /// Returns the amount of bytes taken up by the hash of the current kind
@@ -146,6 +167,8 @@ impl Kind {
     pub const fn len_in_bytes(&self) -> usize {
         match self {
             Kind::Sha1 => 20,
+            #[cfg(feature = "sha256")]
+            Kind::Sha256 => 32,
         }
     }
 
@@ -155,6 +178,8 @@ impl Kind {
     pub const fn from_hex_len(hex_len: usize) -> Option<Self> {
         Some(match hex_len {
             0..=40 => Kind::Sha1,
+            #[cfg(feature = "sha256")]
+            41..=64 => Kind::Sha256,
             _ => return None,
         })
     }
@@ -171,6 +196,8 @@ impl Kind {
     pub(crate) fn from_len_in_bytes(bytes: usize) -> Self {
         match bytes {
             20 => Kind::Sha1,
+            #[cfg(feature = "sha256")]
+            32 => Kind::Sha256,
             _ => panic!("BUG: must be called only with valid hash lengths produced by len_in_bytes()"),
         }
     }
@@ -180,6 +207,8 @@ impl Kind {
     pub fn null_ref(&self) -> &'static oid {
         match self {
             Kind::Sha1 => oid::null_sha1(),
+            #[cfg(feature = "sha256")]
+            Kind::Sha256 => oid::null_sha256(),
         }
     }
 
@@ -188,6 +217,8 @@ impl Kind {
     pub const fn null(&self) -> ObjectId {
         match self {
             Kind::Sha1 => ObjectId::null_sha1(),
+            #[cfg(feature = "sha256")]
+            Kind::Sha256 => ObjectId::null_sha256(),
         }
     }
 }
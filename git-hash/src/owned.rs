@@ -1,5 +1,7 @@
 use std::{borrow::Borrow, convert::TryInto, fmt, ops::Deref};
 
+#[cfg(feature = "sha256")]
+use crate::SIZE_OF_SHA256_DIGEST;
 use crate::{borrowed::oid, Kind, SIZE_OF_SHA1_DIGEST};
 
 /// An partial owned hash possibly identifying an object uniquely,
@@ -15,17 +17,22 @@ pub struct Prefix {
 pub mod prefix;
 
 /// An owned hash identifying objects, most commonly Sha1
-#[derive(PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
+#[derive(PartialEq, Eq, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub enum ObjectId {
     /// A SHA 1 hash digest
     Sha1([u8; SIZE_OF_SHA1_DIGEST]),
+    #[cfg(feature = "sha256")]
+    /// A SHA 256 hash digest
+    Sha256([u8; SIZE_OF_SHA256_DIGEST]),
 }
 
 impl std::fmt::Debug for ObjectId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ObjectId::Sha1(_hash) => f.write_str("Sha1(")?,
+            #[cfg(feature = "sha256")]
+            ObjectId::Sha256(_hash) => f.write_str("Sha256(")?,
         }
         for b in self.as_bytes() {
             write!(f, "{:02x}", b)?;
@@ -41,6 +48,8 @@ impl ObjectId {
     pub fn kind(&self) -> crate::Kind {
         match self {
             ObjectId::Sha1(_) => crate::Kind::Sha1,
+            #[cfg(feature = "sha256")]
+            ObjectId::Sha256(_) => crate::Kind::Sha256,
         }
     }
     /// Return the raw byte slice representing this hash
@@ -48,6 +57,8 @@ impl ObjectId {
     pub fn as_slice(&self) -> &[u8] {
         match self {
             Self::Sha1(b) => b.as_ref(),
+            #[cfg(feature = "sha256")]
+            Self::Sha256(b) => b.as_ref(),
         }
     }
     /// Return the raw mutable byte slice representing this hash
@@ -55,6 +66,8 @@ impl ObjectId {
     pub fn as_mut_slice(&mut self) -> &mut [u8] {
         match self {
             Self::Sha1(b) => b.as_mut(),
+            #[cfg(feature = "sha256")]
+            Self::Sha256(b) => b.as_mut(),
         }
     }
 
@@ -65,6 +78,10 @@ impl ObjectId {
             Kind::Sha1 => {
                 ObjectId::Sha1(*b"\x4b\x82\x5d\xc6\x42\xcb\x6e\xb9\xa0\x60\xe5\x4b\xf8\xd6\x92\x88\xfb\xee\x49\x04")
             }
+            #[cfg(feature = "sha256")]
+            Kind::Sha256 => ObjectId::Sha256(
+                *b"\x6e\xf1\x9b\x41\x22\x5c\x53\x69\xf1\xc1\x04\xd4\x5d\x8d\x85\xef\xa9\xb0\x57\xb5\x3b\x14\xb4\xb9\xb9\x39\xdd\x74\xde\xcc\x53\x21",
+            ),
         }
     }
 
@@ -73,6 +90,8 @@ impl ObjectId {
     pub fn is_null(&self) -> bool {
         match self {
             ObjectId::Sha1(digest) => &digest[..] == oid::null_sha1().as_bytes(),
+            #[cfg(feature = "sha256")]
+            ObjectId::Sha256(digest) => &digest[..] == oid::null_sha256().as_bytes(),
         }
     }
 
@@ -81,6 +100,8 @@ impl ObjectId {
     pub const fn null(kind: crate::Kind) -> ObjectId {
         match kind {
             crate::Kind::Sha1 => Self::null_sha1(),
+            #[cfg(feature = "sha256")]
+            crate::Kind::Sha256 => Self::null_sha256(),
         }
     }
 }
@@ -110,16 +131,45 @@ impl ObjectId {
     }
 }
 
+#[cfg(feature = "sha256")]
+/// Sha256 hash specific methods
+impl ObjectId {
+    /// Instantiate an Digest from a slice 32 borrowed bytes of a Sha256 digest.
+    ///
+    /// Panics of the slice doesn't have a length of 32.
+    #[inline]
+    pub(crate) fn from_32_bytes(b: &[u8]) -> ObjectId {
+        let mut id = [0; SIZE_OF_SHA256_DIGEST];
+        id.copy_from_slice(b);
+        ObjectId::Sha256(id)
+    }
+
+    /// Returns an Digest representing a Sha256 with whose memory is zeroed.
+    #[inline]
+    pub(crate) const fn null_sha256() -> ObjectId {
+        ObjectId::Sha256([0u8; 32])
+    }
+}
+
 impl From<[u8; SIZE_OF_SHA1_DIGEST]> for ObjectId {
     fn from(v: [u8; 20]) -> Self {
         Self::new_sha1(v)
     }
 }
 
+#[cfg(feature = "sha256")]
+impl From<[u8; SIZE_OF_SHA256_DIGEST]> for ObjectId {
+    fn from(v: [u8; 32]) -> Self {
+        ObjectId::Sha256(v)
+    }
+}
+
 impl From<&[u8]> for ObjectId {
     fn from(v: &[u8]) -> Self {
         match v.len() {
             20 => Self::Sha1(v.try_into().expect("prior length validation")),
+            #[cfg(feature = "sha256")]
+            32 => Self::Sha256(v.try_into().expect("prior length validation")),
             other => panic!("BUG: unsupported hash len: {}", other),
         }
     }
@@ -129,10 +179,31 @@ impl From<&crate::oid> for ObjectId {
     fn from(v: &oid) -> Self {
         match v.kind() {
             crate::Kind::Sha1 => ObjectId::from_20_bytes(v.as_bytes()),
+            #[cfg(feature = "sha256")]
+            crate::Kind::Sha256 => ObjectId::from_32_bytes(v.as_bytes()),
         }
     }
 }
 
+// Hash and order by bytes only to be consistent with `oid`, as required by `Borrow<oid>`.
+impl std::hash::Hash for ObjectId {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl PartialOrd for ObjectId {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ObjectId {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
 impl Deref for ObjectId {
     type Target = oid;
 
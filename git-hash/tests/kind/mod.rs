@@ -9,8 +9,53 @@ mod from_hex_len {
         assert_eq!(Kind::from_hex_len(40), Some(Kind::Sha1));
     }
 
+    #[test]
+    #[cfg(feature = "sha256")]
+    fn some_sha256() {
+        assert_eq!(Kind::from_hex_len(41), Some(Kind::Sha256));
+        assert_eq!(Kind::from_hex_len(64), Some(Kind::Sha256));
+    }
+
     #[test]
     fn none_if_there_is_no_fit() {
         assert_eq!(Kind::from_hex_len(65), None);
     }
 }
+
+#[cfg(feature = "sha256")]
+mod sha256 {
+    use std::str::FromStr;
+
+    use git_hash::{Kind, ObjectId};
+
+    #[test]
+    fn lengths_and_names() {
+        assert_eq!(Kind::Sha256.len_in_bytes(), 32);
+        assert_eq!(Kind::Sha256.len_in_hex(), 64);
+        assert_eq!(Kind::from_str("sha256").unwrap(), Kind::Sha256);
+        assert_eq!(Kind::Sha256.to_string(), "SHA256");
+        assert_eq!(Kind::longest(), Kind::Sha256);
+    }
+
+    #[test]
+    fn ids_roundtrip_through_hex() {
+        let hex = "6ef19b41225c5369f1c104d45d8d85efa9b057b53b14b4b9b939dd74decc5321";
+        let id = ObjectId::from_hex(hex.as_bytes()).unwrap();
+        assert_eq!(id.kind(), Kind::Sha256);
+        assert_eq!(id.to_string(), hex);
+        assert_eq!(id, ObjectId::empty_tree(Kind::Sha256));
+        assert_eq!(id.as_ref().to_owned(), id, "borrowed ids know their kind by length");
+        assert!(ObjectId::null(Kind::Sha256).is_null());
+        assert_eq!(Kind::Sha256.null_ref().kind(), Kind::Sha256);
+    }
+
+    #[test]
+    fn owned_ids_can_be_looked_up_by_borrowed_ids_of_any_kind() {
+        let sha1 = ObjectId::empty_tree(Kind::Sha1);
+        let sha256 = ObjectId::empty_tree(Kind::Sha256);
+        let set: std::collections::HashSet<_> = vec![sha1, sha256].into_iter().collect();
+        assert!(set.contains(sha1.as_ref()));
+        assert!(set.contains(sha256.as_ref()));
+        assert_eq!(sha1.cmp(&sha256), sha1.as_ref().cmp(sha256.as_ref()));
+    }
+}
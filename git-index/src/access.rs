@@ -97,6 +97,55 @@ impl State {
     }
 }
 
+/// Mutation
+impl State {
+    /// Set the `id`, `mode` and `stat` of the entry at `path` with stage 0, or insert a new entry while keeping all entries sorted,
+    /// and return its index in [`entries()`][State::entries()].
+    ///
+    /// Entries of `path` in other stages are removed as the conflict is considered resolved, and so is the `tree` extension
+    /// which doesn't represent our entries anymore.
+    pub fn upsert_entry(&mut self, path: &BStr, id: git_hash::ObjectId, mode: entry::Mode, stat: entry::Stat) -> usize {
+        self.tree = None;
+        let (start, end) = self.entry_range_by_path(path);
+        if let Some(idx) = (start..end).find(|idx| self.entries[*idx].stage() == 0) {
+            self.entries.drain(idx + 1..end);
+            self.entries.drain(start..idx);
+            let entry = &mut self.entries[start];
+            entry.id = id;
+            entry.mode = mode;
+            entry.stat = stat;
+            return start;
+        }
+        let path_start = self.path_backing.len();
+        self.path_backing.extend_from_slice(path);
+        let entry = Entry {
+            stat,
+            id,
+            flags: entry::Flags::empty(),
+            mode,
+            path: path_start..self.path_backing.len(),
+        };
+        self.entries.splice(start..end, Some(entry));
+        start
+    }
+
+    /// Remove the entry at `idx` from our entries and return it, or _panic_ if the index is out of bounds.
+    ///
+    /// The `tree` extension is removed as well as it doesn't represent our entries anymore.
+    pub fn remove_entry_at_index(&mut self, idx: usize) -> Entry {
+        self.tree = None;
+        self.entries.remove(idx)
+    }
+
+    /// Return the range of indices of all entries with `path`, which is empty if there is none and marks the position at which
+    /// they would be inserted.
+    fn entry_range_by_path(&self, path: &BStr) -> (usize, usize) {
+        let start = self.entries.partition_point(|e| e.path(self) < path);
+        let end = start + self.entries[start..].partition_point(|e| e.path(self) == path);
+        (start, end)
+    }
+}
+
 /// Extensions
 impl State {
     /// Access the `tree` extension.
@@ -39,7 +39,7 @@ pub fn decode(data: &[u8], object_hash: git_hash::Kind) -> Option<usize> {
         last_chunk = Some(chunk);
     }
 
-    if hasher.digest() != checksum {
+    if hasher.digest().as_bytes() != checksum {
         return None;
     }
     // The last-to-this chunk ends where ours starts
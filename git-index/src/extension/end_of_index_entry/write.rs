@@ -24,7 +24,7 @@ pub fn write_to(
         hasher.update(&signature);
         hasher.update(&size.to_be_bytes());
     }
-    out.write_all(&hasher.digest())?;
+    out.write_all(hasher.digest().as_bytes())?;
 
     Ok(())
 }
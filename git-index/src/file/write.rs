@@ -1,15 +1,68 @@
+use git_features::{fs::fsync, hash};
+
 use crate::{write, File, Version};
-use git_features::hash;
+
+mod error {
+    /// The error returned by [File::write()][super::File::write()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("An IO error occurred while writing the index")]
+        Io(#[from] std::io::Error),
+        #[error("Could not acquire lock for index file")]
+        AcquireLock(#[from] git_lock::acquire::Error),
+        #[error("Could not commit lock for index file")]
+        CommitLock(#[from] git_lock::commit::Error<git_lock::File>),
+    }
+}
+
+pub use error::Error;
 
 impl File {
     /// Write the index to `out` with `options`, to be readable by [`File::at()`], returning the version that was actually written
     /// to retain all information of this index.
-    pub fn write_to(&self, mut out: impl std::io::Write, options: write::Options) -> std::io::Result<Version> {
+    pub fn write_to(&self, out: impl std::io::Write, options: write::Options) -> std::io::Result<Version> {
+        self.write_to_with_checksum(out, options)
+            .map(|(version, _checksum)| version)
+    }
+
+    /// Write the index to our [path][File::path] with `options` while holding a lock, and update our [checksum][File::checksum]
+    /// to match what was written.
+    ///
+    /// If `fsync` is set, the index and the directory containing it are flushed to permanent storage with the given method
+    /// before the write is considered complete.
+    pub fn write(&mut self, options: write::Options, fsync: Option<fsync::Method>) -> Result<Version, Error> {
+        let mut lock = std::io::BufWriter::new(git_lock::File::acquire_to_update_resource(
+            &self.path,
+            git_lock::acquire::Fail::Immediately,
+            None,
+        )?);
+        let (version, checksum) = self.write_to_with_checksum(&mut lock, options)?;
+        let mut lock = lock.into_inner().map_err(std::io::IntoInnerError::into_error)?;
+        if let Some(method) = fsync {
+            lock.with_mut(|file| fsync::file(file, method))?;
+        }
+        lock.commit()?;
+        if fsync.is_some() {
+            if let Some(directory) = self.path.parent() {
+                fsync::directory(directory)?;
+            }
+        }
+        self.state.version = version;
+        self.checksum = checksum;
+        Ok(version)
+    }
+
+    fn write_to_with_checksum(
+        &self,
+        mut out: impl std::io::Write,
+        options: write::Options,
+    ) -> std::io::Result<(Version, git_hash::ObjectId)> {
         let mut hasher = hash::Write::new(&mut out, options.hash_kind);
         let version = self.state.write_to(&mut hasher, options)?;
 
         let hash = hasher.hash.digest();
-        out.write_all(&hash)?;
-        Ok(version)
+        out.write_all(hash.as_slice())?;
+        Ok((version, hash))
     }
 }
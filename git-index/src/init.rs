@@ -0,0 +1,80 @@
+use bstr::BString;
+use filetime::FileTime;
+use git_object::tree::EntryMode;
+
+use crate::{entry, Entry, PathStorage, State, Version};
+
+///
+pub mod from_tree {
+    /// The error returned by [`State::from_tree()`][crate::State::from_tree()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The tree {id} could not be found")]
+        NotFound { id: git_hash::ObjectId },
+        #[error(transparent)]
+        Decode(#[from] git_object::decode::Error),
+    }
+}
+
+/// Initialization
+impl State {
+    /// Create an index state with entries for all blobs, symlinks and submodules in `tree`, recursively, using `find` to obtain
+    /// `tree` and all of its sub-trees.
+    ///
+    /// The entries have empty stat information and are sorted, and no extensions are created.
+    /// This is useful to write the index of a freshly cloned repository or to checkout a tree.
+    pub fn from_tree<Find>(tree: &git_hash::oid, mut find: Find) -> Result<Self, from_tree::Error>
+    where
+        Find: for<'a> FnMut(&git_hash::oid, &'a mut Vec<u8>) -> Option<git_object::TreeRefIter<'a>>,
+    {
+        let mut buf = Vec::new();
+        let mut entries = Vec::new();
+        let mut path_backing = PathStorage::new();
+        let mut trees = vec![(tree.to_owned(), BString::default())];
+        while let Some((tree_id, prefix)) = trees.pop() {
+            let tree = find(&tree_id, &mut buf).ok_or(from_tree::Error::NotFound { id: tree_id })?;
+            for tree_entry in tree {
+                let tree_entry = tree_entry?;
+                let mut path = prefix.clone();
+                if !path.is_empty() {
+                    path.push(b'/');
+                }
+                path.extend_from_slice(tree_entry.filename);
+                let mode = match tree_entry.mode {
+                    EntryMode::Tree => {
+                        trees.push((tree_entry.oid.to_owned(), path));
+                        continue;
+                    }
+                    EntryMode::Blob => entry::Mode::FILE,
+                    EntryMode::BlobExecutable => entry::Mode::FILE_EXECUTABLE,
+                    EntryMode::Link => entry::Mode::SYMLINK,
+                    EntryMode::Commit => entry::Mode::COMMIT,
+                };
+                let start = path_backing.len();
+                path_backing.extend_from_slice(&path);
+                entries.push(Entry {
+                    stat: entry::Stat::default(),
+                    id: tree_entry.oid.to_owned(),
+                    flags: entry::Flags::empty(),
+                    mode,
+                    path: start..path_backing.len(),
+                });
+            }
+        }
+        entries.sort_by(|a, b| a.path_in(&path_backing).cmp(b.path_in(&path_backing)));
+
+        Ok(State {
+            timestamp: FileTime::now(),
+            version: Version::V2,
+            entries,
+            path_backing,
+            is_sparse: false,
+            tree: None,
+            link: None,
+            resolve_undo: None,
+            untracked: None,
+            fs_monitor: None,
+        })
+    }
+}
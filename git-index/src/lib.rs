@@ -21,6 +21,9 @@ pub mod entry;
 
 mod access;
 
+///
+pub mod init;
+
 ///
 pub mod decode;
 
@@ -154,7 +157,7 @@ pub(crate) mod util {
 
 #[test]
 fn size_of_entry() {
-    assert_eq!(std::mem::size_of::<crate::Entry>(), 80);
+    assert_eq!(std::mem::size_of::<crate::Entry>(), 96);
 
     // the reason we have our own time is half the size.
     assert_eq!(std::mem::size_of::<crate::entry::Time>(), 8);
@@ -13,3 +13,41 @@ fn entry_by_path_and_stage() {
         assert_eq!(file.entry_by_path_and_stage(path, 0), Some(entry));
     }
 }
+
+#[test]
+fn upsert_and_remove_entries() {
+    let mut file = read::file("v4_more_files_IEOT");
+    let num_entries = file.entries().len();
+    let id = git_hash::ObjectId::null(git_hash::Kind::Sha1);
+    let existing = file.entries()[1].path(&file).to_owned();
+
+    let idx = file.upsert_entry(
+        existing.as_ref(),
+        id,
+        git_index::entry::Mode::FILE_EXECUTABLE,
+        Default::default(),
+    );
+    assert_eq!(idx, 1, "existing entries are updated in place");
+    assert_eq!(file.entries().len(), num_entries);
+    assert_eq!(file.entries()[1].id, id);
+    assert_eq!(file.entries()[1].mode, git_index::entry::Mode::FILE_EXECUTABLE);
+    assert!(file.tree().is_none(), "the tree extension is removed as it's outdated");
+
+    let idx = file.upsert_entry(
+        "a-new-file".into(),
+        id,
+        git_index::entry::Mode::FILE,
+        Default::default(),
+    );
+    assert_eq!(file.entries().len(), num_entries + 1);
+    assert_eq!(file.entries()[idx].path(&file), "a-new-file");
+    assert!(
+        file.entries().windows(2).all(|w| w[0].path(&file) < w[1].path(&file)),
+        "entries remain sorted"
+    );
+
+    let removed = file.remove_entry_at_index(idx);
+    assert_eq!(removed.path(&file), "a-new-file", "paths remain accessible");
+    assert_eq!(file.entries().len(), num_entries);
+    assert_eq!(file.entry_index_by_path_and_stage("a-new-file".into(), 0), None);
+}
@@ -115,6 +115,24 @@ fn extended_flags_automatically_upgrade_the_version_to_avoid_data_loss() -> crat
     Ok(())
 }
 
+#[test]
+fn write_to_path_updates_checksum_and_can_be_read_back() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let mut file = git_index::File::at(fixture_index_path("v2_more_files"), decode::Options::default())?;
+    file.path = dir.path().join("index");
+
+    let version = file.write(all_ext_but_eoie(), Some(git_features::fs::fsync::Method::Fsync))?;
+    assert_eq!(version, Version::V2);
+
+    let actual = git_index::File::at(&file.path, decode::Options::default())?;
+    assert_eq!(
+        actual.checksum, file.checksum,
+        "the checksum is updated to what was written"
+    );
+    compare_states(&actual, version, &file, all_ext_but_eoie(), "v2_more_files");
+    Ok(())
+}
+
 fn compare_states(actual: &State, actual_version: Version, expected: &State, options: Options, fixture: &str) {
     actual.verify_entries().expect("valid");
     actual.verify_extensions(false, no_find).expect("valid");
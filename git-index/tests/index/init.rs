@@ -0,0 +1,36 @@
+use git_index::State;
+use git_odb::FindExt;
+
+#[test]
+fn from_tree_yields_the_same_entries_as_git() {
+    for fixture in ["v2", "v2_more_files", "v4_more_files_IEOT"] {
+        let index_path = crate::fixture_index_path(fixture);
+        let expected = git_index::File::at(&index_path, git_index::decode::Options::default()).unwrap();
+        let tree_id = expected.tree().expect("tree extension is present").id;
+        let odb = git_odb::at(index_path.parent().unwrap().join("objects")).unwrap();
+
+        let actual = State::from_tree(&tree_id, |oid, buf| odb.find_tree_iter(oid, buf).ok()).unwrap();
+        assert_eq!(actual.entries().len(), expected.entries().len(), "{}", fixture);
+        for (actual_entry, expected_entry) in actual.entries().iter().zip(expected.entries()) {
+            assert_eq!(
+                actual_entry.path(&actual),
+                expected_entry.path(&expected),
+                "{}",
+                fixture
+            );
+            assert_eq!(actual_entry.id, expected_entry.id);
+            assert_eq!(actual_entry.mode, expected_entry.mode);
+            assert_eq!(actual_entry.stage(), 0);
+        }
+        assert!(actual.tree().is_none(), "extensions aren't created");
+    }
+}
+
+#[test]
+fn from_tree_fails_if_the_tree_is_missing() {
+    let empty_tree = git_hash::ObjectId::empty_tree(git_hash::Kind::Sha1);
+    match State::from_tree(&empty_tree, |_, _| None) {
+        Err(git_index::init::from_tree::Error::NotFound { id }) => assert_eq!(id, empty_tree),
+        _ => unreachable!("the tree can't be found"),
+    }
+}
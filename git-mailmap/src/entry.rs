@@ -22,6 +22,32 @@ impl<'a> Entry<'a> {
     }
 }
 
+/// Serialization
+impl<'a> Entry<'a> {
+    /// Write this entry as a single line in mailmap format to `out`, without a trailing newline.
+    ///
+    /// Mappings by name and email always write the email to map to, using the email to look for if there is no new email,
+    /// to make them readable by [`parse()`][crate::parse()].
+    pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+        if let Some(name) = self.new_name {
+            out.write_all(name)?;
+            out.write_all(b" ")?;
+        }
+        if self.new_email.is_some() || self.old_name.is_some() {
+            out.write_all(b"<")?;
+            out.write_all(self.new_email.unwrap_or(self.old_email))?;
+            out.write_all(b"> ")?;
+        }
+        if let Some(name) = self.old_name {
+            out.write_all(name)?;
+            out.write_all(b" ")?;
+        }
+        out.write_all(b"<")?;
+        out.write_all(self.old_email)?;
+        out.write_all(b">")
+    }
+}
+
 /// Constructors indicating what kind of mapping is created.
 ///
 /// Only these combinations of values are valid.
@@ -221,6 +221,47 @@ impl Snapshot {
         self
     }
 
+    /// Remove the mapping for `old_email`, or the one for `old_name` and `old_email` if `old_name` is set, returning `true`
+    /// if such a mapping existed.
+    ///
+    /// Note that removing the mapping for only `old_email` leaves all mappings by `old_name` and `old_email` in place.
+    pub fn remove(&mut self, old_email: &BStr, old_name: Option<&BStr>) -> bool {
+        let email: EncodedStringRef<'_> = old_email.into();
+        let pos = match self
+            .entries_by_old_email
+            .binary_search_by(|e| e.old_email.cmp_ref(email))
+        {
+            Ok(pos) => pos,
+            Err(_) => return false,
+        };
+        let entry = &mut self.entries_by_old_email[pos];
+        let removed = match old_name {
+            None => {
+                let had_mapping = entry.new_name.is_some() || entry.new_email.is_some();
+                entry.new_name = None;
+                entry.new_email = None;
+                had_mapping
+            }
+            Some(old_name) => {
+                let old_name: EncodedStringRef<'_> = old_name.into();
+                match entry
+                    .entries_by_old_name
+                    .binary_search_by(|e| e.old_name.cmp_ref(old_name))
+                {
+                    Ok(pos) => {
+                        entry.entries_by_old_name.remove(pos);
+                        true
+                    }
+                    Err(_) => false,
+                }
+            }
+        };
+        if entry.new_name.is_none() && entry.new_email.is_none() && entry.entries_by_old_name.is_empty() {
+            self.entries_by_old_email.remove(pos);
+        }
+        removed
+    }
+
     /// Transform our acceleration structure into a list of entries.
     ///
     /// Note that the order is different from how they were obtained initially, and are explicitly ordered by
@@ -249,6 +290,17 @@ impl Snapshot {
         out
     }
 
+    /// Write all [entries][Snapshot::entries()] to `out` in mailmap format, one per line.
+    ///
+    /// As entries are deduplicated and sorted, this can be used to normalize mailmap files, but note that comments are lost.
+    pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+        for entry in self.entries() {
+            entry.write_to(&mut out)?;
+            out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
     /// Try to resolve `signature` by its contained email and name and provide resolved/mapped names as reference.
     /// Return `None` if no such mapping was found.
     ///
@@ -1,3 +1,4 @@
+use bstr::ByteSlice;
 use git_mailmap::Snapshot;
 use git_testtools::fixture_bytes;
 
@@ -113,6 +114,54 @@ fn overwrite_entries() {
     assert_eq!(snapshot.entries().len(), 4);
 }
 
+#[test]
+fn write_to_produces_sorted_and_deduplicated_entries_which_parse_again() {
+    let mut snapshot = Snapshot::from_bytes(&fixture_bytes("typical.txt"));
+    snapshot.merge(Some(git_mailmap::Entry::change_name_by_email(
+        "Joe R. Developer",
+        "Joe@example.com",
+    )));
+    let mut buf = Vec::new();
+    snapshot.write_to(&mut buf).unwrap();
+    assert_eq!(
+        buf.as_bstr(),
+        "Jane Doe <jane@example.com> Jane <bugs@example.com>\n\
+         Joe R. Developer <joe@example.com> Joe <bugs@example.com>\n\
+         Jane Doe <jane@example.com> <jane@desktop.(none)>\n\
+         Jane Doe <jane@example.com> <jane@laptop.(none)>\n\
+         Joe R. Developer <joe@example.com>\n"
+    );
+
+    let reparsed = Snapshot::from_bytes(&buf);
+    assert_eq!(reparsed.entries(), snapshot.entries());
+}
+
+#[test]
+fn write_to_email_only_mappings() {
+    let snapshot = Snapshot::new(Some(git_mailmap::Entry::change_email_by_email("new", "old")));
+    let mut buf = Vec::new();
+    snapshot.write_to(&mut buf).unwrap();
+    assert_eq!(buf.as_bstr(), "<new> <old>\n");
+}
+
+#[test]
+fn remove() {
+    let mut snapshot = Snapshot::from_bytes(&fixture_bytes("typical.txt"));
+    assert!(!snapshot.remove("unknown@example.com".into(), None));
+    assert!(!snapshot.remove("bugs@example.com".into(), None), "there is no mapping by email only");
+    assert!(snapshot.remove("Bugs@example.com".into(), Some("jane".into())));
+    assert_eq!(snapshot.try_resolve(signature("Jane", "bugs@example.com").to_ref()), None);
+    assert_eq!(
+        snapshot.try_resolve(signature("Joe", "bugs@example.com").to_ref()),
+        Some(signature("Joe R. Developer", "joe@example.com")),
+        "other mappings of the same email remain"
+    );
+
+    assert!(snapshot.remove("joe@example.com".into(), None));
+    assert_eq!(snapshot.try_resolve(signature("Joe", "joe@example.com").to_ref()), None);
+    assert_eq!(snapshot.entries().len(), 3);
+}
+
 fn signature(name: &str, email: &str) -> git_actor::Signature {
     git_actor::Signature {
         name: name.into(),
@@ -0,0 +1,188 @@
+use bstr::{BString, ByteSlice};
+use quick_error::quick_error;
+use smallvec::SmallVec;
+
+use crate::Commit;
+
+quick_error! {
+    /// The error returned by [`Builder::build()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        MissingAuthor {
+            display("A commit needs an author")
+        }
+        MissingCommitter {
+            display("A commit needs a committer")
+        }
+        InvalidIdentity { role: &'static str, field: &'static str, value: BString, reason: &'static str } {
+            display("The {} {} {:?} {}", role, field, value, reason)
+        }
+        InvalidEncoding(encoding: BString) {
+            display("The encoding {:?} must be non-empty and must not contain newlines or NUL bytes", encoding)
+        }
+        InvalidExtraHeaderName(name: BString) {
+            display("The extra header name {:?} must be non-empty and must not contain spaces, newlines or NUL bytes", name)
+        }
+        ReservedExtraHeaderName(name: BString) {
+            display("The extra header name {:?} is reserved for standard commit fields", name)
+        }
+        InvalidExtraHeaderValue(name: BString) {
+            display("The value of extra header {:?} must be non-empty and must not contain NUL bytes", name)
+        }
+        NulInMessage {
+            display("Commit messages must not contain NUL bytes")
+        }
+        DuplicateParent(id: git_hash::ObjectId) {
+            display("Parent {} was added more than once", id)
+        }
+        HashKindMismatch(id: git_hash::ObjectId) {
+            display("Object {} uses a different hash kind than the tree of the commit", id)
+        }
+    }
+}
+
+/// A builder for [`Commit`] instances which validates all fields before they can be encoded.
+///
+/// Use it instead of assembling a [`Commit`] by hand to avoid writing commits that git considers corrupt.
+#[derive(Debug, Clone)]
+pub struct Builder {
+    tree: git_hash::ObjectId,
+    parents: SmallVec<[git_hash::ObjectId; 1]>,
+    author: Option<git_actor::Signature>,
+    committer: Option<git_actor::Signature>,
+    encoding: Option<BString>,
+    message: BString,
+    extra_headers: Vec<(BString, BString)>,
+}
+
+impl Builder {
+    /// Create a new builder for a commit pointing to `tree`.
+    pub fn new(tree: impl Into<git_hash::ObjectId>) -> Self {
+        Builder {
+            tree: tree.into(),
+            parents: Default::default(),
+            author: None,
+            committer: None,
+            encoding: None,
+            message: Default::default(),
+            extra_headers: Vec::new(),
+        }
+    }
+
+    /// Add `id` as parent commit, in order.
+    pub fn parent(mut self, id: impl Into<git_hash::ObjectId>) -> Self {
+        self.parents.push(id.into());
+        self
+    }
+
+    /// Set the `author` of the commit.
+    pub fn author(mut self, author: git_actor::Signature) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// Set the `committer` of the commit.
+    pub fn committer(mut self, committer: git_actor::Signature) -> Self {
+        self.committer = Some(committer);
+        self
+    }
+
+    /// Set the commit `message`.
+    pub fn message(mut self, message: impl Into<BString>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Set the name of the `encoding` of the commit message.
+    pub fn encoding(mut self, encoding: impl Into<BString>) -> Self {
+        self.encoding = Some(encoding.into());
+        self
+    }
+
+    /// Add an extra header with `name` and `value`, which may span multiple lines.
+    pub fn extra_header(mut self, name: impl Into<BString>, value: impl Into<BString>) -> Self {
+        self.extra_headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Validate all fields and return the commit, or the first encountered [`Error`].
+    pub fn build(self) -> Result<Commit, Error> {
+        let Builder {
+            tree,
+            parents,
+            author,
+            committer,
+            encoding,
+            message,
+            extra_headers,
+        } = self;
+        let author = author.ok_or(Error::MissingAuthor)?;
+        let committer = committer.ok_or(Error::MissingCommitter)?;
+        validate_identity("author", &author)?;
+        validate_identity("committer", &committer)?;
+
+        for (idx, parent) in parents.iter().enumerate() {
+            if parent.kind() != tree.kind() {
+                return Err(Error::HashKindMismatch(*parent));
+            }
+            if parents[..idx].contains(parent) {
+                return Err(Error::DuplicateParent(*parent));
+            }
+        }
+        if let Some(encoding) = &encoding {
+            if encoding.is_empty() || encoding.find_byteset(b"\n\0").is_some() {
+                return Err(Error::InvalidEncoding(encoding.clone()));
+            }
+        }
+        for (name, value) in &extra_headers {
+            if name.is_empty() || name.find_byteset(b" \n\0").is_some() {
+                return Err(Error::InvalidExtraHeaderName(name.clone()));
+            }
+            if matches!(
+                name.as_slice(),
+                b"tree" | b"parent" | b"author" | b"committer" | b"encoding"
+            ) {
+                return Err(Error::ReservedExtraHeaderName(name.clone()));
+            }
+            if value.is_empty() || value.find_byte(0).is_some() {
+                return Err(Error::InvalidExtraHeaderValue(name.clone()));
+            }
+        }
+        if message.find_byte(0).is_some() {
+            return Err(Error::NulInMessage);
+        }
+
+        Ok(Commit {
+            tree,
+            parents,
+            author,
+            committer,
+            encoding,
+            message,
+            extra_headers,
+        })
+    }
+}
+
+fn validate_identity(role: &'static str, signature: &git_actor::Signature) -> Result<(), Error> {
+    let invalid = |field: &'static str, value: &BString, reason: &'static str| Error::InvalidIdentity {
+        role,
+        field,
+        value: value.clone(),
+        reason,
+    };
+    if signature.name.trim().is_empty() {
+        return Err(invalid("name", &signature.name, "must not be empty"));
+    }
+    for (field, value) in [("name", &signature.name), ("email", &signature.email)] {
+        if value.find_byteset(b"<>\n\0").is_some() {
+            return Err(invalid(
+                field,
+                value,
+                "must not contain '<', '>', newlines or NUL bytes",
+            ));
+        }
+    }
+    Ok(())
+}
@@ -1,7 +1,9 @@
-use bstr::{BStr, ByteSlice};
+use bstr::{BStr, BString, ByteSlice};
 
 use crate::{Commit, CommitRef, TagRef};
 
+///
+pub mod build;
 mod decode;
 ///
 pub mod message;
@@ -73,11 +75,56 @@ impl<'a> CommitRef<'a> {
     }
 }
 
+/// The names of extra headers which store signatures over the rest of the commit.
+const SIGNATURE_HEADERS: &[&[u8]] = &[b"gpgsig", b"gpgsig-sha256"];
+
+fn is_signature_header(name: &[u8]) -> bool {
+    SIGNATURE_HEADERS.contains(&name)
+}
+
 impl Commit {
     /// Returns a convenient iterator over all extra headers.
     pub fn extra_headers(&self) -> ExtraHeaders<impl Iterator<Item = (&BStr, &BStr)>> {
         ExtraHeaders::new(self.extra_headers.iter().map(|(k, v)| (k.as_bstr(), v.as_bstr())))
     }
+
+    /// Return the serialized commit without any signature, which is exactly the data signatures are created for.
+    ///
+    /// For commits obtained from [`CommitRef`], this allows to verify their signature as the remaining headers,
+    /// their order and the message are retained verbatim.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.write_to_filtered(&mut buf, |name| !is_signature_header(name))
+            .expect("writing to a vec doesn't fail and all fields are valid");
+        buf
+    }
+
+    /// Remove all signature headers and return them as `(name, value)` pairs in order of occurrence,
+    /// which will be empty if the commit wasn't signed.
+    ///
+    /// This is necessary to keep a commit valid after changing it, as its signature won't match anymore.
+    pub fn remove_signatures(&mut self) -> Vec<(BString, BString)> {
+        let (signatures, headers) = std::mem::take(&mut self.extra_headers)
+            .into_iter()
+            .partition(|(name, _)| is_signature_header(name));
+        self.extra_headers = headers;
+        signatures
+    }
+
+    /// Replace all existing signatures with a new one obtained by calling `sign` with the [payload][Commit::signing_payload()]
+    /// of this commit, typically after changing it.
+    ///
+    /// The signature is stored in the `gpgsig` header, after all other headers just like git does it, and a single trailing
+    /// newline is removed as it's implied by the header.
+    pub fn sign<E>(&mut self, sign: impl FnOnce(&[u8]) -> Result<BString, E>) -> Result<(), E> {
+        self.remove_signatures();
+        let mut signature = sign(&self.signing_payload())?;
+        if signature.ends_with(b"\n") {
+            signature.pop();
+        }
+        self.extra_headers.push(("gpgsig".into(), signature));
+        Ok(())
+    }
 }
 
 /// An iterator over extra headers in [owned][crate::Commit] and [borrowed][crate::CommitRef] commits.
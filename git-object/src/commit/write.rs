@@ -6,21 +6,8 @@ use crate::{encode, encode::NL, Commit, CommitRef, Kind};
 
 impl crate::WriteTo for Commit {
     /// Serializes this instance to `out` in the git serialization format.
-    fn write_to(&self, mut out: impl io::Write) -> io::Result<()> {
-        encode::trusted_header_id(b"tree", &self.tree, &mut out)?;
-        for parent in &self.parents {
-            encode::trusted_header_id(b"parent", parent, &mut out)?;
-        }
-        encode::trusted_header_signature(b"author", &self.author.to_ref(), &mut out)?;
-        encode::trusted_header_signature(b"committer", &self.committer.to_ref(), &mut out)?;
-        if let Some(encoding) = self.encoding.as_ref() {
-            encode::header_field(b"encoding", encoding, &mut out)?;
-        }
-        for (name, value) in &self.extra_headers {
-            encode::header_field_multi_line(name, value, &mut out)?;
-        }
-        out.write_all(NL)?;
-        out.write_all(&self.message)
+    fn write_to(&self, out: impl io::Write) -> io::Result<()> {
+        self.write_to_filtered(out, |_| true)
     }
 
     fn size(&self) -> usize {
@@ -51,6 +38,27 @@ impl crate::WriteTo for Commit {
     }
 }
 
+impl Commit {
+    /// Serialize this instance to `out` like [`WriteTo::write_to()`][crate::WriteTo::write_to()], but only write
+    /// extra headers whose name passes `filter`.
+    pub(crate) fn write_to_filtered(&self, mut out: impl io::Write, filter: impl Fn(&[u8]) -> bool) -> io::Result<()> {
+        encode::trusted_header_id(b"tree", &self.tree, &mut out)?;
+        for parent in &self.parents {
+            encode::trusted_header_id(b"parent", parent, &mut out)?;
+        }
+        encode::trusted_header_signature(b"author", &self.author.to_ref(), &mut out)?;
+        encode::trusted_header_signature(b"committer", &self.committer.to_ref(), &mut out)?;
+        if let Some(encoding) = self.encoding.as_ref() {
+            encode::header_field(b"encoding", encoding, &mut out)?;
+        }
+        for (name, value) in self.extra_headers.iter().filter(|(name, _)| filter(name)) {
+            encode::header_field_multi_line(name, value, &mut out)?;
+        }
+        out.write_all(NL)?;
+        out.write_all(&self.message)
+    }
+}
+
 impl<'a> crate::WriteTo for CommitRef<'a> {
     /// Serializes this instance to `out` in the git serialization format.
     fn write_to(&self, mut out: impl io::Write) -> io::Result<()> {
@@ -222,6 +222,8 @@ pub struct TreeRef<'a> {
 pub struct TreeRefIter<'a> {
     /// The directories and files contained in this tree.
     data: &'a [u8],
+    /// The kind of hash of the object ids of all entries.
+    object_hash: git_hash::Kind,
 }
 
 /// A mutable Tree, containing other trees, blobs or commits.
@@ -246,6 +248,8 @@ pub struct Data<'a> {
     pub kind: Kind,
     /// decoded, decompressed data, owned by a backing store.
     pub data: &'a [u8],
+    /// The kind of hash used in the repository the object is stored in, which is the kind of hash of object ids in trees.
+    pub object_hash: git_hash::Kind,
 }
 
 ///
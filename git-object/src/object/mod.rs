@@ -179,16 +179,20 @@ quick_error! {
 }
 
 impl<'a> ObjectRef<'a> {
-    /// Deserialize an object from a loose serialisation
-    pub fn from_loose(data: &'a [u8]) -> Result<ObjectRef<'a>, LooseDecodeError> {
+    /// Deserialize an object from a loose serialisation, with trees containing object ids of kind `object_hash`.
+    pub fn from_loose(data: &'a [u8], object_hash: git_hash::Kind) -> Result<ObjectRef<'a>, LooseDecodeError> {
         let (kind, size, offset) = loose_header(data)?;
-        Ok(Self::from_bytes(kind, &data[offset..][..size])?)
+        Ok(Self::from_bytes(kind, &data[offset..][..size], object_hash)?)
     }
 
-    /// Deserialize an object of `kind` from the given `data`.
-    pub fn from_bytes(kind: Kind, data: &'a [u8]) -> Result<ObjectRef<'a>, crate::decode::Error> {
+    /// Deserialize an object of `kind` from the given `data`, with trees containing object ids of kind `object_hash`.
+    pub fn from_bytes(
+        kind: Kind,
+        data: &'a [u8],
+        object_hash: git_hash::Kind,
+    ) -> Result<ObjectRef<'a>, crate::decode::Error> {
         Ok(match kind {
-            Kind::Tree => ObjectRef::Tree(TreeRef::from_bytes(data)?),
+            Kind::Tree => ObjectRef::Tree(TreeRef::from_bytes(data, object_hash)?),
             Kind::Blob => ObjectRef::Blob(BlobRef { data }),
             Kind::Commit => ObjectRef::Commit(CommitRef::from_bytes(data)?),
             Kind::Tag => ObjectRef::Tag(TagRef::from_bytes(data)?),
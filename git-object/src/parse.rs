@@ -34,7 +34,8 @@ pub(crate) fn any_header_field_multi_line<'a, E: ParseError<&'a [u8]> + ContextE
 
     let bytes = o[..o.len() - 1].as_bstr();
     let mut out = BString::from(Vec::with_capacity(bytes.len()));
-    let mut lines = bytes.lines();
+    // Split at newlines only to retain carriage returns verbatim, which keeps signatures over the raw bytes intact.
+    let mut lines = bytes.split_str(b"\n");
     out.push_str(lines.next().expect("first line"));
     for line in lines {
         out.push(b'\n');
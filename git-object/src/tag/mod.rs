@@ -1,4 +1,6 @@
-use crate::TagRef;
+use bstr::BString;
+
+use crate::{Tag, TagRef, WriteTo};
 
 mod decode;
 
@@ -20,3 +22,29 @@ impl<'a> TagRef<'a> {
         git_hash::ObjectId::from_hex(self.target).expect("prior validation")
     }
 }
+
+impl Tag {
+    /// Return the serialized tag without its signature, which is exactly the data signatures are created for.
+    ///
+    /// It ends with the newline which separates the message from the signature, as it's part of the signed data.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        Tag {
+            pgp_signature: None,
+            ..self.clone()
+        }
+        .write_to(&mut buf)
+        .expect("writing to a vec doesn't fail and all fields are valid");
+        buf.push(b'\n');
+        buf
+    }
+
+    /// Replace an existing signature with a new one obtained by calling `sign` with the [payload][Tag::signing_payload()]
+    /// of this tag, typically after changing it.
+    ///
+    /// The signature is stored after the message just like git does it.
+    pub fn sign<E>(&mut self, sign: impl FnOnce(&[u8]) -> Result<BString, E>) -> Result<(), E> {
+        self.pgp_signature = Some(sign(&self.signing_payload())?);
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use bstr::{BString, ByteSlice};
+use quick_error::quick_error;
+
+use crate::{
+    tree::{cmp_git_order, Entry, EntryMode},
+    Tree,
+};
+
+quick_error! {
+    /// The error returned by [`Builder::build()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        InvalidFilename(name: BString, err: git_validate::path::component::Error) {
+            display("Tree entry filename {:?} is invalid", name)
+            source(err)
+        }
+        NulInFilename(name: BString) {
+            display("Tree entry filenames must not contain NUL bytes: {:?}", name)
+        }
+        DuplicateFilename(name: BString) {
+            display("The filename {:?} was added more than once", name)
+        }
+        HashKindMismatch(name: BString) {
+            display("The object id of entry {:?} uses a different hash kind than the previous entries", name)
+        }
+    }
+}
+
+/// A builder for [`Tree`] instances which validates entries and brings them into the order git expects.
+///
+/// Use it instead of assembling [`Tree::entries`] by hand to avoid writing trees that git considers corrupt.
+#[derive(Default, Debug, Clone)]
+pub struct Builder {
+    entries: Vec<Entry>,
+}
+
+impl Builder {
+    /// Create a new builder without any entries.
+    pub fn new() -> Self {
+        Builder::default()
+    }
+
+    /// Add an entry with `mode` named `filename` which points to `oid`.
+    ///
+    /// Entries may be added in any order.
+    pub fn entry(mut self, mode: EntryMode, filename: impl Into<BString>, oid: impl Into<git_hash::ObjectId>) -> Self {
+        self.entries.push(Entry {
+            mode,
+            filename: filename.into(),
+            oid: oid.into(),
+        });
+        self
+    }
+
+    /// Validate all entries and return a tree with entries sorted the way git does, or the first encountered [`Error`].
+    pub fn build(self) -> Result<Tree, Error> {
+        let Builder { mut entries } = self;
+        for entry in &entries {
+            validate_filename(entry.filename.as_ref())?;
+        }
+        if let Some(first) = entries.first() {
+            let kind = first.oid.kind();
+            if let Some(entry) = entries.iter().find(|e| e.oid.kind() != kind) {
+                return Err(Error::HashKindMismatch(entry.filename.clone()));
+            }
+        }
+
+        entries.sort_by(|lhs, rhs| cmp_git_order(&lhs.filename, lhs.mode, &rhs.filename, rhs.mode));
+        let mut names: Vec<_> = entries.iter().map(|e| e.filename.as_slice()).collect();
+        names.sort_unstable();
+        if let Some(dup) = names.windows(2).find(|w| w[0] == w[1]) {
+            return Err(Error::DuplicateFilename(dup[0].into()));
+        }
+        Ok(Tree { entries })
+    }
+}
+
+/// Validate `name` without the protections against aliases of `.git` on NTFS and HFS+, which only matter on checkout.
+fn validate_filename(name: &[u8]) -> Result<(), Error> {
+    git_validate::path::component(name.as_bstr(), Default::default())
+        .map_err(|err| Error::InvalidFilename(name.into(), err))?;
+    if name.find_byte(0).is_some() {
+        return Err(Error::NulInFilename(name.into()));
+    }
+    Ok(())
+}
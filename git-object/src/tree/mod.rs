@@ -5,6 +5,8 @@ use crate::{
     tree,
 };
 
+///
+pub mod build;
 mod ref_iter;
 ///
 pub mod write;
@@ -109,6 +111,16 @@ impl Ord for Entry {
     }
 }
 
+/// Compare entries by their `filename` the way git sorts them in trees, which is as if trees had a trailing slash.
+pub(crate) fn cmp_git_order(lhs: &[u8], lhs_mode: EntryMode, rhs: &[u8], rhs_mode: EntryMode) -> Ordering {
+    fn with_slash(name: &[u8], mode: EntryMode) -> impl Iterator<Item = u8> + '_ {
+        name.iter()
+            .copied()
+            .chain(if mode.is_tree() { Some(b'/') } else { None })
+    }
+    with_slash(lhs, lhs_mode).cmp(with_slash(rhs, rhs_mode))
+}
+
 /// Serialization
 impl EntryMode {
     /// Return the representation as used in the git internal format.
@@ -5,7 +5,7 @@ use quick_error::quick_error;
 
 use crate::{
     encode::SPACE,
-    tree::{Entry, EntryRef},
+    tree::{cmp_git_order, Entry, EntryRef},
     Kind, Tree, TreeRef,
 };
 
@@ -14,8 +14,8 @@ quick_error! {
     #[derive(Debug)]
     #[allow(missing_docs)]
     pub enum Error {
-        NewlineInFilename(name: BString) {
-            display("Newlines are invalid in file paths: {:?}", name)
+        NulInFilename(name: BString) {
+            display("Nul bytes are invalid in file paths as they terminate them: {:?}", name)
         }
     }
 }
@@ -30,21 +30,21 @@ impl From<Error> for io::Error {
 impl crate::WriteTo for Tree {
     /// Serialize this tree to `out` in the git internal format.
     fn write_to(&self, mut out: impl io::Write) -> io::Result<()> {
-        debug_assert_eq!(
-            &{
-                let mut entries_sorted = self.entries.clone();
-                entries_sorted.sort();
-                entries_sorted
-            },
-            &self.entries,
+        debug_assert!(
+            self.entries.windows(2).all(|e| cmp_git_order(
+                e[0].filename.as_ref(),
+                e[0].mode,
+                e[1].filename.as_ref(),
+                e[1].mode
+            ) != std::cmp::Ordering::Greater),
             "entries for serialization must be sorted by filename"
         );
         for Entry { mode, filename, oid } in &self.entries {
             out.write_all(mode.as_bytes())?;
             out.write_all(SPACE)?;
 
-            if filename.find_byte(b'\n').is_some() {
-                return Err(Error::NewlineInFilename((*filename).to_owned()).into());
+            if filename.find_byte(0).is_some() {
+                return Err(Error::NulInFilename((*filename).to_owned()).into());
             }
             out.write_all(filename)?;
             out.write_all(&[b'\0'])?;
@@ -70,21 +70,21 @@ impl crate::WriteTo for Tree {
 impl<'a> crate::WriteTo for TreeRef<'a> {
     /// Serialize this tree to `out` in the git internal format.
     fn write_to(&self, mut out: impl io::Write) -> io::Result<()> {
-        debug_assert_eq!(
-            &{
-                let mut entries_sorted = self.entries.clone();
-                entries_sorted.sort();
-                entries_sorted
-            },
-            &self.entries,
+        debug_assert!(
+            self.entries.windows(2).all(|e| cmp_git_order(
+                e[0].filename.as_ref(),
+                e[0].mode,
+                e[1].filename.as_ref(),
+                e[1].mode
+            ) != std::cmp::Ordering::Greater),
             "entries for serialization must be sorted by filename"
         );
         for EntryRef { mode, filename, oid } in &self.entries {
             out.write_all(mode.as_bytes())?;
             out.write_all(SPACE)?;
 
-            if filename.find_byte(b'\n').is_some() {
-                return Err(Error::NewlineInFilename((*filename).to_owned()).into());
+            if filename.find_byte(0).is_some() {
+                return Err(Error::NulInFilename((*filename).to_owned()).into());
             }
             out.write_all(filename)?;
             out.write_all(&[b'\0'])?;
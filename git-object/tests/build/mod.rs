@@ -0,0 +1,194 @@
+use git_testtools::hex_to_id;
+
+fn id(hex: &str) -> git_hash::ObjectId {
+    hex_to_id(hex)
+}
+
+const BLOB: &str = "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391";
+const TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+mod tree {
+    use git_object::{
+        tree::{build, EntryMode},
+        WriteTo,
+    };
+
+    use super::{id, BLOB, TREE};
+
+    #[test]
+    fn entries_are_sorted_like_git_does_with_trees_sorting_as_if_they_had_a_trailing_slash() -> crate::Result {
+        let tree = build::Builder::new()
+            .entry(EntryMode::Tree, "a", id(TREE))
+            .entry(EntryMode::Blob, "a.txt", id(BLOB))
+            .entry(EntryMode::BlobExecutable, "a-b", id(BLOB))
+            .entry(EntryMode::Blob, "B", id(BLOB))
+            .build()?;
+        let names: Vec<_> = tree.entries.iter().map(|e| e.filename.to_string()).collect();
+        assert_eq!(names, ["B", "a-b", "a.txt", "a"]);
+
+        let mut buf = Vec::new();
+        tree.write_to(&mut buf)?;
+        assert_eq!(
+            git_object::TreeRef::from_bytes(&buf, git_hash::Kind::Sha1)?
+                .entries
+                .len(),
+            4,
+            "the tree can be encoded and decoded"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn empty_trees_are_valid() -> crate::Result {
+        assert!(build::Builder::new().build()?.entries.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_filenames_are_rejected() {
+        for (name, expected_cause) in [
+            ("", "A path component must not be empty"),
+            (".", "A path component must not be '.' or '..'"),
+            ("..", "A path component must not be '.' or '..'"),
+            (".GiT", "A path component must not be a '.git' directory"),
+            ("a/b", "A path component must not contain a '/'"),
+        ] {
+            let err = build::Builder::new()
+                .entry(EntryMode::Blob, name, id(BLOB))
+                .build()
+                .unwrap_err();
+            assert_eq!(err.to_string(), format!("Tree entry filename {:?} is invalid", name));
+            assert_eq!(
+                std::error::Error::source(&err).map(ToString::to_string).as_deref(),
+                Some(expected_cause)
+            );
+        }
+        let err = build::Builder::new()
+            .entry(EntryMode::Blob, "a\0b", id(BLOB))
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Tree entry filenames must not contain NUL bytes: \"a\\0b\""
+        );
+    }
+
+    #[test]
+    fn filenames_only_invalid_on_some_filesystems_are_accepted_like_git_does() -> crate::Result {
+        let tree = build::Builder::new()
+            .entry(EntryMode::Blob, "a\nb", id(BLOB))
+            .entry(EntryMode::Blob, "a:b", id(BLOB))
+            .entry(EntryMode::Blob, "a\\b", id(BLOB))
+            .entry(EntryMode::Blob, "git~1", id(BLOB))
+            .build()?;
+        assert_eq!(tree.entries.len(), 4);
+        let mut buf = Vec::new();
+        tree.write_to(&mut buf)?;
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_filenames_are_rejected_even_if_their_mode_differs() {
+        let err = build::Builder::new()
+            .entry(EntryMode::Blob, "a", id(BLOB))
+            .entry(EntryMode::Blob, "b", id(BLOB))
+            .entry(EntryMode::Tree, "a", id(TREE))
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, build::Error::DuplicateFilename(name) if name == "a"));
+    }
+}
+
+mod commit {
+    use git_object::{commit::build, WriteTo};
+
+    use super::{id, signature, BLOB, TREE};
+
+    fn builder() -> build::Builder {
+        build::Builder::new(id(TREE))
+            .author(signature("Name", "name@example.com"))
+            .committer(signature("Name", "name@example.com"))
+            .message("title\n\nbody\n")
+    }
+
+    #[test]
+    fn a_valid_commit_round_trips() -> crate::Result {
+        let commit = builder()
+            .parent(id(BLOB))
+            .encoding("ISO-8859-1")
+            .extra_header("gpgsig", "multi\nline")
+            .build()?;
+        let mut buf = Vec::new();
+        commit.write_to(&mut buf)?;
+        let decoded: git_object::Commit = git_object::CommitRef::from_bytes(&buf)?.into();
+        assert_eq!(decoded, commit);
+        Ok(())
+    }
+
+    #[test]
+    fn author_and_committer_are_required() {
+        let err = build::Builder::new(id(TREE)).build().unwrap_err();
+        assert!(matches!(err, build::Error::MissingAuthor));
+        let err = build::Builder::new(id(TREE))
+            .author(signature("Name", "name@example.com"))
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, build::Error::MissingCommitter));
+    }
+
+    #[test]
+    fn malformed_identities_are_rejected() {
+        for (name, email, expected) in [
+            (" ", "e", "The author name \" \" must not be empty"),
+            (
+                "a <b>",
+                "e",
+                "The author name \"a <b>\" must not contain '<', '>', newlines or NUL bytes",
+            ),
+            (
+                "n",
+                "e>\n",
+                "The author email \"e>\\n\" must not contain '<', '>', newlines or NUL bytes",
+            ),
+        ] {
+            let err = builder().author(signature(name, email)).build().unwrap_err();
+            assert_eq!(err.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn invalid_headers_and_messages_are_rejected() {
+        assert!(matches!(
+            builder().encoding("utf\n8").build().unwrap_err(),
+            build::Error::InvalidEncoding(_)
+        ));
+        assert!(matches!(
+            builder().extra_header("has space", "v").build().unwrap_err(),
+            build::Error::InvalidExtraHeaderName(_)
+        ));
+        assert!(matches!(
+            builder().extra_header("parent", "v").build().unwrap_err(),
+            build::Error::ReservedExtraHeaderName(_)
+        ));
+        assert!(matches!(
+            builder().extra_header("name", "").build().unwrap_err(),
+            build::Error::InvalidExtraHeaderValue(_)
+        ));
+        assert!(matches!(
+            builder().message("a\0b").build().unwrap_err(),
+            build::Error::NulInMessage
+        ));
+        assert!(matches!(
+            builder().parent(id(BLOB)).parent(id(BLOB)).build().unwrap_err(),
+            build::Error::DuplicateParent(_)
+        ));
+    }
+}
+
+fn signature(name: &str, email: &str) -> git_actor::Signature {
+    git_actor::Signature {
+        name: name.into(),
+        email: email.into(),
+        time: Default::default(),
+    }
+}
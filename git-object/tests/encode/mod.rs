@@ -9,7 +9,7 @@ quick_error! {
 }
 
 macro_rules! round_trip {
-    ($owned:ty, $borrowed:ty, $( $files:literal ), +) => {
+    ($owned:ty, $borrowed:ty, $( $files:literal ), + $(; $from_bytes_arg:expr)?) => {
         #[test]
         fn round_trip() -> Result<(), Box<dyn std::error::Error>> {
             use std::convert::TryFrom;
@@ -24,7 +24,7 @@ macro_rules! round_trip {
                 let input = fixture_bytes(input);
                 // Test the parse->borrowed->owned->write chain for an object kind
                 let mut output = Vec::new();
-                let item = <$borrowed>::from_bytes(&input)?;
+                let item = <$borrowed>::from_bytes(&input $(, $from_bytes_arg)?)?;
                 item.write_to(&mut output)?;
                 assert_eq!(output.as_bstr(), input.as_bstr());
 
@@ -34,7 +34,7 @@ macro_rules! round_trip {
                 assert_eq!(output.as_bstr(), input.as_bstr());
 
                 // Test the parse->borrowed->owned->write chain for the top-level objects
-                let item = ObjectRef::from(<$borrowed>::from_bytes(&input)?);
+                let item = ObjectRef::from(<$borrowed>::from_bytes(&input $(, $from_bytes_arg)?)?);
                 output.clear();
                 item.write_to(&mut output)?;
                 assert_eq!(output.as_bstr(), input.as_bstr());
@@ -45,13 +45,13 @@ macro_rules! round_trip {
                 assert_eq!(output.as_bstr(), input.as_bstr());
 
                 // Test the loose serialisation -> parse chain for an object kind
-                let item = <$borrowed>::from_bytes(&input)?;
+                let item = <$borrowed>::from_bytes(&input $(, $from_bytes_arg)?)?;
                 output.clear();
                 // serialise to a tagged loose object
                 let w = &mut output;
                 w.write_all(&item.loose_header())?;
                 item.write_to(w)?;
-                let parsed = ObjectRef::from_loose(&output)?;
+                let parsed = ObjectRef::from_loose(&output, git_hash::Kind::Sha1)?;
                 let item2 = <$borrowed>::try_from(parsed).or(Err(super::Error::TryFromError))?;
                 assert_eq!(item2, item);
             }
@@ -70,6 +70,52 @@ mod tag {
         "tag/with-newlines.txt",
         "tag/signed.txt"
     );
+
+    mod signatures {
+        use bstr::ByteSlice;
+        use git_object::{Tag, TagRef, WriteTo};
+
+        use crate::fixture_bytes;
+
+        #[test]
+        fn signing_payload_is_the_tag_up_to_its_signature() -> crate::Result {
+            let input = fixture_bytes("tag/signed.txt");
+            let tag: Tag = TagRef::from_bytes(&input)?.into();
+            let signature_start = input.find(b"-----BEGIN PGP SIGNATURE-----").expect("fixture is signed");
+            assert_eq!(tag.signing_payload().as_bstr(), input[..signature_start].as_bstr());
+            Ok(())
+        }
+
+        #[test]
+        fn resign_after_modification() -> crate::Result {
+            let input = fixture_bytes("tag/signed.txt");
+            let mut tag: Tag = TagRef::from_bytes(&input)?.into();
+            tag.message = "changed".into();
+
+            let mut seen_payload = None;
+            tag.sign(|payload| {
+                seen_payload = Some(payload.to_owned());
+                Ok::<_, std::convert::Infallible>(
+                    "-----BEGIN PGP SIGNATURE-----\nnew\n-----END PGP SIGNATURE-----\n".into(),
+                )
+            })?;
+            let mut reencoded = Vec::new();
+            tag.write_to(&mut reencoded)?;
+            assert_eq!(
+                seen_payload.as_deref().map(|p| p.as_bstr()),
+                reencoded.strip_suffix(tag.pgp_signature.as_deref().expect("signed").as_slice()).map(|p| p.as_bstr()),
+                "the payload is everything that precedes the signature, without the signature that is about to be replaced"
+            );
+
+            let decoded = TagRef::from_bytes(&reencoded)?;
+            assert_eq!(decoded.message, "changed");
+            assert_eq!(
+                decoded.pgp_signature,
+                Some(b"-----BEGIN PGP SIGNATURE-----\nnew\n-----END PGP SIGNATURE-----\n".as_bstr())
+            );
+            Ok(())
+        }
+    }
 }
 
 mod commit {
@@ -87,10 +133,108 @@ mod commit {
         "commit/whitespace.txt",
         "commit/with-encoding.txt"
     );
+
+    mod signatures {
+        use bstr::ByteSlice;
+        use git_object::{Commit, CommitRef, WriteTo};
+
+        use crate::fixture_bytes;
+
+        fn encode(commit: &Commit) -> Vec<u8> {
+            let mut buf = Vec::new();
+            commit.write_to(&mut buf).expect("in-memory writes work");
+            buf
+        }
+
+        /// Remove the `gpgsig` header and all of its continuation lines, the way git computes the signed payload.
+        fn strip_signature_lines(input: &[u8]) -> Vec<u8> {
+            let mut out = Vec::new();
+            let mut in_signature = false;
+            for line in input.lines_with_terminator() {
+                in_signature = line.starts_with(b"gpgsig ") || (in_signature && line.starts_with(b" "));
+                if !in_signature {
+                    out.extend_from_slice(line);
+                }
+            }
+            out
+        }
+
+        #[test]
+        fn carriage_returns_in_multi_line_headers_are_preserved() -> crate::Result {
+            let input = b"tree 9bed6275068a0575243ba8409253e61af81ab2ff\nauthor A <a@b> 1 +0000\ncommitter A <a@b> 1 +0000\ngpgsig -----BEGIN\r\n abc\r\n -----END\r\n\nmsg\n";
+            let commit: Commit = CommitRef::from_bytes(input)?.into();
+            assert_eq!(
+                commit.extra_headers().pgp_signature(),
+                Some(b"-----BEGIN\r\nabc\r\n-----END\r".as_bstr())
+            );
+            assert_eq!(encode(&commit).as_bstr(), input.as_bstr());
+            Ok(())
+        }
+
+        #[test]
+        fn signing_payload_is_the_commit_without_its_signature() -> crate::Result {
+            for fixture in [
+                "commit/signed.txt",
+                "commit/signed-whitespace.txt",
+                "commit/signed-with-encoding.txt",
+            ] {
+                let input = fixture_bytes(fixture);
+                let commit: Commit = CommitRef::from_bytes(&input)?.into();
+                assert_eq!(
+                    commit.signing_payload().as_bstr(),
+                    strip_signature_lines(&input).as_bstr(),
+                    "{}",
+                    fixture
+                );
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn resign_after_modification_and_strip_signatures() -> crate::Result {
+            let input = fixture_bytes("commit/signed-with-encoding.txt");
+            let mut commit: Commit = CommitRef::from_bytes(&input)?.into();
+            let original_signature = commit.extra_headers().pgp_signature().map(ToOwned::to_owned);
+            commit.message = "changed".into();
+
+            let mut seen_payload = None;
+            commit.sign(|payload| {
+                seen_payload = Some(payload.to_owned());
+                Ok::<_, std::convert::Infallible>("new\nsignature\n".into())
+            })?;
+            assert_eq!(
+                seen_payload.as_deref().map(|p| p.as_bstr()),
+                Some(strip_signature_lines(&encode(&commit)).as_bstr()),
+                "the payload doesn't contain the signature that is about to be replaced"
+            );
+            assert_ne!(
+                commit.extra_headers().pgp_signature().map(ToOwned::to_owned),
+                original_signature
+            );
+
+            let reencoded = encode(&commit);
+            let decoded = CommitRef::from_bytes(&reencoded)?;
+            assert_eq!(
+                decoded.extra_headers().pgp_signature(),
+                Some(b"new\nsignature".as_bstr())
+            );
+            assert_eq!(decoded.message, "changed");
+
+            let removed = commit.remove_signatures();
+            assert_eq!(removed.len(), 1);
+            assert_eq!(commit.extra_headers().pgp_signature(), None);
+            assert_eq!(
+                encode(&commit),
+                commit.signing_payload(),
+                "stripped commits are their own payload"
+            );
+            Ok(())
+        }
+    }
 }
 
 mod tree {
-    round_trip!(git_object::Tree, git_object::TreeRef, "tree/everything.tree");
+    round_trip!(git_object::Tree, git_object::TreeRef, "tree/everything.tree"; git_hash::Kind::Sha1);
 }
 
 mod blob {
@@ -5,13 +5,17 @@ mod iter {
 
     #[test]
     fn empty() {
-        assert_eq!(TreeRefIter::from_bytes(&[]).count(), 0, "empty trees are definitely ok");
+        assert_eq!(
+            TreeRefIter::from_bytes(&[], git_hash::Kind::Sha1).count(),
+            0,
+            "empty trees are definitely ok"
+        );
     }
 
     #[test]
     fn error_handling() {
         let data = fixture_bytes("tree", "everything.tree");
-        let iter = TreeRefIter::from_bytes(&data[..data.len() / 2]);
+        let iter = TreeRefIter::from_bytes(&data[..data.len() / 2], git_hash::Kind::Sha1);
         let entries = iter.collect::<Vec<_>>();
         assert!(
             entries.last().expect("at least one token").is_err(),
@@ -22,7 +26,8 @@ mod iter {
     #[test]
     fn everything() -> crate::Result {
         assert_eq!(
-            TreeRefIter::from_bytes(&fixture_bytes("tree", "everything.tree")).collect::<Result<Vec<_>, _>>()?,
+            TreeRefIter::from_bytes(&fixture_bytes("tree", "everything.tree"), git_hash::Kind::Sha1)
+                .collect::<Result<Vec<_>, _>>()?,
             vec![
                 EntryRef {
                     mode: tree::EntryMode::BlobExecutable,
@@ -63,7 +68,7 @@ mod from_bytes {
     #[test]
     fn empty() -> crate::Result {
         assert_eq!(
-            TreeRef::from_bytes(&[])?,
+            TreeRef::from_bytes(&[], git_hash::Kind::Sha1)?,
             TreeRef { entries: vec![] },
             "empty trees are valid despite usually rare in the wild"
         );
@@ -73,7 +78,7 @@ mod from_bytes {
     #[test]
     fn everything() -> crate::Result {
         assert_eq!(
-            TreeRef::from_bytes(&fixture_bytes("tree", "everything.tree"))?,
+            TreeRef::from_bytes(&fixture_bytes("tree", "everything.tree"), git_hash::Kind::Sha1)?,
             TreeRef {
                 entries: vec![
                     EntryRef {
@@ -110,7 +115,7 @@ mod from_bytes {
     #[test]
     fn maybe_special() -> crate::Result {
         assert_eq!(
-            TreeRef::from_bytes(&fixture_bytes("tree", "maybe-special.tree"))?
+            TreeRef::from_bytes(&fixture_bytes("tree", "maybe-special.tree"), git_hash::Kind::Sha1)?
                 .entries
                 .len(),
             160
@@ -121,7 +126,7 @@ mod from_bytes {
     #[test]
     fn definitely_special() -> crate::Result {
         assert_eq!(
-            TreeRef::from_bytes(&fixture_bytes("tree", "definitely-special.tree"))?
+            TreeRef::from_bytes(&fixture_bytes("tree", "definitely-special.tree"), git_hash::Kind::Sha1)?
                 .entries
                 .len(),
             19
@@ -142,3 +147,48 @@ mod entry_mode {
         );
     }
 }
+
+#[cfg(feature = "sha256")]
+mod sha256 {
+    use git_object::{bstr::ByteSlice, tree, Tree, TreeRef, TreeRefIter, WriteTo};
+
+    fn tree() -> Tree {
+        Tree {
+            entries: vec![
+                tree::Entry {
+                    mode: tree::EntryMode::Blob,
+                    filename: "a".into(),
+                    oid: git_hash::ObjectId::from([1; 32]),
+                },
+                tree::Entry {
+                    mode: tree::EntryMode::Tree,
+                    filename: "b".into(),
+                    oid: git_hash::ObjectId::from([2; 32]),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn entries_are_decoded_with_ids_of_the_given_kind() -> crate::Result {
+        let tree = tree();
+        let mut buf = Vec::new();
+        tree.write_to(&mut buf)?;
+
+        assert_eq!(Tree::from(TreeRef::from_bytes(&buf, git_hash::Kind::Sha256)?), tree);
+        assert_eq!(
+            TreeRefIter::from_bytes(&buf, git_hash::Kind::Sha256)
+                .map(|e| e.map(|e| (e.filename.to_str_lossy().into_owned(), e.oid.to_owned())))
+                .collect::<Result<Vec<_>, _>>()?,
+            tree.entries
+                .iter()
+                .map(|e| (e.filename.to_string(), e.oid))
+                .collect::<Vec<_>>()
+        );
+        assert!(
+            TreeRef::from_bytes(&buf, git_hash::Kind::Sha1).is_err(),
+            "the length of ids can't be guessed"
+        );
+        Ok(())
+    }
+}
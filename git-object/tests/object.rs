@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 
+mod build;
 mod encode;
 mod immutable;
 
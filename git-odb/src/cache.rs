@@ -17,6 +17,28 @@ pub type ObjectCache = dyn git_pack::cache::Object + Send + 'static;
 /// A constructor for boxed object caches.
 pub type NewObjectCacheFn = dyn Fn() -> Box<ObjectCache> + Send + Sync + 'static;
 
+/// The amount of hits and misses of a cache.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hits {
+    /// The amount of times an item was found in the cache.
+    pub hits: usize,
+    /// The amount of times an item was not found in the cache and had to be obtained otherwise.
+    pub misses: usize,
+}
+
+/// Statistics about the efficiency of the caches of a [`Cache`], useful to tune their sizes.
+///
+/// Note that only lookups of caches that are present are counted.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Statistics {
+    /// Lookups of fully decoded objects in the object cache.
+    pub object_cache: Hits,
+    /// Lookups of delta-base objects in the pack cache while decoding packed objects.
+    pub pack_cache: Hits,
+}
+
 impl Cache<crate::store::Handle<Rc<crate::Store>>> {
     /// Convert this cache's handle into one that keeps its store in an arc. This creates an entirely new store,
     /// so should be done early to avoid unnecessary work (and mappings).
@@ -28,6 +50,7 @@ impl Cache<crate::store::Handle<Rc<crate::Store>>> {
             new_object_cache: self.new_object_cache,
             pack_cache: self.pack_cache,
             object_cache: self.object_cache,
+            statistics: self.statistics,
         })
     }
 }
@@ -89,6 +112,43 @@ impl<S> Cache<S> {
         self.object_cache = None;
         self.new_object_cache = None;
     }
+    /// Return the hits and misses of our caches since this instance was created or since the last call to
+    /// [`reset_statistics()`][Self::reset_statistics()].
+    ///
+    /// Note that clones of this instance start with their own caches and thus with empty statistics.
+    pub fn statistics(&self) -> Statistics {
+        self.statistics.get()
+    }
+    /// Set all cache statistics back to zero.
+    pub fn reset_statistics(&self) {
+        self.statistics.set(Default::default());
+    }
+    fn update_statistics(&self, update: impl FnOnce(&mut Statistics)) {
+        let mut stats = self.statistics.get();
+        update(&mut stats);
+        self.statistics.set(stats);
+    }
+}
+
+/// A pack cache that counts the hits and misses of the cache it wraps.
+struct CountingPackCache<'a, C: ?Sized> {
+    inner: &'a mut C,
+    hits: Hits,
+}
+
+impl<C: git_pack::cache::DecodeEntry + ?Sized> git_pack::cache::DecodeEntry for CountingPackCache<'_, C> {
+    fn put(&mut self, pack_id: u32, offset: u64, data: &[u8], kind: git_object::Kind, compressed_size: usize) {
+        self.inner.put(pack_id, offset, data, kind, compressed_size)
+    }
+
+    fn get(&mut self, pack_id: u32, offset: u64, out: &mut Vec<u8>) -> Option<(git_object::Kind, usize)> {
+        let res = self.inner.get(pack_id, offset, out);
+        match res {
+            Some(_) => self.hits.hits += 1,
+            None => self.hits.misses += 1,
+        }
+        res
+    }
 }
 
 impl<S> From<S> for Cache<S>
@@ -102,6 +162,7 @@ where
             new_pack_cache: None,
             object_cache: None,
             new_object_cache: None,
+            statistics: Default::default(),
         }
     }
 }
@@ -114,6 +175,7 @@ impl<S: Clone> Clone for Cache<S> {
             new_object_cache: self.new_object_cache.clone(),
             pack_cache: self.new_pack_cache.as_ref().map(|create| RefCell::new(create())),
             object_cache: self.new_object_cache.as_ref().map(|create| RefCell::new(create())),
+            statistics: Default::default(),
         }
     }
 }
@@ -139,6 +201,7 @@ mod impls {
     use git_object::{Data, Kind};
     use git_pack::cache::Object;
 
+    use super::CountingPackCache;
     use crate::{pack::data::entry::Location, Cache};
 
     impl<S> crate::Write for Cache<S>
@@ -183,7 +246,19 @@ mod impls {
             buffer: &'a mut Vec<u8>,
         ) -> Result<Option<(Data<'a>, Option<Location>)>, Self::Error> {
             match self.pack_cache.as_ref().map(|rc| rc.borrow_mut()) {
-                Some(mut pack_cache) => self.try_find_cached(id, buffer, pack_cache.deref_mut()),
+                Some(mut pack_cache) => {
+                    let mut pack_cache = CountingPackCache {
+                        inner: pack_cache.deref_mut(),
+                        hits: Default::default(),
+                    };
+                    let res = self.try_find_cached(id, buffer, &mut pack_cache);
+                    let hits = pack_cache.hits;
+                    self.update_statistics(|stats| {
+                        stats.pack_cache.hits += hits.hits;
+                        stats.pack_cache.misses += hits.misses;
+                    });
+                    res
+                }
                 None => self.try_find_cached(id, buffer, &mut git_pack::cache::Never),
             }
         }
@@ -196,8 +271,10 @@ mod impls {
         ) -> Result<Option<(Data<'a>, Option<git_pack::data::entry::Location>)>, Self::Error> {
             if let Some(mut obj_cache) = self.object_cache.as_ref().map(|rc| rc.borrow_mut()) {
                 if let Some(kind) = obj_cache.get(&id.as_ref().to_owned(), buffer) {
-                    return Ok(Some((Data::new(kind, buffer), None)));
+                    self.update_statistics(|stats| stats.object_cache.hits += 1);
+                    return Ok(Some((Data::new(kind, buffer, id.as_ref().kind()), None)));
                 }
+                self.update_statistics(|stats| stats.object_cache.misses += 1);
             }
             let possibly_obj = self.inner.try_find_cached(id.as_ref(), buffer, pack_cache)?;
             if let (Some(mut obj_cache), Some((obj, _location))) =
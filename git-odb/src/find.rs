@@ -90,3 +90,41 @@ pub mod existing_iter {
         ObjectKind { expected: git_object::Kind },
     }
 }
+
+/// Information about an object, obtained without decoding it, as returned by [`Handle::try_header()`][crate::store::Handle::try_header()].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Header {
+    /// The object was found in a pack.
+    Packed(git_pack::data::decode_header::Outcome),
+    /// The object is stored loose.
+    Loose {
+        /// The kind of the object.
+        kind: git_object::Kind,
+        /// The size of the object's data in bytes.
+        size: u64,
+    },
+}
+
+impl Header {
+    /// Return the kind of the object.
+    pub fn kind(&self) -> git_object::Kind {
+        match self {
+            Header::Packed(out) => out.kind,
+            Header::Loose { kind, .. } => *kind,
+        }
+    }
+    /// Return the size of the object's data in bytes.
+    pub fn size(&self) -> u64 {
+        match self {
+            Header::Packed(out) => out.object_size,
+            Header::Loose { size, .. } => *size,
+        }
+    }
+    /// Return the amount of deltas that would have to be resolved to decode the object, or `None` if it is stored loose.
+    pub fn num_deltas(&self) -> Option<u32> {
+        match self {
+            Header::Packed(out) => Some(out.num_deltas),
+            Header::Loose { .. } => None,
+        }
+    }
+}
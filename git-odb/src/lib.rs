@@ -44,6 +44,7 @@ pub struct Cache<S> {
     new_object_cache: Option<Arc<cache::NewObjectCacheFn>>,
     pack_cache: Option<RefCell<Box<cache::PackCache>>>,
     object_cache: Option<RefCell<Box<cache::ObjectCache>>>,
+    statistics: std::cell::Cell<cache::Statistics>,
 }
 
 ///
@@ -102,6 +103,8 @@ pub struct Store {
 
     /// The source directory from which all content is loaded, and the central write lock for use when a directory refresh is needed.
     pub(crate) path: PathBuf,
+    /// Object databases to use in addition to the ones listed in `info/alternates` of `path`.
+    pub(crate) alternates: Vec<PathBuf>,
 
     /// A set of replacements that given a source OID return a destination OID. The vector is sorted.
     pub(crate) replacements: Vec<(git_hash::ObjectId, git_hash::ObjectId)>,
@@ -126,6 +129,8 @@ pub struct Store {
     use_multi_pack_index: bool,
     /// The hash kind to use for some operations
     object_hash: git_hash::Kind,
+    /// If set, newly written loose objects are flushed to permanent storage with the given method.
+    fsync: Option<git_features::fs::fsync::Method>,
 }
 
 /// Create a new cached handle to the object store with support for additional options.
@@ -17,6 +17,11 @@ impl Store {
         self.use_multi_pack_index
     }
 
+    /// The method used to flush newly written loose objects to permanent storage, or `None` if they aren't flushed.
+    pub fn fsync(&self) -> Option<git_features::fs::fsync::Method> {
+        self.fsync
+    }
+
     /// An iterator over replacements from object-ids `X` to `X-replaced` as `(X, X-replaced)`, sorted by the original id `X`.
     pub fn replacements(&self) -> impl Iterator<Item = (git_hash::ObjectId, git_hash::ObjectId)> + '_ {
         self.replacements.iter().cloned()
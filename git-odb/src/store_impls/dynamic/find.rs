@@ -3,9 +3,9 @@ use std::{collections::HashSet, convert::TryInto, ops::Deref};
 use git_hash::{oid, ObjectId};
 use git_pack::{cache::DecodeEntry, data::entry::Location};
 
-use crate::store::{handle, load_index};
+use crate::store::{handle, load_index, types};
 
-mod error {
+pub(crate) mod error {
     use crate::{loose, pack};
 
     /// Returned by [`Handle::try_find()`][git_pack::Find::try_find()]
@@ -54,6 +54,24 @@ mod error {
         },
     }
 
+    impl Error {
+        /// Return `true` if the object exists but couldn't be decoded as its data or the data of one of its delta bases is corrupt,
+        /// or `false` if it couldn't be obtained due to an I/O error.
+        ///
+        /// Note that objects that don't exist at all aren't errors in the first place.
+        pub fn is_corrupt(&self) -> bool {
+            match self {
+                Error::Loose(err) => err.is_corrupt(),
+                Error::Pack(_)
+                | Error::ThinPackAtRest { .. }
+                | Error::DeltaBaseRecursionLimit { .. }
+                | Error::DeltaBaseMissing { .. } => true,
+                Error::DeltaBaseLookup { err, .. } => err.is_corrupt(),
+                Error::LooseWalkDir(_) | Error::LoadIndex(_) | Error::LoadPack(_) => false,
+            }
+        }
+    }
+
     #[derive(Copy, Clone)]
     pub(crate) struct DeltaBaseRecursion<'a> {
         pub depth: usize,
@@ -96,14 +114,16 @@ where
     S: Deref<Target = super::Store> + Clone,
 {
     /// Return the exact number of packed objects after loading all currently available indices
-    /// as last seen on disk.
+    /// as last seen on disk, or the ones in the snapshot if this handle is [pinned][Self::pin()].
     pub fn packed_object_count(&self) -> Result<u64, Error> {
         let mut count = self.packed_object_count.borrow_mut();
         match *count {
             Some(count) => Ok(count),
             None => {
                 let mut snapshot = self.snapshot.borrow_mut();
-                *snapshot = self.store.load_all_indices()?;
+                if self.pinned.is_none() {
+                    *snapshot = self.store.load_all_indices()?;
+                }
                 let mut obj_count = 0;
                 for index in &snapshot.indices {
                     obj_count += index.num_objects() as u64;
@@ -176,7 +196,7 @@ where
                 }
             }
 
-            match self.store.load_one_index(self.refresh, snapshot.marker)? {
+            match self.load_one_index(snapshot.marker)? {
                 Some(new_snapshot) => {
                     drop(snapshot);
                     *self.snapshot.borrow_mut() = new_snapshot;
@@ -232,6 +252,7 @@ where
             }
         }
 
+        let mut num_retries = 0;
         'outer: loop {
             {
                 let marker = snapshot.marker;
@@ -244,14 +265,14 @@ where
                     {
                         let pack = match possibly_pack {
                             Some(pack) => pack,
-                            None => match self.store.load_pack(pack_id, marker)? {
-                                Some(pack) => {
+                            None => match self.store.load_pack(pack_id, marker) {
+                                Ok(Some(pack)) => {
                                     *possibly_pack = Some(pack);
                                     possibly_pack.as_deref().expect("just put it in")
                                 }
-                                None => {
+                                Ok(None) => {
                                     // The pack wasn't available anymore so we are supposed to try another round with a fresh index
-                                    match self.store.load_one_index(self.refresh, snapshot.marker)? {
+                                    match self.refresh_after_missing_file(marker, &mut num_retries)? {
                                         Some(new_snapshot) => {
                                             *snapshot = new_snapshot;
                                             self.clear_cache();
@@ -265,6 +286,17 @@ where
                                         }
                                     }
                                 }
+                                Err(err) => {
+                                    // The pack might be in the process of being deleted or replaced, try again with the latest state.
+                                    match self.refresh_after_missing_file(marker, &mut num_retries)? {
+                                        Some(new_snapshot) => {
+                                            *snapshot = new_snapshot;
+                                            self.clear_cache();
+                                            continue 'outer;
+                                        }
+                                        None => return Err(err.into()),
+                                    }
+                                }
                             },
                         };
                         let entry = pack.entry(pack_offset);
@@ -283,6 +315,7 @@ where
                                 git_object::Data {
                                     kind: r.kind,
                                     data: buffer.as_slice(),
+                                    object_hash: id.kind(),
                                 },
                                 Some(git_pack::data::entry::Location {
                                     pack_id: pack.id,
@@ -366,6 +399,7 @@ where
                                         git_object::Data {
                                             kind: r.kind,
                                             data: buffer.as_slice(),
+                                            object_hash: id.kind(),
                                         },
                                         Some(git_pack::data::entry::Location {
                                             pack_id: pack.id,
@@ -389,14 +423,33 @@ where
             for lodb in snapshot.loose_dbs.iter() {
                 // TODO: remove this double-lookup once the borrow checker allows it.
                 if lodb.contains(id) {
-                    return lodb
-                        .try_find(id, buffer)
-                        .map(|obj| obj.map(|obj| (obj, None)))
-                        .map_err(Into::into);
+                    match lodb.try_find(id, buffer)?.map(|obj| obj.kind) {
+                        Some(kind) => {
+                            return Ok(Some((
+                                git_object::Data {
+                                    kind,
+                                    data: buffer.as_slice(),
+                                    object_hash: id.kind(),
+                                },
+                                None,
+                            )))
+                        }
+                        None => {
+                            // The object was removed after we saw it, probably because it was just packed.
+                            match self.refresh_after_missing_file(snapshot.marker, &mut num_retries)? {
+                                Some(new_snapshot) => {
+                                    *snapshot = new_snapshot;
+                                    self.clear_cache();
+                                    continue 'outer;
+                                }
+                                None => return Ok(None),
+                            }
+                        }
+                    }
                 }
             }
 
-            match self.store.load_one_index(self.refresh, snapshot.marker)? {
+            match self.load_one_index(snapshot.marker)? {
                 Some(new_snapshot) => {
                     *snapshot = new_snapshot;
                     self.clear_cache();
@@ -406,7 +459,30 @@ where
         }
     }
 
-    fn clear_cache(&self) {
+    /// Called if a pack or loose object vanished while we tried to read an object from it, which typically happens if the object
+    /// database is repacked concurrently.
+    /// Return a snapshot of the most recent state on disk to retry with, or `None` if `num_retries` is exhausted, if we are pinned
+    /// or if nothing changed.
+    pub(crate) fn refresh_after_missing_file(
+        &self,
+        marker: types::SlotIndexMarker,
+        num_retries: &mut usize,
+    ) -> Result<Option<load_index::Snapshot>, load_index::Error> {
+        if self.pinned.is_some() || *num_retries >= self.max_missing_pack_retries {
+            return Ok(None);
+        }
+        *num_retries += 1;
+        let index = self.store.index.load();
+        if index.generation != marker.generation || index.state_id() != marker.state_id {
+            // Someone else already picked up the changes on disk.
+            return Ok(Some(self.store.collect_snapshot()));
+        }
+        // Something is missing on disk, so refresh even if our refresh mode doesn't allow it.
+        self.store
+            .consolidate_with_disk_state(false /* needs init */, true /* load one new index */)
+    }
+
+    pub(crate) fn clear_cache(&self) {
         self.packed_object_count.borrow_mut().take();
     }
 }
@@ -437,7 +513,7 @@ where
                 }
             }
 
-            match self.store.load_one_index(self.refresh, snapshot.marker) {
+            match self.load_one_index(snapshot.marker) {
                 Ok(Some(new_snapshot)) => {
                     *snapshot = new_snapshot;
                     self.clear_cache();
@@ -469,6 +545,7 @@ where
 
         let id = id.as_ref();
         let mut snapshot = self.snapshot.borrow_mut();
+        let mut num_retries = 0;
         'outer: loop {
             {
                 let marker = snapshot.marker;
@@ -481,14 +558,14 @@ where
                     {
                         let pack = match possibly_pack {
                             Some(pack) => pack,
-                            None => match self.store.load_pack(pack_id, marker).ok()? {
+                            None => match self.store.load_pack(pack_id, marker).ok().flatten() {
                                 Some(pack) => {
                                     *possibly_pack = Some(pack);
                                     possibly_pack.as_deref().expect("just put it in")
                                 }
                                 None => {
                                     // The pack wasn't available anymore so we are supposed to try another round with a fresh index
-                                    match self.store.load_one_index(self.refresh, snapshot.marker).ok()? {
+                                    match self.refresh_after_missing_file(marker, &mut num_retries).ok()? {
                                         Some(new_snapshot) => {
                                             *snapshot = new_snapshot;
                                             self.clear_cache();
@@ -525,7 +602,7 @@ where
                 }
             }
 
-            match self.store.load_one_index(self.refresh, snapshot.marker).ok()? {
+            match self.load_one_index(snapshot.marker).ok()? {
                 Some(new_snapshot) => {
                     *snapshot = new_snapshot;
                     self.clear_cache();
@@ -551,7 +628,7 @@ where
                 }
             }
 
-            match self.store.load_one_index(self.refresh, snapshot.marker).ok()? {
+            match self.load_one_index(snapshot.marker).ok()? {
                 Some(new_snapshot) => {
                     drop(snapshot);
                     *self.snapshot.borrow_mut() = new_snapshot;
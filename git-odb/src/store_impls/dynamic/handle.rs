@@ -16,6 +16,7 @@ pub(crate) mod multi_index {
     pub type File = git_pack::multi_index::File;
 }
 
+#[derive(Clone)]
 pub enum SingleOrMultiIndex {
     Single {
         index: Arc<git_pack::index::File>,
@@ -55,6 +56,7 @@ impl<'a> IntraPackLookup<'a> {
     }
 }
 
+#[derive(Clone)]
 pub struct IndexLookup {
     pub(crate) file: SingleOrMultiIndex,
     /// The index we were found at in the slot map
@@ -239,6 +241,9 @@ impl super::Store {
     /// The amount of times a ref-delta base can be followed when multi-indices are involved.
     pub const INITIAL_MAX_RECURSION_DEPTH: usize = 32;
 
+    /// The amount of times a lookup is retried after refreshing the object database if a pack or loose object vanished from disk.
+    pub const INITIAL_MAX_MISSING_PACK_RETRIES: usize = 3;
+
     /// Create a new cache filled with a handle to this store, if this store is supporting shared ownership.
     ///
     /// Note that the actual type of `OwnShared` depends on the `parallel` feature toggle of the `git-features` crate.
@@ -263,7 +268,9 @@ impl super::Store {
             token: Some(token),
             snapshot: RefCell::new(self.collect_snapshot()),
             max_recursion_depth: Self::INITIAL_MAX_RECURSION_DEPTH,
+            max_missing_pack_retries: Self::INITIAL_MAX_MISSING_PACK_RETRIES,
             packed_object_count: Default::default(),
+            pinned: None,
         }
     }
 
@@ -279,7 +286,9 @@ impl super::Store {
             token: Some(token),
             snapshot: RefCell::new(self.collect_snapshot()),
             max_recursion_depth: Self::INITIAL_MAX_RECURSION_DEPTH,
+            max_missing_pack_retries: Self::INITIAL_MAX_MISSING_PACK_RETRIES,
             packed_object_count: Default::default(),
+            pinned: None,
         }
     }
 
@@ -354,6 +363,8 @@ impl TryFrom<&super::Store> for super::Store {
                 slots: crate::store::init::Slots::Given(s.files.len().try_into().expect("BUG: too many slots")),
                 object_hash: Default::default(),
                 use_multi_pack_index: false,
+                fsync: s.fsync,
+                alternates: s.alternates.clone(),
             },
         )
     }
@@ -366,6 +377,7 @@ impl super::Handle<Rc<super::Store>> {
         let mut cache = store.to_handle_arc();
         cache.refresh = self.refresh;
         cache.max_recursion_depth = self.max_recursion_depth;
+        cache.max_missing_pack_retries = self.max_missing_pack_retries;
         Ok(cache)
     }
 }
@@ -394,9 +406,14 @@ where
                 }
                 .into()
             },
-            snapshot: RefCell::new(self.store.collect_snapshot()),
+            snapshot: RefCell::new(match self.pinned {
+                Some(_) => self.snapshot.borrow().clone(),
+                None => self.store.collect_snapshot(),
+            }),
             max_recursion_depth: self.max_recursion_depth,
+            max_missing_pack_retries: self.max_missing_pack_retries,
             packed_object_count: Default::default(),
+            pinned: self.pinned.clone(),
         }
     }
 }
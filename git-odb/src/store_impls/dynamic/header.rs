@@ -0,0 +1,187 @@
+use std::ops::Deref;
+
+use git_hash::oid;
+
+use super::find::error::DeltaBaseRecursion;
+use crate::{
+    find::Header,
+    store::{find::Error, handle, load_index},
+};
+
+impl<S> super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Return the kind and size of the object identified by `id`, along with the length of its delta chain if it is packed,
+    /// without decoding it.
+    ///
+    /// This is considerably cheaper than obtaining the object itself as only the object header and the headers of its deltas
+    /// are decompressed. Return `Ok(None)` if the object doesn't exist.
+    pub fn try_header(&self, id: impl AsRef<oid>) -> Result<Option<Header>, Error> {
+        let id = id.as_ref();
+        let mut snapshot = self.snapshot.borrow_mut();
+        self.try_header_inner(id, &mut snapshot, None)
+    }
+
+    fn try_header_inner<'b>(
+        &'b self,
+        mut id: &'b oid,
+        snapshot: &mut load_index::Snapshot,
+        recursion: Option<DeltaBaseRecursion<'_>>,
+    ) -> Result<Option<Header>, Error> {
+        if let Some(r) = recursion {
+            if r.depth >= self.max_recursion_depth {
+                return Err(Error::DeltaBaseRecursionLimit {
+                    max_depth: self.max_recursion_depth,
+                    id: r.original_id.to_owned(),
+                });
+            }
+        } else if !self.ignore_replacements {
+            if let Ok(pos) = self
+                .store
+                .replacements
+                .binary_search_by(|(map_this, _)| map_this.as_ref().cmp(id))
+            {
+                id = self.store.replacements[pos].1.as_ref();
+            }
+        }
+
+        let mut num_retries = 0;
+        'outer: loop {
+            {
+                let marker = snapshot.marker;
+                for (idx, index) in snapshot.indices.iter_mut().enumerate() {
+                    if let Some(handle::index_lookup::Outcome {
+                        object_index: handle::IndexForObjectInPack { pack_id, pack_offset },
+                        index_file,
+                        pack: possibly_pack,
+                    }) = index.lookup(id)
+                    {
+                        let pack = match possibly_pack {
+                            Some(pack) => pack,
+                            None => match self.store.load_pack(pack_id, marker) {
+                                Ok(Some(pack)) => {
+                                    *possibly_pack = Some(pack);
+                                    possibly_pack.as_deref().expect("just put it in")
+                                }
+                                Ok(None) => {
+                                    // The pack wasn't available anymore so we are supposed to try another round with a fresh index
+                                    match self.refresh_after_missing_file(marker, &mut num_retries)? {
+                                        Some(new_snapshot) => {
+                                            *snapshot = new_snapshot;
+                                            self.clear_cache();
+                                            continue 'outer;
+                                        }
+                                        None => return Ok(None),
+                                    }
+                                }
+                                Err(err) => {
+                                    // The pack might be in the process of being deleted or replaced, try again with the latest state.
+                                    match self.refresh_after_missing_file(marker, &mut num_retries)? {
+                                        Some(new_snapshot) => {
+                                            *snapshot = new_snapshot;
+                                            self.clear_cache();
+                                            continue 'outer;
+                                        }
+                                        None => return Err(err.into()),
+                                    }
+                                }
+                            },
+                        };
+                        let entry = pack.entry(pack_offset);
+                        let res = match pack.decode_header(entry, |id| {
+                            index_file.pack_offset_by_id(id).map(|pack_offset| {
+                                git_pack::data::decode_header::ResolvedBase::InPack(pack.entry(pack_offset))
+                            })
+                        }) {
+                            Ok(header) => Ok(header),
+                            Err(git_pack::data::decode_entry::Error::DeltaBaseUnresolved(base_id)) => {
+                                // Only with multi-pack indices it's allowed to refer to objects in other packs, but like
+                                // `try_find()` we resolve these safely nonetheless.
+                                let base = self
+                                    .try_header_inner(
+                                        &base_id,
+                                        snapshot,
+                                        recursion
+                                            .map(|r| r.inc_depth())
+                                            .or_else(|| DeltaBaseRecursion::new(id).into()),
+                                    )
+                                    .map_err(|err| Error::DeltaBaseLookup {
+                                        err: Box::new(err),
+                                        base_id,
+                                        id: id.to_owned(),
+                                    })?
+                                    .ok_or_else(|| Error::DeltaBaseMissing {
+                                        base_id,
+                                        id: id.to_owned(),
+                                    })?;
+                                let index = &mut snapshot.indices[idx];
+                                let handle::index_lookup::Outcome {
+                                    object_index:
+                                        handle::IndexForObjectInPack {
+                                            pack_id: _,
+                                            pack_offset,
+                                        },
+                                    index_file,
+                                    pack: possibly_pack,
+                                } = index.lookup(id).expect("to find the object again in snapshot");
+                                let pack = possibly_pack
+                                    .as_ref()
+                                    .expect("pack to still be available like just now");
+                                pack.decode_header(pack.entry(pack_offset), |id| {
+                                    index_file
+                                        .pack_offset_by_id(id)
+                                        .map(|pack_offset| {
+                                            git_pack::data::decode_header::ResolvedBase::InPack(pack.entry(pack_offset))
+                                        })
+                                        .or_else(|| {
+                                            (id == base_id).then(|| {
+                                                git_pack::data::decode_header::ResolvedBase::OutOfPack {
+                                                    kind: base.kind(),
+                                                    num_deltas: base.num_deltas(),
+                                                }
+                                            })
+                                        })
+                                })
+                            }
+                            Err(err) => Err(err),
+                        }?;
+
+                        if idx != 0 {
+                            snapshot.indices.swap(0, idx);
+                        }
+                        return Ok(Some(Header::Packed(res)));
+                    }
+                }
+            }
+
+            for lodb in snapshot.loose_dbs.iter() {
+                // TODO: remove this double-lookup once the borrow checker allows it.
+                if lodb.contains(id) {
+                    match lodb.try_header(id)? {
+                        Some((kind, size)) => return Ok(Some(Header::Loose { kind, size })),
+                        None => {
+                            // The object was removed after we saw it, probably because it was just packed.
+                            match self.refresh_after_missing_file(snapshot.marker, &mut num_retries)? {
+                                Some(new_snapshot) => {
+                                    *snapshot = new_snapshot;
+                                    self.clear_cache();
+                                    continue 'outer;
+                                }
+                                None => return Ok(None),
+                            }
+                        }
+                    }
+                }
+            }
+
+            match self.load_one_index(snapshot.marker)? {
+                Some(new_snapshot) => {
+                    *snapshot = new_snapshot;
+                    self.clear_cache();
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+}
@@ -8,7 +8,7 @@ use crate::{
 };
 
 /// Options for use in [`Store::at_opts()`].
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct Options {
     /// How to obtain a size for the slot map.
     pub slots: Slots,
@@ -16,6 +16,12 @@ pub struct Options {
     pub object_hash: git_hash::Kind,
     /// If false, no multi-pack indices will be used. If true, they will be used if their hash matches `object_hash`.
     pub use_multi_pack_index: bool,
+    /// If set, newly written loose objects and their directories are flushed to permanent storage with the given method.
+    /// Otherwise, and by default, it's left to the operating system to write them eventually.
+    pub fsync: Option<git_features::fs::fsync::Method>,
+    /// Object databases to use in addition to the ones listed in `info/alternates`, and after them, similar to
+    /// `GIT_ALTERNATE_OBJECT_DIRECTORIES`. Their own alternates are used as well.
+    pub alternates: Vec<PathBuf>,
 }
 
 impl Default for Options {
@@ -24,6 +30,8 @@ impl Default for Options {
             slots: Default::default(),
             object_hash: Default::default(),
             use_multi_pack_index: true,
+            fsync: None,
+            alternates: Vec::new(),
         }
     }
 }
@@ -72,6 +80,8 @@ impl Store {
             slots,
             object_hash,
             use_multi_pack_index,
+            fsync,
+            alternates,
         }: Options,
     ) -> std::io::Result<Self> {
         let objects_dir = objects_dir.into();
@@ -84,9 +94,8 @@ impl Store {
         let slot_count = match slots {
             Slots::Given(n) => n as usize,
             Slots::AsNeededByDiskState { multiplier, minimum } => {
-                let mut db_paths = crate::alternate::resolve(&objects_dir)
+                let db_paths = super::Store::db_paths(&objects_dir, &alternates)
                     .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
-                db_paths.insert(0, objects_dir.clone());
                 let num_slots = super::Store::collect_indices_and_mtime_sorted_by_size(db_paths, None, None)
                     .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?
                     .len();
@@ -107,10 +116,12 @@ impl Store {
             write: Default::default(),
             replacements,
             path: objects_dir,
+            alternates,
             files: Vec::from_iter(std::iter::repeat_with(MutableIndexAndPack::default).take(slot_count)),
             index: ArcSwap::new(Arc::new(SlotMapIndex::default())),
             use_multi_pack_index,
             object_hash,
+            fsync,
             num_handles_stable: Default::default(),
             num_handles_unstable: Default::default(),
             num_disk_state_consolidation: Default::default(),
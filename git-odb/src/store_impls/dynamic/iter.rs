@@ -1,4 +1,10 @@
-use std::{ops::Deref, option::Option::None, sync::Arc, vec::IntoIter};
+use std::{
+    ops::Deref,
+    option::Option::None,
+    path::{Path, PathBuf},
+    sync::Arc,
+    vec::IntoIter,
+};
 
 use git_hash::ObjectId;
 
@@ -8,6 +14,7 @@ enum State {
     Pack {
         index_iter: IntoIter<handle::IndexLookup>,
         index: handle::IndexLookup,
+        pack_paths: Vec<Arc<Path>>,
         entry_index: u32,
         num_objects: u32,
     },
@@ -18,6 +25,23 @@ enum State {
     Depleted,
 }
 
+/// The location of an object within the object store, as returned by [`AllObjects::with_location()`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Location {
+    /// The object is stored in a pack.
+    Pack {
+        /// The path to the pack data file containing the object.
+        pack_path: Arc<Path>,
+        /// The offset of the object's entry within the pack data file.
+        pack_offset: u64,
+    },
+    /// The object is stored as loose object.
+    Loose {
+        /// The path to the file containing the object.
+        path: PathBuf,
+    },
+}
+
 /// An iterator over all objects of an object store.
 pub struct AllObjects {
     state: State,
@@ -25,6 +49,11 @@ pub struct AllObjects {
     loose_dbs: Arc<Vec<loose::Store>>,
 }
 
+/// An iterator over all objects of an object store along with their [`Location`], created by [`AllObjects::with_location()`].
+pub struct AllObjectsWithLocation {
+    inner: AllObjects,
+}
+
 impl AllObjects {
     /// Create a new iterator from a dynamic store, which will be forced to load all indices eagerly and in the current thread.
     pub fn new(db: &dynamic::Store) -> Result<Self, crate::store::load_index::Error> {
@@ -41,6 +70,7 @@ impl AllObjects {
                 let num_objects = index.num_objects();
                 State::Pack {
                     index_iter,
+                    pack_paths: Vec::new(),
                     index,
                     entry_index: 0,
                     num_objects,
@@ -60,30 +90,50 @@ impl AllObjects {
             num_objects: packed_objects,
         })
     }
-}
 
-impl Iterator for AllObjects {
-    type Item = Result<ObjectId, loose::iter::Error>;
+    /// Turn this iterator into one which also provides the [`Location`] of each object.
+    pub fn with_location(self) -> AllObjectsWithLocation {
+        AllObjectsWithLocation { inner: self }
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
+    fn next_entry(&mut self, with_location: bool) -> Option<Result<(ObjectId, Option<Location>), loose::iter::Error>> {
         match &mut self.state {
             State::Depleted => None,
             State::Pack {
                 index_iter,
                 index,
+                pack_paths,
                 entry_index,
                 num_objects,
             } => {
                 if *entry_index < *num_objects {
                     let oid = index.oid_at_index(*entry_index).to_owned();
+                    let location = with_location.then(|| {
+                        if pack_paths.is_empty() {
+                            *pack_paths = pack_paths_of(index);
+                        }
+                        let (pack_index, pack_offset) = match &index.file {
+                            handle::SingleOrMultiIndex::Single { index, .. } => {
+                                (0, index.pack_offset_at_index(*entry_index))
+                            }
+                            handle::SingleOrMultiIndex::Multi { index, .. } => {
+                                index.pack_id_and_pack_offset_at_index(*entry_index)
+                            }
+                        };
+                        Location::Pack {
+                            pack_path: Arc::clone(&pack_paths[pack_index as usize]),
+                            pack_offset,
+                        }
+                    });
                     *entry_index += 1;
-                    Some(Ok(oid))
+                    Some(Ok((oid, location)))
                 } else {
                     match index_iter.next() {
                         Some(new_index) => {
                             *index = new_index;
                             *entry_index = 0;
                             *num_objects = index.num_objects();
+                            pack_paths.clear();
                         }
                         None => {
                             let index = 0;
@@ -93,17 +143,25 @@ impl Iterator for AllObjects {
                             }
                         }
                     }
-                    self.next()
+                    self.next_entry(with_location)
                 }
             }
             State::Loose { iter, index } => match iter.next() {
-                Some(id) => Some(id),
+                Some(id) => {
+                    let loose_db = &self.loose_dbs[*index];
+                    Some(id.map(|id| {
+                        let location = with_location.then(|| Location::Loose {
+                            path: loose::hash_path(&id, loose_db.path().to_owned()),
+                        });
+                        (id, location)
+                    }))
+                }
                 None => {
                     *index += 1;
                     match self.loose_dbs.get(*index).map(|ldb| ldb.iter()) {
                         Some(new_iter) => {
                             *iter = new_iter;
-                            self.next()
+                            self.next_entry(with_location)
                         }
                         None => {
                             self.state = State::Depleted;
@@ -114,12 +172,52 @@ impl Iterator for AllObjects {
             },
         }
     }
+}
+
+/// Return the paths to all pack data files of `index`, in the order of their pack index.
+fn pack_paths_of(index: &handle::IndexLookup) -> Vec<Arc<Path>> {
+    match &index.file {
+        handle::SingleOrMultiIndex::Single { index, .. } => vec![index.path().with_extension("pack").into()],
+        handle::SingleOrMultiIndex::Multi { index, .. } => {
+            let pack_dir = index
+                .path()
+                .parent()
+                .expect("multi-index is located in the pack directory");
+            index
+                .index_names()
+                .iter()
+                .map(|name| pack_dir.join(name).with_extension("pack").into())
+                .collect()
+        }
+    }
+}
+
+impl Iterator for AllObjects {
+    type Item = Result<ObjectId, loose::iter::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_entry(false).map(|res| res.map(|(id, _)| id))
+    }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
         (self.num_objects, None)
     }
 }
 
+impl Iterator for AllObjectsWithLocation {
+    type Item = Result<(ObjectId, Location), loose::iter::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_entry(true)
+            .map(|res| res.map(|(id, location)| (id, location.expect("location is always computed if requested"))))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
 impl<S> super::Handle<S>
 where
     S: Deref<Target = super::Store> + Clone,
@@ -12,6 +12,7 @@ use std::{
 
 use crate::store::{handle, types, RefreshMode};
 
+#[derive(Clone)]
 pub(crate) struct Snapshot {
     /// Indices ready for object lookup or contains checks, ordered usually by modification data, recent ones first.
     pub(crate) indices: Vec<handle::IndexLookup>,
@@ -197,9 +198,7 @@ impl super::Store {
         }
         self.num_disk_state_consolidation.fetch_add(1, Ordering::Relaxed);
 
-        let db_paths: Vec<_> = std::iter::once(objects_directory.to_owned())
-            .chain(crate::alternate::resolve(objects_directory)?)
-            .collect();
+        let db_paths = Self::db_paths(objects_directory, &self.alternates)?;
 
         // turn db paths into loose object databases. Reuse what's there, but only if it is in the right order.
         let loose_dbs = if was_uninitialized
@@ -212,7 +211,7 @@ impl super::Store {
             Arc::new(
                 db_paths
                     .iter()
-                    .map(|path| crate::loose::Store::at(path, self.object_hash))
+                    .map(|path| crate::loose::Store::at(path, self.object_hash).with_fsync(self.fsync))
                     .collect::<Vec<_>>(),
             )
         } else {
@@ -594,6 +593,41 @@ impl super::Store {
         self.num_handles_stable.load(Ordering::SeqCst) > 0
     }
 
+    /// Return `objects_directory` followed by all of its alternates and the given `alternates` along with their alternates,
+    /// in that order.
+    pub(crate) fn db_paths(
+        objects_directory: &Path,
+        alternates: &[PathBuf],
+    ) -> Result<Vec<PathBuf>, crate::alternate::Error> {
+        let mut db_paths: Vec<_> = std::iter::once(objects_directory.to_owned())
+            .chain(crate::alternate::resolve(objects_directory)?)
+            .collect();
+        for alternate in alternates {
+            if db_paths.contains(alternate) {
+                continue;
+            }
+            db_paths.push(alternate.to_owned());
+            db_paths.extend(crate::alternate::resolve(alternate)?);
+        }
+        Ok(db_paths)
+    }
+
+    /// Return the paths and modification times of all pack indices and multi-pack indices currently on disk, sorted by path,
+    /// to be able to tell if the disk state changed compared to an earlier call.
+    pub(crate) fn index_files_on_disk(&self) -> Result<Vec<(PathBuf, SystemTime)>, Error> {
+        let db_paths = Self::db_paths(&self.path, &self.alternates)?;
+        let mut files: Vec<_> = Self::collect_indices_and_mtime_sorted_by_size(
+            db_paths,
+            None,
+            self.use_multi_pack_index.then(|| self.object_hash),
+        )?
+        .into_iter()
+        .map(|(file, mtime, _len)| (file.path().to_owned(), mtime))
+        .collect();
+        files.sort();
+        Ok(files)
+    }
+
     pub(crate) fn collect_snapshot(&self) -> Snapshot {
         let index = self.index.load();
         let indices = if index.is_initialized() {
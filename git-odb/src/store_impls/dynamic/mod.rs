@@ -18,6 +18,9 @@ where
     /// This value doesn't have to be huge as in typical scenarios, these kind of objects are rare and chains supposedly are
     /// even more rare.
     pub max_recursion_depth: usize,
+    /// The maximum amount of times the object database is refreshed to retry a lookup if a pack or loose object vanished while
+    /// trying to read an object from it, as happens if the repository is repacked concurrently by another process.
+    pub max_missing_pack_retries: usize,
 
     /// If true, replacements will not be performed even if these are available.
     pub ignore_replacements: bool,
@@ -25,6 +28,8 @@ where
     pub(crate) token: Option<handle::Mode>,
     snapshot: RefCell<load_index::Snapshot>,
     packed_object_count: RefCell<Option<u64>>,
+    /// Boxed as handles are part of every repository, but are rarely pinned.
+    pinned: Option<Box<pin::State>>,
 }
 
 /// Decide what happens when all indices are loaded.
@@ -55,6 +60,8 @@ impl RefreshMode {
 ///
 pub mod find;
 
+mod header;
+
 ///
 pub mod iter;
 
@@ -75,6 +82,8 @@ pub mod load_index;
 ///
 pub mod verify;
 
+mod pin;
+
 mod load_one;
 
 mod metrics;
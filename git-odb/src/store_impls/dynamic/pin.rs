@@ -0,0 +1,71 @@
+use std::{ops::Deref, path::PathBuf, time::SystemTime};
+
+use crate::store::{load_index, types};
+
+/// The index files on disk at the time a handle was pinned.
+#[derive(Clone)]
+pub(crate) struct State {
+    index_files: Vec<(PathBuf, SystemTime)>,
+}
+
+/// Snapshot pinning
+impl<S> super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Load all pack indices currently on disk and keep using exactly these for all subsequent lookups, ignoring all changes
+    /// made to the object database by concurrent repacks or garbage collection, until [`unpin()`][Self::unpin()] is called.
+    ///
+    /// This is useful for operations that consist of multiple steps which have to see the same set of packed objects, like
+    /// negotiating and then producing a pack for a client. Packs in the snapshot remain available even if they are deleted from disk
+    /// in the meantime, as [`prevent_pack_unload()`][Self::prevent_pack_unload()] is called as well.
+    /// Use [`is_stale()`][Self::is_stale()] to learn if the object database changed since.
+    ///
+    /// Clones of a pinned handle are pinned to the same snapshot.
+    ///
+    /// Note that loose objects are always read from disk and are thus not part of the snapshot.
+    /// Calling this on a handle that is already pinned will pin the most recent state of the object database.
+    pub fn pin(&mut self) -> Result<(), load_index::Error> {
+        self.prevent_pack_unload();
+        self.store
+            .consolidate_with_disk_state(false /* needs init */, false /* load new index */)?;
+        let index_files = self.store.index_files_on_disk()?;
+        *self.snapshot.borrow_mut() = self.store.load_all_indices()?;
+        self.clear_cache();
+        self.pinned = Some(Box::new(State { index_files }));
+        Ok(())
+    }
+
+    /// Stop using the snapshot obtained by [`pin()`][Self::pin()] and pick up changes to the object database again on demand.
+    pub fn unpin(&mut self) {
+        self.pinned = None;
+    }
+
+    /// Return `true` if this handle was [pinned][Self::pin()] to a snapshot of the object database.
+    pub fn is_pinned(&self) -> bool {
+        self.pinned.is_some()
+    }
+
+    /// Return `true` if the pack indices on disk differ from the ones seen when this handle was [pinned][Self::pin()], which
+    /// happens if packs were added or removed, for instance by fetches, repacks or garbage collection.
+    ///
+    /// Return `false` if the handle isn't pinned, as it will pick up changes on its own.
+    /// Note that this call always reads the pack directories, and changes to loose objects are not detected.
+    pub fn is_stale(&self) -> Result<bool, load_index::Error> {
+        Ok(match &self.pinned {
+            Some(state) => self.store.index_files_on_disk()? != state.index_files,
+            None => false,
+        })
+    }
+
+    /// Like [`Store::load_one_index()`][super::Store::load_one_index()], but never changes the set of indices if pinned.
+    pub(crate) fn load_one_index(
+        &self,
+        marker: types::SlotIndexMarker,
+    ) -> Result<Option<load_index::Snapshot>, load_index::Error> {
+        match self.pinned {
+            Some(_) => Ok(None),
+            None => self.store.load_one_index(self.refresh, marker),
+        }
+    }
+}
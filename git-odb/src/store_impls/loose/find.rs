@@ -4,6 +4,9 @@ use git_features::zlib;
 
 use crate::store_impls::loose::{hash_path, Store, HEADER_READ_UNCOMPRESSED_BYTES};
 
+/// The amount of compressed bytes to read to be able to decompress the header of a loose object.
+const HEADER_READ_COMPRESSED_BYTES: usize = 256;
+
 /// Returned by [`Store::try_find()`]
 #[derive(thiserror::Error, Debug)]
 #[allow(missing_docs)]
@@ -21,6 +24,23 @@ pub enum Error {
         action: &'static str,
         path: PathBuf,
     },
+    #[error("The loose object at '{path}' claims to have {expected} bytes, but {actual} bytes were decompressed")]
+    SizeMismatch {
+        expected: usize,
+        actual: usize,
+        path: PathBuf,
+    },
+}
+
+impl Error {
+    /// Return `true` if the object exists but couldn't be decoded as its data is corrupt, or `false` if it couldn't be read
+    /// due to an I/O error.
+    pub fn is_corrupt(&self) -> bool {
+        match self {
+            Error::DecompressFile { .. } | Error::Decode(_) | Error::SizeMismatch { .. } => true,
+            Error::Io { .. } => false,
+        }
+    }
 }
 
 /// Object lookup
@@ -124,6 +144,49 @@ impl Store {
         }
     }
 
+    /// Return only the kind and size of the object identified by the given [`ObjectId`][git_hash::ObjectId] if present in
+    /// this database, which only decompresses the object header and is thus much cheaper than [`try_find()`][Self::try_find()].
+    ///
+    /// Returns `Ok<None>` if there was no such object.
+    pub fn try_header(&self, id: impl AsRef<git_hash::oid>) -> Result<Option<(git_object::Kind, u64)>, Error> {
+        debug_assert_eq!(self.object_hash, id.as_ref().kind());
+        let path = hash_path(id.as_ref(), self.path.clone());
+        let mut istream = match fs::File::open(&path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(Error::Io {
+                    source: err,
+                    action: Self::OPEN_ACTION,
+                    path,
+                })
+            }
+        };
+
+        let mut input = [0; HEADER_READ_COMPRESSED_BYTES];
+        let mut bytes_read = 0;
+        while bytes_read < input.len() {
+            match istream.read(&mut input[bytes_read..]) {
+                Ok(0) => break,
+                Ok(n) => bytes_read += n,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(err) => {
+                    return Err(Error::Io {
+                        source: err,
+                        action: "read",
+                        path,
+                    })
+                }
+            }
+        }
+        let mut output = [0; HEADER_READ_UNCOMPRESSED_BYTES];
+        let (_status, _consumed_in, consumed_out) = zlib::Inflate::default()
+            .once(&input[..bytes_read], &mut output)
+            .map_err(|e| Error::DecompressFile { source: e, path })?;
+        let (kind, size, _header_size) = git_object::decode::loose_header(&output[..consumed_out])?;
+        Ok(Some((kind, size as u64)))
+    }
+
     fn find_inner<'a>(&self, id: &git_hash::oid, buf: &'a mut Vec<u8>) -> Result<git_object::Data<'a>, Error> {
         let path = hash_path(id, self.path.clone());
 
@@ -166,11 +229,13 @@ impl Store {
         if status == zlib::Status::StreamEnd {
             let decompressed_body_bytes_sans_header =
                 decompressed_start + header_size..decompressed_start + consumed_out;
-            assert_eq!(
-                consumed_out,
-                size + header_size,
-                "At this point we have decompressed everything and given 'size' should match"
-            );
+            if consumed_out != size + header_size {
+                return Err(Error::SizeMismatch {
+                    expected: size,
+                    actual: consumed_out.saturating_sub(header_size),
+                    path,
+                });
+            }
             buf.copy_within(decompressed_body_bytes_sans_header, 0);
         } else {
             buf.resize(bytes_read + size + header_size, 0);
@@ -181,20 +246,25 @@ impl Store {
                     &mut inflate.state,
                     &mut output[consumed_out..],
                 )
-                .map_err(|e| Error::Io {
-                    source: e,
-                    action: "deflate",
+                .map_err(|e| Error::DecompressFile {
+                    source: zlib::inflate::Error::WriteInflated(e),
                     path: path.to_owned(),
                 })?;
-                assert_eq!(
-                    num_decompressed_bytes + consumed_out,
-                    size + header_size,
-                    "Object should have been decompressed entirely and match given 'size'"
-                );
+                if num_decompressed_bytes + consumed_out != size + header_size {
+                    return Err(Error::SizeMismatch {
+                        expected: size,
+                        actual: (num_decompressed_bytes + consumed_out).saturating_sub(header_size),
+                        path,
+                    });
+                }
             };
             buf.copy_within(decompressed_start + header_size.., 0);
         }
         buf.resize(size, 0);
-        Ok(git_object::Data { kind, data: buf })
+        Ok(git_object::Data {
+            kind,
+            data: buf,
+            object_hash: self.object_hash,
+        })
     }
 }
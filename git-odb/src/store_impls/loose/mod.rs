@@ -11,6 +11,8 @@ pub struct Store {
     pub(crate) path: PathBuf,
     /// The kind of hash we should assume during iteration and when writing new objects.
     pub(crate) object_hash: git_hash::Kind,
+    /// If set, flush newly written objects and their directories to permanent storage using the given method.
+    pub(crate) fsync: Option<fs::fsync::Method>,
 }
 
 /// Initialization
@@ -25,9 +27,17 @@ impl Store {
         Store {
             path: objects_directory.into(),
             object_hash,
+            fsync: None,
         }
     }
 
+    /// Flush newly written objects and the directories containing them to permanent storage using `method`, or don't flush
+    /// them at all if `None`.
+    pub fn with_fsync(mut self, method: Option<fs::fsync::Method>) -> Self {
+        self.fsync = method;
+        self
+    }
+
     /// Return the path to our `objects` directory.
     pub fn path(&self) -> &Path {
         &self.path
@@ -39,7 +49,7 @@ impl Store {
     }
 }
 
-fn hash_path(id: &git_hash::oid, mut root: PathBuf) -> PathBuf {
+pub(crate) fn hash_path(id: &git_hash::oid, mut root: PathBuf) -> PathBuf {
     let mut hex = git_hash::Kind::hex_buf();
     let hex_len = id.hex_to_buf(hex.as_mut());
     let buf = std::str::from_utf8(&hex[..hex_len]).expect("ascii only in hex");
@@ -1,6 +1,6 @@
 use std::{convert::TryInto, fs, io, io::Write, path::PathBuf};
 
-use git_features::{hash, zlib::stream::deflate};
+use git_features::{fs::fsync, hash, zlib::stream::deflate};
 use git_object::WriteTo;
 use tempfile::NamedTempFile;
 
@@ -114,22 +114,32 @@ impl Store {
         &self,
         hash::Write { hash, inner: file }: hash::Write<CompressedTempfile>,
     ) -> Result<git_hash::ObjectId, Error> {
-        let id = git_hash::ObjectId::from(hash.digest());
+        let id = hash.digest();
         let object_path = loose::hash_path(&id, self.path.clone());
         let object_dir = object_path
             .parent()
             .expect("each object path has a 1 hex-bytes directory");
-        if let Err(err) = fs::create_dir(object_dir) {
-            match err.kind() {
-                io::ErrorKind::AlreadyExists => {}
+        let created_object_dir = match fs::create_dir(object_dir) {
+            Ok(()) => true,
+            Err(err) => match err.kind() {
+                io::ErrorKind::AlreadyExists => false,
                 _ => return Err(err.into()),
-            }
-        }
+            },
+        };
         let file = file.into_inner();
+        if let Some(method) = self.fsync {
+            fsync::file(file.as_file(), method)?;
+        }
         file.persist(&object_path).map_err(|err| Error::Persist {
             source: err,
-            target: object_path,
+            target: object_path.clone(),
         })?;
+        if self.fsync.is_some() {
+            fsync::directory(object_dir)?;
+            if created_object_dir {
+                fsync::directory(&self.path)?;
+            }
+        }
         Ok(id)
     }
 }
@@ -26,3 +26,100 @@ fn pack_object() {
     can_locate(&db, "4dac9989f96bc5b5b1263b582c08f0c5f0b58542"); // pack a2bf
     can_locate(&db, "dd25c539efbb0ab018caa4cda2d133285634e9b5"); // pack c043
 }
+
+mod cache_statistics {
+    use std::collections::HashMap;
+
+    use git_odb::Find;
+
+    use super::linked_db;
+    use crate::hex_to_id;
+
+    #[derive(Default)]
+    struct MapCache(HashMap<(u32, u64), (git_object::Kind, Vec<u8>, usize)>);
+
+    impl git_pack::cache::DecodeEntry for MapCache {
+        fn put(&mut self, pack_id: u32, offset: u64, data: &[u8], kind: git_object::Kind, compressed_size: usize) {
+            self.0
+                .insert((pack_id, offset), (kind, data.to_owned(), compressed_size));
+        }
+
+        fn get(&mut self, pack_id: u32, offset: u64, out: &mut Vec<u8>) -> Option<(git_object::Kind, usize)> {
+            self.0.get(&(pack_id, offset)).map(|(kind, data, compressed_size)| {
+                out.clear();
+                out.extend_from_slice(data);
+                (*kind, *compressed_size)
+            })
+        }
+    }
+
+    #[derive(Default)]
+    struct MapObjectCache(HashMap<git_hash::ObjectId, (git_object::Kind, Vec<u8>)>);
+
+    impl git_pack::cache::Object for MapObjectCache {
+        fn put(&mut self, id: git_hash::ObjectId, kind: git_object::Kind, data: &[u8]) {
+            self.0.insert(id, (kind, data.to_owned()));
+        }
+
+        fn get(&mut self, id: &git_hash::ObjectId, out: &mut Vec<u8>) -> Option<git_object::Kind> {
+            self.0.get(id).map(|(kind, data)| {
+                out.clear();
+                out.extend_from_slice(data);
+                *kind
+            })
+        }
+    }
+
+    #[test]
+    fn are_empty_without_caches() {
+        let db = linked_db();
+        let mut buf = Vec::new();
+        db.try_find(hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0"), &mut buf)
+            .expect("no read error")
+            .expect("present");
+        assert_eq!(db.statistics(), Default::default(), "nothing is counted without caches");
+    }
+
+    #[test]
+    fn count_hits_and_misses_of_present_caches_per_instance() {
+        let mut db = linked_db();
+        db.set_object_cache(|| Box::new(MapObjectCache::default()));
+
+        let id = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+        let mut buf = Vec::new();
+        for _round in 0..2 {
+            db.try_find(id, &mut buf).expect("no read error").expect("present");
+        }
+        assert_eq!(
+            db.statistics().object_cache,
+            git_odb::cache::Hits { hits: 1, misses: 1 },
+            "the second lookup is served by the object cache"
+        );
+        assert_eq!(
+            db.statistics().pack_cache,
+            Default::default(),
+            "there is no pack cache to count"
+        );
+
+        let clone = db.clone();
+        assert_eq!(clone.statistics(), Default::default(), "clones have their own caches");
+
+        db.reset_statistics();
+        assert_eq!(db.statistics(), Default::default());
+    }
+
+    #[test]
+    fn count_pack_cache_lookups_while_decoding_deltas() -> crate::Result {
+        let mut db = linked_db();
+        db.set_pack_cache(|| Box::new(MapCache::default()));
+        let mut buf = Vec::new();
+        for id in db.iter()? {
+            db.try_find(id?, &mut buf)?.expect("present");
+        }
+        let stats = db.statistics();
+        assert!(stats.pack_cache.misses > 0, "delta bases are looked up in the cache");
+        assert!(stats.pack_cache.hits > 0, "and are found once they are stored");
+        assert_eq!(stats.object_cache, Default::default());
+        Ok(())
+    }
+}
@@ -205,6 +205,33 @@ fn write() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn additional_alternates_are_used_after_the_configured_ones() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let handle = git_odb::at_opts(
+        dir.path(),
+        None,
+        store::init::Options {
+            alternates: vec![fixture_path("objects")],
+            ..Default::default()
+        },
+    )?;
+    let mut buf = Vec::new();
+    assert!(
+        handle.contains(hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0")),
+        "objects are found in the additional alternate"
+    );
+    assert!(handle
+        .try_find(hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0"), &mut buf)?
+        .is_some());
+    assert_eq!(
+        handle.store_ref().structure()?.len(),
+        2 + 3,
+        "one loose db for each path, plus the alternate's packs"
+    );
+    Ok(())
+}
+
 #[test]
 fn object_replacement() {
     let dir = git_testtools::scripted_fixture_repo_read_only("make_replaced_history.sh").unwrap();
@@ -687,6 +714,62 @@ fn iterate_over_a_bunch_of_loose_and_packed_objects() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn headers_match_the_kind_and_size_of_decoded_objects() -> crate::Result {
+    let (db, _tmp) = db_with_all_object_sources()?;
+    let mut buf = Vec::new();
+    let (mut num_loose, mut num_deltified) = (0, 0);
+    for id in db.iter()? {
+        let id = id?;
+        let header = db.try_header(id)?.expect("object exists");
+        let object = db.find(id, &mut buf)?;
+        assert_eq!(header.kind(), object.kind);
+        assert_eq!(header.size(), object.data.len() as u64);
+        match header {
+            git_odb::find::Header::Loose { .. } => num_loose += 1,
+            git_odb::find::Header::Packed(out) if out.num_deltas > 0 => num_deltified += 1,
+            git_odb::find::Header::Packed(_) => {}
+        }
+    }
+    assert_eq!(num_loose, 7);
+    assert!(num_deltified > 0, "some packed objects are deltified");
+    assert_eq!(
+        db.try_header(hex_to_id("cccccccccccccccccccccccccccccccccccccccc"))?,
+        None
+    );
+    Ok(())
+}
+
+#[test]
+fn iterate_over_a_bunch_of_loose_and_packed_objects_with_their_location() -> crate::Result {
+    let (db, _tmp) = db_with_all_object_sources()?;
+    let mut pack_paths = std::collections::BTreeSet::new();
+    let mut num_loose = 0;
+    for item in db.iter()?.with_location() {
+        let (id, location) = item?;
+        match location {
+            store::iter::Location::Pack { pack_path, pack_offset } => {
+                assert!(pack_path.is_file(), "pack data files exist");
+                let index = git_odb::pack::index::File::at(pack_path.with_extension("idx"), git_hash::Kind::Sha1)?;
+                let entry_index = index.lookup(id).expect("object is in the pack it is said to be in");
+                assert_eq!(index.pack_offset_at_index(entry_index), pack_offset);
+                pack_paths.insert(pack_path);
+            }
+            store::iter::Location::Loose { path } => {
+                assert!(path.is_file(), "loose objects are files");
+                num_loose += 1;
+            }
+        }
+    }
+    assert_eq!(
+        pack_paths.len(),
+        3,
+        "the multi-pack index refers to two packs, and there is one more pack"
+    );
+    assert_eq!(num_loose, 7);
+    Ok(())
+}
+
 #[test]
 fn auto_refresh_with_and_without_id_stability() -> crate::Result {
     let tmp = git_testtools::tempfile::TempDir::new()?;
@@ -839,6 +922,126 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn pinned_handles_ignore_changes_on_disk_until_unpinned() -> crate::Result {
+    let tmp = git_testtools::tempfile::TempDir::new()?;
+    assert!(
+        Command::new("git")
+            .arg("-C")
+            .arg(tmp.path())
+            .arg("init")
+            .arg("--bare")
+            .status()?
+            .success(),
+        "git should work"
+    );
+    git_testtools::copy_recursively_into_existing_dir(fixture_path("objects/pack"), tmp.path().join("objects/pack"))?;
+    let move_pack = |name: &str, from: &str, to: &str| {
+        let stem = tmp.path().join("objects/pack").join(name);
+        for ext in &["idx", "pack"] {
+            std::fs::rename(
+                stem.with_extension(format!("{}{}", ext, from)),
+                stem.with_extension(format!("{}{}", ext, to)),
+            )
+            .unwrap();
+        }
+    };
+    move_pack("pack-11fdfa9e156ab73caae3b6da867192221f2089c2", "", ".bak");
+    move_pack("pack-a2bf8e71d8c18879e499335762dd95119d93d9f1", "", ".bak");
+
+    let mut handle = git_odb::at(tmp.path().join("objects"))?;
+    assert!(!handle.is_pinned());
+    assert!(!handle.is_stale()?, "unpinned handles are never stale");
+    handle.pin()?;
+    assert!(handle.is_pinned());
+    assert!(!handle.is_stale()?, "nothing changed on disk yet");
+
+    let mut buf = Vec::new();
+    let packed_id = hex_to_id("dd25c539efbb0ab018caa4cda2d133285634e9b5");
+    let new_id = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+    assert!(handle.try_find(packed_id, &mut buf)?.is_some());
+    assert_eq!(handle.packed_object_count()?, 67);
+
+    move_pack("pack-c0438c19fb16422b6bbcce24387b3264416d485b", "", ".bak");
+    move_pack("pack-11fdfa9e156ab73caae3b6da867192221f2089c2", ".bak", "");
+    assert!(handle.is_stale()?, "the packs on disk changed");
+
+    let pinned_clone = handle.clone();
+    for handle in [&handle, &pinned_clone] {
+        assert!(handle.is_pinned(), "clones are pinned to the same snapshot");
+        assert!(
+            handle.try_find(packed_id, &mut buf)?.is_some(),
+            "the removed pack remains available"
+        );
+        assert!(
+            handle.try_find(new_id, &mut buf)?.is_none(),
+            "the new pack isn't picked up"
+        );
+        assert_eq!(handle.packed_object_count()?, 67, "the count reflects the snapshot");
+    }
+    assert_eq!(
+        handle.store_ref().metrics().num_refreshes,
+        1,
+        "the disk state was read only once when pinning"
+    );
+
+    handle.unpin();
+    assert!(!handle.is_stale()?);
+    assert!(
+        handle.try_find(new_id, &mut buf)?.is_some(),
+        "after unpinning, the new pack is found after a refresh"
+    );
+
+    handle.pin()?;
+    assert!(!handle.is_stale()?, "pinning again picks up the current state");
+    Ok(())
+}
+
+#[test]
+fn lookups_are_retried_if_a_pack_vanished_due_to_a_concurrent_repack() -> crate::Result {
+    for (max_missing_pack_retries, expect_found) in
+        [(git_odb::Store::INITIAL_MAX_MISSING_PACK_RETRIES, true), (0, false)]
+    {
+        let tmp = git_testtools::tempfile::TempDir::new()?;
+        std::fs::create_dir(tmp.path().join("pack"))?;
+        git_testtools::copy_recursively_into_existing_dir(fixture_path("objects/pack"), tmp.path().join("pack"))?;
+        let mut handle = git_odb::at(tmp.path())?;
+        handle.refresh_never();
+        handle.max_missing_pack_retries = max_missing_pack_retries;
+
+        let id = hex_to_id("dd25c539efbb0ab018caa4cda2d133285634e9b5");
+        assert!(handle.contains(id), "indices are loaded, but the pack isn't");
+
+        let repack = |from: &str, to: &str| {
+            for ext in &["idx", "pack"] {
+                let pack_dir = tmp.path().join("pack");
+                std::fs::rename(
+                    pack_dir.join(from).with_extension(ext),
+                    pack_dir.join(to).with_extension(ext),
+                )
+                .unwrap();
+            }
+        };
+        repack(
+            "pack-c0438c19fb16422b6bbcce24387b3264416d485b",
+            "pack-0000000000000000000000000000000000000000",
+        );
+
+        let mut buf = Vec::new();
+        assert_eq!(
+            handle.try_find(id, &mut buf)?.is_some(),
+            expect_found,
+            "the object is found in its new pack even though refreshes are disabled, unless retries are disabled"
+        );
+        assert_eq!(
+            handle.store_ref().metrics().num_refreshes,
+            1 + usize::from(expect_found),
+            "one refresh for the retry"
+        );
+    }
+    Ok(())
+}
+
 mod verify {
     use std::sync::atomic::AtomicBool;
 
@@ -70,6 +70,25 @@ mod write {
         }
         Ok(())
     }
+
+    #[test]
+    fn write_with_fsync() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let db = loose::Store::at(dir.path(), git_hash::Kind::Sha1)
+            .with_fsync(Some(git_features::fs::fsync::Method::WriteoutOnly));
+        let mut buf = Vec::new();
+        let mut buf2 = Vec::new();
+
+        for oid in object_ids() {
+            let obj = locate_oid(oid, &mut buf);
+            assert_eq!(db.write_buf(obj.kind, obj.data)?, oid);
+            assert_eq!(
+                db.try_find(oid, &mut buf2)?.expect("id present").decode()?,
+                obj.decode()?
+            );
+        }
+        Ok(())
+    }
 }
 
 mod contains {
@@ -274,6 +293,38 @@ cjHJZXWmV4CcRfmLsXzU8s2cR9A0DBvOxhPD1TlKC2JhBFXigjuL9U4Rbq9tdegB
         Ok(())
     }
 
+    #[test]
+    fn corrupt_objects_are_errors_unlike_missing_ones() -> crate::Result {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir()?;
+        let db = git_odb::loose::Store::at(dir.path(), git_hash::Kind::Sha1);
+        let id = hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980");
+        let object_path = dir.path().join("37").join("d4e6c5c48ba0d245164c4e10d5f41140cab980");
+        std::fs::create_dir(object_path.parent().expect("parent"))?;
+        let mut buf = Vec::new();
+        assert!(db.try_find(id, &mut buf)?.is_none(), "missing objects aren't errors");
+
+        let deflated = |data: &[u8]| -> std::io::Result<Vec<u8>> {
+            let mut out = git_features::zlib::stream::deflate::Write::new(Vec::new());
+            out.write_all(data)?;
+            out.flush()?;
+            Ok(out.into_inner())
+        };
+        let valid = std::fs::read(crate::fixture_path("objects/37/d4e6c5c48ba0d245164c4e10d5f41140cab980"))?;
+        for data in [
+            b"garbage".to_vec(),
+            valid[..valid.len() / 2].to_vec(),
+            deflated(b"blob 10\0too short")?,
+            deflated(b"unknown 3\0abc")?,
+        ] {
+            std::fs::write(&object_path, &data)?;
+            let err = db.try_find(id, &mut buf).expect_err("corrupt objects can't be decoded");
+            assert!(err.is_corrupt(), "{:?}", err);
+        }
+        Ok(())
+    }
+
     fn try_locate<'a>(hex: &str, buf: &'a mut Vec<u8>) -> Option<git_object::Data<'a>> {
         ldb().try_find(hex_to_id(hex), buf).ok().flatten()
     }
@@ -0,0 +1,90 @@
+use std::path::Path;
+
+use crate::{
+    bitmap::{decode, init, Bitmap, File, Version},
+    index::EntryIndex,
+};
+
+/// Access methods
+impl File {
+    /// Returns the version of the bitmap file.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+    /// Returns the path from which the bitmap file was loaded.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    /// Returns the amount of commits that have a bitmap.
+    pub fn num_commits(&self) -> u32 {
+        self.entries.len() as u32
+    }
+    /// Returns the checksum of the pack this file has bitmaps for, which is the same as [`crate::index::File::pack_checksum()`]
+    /// of its index.
+    pub fn pack_checksum(&self) -> git_hash::ObjectId {
+        git_hash::ObjectId::from(&self.data[12..][..self.hash_len])
+    }
+    /// Returns true if the file has a table to look up the bitmap of a commit, which makes opening it faster.
+    pub fn has_lookup_table(&self) -> bool {
+        self.flags & init::FLAG_LOOKUP_TABLE != 0
+    }
+    /// Returns true if the file contains pseudo-merge bitmaps, which are ignored.
+    pub fn has_pseudo_merges(&self) -> bool {
+        self.flags & init::FLAG_PSEUDO_MERGES != 0
+    }
+    /// Returns the positions of the commits with a bitmap in the pack index, in ascending order.
+    pub fn commits(&self) -> impl Iterator<Item = EntryIndex> + '_ {
+        self.entries.iter().map(|entry| entry.commit_index)
+    }
+}
+
+impl File {
+    /// Return the bitmap of all objects in the pack of the given `kind`.
+    pub fn objects_of_kind(&self, kind: git_object::Kind) -> Result<Bitmap, decode::Error> {
+        use git_object::Kind::*;
+        let ofs = self.kind_ofs[match kind {
+            Commit => 0,
+            Tree => 1,
+            Blob => 2,
+            Tag => 3,
+        }];
+        let (ewah, _) = git_bitmap::ewah::decode(&self.data[ofs..])?;
+        Ok(Bitmap::from_ewah(&ewah))
+    }
+
+    /// Return the bitmap of all objects reachable from the commit at `index` in the pack index, including the commit itself,
+    /// or `None` if the commit doesn't have a bitmap.
+    pub fn commit_bitmap(&self, index: EntryIndex) -> Result<Option<Bitmap>, decode::Error> {
+        let row = match self.entries.binary_search_by_key(&index, |entry| entry.commit_index) {
+            Ok(row) => row,
+            Err(_) => return Ok(None),
+        };
+        let mut chain = vec![row];
+        while let Some(row) = self.entries[*chain.last().expect("non-empty")].xor_with {
+            if chain.len() > self.entries.len() {
+                return Err(decode::Error::Corrupt {
+                    message: "bitmaps are XORed with each other in a cycle",
+                });
+            }
+            chain.push(row as usize);
+        }
+        let mut bitmap = Bitmap::default();
+        for row in chain.into_iter().rev() {
+            bitmap.xor(&self.entry_bitmap(row)?);
+        }
+        Ok(Some(bitmap))
+    }
+
+    /// Decode the bitmap of the entry at `row`, without applying its XOR base.
+    fn entry_bitmap(&self, row: usize) -> Result<Bitmap, decode::Error> {
+        let entry = self.entries[row];
+        let data = &self.data[entry.ofs..self.data.len() - self.hash_len];
+        if data.len() < 6 || crate::read_u32(&data[..4]) != entry.commit_index {
+            return Err(decode::Error::Corrupt {
+                message: "bitmap entry doesn't belong to the commit it was looked up for",
+            });
+        }
+        let (ewah, _) = git_bitmap::ewah::decode(&data[6..])?;
+        Ok(Bitmap::from_ewah(&ewah))
+    }
+}
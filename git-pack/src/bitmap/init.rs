@@ -0,0 +1,189 @@
+use std::{convert::TryFrom, path::Path};
+
+use crate::bitmap::{Entry, File, Version};
+
+mod error {
+    /// The error returned by [File::at()][super::File::at()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open bitmap file at '{path}'")]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error("{message}")]
+        Corrupt { message: &'static str },
+        #[error("Unsupported bitmap version: {version}")]
+        UnsupportedVersion { version: u16 },
+        #[error("Bitmaps that don't include all reachable objects aren't supported")]
+        NotFullDag,
+        #[error(transparent)]
+        Decode(#[from] crate::bitmap::decode::Error),
+    }
+}
+
+pub use error::Error;
+
+/// The bitmaps of each commit include all objects reachable from it.
+pub(crate) const FLAG_FULL_DAG: u16 = 0x1;
+/// The name-hash of each object is stored at the end of the file.
+pub(crate) const FLAG_HASH_CACHE: u16 = 0x4;
+/// A table to look up the entry of each commit is stored before the hash cache.
+pub(crate) const FLAG_LOOKUP_TABLE: u16 = 0x10;
+/// Pseudo-merge bitmaps are stored before the lookup table.
+pub(crate) const FLAG_PSEUDO_MERGES: u16 = 0x20;
+
+/// The size of each entry in the lookup table: the commit position, the offset of its entry and the row of its XOR base.
+const LOOKUP_ENTRY_LEN: usize = 4 + 8 + 4;
+/// The `xor_with` of an entry in the lookup table without XOR base.
+const NO_XOR_BASE: u32 = u32::MAX;
+
+/// Initialization
+impl File {
+    /// Open the bitmap file at the given `path`, belonging to a pack with `num_objects` objects whose ids are of kind `object_hash`.
+    ///
+    /// Use [`crate::index::File::num_objects()`] and [`crate::index::File::object_hash()`] of its pack index to obtain them.
+    pub fn at(path: impl AsRef<Path>, num_objects: u32, object_hash: git_hash::Kind) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let data = crate::mmap::read_only(path).map_err(|source| Error::Io {
+            source,
+            path: path.to_owned(),
+        })?;
+        let hash_len = object_hash.len_in_bytes();
+        let header_len = Self::SIGNATURE.len() + 2 /* version */ + 2 /* flags */ + 4 /* entries */ + hash_len;
+        if data.len() < header_len + hash_len {
+            return Err(Error::Corrupt {
+                message: "bitmap file is truncated and too short",
+            });
+        }
+        if &data[..4] != Self::SIGNATURE {
+            return Err(Error::Corrupt {
+                message: "Invalid signature",
+            });
+        }
+        let version = match u16::from_be_bytes([data[4], data[5]]) {
+            1 => Version::V1,
+            version => return Err(Error::UnsupportedVersion { version }),
+        };
+        let flags = u16::from_be_bytes([data[6], data[7]]);
+        if flags & FLAG_FULL_DAG == 0 {
+            return Err(Error::NotFullDag);
+        }
+        let num_entries = crate::read_u32(&data[8..12]);
+
+        let corrupt = |message| Error::Corrupt { message };
+        let mut end = data.len() - hash_len;
+        if flags & FLAG_HASH_CACHE != 0 {
+            end = end
+                .checked_sub(num_objects as usize * 4)
+                .filter(|end| *end >= header_len)
+                .ok_or_else(|| corrupt("bitmap file is too short to fit the hash cache"))?;
+        }
+        let lookup_table = if flags & FLAG_LOOKUP_TABLE != 0 {
+            end = end
+                .checked_sub(num_entries as usize * LOOKUP_ENTRY_LEN)
+                .filter(|end| *end >= header_len)
+                .ok_or_else(|| corrupt("bitmap file is too short to fit the lookup table"))?;
+            Some(&data[end..][..num_entries as usize * LOOKUP_ENTRY_LEN])
+        } else {
+            None
+        };
+        // Pseudo-merges are stored before the lookup table, which is why we can skip them by not looking at this part of the file.
+
+        let mut kind_ofs = [0; 4];
+        let mut ofs = header_len;
+        for kind_ofs in kind_ofs.iter_mut() {
+            *kind_ofs = ofs;
+            ofs += ewah_len(&data[ofs..end])?;
+        }
+
+        let entries = match lookup_table {
+            Some(table) => {
+                let entries = table
+                    .chunks(LOOKUP_ENTRY_LEN)
+                    .map(|entry| {
+                        let xor_with = crate::read_u32(&entry[12..]);
+                        Entry {
+                            commit_index: crate::read_u32(&entry[..4]),
+                            ofs: crate::read_u64(&entry[4..12]) as usize,
+                            xor_with: (xor_with != NO_XOR_BASE).then(|| xor_with),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let is_valid = entries.iter().all(|entry| {
+                    entry.ofs >= ofs
+                        && entry.ofs < end
+                        && entry.xor_with.map_or(true, |row| (row as usize) < entries.len())
+                }) && entries
+                    .windows(2)
+                    .all(|pair| pair[0].commit_index < pair[1].commit_index);
+                if !is_valid {
+                    return Err(corrupt("lookup table entries are out of bounds or not sorted"));
+                }
+                entries
+            }
+            None => {
+                let mut entries = Vec::with_capacity(num_entries as usize);
+                for index in 0..num_entries {
+                    let entry = data
+                        .get(ofs..end)
+                        .filter(|entry| entry.len() >= 6)
+                        .ok_or_else(|| corrupt("bitmap entry is truncated"))?;
+                    let xor_offset = u32::from(entry[4]);
+                    if xor_offset > index {
+                        return Err(corrupt("bitmap entry refers to an entry before the first one"));
+                    }
+                    entries.push(Entry {
+                        commit_index: crate::read_u32(&entry[..4]),
+                        ofs,
+                        xor_with: (xor_offset != 0).then(|| index - xor_offset),
+                    });
+                    ofs += 6 + ewah_len(&entry[6..])?;
+                }
+                sort_by_commit(entries)
+            }
+        };
+        if entries.iter().any(|entry| entry.commit_index >= num_objects) {
+            return Err(corrupt("bitmap entry refers to a commit that isn't in the pack"));
+        }
+
+        Ok(File {
+            data,
+            path: path.to_owned(),
+            version,
+            flags,
+            hash_len,
+            kind_ofs,
+            entries,
+        })
+    }
+}
+
+impl File {
+    /// The bitmap file signature.
+    pub const SIGNATURE: &'static [u8] = b"BITM";
+}
+
+/// Return the length of the bitmap at the beginning of `data`.
+fn ewah_len(data: &[u8]) -> Result<usize, crate::bitmap::decode::Error> {
+    let (_, rest) = git_bitmap::ewah::decode(data)?;
+    Ok(data.len() - rest.len())
+}
+
+/// Order `entries` in file order by the position of their commit in the pack index, like the lookup table does.
+fn sort_by_commit(entries: Vec<Entry>) -> Vec<Entry> {
+    let mut order: Vec<_> = (0..entries.len()).collect();
+    order.sort_by_key(|index| entries[*index].commit_index);
+    let mut row_by_index = vec![0; entries.len()];
+    for (row, index) in order.iter().enumerate() {
+        row_by_index[*index] = u32::try_from(row).expect("entry count fits into u32");
+    }
+    order
+        .into_iter()
+        .map(|index| Entry {
+            xor_with: entries[index].xor_with.map(|index| row_by_index[index as usize]),
+            ..entries[index]
+        })
+        .collect()
+}
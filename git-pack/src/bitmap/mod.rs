@@ -0,0 +1,157 @@
+//! Reachability bitmaps as stored alongside a pack in a `.bitmap` file, to learn which objects are reachable from a commit without
+//! traversing the commit graph and all of its trees.
+//!
+//! Each bit refers to an object in the pack by its position in the pack, i.e. the position of its entry when ordering them by
+//! pack offset. [`File::reachable()`] traverses commits without a bitmap until it reaches commits that have one.
+//!
+//! Pseudo-merge bitmaps are skipped when reading, as they are only an optimization for repositories with many references.
+use memmap2::Mmap;
+
+/// Known bitmap file versions
+#[derive(PartialEq, Eq, Ord, PartialOrd, Debug, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum Version {
+    V1 = 1,
+}
+
+impl Default for Version {
+    fn default() -> Self {
+        Version::V1
+    }
+}
+
+/// A representation of a `.bitmap` file with reachability bitmaps of selected commits of the pack it belongs to.
+pub struct File {
+    data: Mmap,
+    path: std::path::PathBuf,
+    version: Version,
+    flags: u16,
+    hash_len: usize,
+    /// The offsets of the bitmaps of all commits, trees, blobs and tags, in that order.
+    kind_ofs: [usize; 4],
+    /// All entries ordered by their commit's position in the pack index, which is how the lookup table stores them.
+    entries: Vec<Entry>,
+}
+
+/// An entry pointing to the bitmap of a commit.
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    /// The position of the commit in the pack index.
+    commit_index: crate::index::EntryIndex,
+    /// The offset of the entry in the file.
+    ofs: usize,
+    /// The entry whose bitmap is combined with ours via XOR to obtain the actual bitmap, as index into the entries.
+    xor_with: Option<u32>,
+}
+
+/// An uncompressed bitmap, with each bit referring to an object by its position in the pack.
+#[derive(Default, Debug, Clone, Eq)]
+pub struct Bitmap {
+    words: Vec<u64>,
+}
+
+impl PartialEq for Bitmap {
+    fn eq(&self, other: &Self) -> bool {
+        let (shorter, longer) = if self.words.len() < other.words.len() {
+            (&self.words, &other.words)
+        } else {
+            (&other.words, &self.words)
+        };
+        longer[..shorter.len()] == shorter[..] && longer[shorter.len()..].iter().all(|word| *word == 0)
+    }
+}
+
+impl Bitmap {
+    /// Create a bitmap from a compressed `ewah` bitmap.
+    pub fn from_ewah(ewah: &git_bitmap::ewah::Vec) -> Self {
+        let mut bitmap = Bitmap {
+            words: vec![0; (ewah.num_bits() + 63) / 64],
+        };
+        ewah.for_each_set_bit(|pos| {
+            bitmap.insert(pos);
+            Some(())
+        });
+        bitmap
+    }
+
+    /// Return true if the bit at `pos` is set.
+    pub fn contains(&self, pos: usize) -> bool {
+        self.words
+            .get(pos / 64)
+            .map_or(false, |word| word & (1 << (pos % 64)) != 0)
+    }
+
+    /// Set the bit at `pos` and return true if it wasn't set before.
+    pub fn insert(&mut self, pos: usize) -> bool {
+        let word = pos / 64;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let bit = 1 << (pos % 64);
+        let was_unset = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        was_unset
+    }
+
+    /// Set all bits that are set in `other`.
+    pub fn union(&mut self, other: &Bitmap) {
+        self.combine(other, |a, b| a | b)
+    }
+
+    /// Flip all bits that are set in `other`.
+    pub fn xor(&mut self, other: &Bitmap) {
+        self.combine(other, |a, b| a ^ b)
+    }
+
+    /// Unset all bits that aren't set in `other`.
+    pub fn intersect(&mut self, other: &Bitmap) {
+        self.words.truncate(other.words.len());
+        self.combine(other, |a, b| a & b)
+    }
+
+    /// Return the amount of set bits.
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Return an iterator over the positions of all set bits, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(index, word)| {
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| index * 64 + bit)
+        })
+    }
+
+    fn combine(&mut self, other: &Bitmap, op: impl Fn(u64, u64) -> u64) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word = op(*word, *other);
+        }
+    }
+}
+
+///
+pub mod decode {
+    /// The error returned when decoding the bitmaps of a [`File`][super::File].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not decode a bitmap")]
+        Ewah(#[from] git_bitmap::ewah::decode::Error),
+        #[error("{message}")]
+        Corrupt { message: &'static str },
+    }
+}
+
+mod access;
+
+///
+pub mod init;
+
+///
+pub mod reachable;
+pub use reachable::Reachable;
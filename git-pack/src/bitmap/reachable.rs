@@ -0,0 +1,205 @@
+use std::collections::HashSet;
+
+use git_hash::ObjectId;
+use git_object::Kind;
+
+use crate::{
+    bitmap::{Bitmap, File},
+    index::EntryIndex,
+    FindExt,
+};
+
+/// The error returned by [`File::reachable()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    #[error("The bitmap is for pack {actual}, but the index is for pack {expected}")]
+    PackMismatch { expected: ObjectId, actual: ObjectId },
+    #[error(transparent)]
+    Decode(#[from] crate::bitmap::decode::Error),
+    #[error(transparent)]
+    Find(#[from] crate::find::existing_iter::Error<E>),
+    #[error("Could not find object {oid}")]
+    NotFound { oid: ObjectId },
+    #[error(transparent)]
+    DecodeObject(#[from] git_object::decode::Error),
+    #[error(transparent)]
+    FindObject(E),
+}
+
+/// All objects reachable from a set of tips, as returned by [`File::reachable()`].
+#[derive(Debug, Clone)]
+pub struct Reachable {
+    /// The reachable objects in the pack, by their position in the pack.
+    pub in_pack: Bitmap,
+    /// The reachable objects that aren't in the pack, which are only found by traversal.
+    pub outside_pack: HashSet<ObjectId>,
+    /// The amount of commits that had to be traversed because they didn't have a bitmap and weren't reachable from a commit with one.
+    pub num_traversed_commits: usize,
+    /// The position in the pack index of each object, ordered by its position in the pack.
+    index_by_pack_position: Vec<EntryIndex>,
+}
+
+impl Reachable {
+    /// Return the amount of reachable objects.
+    pub fn len(&self) -> usize {
+        self.in_pack.count() + self.outside_pack.len()
+    }
+
+    /// Return true if there are no reachable objects, which can only be the case if there were no tips.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the ids of all reachable objects, using `index`, the same index passed to [`File::reachable()`], to learn the
+    /// ids of those in the pack.
+    pub fn ids<'a>(&'a self, index: &'a crate::index::File) -> impl Iterator<Item = ObjectId> + 'a {
+        self.in_pack
+            .iter()
+            .map(move |pos| index.oid_at_index(self.index_by_pack_position[pos]).to_owned())
+            .chain(self.outside_pack.iter().cloned())
+    }
+}
+
+/// Reachability
+impl File {
+    /// Return all objects reachable from `tips`, using `index` of our pack to find the bitmaps of commits, and `find` to traverse
+    /// all commits without a bitmap until reaching the boundary to commits which have one.
+    ///
+    /// As [`git`](https://github.com/git/git/blob/master/Documentation/technical/bitmap-format.txt) only writes bitmaps for
+    /// selected commits, like those that the references pointed to when packing, commits made afterwards or not selected are found
+    /// by traversal. Along with the commits, their trees are traversed as well, skipping those which are already known to be
+    /// reachable.
+    pub fn reachable<Find>(
+        &self,
+        tips: impl IntoIterator<Item = ObjectId>,
+        index: &crate::index::File,
+        find: Find,
+    ) -> Result<Reachable, Error<Find::Error>>
+    where
+        Find: crate::Find,
+    {
+        if index.pack_checksum() != self.pack_checksum() {
+            return Err(Error::PackMismatch {
+                expected: index.pack_checksum(),
+                actual: self.pack_checksum(),
+            });
+        }
+        let (index_by_pack_position, pack_position_by_index) = pack_order(index);
+        let mut out = Reachable {
+            in_pack: Bitmap::default(),
+            outside_pack: Default::default(),
+            num_traversed_commits: 0,
+            index_by_pack_position,
+        };
+        let pack_position = |id: &git_hash::oid| index.lookup(id).map(|index| pack_position_by_index[index as usize]);
+        let insert = |out: &mut Reachable, id: &git_hash::oid| match pack_position(id) {
+            Some(pos) => out.in_pack.insert(pos as usize),
+            None => out.outside_pack.insert(id.to_owned()),
+        };
+        let contains = |out: &Reachable, id: &git_hash::oid| match pack_position(id) {
+            Some(pos) => out.in_pack.contains(pos as usize),
+            None => out.outside_pack.contains(id),
+        };
+
+        let mut buf = Vec::new();
+        let mut commits = Vec::new();
+        let mut others = Vec::new();
+        for tip in tips {
+            let mut id = tip;
+            loop {
+                let object = find
+                    .try_find(id, &mut buf)
+                    .map_err(Error::FindObject)?
+                    .ok_or(Error::NotFound { oid: id })?
+                    .0;
+                match object.kind {
+                    Kind::Tag => {
+                        if !insert(&mut out, &id) {
+                            break;
+                        }
+                        id = object
+                            .try_into_tag_iter()
+                            .expect("tag")
+                            .target_id()
+                            .map_err(Error::DecodeObject)?;
+                    }
+                    Kind::Commit => {
+                        commits.push(id);
+                        break;
+                    }
+                    kind => {
+                        others.push((id, kind));
+                        break;
+                    }
+                }
+            }
+        }
+
+        // Use the bitmaps of the tips first as they might cover the commits reachable from other tips.
+        let mut commits_without_bitmap = Vec::new();
+        for id in commits {
+            match index
+                .lookup(id)
+                .map(|index| self.commit_bitmap(index))
+                .transpose()?
+                .flatten()
+            {
+                Some(bitmap) => out.in_pack.union(&bitmap),
+                None => commits_without_bitmap.push(id),
+            }
+        }
+        let mut commits = commits_without_bitmap;
+        while let Some(id) = commits.pop() {
+            if contains(&out, &id) {
+                continue;
+            }
+            if let Some(bitmap) = index
+                .lookup(id)
+                .map(|index| self.commit_bitmap(index))
+                .transpose()?
+                .flatten()
+            {
+                out.in_pack.union(&bitmap);
+                continue;
+            }
+            insert(&mut out, &id);
+            out.num_traversed_commits += 1;
+            let mut commit = find.find_commit_iter(id, &mut buf)?.0;
+            others.push((commit.tree_id()?, Kind::Tree));
+            commits.extend(commit.parent_ids());
+        }
+
+        // Trees are traversed last so that those reachable from commits with bitmap aren't traversed unnecessarily.
+        while let Some((id, kind)) = others.pop() {
+            if !insert(&mut out, &id) || kind != Kind::Tree {
+                continue;
+            }
+            for entry in find.find_tree_iter(id, &mut buf)?.0 {
+                let entry = entry?;
+                use git_object::tree::EntryMode::*;
+                match entry.mode {
+                    Tree => others.push((entry.oid.to_owned(), Kind::Tree)),
+                    Blob | BlobExecutable | Link => others.push((entry.oid.to_owned(), Kind::Blob)),
+                    Commit => {}
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Return the position in `index` of all objects ordered by their position in the pack, along with the position in the pack of
+/// all objects ordered by their position in `index`.
+fn pack_order(index: &crate::index::File) -> (Vec<EntryIndex>, Vec<u32>) {
+    let mut index_by_pack_position: Vec<_> = (0..index.num_objects()).collect();
+    index_by_pack_position.sort_by_key(|index_position| index.pack_offset_at_index(*index_position));
+    let mut pack_position_by_index = vec![0; index_by_pack_position.len()];
+    for (pack_position, index_position) in index_by_pack_position.iter().enumerate() {
+        pack_position_by_index[*index_position as usize] = pack_position as u32;
+    }
+    (index_by_pack_position, pack_position_by_index)
+}
@@ -0,0 +1,129 @@
+use git_hash::ObjectId;
+
+use crate::{data, data::entry::Header};
+
+/// The error returned by [`Bundle::explain()`][crate::Bundle::explain()].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not decode the object to explain")]
+    Decode(#[from] data::decode_entry::Error),
+    #[error("The base object {base_id} of the delta at pack offset {pack_offset} is not contained in the pack")]
+    RefBaseNotInPack {
+        base_id: ObjectId,
+        pack_offset: data::Offset,
+    },
+    #[error("No object starts at pack offset {base_offset}, the base of the delta at pack offset {pack_offset}")]
+    OfsBaseNotInPack {
+        base_offset: data::Offset,
+        pack_offset: data::Offset,
+    },
+}
+
+/// A pack entry in the delta chain of an object.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Link {
+    /// The id of the object stored in the entry.
+    pub id: ObjectId,
+    /// The offset of the entry in the pack.
+    pub pack_offset: data::Offset,
+    /// The header of the entry, identifying it as full object or delta.
+    pub header: Header,
+    /// The size of the decompressed entry data, which are delta instructions for deltas.
+    pub decompressed_size: u64,
+    /// The amount of bytes the entry occupies in the pack, including its header.
+    pub entry_size: u64,
+}
+
+/// Returned by [`Bundle::explain()`][crate::Bundle::explain()].
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outcome {
+    /// The kind of the object.
+    pub kind: git_object::Kind,
+    /// The size of the object after all deltas were applied.
+    pub object_size: u64,
+    /// The entry of the object itself, followed by the entries of its bases with the full object the chain is based on being last.
+    ///
+    /// It only has a single item if the object isn't stored as delta.
+    pub chain: Vec<Link>,
+}
+
+impl Outcome {
+    /// The amount of deltas that have to be applied to produce the object.
+    pub fn num_deltas(&self) -> usize {
+        self.chain.len() - 1
+    }
+
+    /// The amount of bytes all entries in the delta chain occupy in the pack.
+    pub fn chain_size(&self) -> u64 {
+        self.chain.iter().map(|link| link.entry_size).sum()
+    }
+}
+
+impl crate::Bundle {
+    /// Explain how the object with `id` is stored in this pack by following its delta chain to the full object it is based on,
+    /// or return `None` if the object isn't contained in this pack.
+    ///
+    /// This is useful to understand the cost of decoding objects, and will decode the object itself to learn its size.
+    pub fn explain(&self, id: impl AsRef<git_hash::oid>) -> Result<Option<Outcome>, Error> {
+        let id = id.as_ref();
+        let idx = match self.index.lookup(id) {
+            Some(idx) => idx,
+            None => return Ok(None),
+        };
+        let mut buf = Vec::new();
+        let (object, _location) = self.get_object_by_index(idx, &mut buf, &mut crate::cache::Never)?;
+        let (kind, object_size) = (object.kind, object.data.len() as u64);
+
+        let sorted_offsets = self.index.sorted_offsets();
+        let entry_size = |pack_offset: data::Offset| {
+            let next = sorted_offsets.partition_point(|ofs| *ofs <= pack_offset);
+            sorted_offsets
+                .get(next)
+                .copied()
+                .unwrap_or(self.pack.pack_end() as data::Offset)
+                - pack_offset
+        };
+
+        let mut chain = Vec::new();
+        let mut id = id.to_owned();
+        let mut pack_offset = self.index.pack_offset_at_index(idx);
+        loop {
+            let entry = self.pack.entry(pack_offset);
+            chain.push(Link {
+                id,
+                pack_offset,
+                header: entry.header,
+                decompressed_size: entry.decompressed_size,
+                entry_size: entry_size(pack_offset),
+            });
+            let (base_idx, base_offset) = match entry.header {
+                Header::OfsDelta { base_distance } => {
+                    let base_offset = entry.base_pack_offset(base_distance);
+                    let (base_idx, _entry) = self.entry_at_offset(base_offset).ok_or(Error::OfsBaseNotInPack {
+                        base_offset,
+                        pack_offset,
+                    })?;
+                    (base_idx, base_offset)
+                }
+                Header::RefDelta { base_id } => {
+                    let base_idx = self
+                        .index
+                        .lookup(base_id)
+                        .ok_or(Error::RefBaseNotInPack { base_id, pack_offset })?;
+                    (base_idx, self.index.pack_offset_at_index(base_idx))
+                }
+                Header::Commit | Header::Tree | Header::Blob | Header::Tag => break,
+            };
+            id = self.index.oid_at_index(base_idx).to_owned();
+            pack_offset = base_offset;
+        }
+        Ok(Some(Outcome {
+            kind,
+            object_size,
+            chain,
+        }))
+    }
+}
@@ -49,6 +49,7 @@ impl crate::Bundle {
                     git_object::Data {
                         kind: r.kind,
                         data: out.as_slice(),
+                        object_hash: self.index.object_hash(),
                     },
                     crate::data::entry::Location {
                         pack_id: self.pack.id,
@@ -58,4 +59,14 @@ impl crate::Bundle {
                 )
             })
     }
+
+    /// Return the index and the pack [entry][crate::data::Entry] of the object starting at `pack_offset`, or `None` if
+    /// no object starts at this offset.
+    ///
+    /// _Note_ that the lookup performs a linear search over all index entries.
+    pub fn entry_at_offset(&self, pack_offset: crate::data::Offset) -> Option<(u32, crate::data::Entry)> {
+        self.index
+            .lookup_pack_offset(pack_offset)
+            .map(|idx| (idx, self.pack.entry(pack_offset)))
+    }
 }
@@ -1,6 +1,8 @@
 ///
 pub mod init;
 
+///
+pub mod explain;
 mod find;
 ///
 pub mod write;
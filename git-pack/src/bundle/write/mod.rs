@@ -4,13 +4,13 @@ use std::{
     sync::{atomic::AtomicBool, Arc},
 };
 
-use git_features::{interrupt, progress, progress::Progress};
+use git_features::{fs::fsync, interrupt, progress, progress::Progress};
 use git_tempfile::{handle::Writable, AutoRemove, ContainingDirectory};
 
 use crate::data;
 
 mod error;
-use error::Error;
+pub use error::Error;
 
 mod types;
 use types::{LockWriter, PassThrough};
@@ -230,6 +230,7 @@ impl crate::Bundle {
             iteration_mode: _,
             index_kind,
             object_hash,
+            fsync,
         }: Options,
         data_file: Arc<parking_lot::Mutex<git_tempfile::Handle<Writable>>>,
         pack_entries_iter: impl Iterator<Item = Result<data::input::Entry, data::input::Error>>,
@@ -258,10 +259,14 @@ impl crate::Bundle {
                 let data_path = directory.join(format!("{}.pack", outcome.data_hash.to_hex()));
                 let index_path = data_path.with_extension("idx");
 
-                Arc::try_unwrap(data_file)
+                let mut data_file = Arc::try_unwrap(data_file)
                     .expect("only one handle left after pack was consumed")
-                    .into_inner()
-                    .persist(&data_path)?;
+                    .into_inner();
+                if let Some(method) = fsync {
+                    data_file.with_mut(|file| fsync::file(file.as_file(), method))??;
+                    index_file.with_mut(|file| fsync::file(file.as_file(), method))??;
+                }
+                data_file.persist(&data_path)?;
                 index_file
                     .persist(&index_path)
                     .map_err(|err| {
@@ -271,6 +276,9 @@ impl crate::Bundle {
                         ));
                         err
                     })?;
+                if fsync.is_some() {
+                    fsync::directory(directory)?;
+                }
                 (outcome, Some(data_path), Some(index_path))
             }
             None => (
@@ -14,6 +14,9 @@ pub struct Options {
     pub index_kind: crate::index::Version,
     /// The kind of hash to use when writing the bundle.
     pub object_hash: git_hash::Kind,
+    /// If set, the pack and its index as well as the directory containing them are flushed to permanent storage
+    /// with the given method before the bundle is considered written.
+    pub fsync: Option<git_features::fs::fsync::Method>,
 }
 
 impl Default for Options {
@@ -24,6 +27,7 @@ impl Default for Options {
             iteration_mode: crate::data::input::Mode::Verify,
             index_kind: Default::default(),
             object_hash: Default::default(),
+            fsync: None,
         }
     }
 }
@@ -0,0 +1,106 @@
+use std::convert::TryInto;
+
+use git_features::zlib;
+
+use crate::{
+    data,
+    data::{decode_entry::Error, delta, File},
+};
+
+/// The amount of decompressed bytes needed to hold the base and result size at the beginning of each delta.
+const DELTA_HEADER_DECOMPRESSED_BYTES: usize = 32;
+
+/// A return value of a resolve function, which given an [`ObjectId`][git_hash::ObjectId] determines where an object can be found.
+#[derive(Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum ResolvedBase {
+    /// Indicate an object is within this pack, at the given entry, and thus can be looked up locally.
+    InPack(data::Entry),
+    /// Indicates the object of `kind` was found outside of the pack.
+    OutOfPack {
+        /// The kind of the object.
+        kind: git_object::Kind,
+        /// If the object is itself deltified, the amount of deltas in its chain.
+        num_deltas: Option<u32>,
+    },
+}
+
+/// Information about an object obtained by [`File::decode_header()`] without decoding it.
+#[derive(Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outcome {
+    /// The kind of resolved object.
+    pub kind: git_object::Kind,
+    /// The size of the resolved object.
+    pub object_size: u64,
+    /// The amount of deltas in the chain of objects that would have to be resolved to decode the object.
+    pub num_deltas: u32,
+}
+
+/// Obtaining object information without decoding objects
+impl File {
+    /// Resolve the kind and size of the object at `entry` without decoding it, which only decompresses the first bytes of
+    /// each delta in its chain.
+    ///
+    /// `resolve` is a function to lookup objects with the given [`ObjectId`][git_hash::ObjectId], in case the full object id
+    /// is used to refer to a base object, instead of an in-pack offset.
+    pub fn decode_header(
+        &self,
+        mut entry: data::Entry,
+        resolve: impl Fn(&git_hash::oid) -> Option<ResolvedBase>,
+    ) -> Result<Outcome, Error> {
+        use crate::data::entry::Header::*;
+        let mut num_deltas = 0;
+        let mut first_delta_result_size = None;
+        loop {
+            match entry.header {
+                Tree | Blob | Commit | Tag => {
+                    return Ok(Outcome {
+                        kind: entry.header.as_kind().expect("a non-delta entry"),
+                        object_size: first_delta_result_size.unwrap_or(entry.decompressed_size),
+                        num_deltas,
+                    })
+                }
+                OfsDelta { base_distance } => {
+                    num_deltas += 1;
+                    if first_delta_result_size.is_none() {
+                        first_delta_result_size = Some(self.decode_delta_result_size(&entry)?);
+                    }
+                    entry = self.entry(entry.base_pack_offset(base_distance));
+                }
+                RefDelta { base_id } => {
+                    num_deltas += 1;
+                    if first_delta_result_size.is_none() {
+                        first_delta_result_size = Some(self.decode_delta_result_size(&entry)?);
+                    }
+                    match resolve(base_id.as_ref()) {
+                        Some(ResolvedBase::InPack(base_entry)) => entry = base_entry,
+                        Some(ResolvedBase::OutOfPack {
+                            kind,
+                            num_deltas: base_num_deltas,
+                        }) => {
+                            return Ok(Outcome {
+                                kind,
+                                object_size: first_delta_result_size.expect("set just now"),
+                                num_deltas: num_deltas + base_num_deltas.unwrap_or(0),
+                            })
+                        }
+                        None => return Err(Error::DeltaBaseUnresolved(base_id)),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Return the size of the object that the delta at `entry` produces, which is stored in its header.
+    fn decode_delta_result_size(&self, entry: &data::Entry) -> Result<u64, Error> {
+        let offset: usize = entry.data_offset.try_into().expect("offset representable by machine");
+        assert!(offset < self.data.len(), "entry offset out of bounds");
+
+        let mut buf = [0u8; DELTA_HEADER_DECOMPRESSED_BYTES];
+        zlib::Inflate::default().once(&self.data[offset..], &mut buf)?;
+        let (_base_size, consumed) = delta::decode_header_size(&buf);
+        let (result_size, _consumed) = delta::decode_header_size(&buf[consumed..]);
+        Ok(result_size)
+    }
+}
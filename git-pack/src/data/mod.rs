@@ -22,7 +22,7 @@ pub struct Entry {
 }
 
 mod file;
-pub use file::{decode_entry, verify, ResolvedBase};
+pub use file::{decode_entry, decode_header, verify, ResolvedBase};
 ///
 pub mod header;
 
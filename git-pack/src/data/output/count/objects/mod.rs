@@ -228,7 +228,7 @@ mod expand {
                                     push_obj_count_unique(
                                         &mut out, seen_objs, &tree_id, location, progress, stats, true,
                                     );
-                                    git_object::TreeRefIter::from_bytes(obj.data)
+                                    git_object::TreeRefIter::from_bytes(obj.data, obj.object_hash)
                                 };
 
                                 let objects = if parent_commit_ids.is_empty() {
@@ -275,7 +275,10 @@ mod expand {
                                                 stats,
                                                 true,
                                             );
-                                            git_object::TreeRefIter::from_bytes(parent_tree_obj.data)
+                                            git_object::TreeRefIter::from_bytes(
+                                                parent_tree_obj.data,
+                                                parent_tree_obj.object_hash,
+                                            )
                                         };
 
                                         changes_delegate.clear();
@@ -311,7 +314,7 @@ mod expand {
                             Tree => {
                                 traverse_delegate.clear();
                                 git_traverse::tree::breadthfirst(
-                                    git_object::TreeRefIter::from_bytes(obj.0.data),
+                                    git_object::TreeRefIter::from_bytes(obj.0.data, obj.0.object_hash),
                                     &mut tree_traversal_state,
                                     |oid, buf| {
                                         stats.decoded_objects += 1;
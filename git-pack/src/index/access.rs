@@ -146,6 +146,15 @@ impl index::File {
         )
     }
 
+    /// Returns the `index` of the object whose entry starts at `pack_offset` in our pack data file, or `None` if no object
+    /// starts at this offset.
+    ///
+    /// _Note_ that this performs a linear search, prefer building a lookup table with [`iter()`][index::File::iter()] if
+    /// many offsets have to be resolved.
+    pub fn lookup_pack_offset(&self, pack_offset: data::Offset) -> Option<EntryIndex> {
+        (0..self.num_objects).find(|idx| self.pack_offset_at_index(*idx) == pack_offset)
+    }
+
     /// An iterator over all [`Entries`][Entry] of this index file.
     pub fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = Entry> + 'a> {
         match self.version {
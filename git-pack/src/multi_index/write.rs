@@ -195,7 +195,7 @@ impl multi_index::File {
         }
 
         // write trailing checksum
-        let multi_index_checksum: git_hash::ObjectId = out.inner.hash.digest().into();
+        let multi_index_checksum: git_hash::ObjectId = out.inner.hash.digest();
         out.inner.inner.write_all(multi_index_checksum.as_slice())?;
         out.progress.show_throughput(write_start);
 
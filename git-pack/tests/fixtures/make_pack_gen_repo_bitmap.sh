@@ -0,0 +1,35 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function commit() {
+  local name=${1:?the name of the commit}
+  mkdir -p "dir/$name"
+  echo "$name" > "dir/$name/file"
+  echo "$name" >> file
+  git add .
+  git commit -qm "$name"
+}
+
+git init -q base
+(cd base
+  git checkout -q -b main
+  for n in $(seq 30); do commit "$n"; done
+  git checkout -q -b other main~10
+  for n in $(seq 31 40); do commit "$n"; done
+  git checkout -q main
+  git tag -m "an annotated tag" v1 main~5
+)
+
+for lookup_table in true false; do
+  repo="lookup-table-$lookup_table.git"
+  git clone -q --bare --no-local base "$repo"
+  git -C "$repo" -c pack.writeBitmapLookupTable=$lookup_table -c pack.writeBitmapHashCache=true repack -adbq
+done
+
+# commits that aren't in the pack and hence not covered by any bitmap
+(cd base
+  for n in $(seq 41 43); do commit "$n"; done
+)
+for lookup_table in true false; do
+  git -C "lookup-table-$lookup_table.git" -c transfer.unpackLimit=1000 fetch -q ../base main:main
+done
@@ -0,0 +1,137 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use git_pack::{bitmap, index};
+
+fn repo(lookup_table: bool) -> PathBuf {
+    git_testtools::scripted_fixture_repo_read_only("make_pack_gen_repo_bitmap.sh")
+        .expect("test fixture exists")
+        .join(format!("lookup-table-{}.git", lookup_table))
+}
+
+fn bitmap_and_index(lookup_table: bool) -> crate::Result<(bitmap::File, index::File)> {
+    let pack_dir = repo(lookup_table).join("objects/pack");
+    let bitmap_path = std::fs::read_dir(&pack_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .find(|path| path.extension().map_or(false, |ext| ext == "bitmap"))
+        .expect("a bitmap was written");
+    let index = index::File::at(bitmap_path.with_extension("idx"), git_hash::Kind::Sha1)?;
+    let bitmap = bitmap::File::at(&bitmap_path, index.num_objects(), index.object_hash())?;
+    Ok((bitmap, index))
+}
+
+fn rev_list_objects(lookup_table: bool, tips: &[&str]) -> crate::Result<HashSet<git_hash::ObjectId>> {
+    let output = std::process::Command::new("git")
+        .args(["rev-list", "--objects"])
+        .args(tips)
+        .current_dir(repo(lookup_table))
+        .output()?;
+    assert!(output.status.success());
+    String::from_utf8(output.stdout)?
+        .lines()
+        .map(|line| git_hash::ObjectId::from_hex(line[..40].as_bytes()).map_err(Into::into))
+        .collect()
+}
+
+fn rev_parse(lookup_table: bool, spec: &str) -> crate::Result<git_hash::ObjectId> {
+    let output = std::process::Command::new("git")
+        .args(["rev-parse", spec])
+        .current_dir(repo(lookup_table))
+        .output()?;
+    Ok(git_hash::ObjectId::from_hex(
+        String::from_utf8(output.stdout)?.trim_end().as_bytes(),
+    )?)
+}
+
+#[test]
+fn commit_bitmaps_are_the_same_with_and_without_lookup_table() -> crate::Result {
+    let (with_table, index) = bitmap_and_index(true)?;
+    let (without_table, _) = bitmap_and_index(false)?;
+    assert!(with_table.has_lookup_table());
+    assert!(!without_table.has_lookup_table());
+    assert!(!with_table.has_pseudo_merges());
+    assert_eq!(with_table.pack_checksum(), index.pack_checksum());
+    assert_eq!(with_table.num_commits(), 40, "all commits in the pack have a bitmap");
+    assert_eq!(
+        with_table.commits().collect::<Vec<_>>(),
+        without_table.commits().collect::<Vec<_>>()
+    );
+
+    for commit in with_table.commits() {
+        let bitmap = with_table.commit_bitmap(commit)?.expect("present");
+        assert_eq!(without_table.commit_bitmap(commit)?.as_ref(), Some(&bitmap));
+    }
+    assert_eq!(
+        with_table.commit_bitmap(index.lookup(rev_parse(true, "other:file")?).expect("in pack"))?,
+        None,
+        "blobs have no bitmap"
+    );
+    Ok(())
+}
+
+#[test]
+fn objects_of_kind() -> crate::Result {
+    let (bitmap, index) = bitmap_and_index(true)?;
+    use git_object::Kind::*;
+    let counts = [Commit, Tree, Blob, Tag]
+        .iter()
+        .map(|kind| bitmap.objects_of_kind(*kind).map(|bitmap| bitmap.count()))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(counts[0], 40);
+    assert_eq!(counts[3], 1, "the annotated tag");
+    assert_eq!(counts.iter().sum::<usize>(), index.num_objects() as usize);
+    Ok(())
+}
+
+#[test]
+fn reachable_objects_match_a_traversal() -> crate::Result {
+    for lookup_table in [true, false] {
+        let (bitmap, index) = bitmap_and_index(lookup_table)?;
+        let odb = git_odb::at(repo(lookup_table).join("objects"))?;
+        for tips in [
+            &["other"][..],
+            &["v1"],
+            &["main~3"],
+            &["main"],
+            &["main", "other", "v1"],
+        ] {
+            let ids = tips
+                .iter()
+                .map(|tip| rev_parse(lookup_table, tip))
+                .collect::<Result<Vec<_>, _>>()?;
+            let reachable = bitmap.reachable(ids, &index, &odb)?;
+            let expected = rev_list_objects(lookup_table, tips)?;
+            assert_eq!(reachable.len(), expected.len(), "{:?}", tips);
+            assert_eq!(reachable.ids(&index).collect::<HashSet<_>>(), expected, "{:?}", tips);
+
+            let traverses_loose_commits = tips.contains(&"main");
+            assert_eq!(
+                reachable.num_traversed_commits,
+                if traverses_loose_commits { 3 } else { 0 },
+                "only commits made after packing need to be traversed until reaching a commit with bitmap"
+            );
+            assert_eq!(
+                reachable.outside_pack.len(),
+                if traverses_loose_commits { 3 * 6 } else { 0 },
+                "each loose commit adds itself, three trees and two blobs"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn reachable_refuses_indices_of_other_packs() -> crate::Result {
+    let (bitmap, _) = bitmap_and_index(true)?;
+    let other_index = index::File::at(
+        git_testtools::fixture_path(super::SMALL_PACK_INDEX),
+        git_hash::Kind::Sha1,
+    )?;
+    let odb = git_odb::at(repo(true).join("objects"))?;
+    assert!(matches!(
+        bitmap.reachable(None, &other_index, &odb),
+        Err(bitmap::reachable::Error::PackMismatch { .. })
+    ));
+    Ok(())
+}
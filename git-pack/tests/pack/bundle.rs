@@ -75,6 +75,78 @@ mod locate {
     }
 }
 
+mod explain {
+    use git_odb::pack;
+
+    use crate::{fixture_path, hex_to_id, pack::SMALL_PACK_INDEX};
+
+    fn bundle() -> pack::Bundle {
+        pack::Bundle::at(fixture_path(SMALL_PACK_INDEX), git_hash::Kind::Sha1).expect("pack and idx")
+    }
+
+    #[test]
+    fn delta_chain_is_followed_to_the_base_object() -> Result<(), Box<dyn std::error::Error>> {
+        let bundle = bundle();
+        let outcome = bundle
+            .explain(hex_to_id("eeb31e7fb4a7c55fe126c65df838150b8933dc4b"))?
+            .expect("present");
+        assert_eq!(outcome.kind, git_object::Kind::Blob);
+        assert_eq!(outcome.num_deltas(), 2);
+        assert_eq!(
+            outcome
+                .chain
+                .iter()
+                .map(|link| (link.id, link.pack_offset, link.decompressed_size, link.entry_size))
+                .collect::<Vec<_>>(),
+            vec![
+                (hex_to_id("eeb31e7fb4a7c55fe126c65df838150b8933dc4b"), 3350, 9, 20),
+                (hex_to_id("e0dc2c78d3bd3d14d20906ad7905a32f0f0918a8"), 3009, 13, 24),
+                (hex_to_id("78bed30acc35358406b51120a2e7d29a0cebd6c1"), 2392, 1181, 617)
+            ],
+            "the values are the same as reported by git verify-pack"
+        );
+        assert!(outcome.chain[0].header.is_delta());
+        assert!(outcome.chain[2].header.is_base());
+        assert_eq!(outcome.chain_size(), 20 + 24 + 617);
+
+        let mut buf = Vec::new();
+        let (object, _) = bundle
+            .find(outcome.chain[0].id, &mut buf, &mut pack::cache::Never)?
+            .expect("present");
+        assert_eq!(outcome.object_size, object.data.len() as u64);
+        Ok(())
+    }
+
+    #[test]
+    fn base_objects_have_a_single_link() -> Result<(), Box<dyn std::error::Error>> {
+        let outcome = bundle()
+            .explain(hex_to_id("78bed30acc35358406b51120a2e7d29a0cebd6c1"))?
+            .expect("present");
+        assert_eq!(outcome.num_deltas(), 0);
+        assert_eq!(outcome.object_size, 1181);
+        assert_eq!(outcome.chain_size(), 617);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_objects_are_none() -> Result<(), Box<dyn std::error::Error>> {
+        assert!(bundle().explain(git_hash::Kind::Sha1.null())?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn entries_can_be_looked_up_by_offset() {
+        let bundle = bundle();
+        let (idx, entry) = bundle.entry_at_offset(3009).expect("an object starts here");
+        assert_eq!(
+            bundle.index.oid_at_index(idx),
+            hex_to_id("e0dc2c78d3bd3d14d20906ad7905a32f0f0918a8")
+        );
+        assert_eq!(entry.pack_offset(), 3009);
+        assert!(bundle.entry_at_offset(3010).is_none(), "no object starts here");
+    }
+}
+
 mod write_to_directory {
     use std::{fs, path::Path, sync::atomic::AtomicBool};
 
@@ -158,6 +230,7 @@ mod write_to_directory {
                 iteration_mode: pack::data::input::Mode::Verify,
                 index_kind: pack::index::Version::V2,
                 object_hash: git_hash::Kind::Sha1,
+                fsync: None,
             },
         )
         .map_err(Into::into)
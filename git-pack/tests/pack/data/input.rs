@@ -30,6 +30,7 @@ mod lookup_ref_delta_objects {
         let obj = git_object::Data {
             kind: header.as_kind().unwrap_or(git_object::Kind::Blob),
             data,
+            object_hash: git_hash::Kind::Sha1,
         };
         let mut entry = input::Entry::from_data_obj(&obj, 0).expect("valid object");
         entry.header = header;
@@ -96,6 +97,7 @@ mod lookup_ref_delta_objects {
             Some(git_object::Data {
                 kind: git_object::Kind::Blob,
                 data: buf.as_slice(),
+                object_hash: git_hash::Kind::Sha1,
             })
         });
         assert_eq!(iter.size_hint(), (actual_size.0, actual_size.1.map(|s| s * 2)),
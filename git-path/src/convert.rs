@@ -190,7 +190,7 @@ pub fn to_unix_separators<'a>(path: impl Into<Cow<'a, BStr>>) -> Cow<'a, BStr> {
     replace(path, b'\\', b'/')
 }
 
-/// Find backslashes and replace them with slashes, which typically resembles a unix path, unconditionally.
+/// Find slashes and replace them with backslashes, which typically resembles a windows path, unconditionally.
 ///
 /// **Note** Do not use these and prefer the conditional versions of this method.
 // TODO: use https://lib.rs/crates/path-slash to handle escapes
@@ -229,3 +229,22 @@ pub fn absolutize<'a>(path: impl Into<Cow<'a, Path>>, mut current_dir: Option<im
         path.into()
     }
 }
+
+/// Return true if the path `component` looks like an 8.3 short name as generated by windows for long file names,
+/// like `PROGRA~1` or `LONGFI~2.TXT`.
+///
+/// These are aliases of their long counterparts, which can only be resolved by accessing the file system.
+pub fn is_short_name(component: &BStr) -> bool {
+    let component: &[u8] = component.as_ref();
+    let (base, extension) = match component.iter().rposition(|b| *b == b'.') {
+        Some(pos) => (&component[..pos], Some(&component[pos + 1..])),
+        None => (component, None),
+    };
+    if base.is_empty() || base.len() > 8 || extension.map_or(false, |ext| ext.is_empty() || ext.len() > 3) {
+        return false;
+    }
+    match base.iter().rposition(|b| *b == b'~') {
+        Some(pos) => pos > 0 && pos + 1 < base.len() && base[pos + 1..].iter().all(u8::is_ascii_digit),
+        None => false,
+    }
+}
@@ -3,6 +3,17 @@
 //! Generally `git` treats paths as bytes, but inherently assumes non-illformed UTF-8 as encoding on windows. Internally, it expects
 //! slashes to be used as path separators and paths in files must have slashes, with conversions being performed on windows accordingly.
 //!
+//! ### Conversions
+//!
+//! * **bytes ↔ paths** - [`try_into_bstr()`], [`try_from_bstr()`] and their variations convert between `Path` and the bytes
+//!   that `git` stores, losslessly on unix and via UTF-8 on windows.
+//! * **separators** - [`to_unix_separators_on_windows()`] produces the slash-separated form used in the index, trees and configuration
+//!   files, and [`to_native_path_on_windows()`] turns it back into a path to use with the file system.
+//! * **relative paths** - [`to_repo_relative()`] computes the path of a file relative to the work tree as stored in the index, and
+//!   [`absolutize()`] resolves `.` and `..` components without accessing the file system. [`relativize_with_prefix()`] turns
+//!   such paths into ones relative to the current directory for display.
+//! * **8.3 short names** - [`is_short_name()`] identifies aliases like `PROGRA~1` that windows may hand out instead of long names.
+//!
 //! <details>
 //!
 //! ### Research
@@ -49,8 +60,6 @@
 #![deny(missing_docs, rust_2018_idioms)]
 #![forbid(unsafe_code)]
 
-/// A dummy type to represent path specs and help finding all spots that take path specs once it is implemented.
-
 /// A preliminary version of a path-spec based on glances of the code.
 #[derive(Clone, Debug)]
 pub struct Spec(bstr::BString);
@@ -63,3 +72,7 @@ mod spec;
 ///
 pub mod realpath;
 pub use realpath::function::{realpath, realpath_opts};
+
+///
+pub mod relative;
+pub use relative::function::{relativize_with_prefix, to_repo_relative};
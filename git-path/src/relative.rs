@@ -0,0 +1,110 @@
+use std::path::PathBuf;
+
+/// The error returned by [`to_repo_relative()`][crate::to_repo_relative()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The path '{}' is not located within the work tree at '{}'", .path.display(), .work_dir.display())]
+    OutsideOfWorkTree { path: PathBuf, work_dir: PathBuf },
+    #[error(transparent)]
+    IllformedUtf8(#[from] crate::Utf8Error),
+}
+
+pub(crate) mod function {
+    use std::path::{Path, PathBuf};
+
+    use std::borrow::Cow;
+
+    use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+    use super::Error;
+
+    /// Compute the path of `path` relative to `work_dir` with slashes as separators, the way it is stored in the index and in trees.
+    ///
+    /// Both paths are made absolute using `current_dir` if they are relative, and `.` and `..` components are resolved
+    /// without accessing the file system. On windows, if `path` isn't located in `work_dir` and either one of them contains
+    /// [8.3 short names][crate::is_short_name()], both are canonicalized to their long form before trying again.
+    ///
+    /// Returns an empty path if `path` is `work_dir` itself, and fails if `path` is outside of `work_dir`.
+    pub fn to_repo_relative(
+        path: impl AsRef<Path>,
+        work_dir: impl AsRef<Path>,
+        current_dir: impl AsRef<Path>,
+    ) -> Result<BString, Error> {
+        let current_dir = current_dir.as_ref();
+        let path = absolute(path.as_ref(), current_dir);
+        let work_dir = absolute(work_dir.as_ref(), current_dir);
+        let relative = match path.strip_prefix(&work_dir) {
+            Ok(relative) => relative.to_owned(),
+            Err(_) => long_names(&path, &work_dir).ok_or_else(|| Error::OutsideOfWorkTree {
+                path: path.clone(),
+                work_dir: work_dir.clone(),
+            })?,
+        };
+        let relative = crate::try_into_bstr(relative)?;
+        Ok(crate::to_unix_separators_on_windows(relative).into_owned())
+    }
+
+    /// Turn the slash-separated `relative_path`, relative to the work tree, into a path relative to `prefix`, the slash-separated
+    /// location of the current directory within the work tree as returned by `Repository::prefix()`, the way `git` displays paths
+    /// when invoked from a sub-directory.
+    ///
+    /// Paths that aren't located within `prefix` are reached with as many `../` components as needed, and `.` is returned
+    /// if `relative_path` is `prefix` itself. `relative_path` is returned unchanged if `prefix` is empty.
+    pub fn relativize_with_prefix<'a>(relative_path: &'a BStr, prefix: &BStr) -> Cow<'a, BStr> {
+        let mut prefix_components = prefix.split_str("/").filter(|c| !c.is_empty() && *c != b".").peekable();
+        if prefix_components.peek().is_none() {
+            return Cow::Borrowed(relative_path);
+        }
+        let mut path_components = relative_path.split_str("/").filter(|c| !c.is_empty()).peekable();
+        while let (Some(prefix_component), Some(path_component)) = (prefix_components.peek(), path_components.peek()) {
+            if prefix_component != path_component {
+                break;
+            }
+            prefix_components.next();
+            path_components.next();
+        }
+        let mut out = BString::default();
+        for _ in prefix_components {
+            out.push_str("../");
+        }
+        for component in path_components {
+            out.push_str(component);
+            out.push(b'/');
+        }
+        if out.pop().is_none() {
+            out.push(b'.');
+        }
+        Cow::Owned(out)
+    }
+
+    fn absolute(path: &Path, current_dir: &Path) -> PathBuf {
+        let path = if path.is_relative() {
+            current_dir.join(path)
+        } else {
+            path.to_owned()
+        };
+        crate::absolutize(path, Some(current_dir)).into_owned()
+    }
+
+    #[cfg(windows)]
+    fn long_names(path: &Path, work_dir: &Path) -> Option<PathBuf> {
+        let has_short_name = |path: &Path| {
+            path.components().any(|c| match c {
+                std::path::Component::Normal(c) => crate::os_str_into_bstr(c).map_or(false, crate::is_short_name),
+                _ => false,
+            })
+        };
+        if !(has_short_name(path) || has_short_name(work_dir)) {
+            return None;
+        }
+        let path = std::fs::canonicalize(path).ok()?;
+        let work_dir = std::fs::canonicalize(work_dir).ok()?;
+        path.strip_prefix(work_dir).ok().map(ToOwned::to_owned)
+    }
+
+    #[cfg(not(windows))]
+    fn long_names(_path: &Path, _work_dir: &Path) -> Option<PathBuf> {
+        None
+    }
+}
@@ -1,5 +1,5 @@
 use bstr::ByteSlice;
-use git_path::{to_unix_separators, to_windows_separators};
+use git_path::{is_short_name, to_unix_separators, to_windows_separators};
 
 #[test]
 fn assure_unix_separators() {
@@ -19,3 +19,22 @@ fn assure_windows_separators() {
 }
 
 mod absolutize;
+
+#[test]
+fn short_names() {
+    for name in ["PROGRA~1", "GIT~1", "LONGFI~2.TXT", "a~12.c"] {
+        assert!(is_short_name(name.into()), "{}", name);
+    }
+    for name in [
+        "~1",
+        "GIT~",
+        "GIT~a",
+        "TOOLONGN~1",
+        "FILE~1.",
+        "FILE~1.TEXT",
+        ".git",
+        "a~1b",
+    ] {
+        assert!(!is_short_name(name.into()), "{}", name);
+    }
+}
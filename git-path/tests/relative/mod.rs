@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use git_path::{relative::Error, to_repo_relative};
+
+#[test]
+fn paths_within_the_work_tree_are_made_relative_with_slashes() -> crate::Result {
+    let work_dir = Path::new("/repo");
+    let cwd = Path::new("/repo/sub");
+    assert_eq!(to_repo_relative("/repo/a/b", work_dir, cwd)?, "a/b");
+    assert_eq!(
+        to_repo_relative("c", work_dir, cwd)?,
+        "sub/c",
+        "relative paths are relative to cwd"
+    );
+    assert_eq!(
+        to_repo_relative("../d/./e", work_dir, cwd)?,
+        "d/e",
+        "relative components are resolved"
+    );
+    assert_eq!(to_repo_relative(".", ".", cwd)?, "", "the work tree itself is empty");
+    assert_eq!(
+        to_repo_relative("/repo/sub/f", "..", cwd)?,
+        "sub/f",
+        "the work dir may also be relative"
+    );
+    Ok(())
+}
+
+#[test]
+fn paths_outside_of_the_work_tree_are_rejected() {
+    for path in ["/other/a", "/repository/a", "../a"] {
+        assert!(
+            matches!(
+                to_repo_relative(path, "/repo", "/repo"),
+                Err(Error::OutsideOfWorkTree { .. })
+            ),
+            "{}",
+            path
+        );
+    }
+}
+
+mod relativize_with_prefix {
+    use git_path::relativize_with_prefix;
+
+    fn relativize(path: &str, prefix: &str) -> String {
+        relativize_with_prefix(path.into(), prefix.into()).to_string()
+    }
+
+    #[test]
+    fn an_empty_prefix_leaves_paths_unchanged() {
+        assert_eq!(relativize("a/b", ""), "a/b");
+        assert_eq!(relativize("a/b", "./"), "a/b");
+    }
+
+    #[test]
+    fn paths_within_the_prefix_are_shortened() {
+        assert_eq!(relativize("a/b/c", "a"), "b/c");
+        assert_eq!(relativize("a/b/c", "a/b/"), "c");
+        assert_eq!(relativize("a/b", "a/b"), ".", "the prefix itself");
+    }
+
+    #[test]
+    fn paths_outside_of_the_prefix_are_reached_through_parent_directories() {
+        assert_eq!(relativize("c", "a/b"), "../../c");
+        assert_eq!(relativize("a/c", "a/b"), "../c");
+        assert_eq!(relativize("ab", "a"), "../ab", "components are compared as a whole");
+        assert_eq!(relativize("a", "a/b"), "..");
+    }
+}
@@ -1,5 +1,5 @@
 //! Parse [path specifications](https://git-scm.com/docs/gitglossary#Documentation/gitglossary.txt-aiddefpathspecapathspec) and
-//! see if a path matches.
+//! see if a path matches, either with [`Pattern::matches()`] or with [`is_included()`] for a whole list of patterns.
 #![deny(missing_docs, rust_2018_idioms)]
 #![forbid(unsafe_code)]
 
@@ -9,6 +9,9 @@ use bstr::BString;
 ///
 pub mod parse;
 
+mod matching;
+pub use matching::is_included;
+
 /// The output of a pathspec [parsing][parse()] operation. It can be used to match against a one or more paths.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 pub struct Pattern {
@@ -0,0 +1,69 @@
+use bstr::{BStr, ByteSlice};
+
+use crate::{MagicSignature, MatchMode, Pattern};
+
+impl Pattern {
+    /// Return `true` if this pattern excludes the paths it matches, as in `:(exclude)path` or `:!path`.
+    pub fn is_excluded(&self) -> bool {
+        self.signature.contains(MagicSignature::EXCLUDE)
+    }
+
+    /// Return `true` if `path`, relative to the root of the repository, is matched by this pattern, disregarding
+    /// whether or not it [is excluding][Pattern::is_excluded()].
+    ///
+    /// Like in `git`, a pattern also matches all paths below the directory it names, so `dir` matches `dir/file`.
+    /// The path of the pattern is assumed to be relative to the root of the repository as well, and its
+    /// [attributes][Pattern::attributes] are not taken into consideration.
+    pub fn matches(&self, path: &BStr) -> bool {
+        let ignore_case = self.signature.contains(MagicSignature::ICASE);
+        let pattern = self.path.as_bstr();
+        if pattern.is_empty() || is_prefix_of(pattern, path, ignore_case) {
+            return true;
+        }
+        let mode = match self.search_mode {
+            MatchMode::Literal => return false,
+            MatchMode::ShellGlob => git_glob::wildmatch::Mode::empty(),
+            MatchMode::PathAwareGlob => git_glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+        };
+        let mode = if ignore_case {
+            mode | git_glob::wildmatch::Mode::IGNORE_CASE
+        } else {
+            mode
+        };
+        pattern.find_byteset(b"*?[\\").is_some() && git_glob::wildmatch(pattern, path, mode)
+    }
+}
+
+/// Return `true` if all `patterns` taken together select `path`, relative to the root of the repository.
+///
+/// This is the case if it matches at least one pattern that isn't [excluding][Pattern::is_excluded()], or if there is no
+/// such pattern, and if it matches none of the excluding patterns.
+pub fn is_included<'a>(patterns: impl IntoIterator<Item = &'a Pattern>, path: &BStr) -> bool {
+    let mut has_inclusions = false;
+    let mut included = false;
+    for pattern in patterns {
+        if pattern.is_excluded() {
+            if pattern.matches(path) {
+                return false;
+            }
+        } else {
+            has_inclusions = true;
+            included |= pattern.matches(path);
+        }
+    }
+    included || !has_inclusions
+}
+
+fn is_prefix_of(pattern: &[u8], path: &[u8], ignore_case: bool) -> bool {
+    let pattern = pattern.strip_suffix(b"/").unwrap_or(pattern);
+    if path.len() < pattern.len() {
+        return false;
+    }
+    let (prefix, rest) = path.split_at(pattern.len());
+    let prefix_matches = if ignore_case {
+        prefix.eq_ignore_ascii_case(pattern)
+    } else {
+        prefix == pattern
+    };
+    prefix_matches && (rest.is_empty() || rest[0] == b'/')
+}
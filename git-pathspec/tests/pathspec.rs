@@ -513,3 +513,52 @@ mod parse {
         *base == 0
     }
 }
+
+mod matching {
+    use bstr::ByteSlice;
+
+    fn matches(spec: &str, path: &str) -> bool {
+        git_pathspec::parse(spec.as_bytes())
+            .expect("valid pathspec")
+            .matches(path.as_bytes().as_bstr())
+    }
+
+    fn is_included(specs: &[&str], path: &str) -> bool {
+        let patterns: Vec<_> = specs
+            .iter()
+            .map(|spec| git_pathspec::parse(spec.as_bytes()).expect("valid pathspec"))
+            .collect();
+        git_pathspec::is_included(&patterns, path.as_bytes().as_bstr())
+    }
+
+    #[test]
+    fn directories_match_everything_below_them() {
+        assert!(matches("dir", "dir"));
+        assert!(matches("dir", "dir/file"));
+        assert!(matches("dir/", "dir/sub/file"));
+        assert!(!matches("dir", "dir-other/file"));
+        assert!(!matches("dir/file", "dir"));
+        assert!(matches(":(icase)DIR", "dir/file"));
+    }
+
+    #[test]
+    fn globs_depend_on_the_search_mode() {
+        assert!(matches("*.rs", "src/lib.rs"), "shell globs match slashes");
+        assert!(!matches(":(glob)*.rs", "src/lib.rs"));
+        assert!(matches(":(glob)**/*.rs", "src/lib.rs"));
+        assert!(!matches(":(literal)*.rs", "src/lib.rs"));
+        assert!(matches(":(literal)*.rs", "*.rs"));
+        assert!(matches(":(icase)*.RS", "src/lib.rs"));
+    }
+
+    #[test]
+    fn exclusions_take_precedence() {
+        assert!(is_included(&[], "anything"));
+        assert!(is_included(&[":(exclude)generated"], "src/lib.rs"));
+        assert!(!is_included(&[":!generated"], "generated/lib.rs"));
+        assert!(is_included(&["src", "tests"], "tests/a.rs"));
+        assert!(!is_included(&["src"], "tests/a.rs"));
+        assert!(!is_included(&["src", ":^src/generated"], "src/generated/a.rs"));
+        assert!(is_included(&["src", ":^src/generated"], "src/lib.rs"));
+    }
+}
@@ -194,6 +194,7 @@ mod with_io {
                         }
                         match *feature {
                             "agent" => {}
+                            "object-format" if server.contains("object-format") => {}
                             _ => panic!("{}: V2 feature/capability {} is not supported", self.as_str(), feature),
                         }
                     }
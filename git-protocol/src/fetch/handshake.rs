@@ -66,9 +66,18 @@ pub(crate) mod function {
                 .iter()
                 .map(|(k, v)| (k.as_str(), v.as_ref().map(|s| s.as_str())))
                 .collect();
-            let supported_versions: Vec<_> = transport.supported_protocol_versions().into();
+            let mut supported_versions: Vec<_> = transport.supported_protocol_versions().into();
 
-            let result = transport.handshake(Service::UploadPack, &extra_parameters).await;
+            let mut result = transport.handshake(Service::UploadPack, &extra_parameters).await;
+            if matches!(&result, Err(err) if is_advertisement_failure(err)) {
+                let err = result.err().expect("checked above");
+                if !transport.downgrade_protocol_version(git_transport::Protocol::V1) {
+                    return Err(err.into());
+                }
+                progress.set_name("handshake (protocol V1)");
+                supported_versions = transport.supported_protocol_versions().into();
+                result = transport.handshake(Service::UploadPack, &extra_parameters).await;
+            }
             let SetServiceResponse {
                 actual_protocol,
                 capabilities,
@@ -136,4 +145,24 @@ pub(crate) mod function {
             capabilities,
         })
     }
+
+    /// Return `true` if `err` indicates that the server failed to send a readable advertisement, which might be due to it
+    /// not speaking the protocol version we asked for.
+    fn is_advertisement_failure(err: &client::Error) -> bool {
+        match err {
+            client::Error::Capabilities { .. }
+            | client::Error::LineDecode { .. }
+            | client::Error::ExpectedLine(_)
+            | client::Error::ExpectedDataLine
+            | client::Error::UnsupportedProtocolVersion(_) => true,
+            client::Error::Io { err } => matches!(
+                err.kind(),
+                std::io::ErrorKind::UnexpectedEof
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::BrokenPipe
+            ),
+            _ => false,
+        }
+    }
 }
@@ -131,6 +131,16 @@ mod v2 {
                 );
             }
 
+            #[test]
+            fn the_object_format_can_be_used_if_advertised() {
+                Command::LsRefs.validate_argument_prefixes_or_panic(
+                    git_transport::Protocol::V2,
+                    &capabilities("object-format", "sha256"),
+                    &[],
+                    &[("object-format", Some("sha256"))],
+                );
+            }
+
             #[test]
             #[should_panic]
             fn unknown_argument() {
@@ -95,3 +95,87 @@ async fn ls_remote_handshake_failure_due_to_downgrade() -> crate::Result {
     );
     Ok(())
 }
+
+#[cfg(feature = "blocking-client")]
+mod downgrade {
+    use std::io::Cursor;
+
+    use git_features::progress;
+    use git_protocol::FetchConnection;
+    use git_transport::{
+        client::{self, git::ConnectMode, MessageKind, RequestWriter, SetServiceResponse, WriteMode},
+        Protocol, Service,
+    };
+
+    use crate::fetch::{transport, LsRemoteDelegate};
+
+    /// A transport whose server hangs up during the V2 advertisement, but which answers in V1 after a downgrade.
+    struct HangUpOnV2 {
+        connection: client::git::Connection<Cursor<Vec<u8>>, Vec<u8>>,
+        downgrades: usize,
+    }
+
+    impl client::TransportWithoutIO for HangUpOnV2 {
+        fn request(
+            &mut self,
+            write_mode: WriteMode,
+            on_into_read: MessageKind,
+        ) -> Result<RequestWriter<'_>, client::Error> {
+            self.connection.request(write_mode, on_into_read)
+        }
+
+        fn to_url(&self) -> String {
+            self.connection.to_url()
+        }
+
+        fn supported_protocol_versions(&self) -> &[Protocol] {
+            self.connection.supported_protocol_versions()
+        }
+
+        fn downgrade_protocol_version(&mut self, version: Protocol) -> bool {
+            self.downgrades += 1;
+            self.connection = transport(Vec::new(), "v1/clone.response", version, ConnectMode::Daemon);
+            true
+        }
+
+        fn connection_persists_across_multiple_requests(&self) -> bool {
+            true
+        }
+    }
+
+    impl client::Transport for HangUpOnV2 {
+        fn handshake<'a>(
+            &mut self,
+            service: Service,
+            extra_parameters: &'a [(&'a str, Option<&'a str>)],
+        ) -> Result<SetServiceResponse<'_>, client::Error> {
+            self.connection.handshake(service, extra_parameters)
+        }
+    }
+
+    #[test]
+    fn failing_v2_advertisement_retries_with_v1() -> crate::Result {
+        let mut delegate = LsRemoteDelegate::default();
+        let mut transport = HangUpOnV2 {
+            connection: client::git::Connection::new(
+                Cursor::new(Vec::new()),
+                Vec::new(),
+                Protocol::V2,
+                "does/not/matter",
+                None::<(&str, _)>,
+                ConnectMode::Daemon,
+            ),
+            downgrades: 0,
+        };
+        git_protocol::fetch(
+            &mut transport,
+            &mut delegate,
+            git_protocol::credentials::helper,
+            progress::Discard,
+            FetchConnection::AllowReuse,
+        )?;
+        assert_eq!(transport.downgrades, 1, "the handshake is retried once");
+        assert_eq!(delegate.refs.len(), 2, "the V1 advertisement is used");
+        Ok(())
+    }
+}
@@ -3,3 +3,6 @@
 
 ///
 pub mod ansi_c;
+
+mod single;
+pub use single::single;
@@ -0,0 +1,22 @@
+use bstr::{BStr, BString, ByteSlice};
+
+/// Transforms the given `value` to be suitable for use as an argument for Bourne shells by wrapping it into single quotes.
+///
+/// Every single-quote `'` is escaped with `\'`, every exclamation mark `!` is escaped with `\!`, and the entire string is enclosed
+/// in single quotes, just like `git` does it.
+pub fn single(value: &BStr) -> BString {
+    let mut quoted = BString::from(Vec::with_capacity(value.len() + 2));
+    quoted.push(b'\'');
+    for byte in value.bytes() {
+        match byte {
+            b'\'' | b'!' => {
+                quoted.extend_from_slice(b"'\\");
+                quoted.push(byte);
+                quoted.push(b'\'');
+            }
+            _ => quoted.push(byte),
+        }
+    }
+    quoted.push(b'\'');
+    quoted
+}
@@ -2,7 +2,7 @@ use std::{borrow::Borrow, convert::TryFrom, path::Path};
 
 use git_object::bstr::{BStr, BString, ByteSlice};
 
-use crate::{bstr::ByteVec, name::is_pseudo_ref, Category, FullName, FullNameRef, Namespace, PartialNameRef};
+use crate::{bstr::ByteVec, name::is_pseudo_ref, Category, Class, FullName, FullNameRef, Namespace, PartialNameRef};
 
 impl TryFrom<&str> for FullName {
     type Error = git_validate::refname::Error;
@@ -92,6 +92,23 @@ impl FullNameRef {
         self.category_and_short_name().map(|(cat, _)| cat)
     }
 
+    /// Return the storage class of this name, which determines which worktrees can see it.
+    ///
+    /// Names prefixed with `main-worktree/` or `worktrees/<id>/` have the class of the reference they refer to.
+    pub fn class(&self) -> Class {
+        match self.category_and_short_name() {
+            Some((Category::MainRef | Category::LinkedRef { .. }, short_name)) => {
+                FullNameRef::new_unchecked(short_name).class()
+            }
+            Some((Category::PseudoRef | Category::MainPseudoRef | Category::LinkedPseudoRef { .. }, _)) => {
+                Class::Pseudo
+            }
+            Some((Category::Bisect | Category::Rewritten | Category::WorktreePrivate, _)) => Class::PerWorktree,
+            _ if self.0.starts_with_str("refs/namespaces/") => Class::Namespaced,
+            _ => Class::Shared,
+        }
+    }
+
     /// Classify this name, or return `None` if it's unclassified. If `Some`,
     /// the shortened name is returned as well.
     pub fn category_and_short_name(&self) -> Option<(Category<'_>, &BStr)> {
@@ -192,6 +209,11 @@ impl FullName {
         self.as_ref().category()
     }
 
+    /// Return the storage class of this name, which determines which worktrees can see it.
+    pub fn class(&self) -> crate::Class {
+        self.as_ref().class()
+    }
+
     /// Classify this name, or return `None` if it's unclassified. If `Some`,
     /// the shortened name is returned as well.
     pub fn category_and_short_name(&self) -> Option<(crate::Category<'_>, &BStr)> {
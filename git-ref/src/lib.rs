@@ -183,6 +183,21 @@ pub enum Category<'a> {
     // REF_TYPE_NORMAL,	  /* normal/shared refs inside refs/        */
 }
 
+/// The storage class of a reference, determining which worktrees can see it and whether it may be packed.
+///
+/// Unlike [`Category`], every full reference name has a class.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Class {
+    /// A reference shared among all worktrees, like `refs/heads/main` or `refs/stash`, which lives in the common dir.
+    Shared,
+    /// A shared reference in a namespace, with a `refs/namespaces/` prefix.
+    Namespaced,
+    /// A reference in `refs/` that is private to each worktree, like `refs/bisect/good`, `refs/rewritten/*` or `refs/worktree/*`.
+    PerWorktree,
+    /// A reference outside of `refs/` that is private to each worktree, like `HEAD` or `FETCH_HEAD`.
+    Pseudo,
+}
+
 /// Denotes a ref target, equivalent to [`Kind`], but with mutable data.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
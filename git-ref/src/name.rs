@@ -2,7 +2,7 @@ use std::{convert, convert::Infallible, ffi::OsStr, path::Path};
 
 use git_object::bstr::{BStr, BString, ByteSlice, ByteVec};
 
-use crate::{Category, FullName, FullNameRef, PartialName, PartialNameRef};
+use crate::{Category, Class, FullName, FullNameRef, PartialName, PartialNameRef};
 
 /// The error used in the [`PartialNameRef`][super::PartialNameRef]::try_from(…) implementations.
 pub type Error = git_validate::reference::name::Error;
@@ -41,6 +41,14 @@ impl<'a> Category<'a> {
     }
 }
 
+impl Class {
+    /// Returns true if references of this class are shared among all worktrees, which also makes them eligible for
+    /// being stored in the `packed-refs` file.
+    pub fn is_shared(&self) -> bool {
+        matches!(self, Class::Shared | Class::Namespaced)
+    }
+}
+
 impl FullNameRef {
     pub(crate) fn new_unchecked(v: &BStr) -> &Self {
         // SAFETY: FullNameRef is transparent and equivalent to a &BStr if provided as reference
@@ -189,7 +189,14 @@ impl file::Store {
                     PseudoRef | Bisect | Rewritten | WorktreePrivate => return None,
                 })
             })
-            .unwrap_or((self.git_dir.as_path().into(), name))
+            .unwrap_or_else(|| {
+                if name.as_bstr().starts_with(b"refs/") && name.class().is_shared() {
+                    // uncategorized references like `refs/stash` are shared among all worktrees
+                    (commondir.into(), name)
+                } else {
+                    (self.git_dir.as_path().into(), name)
+                }
+            })
     }
 
     /// Implements the logic required to transform a fully qualified refname into a filesystem path
@@ -91,6 +91,7 @@ pub mod create_or_update {
         path::{Path, PathBuf},
     };
 
+    use git_features::fs::fsync;
     use git_hash::{oid, ObjectId};
     use git_object::bstr::BStr;
 
@@ -161,6 +162,13 @@ pub mod create_or_update {
                                     writeln!(file)
                                 }
                             })
+                            .and_then(|_| match self.fsync {
+                                Some(method) => {
+                                    fsync::file(&file, method)?;
+                                    fsync::directory(log_path.parent().expect("always with parent directory"))
+                                }
+                                None => Ok(()),
+                            })
                             .map_err(|err| Error::Append {
                                 err,
                                 reflog_path: self.reflog_path(name),
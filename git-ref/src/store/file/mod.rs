@@ -25,6 +25,9 @@ pub struct Store {
     pub write_reflog: WriteReflog,
     /// The namespace to use for edits and reads
     pub namespace: Option<Namespace>,
+    /// If set, loose references, reflogs and the packed-refs file as well as the directories containing them are flushed to
+    /// permanent storage with the given method after changing them.
+    pub fsync: Option<git_features::fs::fsync::Method>,
     /// A packed buffer which can be mapped in one version and shared as such.
     /// It's updated only in one spot, which is prior to reading it based on file stamps.
     /// Doing it like this has the benefit of being able to hand snapshots out to people without blocking others from updating it.
@@ -80,7 +83,7 @@ mod overlay_iter;
 
 ///
 pub mod iter {
-    pub use super::overlay_iter::{LooseThenPacked, Platform};
+    pub use super::overlay_iter::{LooseThenPacked, OfClass, Platform};
 
     ///
     pub mod loose_then_packed {
@@ -100,5 +103,9 @@ pub mod transaction;
 ///
 pub mod packed;
 
+///
+pub mod provenance;
+pub use provenance::Provenance;
+
 mod raw_ext;
 pub use raw_ext::ReferenceExt;
@@ -9,7 +9,7 @@ use std::{
 use crate::{
     file::{loose, loose::iter::SortedLoosePaths, path_to_name},
     store_impl::{file, packed},
-    BString, FullName, Namespace, Reference,
+    BString, Class, FullName, Namespace, Reference,
 };
 
 /// An iterator stepping through sorted input of loose references and packed references, preferring loose refs over otherwise
@@ -33,6 +33,12 @@ enum IterKind {
     Common,
 }
 
+/// An iterator over all references of a single [`Class`], as obtained by [`Platform::of_class()`].
+pub struct OfClass<'p, 's> {
+    inner: LooseThenPacked<'p, 's>,
+    class: Class,
+}
+
 /// An intermediate structure to hold shared state alive long enough for iteration to happen.
 #[must_use = "Iterators should be obtained from this platform"]
 pub struct Platform<'s> {
@@ -116,9 +122,11 @@ impl<'p, 's> Iterator for LooseThenPacked<'p, 's> {
     type Item = Result<Reference, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        fn advance_to_non_private(iter: &mut Peekable<SortedLoosePaths>) {
+        /// Skip over all references in `iter` whose worktree-privacy isn't `is_private`, as the private git dir of linked
+        /// worktrees only provides private references while all others are shared via the common dir.
+        fn advance_to(iter: &mut Peekable<SortedLoosePaths>, is_private: bool) {
             while let Some(Ok((_path, name))) = iter.peek() {
-                if name.category().map_or(true, |cat| cat.is_worktree_private()) {
+                if name.category().map_or(false, |cat| cat.is_worktree_private()) != is_private {
                     iter.next();
                 } else {
                     break;
@@ -131,10 +139,16 @@ impl<'p, 's> Iterator for LooseThenPacked<'p, 's> {
             common_dir: Option<&'a mut Peekable<SortedLoosePaths>>,
         ) -> Option<(&'a std::io::Result<(PathBuf, FullName)>, IterKind)> {
             match common_dir {
-                Some(common_dir) => match (git_dir.peek(), {
-                    advance_to_non_private(common_dir);
-                    common_dir.peek()
-                }) {
+                Some(common_dir) => match (
+                    {
+                        advance_to(git_dir, true);
+                        git_dir.peek()
+                    },
+                    {
+                        advance_to(common_dir, false);
+                        common_dir.peek()
+                    },
+                ) {
                     (None, None) => None,
                     (None, Some(res)) | (Some(_), Some(res @ Err(_))) => Some((res, IterKind::Common)),
                     (Some(res), None) | (Some(res @ Err(_)), Some(_)) => Some((res, IterKind::Git)),
@@ -187,6 +201,19 @@ impl<'p, 's> Iterator for LooseThenPacked<'p, 's> {
     }
 }
 
+impl<'p, 's> Iterator for OfClass<'p, 's> {
+    type Item = Result<Reference, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.inner.next()? {
+                Ok(r) if r.name.class() != self.class => continue,
+                res => return Some(res),
+            }
+        }
+    }
+}
+
 impl<'s> Platform<'s> {
     /// Return an iterator over all references, loose or `packed`, sorted by their name.
     ///
@@ -195,6 +222,18 @@ impl<'s> Platform<'s> {
         self.store.iter_packed(self.packed.as_ref().map(|b| &***b))
     }
 
+    /// Return an iterator over all references of the given `class`, loose or `packed`, sorted by their name, which allows
+    /// to process shared and per-worktree references separately.
+    ///
+    /// Note that [pseudo-refs][Class::Pseudo] like `HEAD` are never returned as only references in `refs/` are iterated,
+    /// and that namespaced references are only returned if the store itself has no namespace set.
+    pub fn of_class(&self, class: Class) -> std::io::Result<OfClass<'_, '_>> {
+        Ok(OfClass {
+            inner: self.all()?,
+            class,
+        })
+    }
+
     /// As [`iter(…)`][file::Store::iter()], but filters by `prefix`, i.e. "refs/heads".
     ///
     /// Please note that "refs/heads` or "refs\\heads" is equivalent to "refs/heads/"
@@ -0,0 +1,75 @@
+use std::{io, path::PathBuf, time::SystemTime};
+
+use crate::{
+    file,
+    store_impl::{file::find, packed},
+    FullNameRef,
+};
+
+/// The kind of storage a reference was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Source {
+    /// The reference is stored in its own file.
+    Loose,
+    /// The reference is stored in the `packed-refs` file.
+    Packed,
+}
+
+/// Information about where a reference is stored, as obtained by [`file::Store::provenance()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    /// The kind of storage the reference would be read from.
+    pub source: Source,
+    /// The path to the loose reference file or to the `packed-refs` file.
+    pub path: PathBuf,
+    /// The time at which the file at `path` was last modified, if the platform supports it.
+    pub modified: Option<SystemTime>,
+}
+
+impl file::Store {
+    /// Return information about where the reference with the given full `name` is stored, or `None` if it doesn't exist.
+    ///
+    /// As loose references shadow packed ones, the reference is considered `Loose` if a loose file exists, matching the
+    /// result of [`find()`][file::Store::find()].
+    /// Note that this is a separate lookup, so the reference may have been moved or deleted since it was found.
+    pub fn provenance(&self, name: &FullNameRef) -> Result<Option<Provenance>, find::Error> {
+        let path = self.reference_path(name);
+        match std::fs::metadata(&path) {
+            Ok(metadata) if metadata.is_file() => {
+                return Ok(Some(Provenance {
+                    source: Source::Loose,
+                    path,
+                    modified: metadata.modified().ok(),
+                }))
+            }
+            Ok(_) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            #[cfg(windows)]
+            Err(err) if err.kind() == std::io::ErrorKind::PermissionDenied => {}
+            Err(err) => return Err(find::Error::ReadFileContents { err, path }),
+        }
+
+        let packed = match self.assure_packed_refs_uptodate()? {
+            Some(packed) => packed,
+            None => return Ok(None),
+        };
+        let full_name = match packed::find::transform_full_name_for_lookup(name) {
+            Some(name) => name,
+            None => return Ok(None),
+        };
+        let is_packed = match &self.namespace {
+            Some(namespace) => packed
+                .try_find_full_name(namespace.to_owned().into_namespaced_name(full_name).as_ref())?
+                .is_some(),
+            None => packed.try_find_full_name(full_name)?.is_some(),
+        };
+        Ok(is_packed.then(|| {
+            let path = self.packed_refs_path();
+            Provenance {
+                source: Source::Packed,
+                modified: path.metadata().and_then(|m| m.modified()).ok(),
+                path,
+            }
+        }))
+    }
+}
@@ -73,6 +73,7 @@ impl<'s> Transaction<'s> {
                         continue;
                     }
                     if update_ref {
+                        let reference_path = lock.resource_path();
                         if let Err(err) = lock.commit() {
                             // TODO: when Kind::IsADirectory becomes stable, use that.
                             let err = if err.instance.resource_path().is_dir() {
@@ -91,6 +92,15 @@ impl<'s> Transaction<'s> {
                                 });
                             }
                         };
+                        if self.store.fsync.is_some() {
+                            git_features::fs::fsync::directory(
+                                reference_path.parent().expect("references are always in a directory"),
+                            )
+                            .map_err(|err| Error::LockCommit {
+                                err,
+                                full_name: change.name(),
+                            })?;
+                        }
                     }
                 }
                 Change::Delete { .. } => {}
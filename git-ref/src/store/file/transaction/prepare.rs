@@ -154,9 +154,15 @@ impl<'s> Transaction<'s> {
                     *expected = PreviousValue::MustExistAndMatch(existing.target);
                 };
 
-                lock.with_mut(|file| match new {
-                    Target::Peeled(oid) => write!(file, "{}", oid),
-                    Target::Symbolic(name) => write!(file, "ref: {}", name.0),
+                lock.with_mut(|file| {
+                    match new {
+                        Target::Peeled(oid) => write!(file, "{}", oid),
+                        Target::Symbolic(name) => write!(file, "ref: {}", name.0),
+                    }?;
+                    match store.fsync {
+                        Some(method) => git_features::fs::fsync::file(file, method),
+                        None => Ok(()),
+                    }
                 })?;
 
                 lock.close()?
@@ -280,7 +286,10 @@ impl<'s> Transaction<'s> {
                     // no packed-ref file exists anyway
                     self.store
                         .assure_packed_refs_uptodate()?
-                        .map(|p| buffer_into_transaction(p, lock_fail_mode).map_err(Error::PackedTransactionAcquire))
+                        .map(|p| {
+                            buffer_into_transaction(p, lock_fail_mode, self.store.fsync)
+                                .map_err(Error::PackedTransactionAcquire)
+                        })
                         .transpose()?
                 };
                 if let Some(transaction) = packed_transaction {
@@ -346,22 +355,16 @@ impl<'s> Transaction<'s> {
     }
 }
 
+/// Return the name under which `name` would be stored in packed-refs, or `None` if it must never be packed as it's not shared.
 fn possibly_adjust_name_for_prefixes(name: &FullNameRef) -> Option<FullName> {
+    if !name.class().is_shared() {
+        return None;
+    }
     match name.category_and_short_name() {
-        Some((c, sn)) => {
-            use crate::Category::*;
-            let sn = FullNameRef::new_unchecked(sn);
-            match c {
-                Bisect | Rewritten | WorktreePrivate | LinkedPseudoRef { .. } | PseudoRef | MainPseudoRef => None,
-                Tag | LocalBranch | RemoteBranch | Note => name.into(),
-                MainRef | LinkedRef { .. } => sn
-                    .category()
-                    .map_or(false, |cat| !cat.is_worktree_private())
-                    .then(|| sn),
-            }
-            .map(|n| n.to_owned())
+        Some((crate::Category::MainRef | crate::Category::LinkedRef { .. }, sn)) => {
+            Some(FullNameRef::new_unchecked(sn).to_owned())
         }
-        None => Some(name.to_owned()), // allow (uncategorized/very special) refs to be packed
+        _ => Some(name.to_owned()), // allow (uncategorized/very special) refs to be packed
     }
 }
 
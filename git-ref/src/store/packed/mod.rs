@@ -38,6 +38,8 @@ pub(crate) struct Transaction {
     lock: Option<git_lock::File>,
     #[allow(dead_code)] // It just has to be kept alive, hence no reads
     closed_lock: Option<git_lock::Marker>,
+    /// If set, the new packed-refs file is flushed to permanent storage with the given method before it is moved into place.
+    fsync: Option<git_features::fs::fsync::Method>,
 }
 
 /// A reference as parsed from the `packed-refs` file
@@ -1,5 +1,7 @@
 use std::io::Write;
 
+use git_features::fs::fsync;
+
 use crate::{
     file,
     store_impl::{file::transaction::FindObjectFn, packed, packed::Edit},
@@ -14,12 +16,14 @@ impl packed::Transaction {
     pub(crate) fn new_from_pack_and_lock(
         buffer: Option<file::packed::SharedBufferSnapshot>,
         lock: git_lock::File,
+        fsync: Option<git_features::fs::fsync::Method>,
     ) -> Self {
         packed::Transaction {
             buffer,
             edits: None,
             lock: Some(lock),
             closed_lock: None,
+            fsync,
         }
     }
 }
@@ -170,11 +174,18 @@ impl packed::Transaction {
             }
         }
 
+        let packed_refs_path = file.resource_path();
         if num_written_lines == 0 {
-            std::fs::remove_file(file.resource_path())?;
+            std::fs::remove_file(&packed_refs_path)?;
         } else {
+            if let Some(method) = self.fsync {
+                file.with_mut(|f| fsync::file(f, method))?;
+            }
             file.commit()?;
         }
+        if self.fsync.is_some() {
+            fsync::directory(packed_refs_path.parent().expect("packed-refs is always in a directory"))?;
+        }
         drop(refs_sorted);
         Ok(())
     }
@@ -217,6 +228,7 @@ fn write_edit(mut out: impl std::io::Write, edit: &Edit, lines_written: &mut i32
 pub(crate) fn buffer_into_transaction(
     buffer: file::packed::SharedBufferSnapshot,
     lock_mode: git_lock::acquire::Fail,
+    fsync: Option<git_features::fs::fsync::Method>,
 ) -> Result<packed::Transaction, git_lock::acquire::Error> {
     let lock = git_lock::File::acquire_to_update_resource(&buffer.path, lock_mode, None)?;
     Ok(packed::Transaction {
@@ -224,6 +236,7 @@ pub(crate) fn buffer_into_transaction(
         lock: Some(lock),
         closed_lock: None,
         edits: None,
+        fsync,
     })
 }
 
@@ -1,3 +1,4 @@
 mod find;
 mod iter;
+mod provenance;
 mod reflog;
@@ -0,0 +1,31 @@
+use std::convert::TryInto;
+
+use git_ref::file::provenance::Source;
+
+use crate::file::store_at;
+
+#[test]
+fn loose_refs_shadow_packed_ones_and_missing_refs_have_none() -> crate::Result {
+    let store = store_at("make_packed_ref_repository_for_overlay.sh")?;
+    let git_dir = store.git_dir().to_owned();
+
+    for (name, source, path) in [
+        ("refs/heads/main", Source::Packed, git_dir.join("packed-refs")),
+        (
+            "refs/heads/newer-as-loose",
+            Source::Loose,
+            git_dir.join("refs").join("heads").join("newer-as-loose"),
+        ),
+        ("HEAD", Source::Loose, git_dir.join("HEAD")),
+    ] {
+        let provenance = store
+            .provenance(name.try_into()?)?
+            .expect("reference exists in the fixture");
+        assert_eq!(provenance.source, source, "{}", name);
+        assert_eq!(provenance.path, path);
+        assert_eq!(provenance.modified, Some(std::fs::metadata(&path)?.modified()?));
+    }
+
+    assert_eq!(store.provenance("refs/heads/does-not-exist".try_into()?)?, None);
+    Ok(())
+}
@@ -775,3 +775,50 @@ fn packed_refs_creation_with_packed_refs_mode_leave_keeps_original_loose_refs()
     );
     Ok(())
 }
+
+#[test]
+fn loose_refs_reflogs_and_packed_refs_can_be_written_with_fsync() -> crate::Result {
+    let (_keep, mut store) = empty_store()?;
+    store.fsync = Some(git_features::fs::fsync::Method::Fsync);
+    let new = hex_to_id("28ce6a8b26aa170e1de65536fe8abe1832bd3242");
+    let edit = |name: &str| RefEdit {
+        change: Change::Update {
+            log: LogChange {
+                force_create_reflog: true,
+                ..LogChange::default()
+            },
+            expected: PreviousValue::MustNotExist,
+            new: Target::Peeled(new),
+        },
+        name: name.try_into().expect("valid"),
+        deref: false,
+    };
+
+    store
+        .transaction()
+        .prepare(Some(edit("refs/heads/loose")), Fail::Immediately)?
+        .commit(committer().to_ref())?;
+    assert_eq!(
+        store.find_loose("refs/heads/loose")?.target.to_ref().try_id(),
+        Some(new.as_ref())
+    );
+    assert_eq!(reflog_lines(&store, "refs/heads/loose")?.len(), 1);
+
+    store
+        .transaction()
+        .packed_refs(PackedRefs::DeletionsAndNonSymbolicUpdates(Box::new(|_, _| {
+            Ok(Some(git_object::Kind::Commit))
+        })))
+        .prepare(Some(edit("refs/heads/packed")), Fail::Immediately)?
+        .commit(committer().to_ref())?;
+    assert_eq!(
+        store
+            .open_packed_buffer()?
+            .expect("created")
+            .find("refs/heads/packed")?
+            .target(),
+        new,
+        "packed-refs are flushed before being moved into place"
+    );
+    Ok(())
+}
@@ -80,6 +80,8 @@ impl From<Mode> for bool {
 }
 
 mod read_only {
+    use git_ref::Class;
+
     use crate::file::worktree::{assert_reflog, into_peel, main_store, worktree_store, Mode};
 
     #[test]
@@ -188,6 +190,36 @@ mod read_only {
         }
         Ok(())
     }
+
+    #[test]
+    fn iteration_by_class() -> crate::Result {
+        for packed in [false, true] {
+            for (store, expected_private) in [
+                (main_store(packed, Mode::Read)?.0, "refs/bisect/bad"),
+                (worktree_store(packed, "w1", Mode::Read)?.0, "refs/bisect/bad"),
+            ] {
+                let names = |class: Class| -> crate::Result<Vec<String>> {
+                    Ok(store
+                        .iter()?
+                        .of_class(class)?
+                        .map(|r| r.map(|r| r.name.to_string()))
+                        .collect::<Result<_, _>>()?)
+                };
+                assert_eq!(names(Class::PerWorktree)?, [expected_private]);
+                assert_eq!(
+                    names(Class::Shared)?,
+                    ["refs/heads/main", "refs/heads/w1", "refs/tags/dt1", "refs/tags/t1"],
+                    "shared references are the ones that can be packed"
+                );
+                assert!(names(Class::Namespaced)?.is_empty());
+                assert!(
+                    names(Class::Pseudo)?.is_empty(),
+                    "pseudo-refs are outside of refs/ and thus never iterated"
+                );
+            }
+        }
+        Ok(())
+    }
 }
 
 mod writable {
@@ -613,6 +645,83 @@ mod writable {
 
         Ok(())
     }
+
+    #[test]
+    fn linked_refs_outside_of_known_categories_are_shared_with_all_worktrees() -> crate::Result {
+        let new_id_str = "134385f6d781b7e97062102c6a483440bfda2a03";
+        let new_id = hex_to_id(new_id_str);
+        let (store, _odb, tmp) = worktree_store(false, "w1", Mode::Write)?;
+        let common_dir = tmp.as_ref().expect("writable").path().join("repo").join(".git");
+        let main_store = Store::at(&common_dir, Default::default(), Default::default());
+
+        let forced_log_change = || Change::Update {
+            log: LogChange {
+                force_create_reflog: true,
+                ..Default::default()
+            },
+            expected: PreviousValue::MustNotExist,
+            new: Target::Peeled(new_id),
+        };
+        let edits = store
+            .transaction()
+            .prepare(
+                vec![
+                    RefEdit {
+                        change: forced_log_change(),
+                        name: "refs/stash".try_into()?,
+                        deref: false,
+                    },
+                    RefEdit {
+                        change: forced_log_change(),
+                        name: "refs/custom/shared".try_into()?,
+                        deref: false,
+                    },
+                ],
+                git_lock::acquire::Fail::Immediately,
+            )?
+            .commit(committer().to_ref())?;
+
+        let mut buf = Vec::new();
+        for edit in &edits {
+            let name = edit.name.as_ref();
+            assert!(
+                common_dir.join(git_path::from_bstr(name.as_bstr())).is_file(),
+                "{} is written into the common dir",
+                name.as_bstr()
+            );
+            assert_eq!(main_store.find(name)?.target.id(), new_id, "the main worktree sees it");
+            assert_eq!(
+                reflog_for_name(&main_store, name, &mut buf),
+                vec![new_id_str.to_string()],
+                "reflogs are shared as well"
+            );
+        }
+
+        let private_heads = store.git_dir().join("refs").join("heads");
+        std::fs::create_dir_all(&private_heads)?;
+        std::fs::write(private_heads.join("stray"), format!("{}\n", new_id_str))?;
+        assert!(
+            store.try_find("refs/heads/stray")?.is_none(),
+            "shared refs in the private git dir are ignored"
+        );
+
+        for store in [&store, &main_store] {
+            let names = store
+                .iter()?
+                .all()?
+                .map(Result::unwrap)
+                .map(|r| r.name.to_string())
+                .collect::<Vec<_>>();
+            for name in ["refs/custom/shared", "refs/stash"] {
+                assert!(names.iter().any(|n| n == name), "{} is visible to all worktrees", name);
+            }
+            assert!(
+                !names.iter().any(|n| n == "refs/heads/stray"),
+                "shared refs in the private git dir aren't iterated either"
+            );
+        }
+        Ok(())
+    }
 }
 
 fn assert_reflog(store: &git_ref::file::Store, a: Reference, b: Reference) {
@@ -1,6 +1,6 @@
 use std::{borrow::Cow, convert::TryInto};
 
-use git_ref::{Category, FullNameRef, PartialNameRef};
+use git_ref::{Category, Class, FullNameRef, PartialNameRef};
 
 #[test]
 fn cow() {
@@ -99,6 +99,40 @@ fn shorten_and_category() {
     }
 }
 
+#[test]
+fn class() {
+    for (input, expected) in [
+        ("refs/heads/main", Class::Shared),
+        ("refs/tags/v1", Class::Shared),
+        ("refs/remotes/origin/main", Class::Shared),
+        ("refs/notes/commits", Class::Shared),
+        ("refs/stash", Class::Shared),
+        ("refs/replace/abc", Class::Shared),
+        ("hello/world", Class::Shared),
+        ("refs/namespaces/foo/refs/heads/main", Class::Namespaced),
+        ("refs/bisect/good", Class::PerWorktree),
+        ("refs/rewritten/123456", Class::PerWorktree),
+        ("refs/worktree/private", Class::PerWorktree),
+        ("HEAD", Class::Pseudo),
+        ("FETCH_HEAD", Class::Pseudo),
+        ("main-worktree/HEAD", Class::Pseudo),
+        ("worktrees/name/HEAD", Class::Pseudo),
+        ("main-worktree/refs/heads/main", Class::Shared),
+        ("main-worktree/refs/stash", Class::Shared),
+        ("main-worktree/refs/bisect/good", Class::PerWorktree),
+        ("worktrees/name/refs/worktree/private", Class::PerWorktree),
+        ("worktrees/name/refs/namespaces/foo/refs/heads/main", Class::Namespaced),
+    ] {
+        let name: git_ref::FullName = input.try_into().unwrap();
+        assert_eq!(name.class(), expected, "{}", input);
+        assert_eq!(name.as_ref().class(), expected);
+    }
+    assert!(Class::Shared.is_shared());
+    assert!(Class::Namespaced.is_shared());
+    assert!(!Class::PerWorktree.is_shared());
+    assert!(!Class::Pseudo.is_shared());
+}
+
 #[test]
 fn prefix_with_namespace_and_stripping() {
     let ns = git_ref::namespace::expand("foo").unwrap();
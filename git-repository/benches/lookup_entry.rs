@@ -0,0 +1,67 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use git_repository as git;
+use git_repository::objs::tree::{build::Builder, EntryMode};
+
+const DIRS: usize = 20;
+const SUBDIRS: usize = 20;
+const FILES: usize = 10;
+
+/// Create a repository with a tree of `DIRS * SUBDIRS * FILES` files and return it along with the path to each file.
+fn fixture() -> (git::Repository, tempfile::TempDir, git::ObjectId, Vec<Vec<String>>) {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = git::init_bare(tmp.path()).unwrap();
+    let blob = repo
+        .write_object(git::objs::Blob {
+            data: b"content".to_vec(),
+        })
+        .unwrap()
+        .detach();
+    let mut paths = Vec::new();
+    let mut root = Builder::new();
+    for dir in 0..DIRS {
+        let mut dir_tree = Builder::new();
+        for subdir in 0..SUBDIRS {
+            let mut subdir_tree = Builder::new();
+            for file in 0..FILES {
+                let name = format!("file-{}", file);
+                paths.push(vec![format!("dir-{}", dir), format!("subdir-{}", subdir), name.clone()]);
+                subdir_tree = subdir_tree.entry(EntryMode::Blob, name, blob);
+            }
+            let id = repo.write_object(subdir_tree.build().unwrap()).unwrap().detach();
+            dir_tree = dir_tree.entry(EntryMode::Tree, format!("subdir-{}", subdir), id);
+        }
+        let id = repo.write_object(dir_tree.build().unwrap()).unwrap().detach();
+        root = root.entry(EntryMode::Tree, format!("dir-{}", dir), id);
+    }
+    let root = repo.write_object(root.build().unwrap()).unwrap().detach();
+    (repo, tmp, root, paths)
+}
+
+fn lookup_entry(c: &mut Criterion) {
+    let (repo, _tmp, root, paths) = fixture();
+    let tree = repo.find_object(root).unwrap().into_tree();
+
+    c.bench_function("lookup_path() for all paths", |b| {
+        b.iter(|| {
+            for path in &paths {
+                let tree = git::Tree::from_data(tree.id, tree.data.clone(), &repo);
+                black_box(tree.lookup_path(path.iter().map(String::as_str)).unwrap().unwrap());
+            }
+        })
+    });
+    c.bench_function("lookup_entry_cached() for all paths", |b| {
+        b.iter(|| {
+            let mut cache = git::object::tree::lookup::Cache::new();
+            for path in &paths {
+                black_box(
+                    tree.lookup_entry_cached(path.iter().map(String::as_str), &mut cache)
+                        .unwrap()
+                        .unwrap(),
+                );
+            }
+        })
+    });
+}
+
+criterion_group!(benches, lookup_entry);
+criterion_main!(benches);
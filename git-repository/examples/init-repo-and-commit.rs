@@ -25,7 +25,7 @@ fn main() -> anyhow::Result<()> {
         email: "maria@example.com".into(),
         time: git_date::Time::now_local_or_utc(),
     };
-    let initial_commit_id = repo.commit(
+    let initial_commit_id = repo.commit_as(
         "HEAD",
         author,
         author,
@@ -46,7 +46,7 @@ fn main() -> anyhow::Result<()> {
     tree.entries.push(entry);
     let hello_tree_id = repo.write_object(&tree)?;
 
-    let blob_commit_id = repo.commit(
+    let blob_commit_id = repo.commit_as(
         "HEAD",
         author,
         author,
@@ -0,0 +1,128 @@
+//! Apply patches as produced by `git diff` to the worktree, the index or both, similar to `git apply`.
+use crate::bstr::BString;
+
+///
+pub mod patch;
+
+///
+pub mod text;
+
+/// Where to apply a patch to with [`Repository::apply()`][crate::Repository::apply()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    /// Apply the patch to files in the worktree, leaving the index untouched. This is the default.
+    Worktree,
+    /// Apply the patch to the index only, leaving the worktree untouched, like `git apply --cached`.
+    Index,
+    /// Apply the patch to the index and the worktree, like `git apply --index`.
+    ///
+    /// Files to patch must exist in the index and their worktree content must match it.
+    Both,
+}
+
+impl Default for Location {
+    fn default() -> Self {
+        Location::Worktree
+    }
+}
+
+/// What to do with lines the patch adds if they end in whitespace, like `git apply --whitespace=<action>`.
+///
+/// Note that only trailing spaces and tabs are considered whitespace errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Whitespace {
+    /// Don't count whitespace errors.
+    Nowarn,
+    /// Count whitespace errors in the [`Outcome`], but apply the patch as is. This is the default.
+    Warn,
+    /// Remove trailing whitespace from added lines while applying the patch, and count the lines that were fixed in the [`Outcome`].
+    Fix,
+    /// Refuse to apply the patch if it contains whitespace errors.
+    Error,
+}
+
+impl Default for Whitespace {
+    fn default() -> Self {
+        Whitespace::Warn
+    }
+}
+
+/// Options for use in [`Repository::apply()`][crate::Repository::apply()].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// Whether to change the worktree, the index or both.
+    pub location: Location,
+    /// If true, apply the patch in reverse, like `git apply -R`.
+    pub reverse: bool,
+    /// If true, apply all hunks that apply and write those that don't to `<path>.rej` in the worktree, like `git apply --reject`.
+    ///
+    /// Otherwise, nothing is changed if a single hunk doesn't apply.
+    pub reject: bool,
+    /// How to handle whitespace errors in added lines.
+    pub whitespace: Whitespace,
+}
+
+/// The hunks of a file that didn't apply, as listed in the [`Outcome`] of [`Repository::apply()`][crate::Repository::apply()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reject {
+    /// The path of the patched file, to which `.rej` was appended to obtain the file containing the rejected hunks.
+    pub path: BString,
+    /// The numbers of the hunks that didn't apply, starting at 1 like `git` counts them.
+    pub hunks: Vec<usize>,
+}
+
+/// The outcome of [`Repository::apply()`][crate::Repository::apply()].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The paths of all files that were patched, in the order they appear in the patch, with renamed files listed by their new path.
+    pub paths: Vec<BString>,
+    /// The files with hunks that didn't apply, which only happens if [rejects are allowed][Options::reject].
+    pub rejects: Vec<Reject>,
+    /// The amount of added lines with trailing whitespace, unless [whitespace errors are ignored][Whitespace::Nowarn].
+    pub whitespace_errors: usize,
+}
+
+/// The error returned by [`Repository::apply()`][crate::Repository::apply()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Parse(#[from] patch::parse::Error),
+    #[error(transparent)]
+    MissingWorkTree(#[from] crate::worktree::require::Error),
+    #[error(transparent)]
+    ReadOnly(#[from] crate::read_only::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error("Could not create an empty index")]
+    EmptyIndex(#[from] git_index::init::from_tree::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] git_index::file::write::Error),
+    #[error("The path {path:?} in the patch is invalid")]
+    InvalidPath {
+        path: BString,
+        source: git_validate::path::component::Error,
+    },
+    #[error("Cannot apply binary patch to {path:?}")]
+    Binary { path: BString },
+    #[error("Cannot apply patch to submodule {path:?}")]
+    Submodule { path: BString },
+    #[error("{path:?} does not exist")]
+    DoesNotExist { path: BString },
+    #[error("{path:?} already exists")]
+    AlreadyExists { path: BString },
+    #[error("{path:?} does not match the index")]
+    DoesNotMatchIndex { path: BString },
+    #[error("Patch failed at {path:?}: hunk #{hunk} does not apply")]
+    DoesNotApply { path: BString, hunk: usize },
+    #[error("Removal patch leaves content in {path:?}")]
+    LeavesContent { path: BString },
+    #[error("{count} line(s) add whitespace errors")]
+    Whitespace { count: usize },
+    #[error("Could not access {path:?} in the worktree")]
+    Io { path: BString, source: std::io::Error },
+}
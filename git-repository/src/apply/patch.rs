@@ -0,0 +1,424 @@
+use std::io::Write;
+
+use git_object::tree::EntryMode;
+
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// The changes to a single file as described by a patch.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct File {
+    /// The path of the file before the change, or `None` if it's created.
+    pub old_path: Option<BString>,
+    /// The path of the file after the change, or `None` if it's deleted.
+    pub new_path: Option<BString>,
+    /// The mode of the file before the change, if the patch mentions it.
+    pub old_mode: Option<EntryMode>,
+    /// The mode of the file after the change, if the patch mentions it.
+    pub new_mode: Option<EntryMode>,
+    /// If true, the file at `new_path` is created as copy of the one at `old_path`, which remains unchanged.
+    pub is_copy: bool,
+    /// If true, the patch changes binary content which can't be applied.
+    pub is_binary: bool,
+    /// The changes to the content of the file.
+    pub hunks: Vec<Hunk>,
+}
+
+/// A contiguous range of changed lines along with their context.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// The line number at which the hunk starts in the old file, starting at 1, or 0 if the old file is empty.
+    pub old_start: usize,
+    /// The amount of lines of the old file that are part of the hunk.
+    pub old_len: usize,
+    /// The line number at which the hunk starts in the new file, starting at 1, or 0 if the new file is empty.
+    pub new_start: usize,
+    /// The amount of lines of the new file that are part of the hunk.
+    pub new_len: usize,
+    /// The text following the range information in the hunk header, typically the function the hunk is in.
+    pub section: BString,
+    /// The lines of the hunk.
+    pub lines: Vec<Line>,
+}
+
+/// A line in a [`Hunk`], with its content including the line terminator unless it's the last line of a file without one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Line {
+    /// A line that is unchanged.
+    Context(BString),
+    /// A line that is only in the old file.
+    Removed(BString),
+    /// A line that is only in the new file.
+    Added(BString),
+}
+
+impl Line {
+    /// Return the content of the line.
+    pub fn content(&self) -> &BStr {
+        match self {
+            Line::Context(line) | Line::Removed(line) | Line::Added(line) => line.as_bstr(),
+        }
+    }
+}
+
+impl File {
+    /// Change ourselves to undo the changes we describe, like `git apply -R` does.
+    pub fn reverse(&mut self) {
+        std::mem::swap(&mut self.old_path, &mut self.new_path);
+        std::mem::swap(&mut self.old_mode, &mut self.new_mode);
+        for hunk in &mut self.hunks {
+            std::mem::swap(&mut hunk.old_start, &mut hunk.new_start);
+            std::mem::swap(&mut hunk.old_len, &mut hunk.new_len);
+            for line in &mut hunk.lines {
+                *line = match std::mem::replace(line, Line::Context(BString::default())) {
+                    Line::Added(line) => Line::Removed(line),
+                    Line::Removed(line) => Line::Added(line),
+                    context => context,
+                };
+            }
+        }
+    }
+}
+
+impl Hunk {
+    /// Serialize this hunk to `out` in the unified diff format, as used in `.rej` files.
+    pub fn write_to(&self, mut out: impl Write) -> std::io::Result<()> {
+        write!(out, "@@ -")?;
+        write_range(&mut out, self.old_start, self.old_len)?;
+        write!(out, " +")?;
+        write_range(&mut out, self.new_start, self.new_len)?;
+        out.write_all(b" @@")?;
+        if !self.section.is_empty() {
+            out.write_all(b" ")?;
+            out.write_all(&self.section)?;
+        }
+        out.write_all(b"\n")?;
+        for line in &self.lines {
+            let prefix = match line {
+                Line::Context(_) => b' ',
+                Line::Removed(_) => b'-',
+                Line::Added(_) => b'+',
+            };
+            let content = line.content();
+            out.write_all(&[prefix])?;
+            out.write_all(content)?;
+            if !content.ends_with(b"\n") {
+                out.write_all(b"\n\\ No newline at end of file\n")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_range(out: &mut impl Write, start: usize, len: usize) -> std::io::Result<()> {
+    if len == 1 {
+        write!(out, "{}", start)
+    } else {
+        write!(out, "{},{}", start, len)
+    }
+}
+
+///
+pub mod parse {
+    /// The error returned by [`parse()`][super::parse()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("No valid patches in input")]
+        Empty,
+        #[error("Corrupt patch at line {line}")]
+        Corrupt { line: usize },
+        #[error("Could not determine the file name of the patch at line {line}")]
+        MissingFileName { line: usize },
+        #[error("Invalid mode at line {line}")]
+        InvalidMode { line: usize },
+    }
+}
+
+/// Parse all file changes in `input`, which may be the output of `git diff`, `git format-patch` or `diff -u`.
+///
+/// Text that isn't part of a patch, like commit messages, is ignored. Paths in `---` and `+++` lines have their first
+/// component removed like with `git apply -p1`.
+pub fn parse(input: &[u8]) -> Result<Vec<File>, parse::Error> {
+    let mut parser = Parser {
+        lines: input.lines_with_terminator().collect(),
+        pos: 0,
+    };
+    let mut files = Vec::new();
+    while let Some(line) = parser.peek() {
+        if line.starts_with(b"diff --git ") {
+            files.push(parser.git_file()?);
+        } else if line.starts_with(b"--- ")
+            && parser.peek_at(1).map_or(false, |line| line.starts_with(b"+++ "))
+            && parser.peek_at(2).map_or(false, |line| line.starts_with(b"@@ -"))
+        {
+            files.push(parser.unified_file()?);
+        } else {
+            parser.pos += 1;
+        }
+    }
+    if files.is_empty() {
+        return Err(parse::Error::Empty);
+    }
+    Ok(files)
+}
+
+struct Parser<'a> {
+    lines: Vec<&'a [u8]>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a [u8]> {
+        self.lines.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&'a [u8]> {
+        self.lines.get(self.pos + offset).copied()
+    }
+
+    /// The number of the current line, starting at 1.
+    fn line_number(&self) -> usize {
+        self.pos + 1
+    }
+
+    fn git_file(&mut self) -> Result<File, parse::Error> {
+        let header_line = self.line_number();
+        let default_name = self
+            .peek()
+            .and_then(|line| git_header_name(trim_newline(&line[b"diff --git ".len()..])));
+        self.pos += 1;
+
+        let mut file = File::default();
+        let (mut is_new, mut is_delete) = (false, false);
+        let (mut old_name, mut new_name) = (None, None);
+        while let Some(line) = self.peek() {
+            let line_number = self.line_number();
+            let mode =
+                |value: &[u8]| parse_mode(trim_newline(value)).ok_or(parse::Error::InvalidMode { line: line_number });
+            if let Some(value) = line.strip_prefix(b"old mode ") {
+                file.old_mode = Some(mode(value)?);
+            } else if let Some(value) = line.strip_prefix(b"new mode ") {
+                file.new_mode = Some(mode(value)?);
+            } else if let Some(value) = line.strip_prefix(b"deleted file mode ") {
+                file.old_mode = Some(mode(value)?);
+                is_delete = true;
+            } else if let Some(value) = line.strip_prefix(b"new file mode ") {
+                file.new_mode = Some(mode(value)?);
+                is_new = true;
+            } else if let Some(value) = line
+                .strip_prefix(b"rename from ")
+                .or_else(|| line.strip_prefix(b"copy from "))
+            {
+                old_name = Some(Some(unquote(trim_newline(value))));
+            } else if let Some(value) = line.strip_prefix(b"rename to ") {
+                new_name = Some(Some(unquote(trim_newline(value))));
+            } else if let Some(value) = line.strip_prefix(b"copy to ") {
+                new_name = Some(Some(unquote(trim_newline(value))));
+                file.is_copy = true;
+            } else if let Some(value) = line.strip_prefix(b"--- ") {
+                old_name = old_name.or_else(|| Some(patch_name(value)));
+            } else if let Some(value) = line.strip_prefix(b"+++ ") {
+                new_name = new_name.or_else(|| Some(patch_name(value)));
+            } else if line.starts_with(b"Binary files ") || line.starts_with(b"GIT binary patch") {
+                file.is_binary = true;
+                self.pos += 1;
+                while self.peek().map_or(false, |line| !line.starts_with(b"diff ")) {
+                    self.pos += 1;
+                }
+                break;
+            } else if !(line.starts_with(b"index ")
+                || line.starts_with(b"similarity index ")
+                || line.starts_with(b"dissimilarity index "))
+            {
+                break;
+            }
+            self.pos += 1;
+        }
+
+        file.old_path = if is_new {
+            None
+        } else {
+            old_name.unwrap_or_else(|| default_name.clone())
+        };
+        file.new_path = if is_delete {
+            None
+        } else {
+            new_name.unwrap_or(default_name)
+        };
+        if file.old_path.is_none() && file.new_path.is_none() {
+            return Err(parse::Error::MissingFileName { line: header_line });
+        }
+        if !file.is_binary {
+            file.hunks = self.hunks()?;
+        }
+        Ok(file)
+    }
+
+    fn unified_file(&mut self) -> Result<File, parse::Error> {
+        let old_path = patch_name(&self.lines[self.pos][b"--- ".len()..]);
+        let new_path = patch_name(&self.lines[self.pos + 1][b"+++ ".len()..]);
+        if old_path.is_none() && new_path.is_none() {
+            return Err(parse::Error::MissingFileName {
+                line: self.line_number(),
+            });
+        }
+        self.pos += 2;
+        Ok(File {
+            old_path,
+            new_path,
+            hunks: self.hunks()?,
+            ..Default::default()
+        })
+    }
+
+    fn hunks(&mut self) -> Result<Vec<Hunk>, parse::Error> {
+        let mut hunks = Vec::new();
+        while let Some(header) = self.peek().filter(|line| line.starts_with(b"@@ -")) {
+            let corrupt = parse::Error::Corrupt {
+                line: self.line_number(),
+            };
+            let mut hunk = parse_hunk_header(trim_newline(header)).ok_or(corrupt)?;
+            self.pos += 1;
+            let (mut old_remaining, mut new_remaining) = (hunk.old_len, hunk.new_len);
+            while old_remaining > 0 || new_remaining > 0 {
+                let line_number = self.line_number();
+                let corrupt = || parse::Error::Corrupt { line: line_number };
+                let line = self.peek().ok_or_else(corrupt)?;
+                let line = match line.split_first() {
+                    // Some tools strip the trailing space of empty context lines.
+                    Some((b'\n', _)) | Some((b'\r', _)) => Line::Context(line.into()),
+                    Some((b' ', content)) => Line::Context(content.into()),
+                    Some((b'-', content)) => Line::Removed(content.into()),
+                    Some((b'+', content)) => Line::Added(content.into()),
+                    Some((b'\\', _)) => {
+                        strip_last_newline(&mut hunk.lines);
+                        self.pos += 1;
+                        continue;
+                    }
+                    _ => return Err(corrupt()),
+                };
+                if !matches!(line, Line::Added(_)) {
+                    old_remaining = old_remaining.checked_sub(1).ok_or_else(corrupt)?;
+                }
+                if !matches!(line, Line::Removed(_)) {
+                    new_remaining = new_remaining.checked_sub(1).ok_or_else(corrupt)?;
+                }
+                hunk.lines.push(line);
+                self.pos += 1;
+            }
+            if self.peek().map_or(false, |line| line.starts_with(b"\\")) {
+                strip_last_newline(&mut hunk.lines);
+                self.pos += 1;
+            }
+            hunks.push(hunk);
+        }
+        Ok(hunks)
+    }
+}
+
+/// Handle `\ No newline at end of file` by removing the line terminator of the line it refers to.
+fn strip_last_newline(lines: &mut [Line]) {
+    if let Some(Line::Context(line) | Line::Removed(line) | Line::Added(line)) = lines.last_mut() {
+        if line.ends_with(b"\n") {
+            line.pop();
+        }
+    }
+}
+
+/// Parse `@@ -<old_start>[,<old_len>] +<new_start>[,<new_len>] @@[ <section>]`.
+fn parse_hunk_header(line: &[u8]) -> Option<Hunk> {
+    let line = line.strip_prefix(b"@@ -")?;
+    let (ranges, section) = split_once(line, b" @@")?;
+    let (old, new) = split_once(ranges, b" +")?;
+    let (old_start, old_len) = parse_range(old)?;
+    let (new_start, new_len) = parse_range(new)?;
+    Some(Hunk {
+        old_start,
+        old_len,
+        new_start,
+        new_len,
+        section: section.trim_start().into(),
+        lines: Vec::new(),
+    })
+}
+
+fn parse_range(range: &[u8]) -> Option<(usize, usize)> {
+    let number = |n: &[u8]| n.to_str().ok()?.parse().ok();
+    match split_once(range, b",") {
+        Some((start, len)) => Some((number(start)?, number(len)?)),
+        None => Some((number(range)?, 1)),
+    }
+}
+
+fn split_once<'a>(input: &'a [u8], separator: &[u8]) -> Option<(&'a [u8], &'a [u8])> {
+    let pos = input.find(separator)?;
+    Some((&input[..pos], &input[pos + separator.len()..]))
+}
+
+fn parse_mode(mode: &[u8]) -> Option<EntryMode> {
+    Some(match mode {
+        b"100644" => EntryMode::Blob,
+        b"100755" => EntryMode::BlobExecutable,
+        b"120000" => EntryMode::Link,
+        b"160000" => EntryMode::Commit,
+        // Very old versions of git recorded the permissions of the file as is.
+        _ if mode.starts_with(b"100") && mode.len() == 6 => {
+            if mode[3..].iter().any(|digit| (digit - b'0') & 1 == 1) {
+                EntryMode::BlobExecutable
+            } else {
+                EntryMode::Blob
+            }
+        }
+        _ => return None,
+    })
+}
+
+/// Obtain the path of a `---` or `+++` line, or `None` if it's `/dev/null`, with the first path component removed.
+fn patch_name(value: &[u8]) -> Option<BString> {
+    let value = trim_newline(value);
+    let name = if value.starts_with(b"\"") {
+        unquote(value)
+    } else {
+        // Traditional diffs may follow the name with a tab and a timestamp.
+        value.split_str(b"\t").next().unwrap_or_default().into()
+    };
+    if name == "/dev/null" {
+        return None;
+    }
+    Some(match name.find_byte(b'/') {
+        Some(pos) => name[pos + 1..].into(),
+        None => name,
+    })
+}
+
+/// Obtain the path from the `a/<path> b/<path>` part of a `diff --git` line, which is only possible if both paths are the same.
+fn git_header_name(value: &[u8]) -> Option<BString> {
+    let (old, new) = if value.starts_with(b"\"") {
+        let (old, consumed) = git_quote::ansi_c::undo(value.as_bstr()).ok()?;
+        let new = value[consumed..].strip_prefix(b" ")?;
+        (old.into_owned(), unquote(new))
+    } else {
+        let half = value.len().checked_sub(1)? / 2;
+        if value.len() % 2 != 1 || value[half] != b' ' {
+            return None;
+        }
+        (value[..half].into(), value[half + 1..].into())
+    };
+    let strip = |name: &BString| name.find_byte(b'/').map(|pos| name[pos + 1..].as_bstr().to_owned());
+    let (old, new) = (strip(&old)?, strip(&new)?);
+    (old == new).then(|| old)
+}
+
+fn unquote(value: &[u8]) -> BString {
+    if value.starts_with(b"\"") {
+        if let Ok((unquoted, _)) = git_quote::ansi_c::undo(value.as_bstr()) {
+            return unquoted.into_owned();
+        }
+    }
+    value.into()
+}
+
+fn trim_newline(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
@@ -0,0 +1,100 @@
+use std::borrow::Cow;
+
+use crate::{
+    apply::patch::{Hunk, Line},
+    bstr::ByteSlice,
+};
+
+/// The outcome of [`apply()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The content with all hunks applied that could be applied.
+    pub data: Vec<u8>,
+    /// The indices of the hunks that didn't apply.
+    pub rejected: Vec<usize>,
+}
+
+/// Apply `hunks` to `data` one after another without fuzz, and return the result along with the hunks that didn't apply.
+///
+/// Like `git apply`, each hunk is searched near the position it's expected at in case the lines before it changed, but hunks
+/// starting at the first line must match at the beginning of `data` and hunks without trailing context must match at its end.
+/// If `fix_whitespace` is true, trailing whitespace is removed from all added lines.
+pub fn apply(data: &[u8], hunks: &[Hunk], fix_whitespace: bool) -> Outcome {
+    let mut image: Vec<Cow<'_, [u8]>> = data.lines_with_terminator().map(Cow::Borrowed).collect();
+    let mut rejected = Vec::new();
+    let mut min_pos = 0;
+    for (hunk_idx, hunk) in hunks.iter().enumerate() {
+        let preimage: Vec<&[u8]> = hunk
+            .lines
+            .iter()
+            .filter_map(|line| match line {
+                Line::Context(line) | Line::Removed(line) => Some(line.as_slice()),
+                Line::Added(_) => None,
+            })
+            .collect();
+        let match_beginning = hunk.old_start <= 1;
+        let match_end = !matches!(hunk.lines.last(), Some(Line::Context(_)));
+        let fits = |pos: usize| {
+            pos >= min_pos
+                && (!match_beginning || pos == 0)
+                && (!match_end || pos + preimage.len() == image.len())
+                && image[pos..][..preimage.len()]
+                    .iter()
+                    .zip(&preimage)
+                    .all(|(actual, expected)| actual.as_ref() == *expected)
+        };
+        let pos = match image.len().checked_sub(preimage.len()) {
+            Some(max_pos) => {
+                let hint = hunk.new_start.saturating_sub(1).min(max_pos);
+                (0..=max_pos)
+                    .flat_map(|distance| std::iter::once(hint + distance).chain(hint.checked_sub(distance)))
+                    .find(|pos| *pos <= max_pos && fits(*pos))
+            }
+            None => None,
+        };
+        let pos = match pos {
+            Some(pos) => pos,
+            None => {
+                rejected.push(hunk_idx);
+                continue;
+            }
+        };
+
+        let postimage: Vec<_> = hunk
+            .lines
+            .iter()
+            .filter_map(|line| match line {
+                Line::Context(line) => Some(Cow::Borrowed(line.as_slice())),
+                Line::Added(line) if fix_whitespace && has_trailing_whitespace(line) => {
+                    Some(Cow::Owned(without_trailing_whitespace(line)))
+                }
+                Line::Added(line) => Some(Cow::Borrowed(line.as_slice())),
+                Line::Removed(_) => None,
+            })
+            .collect();
+        min_pos = pos + postimage.len();
+        image.splice(pos..pos + preimage.len(), postimage);
+    }
+    Outcome {
+        data: image.concat(),
+        rejected,
+    }
+}
+
+/// Return true if `line` ends in spaces or tabs, ignoring its line terminator.
+pub fn has_trailing_whitespace(line: &[u8]) -> bool {
+    matches!(content(line).last(), Some(b' ') | Some(b'\t'))
+}
+
+fn without_trailing_whitespace(line: &[u8]) -> Vec<u8> {
+    let content = content(line);
+    let mut fixed = content.trim_end_with(|c| c == ' ' || c == '\t').to_owned();
+    fixed.extend_from_slice(&line[content.len()..]);
+    fixed
+}
+
+/// Return `line` without its line terminator.
+fn content(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
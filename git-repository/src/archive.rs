@@ -0,0 +1,189 @@
+//! Write the contents of a tree as `tar`, `tar.gz` or `zip` archive, similar to `git archive`.
+//!
+//! The entries are obtained from a [worktree stream][crate::worktree::stream], which is affected by the `export-ignore`
+//! and `export-subst` attributes.
+use std::{
+    convert::TryFrom,
+    io::{Cursor, Write},
+};
+
+use git_hash::ObjectId;
+
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// The kind of archive to write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Format {
+    /// An uncompressed `tar` archive.
+    Tar,
+    /// A `tar` archive compressed with `gzip`.
+    TarGz,
+    /// A `zip` archive whose files are compressed with `deflate`.
+    Zip,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Format::Tar
+    }
+}
+
+/// Options for use in [`Repository::archive()`][crate::Repository::archive()].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The kind of archive to write.
+    pub format: Format,
+    /// A path to prepend to each entry in the archive, like `project/` to put all files into the `project` directory.
+    ///
+    /// Note that it's used as is, so it should end with a slash if it is supposed to be a directory.
+    pub prefix: Option<BString>,
+    /// The modification time of all entries in seconds since the unix epoch.
+    ///
+    /// If `None`, the commit time is used if a commit was provided, or the current time otherwise.
+    pub modification_time: Option<u32>,
+}
+
+/// The error returned by [`Repository::archive()`][crate::Repository::archive()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Stream(#[from] crate::worktree::stream::Error),
+    #[error("Could not write the archive")]
+    Io(#[from] std::io::Error),
+    #[error("Could not write the zip archive")]
+    Zip(#[from] zip::result::ZipError),
+}
+
+/// The information returned by [`Repository::archive()`][crate::Repository::archive()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// The id of the tree that was written.
+    pub tree_id: ObjectId,
+    /// The id of the commit the tree was taken from, if the archive was created from a commit.
+    pub commit_id: Option<ObjectId>,
+    /// The amount of files, links and directories written to the archive.
+    pub entries: usize,
+}
+
+/// The file modes to use for entries in the archive, following git with its default `tar.umask` of `0002`.
+pub(crate) mod mode {
+    pub const DIRECTORY: u32 = 0o775;
+    pub const FILE: u32 = 0o664;
+    pub const EXECUTABLE: u32 = 0o775;
+    pub const SYMLINK: u32 = 0o777;
+}
+
+/// The writer for all supported formats.
+pub(crate) enum Sink<W: Write> {
+    Tar(tar::Builder<W>),
+    TarGz(tar::Builder<flate2::write::GzEncoder<W>>),
+    Zip {
+        zip: zip::ZipWriter<Cursor<Vec<u8>>>,
+        out: W,
+    },
+}
+
+impl<W: Write> Sink<W> {
+    pub fn new(format: Format, out: W) -> Self {
+        match format {
+            Format::Tar => Sink::Tar(tar::Builder::new(out)),
+            Format::TarGz => Sink::TarGz(tar::Builder::new(flate2::write::GzEncoder::new(
+                out,
+                flate2::Compression::default(),
+            ))),
+            Format::Zip => Sink::Zip {
+                zip: zip::ZipWriter::new(Cursor::new(Vec::new())),
+                out,
+            },
+        }
+    }
+
+    pub fn add_directory(&mut self, path: &BStr, mtime: u32) -> Result<(), Error> {
+        match self {
+            Sink::Tar(tar) => append_tar(tar, tar::EntryType::Directory, path, mode::DIRECTORY, mtime, &[])?,
+            Sink::TarGz(tar) => append_tar(tar, tar::EntryType::Directory, path, mode::DIRECTORY, mtime, &[])?,
+            Sink::Zip { zip, .. } => zip.add_directory(path.to_str_lossy(), zip_options(mode::DIRECTORY, mtime))?,
+        }
+        Ok(())
+    }
+
+    pub fn add_file(&mut self, path: &BStr, mode: u32, mtime: u32, data: &[u8]) -> Result<(), Error> {
+        match self {
+            Sink::Tar(tar) => append_tar(tar, tar::EntryType::Regular, path, mode, mtime, data)?,
+            Sink::TarGz(tar) => append_tar(tar, tar::EntryType::Regular, path, mode, mtime, data)?,
+            Sink::Zip { zip, .. } => {
+                zip.start_file(path.to_str_lossy(), zip_options(mode, mtime))?;
+                zip.write_all(data)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn add_symlink(&mut self, path: &BStr, mtime: u32, target: &[u8]) -> Result<(), Error> {
+        match self {
+            Sink::Tar(tar) => append_tar(tar, tar::EntryType::Symlink, path, mode::SYMLINK, mtime, target)?,
+            Sink::TarGz(tar) => append_tar(tar, tar::EntryType::Symlink, path, mode::SYMLINK, mtime, target)?,
+            Sink::Zip { zip, .. } => zip.add_symlink(
+                path.to_str_lossy(),
+                target.to_str_lossy(),
+                zip_options(mode::SYMLINK, mtime),
+            )?,
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<(), Error> {
+        match self {
+            Sink::Tar(tar) => {
+                tar.into_inner()?.flush()?;
+            }
+            Sink::TarGz(tar) => {
+                tar.into_inner()?.finish()?.flush()?;
+            }
+            Sink::Zip { mut zip, mut out } => {
+                out.write_all(zip.finish()?.get_ref())?;
+                out.flush()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn append_tar<W: Write>(
+    tar: &mut tar::Builder<W>,
+    kind: tar::EntryType,
+    path: &BStr,
+    mode: u32,
+    mtime: u32,
+    data: &[u8],
+) -> std::io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(kind);
+    header.set_mode(mode);
+    header.set_mtime(mtime as u64);
+    let path = git_path::from_bstr(path);
+    match kind {
+        tar::EntryType::Symlink => {
+            header.set_size(0);
+            tar.append_link(&mut header, path, git_path::from_byte_slice(data))
+        }
+        _ => {
+            header.set_size(data.len() as u64);
+            tar.append_data(&mut header, path, data)
+        }
+    }
+}
+
+fn zip_options(mode: u32, mtime: u32) -> zip::write::FileOptions {
+    let options = zip::write::FileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .unix_permissions(mode);
+    match time::OffsetDateTime::from_unix_timestamp(mtime as i64)
+        .ok()
+        .and_then(|time| zip::DateTime::try_from(time).ok())
+    {
+        Some(time) => options.last_modified_time(time),
+        None => options,
+    }
+}
@@ -0,0 +1,121 @@
+use std::io::{BufReader, Read};
+
+use git_protocol::transport::{
+    client::{git, Transport},
+    Protocol,
+};
+
+use crate::{
+    bstr::BString,
+    bundle::{decode, Header},
+};
+
+/// The largest amount of pack data to put into a single side-band packet line, leaving room for its length and band.
+const MAX_DATA_PER_LINE: usize = 65520 - 5;
+
+/// Open the bundle file at `path` and return a transport which serves its references and pack like a remote using protocol V1
+/// would, along with the bundle header to allow verifying its prerequisites.
+///
+/// As the pack can't be adjusted to what the client has, it's sent in response to the first request, no matter what was negotiated.
+pub(crate) fn connect(path: &std::path::Path) -> Result<(Box<dyn Transport + Send>, Header), decode::Error> {
+    let mut read = BufReader::new(std::fs::File::open(path)?);
+    let header = Header::from_read(&mut read)?;
+    let response = std::io::Cursor::new(advertisement_and_acknowledgement(&header))
+        .chain(SidebandPack {
+            inner: read,
+            line: Vec::new(),
+            pos: 0,
+            done: false,
+        })
+        .chain(&b"0000"[..]);
+    let transport = git::Connection::new(
+        response,
+        std::io::sink(),
+        Protocol::V1,
+        git_path::into_bstr(path).into_owned(),
+        None::<(&str, _)>,
+        git::ConnectMode::Process,
+    );
+    Ok((Box::new(transport), header))
+}
+
+/// Produce the ref advertisement of a V1 server for the refs in `header`, followed by the `NAK` a server sends before the pack
+/// if nothing is in common.
+///
+/// Like `git`, we assume that `HEAD` points to the first branch with the same id as bundles don't store symbolic references.
+fn advertisement_and_acknowledgement(header: &Header) -> Vec<u8> {
+    // These are the capabilities our client needs, even though negotiation has no effect on what we send.
+    let mut capabilities = BString::from("multi_ack_detailed side-band-64k agent=git/oxide-bundle");
+    let head = header.refs.iter().find(|r| r.name.as_bstr() == "HEAD");
+    if let Some(branch) = head.and_then(|head| {
+        header
+            .refs
+            .iter()
+            .find(|r| r.id == head.id && r.name.as_bstr().starts_with(b"refs/heads/"))
+    }) {
+        capabilities.extend_from_slice(b" symref=HEAD:");
+        capabilities.extend_from_slice(branch.name.as_bstr());
+    }
+    if let Some((_, Some(format))) = header.capabilities.iter().find(|(key, _)| key == "object-format") {
+        capabilities.extend_from_slice(b" object-format=");
+        capabilities.extend_from_slice(format);
+    }
+
+    let mut out = Vec::new();
+    for (index, r) in head
+        .into_iter()
+        .chain(header.refs.iter().filter(|r| r.name.as_bstr() != "HEAD"))
+        .enumerate()
+    {
+        let mut line = BString::from(format!("{} ", r.id));
+        line.extend_from_slice(r.name.as_bstr());
+        if index == 0 {
+            line.push(0);
+            line.extend_from_slice(&capabilities);
+        }
+        line.push(b'\n');
+        write_line(&mut out, &line);
+    }
+    out.extend_from_slice(b"0000");
+    write_line(&mut out, b"NAK\n");
+    out
+}
+
+fn write_line(out: &mut Vec<u8>, data: &[u8]) {
+    out.extend_from_slice(format!("{:04x}", data.len() + 4).as_bytes());
+    out.extend_from_slice(data);
+}
+
+/// Put all data of `inner` into packet lines on the data band, as expected from a server supporting `side-band-64k`.
+struct SidebandPack<R> {
+    inner: R,
+    line: Vec<u8>,
+    pos: usize,
+    done: bool,
+}
+
+impl<R: Read> Read for SidebandPack<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pos == self.line.len() {
+            if self.done {
+                return Ok(0);
+            }
+            self.line.resize(5 + MAX_DATA_PER_LINE, 0);
+            let num_read = self.inner.read(&mut self.line[5..])?;
+            if num_read == 0 {
+                self.line.clear();
+                self.pos = 0;
+                self.done = true;
+                return Ok(0);
+            }
+            self.line.truncate(5 + num_read);
+            self.line[..4].copy_from_slice(format!("{:04x}", 5 + num_read).as_bytes());
+            self.line[4] = 1;
+            self.pos = 0;
+        }
+        let num_copied = buf.len().min(self.line.len() - self.pos);
+        buf[..num_copied].copy_from_slice(&self.line[self.pos..][..num_copied]);
+        self.pos += num_copied;
+        Ok(num_copied)
+    }
+}
@@ -0,0 +1,55 @@
+use git_hash::ObjectId;
+
+use crate::bundle::Header;
+
+/// The error returned by [`Repository::create_bundle()`][crate::Repository::create_bundle()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    FindCommit(#[from] git_odb::find::existing_iter::Error<git_odb::store::find::Error>),
+    #[error(transparent)]
+    PeelObject(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    TraverseExcluded(#[from] git_traverse::commit::ancestors::Error),
+    #[error("Refusing to create an empty bundle as all references are reachable from the excluded commits")]
+    Empty,
+    #[error(transparent)]
+    CountObjects(
+        #[from]
+        git_pack::data::output::count::objects::Error<
+            git_pack::find::existing::Error<git_odb::store::find::Error>,
+            std::convert::Infallible,
+        >,
+    ),
+    #[error(transparent)]
+    CreateEntries(#[from] git_pack::data::output::entry::iter_from_counts::Error<git_odb::store::find::Error>),
+    #[error(transparent)]
+    WritePack(
+        #[from]
+        git_pack::data::output::bytes::Error<
+            git_pack::data::output::entry::iter_from_counts::Error<git_odb::store::find::Error>,
+        >,
+    ),
+    #[error("Could not write the bundle header")]
+    Io(#[from] std::io::Error),
+    #[error("The operation was interrupted")]
+    Interrupted,
+}
+
+/// The outcome of [`Repository::create_bundle()`][crate::Repository::create_bundle()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The header that was written before the pack.
+    pub header: Header,
+    /// The amount of objects in the pack.
+    pub num_objects: u32,
+    /// The checksum at the end of the pack.
+    pub pack_checksum: ObjectId,
+}
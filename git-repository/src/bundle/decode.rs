@@ -0,0 +1,125 @@
+use std::{convert::TryFrom, io::BufRead};
+
+use git_hash::ObjectId;
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    bundle::{Header, Prerequisite, Ref, Version},
+};
+
+pub(crate) const SIGNATURE_V2: &[u8] = b"# v2 git bundle\n";
+pub(crate) const SIGNATURE_V3: &[u8] = b"# v3 git bundle\n";
+
+/// The error returned by [`Header::from_read()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read the bundle header")]
+    Io(#[from] std::io::Error),
+    #[error("The bundle signature {signature:?} isn't supported")]
+    UnsupportedSignature { signature: BString },
+    #[error("The bundle header ended before the empty line separating it from the pack")]
+    UnexpectedEof,
+    #[error("Capability {capability:?} isn't supported")]
+    UnsupportedCapability { capability: BString },
+    #[error("Capabilities are only allowed in V3 bundles, right after the signature, got {line:?}")]
+    UnexpectedCapability { line: BString },
+    #[error("Could not parse object id in line {line:?}")]
+    Id {
+        line: BString,
+        source: git_hash::decode::Error,
+    },
+    #[error("The reference line {line:?} doesn't have a name")]
+    MissingRefName { line: BString },
+    #[error(transparent)]
+    RefName(#[from] git_validate::refname::Error),
+}
+
+impl Header {
+    /// Read a bundle header from `read`, leaving it positioned at the beginning of the pack that follows it.
+    pub fn from_read(mut read: impl BufRead) -> Result<Self, Error> {
+        let mut buf = Vec::new();
+        let version = match read_line(&mut read, &mut buf)? {
+            Some(signature) if signature == SIGNATURE_V2.trim_end() => Version::V2,
+            Some(signature) if signature == SIGNATURE_V3.trim_end() => Version::V3,
+            Some(signature) => {
+                return Err(Error::UnsupportedSignature {
+                    signature: signature.into(),
+                })
+            }
+            None => return Err(Error::UnexpectedEof),
+        };
+
+        let mut header = Header {
+            version,
+            capabilities: Vec::new(),
+            prerequisites: Vec::new(),
+            refs: Vec::new(),
+        };
+        let mut may_have_capabilities = version == Version::V3;
+        loop {
+            let line = match read_line(&mut read, &mut buf)? {
+                Some(line) => line,
+                None => return Err(Error::UnexpectedEof),
+            };
+            if line.is_empty() {
+                break;
+            }
+            if let Some(capability) = line.strip_prefix(b"@") {
+                if !may_have_capabilities {
+                    return Err(Error::UnexpectedCapability { line: line.into() });
+                }
+                let (key, value) = match capability.find_byte(b'=') {
+                    Some(pos) => (&capability[..pos], Some(&capability[pos + 1..])),
+                    None => (capability, None),
+                };
+                match (key, value) {
+                    (b"object-format", Some(b"sha1")) | (b"filter", Some(_)) => {}
+                    _ => {
+                        return Err(Error::UnsupportedCapability {
+                            capability: capability.into(),
+                        })
+                    }
+                }
+                header.capabilities.push((key.into(), value.map(Into::into)));
+                continue;
+            }
+            may_have_capabilities = false;
+
+            let (hex, rest) = match line.find_byte(b' ') {
+                Some(pos) => (&line[..pos], Some(&line[pos + 1..])),
+                None => (line, None),
+            };
+            match hex.strip_prefix(b"-") {
+                Some(hex) => header.prerequisites.push(Prerequisite {
+                    id: parse_id(hex, line)?,
+                    comment: rest.unwrap_or_default().into(),
+                }),
+                None => header.refs.push(Ref {
+                    id: parse_id(hex, line)?,
+                    name: git_ref::FullName::try_from(
+                        rest.ok_or_else(|| Error::MissingRefName { line: line.into() })?
+                            .as_bstr(),
+                    )?,
+                }),
+            }
+        }
+        Ok(header)
+    }
+}
+
+fn parse_id(hex: &[u8], line: &[u8]) -> Result<ObjectId, Error> {
+    ObjectId::from_hex(hex).map_err(|err| Error::Id {
+        line: line.into(),
+        source: err,
+    })
+}
+
+/// Read a line without its trailing newline into `buf`, or return `None` on EOF.
+fn read_line<'a>(read: &mut impl BufRead, buf: &'a mut Vec<u8>) -> std::io::Result<Option<&'a [u8]>> {
+    buf.clear();
+    if read.read_until(b'\n', buf)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(buf.strip_suffix(b"\n").unwrap_or(buf)))
+}
@@ -0,0 +1,94 @@
+//! Read and write [bundle files](https://git-scm.com/docs/gitformat-bundle), which contain references along with a pack
+//! of the objects they need, to transfer history without a network connection.
+use git_hash::ObjectId;
+
+use crate::bstr::BString;
+
+///
+pub mod create;
+///
+pub mod decode;
+///
+pub mod unbundle;
+///
+pub mod verify;
+
+#[cfg(feature = "blocking-network-client")]
+mod connect;
+#[cfg(feature = "blocking-network-client")]
+pub(crate) use connect::connect;
+
+/// The version of the bundle format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Version {
+    /// The original format which can only hold objects using the SHA1 hash.
+    V2,
+    /// The format which may declare capabilities like `@object-format` and `@filter` before its prerequisites.
+    V3,
+}
+
+/// A commit that the receiver of a bundle must already have as the pack in the bundle doesn't contain it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Prerequisite {
+    /// The id of the commit.
+    pub id: ObjectId,
+    /// An optional comment to help humans, typically the commit's subject line. It may be empty.
+    pub comment: BString,
+}
+
+/// A reference whose objects are contained in a bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Ref {
+    /// The id the reference points to.
+    pub id: ObjectId,
+    /// The full name of the reference, like `refs/heads/main` or `HEAD`.
+    pub name: git_ref::FullName,
+}
+
+/// The header of a bundle file, which is followed by a pack containing the objects of all [refs][Header::refs].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Header {
+    /// The format version of the bundle.
+    pub version: Version,
+    /// All capabilities as `(key, value)` pairs, without the leading `@`. They are only written in [`Version::V3`].
+    pub capabilities: Vec<(BString, Option<BString>)>,
+    /// The commits the receiver needs to have for the pack to be complete.
+    pub prerequisites: Vec<Prerequisite>,
+    /// The references contained in the bundle.
+    pub refs: Vec<Ref>,
+}
+
+impl Header {
+    /// Write this header to `out` in the format identified by our [version][Header::version], ready to be followed by the pack.
+    pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+        match self.version {
+            Version::V2 => out.write_all(decode::SIGNATURE_V2)?,
+            Version::V3 => {
+                out.write_all(decode::SIGNATURE_V3)?;
+                for (key, value) in &self.capabilities {
+                    out.write_all(b"@")?;
+                    out.write_all(key)?;
+                    if let Some(value) = value {
+                        out.write_all(b"=")?;
+                        out.write_all(value)?;
+                    }
+                    out.write_all(b"\n")?;
+                }
+            }
+        }
+        for prerequisite in &self.prerequisites {
+            write!(out, "-{}", prerequisite.id)?;
+            if !prerequisite.comment.is_empty() {
+                out.write_all(b" ")?;
+                out.write_all(&prerequisite.comment)?;
+            }
+            out.write_all(b"\n")?;
+        }
+        for r in &self.refs {
+            write!(out, "{} ", r.id)?;
+            out.write_all(r.name.as_bstr())?;
+            out.write_all(b"\n")?;
+        }
+        out.write_all(b"\n")
+    }
+}
@@ -0,0 +1,26 @@
+use crate::bundle::Header;
+
+/// The error returned by [`Repository::unbundle()`][crate::Repository::unbundle()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Decode(#[from] crate::bundle::decode::Error),
+    #[error(transparent)]
+    Verify(#[from] crate::bundle::verify::Error),
+    #[error("Could not create the directory to write the pack into")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    WritePack(#[from] git_pack::bundle::write::Error),
+    #[error(transparent)]
+    ReadOnly(#[from] crate::read_only::Error),
+}
+
+/// The outcome of [`Repository::unbundle()`][crate::Repository::unbundle()].
+#[derive(Debug)]
+pub struct Outcome {
+    /// The header of the bundle, whose [refs][Header::refs] can be used to update references in the repository.
+    pub header: Header,
+    /// Information about the pack and index that were written into the object database.
+    pub write: git_pack::bundle::write::Outcome,
+}
@@ -0,0 +1,9 @@
+use git_hash::ObjectId;
+
+/// The error returned by [`Repository::verify_bundle()`][crate::Repository::verify_bundle()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The repository lacks {} prerequisite commit(s) of the bundle: {}", .ids.len(), .ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", "))]
+    MissingPrerequisites { ids: Vec<ObjectId> },
+}
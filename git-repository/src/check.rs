@@ -0,0 +1,233 @@
+//! Check the integrity and connectivity of a repository, similar to `git fsck`.
+use git_hash::ObjectId;
+
+use crate::bstr::BString;
+
+/// The error returned by [`Repository::check()`][crate::Repository::check()].
+///
+/// Note that problems with individual objects, references or the index are reported as [`Finding`] instead.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    IterateObjects(#[from] git_odb::store::load_index::Error),
+    #[error(transparent)]
+    IterateLooseObjects(#[from] git_odb::loose::iter::Error),
+    #[error(transparent)]
+    FindObject(#[from] git_odb::store::find::Error),
+    #[error(transparent)]
+    References(#[from] crate::reference::iter::Error),
+    #[error(transparent)]
+    IterateReferences(#[from] crate::reference::iter::init::Error),
+}
+
+/// How severe a [`Finding`] is, ordered from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    /// Informational only, like dangling objects which are expected to occur in healthy repositories.
+    Info,
+    /// Something is unusual or malformed, but git can still work with it.
+    Warning,
+    /// Data is missing or corrupt.
+    Error,
+}
+
+/// A problem found by [`Repository::check()`][crate::Repository::check()].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Finding {
+    /// The object stored under `id` hashes to `actual` instead.
+    HashMismatch {
+        /// The id under which the object is stored.
+        id: ObjectId,
+        /// The kind of the object.
+        kind: git_object::Kind,
+        /// The hash of the object's data.
+        actual: ObjectId,
+    },
+    /// The object `id` could not be read from the object database, for instance because it is corrupt.
+    Unreadable {
+        /// The id of the object.
+        id: ObjectId,
+        /// A description of the problem.
+        message: String,
+    },
+    /// The object `id` could not be decoded.
+    Unparsable {
+        /// The id of the object.
+        id: ObjectId,
+        /// The kind of the object.
+        kind: git_object::Kind,
+        /// A description of the problem.
+        message: String,
+    },
+    /// The object `id` could be decoded, but doesn't follow all rules of its format, like trees with unsorted entries
+    /// or commits with invalid identities.
+    Malformed {
+        /// The id of the object.
+        id: ObjectId,
+        /// The kind of the object.
+        kind: git_object::Kind,
+        /// A description of the problem.
+        message: String,
+    },
+    /// The object `id` is referenced by the object `referenced_by`, but doesn't exist.
+    MissingObject {
+        /// The id of the missing object.
+        id: ObjectId,
+        /// The object that refers to it.
+        referenced_by: ObjectId,
+    },
+    /// The reference `name` could not be read or parsed.
+    InvalidReference {
+        /// The name of the reference, if it could be determined.
+        name: Option<BString>,
+        /// A description of the problem.
+        message: String,
+    },
+    /// The reference `name` points to the object `id`, which doesn't exist.
+    MissingReferenceTarget {
+        /// The full name of the reference.
+        name: BString,
+        /// The id of the missing object.
+        id: ObjectId,
+    },
+    /// The symbolic reference `name` points to the reference `target`, which doesn't exist.
+    ///
+    /// This is expected for `HEAD` in repositories without commits.
+    DanglingSymbolicReference {
+        /// The full name of the symbolic reference.
+        name: BString,
+        /// The full name of the reference it points to.
+        target: BString,
+    },
+    /// The index failed to validate.
+    InvalidIndex {
+        /// A description of the problem.
+        message: String,
+    },
+    /// The index entry at `path` points to the object `id`, which doesn't exist.
+    MissingIndexObject {
+        /// The path of the entry relative to the worktree root.
+        path: BString,
+        /// The id of the missing object.
+        id: ObjectId,
+    },
+    /// The object `id` is unreachable from references and the index, and no other object refers to it.
+    Dangling {
+        /// The id of the object.
+        id: ObjectId,
+        /// The kind of the object.
+        kind: git_object::Kind,
+    },
+    /// The object `id` is unreachable from references and the index, but other unreachable objects refer to it.
+    ///
+    /// Only reported if [`Options::unreachable`] is set.
+    Unreachable {
+        /// The id of the object.
+        id: ObjectId,
+        /// The kind of the object.
+        kind: git_object::Kind,
+    },
+}
+
+impl Finding {
+    /// Return how severe this finding is.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Finding::HashMismatch { .. }
+            | Finding::Unreadable { .. }
+            | Finding::Unparsable { .. }
+            | Finding::MissingObject { .. }
+            | Finding::InvalidReference { .. }
+            | Finding::MissingReferenceTarget { .. }
+            | Finding::InvalidIndex { .. }
+            | Finding::MissingIndexObject { .. } => Severity::Error,
+            Finding::Malformed { .. } | Finding::DanglingSymbolicReference { .. } => Severity::Warning,
+            Finding::Dangling { .. } | Finding::Unreachable { .. } => Severity::Info,
+        }
+    }
+}
+
+impl std::fmt::Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Finding::HashMismatch { id, kind, actual } => {
+                write!(f, "hash mismatch: {} {} actually hashes to {}", kind, id, actual)
+            }
+            Finding::Unreadable { id, message } => write!(f, "unreadable object {}: {}", id, message),
+            Finding::Unparsable { id, kind, message } => write!(f, "unparsable {} {}: {}", kind, id, message),
+            Finding::Malformed { id, kind, message } => write!(f, "malformed {} {}: {}", kind, id, message),
+            Finding::MissingObject { id, referenced_by } => {
+                write!(f, "missing object {} referenced by {}", id, referenced_by)
+            }
+            Finding::InvalidReference { name, message } => match name {
+                Some(name) => write!(f, "invalid reference {}: {}", name, message),
+                None => write!(f, "invalid reference: {}", message),
+            },
+            Finding::MissingReferenceTarget { name, id } => write!(f, "{} points to missing object {}", name, id),
+            Finding::DanglingSymbolicReference { name, target } => {
+                write!(f, "{} points to non-existing reference {}", name, target)
+            }
+            Finding::InvalidIndex { message } => write!(f, "invalid index: {}", message),
+            Finding::MissingIndexObject { path, id } => {
+                write!(f, "index entry {} points to missing object {}", path, id)
+            }
+            Finding::Dangling { id, kind } => write!(f, "dangling {} {}", kind, id),
+            Finding::Unreachable { id, kind } => write!(f, "unreachable {} {}", kind, id),
+        }
+    }
+}
+
+/// Tell [`Repository::check()`][crate::Repository::check()] whether to keep looking for problems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Continue checking.
+    Continue,
+    /// Stop checking right away.
+    Cancel,
+}
+
+/// Configure [`Repository::check()`][crate::Repository::check()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If true, the hash of every object is recomputed and compared to the id it is stored under.
+    pub verify_hashes: bool,
+    /// If true, every object is validated beyond decoding, for instance to assure that tree entries are sorted and
+    /// that commits have valid identities.
+    pub strict: bool,
+    /// If true, check that all objects referenced by objects, references and the index exist, and report unreachable objects.
+    pub connectivity: bool,
+    /// If true, report all unreachable objects and not only dangling ones. Has no effect without `connectivity`.
+    pub unreachable: bool,
+    /// If true, validate the index of the worktree, if there is one, and use its entries to determine reachability.
+    pub index: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            verify_hashes: true,
+            strict: true,
+            connectivity: true,
+            unreachable: false,
+            index: true,
+        }
+    }
+}
+
+/// The outcome of [`Repository::check()`][crate::Repository::check()].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Outcome {
+    /// The amount of objects that were checked.
+    pub objects: usize,
+    /// The amount of references that were checked.
+    pub references: usize,
+    /// The amount of findings with [`Severity::Error`].
+    pub errors: usize,
+    /// The amount of findings with [`Severity::Warning`].
+    pub warnings: usize,
+    /// The amount of findings with [`Severity::Info`].
+    pub infos: usize,
+    /// True if the check was cancelled by returning [`Action::Cancel`] before it was complete.
+    pub cancelled: bool,
+}
@@ -0,0 +1,308 @@
+use std::{
+    convert::{TryFrom, TryInto},
+    num::NonZeroU32,
+    path::PathBuf,
+    sync::atomic::AtomicBool,
+};
+
+use git_features::progress::Progress;
+use git_protocol::fetch::Ref;
+use git_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, Target,
+};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    create::key,
+    remote,
+    remote::fetch::update_refs,
+    Repository,
+};
+
+mod error {
+    /// The error returned by [`PrepareClone::new()`][super::PrepareClone::new()] and
+    /// [`PrepareClone::fetch_then_checkout()`][super::PrepareClone::fetch_then_checkout()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Url(#[from] git_url::parse::Error),
+        #[error(transparent)]
+        Init(#[from] crate::create::Error),
+        #[error(transparent)]
+        Open(#[from] crate::open::Error),
+        #[error(transparent)]
+        FindRemote(#[from] crate::remote::find::existing::Error),
+        #[error(transparent)]
+        Connect(#[from] crate::remote::connect::Error),
+        #[error(transparent)]
+        Fetch(#[from] crate::remote::fetch::Error),
+        #[error("The remote HEAD points to an invalid reference name")]
+        InvalidHeadName(#[from] git_validate::refname::Error),
+        #[error(transparent)]
+        EditReferences(#[from] crate::reference::edit::Error),
+        #[error("Could not read the configuration of the new repository to add the upstream branch")]
+        ReadConfig(#[from] git_config::file::init::from_paths::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error("Could not checkout the files of HEAD")]
+        Checkout(#[from] crate::worktree::checkout::Error),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+pub use error::Error;
+
+/// A utility to create a new repository by fetching everything from a remote, similar to `git clone`.
+///
+/// Create it with [`new()`][Self::new()] or [`crate::prepare_clone()`], configure it with the builder methods and run it
+/// with [`fetch_then_checkout()`][Self::fetch_then_checkout()].
+#[derive(Debug, Clone)]
+pub struct PrepareClone {
+    url: git_url::Url,
+    path: PathBuf,
+    bare: bool,
+    depth: Option<NonZeroU32>,
+    remote_name: String,
+}
+
+/// The outcome of [`PrepareClone::fetch_then_checkout()`].
+pub struct Outcome {
+    /// All references advertised by the remote.
+    pub remote_refs: Vec<Ref>,
+    /// Information about the received pack, or `None` if the remote had nothing to fetch.
+    pub pack: Option<git_pack::bundle::write::Outcome>,
+    /// The local references that were created from the references of the remote.
+    pub update_refs: update_refs::Outcome,
+    /// The name of the branch our `HEAD` points to, or `None` if it is detached.
+    pub head_branch: Option<FullName>,
+    /// Information about the checkout, or `None` if the clone is bare or if there was nothing to checkout.
+    pub checkout: Option<git_worktree::index::checkout::Outcome>,
+}
+
+/// Initialization and builder methods
+impl PrepareClone {
+    /// Prepare cloning the repository at `url` into `path`, which must not exist or be an empty directory.
+    ///
+    /// Nothing is done until [`fetch_then_checkout()`][Self::fetch_then_checkout()] is called.
+    pub fn new<Url, E>(url: Url, path: impl Into<PathBuf>) -> Result<Self, Error>
+    where
+        Url: TryInto<git_url::Url, Error = E>,
+        git_url::parse::Error: From<E>,
+    {
+        Ok(PrepareClone {
+            url: url.try_into().map_err(git_url::parse::Error::from)?,
+            path: path.into(),
+            bare: false,
+            depth: None,
+            remote_name: "origin".into(),
+        })
+    }
+
+    /// If `true`, create a bare repository without worktree whose branches mirror the branches of the remote, similar to
+    /// `git clone --bare`.
+    pub fn bare(mut self, bare: bool) -> Self {
+        self.bare = bare;
+        self
+    }
+
+    /// If set, only fetch the last `depth` commits of each branch to create a shallow clone, similar to `git clone --depth`.
+    ///
+    /// Note that tags aren't fetched in shallow clones as they would typically deepen the history.
+    pub fn with_depth(mut self, depth: impl Into<Option<NonZeroU32>>) -> Self {
+        self.depth = depth.into();
+        self
+    }
+
+    /// Use `name` for the remote to clone from instead of `origin`, similar to `git clone --origin`.
+    pub fn with_remote_name(mut self, name: impl Into<String>) -> Self {
+        self.remote_name = name.into();
+        self
+    }
+}
+
+/// Cloning
+impl PrepareClone {
+    /// Create the repository with the remote configured along with its fetch ref-specs, fetch all branches and tags
+    /// into it and create the local references for them, point `HEAD` to the branch the remote `HEAD` points to and
+    /// checkout its files unless the clone is bare.
+    ///
+    /// `progress` is used to report on the fetch and the checkout, and `should_interrupt` is checked while receiving the pack
+    /// and checking out files. Note that the repository remains on disk if an error occurs.
+    pub fn fetch_then_checkout(
+        self,
+        mut progress: impl Progress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(Repository, Outcome), Error> {
+        let PrepareClone {
+            url,
+            path,
+            bare,
+            depth,
+            remote_name,
+        } = self;
+        let url_string = url.to_bstring();
+        if bare {
+            // bare repositories are created in existing directories only.
+            std::fs::create_dir_all(&path)?;
+        }
+        let path = crate::create::into_with_config(
+            path,
+            crate::create::Options {
+                bare,
+                fs_capabilities: None,
+                // The object format of the remote is adopted once it's known while fetching.
+                object_hash: git_hash::Kind::Sha1,
+            },
+            |config| {
+                let mut remote = config
+                    .new_section("remote", Some(remote_name.clone().into()))
+                    .expect("valid section name");
+                remote.push(key("url"), Some(url_string.as_ref()));
+                if !bare {
+                    let spec = format!("+refs/heads/*:refs/remotes/{}/*", remote_name);
+                    remote.push(key("fetch"), Some(spec.as_str().into()));
+                }
+            },
+        )?;
+        let repo = open(path.clone())?;
+
+        let remote::fetch::Outcome {
+            remote_refs,
+            pack,
+            update_refs,
+            ..
+        } = {
+            let mut remote = repo.find_remote(&remote_name)?;
+            if bare {
+                remote = remote
+                    .with_refspec("+refs/heads/*:refs/heads/*", remote::Direction::Fetch)
+                    .expect("valid static ref-spec");
+            }
+            remote
+                .connect(remote::Direction::Fetch, progress.add_child("fetch"))?
+                .fetch_inner(
+                    should_interrupt,
+                    remote::fetch::Options {
+                        depth,
+                        ..Default::default()
+                    },
+                    true,
+                )?
+        };
+
+        let repo = match remote_refs.first() {
+            Some(r) if r.unpack().1.kind() != repo.object_hash() => open(path.clone())?,
+            _ => repo,
+        };
+
+        let reflog_message: BString = format!("clone: from {}", url_string).into();
+        let (head_branch, head_id) = set_head(&repo, &remote_refs, bare, reflog_message.as_ref())?;
+        let repo = match head_branch.as_ref() {
+            Some(branch) if !bare => {
+                let config_path = repo.git_dir().join("config");
+                let mut config =
+                    git_config::File::from_path_no_includes(config_path.clone(), git_config::Source::Local)?;
+                let mut section = config
+                    .new_section("branch", Some(branch.shorten().to_str_lossy().into_owned().into()))
+                    .expect("valid section name");
+                section.push(key("remote"), Some(remote_name.as_str().into()));
+                section.push(key("merge"), Some(branch.as_bstr()));
+                std::fs::write(config_path, config.to_bstring())?;
+                open(path)?
+            }
+            _ => repo,
+        };
+
+        let checkout = match head_id {
+            Some(head_id) if !bare => Some(repo.checkout_tree(
+                head_id,
+                progress,
+                should_interrupt,
+                crate::worktree::checkout::Options {
+                    destination_is_initially_empty: true,
+                    ..Default::default()
+                },
+            )?),
+            _ => None,
+        };
+
+        Ok((
+            repo,
+            Outcome {
+                remote_refs,
+                pack,
+                update_refs,
+                head_branch,
+                checkout,
+            },
+        ))
+    }
+}
+
+fn open(path: git_discover::repository::Path) -> Result<Repository, crate::open::Error> {
+    use git_sec::trust::DefaultForLevel;
+    let (git_dir, worktree_dir) = path.into_repository_and_work_tree_directories();
+    let mut options = crate::open::Options::default_for_level(git_sec::Trust::Full);
+    options.git_dir_trust = Some(git_sec::Trust::Full);
+    crate::ThreadSafeRepository::open_from_paths(git_dir, worktree_dir, options).map(Into::into)
+}
+
+/// Point `HEAD` to the branch the remote `HEAD` points to, creating it unless `bare` as a clone mirrors all branches then,
+/// or detach it if the remote `HEAD` is detached.
+/// Return the name of the branch and the commit `HEAD` points to, or `None` if the remote didn't advertise its `HEAD`.
+fn set_head(
+    repo: &Repository,
+    remote_refs: &[Ref],
+    bare: bool,
+    reflog_message: &BStr,
+) -> Result<(Option<FullName>, Option<git_hash::ObjectId>), Error> {
+    let log = LogChange {
+        mode: RefLog::AndReference,
+        force_create_reflog: false,
+        message: reflog_message.to_owned(),
+    };
+    let head: FullName = "HEAD".try_into().expect("valid name");
+    let (branch, id) = match remote_refs.iter().find(|r| r.unpack().0 == "HEAD") {
+        Some(Ref::Symbolic { target, object, .. }) => (Some(FullName::try_from(target.clone())?), *object),
+        Some(Ref::Direct { object, .. }) => (None, *object),
+        Some(Ref::Peeled { .. }) | None => return Ok((None, None)),
+    };
+    let mut edits = Vec::new();
+    match branch.as_ref() {
+        Some(branch) => {
+            if !bare {
+                edits.push(RefEdit {
+                    change: Change::Update {
+                        log: log.clone(),
+                        expected: PreviousValue::MustNotExist,
+                        new: Target::Peeled(id),
+                    },
+                    name: branch.clone(),
+                    deref: false,
+                });
+            }
+            edits.push(RefEdit {
+                change: Change::Update {
+                    log,
+                    expected: PreviousValue::Any,
+                    new: Target::Symbolic(branch.clone()),
+                },
+                name: head,
+                deref: false,
+            });
+        }
+        None => edits.push(RefEdit {
+            change: Change::Update {
+                log,
+                expected: PreviousValue::Any,
+                new: Target::Peeled(id),
+            },
+            name: head,
+            deref: false,
+        }),
+    }
+    repo.edit_references(edits, git_lock::acquire::Fail::Immediately, repo.committer_or_default())?;
+    Ok((branch, Some(id)))
+}
@@ -3,7 +3,7 @@
 /// An empty array of a type usable with the `git::easy` API to help declaring no parents should be used
 pub const NO_PARENT_IDS: [git_hash::ObjectId; 0] = [];
 
-/// The error returned by [`commit(…)`][crate::Repository::commit()].
+/// The error returned by [`commit(…)`][crate::Repository::commit()] and [`commit_as(…)`][crate::Repository::commit_as()].
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
 pub enum Error {
@@ -13,6 +13,8 @@ pub enum Error {
     WriteObject(#[from] crate::object::write::Error),
     #[error(transparent)]
     ReferenceEdit(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    Identity(#[from] crate::identity::Error),
 }
 
 ///
@@ -217,5 +219,51 @@ pub mod describe {
             self.id_as_fallback = true;
             Ok(self.try_format()?.expect("BUG: fallback must always produce a format"))
         }
+
+        /// Turn this platform into a [`Cache`] to describe many commits with the same configuration, like all commits
+        /// of a log view, much faster than describing each one of them on its own.
+        ///
+        /// Note that the commit this platform was created for isn't described unless it's passed to the cache.
+        pub fn into_cache(self) -> Result<Cache<'repo>, Error> {
+            Ok(Cache {
+                inner: git_revision::describe::Cache::new(git_revision::describe::Options {
+                    name_by_oid: self.select.names(self.repo)?,
+                    fallback_to_oid: self.id_as_fallback,
+                    first_parent: self.first_parent,
+                    max_candidates: self.max_candidates,
+                }),
+                repo: self.repo,
+            })
+        }
+    }
+
+    /// A cache to describe many commits, created with [`Platform::into_cache()`].
+    ///
+    /// It obtains the names to choose from only once, and shares graph traversals between the commits to describe.
+    pub struct Cache<'repo> {
+        repo: &'repo Repository,
+        inner: git_revision::describe::Cache<'static>,
+    }
+
+    impl<'repo> Cache<'repo> {
+        /// Try to find a name for the commit with `id`, returning `Some(describe::Format)` if one was found.
+        ///
+        /// Note that there will always be `Some(format)` if the platform was configured to use the id as fallback.
+        pub fn try_format(
+            &mut self,
+            id: impl Into<ObjectId>,
+        ) -> Result<Option<git_revision::describe::Format<'static>>, Error> {
+            let id = id.into();
+            let repo = self.repo;
+            self.inner
+                .describe(&id, |id, buf| {
+                    Ok(repo.objects.try_find(id, buf)?.and_then(|d| d.try_into_commit_iter()))
+                })?
+                .map(|described| -> Result<_, Error> {
+                    let prefix = id.attach(repo).shorten()?;
+                    Ok(described.into_format(prefix.hex_len()))
+                })
+                .transpose()
+        }
     }
 }
@@ -3,7 +3,12 @@ use std::{convert::TryFrom, path::PathBuf};
 use git_config::{Boolean, Integer};
 
 use super::{Cache, Error};
-use crate::{bstr::ByteSlice, remote, repository, repository::identity, revision::spec::parse::ObjectKindHint};
+use crate::{
+    bstr::{BString, ByteSlice},
+    remote, repository,
+    repository::identity,
+    revision::spec::parse::ObjectKindHint,
+};
 
 /// A utility to deal with the cyclic dependency between the ref store and the configuration. The ref-store needs the
 /// object hash kind, and the configuration needs the current branch name to resolve conditional includes with `onbranch`.
@@ -51,17 +56,26 @@ impl StageOne {
             .then(|| Ok(git_hash::Kind::Sha1))
             .or_else(|| {
                 config.string("extensions", None, "objectFormat").map(|format| {
-                    if format.as_ref().eq_ignore_ascii_case(b"sha1") {
-                        Ok(git_hash::Kind::Sha1)
-                    } else {
-                        Err(Error::UnsupportedObjectFormat {
+                    // Only the object formats supported by `git-hash` parse, which depends on its `sha256` feature.
+                    std::str::from_utf8(&format.to_ascii_lowercase())
+                        .ok()
+                        .and_then(|format| format.parse::<git_hash::Kind>().ok())
+                        .ok_or_else(|| Error::UnsupportedObjectFormat {
                             name: format.to_vec().into(),
                         })
-                    }
                 })
             })
             .transpose()?
             .unwrap_or(git_hash::Kind::Sha1);
+        // Objects we write wouldn't be added to the mapping between both formats, breaking it for `git`.
+        if let Some(format) = (repo_format_version == 1)
+            .then(|| config.string("extensions", None, "compatObjectFormat"))
+            .flatten()
+        {
+            return Err(Error::UnsupportedCompatObjectFormat {
+                name: format.into_owned(),
+            });
+        }
 
         let reflog = query_refupdates(&config);
         Ok(StageOne {
@@ -105,6 +119,7 @@ impl Cache {
             includes: use_includes,
         }: repository::permissions::Config,
         lenient_config: bool,
+        config_overrides: &[BString],
     ) -> Result<Self, Error> {
         let options = git_config::file::init::Options {
             includes: if use_includes {
@@ -152,6 +167,7 @@ impl Cache {
                         source: *source,
                         level: 0,
                         trust: git_sec::Trust::Full,
+                        include_chain: Vec::new(),
                     }
                     .into()
                 });
@@ -177,8 +193,16 @@ impl Cache {
             if use_env {
                 globals.append(git_config::File::from_env(options)?.unwrap_or_default());
             }
+            if !config_overrides.is_empty() {
+                globals.append(super::apply_cli_overrides::to_file(config_overrides.iter().cloned())?);
+            }
             globals
         };
+        let is_bare = if config_overrides.is_empty() {
+            is_bare
+        } else {
+            config_bool(&config, "core.bare", is_bare, lenient_config)?
+        };
 
         let excludes_file = match config
             .path_filter("core", None, "excludesFile", &mut filter_config_section)
@@ -197,7 +221,17 @@ impl Cache {
         };
 
         let reflog = query_refupdates(&config);
+        let fsync = parse_fsync(&config);
         let ignore_case = config_bool(&config, "core.ignoreCase", false, lenient_config)?;
+        let path_protections = git_validate::path::component::Options {
+            protect_ntfs: config_bool(&config, "core.protectNTFS", true, lenient_config)?,
+            protect_hfs: config_bool(
+                &config,
+                "core.protectHFS",
+                cfg!(target_vendor = "apple"),
+                lenient_config,
+            )?,
+        };
         let use_multi_pack_index = config_bool(&config, "core.multiPackIndex", true, lenient_config)?;
         let object_kind_hint = config.string("core", None, "disambiguate").and_then(|value| {
             Some(match value.as_ref().as_ref() {
@@ -215,8 +249,10 @@ impl Cache {
             object_hash,
             object_kind_hint,
             reflog,
+            fsync,
             is_bare,
             ignore_case,
+            path_protections,
             hex_len,
             filter_config_section,
             excludes_file,
@@ -264,7 +300,7 @@ impl std::fmt::Debug for Cache {
 impl Cache {
     pub(crate) fn personas(&self) -> &identity::Personas {
         self.personas
-            .get_or_init(|| identity::Personas::from_config_and_env(&self.resolved, &self.git_prefix))
+            .get_or_init(|| identity::Personas::from_config_and_env(&self.resolved, &self.git_prefix).into())
     }
 
     pub(crate) fn url_rewrite(&self) -> &remote::url::Rewrite {
@@ -319,6 +355,66 @@ fn query_refupdates(config: &git_config::File<'static>) -> Option<git_ref::store
     })
 }
 
+/// Parse `core.fsync` and `core.fsyncMethod` like git does, starting out with flushing packs only and ignoring unknown
+/// components and methods.
+fn parse_fsync(config: &git_config::File<'static>) -> git_features::fs::fsync::Policy {
+    use git_features::fs::fsync::{Method, Policy};
+    let mut policy = Policy {
+        packs: true,
+        ..Policy::none()
+    };
+    if config
+        .boolean("core", None, "fsyncObjectFiles")
+        .and_then(Result::ok)
+        .unwrap_or(false)
+    {
+        policy.loose_objects = true;
+    }
+    if let Some(components) = config.string("core", None, "fsync") {
+        let (mut add, mut remove) = (Policy::none(), Policy::none());
+        for component in components.split(|b| *b == b',').map(|c| c.trim()) {
+            let (target, component) = match component.strip_prefix(b"-") {
+                Some(component) => (&mut remove, component),
+                None => (&mut add, component),
+            };
+            let (loose_objects, packs, refs, index) = match component {
+                b"none" => {
+                    policy = Policy::none();
+                    continue;
+                }
+                b"loose-object" => (true, false, false, false),
+                b"pack" | b"pack-metadata" | b"derived-metadata" => (false, true, false, false),
+                b"objects" => (true, true, false, false),
+                b"reference" => (false, false, true, false),
+                b"committed" => (true, true, true, false),
+                b"index" => (false, false, false, true),
+                b"added" | b"all" => (true, true, true, true),
+                _ => continue,
+            };
+            target.loose_objects |= loose_objects;
+            target.packs |= packs;
+            target.refs |= refs;
+            target.index |= index;
+        }
+        policy = Policy {
+            loose_objects: (policy.loose_objects && !remove.loose_objects) || add.loose_objects,
+            packs: (policy.packs && !remove.packs) || add.packs,
+            refs: (policy.refs && !remove.refs) || add.refs,
+            index: (policy.index && !remove.index) || add.index,
+            method: policy.method,
+        };
+    }
+    policy.method = match config
+        .string("core", None, "fsyncMethod")
+        .as_deref()
+        .map(|v| v.as_ref())
+    {
+        Some(b"writeout-only") => Method::WriteoutOnly,
+        _ => Method::Fsync,
+    };
+    policy
+}
+
 fn parse_core_abbrev(config: &git_config::File<'static>, object_hash: git_hash::Kind) -> Result<Option<usize>, Error> {
     match config.string("core", None, "abbrev") {
         Some(hex_len_str) => {
@@ -44,6 +44,8 @@ pub enum Error {
     FromEnv(#[from] git_config::file::init::from_env::Error),
     #[error("Cannot handle objects formatted as {:?}", .name)]
     UnsupportedObjectFormat { name: BString },
+    #[error("Cannot maintain the mapping to objects formatted as {:?} as configured in 'extensions.compatObjectFormat'", .name)]
+    UnsupportedCompatObjectFormat { name: BString },
     #[error("The value for '{}' cannot be empty", .key)]
     EmptyValue { key: &'static str },
     #[error("Invalid value for 'core.abbrev' = '{}'. It must be between 4 and {}", .value, .max)]
@@ -52,6 +54,8 @@ pub enum Error {
     DecodeBoolean { key: String, value: BString },
     #[error(transparent)]
     PathInterpolation(#[from] git_config::path::interpolate::Error),
+    #[error(transparent)]
+    ConfigOverrides(#[from] apply_cli_overrides::Error),
 }
 
 /// Utility type to keep pre-obtained configuration values.
@@ -68,8 +72,12 @@ pub(crate) struct Cache {
     pub use_multi_pack_index: bool,
     /// The representation of `core.logallrefupdates`, or `None` if the variable wasn't set.
     pub reflog: Option<git_ref::store::WriteReflog>,
+    /// Which files to flush to permanent storage after writing them, as configured by `core.fsync` and `core.fsyncMethod`.
+    pub fsync: git_features::fs::fsync::Policy,
     /// identities for later use, lazy initialization.
-    pub personas: OnceCell<identity::Personas>,
+    ///
+    /// Boxed as these are large due to the provenance of each value, but rarely needed.
+    pub personas: OnceCell<Box<identity::Personas>>,
     /// A lazily loaded rewrite list for remote urls
     pub url_rewrite: OnceCell<remote::url::Rewrite>,
     /// The config section filter from the options used to initialize this instance. Keep these in sync!
@@ -78,6 +86,9 @@ pub(crate) struct Cache {
     pub object_kind_hint: Option<spec::parse::ObjectKindHint>,
     /// If true, we are on a case-insensitive file system.
     pub ignore_case: bool,
+    /// Protections against paths that alias the `.git` directory on NTFS and HFS+, as configured by `core.protectNTFS`
+    /// and `core.protectHFS`.
+    pub path_protections: git_validate::path::component::Options,
     /// The path to the user-level excludes file to ignore certain files in the worktree.
     pub excludes_file: Option<std::path::PathBuf>,
     /// Define how we can use values obtained with `xdg_config(…)` and its `XDG_CONFIG_HOME` variable.
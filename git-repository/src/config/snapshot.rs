@@ -81,6 +81,198 @@ impl<'repo> Snapshot<'repo> {
     }
 }
 
+/// Credentials
+#[cfg(feature = "git-credentials")]
+impl<'repo> Snapshot<'repo> {
+    /// Return the credential helpers to use when obtaining credentials for `url`, which can be [invoked][git_credentials::helper::Cascade::invoke()]
+    /// to fill, store and erase credentials.
+    ///
+    /// They are configured by all `credential.helper` values in order of occurrence, taking into account those in `credential.<url>` sections
+    /// whose url matches `url`. An empty value resets the list of helpers. `credential.useHttpPath` and `credential.username` are respected as well.
+    /// If no helper provides a credential, the user is asked using `$GIT_ASKPASS`, `core.askPass` or `$SSH_ASKPASS`, or on the
+    /// terminal unless `$GIT_TERMINAL_PROMPT` is `0`.
+    pub fn credential_helpers(&self, url: &git_url::Url) -> git_credentials::helper::Cascade {
+        use std::convert::TryFrom;
+
+        use crate::bstr::ByteSlice;
+
+        let filter = self.repo.filter_config_section();
+        let mut cascade = git_credentials::helper::Cascade::default();
+        for section in self.repo.config.resolved.sections().filter(|section| {
+            section.header().name().eq_ignore_ascii_case(b"credential")
+                && filter(section.meta())
+                && section
+                    .header()
+                    .subsection_name()
+                    .map(|pattern| url_matches(pattern, url))
+                    .unwrap_or(true)
+        }) {
+            for helper in section.values("helper") {
+                if helper.trim().is_empty() {
+                    cascade.programs.clear();
+                } else {
+                    cascade
+                        .programs
+                        .push(git_credentials::Program::from_custom_definition(helper.into_owned()));
+                }
+            }
+            if let Some(value) = section.value("useHttpPath") {
+                cascade.use_http_path = git_config::Boolean::try_from(value).map(|b| b.0).unwrap_or(false);
+            }
+            if let Some(username) = section.value("username") {
+                cascade.username = Some(username.to_string());
+            }
+        }
+
+        cascade.prompt.askpass = std::env::var_os("GIT_ASKPASS")
+            .map(std::path::PathBuf::from)
+            .or_else(|| {
+                self.trusted_path("core.askPass")
+                    .and_then(Result::ok)
+                    .map(Cow::into_owned)
+            })
+            .or_else(|| std::env::var_os("SSH_ASKPASS").map(std::path::PathBuf::from));
+        cascade.prompt.terminal = std::env::var_os("GIT_TERMINAL_PROMPT")
+            .map(|value| value != "0" && value != "false")
+            .unwrap_or(true);
+        cascade
+    }
+}
+
+/// HTTP
+#[cfg(feature = "blocking-http-transport")]
+impl<'repo> Snapshot<'repo> {
+    /// Return the options to configure the HTTP transport with when connecting to `url`, which are passed to
+    /// [`configure()`][git_transport::client::TransportWithoutIO::configure()] by [`Remote::connect()`][crate::Remote::connect()].
+    ///
+    /// They are configured by `http.proxy`, `http.extraHeader`, `http.sslCAInfo`, `http.sslVerify`, `http.lowSpeedLimit`,
+    /// `http.lowSpeedTime`, `http.cookieFile`, `http.saveCookies` and `http.followRedirects`, which may also be set in `http.<url>` sections whose url matches `url`.
+    /// For each key, the value in the section with the longest matching url wins, and all `http.extraHeader` values are used, with an empty one
+    /// resetting the list.
+    pub fn http_options(&self, url: &git_url::Url) -> git_transport::client::http::Options {
+        use std::convert::TryFrom;
+
+        let filter = self.repo.filter_config_section();
+        let install_dir = self.repo.install_dir().ok();
+        let home = self.repo.config.home_dir();
+        let path = |value: Cow<'_, BStr>| {
+            git_config::Path::from(value)
+                .interpolate(interpolate_context(install_dir.as_deref(), home.as_deref()))
+                .ok()
+                .map(Cow::into_owned)
+        };
+        let integer = |value: Cow<'_, BStr>| {
+            git_config::Integer::try_from(value)
+                .ok()
+                .and_then(|value| value.to_decimal())
+                .and_then(|value| u32::try_from(value).ok())
+        };
+
+        let mut options = git_transport::client::http::Options::default();
+        let mut most_specific = std::collections::BTreeMap::new();
+        for (section, specificity) in self.repo.config.resolved.sections().filter_map(|section| {
+            if !section.header().name().eq_ignore_ascii_case(b"http") || !filter(section.meta()) {
+                return None;
+            }
+            let specificity = match section.header().subsection_name() {
+                Some(pattern) if url_matches(pattern, url) => Some(
+                    git_url::parse(pattern)
+                        .map(|pattern| pattern.path.len())
+                        .unwrap_or_default(),
+                ),
+                Some(_) => return None,
+                None => None,
+            };
+            Some((section, specificity))
+        }) {
+            for header in section.values("extraHeader") {
+                if header.is_empty() {
+                    options.extra_headers.clear();
+                } else {
+                    options.extra_headers.push(header.to_string());
+                }
+            }
+            let mut value = |key: &'static str| {
+                let value = section.value(key)?;
+                match most_specific.get(key) {
+                    Some(most_specific) if specificity < *most_specific => None,
+                    _ => {
+                        most_specific.insert(key, specificity);
+                        Some(value)
+                    }
+                }
+            };
+
+            if let Some(proxy) = value("proxy") {
+                options.proxy = Some(proxy.to_string());
+            }
+            if let Some(ca_info) = value("sslCAInfo") {
+                options.ssl_ca_info = path(ca_info);
+            }
+            if let Some(verify) = value("sslVerify") {
+                options.ssl_verify = git_config::Boolean::try_from(verify).map(|b| b.0).unwrap_or(true);
+            }
+            if let Some(limit) = value("lowSpeedLimit").and_then(integer) {
+                options.low_speed_limit_bytes_per_second = limit;
+            }
+            if let Some(seconds) = value("lowSpeedTime").and_then(integer) {
+                options.low_speed_time = std::time::Duration::from_secs(seconds.into());
+            }
+            if let Some(cookie_file) = value("cookieFile") {
+                options.cookie_file = path(cookie_file);
+            }
+            if let Some(save) = value("saveCookies") {
+                options.save_cookies = git_config::Boolean::try_from(save).map(|b| b.0).unwrap_or(false);
+            }
+            if let Some(follow) = value("followRedirects") {
+                use git_transport::client::http::FollowRedirects;
+                options.follow_redirects = if follow.eq_ignore_ascii_case(b"initial") {
+                    FollowRedirects::Initial
+                } else {
+                    match git_config::Boolean::try_from(follow).map(|b| b.0) {
+                        Ok(true) => FollowRedirects::All,
+                        Ok(false) => FollowRedirects::None,
+                        Err(_) => FollowRedirects::default(),
+                    }
+                };
+            }
+        }
+        options
+    }
+}
+
+/// Return `true` if `pattern`, the subsection name of a `credential` or `http` section, applies to `url` according to the rules of
+/// [git-config](https://git-scm.com/docs/git-config#Documentation/git-config.txt-credentialltulgt).
+#[cfg(any(feature = "git-credentials", feature = "blocking-http-transport"))]
+fn url_matches(pattern: &BStr, url: &git_url::Url) -> bool {
+    let pattern = match git_url::parse(pattern) {
+        Ok(pattern) => pattern,
+        Err(_) => return false,
+    };
+    let host_matches = match (pattern.host(), url.host()) {
+        (Some(pattern), Some(host)) => {
+            let (pattern, host) = (pattern.split('.'), host.split('.'));
+            pattern.clone().count() == host.clone().count()
+                && pattern
+                    .zip(host)
+                    .all(|(pattern, host)| pattern == "*" || pattern.eq_ignore_ascii_case(host))
+        }
+        (None, None) => true,
+        _ => false,
+    };
+    let path_prefix = pattern.path.strip_suffix(b"/").unwrap_or(&pattern.path);
+    pattern.scheme == url.scheme
+        && host_matches
+        && pattern.port == url.port
+        && (pattern.user().is_none() || pattern.user() == url.user())
+        && (path_prefix.is_empty()
+            || url
+                .path
+                .strip_prefix(path_prefix)
+                .map(|rest| rest.is_empty() || rest.starts_with(b"/"))
+                == Some(true))
+}
+
 ///
 pub mod apply_cli_overrides {
     use crate::bstr::{BString, ByteSlice};
@@ -102,6 +294,33 @@ pub mod apply_cli_overrides {
         SectionHeader(#[from] git_config::parse::section::header::Error),
     }
 
+    /// Parse configuration values of the form `core.abbrev=5` or `core.bool-implicit-true` into a new configuration file
+    /// marked with [source CLI][git_config::Source::Cli].
+    pub(crate) fn to_file(
+        values: impl IntoIterator<Item = impl Into<BString>>,
+    ) -> Result<git_config::File<'static>, Error> {
+        let mut file = git_config::File::new(git_config::file::Metadata::from(git_config::Source::Cli));
+        for key_value in values {
+            let key_value = key_value.into();
+            let mut tokens = key_value.splitn(2, |b| *b == b'=').map(|v| v.trim());
+            let key = tokens.next().expect("always one value").as_bstr();
+            let value = tokens.next();
+            let key = git_config::parse::key(key.to_str().map_err(|_| Error::InvalidKey { input: key.into() })?)
+                .ok_or_else(|| Error::InvalidKey { input: key.into() })?;
+            let mut section = file.section_mut_or_create_new(key.section_name, key.subsection_name)?;
+            section.push(
+                git_config::parse::section::Key::try_from(key.value_name.to_owned()).map_err(|err| {
+                    Error::SectionKey {
+                        source: err,
+                        key: key.value_name.into(),
+                    }
+                })?,
+                value.map(|v| v.as_bstr()),
+            );
+        }
+        Ok(file)
+    }
+
     impl SnapshotMut<'_> {
         /// Apply configuration values of the form `core.abbrev=5` or `remote.origin.url = foo` or `core.bool-implicit-true`
         /// to the repository configuration, marked with [source CLI][git_config::Source::Cli].
@@ -109,31 +328,51 @@ pub mod apply_cli_overrides {
             &mut self,
             values: impl IntoIterator<Item = impl Into<BString>>,
         ) -> Result<(), Error> {
-            let mut file = git_config::File::new(git_config::file::Metadata::from(git_config::Source::Cli));
-            for key_value in values {
-                let key_value = key_value.into();
-                let mut tokens = key_value.splitn(2, |b| *b == b'=').map(|v| v.trim());
-                let key = tokens.next().expect("always one value").as_bstr();
-                let value = tokens.next();
-                let key = git_config::parse::key(key.to_str().map_err(|_| Error::InvalidKey { input: key.into() })?)
-                    .ok_or_else(|| Error::InvalidKey { input: key.into() })?;
-                let mut section = file.section_mut_or_create_new(key.section_name, key.subsection_name)?;
-                section.push(
-                    git_config::parse::section::Key::try_from(key.value_name.to_owned()).map_err(|err| {
-                        Error::SectionKey {
-                            source: err,
-                            key: key.value_name.into(),
-                        }
-                    })?,
-                    value.map(|v| v.as_bstr()),
-                );
-            }
-            self.config.append(file);
+            self.config.append(to_file(values)?);
             Ok(())
         }
     }
 }
 
+/// Fetching
+impl<'repo> Snapshot<'repo> {
+    /// Return the algorithm to use when negotiating which commits to send to the remote while fetching, as configured
+    /// with `fetch.negotiationAlgorithm`.
+    ///
+    /// If it is unset, the algorithm is [`Skipping`][crate::remote::fetch::negotiate::Algorithm::Skipping] if `feature.experimental`
+    /// is true, or [`Consecutive`][crate::remote::fetch::negotiate::Algorithm::Consecutive] otherwise.
+    pub fn fetch_negotiation_algorithm(
+        &self,
+    ) -> Result<crate::remote::fetch::negotiate::Algorithm, crate::remote::fetch::negotiate::algorithm::Error> {
+        use crate::remote::fetch::negotiate::Algorithm;
+        let config = &self.repo.config.resolved;
+        match config.string("fetch", None, "negotiationAlgorithm") {
+            Some(name) => std::convert::TryFrom::try_from(name.as_ref()),
+            None => Ok(
+                if config.boolean("feature", None, "experimental").and_then(Result::ok) == Some(true) {
+                    Algorithm::Skipping
+                } else {
+                    Algorithm::Consecutive
+                },
+            ),
+        }
+    }
+}
+
+/// Receiving
+impl<'repo> Snapshot<'repo> {
+    /// Return how to handle updates to branches that are checked out in a worktree when receiving a push, as configured
+    /// with `receive.denyCurrentBranch`, which [refuses][crate::remote::fetch::DenyCurrentBranch::Refuse] them if unset.
+    pub fn receive_deny_current_branch(
+        &self,
+    ) -> Result<crate::remote::fetch::DenyCurrentBranch, crate::remote::fetch::deny_current_branch::Error> {
+        match self.repo.config.resolved.string("receive", None, "denyCurrentBranch") {
+            Some(value) => std::convert::TryFrom::try_from(value.as_ref()),
+            None => Ok(Default::default()),
+        }
+    }
+}
+
 /// Utilities and additional access
 impl<'repo> Snapshot<'repo> {
     /// Returns the underlying configuration implementation for a complete API, despite being a little less convenient.
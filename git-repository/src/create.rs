@@ -106,13 +106,28 @@ pub struct Options {
     /// If set, use these filesystem capabilities to populate the respective git-config fields.
     /// If `None`, the directory will be probed.
     pub fs_capabilities: Option<git_worktree::fs::Capabilities>,
+
+    /// The kind of hash to use for objects in the repository.
+    ///
+    /// Anything but `Sha1` sets `core.repositoryFormatVersion` to 1 and configures `extensions.objectFormat` accordingly.
+    pub object_hash: git_hash::Kind,
 }
 
 /// Create a new `.git` repository of `kind` within the possibly non-existing `directory`
 /// and return its path.
-pub fn into(
+pub fn into(directory: impl Into<PathBuf>, options: Options) -> Result<git_discover::repository::Path, Error> {
+    into_with_config(directory, options, |_| {})
+}
+
+/// Like [`into()`], but calls `edit_config` with the configuration of the new repository before it is written.
+pub(crate) fn into_with_config(
     directory: impl Into<PathBuf>,
-    Options { bare, fs_capabilities }: Options,
+    Options {
+        bare,
+        fs_capabilities,
+        object_hash,
+    }: Options,
+    edit_config: impl FnOnce(&mut git_config::File<'static>),
 ) -> Result<git_discover::repository::Path, Error> {
     let mut dot_git = directory.into();
 
@@ -183,7 +198,8 @@ pub fn into(
             let caps = fs_capabilities.unwrap_or_else(|| git_worktree::fs::Capabilities::probe(&dot_git));
             let mut core = config.new_section("core", None).expect("valid section name");
 
-            core.push(key("repositoryformatversion"), Some("0".into()));
+            let format_version = if object_hash == git_hash::Kind::Sha1 { "0" } else { "1" };
+            core.push(key("repositoryformatversion"), Some(format_version.into()));
             core.push(key("filemode"), Some(bool(caps.executable_bit).into()));
             core.push(key("bare"), Some(bool(bare).into()));
             core.push(key("logallrefupdates"), Some(bool(!bare).into()));
@@ -191,6 +207,14 @@ pub fn into(
             core.push(key("ignorecase"), Some(bool(caps.ignore_case).into()));
             core.push(key("precomposeunicode"), Some(bool(caps.precompose_unicode).into()));
         }
+        if object_hash != git_hash::Kind::Sha1 {
+            let mut extensions = config.new_section("extensions", None).expect("valid section name");
+            extensions.push(
+                key("objectFormat"),
+                Some(object_hash.to_string().to_lowercase().as_str().into()),
+            );
+        }
+        edit_config(&mut config);
         let mut cursor = PathCursor(&mut dot_git);
         let config_path = cursor.at("config");
         std::fs::write(&config_path, &config.to_bstring()).map_err(|err| Error::IoWrite {
@@ -206,11 +230,11 @@ pub fn into(
     ))
 }
 
-fn key(name: &'static str) -> section::Key<'static> {
+pub(crate) fn key(name: &'static str) -> section::Key<'static> {
     section::Key::try_from(name).expect("valid key name")
 }
 
-fn bool(v: bool) -> &'static str {
+pub(crate) fn bool(v: bool) -> &'static str {
     match v {
         true => "true",
         false => "false",
@@ -58,10 +58,11 @@ impl ThreadSafeRepository {
     ///
     /// Finally, use the `trust_map` to determine which of our own repository options to use
     /// based on the trust level of the effective repository directory.
+    /// All other overrides are applied as well, see [`Options::environment_overrides()`][crate::open::Options::environment_overrides()].
     pub fn discover_with_environment_overrides_opts(
         directory: impl AsRef<Path>,
         mut options: upwards::Options,
-        trust_map: git_sec::trust::Mapping<crate::open::Options>,
+        mut trust_map: git_sec::trust::Mapping<crate::open::Options>,
     ) -> Result<Self, Error> {
         fn apply_additional_environment(mut opts: upwards::Options) -> upwards::Options {
             use std::convert::TryFrom;
@@ -83,6 +84,8 @@ impl ThreadSafeRepository {
         }
 
         options = apply_additional_environment(options.apply_environment());
+        trust_map.full.environment_overrides = true;
+        trust_map.reduced.environment_overrides = true;
         Self::discover_opts(directory, options, trust_map)
     }
 }
@@ -0,0 +1,50 @@
+//! Types to describe the identities of authors and committers and where they came from.
+
+/// Where a value of an [`Identity`] was obtained from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Source {
+    /// The environment `variable`, like `GIT_AUTHOR_NAME` or `EMAIL`.
+    Environment {
+        /// The name of the environment variable.
+        variable: &'static str,
+    },
+    /// The configuration `key`, like `committer.email` or `user.name`.
+    Configuration {
+        /// The key holding the value.
+        key: &'static str,
+        /// The kind of configuration file the value was read from.
+        source: crate::config::Source,
+    },
+    /// The value was guessed from the name of the current user and the name of the host, as no value was configured.
+    Guessed,
+    /// The time is the current time as it wasn't set in the environment.
+    CurrentTime,
+}
+
+/// A signature of an author or committer along with information about where its values came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Identity<'a> {
+    /// The signature to use in commits, tags or reflogs.
+    pub signature: git_actor::SignatureRef<'a>,
+    /// The source of the name of the signature.
+    pub name: Source,
+    /// The source of the email of the signature.
+    pub email: Source,
+    /// The source of the time of the signature.
+    pub time: Source,
+}
+
+/// The error returned by [`Repository::author()`][crate::Repository::author()] and [`Repository::committer()`][crate::Repository::committer()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("No {} was given for the {} and auto-detection is disabled by 'user.useConfigOnly'", .field, .role)]
+    AutoDetectionDisabled { role: &'static str, field: &'static str },
+    #[error("No {} was given for the {} and it could not be determined from the current user and host", .field, .role)]
+    AutoDetectionFailed { role: &'static str, field: &'static str },
+    #[error("Invalid date format in {}: '{}'", .variable, .value)]
+    InvalidDate {
+        variable: &'static str,
+        value: crate::bstr::BString,
+    },
+}
@@ -20,6 +20,32 @@
 //!
 //! To help with this, convert it with `.to_sync()` into a [`ThreadSafeRepository`].
 //!
+//! ## Progress and Interruption
+//!
+//! Long-running operations take an implementation of [`Progress`] to report on their work and a `should_interrupt: &AtomicBool`
+//! flag which, once set, causes them to stop as soon as possible with an error. Use [`interrupt::IS_INTERRUPTED`] along with
+//! [`interrupt::init_handler()`] to have it set on `SIGINT` and `SIGTERM`, or use a flag of your own to cancel from another thread.
+//! [`progress::Discard`] can be passed if no progress is needed.
+//!
+//! The progress they report is structured as follows:
+//!
+//! * `clone::PrepareClone::fetch_then_checkout()` (`blocking-network-client` feature)
+//!   * `fetch` - the progress of the remote and of receiving and indexing the pack, in `bytes` and `objects`.
+//!   * `checkout` and `writing` - as for [`Repository::checkout_tree()`].
+//! * `remote::Connection::fetch()` (`blocking-network-client` or `async-network-client` feature) - the progress of the
+//!   remote and of receiving and indexing the pack, in `bytes` and `objects`.
+//!   * `receiving` - the amount of `bytes` received, only in async mode where the pack is buffered before it is indexed.
+//! * [`Repository::checkout_tree()`]
+//!   * `checkout` - the amount of checked out `files`.
+//!   * `writing` - the amount of `bytes` written.
+//! * [`Repository::verify_integrity()`] - the amount of verified `pack indices` and then `loose object stores`.
+//!   * `<path>` - one child per pack index or loose object store, counting the verified `objects`.
+//! * [`Repository::maintain_after_fetch()`] - writes the multi-pack index using the given progress.
+//! * [`Repository::unbundle()`] - the progress of indexing the received pack, in `bytes` and `objects`.
+//!
+//! Traversals are iterators and can be stopped at any time, or with [`revision::walk::Platform::with_interrupt()`] to
+//! have them observe an interrupt flag.
+//!
 //! ## Object-Access Performance
 //!
 //! Accessing objects quickly is the bread-and-butter of working with git, right after accessing references. Hence it's vital
@@ -135,6 +161,7 @@ pub use git_credentials as credentials;
 #[cfg(feature = "unstable")]
 pub use git_date as date;
 pub use git_diff as diff;
+pub use git_features::fs::fsync;
 use git_features::threading::OwnShared;
 #[cfg(feature = "unstable")]
 pub use git_features::{parallel, progress, progress::Progress, threading};
@@ -194,6 +221,7 @@ pub use types::{
 pub mod commit;
 pub mod head;
 pub mod id;
+pub mod identity;
 pub mod object;
 pub mod reference;
 mod repository;
@@ -211,6 +239,7 @@ pub fn init(directory: impl AsRef<std::path::Path>) -> Result<Repository, init::
         create::Options {
             bare: false,
             fs_capabilities: None,
+            object_hash: git_hash::Kind::Sha1,
         },
     )
     .map(Into::into)
@@ -223,11 +252,32 @@ pub fn init_bare(directory: impl AsRef<std::path::Path>) -> Result<Repository, i
         create::Options {
             bare: true,
             fs_capabilities: None,
+            object_hash: git_hash::Kind::Sha1,
         },
     )
     .map(Into::into)
 }
 
+/// See [ThreadSafeRepository::init_bare_with()], but returns a [`Repository`] instead.
+pub fn init_bare_with(
+    directory: impl AsRef<std::path::Path>,
+    options: init::Hosting,
+) -> Result<Repository, init::Error> {
+    ThreadSafeRepository::init_bare_with(directory, options).map(Into::into)
+}
+
+/// Prepare cloning the repository at `url` into `path`, see [`clone::PrepareClone`] for details.
+///
+/// This is the equivalent of `git clone`, and a free function as `Repository::clone()` would clash with [`Clone::clone()`].
+#[cfg(feature = "blocking-network-client")]
+pub fn prepare_clone<Url, E>(url: Url, path: impl Into<std::path::PathBuf>) -> Result<clone::PrepareClone, clone::Error>
+where
+    Url: std::convert::TryInto<git_url::Url, Error = E>,
+    git_url::parse::Error: From<E>,
+{
+    clone::PrepareClone::new(url, path)
+}
+
 /// See [ThreadSafeRepository::open()], but returns a [`Repository`] instead.
 pub fn open(directory: impl Into<std::path::PathBuf>) -> Result<Repository, open::Error> {
     ThreadSafeRepository::open(directory).map(Into::into)
@@ -259,31 +309,56 @@ pub mod permissions {
 }
 pub use repository::permissions::Permissions;
 
+pub mod apply;
+
+///
+#[cfg(feature = "archive")]
+pub mod archive;
+///
+pub mod bundle;
 ///
 pub mod create;
 
 ///
 pub mod open;
 
+///
+pub mod read_only;
+
 ///
 pub mod config;
 
 ///
 pub mod mailmap;
 
+pub mod merge;
+
 ///
 pub mod worktree;
 
+///
+pub mod status;
+
+pub mod check;
+
 pub mod revision;
 
+pub mod shortlog;
+
 ///
 pub mod remote;
 
+pub mod serve;
+
+///
+#[cfg(feature = "blocking-network-client")]
+pub mod clone;
+
 ///
 pub mod init {
     use std::path::Path;
 
-    use crate::ThreadSafeRepository;
+    use crate::{bstr::BString, ThreadSafeRepository};
 
     /// The error returned by [`crate::init()`].
     #[derive(Debug, thiserror::Error)]
@@ -295,7 +370,64 @@ pub mod init {
         Open(#[from] crate::open::Error),
     }
 
+    /// Options for [`ThreadSafeRepository::init_bare_with()`] to configure a bare repository for hosting.
+    ///
+    /// The defaults are the recommended settings for repositories served to clients.
+    #[derive(Default, Debug, Clone)]
+    pub struct Hosting {
+        /// If `true`, default `false`, write reflogs for all reference updates, which rarely pays off on servers.
+        pub reflogs: bool,
+        /// The value of `core.fsync` controlling which files are flushed to disk when written, like `committed` or `all`,
+        /// or `None` to use the default.
+        pub fsync: Option<BString>,
+        /// The kind of hash to use for objects in the repository.
+        pub object_hash: git_hash::Kind,
+        /// Reference prefixes to hide from clients when fetching or pushing, like `refs/pull/`, written to `transfer.hideRefs`.
+        pub hide_refs: Vec<BString>,
+    }
+
     impl ThreadSafeRepository {
+        /// Create a bare repository for hosting within `directory` which must be empty, creating intermediate directories
+        /// as needed, and configure it according to `options` in one go before opening it.
+        pub fn init_bare_with(directory: impl AsRef<Path>, options: Hosting) -> Result<Self, Error> {
+            use git_sec::trust::DefaultForLevel;
+
+            use crate::create::{bool, key};
+            let Hosting {
+                reflogs,
+                fsync,
+                object_hash,
+                hide_refs,
+            } = options;
+            let path = crate::create::into_with_config(
+                directory.as_ref(),
+                crate::create::Options {
+                    bare: true,
+                    fs_capabilities: None,
+                    object_hash,
+                },
+                |config| {
+                    let mut core = config
+                        .section_mut("core", None)
+                        .expect("core section is always present");
+                    core.set(key("logallrefupdates"), bool(reflogs));
+                    if let Some(fsync) = fsync.as_ref() {
+                        core.push(key("fsync"), Some(fsync.as_ref()));
+                    }
+                    if !hide_refs.is_empty() {
+                        let mut transfer = config.new_section("transfer", None).expect("valid section name");
+                        for prefix in &hide_refs {
+                            transfer.push(key("hideRefs"), Some(prefix.as_ref()));
+                        }
+                    }
+                },
+            )?;
+            let (git_dir, worktree_dir) = path.into_repository_and_work_tree_directories();
+            let mut open_options = crate::open::Options::default_for_level(git_sec::Trust::Full);
+            open_options.git_dir_trust = Some(git_sec::Trust::Full);
+            ThreadSafeRepository::open_from_paths(git_dir, worktree_dir, open_options).map_err(Into::into)
+        }
+
         /// Create a repository with work-tree within `directory`, creating intermediate directories as needed.
         ///
         /// Fails without action if there is already a `.git` repository inside of `directory`, but
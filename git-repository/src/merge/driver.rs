@@ -0,0 +1,280 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    merge::{file, Driver, Outcome, Resolution},
+    Repository,
+};
+
+/// A merge driver implemented by a program, as configured in the `merge.<name>` section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct External {
+    /// The name of the driver, as used in the value of the `merge` attribute and in `merge.<name>`.
+    pub name: BString,
+    /// A human-readable name of the driver, as configured in `merge.<name>.name`.
+    pub display_name: Option<BString>,
+    /// The command to run, as configured in `merge.<name>.driver`.
+    ///
+    /// It's passed to a shell after substituting `%O`, `%A` and `%B` with the paths to temporary files holding the
+    /// ancestor's, ours and theirs version respectively, `%L` with the conflict marker size and `%P` with the path of
+    /// the merged file. The program is expected to leave the merge result in the file at `%A` and to exit with a non-zero
+    /// status if there are conflicts.
+    pub command: BString,
+    /// The name of the driver to use when performing an internal merge between common ancestors,
+    /// as configured in `merge.<name>.recursive`.
+    pub recursive: Option<BString>,
+}
+
+/// The input to a merge driver.
+#[derive(Debug, Clone, Copy)]
+pub struct Input<'a> {
+    /// The content of the common ancestor, also known as base.
+    pub base: &'a [u8],
+    /// Our version of the content.
+    pub ours: &'a [u8],
+    /// Their version of the content.
+    pub theirs: &'a [u8],
+    /// The path of the file being merged, relative to the root of the repository.
+    pub path: &'a BStr,
+    /// The amount of characters to use for conflict markers, `7` by default, or whatever the `conflict-marker-size` attribute says.
+    pub marker_size: usize,
+}
+
+/// A merge driver implemented in-process, to be [registered][Drivers::register()] under a name to be used
+/// in the value of the `merge` attribute.
+pub trait InProcess {
+    /// Merge `input` and write the result into `out`, indicating whether or not the merge was clean.
+    fn merge(&self, input: Input<'_>, out: &mut Vec<u8>) -> Result<Resolution, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+impl<F> InProcess for F
+where
+    F: Fn(Input<'_>, &mut Vec<u8>) -> Result<Resolution, Box<dyn std::error::Error + Send + Sync>>,
+{
+    fn merge(&self, input: Input<'_>, out: &mut Vec<u8>) -> Result<Resolution, Box<dyn std::error::Error + Send + Sync>> {
+        self(input, out)
+    }
+}
+
+/// The error returned by [`Drivers::merge()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The in-process merge driver named {name:?} isn't registered")]
+    UnknownInProcess { name: BString },
+    #[error("The in-process merge driver named {name:?} failed")]
+    InProcess {
+        name: BString,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("The merge driver command {command:?} could not be executed")]
+    Spawn { command: BString, source: std::io::Error },
+    #[error("The merge driver command {command:?} was terminated by a signal")]
+    Terminated { command: BString },
+    #[error("Could not write or read the temporary files used to communicate with the merge driver program")]
+    Io(#[from] std::io::Error),
+}
+
+/// A platform to select and run merge drivers, created with [`Repository::merge_drivers()`].
+///
+/// Drivers are looked up by name in the following order: drivers registered in-process, drivers configured in
+/// `merge.<name>.driver` in trusted configuration files and finally the built-in `text`, `binary` and `union` drivers.
+/// Unknown driver names fall back to the `text` driver, just like in `git`.
+pub struct Drivers<'repo> {
+    repo: &'repo Repository,
+    in_process: BTreeMap<BString, Box<dyn InProcess + 'repo>>,
+}
+
+impl<'repo> Drivers<'repo> {
+    pub(crate) fn new(repo: &'repo Repository) -> Self {
+        Drivers {
+            repo,
+            in_process: Default::default(),
+        }
+    }
+
+    /// Register `driver` under `name`, overriding configured or built-in drivers of the same name.
+    pub fn register(&mut self, name: impl Into<BString>, driver: impl InProcess + 'repo) -> &mut Self {
+        self.in_process.insert(name.into(), Box::new(driver));
+        self
+    }
+
+    /// Return the driver to use for a path with the `merge` attribute in the given `state`.
+    ///
+    /// If the attribute is set or unspecified, the driver named by `merge.default` is used, or the `text` driver if there is none.
+    pub fn driver_for_attribute(&self, state: git_attributes::StateRef<'_>) -> Driver {
+        use git_attributes::StateRef;
+        match state {
+            StateRef::Unset => Driver::Binary,
+            StateRef::Value(name) => self.driver_by_name(name),
+            StateRef::Set | StateRef::Unspecified => {
+                match self.repo.config.resolved.string_filter(
+                    "merge",
+                    None,
+                    "default",
+                    &mut self.repo.filter_config_section(),
+                ) {
+                    Some(name) => self.driver_by_name(name.as_ref()),
+                    None => Driver::Text,
+                }
+            }
+        }
+    }
+
+    /// Return the driver with the given `name`, or the `text` driver if it isn't known.
+    pub fn driver_by_name(&self, name: &BStr) -> Driver {
+        if self.in_process.contains_key(name) {
+            return Driver::InProcess { name: name.to_owned() };
+        }
+        if let Some(external) = self.external(name) {
+            return Driver::External(external);
+        }
+        match name.as_bytes() {
+            b"binary" => Driver::Binary,
+            b"union" => Driver::Union,
+            _ => Driver::Text,
+        }
+    }
+
+    /// Merge `input` using `driver`.
+    pub fn merge(&self, driver: &Driver, input: Input<'_>) -> Result<Outcome, Error> {
+        if let Some(data) = trivial_merge(&input) {
+            return Ok(Outcome {
+                data: data.to_owned(),
+                resolution: Resolution::Clean,
+            });
+        }
+        let mut data = Vec::new();
+        let resolution = match driver {
+            Driver::Binary => binary(&input, &mut data),
+            Driver::Text | Driver::Union => {
+                if file::is_binary(input.base) || file::is_binary(input.ours) || file::is_binary(input.theirs) {
+                    binary(&input, &mut data)
+                } else {
+                    file::merge(
+                        input.base,
+                        input.ours,
+                        input.theirs,
+                        &mut data,
+                        file::Options {
+                            conflict: if matches!(driver, Driver::Union) {
+                                file::ConflictStyle::Union
+                            } else {
+                                file::ConflictStyle::Markers
+                            },
+                            marker_size: input.marker_size,
+                            ours_label: None,
+                            theirs_label: None,
+                        },
+                    )
+                }
+            }
+            Driver::InProcess { name } => self
+                .in_process
+                .get(name)
+                .ok_or_else(|| Error::UnknownInProcess { name: name.clone() })?
+                .merge(input, &mut data)
+                .map_err(|err| Error::InProcess {
+                    name: name.clone(),
+                    source: err,
+                })?,
+            Driver::External(external) => run_external(external, input, &mut data)?,
+        };
+        Ok(Outcome { data, resolution })
+    }
+
+    fn external(&self, name: &BStr) -> Option<External> {
+        let name_str = name.to_str().ok()?;
+        let config = &self.repo.config.resolved;
+        let mut filter = self.repo.filter_config_section();
+        let command = config.string_filter("merge", Some(name_str), "driver", &mut filter)?;
+        Some(External {
+            name: name.to_owned(),
+            display_name: config
+                .string_filter("merge", Some(name_str), "name", &mut filter)
+                .map(|v| v.into_owned()),
+            command: command.into_owned(),
+            recursive: config
+                .string_filter("merge", Some(name_str), "recursive", &mut filter)
+                .map(|v| v.into_owned()),
+        })
+    }
+}
+
+/// Return the merge result if it can be determined without looking at the content.
+fn trivial_merge<'a>(input: &Input<'a>) -> Option<&'a [u8]> {
+    if input.ours == input.theirs || input.base == input.theirs {
+        Some(input.ours)
+    } else if input.base == input.ours {
+        Some(input.theirs)
+    } else {
+        None
+    }
+}
+
+fn binary(input: &Input<'_>, out: &mut Vec<u8>) -> Resolution {
+    out.extend_from_slice(input.ours);
+    Resolution::Conflict
+}
+
+fn run_external(driver: &External, input: Input<'_>, out: &mut Vec<u8>) -> Result<Resolution, Error> {
+    use std::io::{Read, Write};
+
+    let temp_file_with = |data: &[u8]| -> std::io::Result<_> {
+        let mut file = git_tempfile::new(
+            std::env::temp_dir(),
+            git_tempfile::ContainingDirectory::Exists,
+            git_tempfile::AutoRemove::Tempfile,
+        )?;
+        let path = file.with_mut(|f| f.write_all(data).map(|_| f.path().to_owned()))??;
+        Ok((file, path))
+    };
+    let (_base_file, base_path) = temp_file_with(input.base)?;
+    let (_ours_file, ours_path) = temp_file_with(input.ours)?;
+    let (_theirs_file, theirs_path) = temp_file_with(input.theirs)?;
+
+    let mut command = BString::default();
+    let mut chars = driver.command.as_bytes().iter().copied();
+    while let Some(b) = chars.next() {
+        if b != b'%' {
+            command.push(b);
+            continue;
+        }
+        match chars.next() {
+            Some(b'O') => push_quoted_path(&mut command, &base_path),
+            Some(b'A') => push_quoted_path(&mut command, &ours_path),
+            Some(b'B') => push_quoted_path(&mut command, &theirs_path),
+            Some(b'L') => command.push_str(input.marker_size.to_string()),
+            Some(b'P') => command.push_str(git_quote::single(input.path)),
+            Some(other) => {
+                command.push(b'%');
+                command.push(other);
+            }
+            None => command.push(b'%'),
+        }
+    }
+
+    let status = std::process::Command::new(if cfg!(windows) { "sh.exe" } else { "sh" })
+        .arg("-c")
+        .arg(git_path::from_bstr(command.as_bstr()).as_ref())
+        .stdin(std::process::Stdio::null())
+        .status()
+        .map_err(|err| Error::Spawn {
+            command: driver.command.clone(),
+            source: err,
+        })?;
+    let code = status.code().ok_or_else(|| Error::Terminated {
+        command: driver.command.clone(),
+    })?;
+
+    std::fs::File::open(&ours_path)?.read_to_end(out)?;
+    Ok(if code == 0 {
+        Resolution::Clean
+    } else {
+        Resolution::Conflict
+    })
+}
+
+fn push_quoted_path(out: &mut BString, path: &std::path::Path) {
+    out.push_str(git_quote::single(git_path::into_bstr(path).as_ref()));
+}
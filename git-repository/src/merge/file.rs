@@ -0,0 +1,196 @@
+use std::ops::Range;
+
+use crate::merge::Resolution;
+
+/// Determine how conflicting hunks are represented in the merged output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictStyle {
+    /// Write both sides of the conflict, separated by conflict markers, like `git merge-file` does by default.
+    Markers,
+    /// Write the lines of our side followed by the lines of their side without any markers, resolving the conflict.
+    ///
+    /// This is what the built-in `union` merge driver does.
+    Union,
+    /// Resolve conflicts by picking our side of the conflicting hunk.
+    Ours,
+    /// Resolve conflicts by picking their side of the conflicting hunk.
+    Theirs,
+}
+
+/// Options for use in [`merge()`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options<'a> {
+    /// How to handle conflicting hunks.
+    pub conflict: ConflictStyle,
+    /// The amount of characters to use for each conflict marker, `7` by default.
+    pub marker_size: usize,
+    /// The label to write after the marker that starts our side of a conflict.
+    pub ours_label: Option<&'a [u8]>,
+    /// The label to write after the marker that ends their side of a conflict.
+    pub theirs_label: Option<&'a [u8]>,
+}
+
+impl Default for Options<'_> {
+    fn default() -> Self {
+        Options {
+            conflict: ConflictStyle::Markers,
+            marker_size: 7,
+            ours_label: None,
+            theirs_label: None,
+        }
+    }
+}
+
+/// A change to a range of lines in the base, replacing them with the `lines` of one side.
+struct Hunk {
+    base: Range<usize>,
+    side: Range<usize>,
+}
+
+/// Perform a line-based three-way merge of `ours` and `theirs`, both of which are derived from `base`, and write the result into `out`.
+///
+/// Changes made by only one side are taken as is, as are identical changes made by both sides. All other changes, including
+/// changes that are directly adjacent to each other, are conflicts which are handled according to `options`.
+///
+/// Returns [`Resolution::Conflict`] if at least one conflict was written with markers, or [`Resolution::Clean`] otherwise.
+pub fn merge(base: &[u8], ours: &[u8], theirs: &[u8], out: &mut Vec<u8>, options: Options<'_>) -> Resolution {
+    let base: Vec<_> = lines(base);
+    let ours: Vec<_> = lines(ours);
+    let theirs: Vec<_> = lines(theirs);
+
+    let our_hunks = hunks(&base, &ours);
+    let their_hunks = hunks(&base, &theirs);
+
+    let mut resolution = Resolution::Clean;
+    let (mut oi, mut ti) = (0, 0);
+    let mut base_pos = 0;
+    loop {
+        let start = match (our_hunks.get(oi), their_hunks.get(ti)) {
+            (Some(o), Some(t)) => o.base.start.min(t.base.start),
+            (Some(o), None) => o.base.start,
+            (None, Some(t)) => t.base.start,
+            (None, None) => break,
+        };
+        extend(out, &base[base_pos..start]);
+
+        let (oi_start, ti_start) = (oi, ti);
+        let mut end = start;
+        loop {
+            let mut absorbed = false;
+            if let Some(h) = our_hunks.get(oi).filter(|h| h.base.start <= end) {
+                end = end.max(h.base.end);
+                oi += 1;
+                absorbed = true;
+            }
+            if let Some(h) = their_hunks.get(ti).filter(|h| h.base.start <= end) {
+                end = end.max(h.base.end);
+                ti += 1;
+                absorbed = true;
+            }
+            if !absorbed {
+                break;
+            }
+        }
+
+        let base_range = start..end;
+        let ours_in_group = &our_hunks[oi_start..oi];
+        let theirs_in_group = &their_hunks[ti_start..ti];
+        match (ours_in_group.is_empty(), theirs_in_group.is_empty()) {
+            (false, true) => apply(out, &base, &ours, ours_in_group, base_range),
+            (true, false) => apply(out, &base, &theirs, theirs_in_group, base_range),
+            _ => {
+                let mut our_side = Vec::new();
+                apply(&mut our_side, &base, &ours, ours_in_group, base_range.clone());
+                let mut their_side = Vec::new();
+                apply(&mut their_side, &base, &theirs, theirs_in_group, base_range);
+                if our_side == their_side {
+                    out.extend_from_slice(&our_side);
+                } else {
+                    match options.conflict {
+                        ConflictStyle::Markers => {
+                            write_marker(out, b'<', options.marker_size, options.ours_label);
+                            extend_terminated(out, &our_side);
+                            write_marker(out, b'=', options.marker_size, None);
+                            extend_terminated(out, &their_side);
+                            write_marker(out, b'>', options.marker_size, options.theirs_label);
+                            resolution = Resolution::Conflict;
+                        }
+                        ConflictStyle::Union => {
+                            extend_terminated(out, &our_side);
+                            out.extend_from_slice(&their_side);
+                        }
+                        ConflictStyle::Ours => out.extend_from_slice(&our_side),
+                        ConflictStyle::Theirs => out.extend_from_slice(&their_side),
+                    }
+                }
+            }
+        }
+        base_pos = end;
+    }
+    extend(out, &base[base_pos..]);
+    resolution
+}
+
+/// Return true if `data` looks like binary data, using the same heuristic as git which looks for null-bytes at the beginning of it.
+pub fn is_binary(data: &[u8]) -> bool {
+    const FIRST_FEW_BYTES: usize = 8000;
+    data[..data.len().min(FIRST_FEW_BYTES)].contains(&0)
+}
+
+fn lines(data: &[u8]) -> Vec<&[u8]> {
+    data.split_inclusive(|b| *b == b'\n').collect()
+}
+
+fn hunks(base: &[&[u8]], side: &[&[u8]]) -> Vec<Hunk> {
+    let mut out = Vec::<Hunk>::new();
+    for op in similar::capture_diff_slices(similar::Algorithm::Myers, base, side) {
+        let (tag, base_range, side_range) = op.as_tag_tuple();
+        if tag == similar::DiffTag::Equal {
+            continue;
+        }
+        match out.last_mut() {
+            Some(prev) if prev.base.end == base_range.start && prev.side.end == side_range.start => {
+                prev.base.end = base_range.end;
+                prev.side.end = side_range.end;
+            }
+            _ => out.push(Hunk {
+                base: base_range,
+                side: side_range,
+            }),
+        }
+    }
+    out
+}
+
+/// Write the content of `base_range` as changed by all `hunks` of `side` into `out`.
+fn apply(out: &mut Vec<u8>, base: &[&[u8]], side: &[&[u8]], hunks: &[Hunk], base_range: Range<usize>) {
+    let mut pos = base_range.start;
+    for hunk in hunks {
+        extend(out, &base[pos..hunk.base.start]);
+        extend(out, &side[hunk.side.clone()]);
+        pos = hunk.base.end;
+    }
+    extend(out, &base[pos..base_range.end]);
+}
+
+fn extend(out: &mut Vec<u8>, lines: &[&[u8]]) {
+    for line in lines {
+        out.extend_from_slice(line);
+    }
+}
+
+fn extend_terminated(out: &mut Vec<u8>, data: &[u8]) {
+    out.extend_from_slice(data);
+    if !data.is_empty() && !data.ends_with(b"\n") {
+        out.push(b'\n');
+    }
+}
+
+fn write_marker(out: &mut Vec<u8>, marker: u8, size: usize, label: Option<&[u8]>) {
+    out.extend(std::iter::repeat(marker).take(size));
+    if let Some(label) = label {
+        out.push(b' ');
+        out.extend_from_slice(label);
+    }
+    out.push(b'\n');
+}
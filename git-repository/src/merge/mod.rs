@@ -0,0 +1,51 @@
+//! Merge the content of files using merge drivers as configured through `.gitattributes` and `merge.<driver>.*` configuration.
+use crate::bstr::BString;
+
+///
+pub mod file;
+
+///
+pub mod driver;
+
+/// Indicate whether the result of a merge can be used as is, or if it contains conflicts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// The merge succeeded without conflicts.
+    Clean,
+    /// The merged result contains conflicts, for instance in the form of conflict markers, which need to be resolved by the user.
+    Conflict,
+}
+
+/// A merge driver as selected by the `merge` attribute of a path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Driver {
+    /// The built-in `text` driver which performs a line-based three-way merge, leaving conflict markers in conflicting hunks.
+    ///
+    /// It's the default if the `merge` attribute is set or unspecified, and falls back to [`Binary`][Driver::Binary] if one of
+    /// the inputs appears to be binary.
+    Text,
+    /// The built-in `binary` driver which keeps our version and marks the merge as conflicting unless one side didn't change.
+    ///
+    /// It's used if the `merge` attribute is unset, i.e. `-merge`.
+    Binary,
+    /// The built-in `union` driver which performs a line-based three-way merge, but resolves conflicts by keeping the lines of both sides.
+    ///
+    /// It's useful for files like changelogs that are only ever appended to.
+    Union,
+    /// A driver implemented by a program, as configured by `merge.<name>.driver`.
+    External(driver::External),
+    /// A driver implemented by a function registered with [`Drivers::register()`][driver::Drivers::register()].
+    InProcess {
+        /// The name of the driver, as used in the value of the `merge` attribute.
+        name: BString,
+    },
+}
+
+/// The outcome of [`Drivers::merge()`][driver::Drivers::merge()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The merged content, possibly with conflict markers if the [`resolution`][Outcome::resolution] is a conflict.
+    pub data: Vec<u8>,
+    /// Whether or not the merge is conflicting.
+    pub resolution: Resolution,
+}
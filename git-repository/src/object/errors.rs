@@ -17,12 +17,46 @@ pub mod conversion {
 
 ///
 pub mod find {
-    /// Indicate that an error occoured when trying to find an object.
-    pub type Error = git_odb::store::find::Error;
+    use git_hash::ObjectId;
+
+    /// Indicate that an error occurred when trying to find an object, distinguishing objects that exist but are corrupt
+    /// from objects that couldn't be read.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Object {id} exists but is corrupt")]
+        Corrupt {
+            id: ObjectId,
+            source: git_odb::store::find::Error,
+        },
+        #[error("Object {id} could not be read from the object database")]
+        Find {
+            id: ObjectId,
+            source: git_odb::store::find::Error,
+        },
+    }
+
+    impl Error {
+        pub(crate) fn new(id: ObjectId, source: git_odb::store::find::Error) -> Self {
+            if source.is_corrupt() {
+                Error::Corrupt { id, source }
+            } else {
+                Error::Find { id, source }
+            }
+        }
+
+        /// Return `true` if the object exists but is corrupt.
+        pub fn is_corrupt(&self) -> bool {
+            matches!(self, Error::Corrupt { .. })
+        }
+    }
 
     ///
     pub mod existing {
         /// An object could not be found in the database, or an error occurred when trying to obtain it.
+        ///
+        /// Use [`is_corrupt()`][git_odb::store::find::Error::is_corrupt()] on the `Find` variant to learn if the object exists
+        /// but is corrupt.
         pub type Error = git_odb::find::existing::Error<git_odb::store::find::Error>;
     }
 }
@@ -30,5 +64,12 @@ pub mod find {
 ///
 pub mod write {
     /// An error to indicate writing to the loose object store failed.
-    pub type Error = git_odb::store::write::Error;
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Write(#[from] git_odb::store::write::Error),
+        #[error(transparent)]
+        ReadOnly(#[from] crate::read_only::Error),
+    }
 }
@@ -0,0 +1,122 @@
+//! Iterate all objects in the object database, as created by [`Repository::objects_iter()`][crate::Repository::objects_iter()].
+use git_hash::ObjectId;
+pub use git_odb::store::iter::Location;
+
+/// Options for use in [`Repository::objects_iter()`][crate::Repository::objects_iter()].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If `true`, objects are returned sorted by their id, which requires all of them to be collected in memory first.
+    ///
+    /// Otherwise objects are returned as they are encountered, first those in all packs, followed by all loose objects.
+    pub sorted: bool,
+    /// If `true`, provide the [location][Location] of each object, like the pack containing it and its offset within.
+    pub with_location: bool,
+}
+
+/// The error returned by [`Repository::objects_iter()`][crate::Repository::objects_iter()] and its iterators.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    LoadIndex(#[from] git_odb::store::load_index::Error),
+    #[error(transparent)]
+    IterateLoose(#[from] git_odb::loose::iter::Error),
+}
+
+/// An object in the object database, as returned by [`Iter`].
+///
+/// Note that objects stored in more than one location, like in multiple packs, are returned once per location.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Item {
+    /// The id of the object.
+    pub id: ObjectId,
+    /// The location of the object if [`Options::with_location`] was set.
+    pub location: Option<Location>,
+}
+
+/// An iterator over all objects in the object database.
+pub struct Iter {
+    state: State,
+}
+
+enum State {
+    Unsorted(git_odb::store::iter::AllObjects),
+    UnsortedWithLocation(git_odb::store::iter::AllObjectsWithLocation),
+    Sorted(std::vec::IntoIter<Item>),
+}
+
+impl Iter {
+    pub(crate) fn new(objects: git_odb::store::iter::AllObjects, options: Options) -> Result<Self, Error> {
+        let state = if options.with_location {
+            State::UnsortedWithLocation(objects.with_location())
+        } else {
+            State::Unsorted(objects)
+        };
+        let mut iter = Iter { state };
+        if options.sorted {
+            let mut items = iter.by_ref().collect::<Result<Vec<_>, _>>()?;
+            items.sort_by_key(|item| item.id);
+            iter.state = State::Sorted(items.into_iter());
+        }
+        Ok(iter)
+    }
+
+    /// Turn this iterator into one returning up to `size` items at a time, which is useful to process them in parallel
+    /// with `git_features::parallel::in_parallel()`.
+    pub fn chunks(self, size: usize) -> Chunks {
+        assert_ne!(size, 0, "chunks must not be empty");
+        Chunks { iter: self, size }
+    }
+}
+
+impl Iterator for Iter {
+    type Item = Result<Item, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.state {
+            State::Unsorted(iter) => iter
+                .next()
+                .map(|res| res.map(|id| Item { id, location: None }).map_err(Into::into)),
+            State::UnsortedWithLocation(iter) => iter.next().map(|res| {
+                res.map(|(id, location)| Item {
+                    id,
+                    location: Some(location),
+                })
+                .map_err(Into::into)
+            }),
+            State::Sorted(iter) => iter.next().map(Ok),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.state {
+            State::Unsorted(iter) => iter.size_hint(),
+            State::UnsortedWithLocation(iter) => iter.size_hint(),
+            State::Sorted(iter) => iter.size_hint(),
+        }
+    }
+}
+
+/// An iterator over chunks of objects in the object database, created by [`Iter::chunks()`].
+pub struct Chunks {
+    iter: Iter,
+    size: usize,
+}
+
+impl Iterator for Chunks {
+    type Item = Result<Vec<Item>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk = Vec::with_capacity(self.size);
+        for item in self.iter.by_ref() {
+            match item {
+                Ok(item) => chunk.push(item),
+                Err(err) => return Some(Err(err)),
+            }
+            if chunk.len() == self.size {
+                break;
+            }
+        }
+        (!chunk.is_empty()).then(|| Ok(chunk))
+    }
+}
@@ -3,6 +3,7 @@ use std::convert::TryInto;
 
 use git_hash::ObjectId;
 pub use git_object::Kind;
+pub use git_odb::find::Header;
 
 use crate::{Commit, Id, Object, ObjectDetached, Tag, Tree};
 
@@ -14,6 +15,8 @@ pub use errors::{conversion, find, write};
 ///
 pub mod commit;
 mod impls;
+///
+pub mod iter;
 pub mod peel;
 mod tag;
 ///
@@ -146,7 +149,7 @@ impl<'repo> Object<'repo> {
 
     /// Obtain a fully parsed commit whose fields reference our data buffer.
     pub fn try_to_commit_ref(&self) -> Result<git_object::CommitRef<'_>, conversion::Error> {
-        git_object::Data::new(self.kind, &self.data)
+        git_object::Data::new(self.kind, &self.data, self.id.kind())
             .decode()?
             .into_commit()
             .ok_or(conversion::Error::UnexpectedType {
@@ -161,14 +164,14 @@ impl<'repo> Object<'repo> {
     ///
     /// - this object is not a commit
     pub fn to_commit_ref_iter(&self) -> git_object::CommitRefIter<'_> {
-        git_object::Data::new(self.kind, &self.data)
+        git_object::Data::new(self.kind, &self.data, self.id.kind())
             .try_into_commit_iter()
             .expect("BUG: This object must be a commit")
     }
 
     /// Obtain a commit token iterator from the data in this instance, if it is a commit.
     pub fn try_to_commit_ref_iter(&self) -> Option<git_object::CommitRefIter<'_>> {
-        git_object::Data::new(self.kind, &self.data).try_into_commit_iter()
+        git_object::Data::new(self.kind, &self.data, self.id.kind()).try_into_commit_iter()
     }
 
     /// Obtain a tag token iterator from the data in this instance.
@@ -177,7 +180,7 @@ impl<'repo> Object<'repo> {
     ///
     /// - this object is not a tag
     pub fn to_tag_ref_iter(&self) -> git_object::TagRefIter<'_> {
-        git_object::Data::new(self.kind, &self.data)
+        git_object::Data::new(self.kind, &self.data, self.id.kind())
             .try_into_tag_iter()
             .expect("BUG: this object must be a tag")
     }
@@ -188,7 +191,7 @@ impl<'repo> Object<'repo> {
     ///
     /// - this object is not a tag
     pub fn try_to_tag_ref_iter(&self) -> Option<git_object::TagRefIter<'_>> {
-        git_object::Data::new(self.kind, &self.data).try_into_tag_iter()
+        git_object::Data::new(self.kind, &self.data, self.id.kind()).try_into_tag_iter()
     }
 
     /// Obtain a tag object from the data in this instance.
@@ -203,7 +206,7 @@ impl<'repo> Object<'repo> {
 
     /// Obtain a fully parsed tag object whose fields reference our data buffer.
     pub fn try_to_tag_ref(&self) -> Result<git_object::TagRef<'_>, conversion::Error> {
-        git_object::Data::new(self.kind, &self.data)
+        git_object::Data::new(self.kind, &self.data, self.id.kind())
             .decode()?
             .into_tag()
             .ok_or(conversion::Error::UnexpectedType {
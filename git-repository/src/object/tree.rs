@@ -1,5 +1,10 @@
+use std::collections::hash_map::Entry;
+
 use git_hash::ObjectId;
-use git_object::{bstr::BStr, TreeRefIter};
+use git_object::{
+    bstr::{BStr, ByteSlice},
+    TreeRefIter,
+};
 use git_odb::FindExt;
 
 use crate::{object::find, Id, Tree};
@@ -40,7 +45,7 @@ impl<'repo> Tree<'repo> {
         // let mut out = None;
         let mut path = path.into_iter().peekable();
         while let Some(component) = path.next() {
-            match TreeRefIter::from_bytes(&self.data)
+            match TreeRefIter::from_bytes(&self.data, self.id.kind())
                 .filter_map(Result::ok)
                 .find(|entry| component.eq(entry.filename))
             {
@@ -63,6 +68,51 @@ impl<'repo> Tree<'repo> {
         Ok(None)
     }
 
+    /// Like [`lookup_path()`][Self::lookup_path()], but keeps all trees it decodes along the way in `cache` to avoid
+    /// finding and decoding them again in subsequent calls.
+    ///
+    /// This is most useful when looking up many paths from the same root tree, for instance when checking thousands of paths
+    /// against a single commit, as intermediate trees are only decoded once.
+    /// As trees are cached by their id, the same `cache` may also be used with different root trees.
+    pub fn lookup_entry_cached<I, P>(
+        &self,
+        path: I,
+        cache: &mut lookup::Cache,
+    ) -> Result<Option<git_object::tree::Entry>, find::existing::Error>
+    where
+        I: IntoIterator<Item = P>,
+        P: PartialEq<BStr>,
+    {
+        let mut path = path.into_iter().peekable();
+        let mut tree_id = self.id;
+        while let Some(component) = path.next() {
+            let entries = match cache.trees.entry(tree_id) {
+                Entry::Occupied(e) => e.into_mut(),
+                Entry::Vacant(e) => {
+                    let entries = if tree_id == self.id {
+                        lookup::decode(&self.data, self.id.kind())
+                    } else {
+                        match self.repo.find_object(tree_id)?.try_into_tree() {
+                            Ok(tree) => lookup::decode(&tree.data, tree.id.kind()),
+                            Err(_) => return Ok(None),
+                        }
+                    };
+                    e.insert(entries)
+                }
+            };
+            match entries.iter().find(|entry| component.eq(entry.filename.as_bstr())) {
+                Some(entry) => {
+                    if path.peek().is_none() {
+                        return Ok(Some(entry.clone()));
+                    }
+                    tree_id = entry.oid;
+                }
+                None => return Ok(None),
+            }
+        }
+        Ok(None)
+    }
+
     /// Obtain a platform for initiating a variety of traversals.
     pub fn traverse(&self) -> Traversal<'_, 'repo> {
         Traversal {
@@ -105,7 +155,7 @@ impl<'a, 'repo> Traversal<'a, 'repo> {
     where
         V: git_traverse::tree::Visit,
     {
-        let root = git_object::TreeRefIter::from_bytes(&self.root.data);
+        let root = git_object::TreeRefIter::from_bytes(&self.root.data, self.root.id.kind());
         let state = git_traverse::tree::breadthfirst::State::default();
         git_traverse::tree::breadthfirst(
             root,
@@ -116,6 +166,53 @@ impl<'a, 'repo> Traversal<'a, 'repo> {
     }
 }
 
+///
+pub mod lookup {
+    use std::collections::HashMap;
+
+    use git_hash::ObjectId;
+    use git_object::TreeRefIter;
+
+    /// A cache for decoded trees to speed up repeated path lookups with
+    /// [`Tree::lookup_entry_cached()`][crate::Tree::lookup_entry_cached()].
+    ///
+    /// Note that it grows with each tree it sees and is never pruned automatically. Use [`clear()`][Cache::clear()]
+    /// to free its memory.
+    #[derive(Default, Debug, Clone)]
+    pub struct Cache {
+        pub(crate) trees: HashMap<ObjectId, Vec<git_object::tree::Entry>>,
+    }
+
+    impl Cache {
+        /// Create a new empty cache.
+        pub fn new() -> Self {
+            Cache::default()
+        }
+
+        /// Return the amount of trees stored in this cache.
+        pub fn len(&self) -> usize {
+            self.trees.len()
+        }
+
+        /// Return true if no tree is stored in this cache.
+        pub fn is_empty(&self) -> bool {
+            self.trees.is_empty()
+        }
+
+        /// Remove all trees from this cache.
+        pub fn clear(&mut self) {
+            self.trees.clear()
+        }
+    }
+
+    pub(crate) fn decode(data: &[u8], object_hash: git_hash::Kind) -> Vec<git_object::tree::Entry> {
+        TreeRefIter::from_bytes(data, object_hash)
+            .filter_map(Result::ok)
+            .map(Into::into)
+            .collect()
+    }
+}
+
 pub use iter::EntryRef;
 
 ///
@@ -165,7 +262,8 @@ mod iter {
         /// Return an iterator over tree entries.
         pub fn iter(&self) -> impl Iterator<Item = Result<EntryRef<'repo, '_>, git_object::decode::Error>> {
             let repo = self.repo;
-            git_object::TreeRefIter::from_bytes(&self.data).map(move |e| e.map(|entry| EntryRef { inner: entry, repo }))
+            git_object::TreeRefIter::from_bytes(&self.data, self.id.kind())
+                .map(move |e| e.map(|entry| EntryRef { inner: entry, repo }))
         }
     }
 }
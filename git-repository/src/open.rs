@@ -2,9 +2,15 @@ use std::path::PathBuf;
 
 use git_features::threading::OwnShared;
 
+use crate::bstr::BString;
+
 use crate::{config, config::cache::interpolate_context, permission, Permissions, ThreadSafeRepository};
 
 /// A way to configure the usage of replacement objects, see `git replace`.
+///
+/// If used, [`Repository::find_object()`][crate::Repository::find_object()] and traversals transparently substitute
+/// objects by their replacement, with [`Repository::replacements()`][crate::Repository::replacements()] providing
+/// the mapping in use.
 #[derive(Debug, Clone)]
 pub enum ReplacementObjects {
     /// Allow replacement objects and configure the ref prefix the standard environment variable `GIT_REPLACE_REF_BASE`,
@@ -71,6 +77,12 @@ pub struct Options {
     pub(crate) lossy_config: Option<bool>,
     pub(crate) lenient_config: bool,
     pub(crate) bail_if_untrusted: bool,
+    pub(crate) config_overrides: Vec<BString>,
+    /// Only used for the repository being opened, and not passed on to linked worktrees.
+    pub(crate) worktree_dir: Option<PathBuf>,
+    /// Only used for the repository being opened, and not passed on to linked worktrees.
+    pub(crate) environment_overrides: bool,
+    pub(crate) read_only: bool,
 }
 
 impl Default for Options {
@@ -84,34 +96,81 @@ impl Default for Options {
             lossy_config: None,
             lenient_config: true,
             bail_if_untrusted: false,
+            config_overrides: Vec::new(),
+            worktree_dir: None,
+            environment_overrides: false,
+            read_only: false,
         }
     }
 }
 
-#[derive(Default, Clone)]
-#[allow(dead_code)]
-pub(crate) struct EnvironmentOverrides {
-    /// An override of the worktree typically from the environment, and overrides even worktree dirs set as parameter.
-    ///
-    /// This emulates the way git handles this override.
-    worktree_dir: Option<PathBuf>,
-    /// An override for the .git directory, typically from the environment.
-    ///
-    /// If set, the passed in `git_dir` parameter will be ignored in favor of this one.
-    git_dir: Option<PathBuf>,
+/// Overrides of repository locations as read from `GIT_*` environment variables, similar to the way `git` applies them.
+///
+/// These are only used if [environment overrides][Options::environment_overrides()] are enabled, and can be inspected
+/// with [`Repository::environment_overrides()`][crate::Repository::environment_overrides()] afterwards.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct EnvironmentOverrides {
+    /// `GIT_DIR`, the `.git` directory to use instead of the one passed to
+    /// [`open_with_environment_overrides()`][ThreadSafeRepository::open_with_environment_overrides()] or discovered by
+    /// [`discover_with_environment_overrides()`][ThreadSafeRepository::discover_with_environment_overrides()].
+    pub git_dir: Option<PathBuf>,
+    /// `GIT_WORK_TREE`, the work tree to use instead of the one set in `core.worktree` or implied by the location of the `.git` directory.
+    pub worktree_dir: Option<PathBuf>,
+    /// `GIT_COMMON_DIR`, the directory with objects, references and configuration to use instead of the one in the `commondir` file.
+    pub common_dir: Option<PathBuf>,
+    /// `GIT_OBJECT_DIRECTORY`, the object database to use instead of the `objects` directory in the common directory.
+    pub object_dir: Option<PathBuf>,
+    /// `GIT_ALTERNATE_OBJECT_DIRECTORIES`, object databases to use in addition to the ones listed in `objects/info/alternates`.
+    pub alternate_object_dirs: Vec<PathBuf>,
+    /// `GIT_INDEX_FILE`, the index to use instead of the `index` file in the `.git` directory.
+    pub index_file: Option<PathBuf>,
+    /// `GIT_CEILING_DIRECTORIES`, the directories above which no repository is discovered.
+    pub ceiling_dirs: Vec<PathBuf>,
 }
 
 impl EnvironmentOverrides {
-    fn from_env() -> Result<Self, permission::env_var::resource::Error> {
-        let mut worktree_dir = None;
-        if let Some(path) = std::env::var_os("GIT_WORK_TREE") {
-            worktree_dir = PathBuf::from(path).into();
-        }
-        let mut git_dir = None;
-        if let Some(path) = std::env::var_os("GIT_DIR") {
-            git_dir = PathBuf::from(path).into();
-        }
-        Ok(EnvironmentOverrides { worktree_dir, git_dir })
+    /// Read all overrides from the environment, with `permission` controlling if the paths they point to may be used.
+    pub fn from_env(permission: &permission::env_var::Resource) -> Result<Self, permission::env_var::resource::Error> {
+        let path = |name: &str| -> Result<Option<PathBuf>, permission::env_var::resource::Error> {
+            match std::env::var_os(name) {
+                Some(path) if !path.is_empty() => permission.check(PathBuf::from(path)),
+                _ => Ok(None),
+            }
+        };
+        let paths = |name: &str| -> Result<Vec<PathBuf>, permission::env_var::resource::Error> {
+            match std::env::var_os(name) {
+                Some(paths) => Ok(std::env::split_paths(&paths)
+                    .filter(|path| !path.as_os_str().is_empty())
+                    .map(|path| permission.check(path))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .into_iter()
+                    .flatten()
+                    .collect()),
+                None => Ok(Vec::new()),
+            }
+        };
+        let ceiling_dirs = match path("GIT_CEILING_DIRECTORIES")? {
+            Some(_) => {
+                git_discover::upwards::Options::default()
+                    .apply_environment()
+                    .ceiling_dirs
+            }
+            None => Vec::new(),
+        };
+        Ok(EnvironmentOverrides {
+            git_dir: path("GIT_DIR")?,
+            worktree_dir: path("GIT_WORK_TREE")?,
+            common_dir: path("GIT_COMMON_DIR")?,
+            object_dir: path("GIT_OBJECT_DIRECTORY")?,
+            alternate_object_dirs: paths("GIT_ALTERNATE_OBJECT_DIRECTORIES")?,
+            index_file: path("GIT_INDEX_FILE")?,
+            ceiling_dirs,
+        })
+    }
+
+    /// Return true if none of the overrides are set.
+    pub fn is_empty(&self) -> bool {
+        *self == EnvironmentOverrides::default()
     }
 }
 
@@ -133,8 +192,9 @@ impl Options {
         self
     }
 
-    // TODO: tests
     /// Configure replacement objects, see the [`ReplacementObjects`] type for details.
+    ///
+    /// Note that replacement objects are never used if `core.useReplaceRefs` is `false`.
     pub fn replacement_objects(mut self, config: ReplacementObjects) -> Self {
         self.replacement_objects = config;
         self
@@ -207,6 +267,51 @@ impl Options {
         self
     }
 
+    /// Apply configuration values of the form `core.abbrev=5` or `remote.origin.url = foo` or `core.bool-implicit-true`
+    /// on top of all other configuration, marked with [source CLI][git_config::Source::Cli].
+    ///
+    /// As opposed to [`SnapshotMut::apply_cli_overrides()`][crate::config::SnapshotMut::apply_cli_overrides()], these
+    /// are in effect while opening the repository and thus affect values like `core.bare` or `core.worktree`, similar to
+    /// `git -c key=value`.
+    pub fn config_overrides(mut self, values: impl IntoIterator<Item = impl Into<BString>>) -> Self {
+        self.config_overrides = values.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Use `path` as the worktree directory of the repository, overriding the worktree implied by the location of
+    /// the `.git` directory as well as `core.worktree`, similar to `git --work-tree <path>`.
+    ///
+    /// Note that this isn't inherited by linked worktrees that are opened from the resulting repository.
+    pub fn worktree_dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.worktree_dir = Some(path.into());
+        self
+    }
+
+    /// If true, default false, apply overrides of the locations of the work tree, the common directory, the object database
+    /// and its alternates as well as the index from `GIT_*` environment variables, see [`EnvironmentOverrides`] for details.
+    ///
+    /// This is what embedding tools spawned by `git`, like hooks, need to find the repository `git` is operating on.
+    /// It's enabled automatically by [`ThreadSafeRepository::open_with_environment_overrides()`] and
+    /// [`ThreadSafeRepository::discover_with_environment_overrides()`], which also apply `GIT_DIR` and `GIT_CEILING_DIRECTORIES`.
+    /// Access to these paths is controlled by the [`git_prefix`][crate::permissions::Environment::git_prefix] permission.
+    ///
+    /// Note that this isn't inherited by linked worktrees that are opened from the resulting repository.
+    pub fn environment_overrides(mut self, toggle: bool) -> Self {
+        self.environment_overrides = toggle;
+        self
+    }
+
+    /// If true, default false, open the repository as read-only snapshot that is never written to, as needed when inspecting
+    /// repositories on read-only mounts or for forensics.
+    ///
+    /// All methods that would write objects, references, reference logs, the index, the work tree or caches like the
+    /// commit-graph fail with a [`read_only::Error`][crate::read_only::Error] instead, and reference logs are never created.
+    /// Check with [`Repository::is_read_only()`][crate::Repository::is_read_only()]. This is inherited by linked worktrees.
+    pub fn read_only(mut self, toggle: bool) -> Self {
+        self.read_only = toggle;
+        self
+    }
+
     /// Open a repository at `path` with the options set so far.
     pub fn open(self, path: impl Into<PathBuf>) -> Result<ThreadSafeRepository, Error> {
         ThreadSafeRepository::open_opts(path, self)
@@ -225,6 +330,10 @@ impl git_sec::trust::DefaultForLevel for Options {
                 lossy_config: None,
                 bail_if_untrusted: false,
                 lenient_config: true,
+                config_overrides: Vec::new(),
+                worktree_dir: None,
+                environment_overrides: false,
+                read_only: false,
             },
             git_sec::Trust::Reduced => Options {
                 object_store_slots: git_odb::store::init::Slots::Given(32), // limit resource usage
@@ -235,6 +344,10 @@ impl git_sec::trust::DefaultForLevel for Options {
                 bail_if_untrusted: false,
                 lenient_config: true,
                 lossy_config: None,
+                config_overrides: Vec::new(),
+                worktree_dir: None,
+                environment_overrides: false,
+                read_only: false,
             },
         }
     }
@@ -292,14 +405,16 @@ impl ThreadSafeRepository {
     /// The `.git` directory whether given or computed is used for trust checks.
     ///
     /// Note that this will read various `GIT_*` environment variables to check for overrides, and is probably most useful when implementing
-    /// custom hooks.
-    // TODO: tests, with hooks, GIT_QUARANTINE for ref-log and transaction control (needs git-sec support to remove write access in git-ref)
+    /// custom hooks. All other overrides are applied as well, see [`Options::environment_overrides()`] for details.
+    // TODO: tests with hooks, GIT_QUARANTINE for ref-log and transaction control (needs git-sec support to remove write access in git-ref)
     pub fn open_with_environment_overrides(
         fallback_directory: impl Into<PathBuf>,
         trust_map: git_sec::trust::Mapping<Options>,
     ) -> Result<Self, Error> {
-        let overrides = EnvironmentOverrides::from_env()?;
-        let (path, path_kind): (PathBuf, _) = match overrides.git_dir {
+        let git_dir_override = std::env::var_os("GIT_DIR")
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from);
+        let (path, path_kind): (PathBuf, _) = match git_dir_override {
             Some(git_dir) => git_discover::is_git(&git_dir).map(|kind| (git_dir, kind))?,
             None => {
                 let fallback_directory = fallback_directory.into();
@@ -309,18 +424,26 @@ impl ThreadSafeRepository {
 
         let (git_dir, worktree_dir) = git_discover::repository::Path::from_dot_git_dir(path, path_kind)
             .into_repository_and_work_tree_directories();
-        let worktree_dir = worktree_dir.or(overrides.worktree_dir);
 
         let git_dir_trust = git_sec::Trust::from_path_ownership(&git_dir)?;
-        let options = trust_map.into_value_by_level(git_dir_trust);
+        let mut options = trust_map.into_value_by_level(git_dir_trust);
+        options.git_dir_trust = git_dir_trust.into();
+        options.environment_overrides = true;
         ThreadSafeRepository::open_from_paths(git_dir, worktree_dir, options)
     }
 
     pub(crate) fn open_from_paths(
         git_dir: PathBuf,
         mut worktree_dir: Option<PathBuf>,
-        options: Options,
+        mut options: Options,
     ) -> Result<Self, Error> {
+        let mut worktree_dir_override = options.worktree_dir.take();
+        let environment = std::mem::take(&mut options.environment_overrides)
+            .then(|| EnvironmentOverrides::from_env(&options.permissions.env.git_prefix))
+            .transpose()?;
+        if let Some(env) = &environment {
+            worktree_dir_override = worktree_dir_override.or_else(|| env.worktree_dir.clone());
+        }
         let Options {
             git_dir_trust,
             object_store_slots,
@@ -330,6 +453,10 @@ impl ThreadSafeRepository {
             lenient_config,
             bail_if_untrusted,
             permissions: Permissions { ref env, config },
+            ref config_overrides,
+            worktree_dir: _,
+            environment_overrides: _,
+            read_only,
         } = options;
         let git_dir_trust = git_dir_trust.expect("trust must be been determined by now");
 
@@ -337,9 +464,12 @@ impl ThreadSafeRepository {
         //       This would be something read in later as have to first check for extensions. Also this means
         //       that each worktree, even if accessible through this instance, has to come in its own Repository instance
         //       as it may have its own configuration. That's fine actually.
-        let common_dir = git_discover::path::from_plain_file(git_dir.join("commondir"))
-            .transpose()?
-            .map(|cd| git_dir.join(cd));
+        let common_dir = match environment.as_ref().and_then(|env| env.common_dir.clone()) {
+            Some(common_dir) => Some(common_dir),
+            None => git_discover::path::from_plain_file(git_dir.join("commondir"))
+                .transpose()?
+                .map(|cd| git_dir.join(cd)),
+        };
         let common_dir_ref = common_dir.as_deref().unwrap_or(&git_dir);
 
         let repo_config = config::cache::StageOne::new(common_dir_ref, git_dir_trust, lossy_config, lenient_config)?;
@@ -368,6 +498,7 @@ impl ThreadSafeRepository {
             env.clone(),
             config,
             lenient_config,
+            config_overrides,
         )?;
 
         if bail_if_untrusted && git_dir_trust != git_sec::Trust::Full {
@@ -390,6 +521,10 @@ impl ThreadSafeRepository {
             }
         }
 
+        if let Some(wt) = worktree_dir_override {
+            worktree_dir = Some(wt);
+        }
+
         match worktree_dir {
             None if !config.is_bare => {
                 worktree_dir = Some(git_dir.parent().expect("parent is always available").to_owned());
@@ -401,17 +536,24 @@ impl ThreadSafeRepository {
             None => {}
         }
 
-        refs.write_reflog = config.reflog.unwrap_or_else(|| {
-            if worktree_dir.is_none() {
+        refs.write_reflog = config.reflog.filter(|_| !read_only).unwrap_or_else(|| {
+            if read_only || worktree_dir.is_none() {
                 git_ref::store::WriteReflog::Disable
             } else {
                 git_ref::store::WriteReflog::Normal
             }
         });
+        refs.fsync = config.fsync.method_if(config.fsync.refs);
 
+        let use_replace_refs = config
+            .resolved
+            .boolean("core", None, "useReplaceRefs")
+            .and_then(Result::ok)
+            .unwrap_or(true);
         let replacements = replacement_objects
             .clone()
             .refs_prefix()
+            .filter(|_| use_replace_refs)
             .and_then(|prefix| {
                 let platform = refs.iter().ok()?;
                 let iter = platform.prefixed(&prefix).ok()?;
@@ -431,12 +573,20 @@ impl ThreadSafeRepository {
 
         Ok(ThreadSafeRepository {
             objects: OwnShared::new(git_odb::Store::at_opts(
-                common_dir_ref.join("objects"),
+                environment
+                    .as_ref()
+                    .and_then(|env| env.object_dir.clone())
+                    .unwrap_or_else(|| common_dir_ref.join("objects")),
                 replacements,
                 git_odb::store::init::Options {
                     slots: object_store_slots,
                     object_hash: config.object_hash,
                     use_multi_pack_index: config.use_multi_pack_index,
+                    fsync: config.fsync.method_if(config.fsync.loose_objects),
+                    alternates: environment
+                        .as_ref()
+                        .map(|env| env.alternate_object_dirs.clone())
+                        .unwrap_or_default(),
                 },
             )?),
             common_dir,
@@ -446,6 +596,7 @@ impl ThreadSafeRepository {
             // used when spawning new repositories off this one when following worktrees
             linked_worktree_options: options,
             index: git_features::fs::MutableSnapshot::new().into(),
+            environment: environment.map(Box::new),
         })
     }
 }
@@ -505,7 +656,7 @@ mod tests {
     fn size_of_options() {
         assert_eq!(
             std::mem::size_of::<Options>(),
-            72,
+            120,
             "size shouldn't change without us knowing"
         );
     }
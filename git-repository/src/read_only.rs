@@ -0,0 +1,7 @@
+/// The error returned when trying to write to a repository that was [opened read-only][crate::open::Options::read_only()].
+#[derive(Debug, thiserror::Error)]
+#[error("Refusing to write to the repository at '{}' as it was opened read-only", .git_dir.display())]
+pub struct Error {
+    /// The `.git` directory of the repository.
+    pub git_dir: std::path::PathBuf,
+}
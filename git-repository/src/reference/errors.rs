@@ -11,6 +11,8 @@ pub mod edit {
         FileTransactionCommit(#[from] git_ref::file::transaction::commit::Error),
         #[error(transparent)]
         NameValidation(#[from] git_validate::reference::name::Error),
+        #[error(transparent)]
+        ReadOnly(#[from] crate::read_only::Error),
     }
 }
 
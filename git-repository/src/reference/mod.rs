@@ -43,6 +43,14 @@ impl<'repo> Reference<'repo> {
         self.inner.name.as_ref()
     }
 
+    /// Return whether this reference is stored as loose file or in the `packed-refs` file, along with the path and the
+    /// modification time of that file, or `None` if it was deleted in the meantime.
+    ///
+    /// This is useful to decide on refresh strategies or to debug references that appear stale.
+    pub fn provenance(&self) -> Result<Option<git_ref::file::Provenance>, find::Error> {
+        Ok(self.repo.refs.provenance(self.name())?)
+    }
+
     /// Turn this instances into a stand-alone reference.
     pub fn detach(self) -> git_ref::Reference {
         self.inner
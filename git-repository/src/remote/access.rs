@@ -20,6 +20,8 @@ impl Remote<'_> {
     /// was created with one of the `_without_url_rewrite()` methods.
     /// For pushing, this is the `remote.<name>.pushUrl` or the `remote.<name>.url` used for fetching, and for fetching it's
     /// the `remote.<name>.url`.
+    /// If there is no push url, the fetch url is rewritten with `pushInsteadOf` for pushing, falling back to the fetch url
+    /// rewritten with `insteadOf`.
     /// Note that it's possible to only have the push url set, in which case there will be no way to fetch from the remote as
     /// the push-url isn't used for that.
     pub fn url(&self, direction: remote::Direction) -> Option<&git_url::Url> {
@@ -49,7 +51,7 @@ impl Remote<'_> {
             Err(err) => err.into(),
         };
         let push_url_err =
-            match remote::init::rewrite_url(&self.repo.config, self.push_url.as_ref(), remote::Direction::Push) {
+            match remote::init::rewrite_push_url(&self.repo.config, self.url.as_ref(), self.push_url.as_ref()) {
                 Ok(url) => {
                     self.push_url_alias = url;
                     None
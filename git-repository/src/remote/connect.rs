@@ -14,10 +14,16 @@ mod error {
         Connect(#[from] git_protocol::transport::client::connect::Error),
         #[error("The {} url was missing - don't know where to establish a connection to", direction.as_str())]
         MissingUrl { direction: remote::Direction },
-        #[error("Protocol named {given:?} is not a valid protocol. Choose between 1 and 2")]
+        #[error("Protocol named {given:?} is not a valid protocol. Choose between 0, 1 and 2")]
         UnknownProtocol { given: BString },
         #[error("Could not verify that file:// url is a valid git directory before attempting to use it")]
         FileUrl(#[from] git_discover::is_git::Error),
+        #[error("The transport could not be configured")]
+        Configure(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error("Could not read the bundle file to fetch from")]
+        Bundle(#[from] crate::bundle::decode::Error),
+        #[error(transparent)]
+        BundlePrerequisites(#[from] crate::bundle::verify::Error),
     }
 }
 pub use error::Error;
@@ -43,7 +49,14 @@ impl<'repo> Remote<'repo> {
     /// Connect to the url suitable for `direction` and return a handle through which operations can be performed.
     ///
     /// Note that the `protocol.version` configuration key affects the transport protocol used to connect,
-    /// with `2` being the default.
+    /// with `2` being the default. Version `0` and `1` both use the original protocol without announcing a version, which
+    /// is what older servers expect. If a server fails to produce a version 2 advertisement, the connection is downgraded
+    /// to version 1 if the transport supports it.
+    /// Connections via HTTP are configured with the [`http.*` options][crate::config::Snapshot::http_options()] that apply to the url.
+    ///
+    /// If the url points to a [bundle file][crate::bundle], its references and objects are served as if it was a remote, after
+    /// [verifying][crate::Repository::verify_bundle()] that we have all of its prerequisites. This is only supported with the
+    /// `blocking-network-client` feature.
     #[cfg(any(feature = "blocking-network-client", feature = "async-network-client-async-std"))]
     #[git_protocol::maybe_async::maybe_async]
     pub async fn connect<P>(
@@ -78,7 +91,8 @@ impl<'repo> Remote<'repo> {
             .map_err(|err| Error::UnknownProtocol { given: err.input })
             .and_then(|num| {
                 Ok(match num {
-                    1 => Protocol::V1,
+                    // Version 0 and 1 are the same on the wire, but V1 doesn't announce itself to avoid confusing old servers.
+                    0 | 1 => Protocol::V1,
                     2 => Protocol::V2,
                     num => {
                         return Err(Error::UnknownProtocol {
@@ -89,7 +103,24 @@ impl<'repo> Remote<'repo> {
             })?;
 
         let url = self.url(direction).ok_or(Error::MissingUrl { direction })?.to_owned();
-        let transport = git_protocol::transport::connect(sanitize(url)?, protocol).await?;
+        #[cfg(feature = "blocking-network-client")]
+        if url.scheme == git_url::Scheme::File {
+            let path = git_path::from_bstr(url.path.as_ref());
+            if path.is_file() {
+                let (transport, header) = crate::bundle::connect(&path)?;
+                self.repo.verify_bundle(&header)?;
+                return Ok(self.to_connection_with_transport(transport, progress));
+            }
+        }
+        let url = sanitize(url)?;
+        #[cfg_attr(not(feature = "blocking-http-transport"), allow(unused_mut))]
+        let mut transport = git_protocol::transport::connect(url.clone(), protocol).await?;
+        #[cfg(feature = "blocking-http-transport")]
+        if matches!(url.scheme, git_url::Scheme::Http | git_url::Scheme::Https) {
+            transport
+                .configure(&self.repo.config_snapshot().http_options(&url))
+                .map_err(Error::Configure)?;
+        }
         Ok(self.to_connection_with_transport(transport, progress))
     }
 }
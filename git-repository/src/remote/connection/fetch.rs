@@ -0,0 +1,481 @@
+use std::{collections::BTreeSet, io, sync::atomic::AtomicBool};
+
+use git_features::progress::Progress;
+use git_hash::ObjectId;
+use git_odb::Find;
+use git_protocol::{
+    fetch::{response::ShallowUpdate, Action, Arguments, LsRefsAction, Ref, Response},
+    transport::client::{Capabilities, Transport},
+};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    remote,
+    remote::{
+        fetch::{negotiate::Negotiator, update_refs, Options, Outcome, Prune, Tags},
+        mapping::matches,
+        Connection,
+    },
+    Repository,
+};
+
+impl<'a, 'repo, T, P> Connection<'a, 'repo, T, P>
+where
+    T: Transport,
+    P: Progress,
+{
+    /// Fetch all objects reachable from the references on the remote matching our [fetch ref-specs][crate::Remote::refspecs()],
+    /// along with tags as configured by [`fetch_tags()`][crate::Remote::fetch_tags()], write them into a new pack and
+    /// [update the local references][crate::Remote::update_refs()] accordingly, similar to `git fetch`.
+    /// Afterwards, stale references are [pruned][crate::Remote::prune()] as configured in [`Options::prune`].
+    ///
+    /// The commits to send as `have` are found with the [negotiation algorithm][crate::config::Snapshot::fetch_negotiation_algorithm()]
+    /// configured in the repository. `should_interrupt` is checked while receiving the pack.
+    ///
+    /// ### Async Mode
+    ///
+    /// With the `async-network-client` feature the pack is received without blocking and buffered in memory, to then be
+    /// indexed and written to disk, which blocks until it is done as these operations are CPU bound.
+    /// Progress for the received bytes is provided along with the one for indexing and writing.
+    #[git_protocol::maybe_async::maybe_async]
+    pub async fn fetch(self, should_interrupt: &AtomicBool, options: Options) -> Result<Outcome, remote::fetch::Error> {
+        self.fetch_inner(should_interrupt, options, false).await
+    }
+
+    /// Like [`fetch()`][Self::fetch()], but if `clone` is `true`, also want the object the remote `HEAD` points to and
+    /// adopt the object format of the remote if it differs from the one of our newly created repository.
+    #[git_protocol::maybe_async::maybe_async]
+    pub(crate) async fn fetch_inner(
+        self,
+        should_interrupt: &AtomicBool,
+        options: Options,
+        clone: bool,
+    ) -> Result<Outcome, remote::fetch::Error> {
+        let Connection {
+            remote,
+            transport,
+            progress,
+        } = self;
+        let repo = remote.repo;
+        repo.require_writable()?;
+        let tags = remote.fetch_tags();
+        let shallow_file = repo.git_dir().join("shallow");
+        let shallow = read_shallow(&shallow_file)?;
+
+        let mut negotiator = repo
+            .config_snapshot()
+            .fetch_negotiation_algorithm()?
+            .into_negotiator(repo);
+        for reference in repo.references()?.all()?.peeled().filter_map(Result::ok) {
+            if let Some(id) = reference.try_id() {
+                negotiator.add_tip(id.detach())?;
+            }
+        }
+
+        let mut delegate = Delegate {
+            repo,
+            sources: fetch_sources(remote),
+            tags,
+            depth: options.depth,
+            clone,
+            object_hash: repo.object_hash(),
+            shallow: &shallow,
+            should_interrupt,
+            negotiator,
+            window: INITIAL_WINDOW,
+            protocol_version: git_protocol::transport::Protocol::V2,
+            remote_refs: Vec::new(),
+            pack: None,
+            shallow_updates: Vec::new(),
+        };
+        let mut credentials = git_url::parse(transport.to_url().as_bytes().into())
+            .map(|url| repo.config_snapshot().credential_helpers(&url))
+            .unwrap_or_default();
+        git_protocol::fetch(
+            transport,
+            &mut delegate,
+            |action| credentials.invoke(action),
+            progress,
+            git_protocol::FetchConnection::TerminateOnSuccessfulCompletion,
+        )
+        .await?;
+        let Delegate {
+            protocol_version,
+            object_hash,
+            remote_refs,
+            pack,
+            shallow_updates,
+            ..
+        } = delegate;
+
+        // Objects of another format can only be found once the repository is reopened with its new configuration.
+        let (reopened_repo, reopened_remote);
+        let remote = if object_hash != repo.object_hash() {
+            reopened_repo = crate::Repository::from(crate::ThreadSafeRepository::open_from_paths(
+                repo.git_dir().to_owned(),
+                repo.work_dir().map(ToOwned::to_owned),
+                repo.options.clone(),
+            )?);
+            reopened_remote = crate::Remote {
+                repo: &reopened_repo,
+                ..remote.clone()
+            };
+            &reopened_remote
+        } else {
+            remote
+        };
+
+        if !shallow_updates.is_empty() {
+            let mut shallow: BTreeSet<_> = shallow.iter().copied().collect();
+            for update in shallow_updates {
+                match update {
+                    ShallowUpdate::Shallow(id) => shallow.insert(id),
+                    ShallowUpdate::Unshallow(id) => shallow.remove(&id),
+                };
+            }
+            write_shallow(&shallow_file, &shallow)?;
+        }
+
+        let update_refs = remote.update_refs(
+            &remote_refs,
+            update_refs::Options {
+                tags,
+                atomic: options.atomic,
+                packed_refs_threshold: options.packed_refs_threshold,
+                checked_out: options.checked_out,
+                ..Default::default()
+            },
+        )?;
+        let prune = match options.prune {
+            Prune::AsConfigured => remote.prune_on_fetch(),
+            Prune::Never => None,
+            Prune::Always(options) => Some(options),
+        }
+        .map(|options| remote.prune(remote_refs.iter().map(|r| r.unpack().0.as_ref()), options))
+        .transpose()?;
+        Ok(Outcome {
+            protocol_version,
+            remote_refs,
+            pack,
+            update_refs,
+            prune,
+        })
+    }
+}
+
+/// The amount of `have` lines to send in the first round of negotiation, which doubles with each round like git does.
+const INITIAL_WINDOW: usize = 16;
+
+fn to_io_error(err: impl std::error::Error + Send + Sync + 'static) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err)
+}
+
+/// Return the ids of all shallow commits listed in `path`, or nothing if the repository isn't shallow.
+fn read_shallow(path: &std::path::Path) -> io::Result<Vec<ObjectId>> {
+    match std::fs::read(path) {
+        Ok(content) => content
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| ObjectId::from_hex(line).map_err(to_io_error))
+            .collect(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+fn write_shallow(path: &std::path::Path, shallow: &BTreeSet<ObjectId>) -> io::Result<()> {
+    if shallow.is_empty() {
+        return match std::fs::remove_file(path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        };
+    }
+    let mut content = BString::default();
+    for id in shallow {
+        content.extend_from_slice(id.to_hex().to_string().as_bytes());
+        content.push(b'\n');
+    }
+    std::fs::write(path, content)
+}
+
+/// Configure the newly cloned `repo` to use `object_hash` like `git clone` does when it learns the object format of the remote.
+fn adopt_object_format(repo: &Repository, object_hash: git_hash::Kind) -> io::Result<()> {
+    let config_path = repo.git_dir().join("config");
+    let mut config =
+        git_config::File::from_path_no_includes(config_path.clone(), git_config::Source::Local).map_err(to_io_error)?;
+    config
+        .set_raw_value("core", None, "repositoryFormatVersion", "1")
+        .map_err(to_io_error)?;
+    config
+        .set_raw_value(
+            "extensions",
+            None,
+            "objectFormat",
+            object_hash.to_string().to_lowercase().as_str(),
+        )
+        .map_err(to_io_error)?;
+    std::fs::write(config_path, config.to_bstring())
+}
+
+/// Return the source patterns of all fetch ref-specs of `remote` which update a local reference.
+fn fetch_sources(remote: &crate::Remote<'_>) -> Vec<BString> {
+    remote
+        .refspecs(remote::Direction::Fetch)
+        .iter()
+        .filter_map(|spec| match spec.to_ref().instruction() {
+            git_refspec::Instruction::Fetch(git_refspec::instruction::Fetch::AndUpdate { src, .. }) => {
+                Some(src.to_owned())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Receive a pack with all objects reachable from the references matching our fetch ref-specs that we don't have yet,
+/// negotiating the commits we have in common with the remote.
+struct Delegate<'a> {
+    repo: &'a Repository,
+    sources: Vec<BString>,
+    tags: Tags,
+    depth: Option<std::num::NonZeroU32>,
+    clone: bool,
+    object_hash: git_hash::Kind,
+    shallow: &'a [ObjectId],
+    should_interrupt: &'a AtomicBool,
+    negotiator: Box<dyn Negotiator + 'a>,
+    window: usize,
+    protocol_version: git_protocol::transport::Protocol,
+    remote_refs: Vec<Ref>,
+    pack: Option<git_pack::bundle::write::Outcome>,
+    shallow_updates: Vec<ShallowUpdate>,
+}
+
+impl Delegate<'_> {
+    fn wants(&self, name: &BStr) -> bool {
+        (self.clone && name == "HEAD")
+            || (self.depth.is_none() && self.tags != Tags::None && name.starts_with(b"refs/tags/"))
+            || self.sources.iter().any(|src| matches(src.as_ref(), name))
+    }
+
+    /// Return the ids of all objects we want and don't have yet.
+    fn missing_wants(&self, refs: &[Ref]) -> BTreeSet<ObjectId> {
+        refs.iter()
+            .map(|r| r.unpack())
+            .filter(|(name, id)| self.wants(name.as_ref()) && (self.depth.is_some() || !self.has_object(id)))
+            .map(|(_, id)| *id)
+            .collect()
+    }
+
+    /// Return `true` if `id` exists in our repository, which has no objects yet if we just adopted the object format of the remote.
+    fn has_object(&self, id: &ObjectId) -> bool {
+        self.object_hash == self.repo.object_hash() && self.repo.objects.contains(id)
+    }
+
+    /// Add up to `window` `have` lines to `arguments` and return `true` if there might be more.
+    fn add_haves(&mut self, arguments: &mut Arguments) -> io::Result<bool> {
+        for _ in 0..self.window {
+            match self.negotiator.next_have() {
+                Some(id) => arguments.have(id.map_err(to_io_error)?),
+                None => return Ok(false),
+            }
+        }
+        self.window *= 2;
+        Ok(true)
+    }
+
+    /// Fail if the object format of the `server` differs from ours, unless we are cloning and can adopt it, and
+    /// request our object format in `features` unless it's the default.
+    fn match_object_format(
+        &mut self,
+        server: &Capabilities,
+        features: &mut Vec<(&str, Option<&str>)>,
+    ) -> io::Result<()> {
+        // Servers that don't announce an object format use SHA1.
+        let local_format = self.object_hash.to_string().to_ascii_lowercase();
+        let remote_format = server
+            .capability("object-format")
+            .and_then(|c| c.value().map(ToOwned::to_owned))
+            .unwrap_or_else(|| "sha1".into());
+        if remote_format != local_format {
+            match remote_format
+                .to_str()
+                .ok()
+                .and_then(|format| format.parse::<git_hash::Kind>().ok())
+            {
+                Some(object_hash) if self.clone => {
+                    adopt_object_format(self.repo, object_hash)?;
+                    self.object_hash = object_hash;
+                }
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "The remote uses the object format {:?}, but the local repository uses {:?}",
+                            remote_format, local_format
+                        ),
+                    ))
+                }
+            }
+        }
+        if self.object_hash != git_hash::Kind::Sha1 {
+            features.push(("object-format", Some("sha256")));
+        }
+        Ok(())
+    }
+
+    fn store_pack(&mut self, input: impl io::BufRead, progress: impl Progress, response: &Response) -> io::Result<()> {
+        let repo = self.repo;
+        let pack_directory = repo.objects.store_ref().path().join("pack");
+        let outcome = git_pack::Bundle::write_to_directory(
+            input,
+            Some(pack_directory),
+            progress,
+            self.should_interrupt,
+            None,
+            git_pack::bundle::write::Options {
+                object_hash: self.object_hash,
+                fsync: repo.config.fsync.method_if(repo.config.fsync.packs),
+                ..Default::default()
+            },
+        )
+        .map_err(to_io_error)?;
+        self.pack = Some(outcome);
+        self.shallow_updates = response.shallow_updates().to_vec();
+        Ok(())
+    }
+}
+
+impl git_protocol::fetch::DelegateBlocking for Delegate<'_> {
+    fn prepare_ls_refs(
+        &mut self,
+        server: &Capabilities,
+        _arguments: &mut Vec<BString>,
+        features: &mut Vec<(&str, Option<&str>)>,
+    ) -> io::Result<LsRefsAction> {
+        self.match_object_format(server, features)?;
+        Ok(LsRefsAction::Continue)
+    }
+
+    fn prepare_fetch(
+        &mut self,
+        version: git_protocol::transport::Protocol,
+        server: &Capabilities,
+        features: &mut Vec<(&str, Option<&str>)>,
+        refs: &[Ref],
+    ) -> io::Result<Action> {
+        self.protocol_version = version;
+        self.match_object_format(server, features)?;
+        // Our depth is absolute and counted from the tips of the remote, like it is in git.
+        features.retain(|(name, _)| *name != "deepen-relative");
+        self.remote_refs = refs.to_vec();
+        Ok(if self.missing_wants(refs).is_empty() {
+            Action::Cancel
+        } else {
+            Action::Continue
+        })
+    }
+
+    fn negotiate(
+        &mut self,
+        refs: &[Ref],
+        arguments: &mut Arguments,
+        previous_response: Option<&Response>,
+    ) -> io::Result<Action> {
+        match previous_response {
+            None => {
+                for id in self.missing_wants(refs) {
+                    arguments.want(id);
+                }
+                for id in self.shallow {
+                    arguments.shallow(id);
+                }
+                if let Some(depth) = self.depth {
+                    if !arguments.can_use_deepen() {
+                        return Err(io::Error::new(
+                            io::ErrorKind::Other,
+                            "The remote doesn't support shallow fetches",
+                        ));
+                    }
+                    arguments.deepen(depth.get() as usize);
+                }
+                for (_, id) in refs.iter().map(|r| r.unpack()) {
+                    if self.has_object(id) {
+                        self.negotiator.known_common(*id).map_err(to_io_error)?;
+                    }
+                }
+            }
+            Some(response) => {
+                for ack in response.acknowledgements() {
+                    if let git_protocol::fetch::response::Acknowledgement::Common(id) = ack {
+                        self.negotiator.in_common_with_remote(*id).map_err(to_io_error)?;
+                    }
+                }
+            }
+        }
+        Ok(if self.add_haves(arguments)? {
+            Action::Continue
+        } else {
+            Action::Cancel
+        })
+    }
+}
+
+#[cfg(feature = "blocking-network-client")]
+impl git_protocol::fetch::Delegate for Delegate<'_> {
+    fn receive_pack(
+        &mut self,
+        input: impl io::BufRead,
+        progress: impl Progress,
+        _refs: &[Ref],
+        response: &Response,
+    ) -> io::Result<()> {
+        self.store_pack(input, progress, response)
+    }
+}
+
+#[cfg(feature = "async-network-client")]
+mod async_io {
+    use std::{io, sync::atomic::Ordering};
+
+    use futures_lite::io::AsyncBufReadExt;
+    use git_features::progress::Progress;
+    use git_protocol::{
+        fetch::{Ref, Response},
+        futures_io::AsyncBufRead,
+    };
+
+    use super::Delegate;
+
+    #[git_protocol::async_trait::async_trait(?Send)]
+    impl git_protocol::fetch::Delegate for Delegate<'_> {
+        async fn receive_pack(
+            &mut self,
+            mut input: impl AsyncBufRead + Unpin + 'async_trait,
+            mut progress: impl Progress,
+            _refs: &[Ref],
+            response: &Response,
+        ) -> io::Result<()> {
+            let mut pack = Vec::new();
+            {
+                let mut receive_progress = progress.add_child("receiving");
+                receive_progress.init(None, git_features::progress::bytes());
+                loop {
+                    if self.should_interrupt.load(Ordering::Relaxed) {
+                        return Err(io::Error::new(io::ErrorKind::Interrupted, "Interrupted by user"));
+                    }
+                    let num_bytes = {
+                        let data = input.fill_buf().await?;
+                        pack.extend_from_slice(data);
+                        data.len()
+                    };
+                    if num_bytes == 0 {
+                        break;
+                    }
+                    input.consume(num_bytes);
+                    receive_progress.inc_by(num_bytes);
+                }
+            }
+            self.store_pack(pack.as_slice(), progress, response)
+        }
+    }
+}
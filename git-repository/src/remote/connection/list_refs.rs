@@ -1,20 +1,11 @@
-use crate::remote::connection::HandshakeWithRefs;
-use crate::remote::{Connection, Direction};
 use git_features::progress::Progress;
 use git_protocol::transport::client::Transport;
 
-mod error {
-    #[derive(Debug, thiserror::Error)]
-    pub enum Error {
-        #[error(transparent)]
-        Handshake(#[from] git_protocol::fetch::handshake::Error),
-        #[error(transparent)]
-        ListRefs(#[from] git_protocol::fetch::refs::Error),
-        #[error(transparent)]
-        Transport(#[from] git_protocol::transport::client::Error),
-    }
-}
-pub use error::Error;
+use crate::remote::{
+    connection::HandshakeWithRefs,
+    list_refs::{Error, Options},
+    Connection, Direction,
+};
 
 impl<'a, 'repo, T, P> Connection<'a, 'repo, T, P>
 where
@@ -25,17 +16,36 @@ where
     ///
     /// Note that this doesn't fetch the objects mentioned in the tips nor does it make any change to underlying repository.
     #[git_protocol::maybe_async::maybe_async]
-    pub async fn list_refs(mut self) -> Result<Vec<git_protocol::fetch::Ref>, Error> {
-        let res = self.fetch_refs().await?;
+    pub async fn list_refs(self) -> Result<Vec<git_protocol::fetch::Ref>, Error> {
+        self.list_refs_with_options(Options::default()).await
+    }
+
+    /// List the references on the remote that match the filters in `options`, see [`Options::filters`] for details.
+    ///
+    /// Note that this doesn't fetch the objects mentioned in the tips nor does it make any change to underlying repository.
+    #[git_protocol::maybe_async::maybe_async]
+    pub async fn list_refs_with_options(mut self, options: Options) -> Result<Vec<git_protocol::fetch::Ref>, Error> {
+        let mut res = self.fetch_refs(&options.ref_prefixes()).await?;
         git_protocol::fetch::indicate_end_of_interaction(&mut self.transport).await?;
+        res.refs.retain(|r| options.matches(r.unpack().0.as_ref()));
         Ok(res.refs)
     }
 
+    /// Perform the handshake and obtain the references of the remote, restricted to the given `ref_prefixes` if not empty
+    /// and if the protocol supports it.
     #[git_protocol::maybe_async::maybe_async]
-    async fn fetch_refs(&mut self) -> Result<HandshakeWithRefs, Error> {
+    async fn fetch_refs(&mut self, ref_prefixes: &[crate::bstr::BString]) -> Result<HandshakeWithRefs, Error> {
+        // Without configured helpers, let `git credential` handle credentials as it did before we invoked helpers ourselves.
+        let mut credentials = git_url::parse(self.transport.to_url().as_bytes().into())
+            .ok()
+            .map(|url| self.remote.repo.config_snapshot().credential_helpers(&url))
+            .filter(|cascade| !cascade.programs.is_empty());
         let mut outcome = git_protocol::fetch::handshake(
             &mut self.transport,
-            git_protocol::credentials::helper,
+            |action| match credentials.as_mut() {
+                Some(cascade) => cascade.invoke(action),
+                None => git_protocol::credentials::helper(action),
+            },
             Vec::new(),
             &mut self.progress,
         )
@@ -47,7 +57,14 @@ where
                     &mut self.transport,
                     outcome.server_protocol_version,
                     &outcome.capabilities,
-                    |_a, _b, _c| Ok(git_protocol::fetch::delegate::LsRefsAction::Continue),
+                    |_capabilities, arguments, _features| {
+                        arguments.extend(
+                            ref_prefixes
+                                .iter()
+                                .map(|prefix| format!("ref-prefix {}", prefix).into()),
+                        );
+                        Ok(git_protocol::fetch::delegate::LsRefsAction::Continue)
+                    },
                     &mut self.progress,
                 )
                 .await?
@@ -29,4 +29,8 @@ mod access {
     }
 }
 
-mod list_refs;
+mod fetch;
+
+pub(crate) mod list_refs;
+
+mod prune;
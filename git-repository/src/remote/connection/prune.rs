@@ -0,0 +1,19 @@
+use git_features::progress::Progress;
+use git_protocol::transport::client::Transport;
+
+use crate::remote::{prune, Connection};
+
+impl<'a, 'repo, T, P> Connection<'a, 'repo, T, P>
+where
+    T: Transport,
+    P: Progress,
+{
+    /// List all references on the remote and [prune][crate::Remote::prune()] all local references that were fetched from it
+    /// but whose counterpart doesn't exist there anymore, similar to `git remote prune`.
+    #[git_protocol::maybe_async::maybe_async]
+    pub async fn prune(self, options: prune::Options) -> Result<prune::Outcome, prune::Error> {
+        let remote = self.remote;
+        let refs = self.list_refs().await?;
+        remote.prune(refs.iter().map(|r| r.unpack().0.as_ref()), options)
+    }
+}
@@ -0,0 +1,75 @@
+use crate::Remote;
+
+mod error {
+    /// The error returned by [`Repository::maintain_after_fetch()`][crate::Repository::maintain_after_fetch()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read the index of the received pack")]
+        OpenPackIndex(#[from] git_pack::index::init::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] git_object::decode::Error),
+        #[error("Could not read existing commit-graph data")]
+        ReadCommitGraph(#[from] git_commitgraph::file::commit::Error),
+        #[error(transparent)]
+        WriteCommitGraph(#[from] git_commitgraph::file::write::Error),
+        #[error(transparent)]
+        WriteMultiPackIndex(#[from] git_pack::multi_index::write::Error),
+        #[error(transparent)]
+        ReadOnly(#[from] crate::read_only::Error),
+        #[error("Could not lock '{}' for writing", .path.display())]
+        AcquireLock {
+            source: git_lock::acquire::Error,
+            path: std::path::PathBuf,
+        },
+        #[error("Could not commit the lock to '{}'", .path.display())]
+        CommitLock {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+pub use error::Error;
+
+/// Options for [`Repository::maintain_after_fetch()`][crate::Repository::maintain_after_fetch()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If `true`, add the commits of the received pack to the commit-graph at `objects/info/commit-graph`, creating it if needed,
+    /// similar to `git fetch --write-commit-graph`.
+    pub write_commit_graph: bool,
+    /// If `true`, rewrite the multi-pack-index at `objects/pack/multi-pack-index` to include the received pack along with all
+    /// other packs.
+    pub write_multi_pack_index: bool,
+}
+
+/// The outcome of [`Repository::maintain_after_fetch()`][crate::Repository::maintain_after_fetch()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Outcome {
+    /// The amount of commits in the commit-graph written, or `None` if no commit-graph was written.
+    pub commit_graph_commits: Option<u32>,
+    /// The checksum of the multi-pack-index written, or `None` if no multi-pack-index was written.
+    pub multi_pack_index_checksum: Option<git_hash::ObjectId>,
+}
+
+/// Maintenance
+impl Remote<'_> {
+    /// Return the maintenance to perform after fetching, which is writing the commit-graph if `fetch.writeCommitGraph` is `true`.
+    ///
+    /// Note that there is no configuration to write the multi-pack-index, it has to be turned on explicitly.
+    pub fn maintenance_on_fetch(&self) -> Options {
+        Options {
+            write_commit_graph: self
+                .repo
+                .config
+                .resolved
+                .boolean("fetch", None, "writeCommitGraph")
+                .and_then(Result::ok)
+                .unwrap_or(false),
+            write_multi_pack_index: false,
+        }
+    }
+}
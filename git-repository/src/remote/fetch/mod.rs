@@ -0,0 +1,200 @@
+//! Types and algorithms used when fetching from a remote.
+use crate::{
+    bstr::{BStr, BString},
+    Remote,
+};
+
+///
+pub mod maintenance;
+
+///
+pub mod negotiate;
+
+///
+#[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+pub mod update_refs;
+
+#[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+mod error {
+    /// The error returned by [`Connection::fetch()`][crate::remote::Connection::fetch()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        NegotiationAlgorithm(#[from] crate::remote::fetch::negotiate::algorithm::Error),
+        #[error(transparent)]
+        Negotiate(#[from] crate::remote::fetch::negotiate::Error),
+        #[error(transparent)]
+        References(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        IterateReferences(#[from] crate::reference::iter::init::Error),
+        #[error(transparent)]
+        Fetch(#[from] git_protocol::fetch::Error),
+        #[error(transparent)]
+        UpdateRefs(#[from] crate::remote::fetch::update_refs::Error),
+        #[error("Could not read or write the list of shallow commits")]
+        Shallow(#[from] std::io::Error),
+        #[error(transparent)]
+        ReadOnly(#[from] crate::read_only::Error),
+        #[error(transparent)]
+        Prune(#[from] crate::remote::prune::Error),
+        #[error("Could not reopen the repository after adopting the object format of the remote")]
+        Reopen(#[from] crate::open::Error),
+    }
+}
+#[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+pub use error::Error;
+
+/// Options for [`Connection::fetch()`][crate::remote::Connection::fetch()].
+#[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If set, only fetch the last `depth` commits of each tip, making the repository shallow, similar to `git fetch --depth`.
+    ///
+    /// Note that tags aren't fetched then as they would typically deepen the history.
+    pub depth: Option<std::num::NonZeroU32>,
+    /// If `true`, update all local references in a single transaction, similar to `git fetch --atomic`.
+    pub atomic: bool,
+    /// If set, write updated references into the `packed-refs` file if there are at least this many, see
+    /// [`update_refs::Options::packed_refs_threshold`].
+    pub packed_refs_threshold: Option<usize>,
+    /// How to handle updates to branches that are checked out in a worktree, see [`update_refs::Options::checked_out`].
+    pub checked_out: DenyCurrentBranch,
+    /// Whether to [prune][Remote::prune()] local references whose counterpart doesn't exist on the remote anymore after
+    /// updating them.
+    pub prune: Prune,
+}
+
+/// Whether to prune stale references after fetching, see [`Options::prune`].
+#[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Prune {
+    /// Prune as configured with `fetch.prune` and `remote.<name>.prune`, see [`Remote::prune_on_fetch()`], which is the default.
+    AsConfigured,
+    /// Don't prune, similar to `git fetch --no-prune`.
+    Never,
+    /// Prune with the given options, similar to `git fetch --prune`.
+    Always(crate::remote::prune::Options),
+}
+
+#[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+impl Default for Prune {
+    fn default() -> Self {
+        Prune::AsConfigured
+    }
+}
+
+/// The outcome of [`Connection::fetch()`][crate::remote::Connection::fetch()].
+#[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+pub struct Outcome {
+    /// The protocol version negotiated with the remote, which may be lower than the configured `protocol.version`
+    /// if the remote doesn't support it.
+    pub protocol_version: git_protocol::transport::Protocol,
+    /// All references advertised by the remote.
+    pub remote_refs: Vec<git_protocol::fetch::Ref>,
+    /// Information about the received pack, or `None` if we had all objects already and nothing was fetched.
+    pub pack: Option<git_pack::bundle::write::Outcome>,
+    /// The local references that were updated from the references of the remote.
+    pub update_refs: update_refs::Outcome,
+    /// The local references that were considered for pruning, or `None` if [pruning][Options::prune] wasn't enabled.
+    pub prune: Option<crate::remote::prune::Outcome>,
+}
+
+/// How to handle tags when fetching, as configured with `remote.<name>.tagOpt`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Tags {
+    /// Don't fetch any tags, similar to `git fetch --no-tags`.
+    None,
+    /// Automatically follow tags pointing to objects in the history that is fetched anyway, which is the default.
+    ///
+    /// Servers supporting the `include-tag` capability send the respective tag objects along with the pack,
+    /// which avoids another round-trip to fetch them.
+    Included,
+    /// Fetch all tags as if `refs/tags/*:refs/tags/*` was one of the fetch ref-specs, similar to `git fetch --tags`.
+    All,
+}
+
+impl Default for Tags {
+    fn default() -> Self {
+        Tags::Included
+    }
+}
+
+///
+pub mod deny_current_branch {
+    use crate::bstr::BString;
+
+    /// The error returned when parsing a [`DenyCurrentBranch`][super::DenyCurrentBranch] from `receive.denyCurrentBranch`.
+    #[derive(Debug, thiserror::Error)]
+    #[error("Invalid value {value:?} for receive.denyCurrentBranch, expected a boolean, 'refuse', 'warn', 'ignore' or 'updateInstead'")]
+    pub struct Error {
+        /// The invalid value.
+        pub value: BString,
+    }
+}
+
+/// How to handle updates to a branch that is checked out in the main worktree or any linked worktree, as configured
+/// with `receive.denyCurrentBranch` when receiving a push.
+///
+/// Updating such a branch makes its index and worktree look like they undo the update, which is why fetches refuse to do
+/// it by default, too.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DenyCurrentBranch {
+    /// Reject the update, which is the default and what `git fetch` does unless `--update-head-ok` is given.
+    Refuse,
+    /// Update the branch, but callers are expected to warn about it, which is possible as such updates are
+    /// [marked with their worktree][update_refs::Update::worktree].
+    Warn,
+    /// Update the branch and leave its index and worktree as they are, similar to `git fetch --update-head-ok`.
+    Ignore,
+    /// Update the branch and check out its new tree, but reject the update if the worktree has any changes.
+    UpdateInstead,
+}
+
+impl Default for DenyCurrentBranch {
+    fn default() -> Self {
+        DenyCurrentBranch::Refuse
+    }
+}
+
+impl std::convert::TryFrom<&BStr> for DenyCurrentBranch {
+    type Error = deny_current_branch::Error;
+
+    /// Parse the value of `receive.denyCurrentBranch`, which is a boolean or one of its keywords, ignoring their case.
+    fn try_from(value: &BStr) -> Result<Self, Self::Error> {
+        Ok(match value.to_ascii_lowercase().as_slice() {
+            b"refuse" => DenyCurrentBranch::Refuse,
+            b"warn" => DenyCurrentBranch::Warn,
+            b"ignore" => DenyCurrentBranch::Ignore,
+            b"updateinstead" => DenyCurrentBranch::UpdateInstead,
+            _ => match git_config::Boolean::try_from(value).map(|boolean| boolean.0) {
+                Ok(true) => DenyCurrentBranch::Refuse,
+                Ok(false) => DenyCurrentBranch::Ignore,
+                Err(_) => {
+                    return Err(deny_current_branch::Error {
+                        value: BString::from(value),
+                    })
+                }
+            },
+        })
+    }
+}
+
+/// Fetching
+impl Remote<'_> {
+    /// Return how tags should be handled when fetching from this remote, as configured with `remote.<name>.tagOpt`,
+    /// which is either `--no-tags` or `--tags`.
+    ///
+    /// Unknown values and remotes without a name [follow tags][Tags::Included] by default.
+    pub fn fetch_tags(&self) -> Tags {
+        let tag_opt = self
+            .name
+            .as_deref()
+            .and_then(|name| self.repo.config.resolved.string("remote", Some(name), "tagOpt"));
+        match tag_opt.as_deref().map(|value| value.as_ref()) {
+            Some(b"--no-tags") => Tags::None,
+            Some(b"--tags") => Tags::All,
+            _ => Tags::Included,
+        }
+    }
+}
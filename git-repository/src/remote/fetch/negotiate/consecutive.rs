@@ -0,0 +1,144 @@
+use git_hash::ObjectId;
+
+use super::{Error, Flags, Graph, Negotiator, Queue};
+use crate::Repository;
+
+/// The commit is known to be in common with the remote.
+const COMMON: Flags = 1 << 0;
+/// The commit is the tip of a reference the remote advertised and we have.
+const COMMON_REF: Flags = 1 << 1;
+/// The commit was put into the queue.
+const SEEN: Flags = 1 << 2;
+/// The commit was taken out of the queue.
+const POPPED: Flags = 1 << 3;
+
+/// The `consecutive` algorithm as implemented in git's `negotiator/default.c`.
+pub(crate) struct Algorithm<'repo> {
+    graph: Graph<'repo>,
+    revs: Queue,
+    non_common_revs: isize,
+}
+
+impl<'repo> Algorithm<'repo> {
+    pub fn new(repo: &'repo Repository) -> Self {
+        Algorithm {
+            graph: Graph::new(repo),
+            revs: Queue::default(),
+            non_common_revs: 0,
+        }
+    }
+
+    /// Add `id` to the queue unless it has all flags in `mark` already, and set them.
+    fn add_to_queue(&mut self, id: ObjectId, mark: Flags) -> Result<(), Error> {
+        if self.graph.flags(&id) & mark != 0 {
+            return Ok(());
+        }
+        self.graph.add_flags(id, mark);
+        if !self.graph.parse(id)? {
+            return Ok(());
+        }
+        self.revs.push(id, self.graph.time(&id));
+        if self.graph.flags(&id) & COMMON == 0 {
+            self.non_common_revs += 1;
+        }
+        Ok(())
+    }
+
+    /// Mark `id` and all of its ancestors as common, or only its ancestors if `ancestors_only` is set.
+    /// If `dont_parse` is set, only ancestors of commits that were parsed already are marked.
+    fn mark_common(&mut self, id: ObjectId, ancestors_only: bool, dont_parse: bool) -> Result<(), Error> {
+        if self.graph.flags(&id) & COMMON != 0 {
+            return Ok(());
+        }
+        let mut queue = Queue::default();
+        queue.push(id, self.graph.time(&id));
+        if !ancestors_only {
+            self.mark_common_and_count(id);
+        }
+        while let Some(id) = queue.pop() {
+            if self.graph.flags(&id) & SEEN == 0 {
+                self.add_to_queue(id, SEEN)?;
+                continue;
+            }
+            if !dont_parse && !self.graph.parse(id)? {
+                continue;
+            }
+            for parent_id in self.graph.parents(&id) {
+                if self.graph.flags(&parent_id) & COMMON != 0 {
+                    continue;
+                }
+                self.mark_common_and_count(parent_id);
+                queue.push(parent_id, self.graph.time(&parent_id));
+            }
+        }
+        Ok(())
+    }
+
+    fn mark_common_and_count(&mut self, id: ObjectId) {
+        self.graph.add_flags(id, COMMON);
+        let flags = self.graph.flags(&id);
+        if flags & SEEN != 0 && flags & POPPED == 0 {
+            self.non_common_revs -= 1;
+        }
+    }
+}
+
+impl Negotiator for Algorithm<'_> {
+    fn known_common(&mut self, id: ObjectId) -> Result<(), Error> {
+        if self.graph.flags(&id) & SEEN == 0 {
+            self.add_to_queue(id, COMMON_REF | SEEN)?;
+            self.mark_common(id, true, true)?;
+        }
+        Ok(())
+    }
+
+    fn add_tip(&mut self, id: ObjectId) -> Result<(), Error> {
+        self.add_to_queue(id, SEEN)
+    }
+
+    fn next_have(&mut self) -> Option<Result<ObjectId, Error>> {
+        loop {
+            if self.non_common_revs == 0 {
+                return None;
+            }
+            let id = self.revs.pop()?;
+            let res = (|| {
+                self.graph.add_flags(id, POPPED);
+                let flags = self.graph.flags(&id);
+                if flags & COMMON == 0 {
+                    self.non_common_revs -= 1;
+                }
+                let (send, mark) = if flags & COMMON != 0 {
+                    // do not send "have", and ignore ancestors
+                    (false, COMMON | SEEN)
+                } else if flags & COMMON_REF != 0 {
+                    // send "have", and ignore ancestors
+                    (true, COMMON | SEEN)
+                } else {
+                    // send "have", also for its ancestors
+                    (true, SEEN)
+                };
+                for parent_id in self.graph.parents(&id) {
+                    if self.graph.flags(&parent_id) & SEEN == 0 {
+                        self.add_to_queue(parent_id, mark)?;
+                    }
+                    if mark & COMMON != 0 {
+                        self.mark_common(parent_id, true, false)?;
+                    }
+                }
+                Ok(send)
+            })();
+            match res {
+                Ok(true) => return Some(Ok(id)),
+                Ok(false) => continue,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+
+    fn in_common_with_remote(&mut self, id: ObjectId) -> Result<bool, Error> {
+        let known_to_be_common = self.graph.flags(&id) & COMMON != 0;
+        self.mark_common(id, false, true)?;
+        Ok(known_to_be_common)
+    }
+}
@@ -0,0 +1,262 @@
+//! Algorithms to find the commits to send to the remote as `have` while fetching, so it can figure out which objects we need.
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use git_hash::ObjectId;
+use git_odb::Find;
+
+use crate::{
+    bstr::{BStr, BString},
+    Repository,
+};
+
+mod consecutive;
+mod skipping;
+
+/// The error returned by [`Negotiator`] methods.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindExisting(#[from] git_odb::store::find::Error),
+    #[error(transparent)]
+    Decode(#[from] git_object::decode::Error),
+}
+
+///
+pub mod algorithm {
+    use crate::bstr::BString;
+
+    /// The error returned when parsing an [`Algorithm`][super::Algorithm] from `fetch.negotiationAlgorithm`.
+    #[derive(Debug, thiserror::Error)]
+    #[error(
+        "Unknown fetch negotiation algorithm {name:?}, expected one of 'consecutive', 'skipping', 'noop' or 'default'"
+    )]
+    pub struct Error {
+        /// The name of the unknown algorithm.
+        pub name: BString,
+    }
+}
+
+/// The way the negotiation is performed, as configured with `fetch.negotiationAlgorithm`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// Do not send any information at all, which typically leads to complete packs to be sent.
+    Noop,
+    /// Walk over consecutive commits and check each one. This can be costly but assures packs are exactly the size they need to be.
+    Consecutive,
+    /// Like `Consecutive`, but skips commits in exponentially growing steps, which sends far fewer `have` lines for long
+    /// histories at the cost of receiving slightly larger packs.
+    Skipping,
+}
+
+impl Default for Algorithm {
+    fn default() -> Self {
+        Algorithm::Consecutive
+    }
+}
+
+impl std::convert::TryFrom<&BStr> for Algorithm {
+    type Error = algorithm::Error;
+
+    /// Parse the value of `fetch.negotiationAlgorithm`, ignoring its case like git does.
+    fn try_from(name: &BStr) -> Result<Self, Self::Error> {
+        Ok(match name.to_ascii_lowercase().as_slice() {
+            b"noop" => Algorithm::Noop,
+            b"consecutive" | b"default" => Algorithm::Consecutive,
+            b"skipping" => Algorithm::Skipping,
+            _ => {
+                return Err(algorithm::Error {
+                    name: BString::from(name),
+                })
+            }
+        })
+    }
+}
+
+impl Algorithm {
+    /// Create a negotiator implementing this algorithm which looks up commits in `repo`.
+    pub fn into_negotiator(self, repo: &Repository) -> Box<dyn Negotiator + '_> {
+        match self {
+            Algorithm::Noop => Box::new(Noop),
+            Algorithm::Consecutive => Box::new(consecutive::Algorithm::new(repo)),
+            Algorithm::Skipping => Box::new(skipping::Algorithm::new(repo)),
+        }
+    }
+}
+
+/// A trait to encapsulate negotiation algorithms, which are used to figure out which commits the remote has in common with us.
+///
+/// Its methods are expected to be called in the order they are declared, where `known_common()` and `add_tip()` are called
+/// for all commits before the first `next_have()`, and `in_common_with_remote()` is called once for each commit the remote
+/// acknowledged in response to our `have` lines before obtaining more of them.
+pub trait Negotiator {
+    /// Mark the commit `id` as known to be common between the remote and us, typically because a tip advertised by the remote
+    /// is present in our repository.
+    fn known_common(&mut self, id: ObjectId) -> Result<(), Error>;
+    /// Add the commit `id` as starting point of the traversal to find commits to send as `have`, typically the tips of all
+    /// our references.
+    fn add_tip(&mut self, id: ObjectId) -> Result<(), Error>;
+    /// Produce the next commit to send to the remote as `have`, or `None` if there are no more commits worth sending.
+    fn next_have(&mut self) -> Option<Result<ObjectId, Error>>;
+    /// Mark the commit `id` as acknowledged by the remote, and return `true` if it was already known to be common.
+    fn in_common_with_remote(&mut self, id: ObjectId) -> Result<bool, Error>;
+}
+
+/// A negotiator that doesn't send any `have` lines.
+struct Noop;
+
+impl Negotiator for Noop {
+    fn known_common(&mut self, _id: ObjectId) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn add_tip(&mut self, _id: ObjectId) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn next_have(&mut self) -> Option<Result<ObjectId, Error>> {
+        None
+    }
+
+    fn in_common_with_remote(&mut self, _id: ObjectId) -> Result<bool, Error> {
+        Ok(false)
+    }
+}
+
+type Flags = u8;
+
+struct Commit {
+    flags: Flags,
+    /// The parents of the commit, or `None` if it wasn't parsed yet.
+    parents: Option<Vec<ObjectId>>,
+    /// The commit time, or 0 if the commit wasn't parsed yet.
+    time: u32,
+}
+
+/// Commits along with their flags, lazily parsed as needed, similar to how git handles commit objects.
+struct Graph<'repo> {
+    repo: &'repo Repository,
+    commits: git_revision::hash_hasher::HashedMap<ObjectId, Commit>,
+    buf: Vec<u8>,
+}
+
+impl<'repo> Graph<'repo> {
+    fn new(repo: &'repo Repository) -> Self {
+        Graph {
+            repo,
+            commits: Default::default(),
+            buf: Vec::new(),
+        }
+    }
+
+    fn entry(&mut self, id: ObjectId) -> &mut Commit {
+        self.commits.entry(id).or_insert(Commit {
+            flags: 0,
+            parents: None,
+            time: 0,
+        })
+    }
+
+    fn flags(&self, id: &ObjectId) -> Flags {
+        self.commits.get(id).map(|c| c.flags).unwrap_or_default()
+    }
+
+    fn add_flags(&mut self, id: ObjectId, flags: Flags) {
+        self.entry(id).flags |= flags;
+    }
+
+    fn time(&self, id: &ObjectId) -> u32 {
+        self.commits.get(id).map(|c| c.time).unwrap_or_default()
+    }
+
+    fn is_parsed(&self, id: &ObjectId) -> bool {
+        self.commits.get(id).map(|c| c.parents.is_some()) == Some(true)
+    }
+
+    /// Return the parents of `id` if it was parsed, or an empty list otherwise.
+    fn parents(&self, id: &ObjectId) -> Vec<ObjectId> {
+        self.commits.get(id).and_then(|c| c.parents.clone()).unwrap_or_default()
+    }
+
+    /// Parse the commit `id` unless it was parsed already, and return `false` if it doesn't exist.
+    fn parse(&mut self, id: ObjectId) -> Result<bool, Error> {
+        if self.is_parsed(&id) {
+            return Ok(true);
+        }
+        let commit_iter = match self
+            .repo
+            .objects
+            .try_find(id, &mut self.buf)?
+            .and_then(|data| data.try_into_commit_iter())
+        {
+            Some(iter) => iter,
+            None => return Ok(false),
+        };
+        let mut parents = Vec::new();
+        let mut time = 0;
+        for token in commit_iter {
+            match token? {
+                git_object::commit::ref_iter::Token::Tree { .. } => continue,
+                git_object::commit::ref_iter::Token::Parent { id } => parents.push(id),
+                git_object::commit::ref_iter::Token::Author { .. } => continue,
+                git_object::commit::ref_iter::Token::Committer { signature } => {
+                    time = signature.time.seconds_since_unix_epoch;
+                    break;
+                }
+                _ => break,
+            }
+        }
+        let commit = self.entry(id);
+        commit.parents = Some(parents);
+        commit.time = time;
+        Ok(true)
+    }
+}
+
+/// A priority queue of commits ordered by commit time, newest first, and in insertion order if times are equal.
+#[derive(Default)]
+struct Queue {
+    heap: BinaryHeap<QueueItem>,
+    count: usize,
+}
+
+struct QueueItem {
+    time: u32,
+    order: usize,
+    id: ObjectId,
+}
+
+impl PartialEq for QueueItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueueItem {}
+
+impl PartialOrd for QueueItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueueItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.time.cmp(&other.time).then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+impl Queue {
+    fn push(&mut self, id: ObjectId, time: u32) {
+        self.heap.push(QueueItem {
+            time,
+            order: self.count,
+            id,
+        });
+        self.count += 1;
+    }
+
+    fn pop(&mut self) -> Option<ObjectId> {
+        self.heap.pop().map(|item| item.id)
+    }
+}
@@ -0,0 +1,174 @@
+use git_hash::ObjectId;
+
+use super::{Error, Flags, Graph, Negotiator, Queue};
+use crate::Repository;
+
+/// The commit is known to be in common with the remote.
+const COMMON: Flags = 1 << 0;
+/// The commit is the tip of a reference the remote advertised and we have.
+const ADVERTISED: Flags = 1 << 1;
+/// The commit was put into the queue.
+const SEEN: Flags = 1 << 2;
+/// The commit was taken out of the queue.
+const POPPED: Flags = 1 << 3;
+
+/// The amount of commits to skip before sending the next one, growing exponentially with each commit that is sent.
+#[derive(Default, Clone, Copy)]
+struct Ttl {
+    original: u16,
+    remaining: u16,
+}
+
+/// The `skipping` algorithm as implemented in git's `negotiator/skipping.c`.
+pub(crate) struct Algorithm<'repo> {
+    graph: Graph<'repo>,
+    revs: Queue,
+    ttl_by_queued_commit: git_revision::hash_hasher::HashedMap<ObjectId, Ttl>,
+    non_common_revs: isize,
+}
+
+impl<'repo> Algorithm<'repo> {
+    pub fn new(repo: &'repo Repository) -> Self {
+        Algorithm {
+            graph: Graph::new(repo),
+            revs: Queue::default(),
+            ttl_by_queued_commit: Default::default(),
+            non_common_revs: 0,
+        }
+    }
+
+    /// Queue `id` with `mark`. Like git, commits aren't parsed here, so parents that weren't parsed yet are queued without
+    /// their commit time and are thus popped in the order they were queued, after all commits whose time is known.
+    fn add_to_queue(&mut self, id: ObjectId, mark: Flags) {
+        self.graph.add_flags(id, mark | SEEN);
+        self.revs.push(id, self.graph.time(&id));
+        self.ttl_by_queued_commit.insert(id, Ttl::default());
+        if mark & COMMON == 0 {
+            self.non_common_revs += 1;
+        }
+    }
+
+    /// Mark the seen commit `id` and all its parsed and seen ancestors as common.
+    fn mark_common(&mut self, id: ObjectId) {
+        if self.graph.flags(&id) & COMMON != 0 {
+            return;
+        }
+        self.graph.add_flags(id, COMMON);
+        let mut queue = vec![id];
+        while let Some(id) = queue.pop() {
+            if self.graph.flags(&id) & POPPED == 0 {
+                self.non_common_revs -= 1;
+            }
+            for parent_id in self.graph.parents(&id) {
+                let flags = self.graph.flags(&parent_id);
+                if flags & SEEN == 0 || flags & COMMON != 0 {
+                    continue;
+                }
+                self.graph.add_flags(parent_id, COMMON);
+                queue.push(parent_id);
+            }
+        }
+    }
+
+    /// Assure `parent_id` is queued with the correct flags and ttl as parent of `id` with `ttl`, and return `true`
+    /// if it is queued, or `false` if it was popped already.
+    fn push_parent(&mut self, id: ObjectId, ttl: Ttl, parent_id: ObjectId) -> bool {
+        let parent_flags = self.graph.flags(&parent_id);
+        if parent_flags & SEEN != 0 {
+            if parent_flags & POPPED != 0 {
+                // The parent was popped already due to clock skew, so pretend it doesn't exist.
+                return false;
+            }
+        } else {
+            self.add_to_queue(parent_id, 0);
+        }
+
+        if self.graph.flags(&id) & (COMMON | ADVERTISED) != 0 {
+            self.mark_common(parent_id);
+        } else {
+            let new_original = if ttl.remaining != 0 {
+                ttl.original
+            } else {
+                (u32::from(ttl.original) * 3 / 2 + 1) as u16
+            };
+            let new_remaining = if ttl.remaining != 0 {
+                ttl.remaining - 1
+            } else {
+                new_original
+            };
+            let parent_ttl = self
+                .ttl_by_queued_commit
+                .get_mut(&parent_id)
+                .expect("BUG: a seen commit that wasn't popped is queued");
+            if parent_ttl.original < new_original {
+                parent_ttl.original = new_original;
+                parent_ttl.remaining = new_remaining;
+            }
+        }
+        true
+    }
+}
+
+impl Negotiator for Algorithm<'_> {
+    fn known_common(&mut self, id: ObjectId) -> Result<(), Error> {
+        if self.graph.flags(&id) & SEEN == 0 {
+            self.graph.parse(id)?;
+            self.add_to_queue(id, ADVERTISED);
+        }
+        Ok(())
+    }
+
+    fn add_tip(&mut self, id: ObjectId) -> Result<(), Error> {
+        if self.graph.flags(&id) & SEEN == 0 {
+            self.graph.parse(id)?;
+            self.add_to_queue(id, 0);
+        }
+        Ok(())
+    }
+
+    fn next_have(&mut self) -> Option<Result<ObjectId, Error>> {
+        loop {
+            if self.non_common_revs == 0 {
+                return None;
+            }
+            let id = self.revs.pop()?;
+            let ttl = self
+                .ttl_by_queued_commit
+                .remove(&id)
+                .expect("BUG: each queued commit has a ttl");
+            let res = (|| {
+                self.graph.add_flags(id, POPPED);
+                let is_common = self.graph.flags(&id) & COMMON != 0;
+                if !is_common {
+                    self.non_common_revs -= 1;
+                }
+                let mut send = !is_common && ttl.remaining == 0;
+                self.graph.parse(id)?;
+                let mut parent_pushed = false;
+                for parent_id in self.graph.parents(&id) {
+                    parent_pushed |= self.push_parent(id, ttl, parent_id);
+                }
+                if self.graph.flags(&id) & COMMON == 0 && !parent_pushed {
+                    // The commit has no parents or they were all popped already due to clock skew, so send it anyway.
+                    send = true;
+                }
+                Ok(send)
+            })();
+            match res {
+                Ok(true) => return Some(Ok(id)),
+                Ok(false) => continue,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+
+    fn in_common_with_remote(&mut self, id: ObjectId) -> Result<bool, Error> {
+        let flags = self.graph.flags(&id);
+        let known_to_be_common = flags & COMMON != 0;
+        // Acknowledgements for commits we didn't send are ignored.
+        if flags & SEEN != 0 {
+            self.mark_common(id);
+        }
+        Ok(known_to_be_common)
+    }
+}
@@ -0,0 +1,536 @@
+use std::{
+    collections::BTreeSet,
+    convert::TryInto,
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use git_hash::ObjectId;
+use git_odb::Find;
+use git_ref::{
+    file::transaction::PackedRefs,
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, Target,
+};
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    remote,
+    remote::{
+        fetch::{DenyCurrentBranch, Tags},
+        mapping::{map, matches},
+    },
+    Remote,
+};
+
+mod error {
+    /// The error returned by [`Remote::update_refs()`][crate::Remote::update_refs()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The local reference name derived from a remote reference is invalid")]
+        InvalidRefName(#[from] git_validate::refname::Error),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        FindHead(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        IsAncestor(#[from] crate::revision::merge_base::Error),
+        #[error(transparent)]
+        EditReferences(#[from] crate::reference::edit::Error),
+        #[error("Could not list the linked worktrees to learn which branches are checked out")]
+        ListWorktrees(#[from] std::io::Error),
+        #[error("Could not open a worktree to learn which branch is checked out")]
+        OpenWorktree(#[from] crate::open::Error),
+        #[error(transparent)]
+        WorktreeStatus(#[from] crate::status::Error),
+        #[error("Could not open the index of the worktree at {path:?}")]
+        OpenIndex {
+            path: std::path::PathBuf,
+            source: crate::worktree::open_index::Error,
+        },
+        #[error("Could not update the worktree at {path:?} after updating its checked out branch")]
+        UpdateWorktree {
+            path: std::path::PathBuf,
+            source: crate::worktree::checkout::Error,
+        },
+        #[error("Could not remove {path:?} which isn't tracked anymore after updating its worktree")]
+        RemoveFile {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+    }
+}
+pub use error::Error;
+
+/// Options for [`Remote::update_refs()`][crate::Remote::update_refs()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If `true`, apply all reference edits in a single transaction, so either all of them are applied or none,
+    /// similar to `git fetch --atomic`. Then a single rejected update prevents all others from being applied, too.
+    ///
+    /// Otherwise, each reference is updated on its own and rejected updates don't affect the other ones.
+    pub atomic: bool,
+    /// If `true`, don't change any reference but only report what would be changed.
+    pub dry_run: bool,
+    /// How to handle tags, typically obtained by [`Remote::fetch_tags()`][crate::Remote::fetch_tags()].
+    pub tags: Tags,
+    /// If set, and at least this many references are created or updated, write them into the `packed-refs` file in one go
+    /// instead of creating a loose reference file for each of them, which also removes their loose versions.
+    ///
+    /// This is much faster for mirrors with thousands of references, which would otherwise spend most of their time
+    /// writing loose references.
+    pub packed_refs_threshold: Option<usize>,
+    /// How to handle updates to branches that are checked out in the main worktree or any linked worktree, which are
+    /// [refused][DenyCurrentBranch::Refuse] by default.
+    pub checked_out: DenyCurrentBranch,
+}
+
+/// What happened, or would have happened, to a local reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// The local reference didn't exist and was created.
+    New,
+    /// The local reference was updated to a descendant of the commit it pointed to.
+    FastForward,
+    /// The local reference was updated even though its new value isn't a descendant of the previous one,
+    /// as the ref-spec is prefixed with `+`.
+    Forced,
+    /// The local reference already points to the same object as the remote one.
+    NoChangeNeeded,
+    /// The update was rejected as the new value isn't a descendant of the previous one, and the ref-spec isn't forced.
+    RejectedNonFastForward,
+    /// The update was rejected as it would change an existing tag, and the ref-spec isn't forced.
+    RejectedTagUpdate,
+    /// The update was rejected as the local reference is the branch checked out in a worktree.
+    RejectedCurrentlyCheckedOut,
+    /// The update was rejected as the local reference is the branch checked out in a worktree which has changes, which
+    /// would be lost when updating it with [`DenyCurrentBranch::UpdateInstead`].
+    RejectedWorktreeHasChanges,
+    /// The update was rejected as the object the remote reference points to doesn't exist in our object database.
+    RejectedSourceObjectNotFound,
+}
+
+impl Mode {
+    /// Return `true` if the update was rejected.
+    pub fn is_rejected(&self) -> bool {
+        matches!(
+            self,
+            Mode::RejectedNonFastForward
+                | Mode::RejectedTagUpdate
+                | Mode::RejectedCurrentlyCheckedOut
+                | Mode::RejectedWorktreeHasChanges
+                | Mode::RejectedSourceObjectNotFound
+        )
+    }
+
+    /// Return the reason `git fetch` shows in parentheses after forced or rejected updates, like `non-fast-forward`.
+    pub fn reason(&self) -> Option<&'static str> {
+        Some(match self {
+            Mode::New | Mode::FastForward | Mode::NoChangeNeeded => return None,
+            Mode::Forced => "forced update",
+            Mode::RejectedNonFastForward => "non-fast-forward",
+            Mode::RejectedTagUpdate => "would clobber existing tag",
+            Mode::RejectedCurrentlyCheckedOut => "refusing to fetch into checked out branch",
+            Mode::RejectedWorktreeHasChanges => "checked out branch has uncommitted changes",
+            Mode::RejectedSourceObjectNotFound => "object not found",
+        })
+    }
+}
+
+/// A local reference that [`Remote::update_refs()`][crate::Remote::update_refs()] considered.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Update {
+    /// The full name of the local reference, like `refs/remotes/origin/main`.
+    pub name: FullName,
+    /// The full name of the reference on the remote it is derived from, like `refs/heads/main`.
+    pub remote_name: BString,
+    /// The object the local reference pointed to before, or `None` if it didn't exist.
+    pub previous: Option<ObjectId>,
+    /// The object the local reference should point to.
+    pub new: ObjectId,
+    /// What happened to the local reference.
+    pub mode: Mode,
+    /// The worktree in which the local reference is checked out, or `None` if it isn't checked out anywhere.
+    pub worktree: Option<PathBuf>,
+    /// If `true`, the local reference was written. This isn't the case in a dry-run, if the update was rejected or not
+    /// needed, or if any update was rejected in atomic mode.
+    pub applied: bool,
+}
+
+impl Update {
+    /// Return the flag and the summary `git fetch` shows for this update, like `*` and `[new branch]`, or `+` and
+    /// `1234567...89abcde` for a forced update.
+    pub fn flag_and_summary(&self) -> (char, String) {
+        let short = |id: &ObjectId| id.to_hex_with_len(7).to_string();
+        match (self.mode, self.previous.as_ref()) {
+            (mode, _) if mode.is_rejected() => ('!', "[rejected]".into()),
+            (Mode::NoChangeNeeded, _) => ('=', "[up to date]".into()),
+            (Mode::FastForward, Some(previous)) => (' ', format!("{}..{}", short(previous), short(&self.new))),
+            (Mode::Forced, Some(previous)) => ('+', format!("{}...{}", short(previous), short(&self.new))),
+            _ => {
+                let kind = if self.name.as_bstr().starts_with(b"refs/tags/") {
+                    "tag"
+                } else if self.remote_name.starts_with(b"refs/heads/") {
+                    "branch"
+                } else {
+                    "ref"
+                };
+                ('*', format!("[new {}]", kind))
+            }
+        }
+    }
+
+    /// Return the shortened name of the remote reference, like `main` for `refs/heads/main`.
+    fn short_remote_name(&self) -> &crate::bstr::BStr {
+        let name = self.remote_name.as_bstr();
+        ["refs/heads/", "refs/tags/", "refs/"]
+            .iter()
+            .find_map(|prefix| name.strip_prefix(prefix.as_bytes()))
+            .map_or(name, |name| name.as_bstr())
+    }
+}
+
+/// Print one line per update like the table `git fetch` shows, for example ` * [new branch]      main       -> origin/main`.
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rows: Vec<_> = self
+            .updates
+            .iter()
+            .map(|update| (update.flag_and_summary(), update))
+            .collect();
+        let summary_width = rows.iter().map(|((_, summary), _)| summary.len()).max().unwrap_or(0);
+        let name_width = rows
+            .iter()
+            .map(|(_, update)| update.short_remote_name().len())
+            .max()
+            .unwrap_or(0);
+        for ((flag, summary), update) in rows {
+            write!(
+                f,
+                " {} {:<summary_width$} {:<name_width$} -> {}",
+                flag,
+                summary,
+                update.short_remote_name().to_string(),
+                update.name.shorten(),
+                summary_width = summary_width,
+                name_width = name_width
+            )?;
+            match update.mode.reason() {
+                Some(reason) => writeln!(f, "  ({})", reason)?,
+                None => writeln!(f)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Measurements taken while applying the reference edits of [`Remote::update_refs()`][crate::Remote::update_refs()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Statistics {
+    /// The amount of reference transactions that were committed, which is 1 unless applying all edits at once failed
+    /// in a non-atomic update and each of them had to be applied on its own.
+    pub transactions: usize,
+    /// If `true`, the edited references were written into the `packed-refs` file as configured by
+    /// [`Options::packed_refs_threshold`].
+    pub packed: bool,
+    /// The time it took to apply all reference edits.
+    pub duration: std::time::Duration,
+}
+
+/// The outcome of [`Remote::update_refs()`][crate::Remote::update_refs()].
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// All local references that were considered, in the order of the remote references they were derived from.
+    pub updates: Vec<Update>,
+    /// The reference edits that were applied, which is empty in a dry-run or if an atomic update was rejected.
+    pub edits: Vec<RefEdit>,
+    /// Measurements taken while applying the edits.
+    pub statistics: Statistics,
+}
+
+impl Outcome {
+    /// Return all updates that were rejected.
+    pub fn rejected(&self) -> impl Iterator<Item = &Update> {
+        self.updates.iter().filter(|update| update.mode.is_rejected())
+    }
+}
+
+/// Updating references after fetching
+impl Remote<'_> {
+    /// Create or update the local references that correspond to `remote_refs`, the references advertised by the remote,
+    /// after the objects they point to were received.
+    ///
+    /// Local reference names are derived using the fetch [ref-specs][Remote::refspecs()], and remote references excluded
+    /// by negative ref-specs are ignored. Existing local references are only updated if the update is a fast-forward
+    /// or if the ref-spec is forced with `+`, and existing tags are never changed unless forced.
+    ///
+    /// With [`Tags::Included`], tags on the remote that aren't matched by a ref-spec are created if both the tag
+    /// and the object it points to exist locally, which is the case for tags pointing into the fetched history if the
+    /// server supports the `include-tag` capability. Existing local tags are left untouched in this case.
+    pub fn update_refs(&self, remote_refs: &[git_protocol::fetch::Ref], options: Options) -> Result<Outcome, Error> {
+        let mut mappings = Vec::new();
+        let mut excludes = Vec::new();
+        let specs: Vec<_> = self
+            .refspecs(remote::Direction::Fetch)
+            .iter()
+            .map(|spec| spec.to_ref())
+            .collect();
+        for spec in &specs {
+            match spec.instruction() {
+                git_refspec::Instruction::Fetch(git_refspec::instruction::Fetch::AndUpdate {
+                    src,
+                    dst,
+                    allow_non_fast_forward,
+                }) => mappings.push((src, dst, allow_non_fast_forward)),
+                git_refspec::Instruction::Fetch(git_refspec::instruction::Fetch::Exclude { src }) => excludes.push(src),
+                _ => {}
+            }
+        }
+        if options.tags == Tags::All {
+            mappings.push(("refs/tags/*".into(), "refs/tags/*".into(), false));
+        }
+
+        let repo = self.repo;
+        let checked_out = repo.checked_out_branches::<Error>()?;
+        let mut seen = BTreeSet::new();
+        let mut outcome = Outcome::default();
+        let mut edits = Vec::new();
+        for remote_ref in remote_refs {
+            let (remote_name, new) = remote_ref.unpack();
+            let peeled = match remote_ref {
+                git_protocol::fetch::Ref::Peeled { object, .. } => object,
+                _ => new,
+            };
+            if excludes.iter().any(|pattern| matches(pattern, remote_name.as_ref())) {
+                continue;
+            }
+            let mut local_names: Vec<_> = mappings
+                .iter()
+                .filter_map(|(src, dst, force)| map(remote_name.as_ref(), src, dst).map(|name| (name, *force, false)))
+                .collect();
+            if local_names.is_empty()
+                && options.tags == Tags::Included
+                && remote_name.starts_with(b"refs/tags/")
+                && repo.objects.contains(new)
+                && repo.objects.contains(peeled)
+            {
+                local_names.push((remote_name.clone(), false, true));
+            }
+
+            for (local_name, force, is_followed_tag) in local_names {
+                let name: FullName = local_name.try_into()?;
+                if !seen.insert(name.clone()) {
+                    continue;
+                }
+                let previous = match repo.try_find_reference(name.as_bstr())? {
+                    Some(reference) => match reference.inner.target {
+                        Target::Peeled(id) => Some(id),
+                        Target::Symbolic(_) => continue,
+                    },
+                    None => None,
+                };
+                if is_followed_tag && previous.is_some() {
+                    continue;
+                }
+                let worktree = checked_out
+                    .iter()
+                    .find(|(branch, _)| *branch == name)
+                    .map(|(_, worktree)| worktree);
+                let mode = if !repo.objects.contains(new) {
+                    Mode::RejectedSourceObjectNotFound
+                } else {
+                    match previous {
+                        None => Mode::New,
+                        Some(previous) if previous == *new => Mode::NoChangeNeeded,
+                        Some(_) if worktree.is_some() && options.checked_out == DenyCurrentBranch::Refuse => {
+                            Mode::RejectedCurrentlyCheckedOut
+                        }
+                        Some(_) if force => Mode::Forced,
+                        Some(_) if name.as_bstr().starts_with(b"refs/tags/") => Mode::RejectedTagUpdate,
+                        Some(previous) => {
+                            if repo.is_ancestor(previous, *new)? {
+                                Mode::FastForward
+                            } else {
+                                Mode::RejectedNonFastForward
+                            }
+                        }
+                    }
+                };
+                let mode = match worktree {
+                    Some(worktree)
+                        if options.checked_out == DenyCurrentBranch::UpdateInstead
+                            && matches!(mode, Mode::FastForward | Mode::Forced)
+                            && worktree.is_dirty()? =>
+                    {
+                        Mode::RejectedWorktreeHasChanges
+                    }
+                    _ => mode,
+                };
+                let reflog_message = match mode {
+                    Mode::New if name.as_bstr().starts_with(b"refs/tags/") => Some("storing tag"),
+                    Mode::New => Some("storing head"),
+                    Mode::FastForward => Some("fast-forward"),
+                    Mode::Forced => Some("forced-update"),
+                    _ => None,
+                };
+                if let Some(message) = reflog_message {
+                    edits.push(RefEdit {
+                        change: Change::Update {
+                            log: LogChange {
+                                mode: RefLog::AndReference,
+                                force_create_reflog: false,
+                                message: match self.name.as_deref() {
+                                    Some(remote) => format!("fetch {}: {}", remote, message),
+                                    None => format!("fetch: {}", message),
+                                }
+                                .into(),
+                            },
+                            expected: match previous {
+                                Some(previous) => PreviousValue::MustExistAndMatch(Target::Peeled(previous)),
+                                None => PreviousValue::MustNotExist,
+                            },
+                            new: Target::Peeled(*new),
+                        },
+                        name: name.clone(),
+                        deref: false,
+                    });
+                }
+                outcome.updates.push(Update {
+                    name,
+                    remote_name: remote_name.clone(),
+                    previous,
+                    new: *new,
+                    mode,
+                    worktree: worktree.and_then(|worktree| worktree.work_dir().map(ToOwned::to_owned)),
+                    applied: false,
+                });
+            }
+        }
+
+        if options.dry_run || edits.is_empty() || (options.atomic && outcome.rejected().next().is_some()) {
+            return Ok(outcome);
+        }
+        let start = std::time::Instant::now();
+        let committer = repo.committer_or_default();
+        let packed = options
+            .packed_refs_threshold
+            .map_or(false, |threshold| edits.len() >= threshold);
+        // Buffer all edits in a single transaction so the `packed-refs` file is locked, and possibly written, only once.
+        // Non-atomic updates fall back to applying each edit on its own if they can't all be applied together.
+        let transaction = self
+            .edit_transaction(packed)
+            .prepare(edits.clone(), git_lock::acquire::Fail::Immediately);
+        match transaction {
+            Ok(transaction) => {
+                outcome.edits = transaction
+                    .commit(committer)
+                    .map_err(crate::reference::edit::Error::from)?;
+                outcome.statistics.transactions = 1;
+            }
+            Err(err) if options.atomic => return Err(crate::reference::edit::Error::from(err).into()),
+            Err(_) => {
+                for edit in edits {
+                    outcome.edits.extend(
+                        self.edit_transaction(packed)
+                            .prepare(Some(edit), git_lock::acquire::Fail::Immediately)
+                            .map_err(crate::reference::edit::Error::from)?
+                            .commit(committer)
+                            .map_err(crate::reference::edit::Error::from)?,
+                    );
+                    outcome.statistics.transactions += 1;
+                }
+            }
+        }
+        outcome.statistics.packed = packed;
+        outcome.statistics.duration = start.elapsed();
+        for update in &mut outcome.updates {
+            update.applied = outcome.edits.iter().any(|edit| edit.name == update.name);
+        }
+        if options.checked_out == DenyCurrentBranch::UpdateInstead {
+            for update in outcome.updates.iter().filter(|update| update.applied) {
+                if let Some((_, worktree)) = checked_out.iter().find(|(branch, _)| *branch == update.name) {
+                    update_worktree(worktree, update.new)?;
+                }
+            }
+        }
+        Ok(outcome)
+    }
+
+    /// Return a new reference transaction which writes updated references into the `packed-refs` file if `packed` is `true`.
+    fn edit_transaction(&self, packed: bool) -> git_ref::file::Transaction<'_> {
+        let transaction = self.repo.refs.transaction();
+        if !packed {
+            return transaction;
+        }
+        let objects = self.repo.objects.clone();
+        transaction.packed_refs(PackedRefs::DeletionsAndNonSymbolicUpdatesRemoveLooseSourceReference(
+            Box::new(move |id, buf| {
+                objects
+                    .try_find(id, buf)
+                    .map(|obj| obj.map(|obj| obj.kind))
+                    .map_err(|err| Box::new(err) as _)
+            }),
+        ))
+    }
+}
+
+/// Check out the tree of `commit` in the clean `worktree` after its branch was updated, and remove the files that
+/// were tracked before but aren't part of the new tree.
+fn update_worktree(worktree: &crate::Repository, commit: ObjectId) -> Result<(), Error> {
+    let work_dir = worktree
+        .work_dir()
+        .expect("only repositories with worktree are checked out");
+    let tracked_paths = |worktree: &crate::Repository| -> Result<BTreeSet<BString>, Error> {
+        match worktree.open_index() {
+            Ok(index) => Ok(index
+                .entries()
+                .iter()
+                .map(|entry| entry.path(&index).to_owned())
+                .collect()),
+            Err(crate::worktree::open_index::Error::IndexFile(git_index::file::init::Error::Io(err)))
+                if err.kind() == std::io::ErrorKind::NotFound =>
+            {
+                Ok(BTreeSet::new())
+            }
+            Err(source) => Err(Error::OpenIndex {
+                path: work_dir.to_owned(),
+                source,
+            }),
+        }
+    };
+    let previous_paths = tracked_paths(worktree)?;
+    worktree
+        .checkout_tree(
+            commit,
+            git_features::progress::Discard,
+            &AtomicBool::default(),
+            crate::worktree::checkout::Options {
+                overwrite_existing: true,
+                ..Default::default()
+            },
+        )
+        .map_err(|source| Error::UpdateWorktree {
+            path: work_dir.to_owned(),
+            source,
+        })?;
+    let paths = tracked_paths(worktree)?;
+    for removed in previous_paths.difference(&paths) {
+        let path = work_dir.join(git_path::from_bstr(removed.as_bstr()));
+        match std::fs::remove_file(&path) {
+            Ok(()) => remove_empty_parents(&path, work_dir),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(source) => return Err(Error::RemoveFile { path, source }),
+        }
+    }
+    Ok(())
+}
+
+/// Remove all empty directories leading to `path` up to but excluding `work_dir`, like git does when removing files.
+fn remove_empty_parents(path: &Path, work_dir: &Path) {
+    for dir in path.ancestors().skip(1).take_while(|dir| *dir != work_dir) {
+        if std::fs::remove_dir(dir).is_err() {
+            break;
+        }
+    }
+}
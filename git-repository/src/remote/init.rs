@@ -61,7 +61,7 @@ impl<'repo> Remote<'repo> {
         git_url::parse::Error: From<E>,
     {
         let url = url.try_into().map_err(|err| Error::Url(err.into()))?;
-        let (url_alias, _) = should_rewrite_urls
+        let (url_alias, push_url_alias) = should_rewrite_urls
             .then(|| rewrite_urls(&repo.config, Some(&url), None))
             .unwrap_or(Ok((None, None)))?;
         Ok(Remote {
@@ -69,7 +69,7 @@ impl<'repo> Remote<'repo> {
             url: Some(url),
             url_alias,
             push_url: None,
-            push_url_alias: None,
+            push_url_alias,
             fetch_specs: Vec::new(),
             push_specs: Vec::new(),
             repo,
@@ -96,13 +96,23 @@ pub(crate) fn rewrite_url(
         .transpose()
 }
 
+/// Rewrite `push_url` with `pushInsteadOf` rules, or `url` if there is no `push_url` as pushing to it is the fallback.
+/// If `url` isn't rewritten, it will be pushed to with the `insteadOf` rewrites applied just like when fetching.
+pub(crate) fn rewrite_push_url(
+    config: &config::Cache,
+    url: Option<&git_url::Url>,
+    push_url: Option<&git_url::Url>,
+) -> Result<Option<git_url::Url>, Error> {
+    rewrite_url(config, push_url.or(url), remote::Direction::Push)
+}
+
 pub(crate) fn rewrite_urls(
     config: &config::Cache,
     url: Option<&git_url::Url>,
     push_url: Option<&git_url::Url>,
 ) -> Result<(Option<git_url::Url>, Option<git_url::Url>), Error> {
     let url_alias = rewrite_url(config, url, remote::Direction::Fetch)?;
-    let push_url_alias = rewrite_url(config, push_url, remote::Direction::Push)?;
+    let push_url_alias = rewrite_push_url(config, url, push_url)?;
 
     Ok((url_alias, push_url_alias))
 }
@@ -0,0 +1,81 @@
+//! Types used when listing the references of a remote.
+use crate::bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// The error returned by [`Connection::list_refs()`][crate::remote::Connection::list_refs()] and
+/// [`Connection::list_refs_with_options()`][crate::remote::Connection::list_refs_with_options()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Handshake(#[from] git_protocol::fetch::handshake::Error),
+    #[error(transparent)]
+    ListRefs(#[from] git_protocol::fetch::refs::Error),
+    #[error(transparent)]
+    Transport(#[from] git_protocol::transport::client::Error),
+}
+
+/// Options for [`Connection::list_refs_with_options()`][crate::remote::Connection::list_refs_with_options()].
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If not empty, only list references matching at least one of these filters, similar to the patterns of `git ls-remote`.
+    ///
+    /// A filter is a glob like `refs/heads/feature/*` matched against the full reference name, or a prefix ending in `/` like
+    /// `refs/tags/` which matches all references below it. Filters that aren't fully qualified, like `main` or `v1.*`,
+    /// match like `git rev-parse` would, so `main` matches `refs/heads/main` as well as `refs/tags/main`.
+    ///
+    /// With protocol V2, the filters are also sent to the server as `ref-prefix` arguments so that listing a few references
+    /// of a remote with many of them is cheap. Older protocols always receive all references, which are then filtered here.
+    pub filters: Vec<BString>,
+}
+
+/// The prefixes `git rev-parse` tries in order to find a reference by a short name.
+const SHORT_NAME_PREFIXES: &[&str] = &["", "refs/", "refs/tags/", "refs/heads/", "refs/remotes/"];
+
+impl Options {
+    /// Return the `ref-prefix` values for the `ls-refs` command of protocol V2, which is empty if all references are needed.
+    pub(crate) fn ref_prefixes(&self) -> Vec<BString> {
+        let mut prefixes = Vec::new();
+        for filter in &self.filters {
+            let literal = filter[..filter.find_byteset(b"*?[\\").unwrap_or(filter.len())].as_bstr();
+            if filter.starts_with(b"refs/") {
+                prefixes.push(literal.to_owned());
+            } else {
+                if literal.is_empty() {
+                    return Vec::new();
+                }
+                prefixes.extend(SHORT_NAME_PREFIXES.iter().map(|prefix| {
+                    let mut name = BString::from(*prefix);
+                    name.push_str(literal);
+                    name
+                }));
+            }
+        }
+        prefixes.sort();
+        prefixes.dedup();
+        prefixes
+    }
+
+    /// Return `true` if the reference with `name` matches our filters.
+    pub(crate) fn matches(&self, name: &BStr) -> bool {
+        if self.filters.is_empty() {
+            return true;
+        }
+        self.filters.iter().any(|filter| {
+            let prefixes: &[&str] = if filter.starts_with(b"refs/") {
+                &[""]
+            } else {
+                SHORT_NAME_PREFIXES
+            };
+            prefixes.iter().any(|prefix| {
+                name.strip_prefix(prefix.as_bytes()).map_or(false, |name| {
+                    let name = name.as_bstr();
+                    if filter.ends_with(b"/") {
+                        name.starts_with(filter)
+                    } else {
+                        git_glob::wildmatch(filter.as_ref(), name, git_glob::wildmatch::Mode::empty())
+                    }
+                })
+            })
+        })
+    }
+}
@@ -0,0 +1,43 @@
+//! Utilities to map reference names using the single-glob patterns of ref-specs.
+use crate::bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// Split `pattern` at its only `*`, if there is one.
+fn split_glob(pattern: &BStr) -> Option<(&BStr, &BStr)> {
+    pattern
+        .find_byte(b'*')
+        .map(|pos| (pattern[..pos].as_bstr(), pattern[pos + 1..].as_bstr()))
+}
+
+/// Return the part of `name` matched by the `*` in `pattern`, or an empty string if `pattern` equals `name`.
+fn capture<'a>(pattern: &BStr, name: &'a BStr) -> Option<&'a BStr> {
+    match split_glob(pattern) {
+        Some((prefix, suffix)) => {
+            (name.len() >= prefix.len() + suffix.len() && name.starts_with(prefix) && name.ends_with(suffix))
+                .then(|| name[prefix.len()..name.len() - suffix.len()].as_bstr())
+        }
+        None => (pattern == name).then(|| "".into()),
+    }
+}
+
+pub(crate) fn matches(pattern: &BStr, name: &BStr) -> bool {
+    capture(pattern, name).is_some()
+}
+
+/// Map the local reference `name` matching `dst` back to the name of the reference matching `src` on the remote.
+pub(crate) fn reverse_map(name: &BStr, src: &BStr, dst: &BStr) -> Option<BString> {
+    let captured = capture(dst, name)?;
+    Some(match split_glob(src) {
+        Some((prefix, suffix)) => {
+            let mut remote_name = BString::from(prefix);
+            remote_name.push_str(captured);
+            remote_name.push_str(suffix);
+            remote_name
+        }
+        None => src.to_owned(),
+    })
+}
+
+/// Map the remote reference `name` matching `src` to the name of the local reference matching `dst`.
+pub(crate) fn map(name: &BStr, src: &BStr, dst: &BStr) -> Option<BString> {
+    reverse_map(name, dst, src)
+}
@@ -35,4 +35,13 @@ mod connection;
 pub use connection::Connection;
 
 mod access;
-pub(crate) mod url;
+///
+pub mod fetch;
+///
+#[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+pub mod list_refs;
+pub(crate) mod mapping;
+///
+pub mod prune;
+///
+pub mod url;
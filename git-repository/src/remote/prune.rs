@@ -0,0 +1,193 @@
+use std::collections::BTreeSet;
+
+use git_ref::{
+    transaction::{Change, PreviousValue, RefEdit, RefLog},
+    Target,
+};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    remote,
+    remote::mapping::{matches, reverse_map},
+    Remote,
+};
+
+mod error {
+    /// The error returned by [`Remote::prune()`][crate::Remote::prune()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+        #[error(transparent)]
+        ListRefs(#[from] crate::remote::list_refs::Error),
+        #[error(transparent)]
+        References(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        IterInit(#[from] crate::reference::iter::init::Error),
+        #[error("A local reference could not be read")]
+        Iter(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        DeleteReferences(#[from] crate::reference::edit::Error),
+    }
+}
+pub use error::Error;
+
+/// Options for [`Remote::prune()`][crate::Remote::prune()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If `true`, also delete local tags that don't exist on the remote, as if `refs/tags/*:refs/tags/*` was one of the
+    /// fetch ref-specs, similar to `git fetch --prune-tags`.
+    pub prune_tags: bool,
+    /// If `true`, don't delete any reference but only report what would be deleted.
+    pub dry_run: bool,
+}
+
+/// What happened to a local reference [mapped][Ref::remote_name] to a reference on the remote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Status {
+    /// The remote reference doesn't exist anymore, so the local one was deleted, or would have been deleted in a dry-run.
+    Deleted,
+    /// The remote reference still exists, so the local one was kept.
+    Kept,
+    /// The remote reference was excluded by a negative ref-spec, so the local one is protected from pruning.
+    Excluded,
+}
+
+/// A local reference that [`Remote::prune()`][crate::Remote::prune()] considered.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Ref {
+    /// The full name of the local reference, like `refs/remotes/origin/main`.
+    pub name: git_ref::FullName,
+    /// The full name of the reference on the remote it was derived from according to the fetch ref-specs, like `refs/heads/main`.
+    pub remote_name: BString,
+    /// What happened to the local reference.
+    pub status: Status,
+}
+
+/// The outcome of [`Remote::prune()`][crate::Remote::prune()].
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Outcome {
+    /// All local references that matched the destination of a fetch ref-spec, in the order they were encountered.
+    pub refs: Vec<Ref>,
+}
+
+impl Outcome {
+    /// Return all references that were deleted.
+    pub fn deleted(&self) -> impl Iterator<Item = &Ref> {
+        self.refs.iter().filter(|r| r.status == Status::Deleted)
+    }
+}
+
+/// Pruning
+impl Remote<'_> {
+    /// Return the options to prune stale references with when fetching, or `None` if pruning isn't configured.
+    ///
+    /// Pruning is configured with `remote.<name>.prune` or `fetch.prune`, and `remote.<name>.pruneTags` or `fetch.pruneTags`
+    /// additionally prunes tags. The values for the remote override the global ones.
+    pub fn prune_on_fetch(&self) -> Option<Options> {
+        let config = &self.repo.config.resolved;
+        let flag = |key: &str| {
+            self.name
+                .as_deref()
+                .and_then(|name| config.boolean("remote", Some(name), key))
+                .or_else(|| config.boolean("fetch", None, key))
+                .and_then(Result::ok)
+                .unwrap_or(false)
+        };
+        flag("prune").then(|| Options {
+            prune_tags: flag("pruneTags"),
+            dry_run: false,
+        })
+    }
+
+    /// Delete local references that were created by fetching from this remote, but whose counterpart doesn't exist in
+    /// `remote_refs` anymore, the full names of all references on the remote, like `refs/heads/main`.
+    ///
+    /// Local references are mapped to references on the remote using the destinations of our fetch [ref-specs][Remote::refspecs()],
+    /// which typically makes them the ones in `refs/remotes/<name>/`. Local references matching no ref-spec are never touched,
+    /// and neither are symbolic references like `refs/remotes/<name>/HEAD`. Remote references excluded by negative ref-specs
+    /// like `^refs/heads/keep` protect their local counterparts from pruning.
+    pub fn prune<'a>(
+        &self,
+        remote_refs: impl IntoIterator<Item = &'a BStr>,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let remote_refs: BTreeSet<_> = remote_refs.into_iter().collect();
+        let mut mappings = Vec::new();
+        let mut excludes = Vec::new();
+        let specs: Vec<_> = self
+            .refspecs(remote::Direction::Fetch)
+            .iter()
+            .map(|spec| spec.to_ref())
+            .collect();
+        for spec in &specs {
+            match spec.instruction() {
+                git_refspec::Instruction::Fetch(git_refspec::instruction::Fetch::AndUpdate { src, dst, .. }) => {
+                    mappings.push((src, dst))
+                }
+                git_refspec::Instruction::Fetch(git_refspec::instruction::Fetch::Exclude { src }) => excludes.push(src),
+                _ => {}
+            }
+        }
+        if options.prune_tags {
+            mappings.push(("refs/tags/*".into(), "refs/tags/*".into()));
+        }
+
+        let platform = self.repo.references()?;
+        let mut seen = BTreeSet::new();
+        let mut outcome = Outcome::default();
+        let mut edits = Vec::new();
+        for (src, dst) in mappings {
+            let prefix = match dst.find_byte(b'*') {
+                Some(pos) => dst[..pos].rfind_byte(b'/').map(|pos| &dst[..=pos]).unwrap_or_default(),
+                None => dst,
+            };
+            for local in platform
+                .prefixed(git_path::from_bstr(prefix))?
+                .map(|r| r.map_err(Error::Iter))
+            {
+                let local = local?.detach();
+                let remote_name = match reverse_map(local.name.as_bstr(), src, dst) {
+                    Some(name) => name,
+                    None => continue,
+                };
+                let target = match local.target {
+                    Target::Peeled(id) => Target::Peeled(id),
+                    Target::Symbolic(_) => continue,
+                };
+                if !seen.insert(local.name.clone()) {
+                    continue;
+                }
+                let status = if excludes.iter().any(|pattern| matches(pattern, remote_name.as_ref())) {
+                    Status::Excluded
+                } else if remote_refs.contains(remote_name.as_bstr()) {
+                    Status::Kept
+                } else {
+                    edits.push(RefEdit {
+                        change: Change::Delete {
+                            expected: PreviousValue::MustExistAndMatch(target),
+                            log: RefLog::AndReference,
+                        },
+                        name: local.name.clone(),
+                        deref: false,
+                    });
+                    Status::Deleted
+                };
+                outcome.refs.push(Ref {
+                    name: local.name,
+                    remote_name,
+                    status,
+                });
+            }
+        }
+
+        if !options.dry_run && !edits.is_empty() {
+            self.repo.edit_references(
+                edits,
+                git_lock::acquire::Fail::Immediately,
+                self.repo.committer_or_default(),
+            )?;
+        }
+        Ok(outcome)
+    }
+}
@@ -3,20 +3,36 @@ use crate::remote::Direction;
 use git_features::threading::OwnShared;
 
 #[derive(Debug, Clone)]
-pub(crate) struct Replace {
+struct Replace {
     find: BString,
     with: OwnShared<BString>,
 }
 
+/// The rules to rewrite remote urls with, as configured with `url.<base>.insteadOf` and `url.<base>.pushInsteadOf`.
+///
+/// Obtain it with [`Repository::url_rewrite()`][crate::Repository::url_rewrite()].
 #[derive(Default, Debug, Clone)]
-pub(crate) struct Rewrite {
+pub struct Rewrite {
     url_rewrite: Vec<Replace>,
     push_url_rewrite: Vec<Replace>,
 }
 
+/// A rule of a [`Rewrite`] that matched a url.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match<'a> {
+    /// The `<base>` of the `url.<base>` section with which the matched prefix is replaced.
+    pub base: &'a BStr,
+    /// The value of `insteadOf` or `pushInsteadOf` that matched the beginning of the url.
+    pub instead_of: &'a BStr,
+    /// The url after replacing [`instead_of`][Match::instead_of] with [`base`][Match::base].
+    ///
+    /// Note that it might not be a valid url as the replacement is syntax-unaware.
+    pub rewritten: BString,
+}
+
 /// Init
 impl Rewrite {
-    pub fn from_config(
+    pub(crate) fn from_config(
         config: &git_config::File<'static>,
         mut filter: fn(&git_config::file::Metadata) -> bool,
     ) -> Rewrite {
@@ -62,7 +78,7 @@ impl Rewrite {
         }
     }
 
-    pub fn longest(&self, url: &git_url::Url, direction: Direction) -> Option<BString> {
+    pub(crate) fn longest(&self, url: &git_url::Url, direction: Direction) -> Option<BString> {
         if self.replacements_for(direction).is_empty() {
             None
         } else {
@@ -71,26 +87,43 @@ impl Rewrite {
         }
     }
 
-    /// Rewrite the given `url` of `direction` and return `true` if a replacement happened.
+    /// Return the rule with the longest `insteadOf` (for [fetching][Direction::Fetch]) or `pushInsteadOf` (for [pushing][Direction::Push])
+    /// value that matches the beginning of `url`, along with the rewritten url, or `None` if no rule matches.
     ///
-    /// Note that the result must still be checked for validity, it might not be a valid URL as we do a syntax-unaware replacement.
-    pub fn rewrite_url_in_place(&self, url: &mut BString, direction: Direction) -> bool {
+    /// If more than one rule has the longest matching value, the first one in the configuration wins.
+    pub fn longest_match(&self, url: &BStr, direction: Direction) -> Option<Match<'_>> {
         self.replacements_for(direction)
             .iter()
-            .fold(None::<(usize, &BStr)>, |mut acc, replace| {
-                if url.starts_with(replace.find.as_ref()) {
-                    let (bytes_matched, prev_rewrite_with) =
-                        acc.get_or_insert((replace.find.len(), replace.with.as_slice().into()));
-                    if *bytes_matched < replace.find.len() {
-                        *bytes_matched = replace.find.len();
-                        *prev_rewrite_with = replace.with.as_slice().into();
-                    }
-                };
-                acc
+            .fold(None::<&Replace>, |best, replace| {
+                if url.starts_with(replace.find.as_ref())
+                    && best.map(|best| best.find.len() < replace.find.len()) != Some(false)
+                {
+                    Some(replace)
+                } else {
+                    best
+                }
             })
-            .map(|(bytes_matched, replace_with)| {
-                url.replace_range(..bytes_matched, replace_with);
+            .map(|replace| {
+                let mut rewritten = url.to_owned();
+                rewritten.replace_range(..replace.find.len(), replace.with.as_slice());
+                Match {
+                    base: replace.with.as_slice().into(),
+                    instead_of: replace.find.as_ref(),
+                    rewritten,
+                }
             })
-            .is_some()
+    }
+
+    /// Rewrite the given `url` of `direction` and return `true` if a replacement happened.
+    ///
+    /// Note that the result must still be checked for validity, it might not be a valid URL as we do a syntax-unaware replacement.
+    pub fn rewrite_url_in_place(&self, url: &mut BString, direction: Direction) -> bool {
+        match self.longest_match(url.as_ref(), direction) {
+            Some(m) => {
+                *url = m.rewritten;
+                true
+            }
+            None => false,
+        }
     }
 }
@@ -0,0 +1,390 @@
+use std::{collections::BTreeMap, io::Write, path::Path};
+
+use git_object::tree::EntryMode;
+
+use crate::{
+    apply::{patch, text, Error, Location, Options, Outcome, Reject, Whitespace},
+    bstr::{BStr, BString, ByteSlice},
+};
+
+/// The content and mode of a file, as obtained from the index or the worktree.
+type Content = (Vec<u8>, EntryMode);
+
+impl crate::Repository {
+    /// Apply all changes in `patch`, as produced by `git diff` or `git format-patch`, to the worktree, the index or both as
+    /// configured in `options`, similar to `git apply`.
+    ///
+    /// Unless [rejects are allowed][Options::reject], nothing is changed if a single hunk doesn't apply. Otherwise all hunks
+    /// that apply are applied, and those that don't are written to `<path>.rej` in the worktree.
+    ///
+    /// Note that binary patches and patches to submodules are not supported, and that content filters like `core.autocrlf` aren't applied.
+    pub fn apply(&self, patch: &[u8], options: Options) -> Result<Outcome, Error> {
+        self.require_writable()?;
+        let mut files = patch::parse(patch)?;
+        if options.reverse {
+            files.iter_mut().for_each(patch::File::reverse);
+        }
+        let work_dir = if options.location != Location::Index || options.reject {
+            Some(self.require_worktree("Applying a patch")?.base())
+        } else {
+            None
+        };
+        let mut index = match options.location {
+            Location::Worktree => None,
+            Location::Index | Location::Both => Some(self.open_or_create_index()?),
+        };
+
+        let mut outcome = Outcome::default();
+        let mut changed = BTreeMap::<BString, Option<Content>>::new();
+        let mut rejected_hunks = Vec::new();
+        for file in &files {
+            for path in file.old_path.iter().chain(file.new_path.iter()) {
+                self.validate_patch_path(path.as_ref())?;
+            }
+            let path = file
+                .new_path
+                .as_ref()
+                .or(file.old_path.as_ref())
+                .expect("parsing assures at least one path is set");
+            if file.is_binary {
+                return Err(Error::Binary { path: path.clone() });
+            }
+            if file.old_mode == Some(EntryMode::Commit) || file.new_mode == Some(EntryMode::Commit) {
+                return Err(Error::Submodule { path: path.clone() });
+            }
+
+            let old = match &file.old_path {
+                Some(old_path) => {
+                    let current = match changed.get(old_path) {
+                        Some(content) => content.clone(),
+                        None => self.read_for_patch(old_path.as_ref(), options.location, index.as_deref(), work_dir)?,
+                    };
+                    Some(current.ok_or_else(|| Error::DoesNotExist { path: old_path.clone() })?)
+                }
+                None => None,
+            };
+            if let Some(new_path) = file
+                .new_path
+                .as_ref()
+                .filter(|new_path| file.old_path.as_ref() != Some(new_path))
+            {
+                let exists = match changed.get(new_path) {
+                    Some(content) => content.is_some(),
+                    None => {
+                        match self.read_for_patch(new_path.as_ref(), options.location, index.as_deref(), work_dir) {
+                            Ok(content) => content.is_some(),
+                            Err(Error::DoesNotMatchIndex { .. }) => true,
+                            Err(err) => return Err(err),
+                        }
+                    }
+                };
+                if exists {
+                    return Err(Error::AlreadyExists { path: new_path.clone() });
+                }
+            }
+
+            if options.whitespace != Whitespace::Nowarn {
+                outcome.whitespace_errors += file
+                    .hunks
+                    .iter()
+                    .flat_map(|hunk| &hunk.lines)
+                    .filter(|line| matches!(line, patch::Line::Added(line) if text::has_trailing_whitespace(line)))
+                    .count();
+            }
+            let (old_data, old_mode) = old.as_ref().map_or((&[][..], None), |(data, mode)| (data, Some(*mode)));
+            let applied = text::apply(old_data, &file.hunks, options.whitespace == Whitespace::Fix);
+            if let Some(hunk) = applied.rejected.first() {
+                if !options.reject {
+                    return Err(Error::DoesNotApply {
+                        path: path.clone(),
+                        hunk: hunk + 1,
+                    });
+                }
+                rejected_hunks.push((file, path.clone(), applied.rejected.clone()));
+                if file.new_path.is_none() {
+                    // Deleting a file that doesn't match what we would delete leaves it as is.
+                    continue;
+                }
+            }
+
+            match &file.new_path {
+                Some(new_path) => {
+                    if let Some(old_path) = file.old_path.as_ref().filter(|old_path| *old_path != new_path) {
+                        if !file.is_copy {
+                            changed.insert(old_path.clone(), None);
+                        }
+                    }
+                    let mode = file.new_mode.or(old_mode).unwrap_or(EntryMode::Blob);
+                    changed.insert(new_path.clone(), Some((applied.data, mode)));
+                }
+                None => {
+                    if !applied.data.is_empty() {
+                        return Err(Error::LeavesContent { path: path.clone() });
+                    }
+                    changed.insert(path.clone(), None);
+                }
+            }
+            outcome.paths.push(path.clone());
+        }
+        if options.whitespace == Whitespace::Error && outcome.whitespace_errors != 0 {
+            return Err(Error::Whitespace {
+                count: outcome.whitespace_errors,
+            });
+        }
+
+        let deletions = changed.iter().filter(|(_, content)| content.is_none());
+        let writes = changed.iter().filter(|(_, content)| content.is_some());
+        for (path, content) in deletions.chain(writes) {
+            if let Some(work_dir) = work_dir.filter(|_| options.location != Location::Index) {
+                write_to_worktree(work_dir, path.as_ref(), content.as_ref())?;
+            }
+            if let Some(index) = index.as_mut() {
+                match content {
+                    Some((data, mode)) => {
+                        let id = self.write_blob(data)?.detach();
+                        index.upsert_entry(path.as_ref(), id, index_mode(*mode), Default::default());
+                    }
+                    None => {
+                        if let Some(idx) = index.entry_index_by_path_and_stage(path.as_ref(), 0) {
+                            index.remove_entry_at_index(idx);
+                        }
+                    }
+                }
+            }
+        }
+        for (file, path, hunks) in rejected_hunks {
+            let work_dir = work_dir.expect("a work tree is required for rejects");
+            let append = outcome.rejects.iter().any(|reject| reject.path == path);
+            write_rejects(work_dir, file, path.as_ref(), &hunks, append).map_err(|err| Error::Io {
+                path: path.clone(),
+                source: err,
+            })?;
+            outcome.rejects.push(Reject {
+                path,
+                hunks: hunks.into_iter().map(|idx| idx + 1).collect(),
+            });
+        }
+        if let Some(index) = index.as_mut() {
+            index.write(
+                git_index::write::Options {
+                    hash_kind: self.object_hash(),
+                    ..Default::default()
+                },
+                self.config.fsync.method_if(self.config.fsync.index),
+            )?;
+        }
+        Ok(outcome)
+    }
+
+    fn open_or_create_index(&self) -> Result<git_index::File, Error> {
+        match self.open_index() {
+            Ok(index) => Ok(index),
+            Err(crate::worktree::open_index::Error::IndexFile(git_index::file::init::Error::Io(err)))
+                if err.kind() == std::io::ErrorKind::NotFound =>
+            {
+                let object_hash = self.object_hash();
+                let empty_tree = git_hash::ObjectId::empty_tree(object_hash);
+                let state = git_index::State::from_tree(&empty_tree, |_, _| {
+                    Some(git_object::TreeRefIter::from_bytes(&[], object_hash))
+                })?;
+                Ok(git_index::File {
+                    state,
+                    path: self.index_path(),
+                    checksum: git_hash::ObjectId::null(self.object_hash()),
+                })
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn validate_patch_path(&self, path: &BStr) -> Result<(), Error> {
+        for component in path.split_str(b"/") {
+            git_validate::path::component(component.as_bstr(), self.config.path_protections).map_err(|err| {
+                Error::InvalidPath {
+                    path: path.to_owned(),
+                    source: err,
+                }
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Read the content of `path` from `location`, or return `None` if it doesn't exist there.
+    ///
+    /// If it should be read from both, the index is authoritative but the worktree must match it.
+    fn read_for_patch(
+        &self,
+        path: &BStr,
+        location: Location,
+        index: Option<&git_index::State>,
+        work_dir: Option<&Path>,
+    ) -> Result<Option<Content>, Error> {
+        let read_worktree = || {
+            let work_dir = work_dir.expect("a work tree is present unless we only use the index");
+            read_from_worktree(&work_dir.join(git_path::from_bstr(path))).map_err(|err| Error::Io {
+                path: path.to_owned(),
+                source: err,
+            })
+        };
+        if location == Location::Worktree {
+            return read_worktree();
+        }
+
+        let index = index.expect("an index is present unless we only use the worktree");
+        let entry = match index.entry_by_path_and_stage(path, 0) {
+            Some(entry) => entry,
+            None if location == Location::Both && read_worktree()?.is_some() => {
+                return Err(Error::DoesNotMatchIndex { path: path.to_owned() })
+            }
+            None => return Ok(None),
+        };
+        let data = self.find_object(entry.id)?.detach().data;
+        if location == Location::Both {
+            let matches = read_worktree()?.map_or(false, |(worktree_data, _)| {
+                git_object::Data::new(git_object::Kind::Blob, &worktree_data, entry.id.kind())
+                    .verify_checksum(entry.id)
+                    .is_ok()
+            });
+            if !matches {
+                return Err(Error::DoesNotMatchIndex { path: path.to_owned() });
+            }
+        }
+        let mode = if entry.mode == git_index::entry::Mode::FILE_EXECUTABLE {
+            EntryMode::BlobExecutable
+        } else if entry.mode == git_index::entry::Mode::SYMLINK {
+            EntryMode::Link
+        } else if entry.mode == git_index::entry::Mode::COMMIT {
+            EntryMode::Commit
+        } else {
+            EntryMode::Blob
+        };
+        Ok(Some((data, mode)))
+    }
+}
+
+fn index_mode(mode: EntryMode) -> git_index::entry::Mode {
+    match mode {
+        EntryMode::BlobExecutable => git_index::entry::Mode::FILE_EXECUTABLE,
+        EntryMode::Link => git_index::entry::Mode::SYMLINK,
+        EntryMode::Commit => git_index::entry::Mode::COMMIT,
+        EntryMode::Blob | EntryMode::Tree => git_index::entry::Mode::FILE,
+    }
+}
+
+fn read_from_worktree(path: &Path) -> std::io::Result<Option<Content>> {
+    let meta = match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => return Ok(None),
+        Ok(meta) => meta,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    if meta.file_type().is_symlink() {
+        let target = git_path::into_bstr(std::fs::read_link(path)?).into_owned();
+        return Ok(Some((target.into(), EntryMode::Link)));
+    }
+    #[cfg(unix)]
+    let mode = {
+        use std::os::unix::fs::PermissionsExt;
+        if meta.permissions().mode() & 0o111 != 0 {
+            EntryMode::BlobExecutable
+        } else {
+            EntryMode::Blob
+        }
+    };
+    #[cfg(not(unix))]
+    let mode = EntryMode::Blob;
+    Ok(Some((std::fs::read(path)?, mode)))
+}
+
+/// Write `content` to `rela_path` in `work_dir`, or delete it along with directories that become empty if it's `None`.
+fn write_to_worktree(work_dir: &Path, rela_path: &BStr, content: Option<&Content>) -> Result<(), Error> {
+    let path = work_dir.join(git_path::from_bstr(rela_path));
+    let io_err = |err| Error::Io {
+        path: rela_path.to_owned(),
+        source: err,
+    };
+    let (data, mode) = match content {
+        Some(content) => content,
+        None => {
+            std::fs::remove_file(&path).map_err(io_err)?;
+            let mut dir = path.parent();
+            while let Some(parent) = dir.filter(|dir| *dir != work_dir) {
+                if std::fs::remove_dir(parent).is_err() {
+                    break;
+                }
+                dir = parent.parent();
+            }
+            return Ok(());
+        }
+    };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_err)?;
+    }
+    let existing = std::fs::symlink_metadata(&path).ok();
+    #[cfg(unix)]
+    if *mode == EntryMode::Link {
+        if existing.is_some() {
+            std::fs::remove_file(&path).map_err(io_err)?;
+        }
+        let target = git_path::try_from_byte_slice(data).map_err(|_| {
+            io_err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "symlink target is not a valid path",
+            ))
+        })?;
+        return std::os::unix::fs::symlink(target, &path).map_err(io_err);
+    }
+    if existing.map_or(false, |meta| meta.file_type().is_symlink()) {
+        std::fs::remove_file(&path).map_err(io_err)?;
+    }
+    std::fs::write(&path, data).map_err(io_err)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut permissions = std::fs::metadata(&path).map_err(io_err)?.permissions();
+        let current = permissions.mode();
+        let desired = if *mode == EntryMode::BlobExecutable {
+            current | ((current & 0o444) >> 2)
+        } else {
+            current & !0o111
+        };
+        if desired != current {
+            permissions.set_mode(desired);
+            std::fs::set_permissions(&path, permissions).map_err(io_err)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write the hunks of `file` at `rejected` indices to `<rela_path>.rej` in `work_dir`, like `git apply --reject` does, or
+/// `append` them if an earlier patch of the same file was rejected as well.
+fn write_rejects(
+    work_dir: &Path,
+    file: &patch::File,
+    rela_path: &BStr,
+    rejected: &[usize],
+    append: bool,
+) -> std::io::Result<()> {
+    let mut out = Vec::new();
+    out.extend_from_slice(b"diff a/");
+    out.extend_from_slice(file.old_path.as_ref().map_or(rela_path, |path| path.as_bstr()));
+    out.extend_from_slice(b" b/");
+    out.extend_from_slice(file.new_path.as_ref().map_or(rela_path, |path| path.as_bstr()));
+    out.extend_from_slice(b"\t(rejected hunks)\n");
+    for idx in rejected {
+        file.hunks[*idx].write_to(&mut out)?;
+    }
+    let mut path = work_dir.join(git_path::from_bstr(rela_path)).into_os_string();
+    path.push(".rej");
+    if let Some(parent) = Path::new(&path).parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)?
+        .write_all(&out)
+}
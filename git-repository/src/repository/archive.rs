@@ -0,0 +1,82 @@
+use std::io::Write;
+
+use git_hash::ObjectId;
+use git_object::{bstr::BString, tree::EntryMode};
+
+use crate::{
+    archive,
+    archive::{mode, Sink},
+    bstr::ByteVec,
+};
+
+impl crate::Repository {
+    /// Write all entries of the tree of `treeish`, which may be a commit or tag pointing to one, as archive to `out`
+    /// as configured by `options`, similar to `git archive`.
+    ///
+    /// Entries with the `export-ignore` attribute are skipped, and files with the `export-subst` attribute have their
+    /// `$Format:<format>$` placeholders expanded if `treeish` points to a commit.
+    /// Use [`worktree_stream()`][crate::Repository::worktree_stream()] to obtain these entries without writing them
+    /// into an archive.
+    pub fn archive(
+        &self,
+        treeish: impl Into<ObjectId>,
+        out: impl Write,
+        options: archive::Options,
+    ) -> Result<archive::Outcome, archive::Error> {
+        let mut stream = self.worktree_stream(treeish)?;
+        let mtime = options
+            .modification_time
+            .or_else(|| {
+                stream
+                    .commit()
+                    .map(|commit| commit.committer.time.seconds_since_unix_epoch)
+            })
+            .unwrap_or_else(|| git_date::Time::now_utc().seconds_since_unix_epoch);
+
+        let mut sink = Sink::new(options.format, out);
+        let mut entries = 0;
+        let mut path = options.prefix.unwrap_or_default();
+        if !path.is_empty() && path.ends_with(b"/") {
+            sink.add_directory(path.as_ref(), mtime)?;
+            entries += 1;
+        }
+        let prefix_len = path.len();
+        while let Some(entry) = stream.next_entry()? {
+            path.truncate(prefix_len);
+            path.push_str(entry.path);
+            write_entry(&mut sink, &entry, &mut path, mtime)?;
+            entries += 1;
+        }
+
+        sink.finish()?;
+        Ok(archive::Outcome {
+            tree_id: stream.tree_id(),
+            commit_id: stream.commit_id(),
+            entries,
+        })
+    }
+}
+
+/// Write `entry` to `sink` using `path`, which is the path of the entry within the archive.
+fn write_entry<W: Write>(
+    sink: &mut Sink<W>,
+    entry: &crate::worktree::stream::Entry<'_>,
+    path: &mut BString,
+    mtime: u32,
+) -> Result<(), archive::Error> {
+    match entry.mode {
+        // Submodules are represented by their empty directory, just like git does it.
+        EntryMode::Tree | EntryMode::Commit => {
+            path.push(b'/');
+            sink.add_directory(path.as_ref(), mtime)
+        }
+        EntryMode::Link => sink.add_symlink(path.as_ref(), mtime, entry.data()),
+        EntryMode::Blob | EntryMode::BlobExecutable => {
+            let mode = match entry.mode {
+                EntryMode::BlobExecutable => mode::EXECUTABLE,
+                _ => mode::FILE,
+            };
+            sink.add_file(path.as_ref(), mode, mtime, entry.data())
+        }
+    }
+}
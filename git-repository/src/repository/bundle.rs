@@ -0,0 +1,198 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    io::{BufRead, Write},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use git_features::{parallel::reduce::Finalize, progress::Progress};
+use git_hash::ObjectId;
+use git_odb::{Find, FindExt};
+
+use crate::{bundle, object::Kind};
+
+impl crate::Repository {
+    /// Write a bundle to `out` which contains the references named in `refs` along with the objects they need,
+    /// excluding all history reachable from the commits in `exclude`, similar to `git bundle create <file> <ref>… ^<exclude>…`.
+    ///
+    /// Parents of commits in the bundle which are excluded become its [prerequisites][bundle::Header::prerequisites], which the
+    /// receiver needs to have for the bundle to be usable. References pointing to excluded commits are not written to the bundle.
+    pub fn create_bundle<'a>(
+        &self,
+        refs: impl IntoIterator<Item = &'a str>,
+        exclude: impl IntoIterator<Item = impl Into<ObjectId>>,
+        mut out: impl Write,
+        mut progress: impl Progress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<bundle::create::Outcome, bundle::create::Error> {
+        use bundle::create::Error;
+        use git_pack::data::output;
+
+        let excluded = self
+            .rev_walk(exclude)
+            .all()?
+            .map(|id| id.map(|id| id.detach()))
+            .collect::<Result<HashSet<_>, _>>()?;
+
+        let mut bundle_refs = Vec::new();
+        let mut tips = Vec::new();
+        for name in refs {
+            let mut reference = self.find_reference(name)?;
+            let id = match reference.try_id() {
+                Some(id) => id.detach(),
+                None => reference.peel_to_id_in_place()?.detach(),
+            };
+            let commit_id = self.find_object(id)?.peel_to_kind(Kind::Commit)?.id;
+            if excluded.contains(&commit_id) {
+                continue;
+            }
+            tips.push(commit_id);
+            bundle_refs.push(bundle::Ref {
+                id,
+                name: reference.name().to_owned(),
+            });
+        }
+        if bundle_refs.is_empty() {
+            return Err(Error::Empty);
+        }
+
+        let mut commits = Vec::new();
+        let mut prerequisites = Vec::new();
+        {
+            let mut progress = progress.add_child("traversing");
+            progress.init(None, git_features::progress::count("commits"));
+            let mut seen: HashSet<_> = tips.iter().cloned().collect();
+            let mut queue: VecDeque<_> = tips.into_iter().collect();
+            let mut buf = Vec::new();
+            while let Some(id) = queue.pop_front() {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                for parent_id in self.objects.find_commit_iter(id, &mut buf)?.parent_ids() {
+                    if !seen.insert(parent_id) {
+                        continue;
+                    }
+                    if excluded.contains(&parent_id) {
+                        let commit = self.find_object(parent_id)?.peel_to_kind(Kind::Commit)?;
+                        prerequisites.push(bundle::Prerequisite {
+                            id: parent_id,
+                            comment: commit
+                                .to_commit_ref_iter()
+                                .message()
+                                .map(|message| {
+                                    git_object::commit::MessageRef::from_bytes(message)
+                                        .summary()
+                                        .into_owned()
+                                })
+                                .unwrap_or_default(),
+                        });
+                    } else {
+                        queue.push_back(parent_id);
+                    }
+                }
+                commits.push(id);
+                progress.inc();
+            }
+        }
+
+        let mut handle = self.objects.clone().into_arc()?;
+        handle.prevent_pack_unload();
+        handle.ignore_replacements = true;
+        let (counts, _) = output::count::objects_unthreaded(
+            handle.clone(),
+            commits
+                .into_iter()
+                .chain(bundle_refs.iter().map(|r| r.id))
+                .map(Ok::<_, std::convert::Infallible>),
+            progress.add_child("counting"),
+            should_interrupt,
+            output::count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+        )?;
+
+        let header = bundle::Header {
+            version: bundle::Version::V2,
+            capabilities: Vec::new(),
+            prerequisites,
+            refs: bundle_refs,
+        };
+        header.write_to(&mut out)?;
+
+        let num_objects = counts.len() as u32;
+        let mut entries = git_features::parallel::InOrderIter::from(output::entry::iter_from_counts(
+            counts,
+            handle,
+            progress.add_child("creating entries"),
+            output::entry::iter_from_counts::Options {
+                mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                ..Default::default()
+            },
+        ));
+        let mut write = output::bytes::FromEntriesIter::new(
+            entries.by_ref(),
+            &mut out,
+            num_objects,
+            git_pack::data::Version::V2,
+            self.object_hash(),
+        );
+        for written in write.by_ref() {
+            written?;
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+        }
+        let pack_checksum = write.digest().expect("iteration is done");
+        entries.inner.finalize()?;
+
+        Ok(bundle::create::Outcome {
+            header,
+            num_objects,
+            pack_checksum,
+        })
+    }
+
+    /// Check if all [prerequisites][bundle::Header::prerequisites] of the bundle with `header` are present in this repository.
+    pub fn verify_bundle(&self, header: &bundle::Header) -> Result<(), bundle::verify::Error> {
+        let missing: Vec<_> = header
+            .prerequisites
+            .iter()
+            .filter(|prerequisite| !self.objects.contains(prerequisite.id))
+            .map(|prerequisite| prerequisite.id)
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(bundle::verify::Error::MissingPrerequisites { ids: missing })
+        }
+    }
+
+    /// Read the bundle from `bundle`, [verify][Self::verify_bundle()] it and write its pack into the object database of this repository,
+    /// similar to `git bundle unbundle`.
+    ///
+    /// No reference is changed, instead the [header][bundle::unbundle::Outcome::header] of the bundle is returned for the caller to
+    /// update references with as needed.
+    pub fn unbundle(
+        &self,
+        mut bundle: impl BufRead,
+        progress: impl Progress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<bundle::unbundle::Outcome, bundle::unbundle::Error> {
+        self.require_writable()?;
+        let header = bundle::Header::from_read(&mut bundle)?;
+        self.verify_bundle(&header)?;
+
+        let pack_directory = self.objects.store_ref().path().join("pack");
+        std::fs::create_dir_all(&pack_directory)?;
+        let write = git_pack::Bundle::write_to_directory(
+            bundle,
+            Some(pack_directory),
+            progress,
+            should_interrupt,
+            None,
+            git_pack::bundle::write::Options {
+                object_hash: self.object_hash(),
+                fsync: self.config.fsync.method_if(self.config.fsync.packs),
+                ..Default::default()
+            },
+        )?;
+        Ok(bundle::unbundle::Outcome { header, write })
+    }
+}
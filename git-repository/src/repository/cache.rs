@@ -27,6 +27,35 @@ impl crate::Repository {
         }
     }
 
+    /// Sets the amount of space used at most for caching delta-base objects while decoding objects in packs to `Some(bytes)`,
+    /// or `None` to deactivate it entirely, which is the programmatic equivalent to `GITOXIDE_PACK_CACHE_MEMORY`.
+    ///
+    /// This cache speeds up decoding objects with long delta chains, which is common during traversals of large histories,
+    /// at the cost of memory. Note that like all caches it's _per-thread_, as each clone of this instance creates its own.
+    #[cfg(feature = "max-performance-safe")]
+    pub fn pack_cache_size(&mut self, bytes: impl Into<Option<usize>>) {
+        match bytes.into() {
+            Some(bytes) => self
+                .objects
+                .set_pack_cache(move || Box::new(git_pack::cache::lru::MemoryCappedHashmap::new(bytes))),
+            None => self.objects.unset_pack_cache(),
+        }
+    }
+
+    /// Return the hits and misses of the object and pack cache of this instance since it was created or since
+    /// the last call to [`reset_cache_statistics()`][Self::reset_cache_statistics()].
+    ///
+    /// Use it to find cache sizes that work well for a particular workload, for instance by running it with different sizes set
+    /// with [`object_cache_size()`][Self::object_cache_size()]. Note that the statistics are _per-thread_ just like the caches.
+    pub fn cache_statistics(&self) -> git_odb::cache::Statistics {
+        self.objects.statistics()
+    }
+
+    /// Set the hits and misses of all caches of this instance back to zero.
+    pub fn reset_cache_statistics(&self) {
+        self.objects.reset_statistics()
+    }
+
     /// Read well-known environment variables related to caches and apply them to this instance, but not to clones of it - each
     /// needs their own configuration.
     ///
@@ -48,21 +77,18 @@ impl crate::Repository {
             let pack_cache_disabled = std::env::var_os("GITOXIDE_DISABLE_PACK_CACHE").is_some();
             let mut this = self;
             if !pack_cache_disabled {
-                let bytes = parse_bytes_from_var("GITOXIDE_PACK_CACHE_MEMORY");
-                let new_pack_cache = move || -> Box<git_odb::cache::PackCache> {
-                    match bytes {
-                        Some(bytes) => Box::new(git_pack::cache::lru::MemoryCappedHashmap::new(bytes)),
-                        None => Box::new(git_pack::cache::lru::StaticLinkedList::<64>::default()),
-                    }
-                };
-                this.objects.set_pack_cache(new_pack_cache);
+                match parse_bytes_from_var("GITOXIDE_PACK_CACHE_MEMORY") {
+                    Some(bytes) => this.pack_cache_size(bytes),
+                    None => this
+                        .objects
+                        .set_pack_cache(|| Box::new(git_pack::cache::lru::StaticLinkedList::<64>::default())),
+                }
             } else {
-                this.objects.unset_pack_cache();
+                this.pack_cache_size(None);
             }
 
             if let Some(bytes) = parse_bytes_from_var("GITOXIDE_OBJECT_CACHE_MEMORY") {
-                this.objects
-                    .set_object_cache(move || Box::new(git_pack::cache::object::MemoryCappedHashmap::new(bytes)));
+                this.object_cache_size(bytes);
             }
             this
         }
@@ -0,0 +1,320 @@
+use std::collections::{HashMap, HashSet};
+
+use git_hash::ObjectId;
+use git_odb::{Find, FindExt, Write};
+use git_ref::TargetRef;
+
+use crate::{
+    check::{Action, Error, Finding, Options, Outcome, Severity},
+    object::Kind,
+    worktree::open_index,
+};
+
+/// An object seen while iterating the object database.
+struct Node {
+    /// The kind of the object, or `None` if it couldn't be read.
+    kind: Option<Kind>,
+    /// All objects the object refers to, only collected if connectivity is checked.
+    edges: Vec<ObjectId>,
+}
+
+/// Count findings and pass them on to the caller.
+struct Report<F> {
+    cb: F,
+    outcome: Outcome,
+}
+
+impl<F> Report<F>
+where
+    F: FnMut(Finding) -> Action,
+{
+    /// Return `true` if the check should be cancelled.
+    fn finding(&mut self, finding: Finding) -> bool {
+        match finding.severity() {
+            Severity::Error => self.outcome.errors += 1,
+            Severity::Warning => self.outcome.warnings += 1,
+            Severity::Info => self.outcome.infos += 1,
+        }
+        self.outcome.cancelled = (self.cb)(finding) == Action::Cancel;
+        self.outcome.cancelled
+    }
+}
+
+impl crate::Repository {
+    /// Check the integrity of all objects in the object database, including those of alternates, along with all references and
+    /// the index of the worktree, similar to `git fsck`, and call `cb` with each [`Finding`] until it returns [`Action::Cancel`].
+    ///
+    /// Each object is read, has its hash verified and is decoded, and with [`Options::strict`] validated to follow the rules of its format.
+    /// With [`Options::connectivity`], all objects referred to by other objects, references and the index must exist, and objects
+    /// that can't be reached from references or the index are reported as dangling or, with [`Options::unreachable`], unreachable.
+    /// Note that reflogs aren't used to determine reachability.
+    ///
+    /// Problems with the repository are reported as findings, whereas errors are only returned if the check itself can't proceed.
+    pub fn check(&self, options: Options, cb: impl FnMut(Finding) -> Action) -> Result<Outcome, Error> {
+        let mut report = Report {
+            cb,
+            outcome: Outcome::default(),
+        };
+        macro_rules! report {
+            ($finding:expr) => {
+                if report.finding($finding) {
+                    return Ok(report.outcome);
+                }
+            };
+        }
+
+        let sink = git_odb::sink(self.object_hash());
+        let mut objects = HashMap::<ObjectId, Node>::new();
+        let mut buf = Vec::new();
+        for id in self.objects.iter()? {
+            let id = id?;
+            if objects.contains_key(&id) {
+                continue;
+            }
+            report.outcome.objects += 1;
+            let data = match self.objects.try_find(id, &mut buf) {
+                Ok(Some(data)) => data,
+                Ok(None) => {
+                    objects.insert(
+                        id,
+                        Node {
+                            kind: None,
+                            edges: vec![],
+                        },
+                    );
+                    report!(Finding::Unreadable {
+                        id,
+                        message: "The object disappeared while it was checked".into(),
+                    });
+                    continue;
+                }
+                Err(err) if err.is_corrupt() => {
+                    objects.insert(
+                        id,
+                        Node {
+                            kind: None,
+                            edges: vec![],
+                        },
+                    );
+                    report!(Finding::Unreadable {
+                        id,
+                        message: err.to_string(),
+                    });
+                    continue;
+                }
+                Err(err) => return Err(err.into()),
+            };
+            let kind = data.kind;
+            let mut findings = Vec::new();
+            if options.verify_hashes {
+                let actual = sink.write_buf(kind, data.data).expect("hashing in memory cannot fail");
+                if actual != id {
+                    findings.push(Finding::HashMismatch { id, kind, actual });
+                }
+            }
+            let mut edges = Vec::new();
+            match git_object::ObjectRef::from_bytes(kind, data.data, data.object_hash) {
+                Ok(object) => {
+                    if options.connectivity {
+                        edges = referenced_objects(&object);
+                    }
+                    if options.strict {
+                        if let Some(message) = validate(object) {
+                            findings.push(Finding::Malformed { id, kind, message });
+                        }
+                    }
+                }
+                Err(err) => findings.push(Finding::Unparsable {
+                    id,
+                    kind,
+                    message: err.to_string(),
+                }),
+            }
+            objects.insert(
+                id,
+                Node {
+                    kind: Some(kind),
+                    edges,
+                },
+            );
+            for finding in findings {
+                report!(finding);
+            }
+        }
+
+        let mut roots = Vec::new();
+        let head = self.try_find_reference("HEAD");
+        for reference in head
+            .transpose()
+            .into_iter()
+            .map(|res| res.map_err(|err| Box::new(err) as Box<dyn std::error::Error + Send + Sync + 'static>))
+            .chain(self.references()?.all()?)
+        {
+            report.outcome.references += 1;
+            let reference = match reference {
+                Ok(reference) => reference,
+                Err(err) => {
+                    report!(Finding::InvalidReference {
+                        name: None,
+                        message: err.to_string(),
+                    });
+                    continue;
+                }
+            };
+            let name = reference.name().as_bstr().to_owned();
+            match reference.target() {
+                TargetRef::Peeled(id) => {
+                    roots.push(id.to_owned());
+                    if !objects.contains_key(id) {
+                        report!(Finding::MissingReferenceTarget {
+                            name,
+                            id: id.to_owned(),
+                        });
+                    }
+                }
+                TargetRef::Symbolic(target) => match self.try_find_reference(target) {
+                    Ok(Some(_)) => {}
+                    Ok(None) => report!(Finding::DanglingSymbolicReference {
+                        name,
+                        target: target.as_bstr().to_owned(),
+                    }),
+                    Err(err) => report!(Finding::InvalidReference {
+                        name: Some(target.as_bstr().to_owned()),
+                        message: err.to_string(),
+                    }),
+                },
+            }
+        }
+
+        if options.index && self.work_dir().is_some() {
+            match self.open_index() {
+                Ok(index) => {
+                    let results = vec![
+                        index.verify_integrity().map_err(|err| err.to_string()),
+                        index.verify_entries().map_err(|err| err.to_string()),
+                        index
+                            .verify_extensions(true, |oid, buf| self.objects.find_tree_iter(oid, buf).ok())
+                            .map_err(|err| err.to_string()),
+                    ];
+                    for message in results.into_iter().filter_map(Result::err) {
+                        report!(Finding::InvalidIndex { message });
+                    }
+                    for entry in index.entries() {
+                        if entry.mode == git_index::entry::Mode::COMMIT {
+                            continue;
+                        }
+                        roots.push(entry.id);
+                        if !objects.contains_key(&entry.id) {
+                            report!(Finding::MissingIndexObject {
+                                path: entry.path(&index).to_owned(),
+                                id: entry.id,
+                            });
+                        }
+                    }
+                }
+                Err(open_index::Error::IndexFile(git_index::file::init::Error::Io(err)))
+                    if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => report!(Finding::InvalidIndex {
+                    message: err.to_string(),
+                }),
+            }
+        }
+
+        if options.connectivity {
+            let mut ids: Vec<_> = objects.keys().copied().collect();
+            ids.sort_unstable();
+
+            let mut referenced = HashSet::new();
+            for id in &ids {
+                for edge in &objects[id].edges {
+                    referenced.insert(*edge);
+                    if !objects.contains_key(edge) {
+                        report!(Finding::MissingObject {
+                            id: *edge,
+                            referenced_by: *id,
+                        });
+                    }
+                }
+            }
+
+            let mut reachable = HashSet::new();
+            while let Some(id) = roots.pop() {
+                if !reachable.insert(id) {
+                    continue;
+                }
+                if let Some(node) = objects.get(&id) {
+                    roots.extend(node.edges.iter().filter(|id| !reachable.contains(*id)));
+                }
+            }
+
+            for id in ids.into_iter().filter(|id| !reachable.contains(id)) {
+                let kind = match objects[&id].kind {
+                    Some(kind) => kind,
+                    None => continue,
+                };
+                if !referenced.contains(&id) {
+                    report!(Finding::Dangling { id, kind });
+                } else if options.unreachable {
+                    report!(Finding::Unreachable { id, kind });
+                }
+            }
+        }
+        Ok(report.outcome)
+    }
+}
+
+/// Return the ids of all objects `object` refers to, except for submodule commits.
+fn referenced_objects(object: &git_object::ObjectRef<'_>) -> Vec<ObjectId> {
+    use git_object::ObjectRef;
+    match object {
+        ObjectRef::Commit(commit) => std::iter::once(commit.tree()).chain(commit.parents()).collect(),
+        ObjectRef::Tree(tree) => tree
+            .entries
+            .iter()
+            .filter(|entry| entry.mode != git_object::tree::EntryMode::Commit)
+            .map(|entry| entry.oid.to_owned())
+            .collect(),
+        ObjectRef::Tag(tag) => vec![tag.target()],
+        ObjectRef::Blob(_) => Vec::new(),
+    }
+}
+
+/// Return a description of the first rule `object` violates, if any.
+fn validate(object: git_object::ObjectRef<'_>) -> Option<String> {
+    use git_object::{commit, tree, ObjectRef};
+    match object {
+        ObjectRef::Tree(tree) => {
+            let tree: git_object::Tree = tree.into();
+            let built = tree
+                .entries
+                .iter()
+                .fold(tree::build::Builder::new(), |builder, entry| {
+                    builder.entry(entry.mode, entry.filename.clone(), entry.oid)
+                })
+                .build();
+            match built {
+                Ok(built) if built.entries != tree.entries => Some("Tree entries are not sorted".into()),
+                Ok(_) => None,
+                Err(err) => Some(err.to_string()),
+            }
+        }
+        ObjectRef::Commit(commit) => {
+            let commit: git_object::Commit = commit.into();
+            let mut builder = commit::build::Builder::new(commit.tree)
+                .author(commit.author)
+                .committer(commit.committer)
+                .message(commit.message);
+            for parent in commit.parents {
+                builder = builder.parent(parent);
+            }
+            if let Some(encoding) = commit.encoding {
+                builder = builder.encoding(encoding);
+            }
+            for (name, value) in commit.extra_headers {
+                builder = builder.extra_header(name, value);
+            }
+            builder.build().err().map(|err| err.to_string())
+        }
+        ObjectRef::Tag(_) | ObjectRef::Blob(_) => None,
+    }
+}
@@ -24,6 +24,14 @@ impl crate::Repository {
     pub fn object_hash(&self) -> git_hash::Kind {
         self.config.object_hash
     }
+
+    /// The policy for flushing written files to permanent storage, as configured by `core.fsync` and `core.fsyncMethod`.
+    ///
+    /// It's applied automatically when writing objects, packs and references, and callers writing an index
+    /// should pass `policy.method_if(policy.index)` along.
+    pub fn fsync_policy(&self) -> crate::fsync::Policy {
+        self.config.fsync
+    }
 }
 
 mod remote {
@@ -1,6 +1,10 @@
 use std::borrow::Cow;
 
-use crate::{bstr::BString, permission};
+use crate::{
+    bstr::{BString, ByteSlice},
+    identity::{Error, Identity, Source},
+    permission,
+};
 
 /// Identity handling.
 impl crate::Repository {
@@ -20,81 +24,100 @@ impl crate::Repository {
         }
     }
 
-    /// Return the committer as configured by this repository, which is determined by…
+    /// Return the committer as configured by this repository along with the [source][Source] of each of its values,
+    /// which is determined like git does it by…
     ///
-    /// * …the git configuration `committer.name|email`…
     /// * …the `GIT_COMMITTER_(NAME|EMAIL|DATE)` environment variables…
+    /// * …the git configuration `committer.name|email`…
     /// * …the configuration for `user.name|email` as fallback…
+    /// * …the `EMAIL` environment variable for the email…
+    /// * …and a name and email guessed from the name of the current user and host…
     ///
-    /// …and in that order, or `None` if there was nothing configured. In that case, one may use the
-    /// [`committer_or_default()`][Self::committer_or_default()] method.
+    /// …and in that order. Guessing fails with an error if `user.useConfigOnly` is set, and the time is the current time
+    /// unless it is set in the environment.
     ///
     /// # Note
     ///
     /// The values are cached when the repository is instantiated.
-    pub fn committer(&self) -> Option<git_actor::SignatureRef<'_>> {
-        let p = self.config.personas();
-
-        git_actor::SignatureRef {
-            name: p.committer.name.as_ref().or(p.user.name.as_ref()).map(|v| v.as_ref())?,
-            email: p
-                .committer
-                .email
-                .as_ref()
-                .or(p.user.email.as_ref())
-                .map(|v| v.as_ref())?,
-            time: p.committer.time.unwrap_or_else(git_date::Time::now_local_or_utc),
-        }
-        .into()
+    pub fn committer(&self) -> Result<Identity<'_>, Error> {
+        self.config.personas().resolve(Role::Committer, true)
     }
 
-    /// Like [`committer()`][Self::committer()], but may use a default value in case nothing is configured.
+    /// Like [`committer()`][Self::committer()], but will guess values even if `user.useConfigOnly` is set and
+    /// use a default value in case no identity can be determined, similar to what git uses in reflogs.
     pub fn committer_or_default(&self) -> git_actor::SignatureRef<'_> {
-        self.committer().unwrap_or_else(|| self.user_default())
+        self.config
+            .personas()
+            .resolve(Role::Committer, false)
+            .map(|identity| identity.signature)
+            .unwrap_or_else(|_| self.user_default())
     }
 
-    /// Return the author as configured by this repository, which is determined by…
+    /// Return the author as configured by this repository along with the [source][Source] of each of its values,
+    /// which is determined like git does it by…
     ///
-    /// * …the git configuration `author.name|email`…
     /// * …the `GIT_AUTHOR_(NAME|EMAIL|DATE)` environment variables…
+    /// * …the git configuration `author.name|email`…
     /// * …the configuration for `user.name|email` as fallback…
+    /// * …the `EMAIL` environment variable for the email…
+    /// * …and a name and email guessed from the name of the current user and host…
     ///
-    /// …and in that order, or `None` if there was nothing configured. In that case, one may use the
-    /// [`author_or_default()`][Self::author_or_default()] method.
+    /// …and in that order. Guessing fails with an error if `user.useConfigOnly` is set, and the time is the current time
+    /// unless it is set in the environment.
     ///
     /// # Note
     ///
     /// The values are cached when the repository is instantiated.
-    pub fn author(&self) -> Option<git_actor::SignatureRef<'_>> {
-        let p = self.config.personas();
-
-        git_actor::SignatureRef {
-            name: p.author.name.as_ref().or(p.user.name.as_ref()).map(|v| v.as_ref())?,
-            email: p.author.email.as_ref().or(p.user.email.as_ref()).map(|v| v.as_ref())?,
-            time: p.author.time.unwrap_or_else(git_date::Time::now_local_or_utc),
-        }
-        .into()
+    pub fn author(&self) -> Result<Identity<'_>, Error> {
+        self.config.personas().resolve(Role::Author, true)
     }
 
-    /// Like [`author()`][Self::author()], but may use a default value in case nothing is configured.
+    /// Like [`author()`][Self::author()], but will guess values even if `user.useConfigOnly` is set and
+    /// use a default value in case no identity can be determined.
     pub fn author_or_default(&self) -> git_actor::SignatureRef<'_> {
-        self.author().unwrap_or_else(|| self.user_default())
+        self.config
+            .personas()
+            .resolve(Role::Author, false)
+            .map(|identity| identity.signature)
+            .unwrap_or_else(|_| self.user_default())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Role {
+    Author,
+    Committer,
+}
+
+impl Role {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Role::Author => "author",
+            Role::Committer => "committer",
+        }
     }
 }
 
+type Value = (BString, Source);
+
 #[derive(Debug, Clone)]
 pub(crate) struct Entity {
-    pub name: Option<BString>,
-    pub email: Option<BString>,
-    /// A time parsed from an environment variable.
-    pub time: Option<git_actor::Time>,
+    pub name: Option<Value>,
+    pub email: Option<Value>,
+    /// A time parsed from the given environment variable, or its value if it couldn't be parsed.
+    pub time: Option<(&'static str, Result<git_actor::Time, BString>)>,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct Personas {
-    user: Entity,
     committer: Entity,
     author: Entity,
+    /// The name of the current user, used if no name is configured.
+    guessed_name: Option<BString>,
+    /// The name of the current user at the current host, used if no email is configured.
+    guessed_email: Option<BString>,
+    /// If `true`, `user.useConfigOnly` is set and we may not guess names and emails.
+    use_config_only: bool,
 }
 
 impl Personas {
@@ -102,55 +125,134 @@ impl Personas {
         fn env_var(name: &str) -> Option<BString> {
             std::env::var_os(name).map(|value| git_path::into_bstr(Cow::Owned(value.into())).into_owned())
         }
-        fn entity_in_section(name: &str, config: &git_config::File<'_>) -> (Option<BString>, Option<BString>) {
-            config
-                .section(name, None)
-                .map(|section| {
+        let git_env_allowed = git_env.eq(&git_sec::Permission::Allow);
+        let from_env = |variable: &'static str| {
+            git_env_allowed
+                .then(|| env_var(variable))
+                .flatten()
+                .map(|value| (value, Source::Environment { variable }))
+        };
+        let from_config = |key: &'static str| {
+            let (section_name, value_name) = key.split_once('.').expect("valid key");
+            config.sections_by_name(section_name).and_then(|sections| {
+                sections
+                    .filter_map(|section| {
+                        section.value(value_name).map(|value| {
+                            (
+                                value.into_owned(),
+                                Source::Configuration {
+                                    key,
+                                    source: section.meta().source,
+                                },
+                            )
+                        })
+                    })
+                    .last()
+            })
+        };
+        let entity = |[name_var, email_var, date_var]: [&'static str; 3], [name_key, email_key]: [&'static str; 2]| {
+            Entity {
+                name: from_env(name_var)
+                    .or_else(|| from_config(name_key))
+                    .or_else(|| from_config("user.name")),
+                email: from_env(email_var)
+                    .or_else(|| from_config(email_key))
+                    .or_else(|| from_config("user.email"))
+                    .or_else(|| from_env("EMAIL")), // NOTE: we don't have permission for this specific one…
+                time: from_env(date_var).map(|(date, _)| {
                     (
-                        section.value("name").map(|v| v.into_owned()),
-                        section.value("email").map(|v| v.into_owned()),
+                        date_var,
+                        date.to_str()
+                            .ok()
+                            .and_then(|date| git_date::parse(date, None))
+                            .ok_or(date),
                     )
-                })
-                .unwrap_or_default()
-        }
-
-        let (mut committer_name, mut committer_email) = entity_in_section("committer", config);
-        let mut committer_date = None;
-        let (mut author_name, mut author_email) = entity_in_section("author", config);
-        let mut author_date = None;
-        let (user_name, mut user_email) = entity_in_section("user", config);
-
-        if git_env.eq(&git_sec::Permission::Allow) {
-            committer_name = committer_name.or_else(|| env_var("GIT_COMMITTER_NAME"));
-            committer_email = committer_email.or_else(|| env_var("GIT_COMMITTER_EMAIL"));
-            committer_date = std::env::var("GIT_COMMITTER_DATE")
-                .ok()
-                .and_then(|date| git_date::parse(&date));
+                }),
+            }
+        };
 
-            author_name = author_name.or_else(|| env_var("GIT_AUTHOR_NAME"));
-            author_email = author_email.or_else(|| env_var("GIT_AUTHOR_EMAIL"));
-            author_date = std::env::var("GIT_AUTHOR_DATE")
-                .ok()
-                .and_then(|date| git_date::parse(&date));
-
-            user_email = user_email.or_else(|| env_var("EMAIL")); // NOTE: we don't have permission for this specific one…
-        }
+        let guessed_name = ["USER", "USERNAME", "LOGNAME"]
+            .iter()
+            .find_map(|name| env_var(name).filter(|name| !name.is_empty()));
+        let guessed_email = guessed_name
+            .as_ref()
+            .zip(hostname())
+            .map(|(user, host)| format!("{}@{}", user, host).into());
         Personas {
-            user: Entity {
-                name: user_name,
-                email: user_email,
-                time: None,
-            },
-            committer: Entity {
-                name: committer_name,
-                email: committer_email,
-                time: committer_date,
-            },
-            author: Entity {
-                name: author_name,
-                email: author_email,
-                time: author_date,
-            },
+            committer: entity(
+                ["GIT_COMMITTER_NAME", "GIT_COMMITTER_EMAIL", "GIT_COMMITTER_DATE"],
+                ["committer.name", "committer.email"],
+            ),
+            author: entity(
+                ["GIT_AUTHOR_NAME", "GIT_AUTHOR_EMAIL", "GIT_AUTHOR_DATE"],
+                ["author.name", "author.email"],
+            ),
+            guessed_name,
+            guessed_email,
+            use_config_only: config
+                .boolean("user", None, "useConfigOnly")
+                .and_then(Result::ok)
+                .unwrap_or(false),
         }
     }
+
+    /// Produce the identity for `role`, failing if values would have to be guessed and `user.useConfigOnly` is set
+    /// if `strict` is `true`.
+    pub fn resolve<'a>(&'a self, role: Role, strict: bool) -> Result<Identity<'a>, Error> {
+        let entity = match role {
+            Role::Author => &self.author,
+            Role::Committer => &self.committer,
+        };
+        let may_guess = !(strict && self.use_config_only);
+        let value = |value: &'a Option<Value>, guessed: &'a Option<BString>, field: &'static str| match value {
+            Some((value, source)) => Ok((value.as_bstr(), *source)),
+            None if !may_guess => Err(Error::AutoDetectionDisabled {
+                role: role.as_str(),
+                field,
+            }),
+            None => {
+                guessed
+                    .as_ref()
+                    .map(|value| (value.as_bstr(), Source::Guessed))
+                    .ok_or(Error::AutoDetectionFailed {
+                        role: role.as_str(),
+                        field,
+                    })
+            }
+        };
+        let (name, name_source) = value(&entity.name, &self.guessed_name, "name")?;
+        let (email, email_source) = value(&entity.email, &self.guessed_email, "email")?;
+        let (time, time_source) = match &entity.time {
+            Some((variable, Ok(time))) => (*time, Source::Environment { variable }),
+            Some((variable, Err(value))) => {
+                return Err(Error::InvalidDate {
+                    variable,
+                    value: value.clone(),
+                })
+            }
+            None => (git_date::Time::now_local_or_utc(), Source::CurrentTime),
+        };
+        Ok(Identity {
+            signature: git_actor::SignatureRef { name, email, time },
+            name: name_source,
+            email: email_source,
+            time: time_source,
+        })
+    }
+}
+
+/// Return the name of the current host from the environment or the system, if possible.
+fn hostname() -> Option<String> {
+    ["HOSTNAME", "COMPUTERNAME"]
+        .iter()
+        .find_map(|name| std::env::var(name).ok())
+        .or_else(|| {
+            if cfg!(unix) {
+                std::fs::read_to_string("/etc/hostname").ok()
+            } else {
+                None
+            }
+        })
+        .map(|name| name.trim().to_owned())
+        .filter(|name| !name.is_empty())
 }
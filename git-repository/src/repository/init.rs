@@ -9,6 +9,7 @@ impl crate::Repository {
         config: crate::config::Cache,
         linked_worktree_options: crate::open::Options,
         index: crate::worktree::IndexStorage,
+        environment: Option<Box<crate::open::EnvironmentOverrides>>,
     ) -> Self {
         crate::Repository {
             bufs: RefCell::new(Vec::with_capacity(4)),
@@ -28,6 +29,7 @@ impl crate::Repository {
             config,
             options: linked_worktree_options,
             index,
+            environment,
         }
     }
 
@@ -21,8 +21,19 @@ impl crate::Repository {
     }
 
     /// Return the path to the worktree index file, which may or may not exist.
+    ///
+    /// It's overridden by `GIT_INDEX_FILE` if [environment overrides][crate::open::Options::environment_overrides()] are enabled.
     pub fn index_path(&self) -> PathBuf {
-        self.git_dir().join("index")
+        self.environment
+            .as_ref()
+            .and_then(|env| env.index_file.clone())
+            .unwrap_or_else(|| self.git_dir().join("index"))
+    }
+
+    /// Return the overrides read from `GIT_*` environment variables when opening this repository, or `None` if
+    /// [environment overrides][crate::open::Options::environment_overrides()] weren't enabled.
+    pub fn environment_overrides(&self) -> Option<&crate::open::EnvironmentOverrides> {
+        self.environment.as_deref()
     }
 
     /// The path to the `.git` directory itself, or equivalent if this is a bare repository.
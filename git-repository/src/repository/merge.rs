@@ -0,0 +1,8 @@
+impl crate::Repository {
+    /// Return a platform to select and run merge drivers, as configured by the `merge` attribute and `merge.<driver>.*` configuration.
+    ///
+    /// In-process drivers can be registered with [`Drivers::register()`][crate::merge::driver::Drivers::register()].
+    pub fn merge_drivers(&self) -> crate::merge::driver::Drivers<'_> {
+        crate::merge::driver::Drivers::new(self)
+    }
+}
@@ -19,18 +19,28 @@ impl crate::Repository {
     }
 }
 
+mod apply;
+#[cfg(feature = "archive")]
+mod archive;
+mod bundle;
 mod cache;
+mod check;
 mod config;
 pub(crate) mod identity;
 mod impls;
 mod init;
 mod location;
+mod merge;
 mod object;
 pub(crate) mod permissions;
+mod read_only;
 mod reference;
 mod remote;
 mod revision;
+mod serve;
+mod shortlog;
 mod snapshots;
 mod state;
+mod status;
 mod thread_safe;
 mod worktree;
@@ -11,6 +11,14 @@ use crate::{commit, ext::ObjectIdExt, object, tag, Id, Object, Reference};
 
 /// Methods related to object creation.
 impl crate::Repository {
+    /// Return an iterator over all objects in the object database, loose and packed, including those of alternates,
+    /// as configured by `options`.
+    ///
+    /// All pack indices are loaded eagerly when calling this method.
+    pub fn objects_iter(&self, options: object::iter::Options) -> Result<object::iter::Iter, object::iter::Error> {
+        object::iter::Iter::new(self.objects.iter()?, options)
+    }
+
     /// Find the object with `id` in the object database or return an error if it could not be found.
     ///
     /// There are various legitimate reasons for an object to not be present, which is why
@@ -43,6 +51,9 @@ impl crate::Repository {
 
     /// Try to find the object with `id` or return `None` it it wasn't found.
     ///
+    /// Objects that exist but can't be decoded yield an error for which [`is_corrupt()`][object::find::Error::is_corrupt()]
+    /// returns `true`, which allows to tell them apart from missing objects and I/O errors.
+    ///
     /// # Important
     ///
     /// As a shared buffer is written to back the object data, the returned `ObjectRef` will prevent other
@@ -60,7 +71,11 @@ impl crate::Repository {
         }
 
         let mut buf = self.free_buf();
-        match self.objects.try_find(&id, &mut buf)? {
+        match self
+            .objects
+            .try_find(&id, &mut buf)
+            .map_err(|err| object::find::Error::new(id, err))?
+        {
             Some(obj) => {
                 let kind = obj.kind;
                 Ok(Some(Object::from_data(id, kind, buf, self)))
@@ -69,8 +84,40 @@ impl crate::Repository {
         }
     }
 
+    /// Obtain only the kind and size of the object with `id`, or return an error if it could not be found.
+    ///
+    /// This is much cheaper than [`find_object()`][crate::Repository::find_object()] as the object doesn't have to be
+    /// decoded, even if it is packed with deltas.
+    pub fn find_header(&self, id: impl Into<ObjectId>) -> Result<object::Header, object::find::existing::Error> {
+        let id = id.into();
+        self.try_find_header(id)
+            .map_err(|err| match err {
+                object::find::Error::Corrupt { source, .. } | object::find::Error::Find { source, .. } => {
+                    git_odb::find::existing::Error::Find(source)
+                }
+            })?
+            .ok_or(git_odb::find::existing::Error::NotFound { oid: id })
+    }
+
+    /// Try to obtain only the kind and size of the object with `id`, or return `None` if it wasn't found.
+    ///
+    /// See [`find_header()`][crate::Repository::find_header()] for details.
+    pub fn try_find_header(&self, id: impl Into<ObjectId>) -> Result<Option<object::Header>, object::find::Error> {
+        let id = id.into();
+        if id == git_hash::ObjectId::empty_tree(self.object_hash()) {
+            return Ok(Some(object::Header::Loose {
+                kind: git_object::Kind::Tree,
+                size: 0,
+            }));
+        }
+        self.objects
+            .try_header(&id)
+            .map_err(|err| object::find::Error::new(id, err))
+    }
+
     /// Write the given object into the object database and return its object id.
     pub fn write_object(&self, object: impl git_object::WriteTo) -> Result<Id<'_>, object::write::Error> {
+        self.require_writable()?;
         self.objects
             .write(object)
             .map(|oid| oid.attach(self))
@@ -79,9 +126,11 @@ impl crate::Repository {
 
     /// Write a blob from the given `bytes`.
     pub fn write_blob(&self, bytes: impl AsRef<[u8]>) -> Result<Id<'_>, object::write::Error> {
-        self.objects
+        self.require_writable()?;
+        Ok(self
+            .objects
             .write_buf(git_object::Kind::Blob, bytes.as_ref())
-            .map(|oid| oid.attach(self))
+            .map(|oid| oid.attach(self))?)
     }
 
     /// Write a blob from the given `Read` implementation.
@@ -89,13 +138,12 @@ impl crate::Repository {
         &self,
         mut bytes: impl std::io::Read + std::io::Seek,
     ) -> Result<Id<'_>, object::write::Error> {
-        let current = bytes.stream_position()?;
-        let len = bytes.seek(std::io::SeekFrom::End(0))? - current;
-        bytes.seek(std::io::SeekFrom::Start(current))?;
-
-        self.objects
+        self.require_writable()?;
+        let len = remaining_len(&mut bytes).map_err(git_odb::store::write::Error::from)?;
+        Ok(self
+            .objects
             .write_stream(git_object::Kind::Blob, len, bytes)
-            .map(|oid| oid.attach(self))
+            .map(|oid| oid.attach(self))?)
     }
 
     /// Create a tag reference named `name` (without `refs/tags/` prefix) pointing to a newly created tag object
@@ -136,7 +184,10 @@ impl crate::Repository {
     /// If there is no parent, the `reference` is expected to not exist yet.
     ///
     /// The method fails immediately if a `reference` lock can't be acquired.
-    pub fn commit<Name, E>(
+    ///
+    /// Use [`commit()`][Self::commit()] to use the [author][Self::author()] and [committer][Self::committer()] as
+    /// configured in the repository instead.
+    pub fn commit_as<Name, E>(
         &self,
         reference: Name,
         author: git_actor::SignatureRef<'_>,
@@ -200,4 +251,92 @@ impl crate::Repository {
         )?;
         Ok(commit_id)
     }
+
+    /// Like [`commit_as()`][Self::commit_as()], but use the [author][Self::author()] and [committer][Self::committer()]
+    /// as configured in this repository, failing if they can't be determined.
+    pub fn commit<Name, E>(
+        &self,
+        reference: Name,
+        message: impl AsRef<str>,
+        tree: impl Into<ObjectId>,
+        parents: impl IntoIterator<Item = impl Into<ObjectId>>,
+    ) -> Result<Id<'_>, commit::Error>
+    where
+        Name: TryInto<FullName, Error = E>,
+        commit::Error: From<E>,
+    {
+        let author = self.author()?.signature;
+        let committer = self.committer()?.signature;
+        self.commit_as(reference, author, committer, message, tree, parents)
+    }
+}
+
+/// Replacement objects
+impl crate::Repository {
+    /// Return an iterator over all replacements from object ids `X` to `X-replaced` as `(X, X-replaced)`, sorted by `X`.
+    ///
+    /// These are obtained from the references in `refs/replace/` when opening the repository, which can be
+    /// configured with [`open::Options::replacement_objects()`][crate::open::Options::replacement_objects()] or
+    /// disabled entirely with the `GIT_NO_REPLACE_OBJECTS` environment variable or `core.useReplaceRefs`.
+    ///
+    /// Note that replacements are performed transparently when finding objects unless `ignore_replacements` is set on
+    /// our [object database handle][crate::Repository::objects].
+    pub fn replacements(&self) -> impl Iterator<Item = (ObjectId, ObjectId)> + '_ {
+        self.objects.store_ref().replacements()
+    }
+}
+
+/// Object database snapshots
+impl crate::Repository {
+    /// Pin the packs currently in the object database so all subsequent object lookups, also by clones of this instance,
+    /// see the same set of packed objects even if packs are added or removed by concurrent fetches, repacks or garbage collection.
+    ///
+    /// Use [`objects_are_stale()`][Self::objects_are_stale()] to learn if the object database changed in the meantime, and
+    /// [`unpin_objects()`][Self::unpin_objects()] to see these changes again.
+    /// Note that loose objects are not part of the snapshot, see [`git_odb::store::Handle::pin()`] for details.
+    pub fn pin_objects(&mut self) -> Result<(), git_odb::store::load_index::Error> {
+        self.objects.pin()
+    }
+
+    /// Stop using the snapshot of packs created with [`pin_objects()`][Self::pin_objects()].
+    pub fn unpin_objects(&mut self) {
+        self.objects.unpin()
+    }
+
+    /// Return `true` if the packs on disk changed since [`pin_objects()`][Self::pin_objects()] was called, or `false` if nothing
+    /// changed or the objects aren't pinned.
+    pub fn objects_are_stale(&self) -> Result<bool, git_odb::store::load_index::Error> {
+        self.objects.is_stale()
+    }
+}
+
+/// Verification
+impl crate::Repository {
+    /// Check the integrity of all objects in the object database, including those of alternates, as configured by `options`.
+    ///
+    /// `progress` counts verified `pack indices` and then verified `loose object stores`, with one child for each of them
+    /// counting its `objects`. `should_interrupt` is checked while objects are verified and aborts the operation with an error if set.
+    pub fn verify_integrity<C, P, F>(
+        &self,
+        progress: P,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+        options: git_odb::store::verify::integrity::Options<F>,
+    ) -> Result<git_odb::store::verify::integrity::Outcome<P>, git_odb::store::verify::integrity::Error>
+    where
+        P: git_features::progress::Progress,
+        C: git_pack::cache::DecodeEntry,
+        F: Fn() -> C + Send + Clone,
+    {
+        self.objects
+            .store_ref()
+            .verify_integrity(progress, should_interrupt, options)
+    }
+}
+
+/// Return the amount of bytes left in `bytes` without changing its position.
+fn remaining_len(bytes: &mut impl std::io::Seek) -> std::io::Result<u64> {
+    let current = bytes.stream_position()?;
+    let len = bytes.seek(std::io::SeekFrom::End(0))? - current;
+    bytes.seek(std::io::SeekFrom::Start(current))?;
+    Ok(len)
 }
@@ -0,0 +1,18 @@
+impl crate::Repository {
+    /// Return `true` if this repository was [opened read-only][crate::open::Options::read_only()], which makes all
+    /// methods that would write to it fail with a [`read_only::Error`][crate::read_only::Error].
+    pub fn is_read_only(&self) -> bool {
+        self.options.read_only
+    }
+
+    /// Fail if this repository must not be written to.
+    pub(crate) fn require_writable(&self) -> Result<(), crate::read_only::Error> {
+        if self.is_read_only() {
+            Err(crate::read_only::Error {
+                git_dir: self.git_dir().to_owned(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
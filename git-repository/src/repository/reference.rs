@@ -150,6 +150,7 @@ impl crate::Repository {
         lock_mode: lock::acquire::Fail,
         log_committer: actor::SignatureRef<'_>,
     ) -> Result<Vec<RefEdit>, reference::edit::Error> {
+        self.require_writable()?;
         self.refs
             .transaction()
             .prepare(edits, lock_mode)?
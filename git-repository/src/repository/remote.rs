@@ -23,6 +23,14 @@ impl crate::Repository {
         Remote::from_fetch_url(url, false, self)
     }
 
+    /// Return the rules to rewrite remote urls with, as configured with `url.<base>.insteadOf` and `url.<base>.pushInsteadOf`,
+    /// to learn which rule applies to a url.
+    ///
+    /// These are applied automatically to remotes unless they are obtained with one of the `_without_url_rewrite()` methods.
+    pub fn url_rewrite(&self) -> &remote::url::Rewrite {
+        self.config.url_rewrite()
+    }
+
     /// Find the remote with the given `name` or report an error, similar to [`try_find_remote(…)`][Self::try_find_remote()].
     ///
     /// Note that we will include remotes only if we deem them [trustworthy][crate::open::Options::filter_config_section()].
@@ -142,3 +150,135 @@ impl crate::Repository {
         }
     }
 }
+
+mod maintenance {
+    use std::{collections::BTreeMap, io::BufWriter, path::Path, sync::atomic::AtomicBool};
+
+    use git_commitgraph::file::write;
+    use git_features::progress::Progress;
+    use git_hash::ObjectId;
+
+    use crate::{
+        object::Kind,
+        remote::fetch::maintenance::{Error, Options, Outcome},
+    };
+
+    /// Maintenance after fetching
+    impl crate::Repository {
+        /// Perform the maintenance configured in `options` right after the pack whose index is at `pack_index_path` was received
+        /// into our object database, so that subsequent operations can benefit from it immediately.
+        ///
+        /// The commit-graph is extended with all commits of the received pack along with their ancestors that it doesn't contain yet,
+        /// which is skipped in shallow repositories as git doesn't use commit-graphs there.
+        /// The multi-pack-index is rewritten to contain all packs, using `progress` and `should_interrupt` while doing so.
+        pub fn maintain_after_fetch(
+            &self,
+            pack_index_path: impl AsRef<Path>,
+            progress: impl Progress,
+            should_interrupt: &AtomicBool,
+            options: Options,
+        ) -> Result<Outcome, Error> {
+            self.require_writable()?;
+            let objects_dir = self.objects.store_ref().path();
+            let mut outcome = Outcome::default();
+            if options.write_commit_graph && !self.common_dir().join("shallow").is_file() {
+                outcome.commit_graph_commits = Some(self.extend_commit_graph(pack_index_path.as_ref(), objects_dir)?);
+            }
+            if options.write_multi_pack_index {
+                let pack_dir = objects_dir.join("pack");
+                let mut index_paths = Vec::new();
+                for entry in std::fs::read_dir(&pack_dir)? {
+                    let path = entry?.path();
+                    if path.extension().map(|ext| ext == "idx") == Some(true) {
+                        index_paths.push(path);
+                    }
+                }
+                let path = pack_dir.join("multi-pack-index");
+                let mut out = self.lock_for_writing(&path, objects_dir)?;
+                let write = git_pack::multi_index::File::write_from_index_paths(
+                    index_paths,
+                    &mut out,
+                    progress,
+                    should_interrupt,
+                    git_pack::multi_index::write::Options {
+                        object_hash: self.object_hash(),
+                    },
+                )?;
+                self.commit_written_lock(out, path)?;
+                outcome.multi_pack_index_checksum = Some(write.multi_index_checksum);
+            }
+            Ok(outcome)
+        }
+
+        fn extend_commit_graph(&self, pack_index_path: &Path, objects_dir: &Path) -> Result<u32, Error> {
+            let info_dir = objects_dir.join("info");
+            let mut entries = BTreeMap::new();
+            if let Ok(graph) = git_commitgraph::Graph::from_info_dir(&info_dir) {
+                for commit in graph.iter_commits() {
+                    let entry = write::Entry::from_graph(&graph, &commit)?;
+                    entries.insert(entry.id, entry);
+                }
+            }
+
+            let index = git_pack::index::File::at(pack_index_path, self.object_hash())?;
+            let mut ids: Vec<ObjectId> = index.iter().map(|entry| entry.oid).collect();
+            while let Some(id) = ids.pop() {
+                if entries.contains_key(&id) {
+                    continue;
+                }
+                let object = self.find_object(id)?;
+                if object.kind != Kind::Commit {
+                    continue;
+                }
+                let commit = git_object::CommitRef::from_bytes(&object.data)?;
+                let parents: Vec<_> = commit.parents().collect();
+                ids.extend(parents.iter().filter(|parent| !entries.contains_key(*parent)));
+                entries.insert(
+                    id,
+                    write::Entry {
+                        id,
+                        root_tree_id: commit.tree(),
+                        parents,
+                        committer_timestamp: commit.committer.time.seconds_since_unix_epoch.into(),
+                    },
+                );
+            }
+
+            let path = info_dir.join("commit-graph");
+            let mut out = self.lock_for_writing(&path, objects_dir)?;
+            let write = git_commitgraph::file::File::write_to(
+                entries.into_values(),
+                &mut out,
+                write::Options {
+                    object_hash: self.object_hash(),
+                },
+            )?;
+            self.commit_written_lock(out, path)?;
+            Ok(write.num_commits)
+        }
+
+        fn lock_for_writing(&self, path: &Path, objects_dir: &Path) -> Result<BufWriter<git_lock::File>, Error> {
+            git_lock::File::acquire_to_update_resource(
+                path,
+                git_lock::acquire::Fail::Immediately,
+                Some(objects_dir.to_owned()),
+            )
+            .map(BufWriter::new)
+            .map_err(|source| Error::AcquireLock {
+                source,
+                path: path.to_owned(),
+            })
+        }
+
+        fn commit_written_lock(&self, out: BufWriter<git_lock::File>, path: std::path::PathBuf) -> Result<(), Error> {
+            let mut lock = out.into_inner().map_err(std::io::IntoInnerError::into_error)?;
+            if let Some(method) = self.config.fsync.method_if(self.config.fsync.packs) {
+                lock.with_mut(|file| crate::fsync::file(file, method))?;
+            }
+            lock.commit().map(|_| ()).map_err(|err| Error::CommitLock {
+                source: err.error,
+                path,
+            })
+        }
+    }
+}
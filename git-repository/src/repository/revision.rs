@@ -1,4 +1,12 @@
-use crate::{bstr::BStr, revision};
+use git_hash::ObjectId;
+
+use crate::{
+    bstr::BStr,
+    ext::ObjectIdExt,
+    revision,
+    revision::{ahead_behind, merge_base},
+    Id,
+};
 
 /// Methods for resolving revisions by spec or working with the commit graph.
 impl crate::Repository {
@@ -23,4 +31,93 @@ impl crate::Repository {
     ) -> revision::walk::Platform<'_> {
         revision::walk::Platform::new(tips, self)
     }
+
+    /// Return the best common ancestor of the commits `one` and `two`, or `None` if they have no common history,
+    /// similar to `git merge-base`.
+    ///
+    /// If there are multiple best common ancestors, which can happen with criss-cross merges, the most recent one is returned.
+    /// Use [`merge_base_many()`][Self::merge_base_many()] to obtain all of them.
+    pub fn merge_base(
+        &self,
+        one: impl Into<ObjectId>,
+        two: impl Into<ObjectId>,
+    ) -> Result<Option<Id<'_>>, merge_base::Error> {
+        Ok(self.merge_base_many(one, Some(two))?.into_iter().next())
+    }
+
+    /// Return all best common ancestors of the commit `one` and any of the `others`, sorted by commit time with the most
+    /// recent commit first, similar to `git merge-base --all <one> <others>…`.
+    ///
+    /// A best common ancestor is a common ancestor that isn't reachable from any other common ancestor.
+    /// Note that with more than one of `others`, this computes the merge bases of `one` and a hypothetical merge of all `others`.
+    pub fn merge_base_many(
+        &self,
+        one: impl Into<ObjectId>,
+        others: impl IntoIterator<Item = impl Into<ObjectId>>,
+    ) -> Result<Vec<Id<'_>>, merge_base::Error> {
+        let others: Vec<_> = others.into_iter().map(Into::into).collect();
+        Ok(merge_base::Graph::new(self)
+            .merge_bases_many(one.into(), &others)?
+            .into_iter()
+            .map(|id| id.attach(self))
+            .collect())
+    }
+
+    /// Return the best common ancestors of all `commits` for use in an octopus merge, similar to `git merge-base --octopus`.
+    ///
+    /// The result is empty if `commits` is empty or if they don't share any history, and is the only commit if only one is given.
+    pub fn merge_base_octopus(
+        &self,
+        commits: impl IntoIterator<Item = impl Into<ObjectId>>,
+    ) -> Result<Vec<Id<'_>>, merge_base::Error> {
+        let mut graph = merge_base::Graph::new(self);
+        let mut commits = commits.into_iter().map(Into::into);
+        let mut result = match commits.next() {
+            Some(first) => vec![first],
+            None => return Ok(Vec::new()),
+        };
+        for commit in commits {
+            let mut bases = Vec::new();
+            for previous in result {
+                for base in graph.merge_bases_many(previous, &[commit])? {
+                    if !bases.contains(&base) {
+                        bases.push(base);
+                    }
+                }
+            }
+            result = bases;
+        }
+        Ok(result.into_iter().map(|id| id.attach(self)).collect())
+    }
+
+    /// Return `true` if the commit `ancestor` is reachable from the commit `descendant` or if both are the same,
+    /// similar to `git merge-base --is-ancestor`.
+    ///
+    /// Generation numbers from the commit-graph are used to stop the traversal early if available.
+    pub fn is_ancestor(
+        &self,
+        ancestor: impl Into<ObjectId>,
+        descendant: impl Into<ObjectId>,
+    ) -> Result<bool, merge_base::Error> {
+        merge_base::Graph::new(self).is_ancestor(ancestor.into(), descendant.into())
+    }
+
+    /// Count the commits reachable from `one` but not from `two` as `ahead`, and the ones reachable from `two` but not from `one`
+    /// as `behind`, similar to `git rev-list --left-right --count one...two`.
+    ///
+    /// This is what is shown for a branch compared to its upstream branch. Generation numbers from the commit-graph are used
+    /// if available, and [`options.max_commits`][ahead_behind::Options::max_commits] limits the cost of the traversal otherwise.
+    pub fn ahead_behind(
+        &self,
+        one: impl Into<ObjectId>,
+        two: impl Into<ObjectId>,
+        options: ahead_behind::Options,
+    ) -> Result<ahead_behind::Outcome, ahead_behind::Error> {
+        let (ahead, behind) = merge_base::Graph::new(self)
+            .ahead_behind(one.into(), two.into(), options.max_commits)?
+            .ok_or(ahead_behind::Error::TooExpensive {
+                max_commits: options.max_commits.unwrap_or_default(),
+            })?;
+        Ok(ahead_behind::Outcome { ahead, behind })
+    }
 }
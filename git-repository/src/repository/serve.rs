@@ -0,0 +1,616 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    convert::TryFrom,
+    io::{BufRead, Write},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use git_features::{parallel::reduce::Finalize, progress::Progress};
+use git_hash::ObjectId;
+use git_odb::{Find, FindExt};
+use git_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, Target,
+};
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    object::Kind,
+    remote::fetch::DenyCurrentBranch,
+    serve::{self, advertise, http, receive_pack, upload_pack, AdvertisedRef, Mode, Service},
+};
+
+/// Serving
+impl crate::Repository {
+    /// Serve a client that fetches or clones from this repository like `git upload-pack`, reading its requests from `input`
+    /// and writing the responses to `out`, with `options` deciding if references are advertised and how many requests are served.
+    ///
+    /// Once the client is done negotiating, it receives a pack with all objects reachable from the objects it wants, without those
+    /// reachable from the objects it said it has. Progress isn't sent to the client, and `progress` only shows how the pack is created.
+    pub fn upload_pack(
+        &self,
+        mut input: impl BufRead,
+        mut out: impl Write,
+        options: upload_pack::Options,
+        progress: impl Progress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<upload_pack::Outcome, upload_pack::Error> {
+        use upload_pack::Error;
+
+        let (refs, head_target) = self.advertised_refs()?;
+        if options.mode != Mode::StatelessRpc {
+            let mut capabilities = String::from("multi_ack_detailed side-band-64k side-band ofs-delta no-progress");
+            if let Some(target) = head_target {
+                capabilities.push_str(" symref=HEAD:");
+                capabilities.push_str(&target.to_str_lossy());
+            }
+            self.write_advertisement(&mut out, &refs, capabilities)?;
+            if options.mode == Mode::AdvertiseRefs {
+                return Ok(Default::default());
+            }
+        }
+
+        let mut outcome = upload_pack::Outcome::default();
+        let mut capabilities = Vec::new();
+        while let Some(line) = read_request_line(&mut input, options.mode, outcome.wants.is_empty())? {
+            let (id, rest) = line
+                .strip_prefix(b"want ")
+                .and_then(parse_id)
+                .ok_or_else(|| Error::UnexpectedLine {
+                    line: line.clone().into(),
+                })?;
+            if outcome.wants.is_empty() {
+                capabilities = parse_capabilities(rest);
+            }
+            outcome.wants.push(id);
+        }
+        if outcome.wants.is_empty() {
+            return Ok(outcome);
+        }
+        if let Some(id) = outcome
+            .wants
+            .iter()
+            .find(|id| !refs.iter().any(|r| r.id == **id || r.peeled == Some(**id)))
+        {
+            serve::write_line(&mut out, format!("ERR upload-pack: not our ref {}\n", id).as_bytes())?;
+            return Err(Error::NotOurRef { id: *id });
+        }
+
+        loop {
+            match serve::read_line(&mut input)?.as_deref().map(without_newline) {
+                None => {
+                    serve::write_line(&mut out, b"NAK\n")?;
+                    out.flush()?;
+                    if options.mode == Mode::StatelessRpc {
+                        return Ok(outcome);
+                    }
+                }
+                Some(b"done") => break,
+                Some(line) => {
+                    let id = line
+                        .strip_prefix(b"have ")
+                        .and_then(|hex| ObjectId::from_hex(hex).ok())
+                        .ok_or_else(|| Error::UnexpectedLine { line: line.into() })?;
+                    if self.objects.contains(id) {
+                        serve::write_line(&mut out, format!("ACK {} common\n", id).as_bytes())?;
+                        if !outcome.common.contains(&id) {
+                            outcome.common.push(id);
+                        }
+                    }
+                }
+            }
+        }
+        match outcome.common.last() {
+            Some(id) => serve::write_line(&mut out, format!("ACK {}\n", id).as_bytes())?,
+            None => serve::write_line(&mut out, b"NAK\n")?,
+        }
+
+        let has = |name: &str| capabilities.iter().any(|capability| capability == name);
+        let max_line_len = if has("side-band-64k") {
+            Some(65520)
+        } else if has("side-band") {
+            Some(1000)
+        } else {
+            None
+        };
+        let num_objects = match max_line_len {
+            Some(max_line_len) => {
+                let mut sideband = serve::Sideband::new(&mut out, max_line_len);
+                let written = self.write_pack(
+                    &outcome.wants,
+                    &outcome.common,
+                    std::io::BufWriter::with_capacity(max_line_len - 5, &mut sideband),
+                    progress,
+                    should_interrupt,
+                );
+                match written {
+                    Ok(num_objects) => num_objects,
+                    Err(err) => {
+                        sideband.write_message(format!("fatal: {}\n", err).as_bytes(), true)?;
+                        return Err(err);
+                    }
+                }
+            }
+            None => self.write_pack(&outcome.wants, &outcome.common, &mut out, progress, should_interrupt)?,
+        };
+        if max_line_len.is_some() {
+            serve::write_flush(&mut out)?;
+        }
+        out.flush()?;
+        outcome.num_objects = Some(num_objects);
+        Ok(outcome)
+    }
+
+    /// Serve a client that pushes to this repository like `git receive-pack`, reading its requests from `input` and writing the
+    /// responses to `out`, with `options` deciding if references are advertised and how many requests are served.
+    ///
+    /// The pack sent by the client is written into the object database and references are updated as requested, unless they are
+    /// checked out in a worktree and `receive.denyCurrentBranch` refuses it, which it does by default. `updateInstead` isn't supported
+    /// and refuses as well. Updates are applied one by one, unless the client asks for them to be applied atomically.
+    pub fn receive_pack(
+        &self,
+        mut input: impl BufRead,
+        mut out: impl Write,
+        options: receive_pack::Options,
+        progress: impl Progress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<receive_pack::Outcome, receive_pack::Error> {
+        use receive_pack::Error;
+
+        self.require_writable()?;
+        let deny_current_branch = self.config_snapshot().receive_deny_current_branch()?;
+        if options.mode != Mode::StatelessRpc {
+            let (mut refs, _) = self.advertised_refs()?;
+            refs.retain(|r| r.name != "HEAD");
+            self.write_advertisement(&mut out, &refs, "report-status delete-refs atomic ofs-delta".into())?;
+            if options.mode == Mode::AdvertiseRefs {
+                return Ok(Default::default());
+            }
+        }
+
+        let mut outcome = receive_pack::Outcome::default();
+        let mut capabilities = Vec::new();
+        while let Some(line) = read_request_line(&mut input, options.mode, outcome.updates.is_empty())? {
+            let unexpected_line = || Error::UnexpectedLine {
+                line: line.clone().into(),
+            };
+            let (command, rest) = match line.find_byte(0) {
+                Some(pos) => (&line[..pos], Some(&line[pos + 1..])),
+                None => (&line[..], None),
+            };
+            let (previous, command) = parse_id(command).ok_or_else(unexpected_line)?;
+            let (new, name) = parse_id(command).ok_or_else(unexpected_line)?;
+            if name.is_empty() {
+                return Err(unexpected_line());
+            }
+            if let (true, Some(rest)) = (outcome.updates.is_empty(), rest) {
+                capabilities = parse_capabilities(rest);
+            }
+            outcome.updates.push(receive_pack::Update {
+                name: name.into(),
+                previous: (!previous.is_null()).then(|| previous),
+                new: (!new.is_null()).then(|| new),
+                rejection: None,
+            });
+        }
+        if outcome.updates.is_empty() {
+            return Ok(outcome);
+        }
+        let has = |name: &str| capabilities.iter().any(|capability| capability == name);
+        let report_status = has("report-status");
+
+        if outcome.updates.iter().any(|update| update.new.is_some()) {
+            match self.write_received_pack(&mut input, progress, should_interrupt) {
+                Ok(write) => outcome.pack = write,
+                Err(err) => {
+                    if report_status {
+                        let message = err.to_string().replace('\n', " ");
+                        serve::write_line(&mut out, format!("unpack {}\n", message).as_bytes())?;
+                        for update in &outcome.updates {
+                            serve::write_line(&mut out, format!("ng {} unpacker error\n", update.name).as_bytes())?;
+                        }
+                        serve::write_flush(&mut out)?;
+                        out.flush()?;
+                    }
+                    return Err(err.into());
+                }
+            }
+        }
+
+        let checked_out: Vec<_> = self
+            .checked_out_branches::<receive_pack::checked_out::Error>()?
+            .into_iter()
+            .map(|(name, _worktree)| name)
+            .collect();
+        let mut edits = Vec::new();
+        for (index, update) in outcome.updates.iter_mut().enumerate() {
+            let name = match FullName::try_from(update.name.as_bstr()) {
+                Ok(name) if name.as_bstr().starts_with(b"refs/") => name,
+                _ => {
+                    update.rejection = Some("funny refname".into());
+                    continue;
+                }
+            };
+            let is_checked_out = checked_out.contains(&name);
+            let rejection = match update.new {
+                None if is_checked_out => Some("deletion of the current branch prohibited"),
+                Some(_)
+                    if is_checked_out
+                        && matches!(
+                            deny_current_branch,
+                            DenyCurrentBranch::Refuse | DenyCurrentBranch::UpdateInstead
+                        ) =>
+                {
+                    Some("branch is currently checked out")
+                }
+                Some(new) if !self.objects.contains(new) => Some("missing necessary objects"),
+                _ => None,
+            };
+            if let Some(rejection) = rejection {
+                update.rejection = Some(rejection.into());
+                continue;
+            }
+            let change = match update.new {
+                Some(new) => Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: "push".into(),
+                    },
+                    expected: match update.previous {
+                        Some(previous) => PreviousValue::MustExistAndMatch(Target::Peeled(previous)),
+                        None => PreviousValue::MustNotExist,
+                    },
+                    new: Target::Peeled(new),
+                },
+                None => Change::Delete {
+                    expected: match update.previous {
+                        Some(previous) => PreviousValue::MustExistAndMatch(Target::Peeled(previous)),
+                        None => PreviousValue::MustExist,
+                    },
+                    log: RefLog::AndReference,
+                },
+            };
+            edits.push((
+                index,
+                RefEdit {
+                    change,
+                    name,
+                    deref: false,
+                },
+            ));
+        }
+
+        let lock_mode = git_lock::acquire::Fail::Immediately;
+        if has("atomic") {
+            let failure = if outcome.updates.iter().any(|update| update.rejection.is_some()) {
+                Some("atomic push failed".to_string())
+            } else {
+                self.edit_references(
+                    edits.iter().map(|(_, edit)| edit.clone()),
+                    lock_mode,
+                    self.committer_or_default(),
+                )
+                .err()
+                .map(|err| err.to_string())
+            };
+            if let Some(failure) = failure {
+                for (index, _) in edits {
+                    outcome.updates[index].rejection = Some(failure.clone());
+                }
+            }
+        } else {
+            for (index, edit) in edits {
+                if let Err(err) = self.edit_reference(edit, lock_mode, self.committer_or_default()) {
+                    outcome.updates[index].rejection = Some(err.to_string());
+                }
+            }
+        }
+
+        if report_status {
+            serve::write_line(&mut out, b"unpack ok\n")?;
+            for update in &outcome.updates {
+                let line = match &update.rejection {
+                    Some(rejection) => format!("ng {} {}\n", update.name, rejection.replace('\n', " ")),
+                    None => format!("ok {}\n", update.name),
+                };
+                serve::write_line(&mut out, line.as_bytes())?;
+            }
+            serve::write_flush(&mut out)?;
+        }
+        out.flush()?;
+        Ok(outcome)
+    }
+
+    /// Answer a request for the smart HTTP `endpoint` of this repository, reading the body of the request from `body` and writing the
+    /// body of the response to `out`.
+    ///
+    /// `body` must be decoded already if it was sent with `Content-Encoding: gzip`, which clients do for large requests. The caller is
+    /// responsible for deciding which services to allow, and to write the response headers, including its
+    /// [content type][http::Endpoint::content_type()].
+    pub fn serve_http(
+        &self,
+        endpoint: http::Endpoint,
+        body: impl BufRead,
+        mut out: impl Write,
+        progress: impl Progress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(), http::Error> {
+        let mode = match endpoint {
+            http::Endpoint::InfoRefs(service) => {
+                serve::write_line(&mut out, format!("# service={}\n", service.as_str()).as_bytes())?;
+                serve::write_flush(&mut out)?;
+                Mode::AdvertiseRefs
+            }
+            http::Endpoint::Service(_) => Mode::StatelessRpc,
+        };
+        match endpoint.service() {
+            Service::UploadPack => {
+                self.upload_pack(body, out, upload_pack::Options { mode }, progress, should_interrupt)?;
+            }
+            Service::ReceivePack => {
+                self.receive_pack(body, out, receive_pack::Options { mode }, progress, should_interrupt)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Return all references to advertise, starting with `HEAD` if it points to an object, along with the name of the branch
+    /// `HEAD` points to in that case.
+    fn advertised_refs(&self) -> Result<(Vec<AdvertisedRef>, Option<BString>), advertise::Error> {
+        let mut refs = Vec::new();
+        let head = self.head()?;
+        let head_target = match head.id() {
+            Some(id) => {
+                refs.push(AdvertisedRef {
+                    name: "HEAD".into(),
+                    id: id.detach(),
+                    peeled: self.peeled_tag(id.detach())?,
+                });
+                head.referent_name().map(|name| name.as_bstr().to_owned())
+            }
+            None => None,
+        };
+        for reference in self.references()?.all()? {
+            let reference = reference.map_err(advertise::Error::IterReferences)?;
+            let mut target = reference.target().into_owned();
+            let mut depth = 0;
+            let id = loop {
+                match target {
+                    Target::Peeled(id) => break Some(id),
+                    Target::Symbolic(name) if depth < 5 => match self.try_find_reference(name.as_ref())? {
+                        Some(referent) => {
+                            target = referent.detach().target;
+                            depth += 1;
+                        }
+                        None => break None,
+                    },
+                    Target::Symbolic(_) => break None,
+                }
+            };
+            if let Some(id) = id {
+                refs.push(AdvertisedRef {
+                    name: reference.name().as_bstr().to_owned(),
+                    id,
+                    peeled: self.peeled_tag(id)?,
+                });
+            }
+        }
+        Ok((refs, head_target))
+    }
+
+    /// Return the object at the end of the tag chain if `id` is an annotated tag.
+    fn peeled_tag(&self, id: ObjectId) -> Result<Option<ObjectId>, crate::object::find::existing::Error> {
+        let object = self.find_object(id)?;
+        Ok(match object.kind {
+            Kind::Tag => Some(object.peel_tags_to_end()?.id),
+            _ => None,
+        })
+    }
+
+    /// Write `refs` to `out` as advertised by protocol V0, with `capabilities` followed by our agent and object format.
+    fn write_advertisement(
+        &self,
+        mut out: impl Write,
+        refs: &[AdvertisedRef],
+        mut capabilities: String,
+    ) -> std::io::Result<()> {
+        capabilities.push_str(&format!(
+            " agent={} object-format={}",
+            serve::AGENT,
+            self.object_hash().to_string().to_ascii_lowercase()
+        ));
+        if refs.is_empty() {
+            let line = format!("{} capabilities^{{}}\0{}\n", self.object_hash().null(), capabilities);
+            serve::write_line(&mut out, line.as_bytes())?;
+        }
+        for (index, r) in refs.iter().enumerate() {
+            let mut line = BString::from(format!("{} ", r.id));
+            line.extend_from_slice(&r.name);
+            if index == 0 {
+                line.push(0);
+                line.extend_from_slice(capabilities.as_bytes());
+            }
+            line.push(b'\n');
+            serve::write_line(&mut out, &line)?;
+            if let Some(peeled) = r.peeled {
+                let mut line = BString::from(format!("{} ", peeled));
+                line.extend_from_slice(&r.name);
+                line.extend_from_slice(b"^{}\n");
+                serve::write_line(&mut out, &line)?;
+            }
+        }
+        serve::write_flush(&mut out)?;
+        out.flush()
+    }
+
+    /// Write a pack with all objects reachable from `wants` but not from `common` to `out`, and return the amount of objects in it.
+    fn write_pack(
+        &self,
+        wants: &[ObjectId],
+        common: &[ObjectId],
+        mut out: impl Write,
+        mut progress: impl Progress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<u32, upload_pack::Error> {
+        use git_pack::data::output;
+        use upload_pack::Error;
+
+        let commit_of = |id: ObjectId| -> Result<Option<ObjectId>, Error> {
+            Ok(self
+                .find_object(id)?
+                .peel_to_kind(Kind::Commit)
+                .ok()
+                .map(|commit| commit.id))
+        };
+        let mut common_commits = Vec::new();
+        for id in common {
+            common_commits.extend(commit_of(*id)?);
+        }
+        let excluded = self
+            .rev_walk(common_commits)
+            .all()?
+            .map(|id| id.map(|id| id.detach()))
+            .collect::<Result<HashSet<_>, _>>()?;
+
+        let mut commits = Vec::new();
+        {
+            let mut progress = progress.add_child("traversing");
+            progress.init(None, git_features::progress::count("commits"));
+            let mut seen = HashSet::new();
+            let mut queue = VecDeque::new();
+            for id in wants {
+                if let Some(commit) = commit_of(*id)? {
+                    if !excluded.contains(&commit) && seen.insert(commit) {
+                        queue.push_back(commit);
+                    }
+                }
+            }
+            let mut buf = Vec::new();
+            while let Some(id) = queue.pop_front() {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                for parent_id in self.objects.find_commit_iter(id, &mut buf)?.parent_ids() {
+                    if !excluded.contains(&parent_id) && seen.insert(parent_id) {
+                        queue.push_back(parent_id);
+                    }
+                }
+                commits.push(id);
+                progress.inc();
+            }
+        }
+
+        let mut handle = self.objects.clone().into_arc()?;
+        handle.prevent_pack_unload();
+        handle.ignore_replacements = true;
+        let (counts, _) = output::count::objects_unthreaded(
+            handle.clone(),
+            commits
+                .into_iter()
+                .chain(wants.iter().cloned())
+                .map(Ok::<_, std::convert::Infallible>),
+            progress.add_child("counting"),
+            should_interrupt,
+            output::count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+        )?;
+
+        let num_objects = counts.len() as u32;
+        let mut entries = git_features::parallel::InOrderIter::from(output::entry::iter_from_counts(
+            counts,
+            handle,
+            progress.add_child("creating entries"),
+            output::entry::iter_from_counts::Options {
+                mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                ..Default::default()
+            },
+        ));
+        let mut write = output::bytes::FromEntriesIter::new(
+            entries.by_ref(),
+            &mut out,
+            num_objects,
+            git_pack::data::Version::V2,
+            self.object_hash(),
+        );
+        for written in write.by_ref() {
+            written?;
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+        }
+        entries.inner.finalize()?;
+        out.flush()?;
+        Ok(num_objects)
+    }
+
+    /// Write the pack a client sends after its commands from `input` into the object database, resolving thin packs against it.
+    ///
+    /// Return `None` if the pack is empty, which happens if the client only points references to objects we already have.
+    fn write_received_pack(
+        &self,
+        mut input: impl BufRead,
+        progress: impl Progress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Option<git_pack::bundle::write::Outcome>, git_pack::bundle::write::Error> {
+        let mut header = [0; 12];
+        input.read_exact(&mut header)?;
+        if header[8..] == [0; 4] {
+            let mut trailer = vec![0; self.object_hash().len_in_bytes()];
+            input.read_exact(&mut trailer)?;
+            return Ok(None);
+        }
+
+        let pack_directory = self.objects.store_ref().path().join("pack");
+        std::fs::create_dir_all(&pack_directory)?;
+        let objects = self.objects.clone();
+        git_pack::Bundle::write_to_directory(
+            std::io::Read::chain(&header[..], input),
+            Some(pack_directory),
+            progress,
+            should_interrupt,
+            Some(Box::new(move |id, buf| objects.try_find(id, buf).ok().flatten())),
+            git_pack::bundle::write::Options {
+                object_hash: self.object_hash(),
+                fsync: self.config.fsync.method_if(self.config.fsync.packs),
+                ..Default::default()
+            },
+        )
+        .map(Some)
+    }
+}
+
+/// Read the next line of a request from `input`, or return `None` at the flush packet ending it.
+///
+/// Clients that only list references disconnect right after the advertisement, so if `is_first` is true, the end of a connection
+/// in [`Mode::Connection`] is handled like a flush packet.
+fn read_request_line(input: &mut impl BufRead, mode: Mode, is_first: bool) -> std::io::Result<Option<Vec<u8>>> {
+    match serve::read_line(input) {
+        Ok(line) => Ok(line.map(|mut line| {
+            if line.last() == Some(&b'\n') {
+                line.pop();
+            }
+            line
+        })),
+        Err(err) if is_first && mode == Mode::Connection && err.kind() == std::io::ErrorKind::UnexpectedEof => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Parse an object id at the beginning of `line` and return it along with the rest of the line after the following space.
+fn parse_id(line: &[u8]) -> Option<(ObjectId, &[u8])> {
+    let end = line.find_byte(b' ').unwrap_or(line.len());
+    let id = ObjectId::from_hex(&line[..end]).ok()?;
+    Some((id, line.get(end + 1..).unwrap_or_default()))
+}
+
+fn parse_capabilities(capabilities: &[u8]) -> Vec<BString> {
+    capabilities
+        .split_str(" ")
+        .filter(|capability| !capability.is_empty())
+        .map(Into::into)
+        .collect()
+}
+
+fn without_newline(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\n").unwrap_or(line)
+}
@@ -0,0 +1,152 @@
+use std::collections::{HashMap, HashSet};
+
+use git_hash::ObjectId;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    object::Kind,
+    shortlog::{Contributor, Error, Group, Options, Outcome},
+};
+
+/// Collect contributors along with the commits attributed to them.
+struct Contributors {
+    by_key: HashMap<BString, usize>,
+    contributors: Vec<Contributor>,
+    email: bool,
+}
+
+impl Contributors {
+    /// Return the index of the contributor identified by `name` and `email`, creating it if needed.
+    fn index_of(&mut self, name: &BStr, email: &BStr) -> usize {
+        let mut key = BString::from(name);
+        if self.email {
+            key.push(b'\0');
+            key.extend_from_slice(email);
+        }
+        let contributors = &mut self.contributors;
+        *self.by_key.entry(key).or_insert_with(|| {
+            contributors.push(Contributor {
+                name: name.into(),
+                email: email.into(),
+                commits: 0,
+                summaries: Vec::new(),
+            });
+            contributors.len() - 1
+        })
+    }
+}
+
+impl crate::Repository {
+    /// Aggregate all commits in `range` by the contributors they are attributed to, similar to `git shortlog`, as configured by `options`.
+    ///
+    /// `range` is typically obtained with [`rev_parse()`][crate::Repository::rev_parse()], like `v1.0..v2.0` to see who contributed to
+    /// a release, or `HEAD` for the entire history. A contributor is attributed each commit at most once, even if they are listed
+    /// as co-author of their own commit.
+    pub fn shortlog(&self, range: git_revision::Spec, options: Options) -> Result<Outcome, Error> {
+        let (include, exclude) = self.shortlog_tips(range)?;
+        let mut excluded = HashSet::new();
+        if !exclude.is_empty() {
+            for id in self.rev_walk(exclude).all()? {
+                excluded.insert(id?.detach());
+            }
+        }
+
+        #[cfg(feature = "git-mailmap")]
+        let mailmap = options.mailmap.then(|| self.open_mailmap());
+        let mut contributors = Contributors {
+            by_key: HashMap::new(),
+            contributors: Vec::new(),
+            email: options.email,
+        };
+        let mut commits = 0;
+        let mut attributed = Vec::new();
+        for id in self.rev_walk(include).all()? {
+            let id = id?;
+            if excluded.contains(id.as_ref()) {
+                continue;
+            }
+            commits += 1;
+            let commit = id.object()?.into_commit();
+            let commit = commit.decode()?;
+            let signature = match options.group {
+                Group::Author => commit.author,
+                Group::Committer => commit.committer,
+            };
+
+            attributed.clear();
+            let mut identities = vec![(signature.name, signature.email)];
+            if options.co_authors {
+                identities.extend(
+                    commit
+                        .message_trailers()
+                        .filter(|trailer| trailer.token.eq_ignore_ascii_case(b"Co-authored-by"))
+                        .filter_map(|trailer| parse_identity(trailer.value)),
+                );
+            }
+            for (name, email) in identities {
+                #[cfg(feature = "git-mailmap")]
+                let (name, email) = match mailmap.as_ref().and_then(|mailmap| {
+                    mailmap.try_resolve_ref(git_actor::SignatureRef {
+                        name,
+                        email,
+                        time: Default::default(),
+                    })
+                }) {
+                    Some(resolved) => (resolved.name.unwrap_or(name), resolved.email.unwrap_or(email)),
+                    None => (name, email),
+                };
+                let index = contributors.index_of(name, email);
+                if attributed.contains(&index) {
+                    continue;
+                }
+                attributed.push(index);
+                let contributor = &mut contributors.contributors[index];
+                contributor.commits += 1;
+                if options.summaries {
+                    contributor.summaries.push(commit.message_summary().into_owned());
+                }
+            }
+        }
+
+        let mut contributors = contributors.contributors;
+        contributors.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.name.cmp(&b.name)));
+        Ok(Outcome { contributors, commits })
+    }
+
+    /// Return the commits to start traversing from, and the ones whose ancestors are excluded, for `range`.
+    fn shortlog_tips(&self, range: git_revision::Spec) -> Result<(Vec<ObjectId>, Vec<ObjectId>), Error> {
+        use git_revision::Spec::*;
+        Ok(match range {
+            Include(id) => (vec![self.peel_to_commit_id(id)?], Vec::new()),
+            Exclude(id) => (Vec::new(), vec![self.peel_to_commit_id(id)?]),
+            Range { from, to } => (vec![self.peel_to_commit_id(to)?], vec![self.peel_to_commit_id(from)?]),
+            Merge { theirs, ours } => {
+                let (theirs, ours) = (self.peel_to_commit_id(theirs)?, self.peel_to_commit_id(ours)?);
+                let bases = self.merge_base_many(theirs, Some(ours))?;
+                (vec![theirs, ours], bases.into_iter().map(|id| id.detach()).collect())
+            }
+            IncludeOnlyParents(id) => {
+                let commit = self.find_object(id)?.peel_to_kind(Kind::Commit)?.into_commit();
+                let parents = commit.parent_ids().map(|id| id.detach()).collect();
+                (parents, Vec::new())
+            }
+            ExcludeParents(id) => {
+                let commit = self.find_object(id)?.peel_to_kind(Kind::Commit)?.into_commit();
+                let parents = commit.parent_ids().map(|id| id.detach()).collect();
+                (vec![commit.id], parents)
+            }
+        })
+    }
+
+    fn peel_to_commit_id(&self, id: ObjectId) -> Result<ObjectId, Error> {
+        Ok(self.find_object(id)?.peel_to_kind(Kind::Commit)?.id)
+    }
+}
+
+/// Parse `Name <email>` as found in trailers like `Co-authored-by`.
+fn parse_identity(value: &BStr) -> Option<(&BStr, &BStr)> {
+    let start = value.find_byte(b'<')?;
+    let end = start + value[start..].find_byte(b'>')?;
+    let name = value[..start].trim();
+    (!name.is_empty()).then(|| (name.as_bstr(), value[start + 1..end].as_bstr()))
+}
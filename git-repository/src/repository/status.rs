@@ -0,0 +1,233 @@
+use std::{cmp::Ordering, time::UNIX_EPOCH};
+
+use git_index::entry::{Flags, Mode};
+
+use crate::{
+    object::Kind,
+    status::{Action, Change, Error},
+    worktree::open_index,
+};
+
+impl crate::Repository {
+    /// Return `true` if there are changes between `HEAD`, the index and the worktree, ignoring untracked files,
+    /// similar to `git diff --quiet HEAD`.
+    ///
+    /// It stops at the first change it finds and thus is much faster than learning about all [changes][Self::changes()].
+    pub fn is_dirty(&self) -> Result<bool, Error> {
+        let mut is_dirty = false;
+        self.changes(|_change| {
+            is_dirty = true;
+            Action::Cancel
+        })?;
+        Ok(is_dirty)
+    }
+
+    /// Call `cb` with each change between `HEAD`, the index and the worktree until it returns [`Action::Cancel`].
+    ///
+    /// Unresolved conflicts and entries added with `--intent-to-add` are reported first, followed by the differences between
+    /// the tree of `HEAD` and the index, and finally the differences between the index and the worktree.
+    /// Untracked files are not considered, nor are submodules and entries marked to skip the worktree or assumed unchanged.
+    ///
+    /// Files whose size and modification time match the one recorded in the index are assumed unchanged, unless their modification
+    /// time is too close to the one of the index itself. Note that filters like `core.autocrlf` aren't applied when comparing
+    /// file content yet.
+    pub fn changes(&self, mut cb: impl FnMut(Change) -> Action) -> Result<(), Error> {
+        let work_dir = self.require_worktree("Listing changes")?.base();
+        let head_tree_id = match self.head()?.peel_to_id_in_place().transpose()? {
+            Some(id) => Some(self.find_object(id)?.peel_to_kind(Kind::Tree)?.id),
+            None => None,
+        };
+        let index = match self.open_index() {
+            Ok(index) => index,
+            Err(open_index::Error::IndexFile(git_index::file::init::Error::Io(err)))
+                if err.kind() == std::io::ErrorKind::NotFound && head_tree_id.is_none() =>
+            {
+                return Ok(())
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut uncomparable_paths = Vec::new();
+        for entry in index.entries() {
+            let path = entry.path(&index);
+            let change = if entry.stage() != 0 {
+                if uncomparable_paths.last() == Some(&path) {
+                    continue;
+                }
+                Change::Conflict { path: path.to_owned() }
+            } else if entry.flags.contains(Flags::INTENT_TO_ADD) {
+                Change::IntentToAdd { path: path.to_owned() }
+            } else {
+                continue;
+            };
+            uncomparable_paths.push(path);
+            if cb(change) == Action::Cancel {
+                return Ok(());
+            }
+        }
+        let is_comparable = |entry: &&git_index::Entry| {
+            entry.stage() == 0 && !entry.flags.contains(Flags::INTENT_TO_ADD) && !entry.mode.is_sparse()
+        };
+
+        let index_matches_head = index
+            .tree()
+            .filter(|tree| tree.num_entries.is_some())
+            .map(|tree| Some(tree.id) == head_tree_id)
+            == Some(true);
+        if !index_matches_head {
+            let mut head_entries = match head_tree_id {
+                Some(id) => self.find_object(id)?.into_tree().traverse().breadthfirst.files()?,
+                None => Vec::new(),
+            };
+            head_entries.retain(|entry| !entry.mode.is_tree());
+            head_entries.sort_by(|a, b| a.filepath.cmp(&b.filepath));
+
+            let mut head_entries = head_entries.into_iter().peekable();
+            let mut index_entries = index.entries().iter().filter(is_comparable).peekable();
+            loop {
+                let ordering = match (head_entries.peek(), index_entries.peek()) {
+                    (None, None) => break,
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (Some(head_entry), Some(index_entry)) => {
+                        head_entry.filepath.as_slice().cmp(index_entry.path(&index).as_ref())
+                    }
+                };
+                let change = match ordering {
+                    Ordering::Less => {
+                        let path = head_entries.next().expect("peeked").filepath;
+                        if uncomparable_paths.binary_search(&path.as_ref()).is_ok() {
+                            continue;
+                        }
+                        Change::Staged { path }
+                    }
+                    Ordering::Greater => Change::Staged {
+                        path: index_entries.next().expect("peeked").path(&index).to_owned(),
+                    },
+                    Ordering::Equal => {
+                        let (head_entry, index_entry) = (
+                            head_entries.next().expect("peeked"),
+                            index_entries.next().expect("peeked"),
+                        );
+                        if head_entry.oid == index_entry.id && head_entry.mode as u32 == index_entry.mode.bits() {
+                            continue;
+                        }
+                        Change::Staged {
+                            path: head_entry.filepath,
+                        }
+                    }
+                };
+                if cb(change) == Action::Cancel {
+                    return Ok(());
+                }
+            }
+        }
+
+        let index_mtime_secs = std::fs::metadata(self.index_path())
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_secs());
+        let use_symlinks = self
+            .config
+            .resolved
+            .boolean("core", None, "symlinks")
+            .and_then(Result::ok)
+            .unwrap_or(true);
+        let use_executable_bit = self
+            .config
+            .resolved
+            .boolean("core", None, "fileMode")
+            .and_then(Result::ok)
+            .unwrap_or(true);
+        for entry in index.entries().iter().filter(is_comparable) {
+            if entry.mode == Mode::COMMIT || entry.flags.intersects(Flags::SKIP_WORKTREE | Flags::ASSUME_VALID) {
+                continue;
+            }
+            let rela_path = entry.path(&index);
+            let change = worktree_change(
+                entry,
+                rela_path,
+                &work_dir.join(git_path::from_bstr(rela_path)),
+                index_mtime_secs,
+                use_symlinks,
+                use_executable_bit,
+            )
+            .map_err(|err| Error::Io {
+                path: rela_path.to_owned(),
+                source: err,
+            })?;
+            if let Some(change) = change {
+                if cb(change) == Action::Cancel {
+                    return Ok(());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Return the change between `entry` at `rela_path` in the index and the file at `path` in the worktree, if there is one.
+#[cfg_attr(not(unix), allow(unused_variables))]
+fn worktree_change(
+    entry: &git_index::Entry,
+    rela_path: &crate::bstr::BStr,
+    path: &std::path::Path,
+    index_mtime_secs: Option<u64>,
+    use_symlinks: bool,
+    use_executable_bit: bool,
+) -> std::io::Result<Option<Change>> {
+    let removed = || {
+        Some(Change::Removed {
+            path: rela_path.to_owned(),
+        })
+    };
+    let modified = || {
+        Some(Change::Modified {
+            path: rela_path.to_owned(),
+        })
+    };
+    let meta = match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => return Ok(removed()),
+        Ok(meta) => meta,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(removed()),
+        Err(err) => return Err(err),
+    };
+    let is_symlink = meta.file_type().is_symlink();
+    if is_symlink != (entry.mode == Mode::SYMLINK && use_symlinks) {
+        return Ok(modified());
+    }
+    #[cfg(unix)]
+    if use_executable_bit && !is_symlink {
+        use std::os::unix::fs::PermissionsExt;
+        let is_executable = meta.permissions().mode() & 0o111 != 0;
+        if is_executable != (entry.mode == Mode::FILE_EXECUTABLE) {
+            return Ok(modified());
+        }
+    }
+
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .unwrap_or_default();
+    let is_racy = index_mtime_secs.map_or(true, |index_secs| u64::from(entry.stat.mtime.secs) >= index_secs);
+    if !is_racy
+        && meta.len() as u32 == entry.stat.size
+        && mtime.as_secs() == u64::from(entry.stat.mtime.secs)
+        && (entry.stat.mtime.nsecs == 0 || mtime.subsec_nanos() == entry.stat.mtime.nsecs)
+    {
+        return Ok(None);
+    }
+
+    let data = if is_symlink {
+        git_path::into_bstr(std::fs::read_link(path)?).into_owned().into()
+    } else {
+        std::fs::read(path)?
+    };
+    Ok(git_object::Data::new(git_object::Kind::Blob, &data, entry.id.kind())
+        .verify_checksum(entry.id)
+        .is_err()
+        .then(modified)
+        .flatten())
+}
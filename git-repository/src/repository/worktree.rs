@@ -1,4 +1,12 @@
-use crate::{worktree, Worktree};
+use std::{
+    convert::TryFrom,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use git_features::progress::Progress;
+use git_odb::FindExt;
+
+use crate::{create::key, worktree, Worktree};
 
 /// Worktree iteration
 impl crate::Repository {
@@ -47,6 +55,34 @@ impl crate::Repository {
         crate::ThreadSafeRepository::open_opts(self.common_dir(), self.options.clone()).map(Into::into)
     }
 
+    /// Return the branches checked out in the main worktree and all linked worktrees, along with the repository of their worktree.
+    pub(crate) fn checked_out_branches<E>(&self) -> Result<Vec<(git_ref::FullName, crate::Repository)>, E>
+    where
+        E: From<std::io::Error> + From<crate::open::Error> + From<crate::reference::find::existing::Error>,
+    {
+        let mut worktrees = Vec::new();
+        if self.git_dir() != self.common_dir() {
+            worktrees.push(self.main_repo()?);
+        }
+        for proxy in self.worktrees()? {
+            if proxy.git_dir() != self.git_dir() {
+                worktrees.push(proxy.into_repo_with_possibly_inaccessible_worktree()?);
+            }
+        }
+        worktrees.push(self.clone());
+
+        let mut branches = Vec::new();
+        for worktree in worktrees {
+            if worktree.work_dir().is_none() {
+                continue;
+            }
+            if let Some(branch) = worktree.head_name()? {
+                branches.push((branch, worktree));
+            }
+        }
+        Ok(branches)
+    }
+
     /// Return the currently set worktree if there is one, acting as platform providing a validated worktree base path.
     ///
     /// Note that there would be `None` if this repository is `bare` and the parent [`Repository`][crate::Repository] was instantiated without
@@ -126,3 +162,222 @@ impl crate::Repository {
             })
     }
 }
+
+/// Bare repositories
+impl crate::Repository {
+    /// Return our work tree, or an error naming the `operation` that needs it if this repository is bare.
+    pub fn require_worktree(&self, operation: &'static str) -> Result<Worktree<'_>, worktree::require::Error> {
+        self.worktree().ok_or_else(|| worktree::require::Error {
+            operation,
+            git_dir: self.git_dir().to_owned(),
+        })
+    }
+
+    /// Turn this repository into a bare one by setting `core.bare = true` and removing `core.worktree` in its local configuration,
+    /// and return it after reopening it.
+    ///
+    /// The index is removed as it describes the work tree, but all files in the work tree remain untouched.
+    pub fn into_bare(self) -> Result<crate::Repository, worktree::convert::Error> {
+        self.require_writable()?;
+        self.assure_main_repository()?;
+        self.update_local_core_config(|core| {
+            core.set(key("bare"), "true");
+            core.remove("worktree");
+        })?;
+        match std::fs::remove_file(self.index_path()) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+        }
+        Ok(crate::ThreadSafeRepository::open_opts(self.git_dir(), self.options.clone())?.into())
+    }
+
+    /// Turn this repository into one with a work tree by setting `core.bare = false` in its local configuration, and return it after
+    /// reopening it.
+    ///
+    /// The work tree is placed at `work_dir`, or in the parent directory of the `.git` directory of this repository if `None`,
+    /// and created if it doesn't exist. `core.worktree` is set if it's not the parent directory.
+    /// Note that no files are checked out, use [`checkout_tree()`][crate::Repository::checkout_tree()] for that.
+    pub fn into_non_bare(
+        self,
+        work_dir: Option<std::path::PathBuf>,
+    ) -> Result<crate::Repository, worktree::convert::Error> {
+        self.require_writable()?;
+        self.assure_main_repository()?;
+        let git_dir = git_path::absolutize(self.git_dir(), std::env::current_dir().ok()).into_owned();
+        let default_work_dir = (git_dir.file_name() == Some(".git".as_ref()))
+            .then(|| git_dir.parent())
+            .flatten();
+        let work_dir = match (work_dir, default_work_dir) {
+            (Some(work_dir), _) => git_path::absolutize(work_dir, std::env::current_dir().ok()).into_owned(),
+            (None, Some(parent)) => parent.to_owned(),
+            (None, None) => return Err(worktree::convert::Error::MissingWorkTreeLocation { git_dir }),
+        };
+        std::fs::create_dir_all(&work_dir)?;
+        let custom_work_dir =
+            (Some(work_dir.as_path()) != default_work_dir).then(|| git_path::into_bstr(work_dir.as_path()));
+        self.update_local_core_config(|core| {
+            core.set(key("bare"), "false");
+            match &custom_work_dir {
+                Some(work_dir) => {
+                    core.set(key("worktree"), work_dir.as_ref());
+                }
+                None => {
+                    core.remove("worktree");
+                }
+            }
+        })?;
+        Ok(crate::ThreadSafeRepository::open_opts(self.git_dir(), self.options.clone())?.into())
+    }
+
+    fn assure_main_repository(&self) -> Result<(), worktree::convert::Error> {
+        if self.git_dir() != self.common_dir() {
+            return Err(worktree::convert::Error::LinkedWorktree {
+                git_dir: self.git_dir().to_owned(),
+            });
+        }
+        Ok(())
+    }
+
+    fn update_local_core_config(
+        &self,
+        update: impl FnOnce(&mut git_config::file::SectionMut<'_, '_>),
+    ) -> Result<(), worktree::convert::Error> {
+        let config_path = self.common_dir().join("config");
+        let mut config = git_config::File::from_path_no_includes(config_path.clone(), git_config::Source::Local)?;
+        update(
+            &mut config
+                .section_mut_or_create_new("core", None)
+                .expect("valid section name"),
+        );
+        std::fs::write(config_path, config.to_bstring())?;
+        Ok(())
+    }
+}
+
+/// Checkout
+impl crate::Repository {
+    /// Write the files of the tree of `treeish`, which may also be a commit or a tag pointing to one, into the work tree and
+    /// replace the index to match it.
+    ///
+    /// `progress` receives two children, `checkout` counting files and `writing` counting bytes, which are initialized
+    /// before any file is written. `should_interrupt` is checked for each file and causes the operation to abort with an error
+    /// if set, leaving the files checked out so far along with the previous index.
+    ///
+    /// Note that untracked files are not removed, and that the `HEAD` reference is not changed.
+    pub fn checkout_tree(
+        &self,
+        treeish: impl Into<git_hash::ObjectId>,
+        mut progress: impl Progress,
+        should_interrupt: &AtomicBool,
+        options: worktree::checkout::Options,
+    ) -> Result<git_worktree::index::checkout::Outcome, worktree::checkout::Error> {
+        let workdir = self.require_worktree("Checking out a tree")?.base();
+        self.require_writable()?;
+        let tree_id = self.find_object(treeish)?.peel_to_kind(crate::object::Kind::Tree)?.id;
+        let mut index = git_index::State::from_tree(&tree_id, |oid, buf| self.objects.find_tree_iter(oid, buf).ok())?;
+        let objects = self.objects.clone().into_arc()?;
+        let mut files = progress.add_child("checkout");
+        let mut bytes = progress.add_child("writing");
+        files.init(Some(index.entries().len()), git_features::progress::count("files"));
+        bytes.init(None, git_features::progress::bytes());
+        let outcome = git_worktree::index::checkout(
+            &mut index,
+            workdir,
+            move |oid, buf| objects.find_blob(oid, buf),
+            &mut files,
+            &mut bytes,
+            should_interrupt,
+            git_worktree::index::checkout::Options {
+                fs: git_worktree::fs::Capabilities::probe(self.git_dir()),
+                destination_is_initially_empty: options.destination_is_initially_empty,
+                overwrite_existing: options.overwrite_existing,
+                validate: self.config.path_protections,
+                filters: self.checkout_filters(),
+                ..Default::default()
+            },
+        )
+        .map_err(|err| worktree::checkout::Error::Checkout(Box::new(err)))?;
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(worktree::checkout::Error::Interrupted);
+        }
+
+        let mut index = git_index::File {
+            state: index,
+            path: self.index_path(),
+            checksum: git_hash::ObjectId::null(self.object_hash()),
+        };
+        index.write(
+            git_index::write::Options {
+                hash_kind: self.object_hash(),
+                ..Default::default()
+            },
+            self.config.fsync.method_if(self.config.fsync.index),
+        )?;
+        Ok(outcome)
+    }
+
+    /// Obtain the content conversions to apply on checkout from `core.autocrlf`, `core.eol` and the `filter.<name>` sections
+    /// of trusted configuration files.
+    fn checkout_filters(&self) -> git_worktree::index::checkout::Filters {
+        use git_worktree::index::checkout::{AutoCrlf, Eol, FilterDriver, Filters};
+        let config = &self.config.resolved;
+        let mut filter = self.filter_config_section();
+        let auto_crlf = match config.string_filter("core", None, "autocrlf", &mut filter) {
+            Some(value) if value.as_ref() == "input" => AutoCrlf::Input,
+            Some(value) => match git_config::Boolean::try_from(value.as_ref()) {
+                Ok(git_config::Boolean(true)) => AutoCrlf::Enabled,
+                _ => AutoCrlf::Disabled,
+            },
+            None => AutoCrlf::Disabled,
+        };
+        let eol = match config.string_filter("core", None, "eol", &mut filter) {
+            Some(value) if value.as_ref() == "lf" => Eol::Lf,
+            Some(value) if value.as_ref() == "crlf" => Eol::Crlf,
+            _ => Eol::default(),
+        };
+        let drivers = config
+            .sections_by_name_and_filter("filter", &mut filter)
+            .map(|sections| {
+                sections
+                    .filter_map(|section| section.header().subsection_name())
+                    .filter_map(|name| {
+                        let name_str = std::str::from_utf8(name.as_ref()).ok()?;
+                        Some(FilterDriver {
+                            name: name.to_owned(),
+                            smudge: config
+                                .string_filter("filter", Some(name_str), "smudge", &mut self.filter_config_section())
+                                .map(|v| v.into_owned()),
+                            required: config
+                                .boolean_filter("filter", Some(name_str), "required", &mut self.filter_config_section())
+                                .and_then(Result::ok)
+                                .unwrap_or(false),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Filters {
+            auto_crlf,
+            eol,
+            drivers,
+        }
+    }
+}
+
+/// Worktree streaming
+#[cfg(feature = "worktree-stream")]
+impl crate::Repository {
+    /// Return a stream over all entries of the tree of `treeish`, which may be a commit or tag pointing to one, in the order
+    /// and with the content they would have in an [archive][crate::Repository::archive()].
+    ///
+    /// Entries with the `export-ignore` attribute are skipped, and files with the `export-subst` attribute have their
+    /// `$Format:<format>$` placeholders expanded if `treeish` points to a commit. This makes it possible to write
+    /// the entries in custom formats.
+    pub fn worktree_stream(
+        &self,
+        treeish: impl Into<git_hash::ObjectId>,
+    ) -> Result<worktree::stream::Stream<'_>, worktree::stream::Error> {
+        worktree::stream::Stream::new(self, treeish.into())
+    }
+}
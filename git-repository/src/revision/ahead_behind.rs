@@ -0,0 +1,33 @@
+//! Count the commits one branch is ahead and behind of another one, similar to `git rev-list --left-right --count`.
+
+/// The error returned by [`Repository::ahead_behind()`][crate::Repository::ahead_behind()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Traverse(#[from] crate::revision::merge_base::Error),
+    #[error(
+        "Counting commits was aborted after reading more than {max_commits} commits that aren't in the commit-graph"
+    )]
+    TooExpensive { max_commits: usize },
+}
+
+/// Options for use in [`Repository::ahead_behind()`][crate::Repository::ahead_behind()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If set, fail with [`Error::TooExpensive`] as soon as more than this amount of commits had to be read from the
+    /// object database as they aren't contained in the commit-graph.
+    ///
+    /// Commits in the commit-graph are cheap to traverse and don't count towards this limit, which allows to show
+    /// the information where it's readily available and to degrade gracefully in huge repositories without commit-graph.
+    pub max_commits: Option<usize>,
+}
+
+/// The outcome of [`Repository::ahead_behind()`][crate::Repository::ahead_behind()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Outcome {
+    /// The amount of commits reachable from the first commit, but not from the second one.
+    pub ahead: usize,
+    /// The amount of commits reachable from the second commit, but not from the first one.
+    pub behind: usize,
+}
@@ -0,0 +1,263 @@
+//! Find the best common ancestors of commits, similar to `git merge-base`.
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use git_hash::{oid, ObjectId};
+use git_odb::FindExt;
+
+use crate::Repository;
+
+/// The error returned by [`Repository::merge_base()`] and related methods.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindExistingCommit(#[from] git_odb::find::existing_iter::Error<git_odb::store::find::Error>),
+    #[error(transparent)]
+    DecodeCommit(#[from] git_object::decode::Error),
+    #[error(transparent)]
+    CommitGraph(#[from] git_commitgraph::file::commit::Error),
+}
+
+/// The generation of commits that aren't contained in the commit-graph, which might be anywhere in the graph.
+const GENERATION_NUMBER_INFINITY: u32 = u32::MAX;
+
+const PARENT1: u8 = 1 << 0;
+const PARENT2: u8 = 1 << 1;
+const STALE: u8 = 1 << 2;
+const RESULT: u8 = 1 << 3;
+
+struct Info {
+    parents: Vec<ObjectId>,
+    generation: u32,
+    time: u64,
+}
+
+/// A commit in the queue, ordered by generation and then by commit time, so the commits most recent in topology come first.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Queued {
+    generation: u32,
+    time: u64,
+    id: ObjectId,
+}
+
+/// Access to the parents, generation numbers and commit times of commits, using the commit-graph if available.
+pub(crate) struct Graph<'repo> {
+    repo: &'repo Repository,
+    commit_graph: Option<git_commitgraph::Graph>,
+    commits: HashMap<ObjectId, Info>,
+    buf: Vec<u8>,
+    /// The amount of commits that had to be read from the object database as they aren't in the commit-graph.
+    pub(crate) commits_read: usize,
+}
+
+impl<'repo> Graph<'repo> {
+    pub(crate) fn new(repo: &'repo Repository) -> Self {
+        Graph {
+            repo,
+            commit_graph: git_commitgraph::Graph::from_info_dir(repo.objects.store_ref().path().join("info")).ok(),
+            commits: HashMap::new(),
+            buf: Vec::new(),
+            commits_read: 0,
+        }
+    }
+
+    fn info(&mut self, id: &oid) -> Result<&Info, Error> {
+        if !self.commits.contains_key(id) {
+            let info = match self.commit_graph.as_ref().and_then(|graph| graph.commit_by_id(id)) {
+                Some(commit) => {
+                    let graph = self.commit_graph.as_ref().expect("present");
+                    Info {
+                        parents: commit
+                            .iter_parents()
+                            .map(|pos| pos.map(|pos| graph.id_at(pos).to_owned()))
+                            .collect::<Result<_, _>>()?,
+                        generation: commit.generation(),
+                        time: commit.committer_timestamp(),
+                    }
+                }
+                None => {
+                    self.commits_read += 1;
+                    let commit = self.repo.objects.find_commit_iter(id, &mut self.buf)?;
+                    let parents = commit.parent_ids().collect();
+                    Info {
+                        parents,
+                        generation: GENERATION_NUMBER_INFINITY,
+                        time: commit.committer()?.time.seconds_since_unix_epoch as u64,
+                    }
+                }
+            };
+            self.commits.insert(id.to_owned(), info);
+        }
+        Ok(&self.commits[id])
+    }
+
+    fn queued(&mut self, id: ObjectId) -> Result<Queued, Error> {
+        let info = self.info(&id)?;
+        Ok(Queued {
+            generation: info.generation,
+            time: info.time,
+            id,
+        })
+    }
+
+    /// Return all commits that are reachable from `one` and at least one of `others` but are not reachable from another
+    /// such commit, sorted by commit time with the most recent commit first.
+    pub(crate) fn merge_bases_many(&mut self, one: ObjectId, others: &[ObjectId]) -> Result<Vec<ObjectId>, Error> {
+        if others.contains(&one) {
+            return Ok(vec![one]);
+        }
+        let (candidates, flags) = self.paint_down_to_common(one, others)?;
+        let mut bases: Vec<_> = candidates
+            .into_iter()
+            .filter(|id| flags[id] & STALE == 0)
+            .map(|id| self.queued(id))
+            .collect::<Result<_, _>>()?;
+        bases.sort_by_key(|b| std::cmp::Reverse(b.time));
+        let bases: Vec<_> = bases.into_iter().map(|queued| queued.id).collect();
+        self.remove_redundant(bases)
+    }
+
+    /// Return `true` if `ancestor` is reachable from `descendant`, or is the same commit.
+    pub(crate) fn is_ancestor(&mut self, ancestor: ObjectId, descendant: ObjectId) -> Result<bool, Error> {
+        if ancestor == descendant {
+            return Ok(true);
+        }
+        let min_generation = self.info(&ancestor)?.generation;
+        let descendant = self.queued(descendant)?;
+        if min_generation != GENERATION_NUMBER_INFINITY
+            && descendant.generation != GENERATION_NUMBER_INFINITY
+            && min_generation >= descendant.generation
+        {
+            return Ok(false);
+        }
+
+        let mut seen = HashSet::new();
+        seen.insert(descendant.id);
+        let mut queue = BinaryHeap::new();
+        queue.push(descendant);
+        while let Some(commit) = queue.pop() {
+            if commit.id == ancestor {
+                return Ok(true);
+            }
+            if min_generation != GENERATION_NUMBER_INFINITY && commit.generation <= min_generation {
+                // all commits reachable from here have a generation lower than the one of `ancestor`.
+                continue;
+            }
+            for parent in self.info(&commit.id)?.parents.clone() {
+                if seen.insert(parent) {
+                    queue.push(self.queued(parent)?);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// Count the commits reachable from `one` but not from `two`, and the ones reachable from `two` but not from `one`.
+    ///
+    /// Return `None` as soon as more than `max_commits` had to be read from the object database, if set.
+    pub(crate) fn ahead_behind(
+        &mut self,
+        one: ObjectId,
+        two: ObjectId,
+        max_commits: Option<usize>,
+    ) -> Result<Option<(usize, usize)>, Error> {
+        let mut flags = HashMap::<ObjectId, u8>::new();
+        let mut queue = BinaryHeap::new();
+        *flags.entry(one).or_default() |= PARENT1;
+        *flags.entry(two).or_default() |= PARENT2;
+        queue.push(self.queued(one)?);
+        if two != one {
+            queue.push(self.queued(two)?);
+        }
+
+        while queue.iter().any(|queued| flags[&queued.id] & STALE == 0) {
+            if max_commits.map_or(false, |max| self.commits_read > max) {
+                return Ok(None);
+            }
+            let commit = queue.pop().expect("non-empty queue");
+            let commit_flags = flags.get_mut(&commit.id).expect("flags set when queued");
+            let mut parent_flags = *commit_flags & (PARENT1 | PARENT2 | STALE);
+            if parent_flags & (PARENT1 | PARENT2) == PARENT1 | PARENT2 {
+                parent_flags |= STALE;
+                *commit_flags |= STALE;
+            }
+            for parent in self.info(&commit.id)?.parents.clone() {
+                let flags = flags.entry(parent).or_default();
+                if *flags & parent_flags == parent_flags {
+                    continue;
+                }
+                *flags |= parent_flags;
+                queue.push(self.queued(parent)?);
+            }
+        }
+
+        let count = |side: u8| {
+            flags
+                .values()
+                .filter(|flags| *flags & (PARENT1 | PARENT2) == side)
+                .count()
+        };
+        Ok(Some((count(PARENT1), count(PARENT2))))
+    }
+
+    /// Walk down from `one` and `others` and return all commits reachable from both sides, along with the flags of
+    /// all commits we have seen. Commits marked as `STALE` are reachable from another common commit.
+    fn paint_down_to_common(
+        &mut self,
+        one: ObjectId,
+        others: &[ObjectId],
+    ) -> Result<(Vec<ObjectId>, HashMap<ObjectId, u8>), Error> {
+        let mut flags = HashMap::<ObjectId, u8>::new();
+        let mut queue = BinaryHeap::new();
+        *flags.entry(one).or_default() |= PARENT1;
+        queue.push(self.queued(one)?);
+        for other in others {
+            *flags.entry(*other).or_default() |= PARENT2;
+            queue.push(self.queued(*other)?);
+        }
+
+        let mut result = Vec::new();
+        while queue.iter().any(|queued| flags[&queued.id] & STALE == 0) {
+            let commit = queue.pop().expect("non-empty queue");
+            let commit_flags = flags.get_mut(&commit.id).expect("flags set when queued");
+            let mut parent_flags = *commit_flags & (PARENT1 | PARENT2 | STALE);
+            if parent_flags == PARENT1 | PARENT2 {
+                if *commit_flags & RESULT == 0 {
+                    *commit_flags |= RESULT;
+                    result.push(commit.id);
+                }
+                parent_flags |= STALE;
+            }
+            for parent in self.info(&commit.id)?.parents.clone() {
+                let flags = flags.entry(parent).or_default();
+                if *flags & parent_flags == parent_flags {
+                    continue;
+                }
+                *flags |= parent_flags;
+                queue.push(self.queued(parent)?);
+            }
+        }
+        Ok((result, flags))
+    }
+
+    /// Remove all `commits` that are reachable from another one in `commits`, while retaining their order.
+    fn remove_redundant(&mut self, commits: Vec<ObjectId>) -> Result<Vec<ObjectId>, Error> {
+        if commits.len() < 2 {
+            return Ok(commits);
+        }
+        let mut result = Vec::with_capacity(commits.len());
+        for (idx, commit) in commits.iter().enumerate() {
+            let mut is_redundant = false;
+            for (other_idx, other) in commits.iter().enumerate() {
+                if idx != other_idx && self.is_ancestor(*commit, *other)? {
+                    is_redundant = true;
+                    break;
+                }
+            }
+            if !is_redundant {
+                result.push(*commit);
+            }
+        }
+        Ok(result)
+    }
+}
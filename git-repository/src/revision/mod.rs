@@ -11,6 +11,12 @@ pub use walk::iter::Walk;
 ///
 pub mod spec;
 
+///
+pub mod merge_base;
+
+///
+pub mod ahead_behind;
+
 /// The specification of a revision as parsed from a revision specification like `HEAD@{1}` or `v1.2.3...main`.
 /// It's typically created by [`repo.rev_parse()`][crate::Repository::rev_parse()].
 ///
@@ -96,59 +96,92 @@ impl<'repo> delegate::Revision for Delegate<'repo> {
 
     fn reflog(&mut self, query: ReflogLookup) -> Option<()> {
         self.unset_disambiguate_call();
+        let r = match &mut self.refs[self.idx] {
+            Some(r) => r.clone().attach(self.repo),
+            val @ None => match self.repo.head().map(|head| head.try_into_referent()) {
+                Ok(Some(r)) => {
+                    *val = Some(r.clone().detach());
+                    r
+                }
+                Ok(None) => {
+                    self.err.push(Error::UnbornHeadsHaveNoRefLog);
+                    return None;
+                }
+                Err(err) => {
+                    self.err.push(err.into());
+                    return None;
+                }
+            },
+        };
+        let mut platform = r.log_iter();
         match query {
-            ReflogLookup::Date(_date) => {
-                self.err.push(Error::Planned {
-                    dependency: "remote handling and ref-specs are fleshed out more",
-                });
-                None
-            }
-            ReflogLookup::Entry(no) => {
-                let r = match &mut self.refs[self.idx] {
-                    Some(r) => r.clone().attach(self.repo),
-                    val @ None => match self.repo.head().map(|head| head.try_into_referent()) {
-                        Ok(Some(r)) => {
-                            *val = Some(r.clone().detach());
-                            r
-                        }
-                        Ok(None) => {
-                            self.err.push(Error::UnbornHeadsHaveNoRefLog);
-                            return None;
-                        }
-                        Err(err) => {
-                            self.err.push(err.into());
-                            return None;
-                        }
-                    },
-                };
-                let mut platform = r.log_iter();
-                match platform.rev().ok().flatten() {
-                    Some(mut it) => match it.nth(no).and_then(Result::ok) {
-                        Some(line) => {
+            ReflogLookup::Date(date) => match platform.rev().ok().flatten() {
+                Some(it) => {
+                    let mut oldest = None;
+                    for line in it.filter_map(Result::ok) {
+                        if line.signature.time.seconds_since_unix_epoch <= date.seconds_since_unix_epoch {
                             self.objs[self.idx]
                                 .get_or_insert_with(HashSet::default)
                                 .insert(line.new_oid);
+                            return Some(());
+                        }
+                        oldest = Some(line);
+                    }
+                    match oldest {
+                        // Like git, use the value before the oldest entry if the date predates the log.
+                        Some(line) => {
+                            self.objs[self.idx].get_or_insert_with(HashSet::default).insert(
+                                if line.previous_oid.is_null() {
+                                    line.new_oid
+                                } else {
+                                    line.previous_oid
+                                },
+                            );
                             Some(())
                         }
                         None => {
-                            let available = platform.rev().ok().flatten().map_or(0, |it| it.count());
-                            self.err.push(Error::RefLogEntryOutOfRange {
-                                reference: r.detach(),
-                                desired: no,
-                                available,
+                            self.err.push(Error::MissingRefLog {
+                                reference: r.name().as_bstr().into(),
+                                action: "lookup entry by date",
                             });
                             None
                         }
-                    },
+                    }
+                }
+                None => {
+                    self.err.push(Error::MissingRefLog {
+                        reference: r.name().as_bstr().into(),
+                        action: "lookup entry by date",
+                    });
+                    None
+                }
+            },
+            ReflogLookup::Entry(no) => match platform.rev().ok().flatten() {
+                Some(mut it) => match it.nth(no).and_then(Result::ok) {
+                    Some(line) => {
+                        self.objs[self.idx]
+                            .get_or_insert_with(HashSet::default)
+                            .insert(line.new_oid);
+                        Some(())
+                    }
                     None => {
-                        self.err.push(Error::MissingRefLog {
-                            reference: r.name().as_bstr().into(),
-                            action: "lookup entry",
+                        let available = platform.rev().ok().flatten().map_or(0, |it| it.count());
+                        self.err.push(Error::RefLogEntryOutOfRange {
+                            reference: r.detach(),
+                            desired: no,
+                            available,
                         });
                         None
                     }
+                },
+                None => {
+                    self.err.push(Error::MissingRefLog {
+                        reference: r.name().as_bstr().into(),
+                        action: "lookup entry",
+                    });
+                    None
                 }
-            }
+            },
         }
     }
 
@@ -1,3 +1,5 @@
+use std::sync::atomic::AtomicBool;
+
 use git_hash::ObjectId;
 use git_odb::FindExt;
 
@@ -9,7 +11,11 @@ pub struct Platform<'repo> {
     pub(crate) repo: &'repo Repository,
     pub(crate) tips: Vec<ObjectId>,
     pub(crate) sorting: git_traverse::commit::Sorting,
+    pub(crate) tie_break: git_traverse::commit::TieBreak,
     pub(crate) parents: git_traverse::commit::Parents,
+    pub(crate) should_interrupt: Option<&'repo AtomicBool>,
+    pub(crate) since: Option<u32>,
+    pub(crate) until: Option<u32>,
 }
 
 impl<'repo> Platform<'repo> {
@@ -18,7 +24,11 @@ impl<'repo> Platform<'repo> {
             repo,
             tips: tips.into_iter().map(Into::into).collect(),
             sorting: Default::default(),
+            tie_break: Default::default(),
             parents: Default::default(),
+            should_interrupt: None,
+            since: None,
+            until: None,
         }
     }
 }
@@ -31,11 +41,41 @@ impl<'repo> Platform<'repo> {
         self
     }
 
+    /// Set how to order commits that are equal according to the sort mode. The default is to return them in the order
+    /// they were encountered.
+    pub fn tie_break(mut self, tie_break: git_traverse::commit::TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
     /// Only traverse the first parent of the commit graph.
     pub fn first_parent_only(mut self) -> Self {
         self.parents = git_traverse::commit::Parents::First;
         self
     }
+
+    /// Stop the traversal once `should_interrupt` is set, which is checked before each commit is produced.
+    ///
+    /// Check [`is_interrupted`][revision::Walk::is_interrupted] once the iteration ended to learn if it was stopped prematurely.
+    pub fn with_interrupt(mut self, should_interrupt: &'repo AtomicBool) -> Self {
+        self.should_interrupt = Some(should_interrupt);
+        self
+    }
+
+    /// Only return commits whose committer time is at or after `time`, similar to `git log --since`.
+    ///
+    /// Use [`git_date::parse()`] to obtain the time from approximate dates like `2 weeks ago`.
+    /// Note that older commits are still traversed to find newer ones that might be reachable from them.
+    pub fn since(mut self, time: git_date::Time) -> Self {
+        self.since = Some(time.seconds_since_unix_epoch);
+        self
+    }
+
+    /// Only return commits whose committer time is at or before `time`, similar to `git log --until`.
+    pub fn until(mut self, time: git_date::Time) -> Self {
+        self.until = Some(time.seconds_since_unix_epoch);
+        self
+    }
 }
 
 /// Produce the iterator
@@ -51,7 +91,11 @@ impl<'repo> Platform<'repo> {
             repo,
             tips,
             sorting,
+            tie_break,
             parents,
+            should_interrupt,
+            since,
+            until,
         } = self;
         Ok(revision::Walk {
             repo,
@@ -62,15 +106,25 @@ impl<'repo> Platform<'repo> {
                     move |oid, buf| repo.objects.find_commit_iter(oid, buf),
                 )
                 .sorting(sorting)?
+                .tie_break(tie_break)
                 .parents(parents),
             ),
             is_shallow: None,
             error_on_missing_commit: false,
+            should_interrupt,
+            is_interrupted: false,
+            since,
+            until,
+            buf: Vec::new(),
         })
     }
 }
 
 pub(crate) mod iter {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use git_odb::FindExt;
+
     use crate::{ext::ObjectIdExt, Id};
 
     /// The iterator returned by [`crate::revision::walk::Platform::all()`].
@@ -87,6 +141,13 @@ pub(crate) mod iter {
         /// This happens if a repository is a shallow clone.
         /// Note that this value is `None` as long as the iteration isn't complete.
         pub is_shallow: Option<bool>,
+        pub(crate) should_interrupt: Option<&'repo AtomicBool>,
+        /// After iteration this flag is true if the iteration was stopped prematurely because an interrupt was requested
+        /// through the flag passed to [`with_interrupt()`][crate::revision::walk::Platform::with_interrupt()].
+        pub is_interrupted: bool,
+        pub(crate) since: Option<u32>,
+        pub(crate) until: Option<u32>,
+        pub(crate) buf: Vec<u8>,
     }
 
     impl<'repo> Walk<'repo> {
@@ -100,27 +161,57 @@ pub(crate) mod iter {
             self.error_on_missing_commit = true;
             self
         }
+
+        /// Return true if the commit with `id` was committed within the configured time range.
+        fn is_in_time_range(&mut self, id: &git_hash::oid) -> Result<bool, git_traverse::commit::ancestors::Error> {
+            if self.since.is_none() && self.until.is_none() {
+                return Ok(true);
+            }
+            let seconds = self
+                .repo
+                .objects
+                .find_commit_iter(id, &mut self.buf)
+                .map_err(|err| git_traverse::commit::ancestors::Error::FindExisting {
+                    oid: id.to_owned(),
+                    source: err.into(),
+                })?
+                .committer()?
+                .time
+                .seconds_since_unix_epoch;
+            Ok(!matches!(self.since, Some(since) if seconds < since)
+                && !matches!(self.until, Some(until) if seconds > until))
+        }
     }
 
     impl<'repo> Iterator for Walk<'repo> {
         type Item = Result<Id<'repo>, git_traverse::commit::ancestors::Error>;
 
         fn next(&mut self) -> Option<Self::Item> {
-            match self.inner.next() {
-                None => {
-                    self.is_shallow = Some(false);
-                    None
+            loop {
+                if self.is_interrupted || self.should_interrupt.map(|flag| flag.load(Ordering::Relaxed)) == Some(true) {
+                    self.is_interrupted = true;
+                    return None;
                 }
-                Some(Ok(oid)) => Some(Ok(oid.attach(self.repo))),
-                Some(Err(err @ git_traverse::commit::ancestors::Error::FindExisting { .. })) => {
-                    if self.error_on_missing_commit {
-                        Some(Err(err))
-                    } else {
-                        self.is_shallow = Some(true);
+                return match self.inner.next() {
+                    None => {
+                        self.is_shallow = Some(false);
                         None
                     }
-                }
-                Some(Err(err)) => Some(Err(err)),
+                    Some(Ok(oid)) => match self.is_in_time_range(&oid) {
+                        Ok(true) => Some(Ok(oid.attach(self.repo))),
+                        Ok(false) => continue,
+                        Err(err) => Some(Err(err)),
+                    },
+                    Some(Err(err @ git_traverse::commit::ancestors::Error::FindExisting { .. })) => {
+                        if self.error_on_missing_commit {
+                            Some(Err(err))
+                        } else {
+                            self.is_shallow = Some(true);
+                            None
+                        }
+                    }
+                    Some(Err(err)) => Some(Err(err)),
+                };
             }
         }
     }
@@ -0,0 +1,72 @@
+//! Parse the request clients send when connecting to a `git://` daemon, to learn which service to run for which repository.
+use crate::{
+    bstr::{BString, ByteSlice},
+    serve::{read_line, Service},
+};
+
+/// The error returned by [`Request::from_read()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read the request of the client")]
+    Io(#[from] std::io::Error),
+    #[error("The client didn't send a request")]
+    Empty,
+    #[error("Invalid request {line:?}")]
+    Invalid { line: BString },
+    #[error("The service {name:?} is unknown")]
+    UnknownService { name: BString },
+}
+
+/// The first packet line a client sends to a `git://` daemon, like `git-upload-pack /project.git\0host=example.com\0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    /// The service to run.
+    pub service: Service,
+    /// The path of the repository as sent by the client, which is usually absolute and can't be trusted.
+    pub path: BString,
+    /// The host and port the client connected to, if it sent it.
+    pub host: Option<BString>,
+    /// Additional parameters like `version=2`, which can be ignored as we only speak protocol V0.
+    pub extra_parameters: Vec<BString>,
+}
+
+impl Request {
+    /// Read the request from `read`, which must be the beginning of the connection.
+    pub fn from_read(mut read: impl std::io::Read) -> Result<Self, Error> {
+        let line = read_line(&mut read)?.ok_or(Error::Empty)?;
+        Self::from_bytes(&line)
+    }
+
+    /// Parse a request from the data of its packet `line`.
+    pub fn from_bytes(line: &[u8]) -> Result<Self, Error> {
+        let invalid = || Error::Invalid { line: line.into() };
+        let mut fields = line.strip_suffix(b"\n").unwrap_or(line).split(|b| *b == 0);
+        let command = fields.next().ok_or_else(invalid)?;
+        let space = command.find_byte(b' ').ok_or_else(invalid)?;
+        let (name, path) = (&command[..space], &command[space + 1..]);
+        let service = Service::from_bytes(name).ok_or_else(|| Error::UnknownService { name: name.into() })?;
+        if path.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut host = None;
+        let mut extra_parameters = Vec::new();
+        let mut in_extra_parameters = false;
+        for field in fields {
+            if field.is_empty() {
+                in_extra_parameters = true;
+            } else if in_extra_parameters {
+                extra_parameters.push(field.into());
+            } else if let Some(value) = field.strip_prefix(b"host=") {
+                host = Some(value.into());
+            }
+        }
+        Ok(Request {
+            service,
+            path: path.into(),
+            host,
+            extra_parameters,
+        })
+    }
+}
@@ -0,0 +1,73 @@
+//! Map smart HTTP requests to services, for use with [`Repository::serve_http()`][crate::Repository::serve_http()].
+//!
+//! The HTTP server itself isn't provided, nor is the dumb HTTP protocol, which serves the files of a repository as they are.
+use crate::serve::Service;
+
+/// The error returned by [`Repository::serve_http()`][crate::Repository::serve_http()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not write the response")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    UploadPack(#[from] super::upload_pack::Error),
+    #[error(transparent)]
+    ReceivePack(#[from] super::receive_pack::Error),
+}
+
+/// One of the endpoints of a repository that smart HTTP clients use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    /// `GET <repository>/info/refs?service=<service>`, which advertises the references of the repository for `service`.
+    InfoRefs(Service),
+    /// `POST <repository>/<service>`, which serves a single request for `service`.
+    Service(Service),
+}
+
+impl Endpoint {
+    /// Find the endpoint requested with `method` for `path` along with `query`, the part of the URL after `?`, and return it along
+    /// with the path of the repository, which is the part of `path` before the endpoint without its trailing slash.
+    ///
+    /// Return `None` if the request isn't for a smart HTTP endpoint.
+    pub fn from_request<'a>(method: &str, path: &'a str, query: Option<&str>) -> Option<(&'a str, Endpoint)> {
+        if let Some(repository) = path.strip_suffix("/info/refs") {
+            if method != "GET" {
+                return None;
+            }
+            let service = query?
+                .split('&')
+                .find_map(|parameter| parameter.strip_prefix("service="))
+                .and_then(|name| Service::from_bytes(name.as_bytes()))?;
+            return Some((repository, Endpoint::InfoRefs(service)));
+        }
+        if method != "POST" {
+            return None;
+        }
+        let slash = path.rfind('/')?;
+        let service = Service::from_bytes(&path.as_bytes()[slash + 1..])?;
+        Some((&path[..slash], Endpoint::Service(service)))
+    }
+
+    /// Return the service this endpoint is for.
+    pub fn service(&self) -> Service {
+        match self {
+            Endpoint::InfoRefs(service) | Endpoint::Service(service) => *service,
+        }
+    }
+
+    /// Return the value of the `Content-Type` header of the response.
+    pub fn content_type(&self) -> String {
+        match self {
+            Endpoint::InfoRefs(service) => format!("application/x-{}-advertisement", service.as_str()),
+            Endpoint::Service(service) => format!("application/x-{}-result", service.as_str()),
+        }
+    }
+
+    /// Return the value of the `Content-Type` header requests must have, or `None` if requests don't have a body.
+    pub fn request_content_type(&self) -> Option<String> {
+        match self {
+            Endpoint::InfoRefs(_) => None,
+            Endpoint::Service(service) => Some(format!("application/x-{}-request", service.as_str())),
+        }
+    }
+}
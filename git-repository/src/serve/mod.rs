@@ -0,0 +1,101 @@
+//! Serve repositories to `git` clients like `git upload-pack` and `git receive-pack` do, which allows fetching from and pushing to them
+//! through a `git://` daemon or via smart HTTP.
+//!
+//! Only protocol V0 (and V1, which is the same) is spoken, which clients asking for protocol V2 fall back to.
+//! Shallow clones, partial clones and the `include-tag` capability aren't supported and hence not advertised.
+use crate::bstr::BString;
+
+///
+pub mod advertise {
+    /// The error returned when listing the references to advertise to clients.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        OpenReferences(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        InitReferences(#[from] crate::reference::iter::init::Error),
+        #[error("Could not read a reference")]
+        IterReferences(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        FindHead(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+    }
+}
+
+///
+pub mod daemon;
+///
+pub mod http;
+///
+pub mod receive_pack;
+///
+pub mod upload_pack;
+
+mod packetline;
+pub(crate) use packetline::{read_line, write_flush, write_line, Sideband};
+
+/// The name and version we advertise to clients.
+pub(crate) const AGENT: &str = concat!("git/oxide-", env!("CARGO_PKG_VERSION"));
+
+/// A service a client can connect to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Service {
+    /// Send packs to clients that fetch or clone, like `git upload-pack`.
+    UploadPack,
+    /// Receive packs and update references for clients that push, like `git receive-pack`.
+    ReceivePack,
+}
+
+impl Service {
+    /// Return the name clients use for this service, like `git-upload-pack`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Service::UploadPack => "git-upload-pack",
+            Service::ReceivePack => "git-receive-pack",
+        }
+    }
+
+    /// Parse a service `name` as returned by [`as_str()`][Service::as_str()], or return `None` if it's unknown.
+    pub fn from_bytes(name: &[u8]) -> Option<Self> {
+        Some(match name {
+            b"git-upload-pack" => Service::UploadPack,
+            b"git-receive-pack" => Service::ReceivePack,
+            _ => return None,
+        })
+    }
+}
+
+/// How a service talks to its client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Advertise all references and serve the requests of the client on the same connection, as needed for `git://`,
+    /// `ssh://` or `file://` URLs. This is the default.
+    Connection,
+    /// Only advertise all references and return, like `--advertise-refs`, to answer `GET info/refs` via smart HTTP.
+    AdvertiseRefs,
+    /// Serve a single request without advertising references first, like `--stateless-rpc`, to answer a `POST` via smart HTTP.
+    ///
+    /// Clients resend everything the server needs to know with each request.
+    StatelessRpc,
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Mode::Connection
+    }
+}
+
+/// A reference as advertised to clients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AdvertisedRef {
+    /// The full name of the reference.
+    pub name: BString,
+    /// The object the reference points to, after following symbolic references.
+    pub id: git_hash::ObjectId,
+    /// The object at the end of the tag chain if `id` is an annotated tag.
+    pub peeled: Option<git_hash::ObjectId>,
+}
@@ -0,0 +1,80 @@
+use std::io::{Read, Write};
+
+/// The largest packet line we accept and write, including its length prefix.
+const MAX_LINE_LEN: usize = 65520;
+
+/// Read a packet line from `read` and return its data, or `None` if it's a flush packet.
+pub(crate) fn read_line(read: &mut impl Read) -> std::io::Result<Option<Vec<u8>>> {
+    let mut hex_len = [0; 4];
+    read.read_exact(&mut hex_len)?;
+    let len = std::str::from_utf8(&hex_len)
+        .ok()
+        .and_then(|hex| usize::from_str_radix(hex, 16).ok())
+        .filter(|len| *len == 0 || (4..=MAX_LINE_LEN).contains(len))
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Invalid packet line length {:?}", String::from_utf8_lossy(&hex_len)),
+            )
+        })?;
+    if len == 0 {
+        return Ok(None);
+    }
+    let mut data = vec![0; len - 4];
+    read.read_exact(&mut data)?;
+    Ok(Some(data))
+}
+
+/// Write `data` as packet line to `out`.
+pub(crate) fn write_line(mut out: impl Write, data: &[u8]) -> std::io::Result<()> {
+    write!(out, "{:04x}", data.len() + 4)?;
+    out.write_all(data)
+}
+
+/// Write a flush packet to `out`.
+pub(crate) fn write_flush(mut out: impl Write) -> std::io::Result<()> {
+    out.write_all(b"0000")
+}
+
+/// Put all data written to it into packet lines on the data band, as expected from a server supporting `side-band` or `side-band-64k`.
+pub(crate) struct Sideband<W> {
+    inner: W,
+    max_data_per_line: usize,
+}
+
+impl<W: Write> Sideband<W> {
+    /// Wrap `inner` to write packet lines no longer than `max_line_len` bytes, which is 1000 for `side-band` and 65520 for `side-band-64k`.
+    pub fn new(inner: W, max_line_len: usize) -> Self {
+        Sideband {
+            inner,
+            max_data_per_line: max_line_len - 5,
+        }
+    }
+
+    /// Write `message` to the band for progress messages, or to the band for errors if `is_error` is true.
+    pub fn write_message(&mut self, message: &[u8], is_error: bool) -> std::io::Result<()> {
+        for chunk in message.chunks(self.max_data_per_line) {
+            write!(self.inner, "{:04x}", chunk.len() + 5)?;
+            self.inner.write_all(&[if is_error { 3 } else { 2 }])?;
+            self.inner.write_all(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for Sideband<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let chunk = &buf[..buf.len().min(self.max_data_per_line)];
+        if chunk.is_empty() {
+            return Ok(0);
+        }
+        write!(self.inner, "{:04x}", chunk.len() + 5)?;
+        self.inner.write_all(&[1])?;
+        self.inner.write_all(chunk)?;
+        Ok(chunk.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
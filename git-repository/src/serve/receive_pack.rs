@@ -0,0 +1,68 @@
+use git_hash::ObjectId;
+
+use crate::{bstr::BString, serve::Mode};
+
+/// The error returned by [`Repository::receive_pack()`][crate::Repository::receive_pack()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not communicate with the client")]
+    Io(#[from] std::io::Error),
+    #[error("The client sent an unexpected line: {line:?}")]
+    UnexpectedLine { line: BString },
+    #[error(transparent)]
+    ReadOnly(#[from] crate::read_only::Error),
+    #[error(transparent)]
+    AdvertiseRefs(#[from] super::advertise::Error),
+    #[error(transparent)]
+    DenyCurrentBranch(#[from] crate::remote::fetch::deny_current_branch::Error),
+    #[error("Could not learn which branches are checked out")]
+    CheckedOut(#[from] checked_out::Error),
+    #[error("Could not unpack the objects sent by the client")]
+    Unpack(#[from] git_pack::bundle::write::Error),
+}
+
+///
+pub mod checked_out {
+    /// The error returned when learning which branches are checked out in any worktree, to refuse updating them.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not list the linked worktrees")]
+        ListWorktrees(#[from] std::io::Error),
+        #[error("Could not open a worktree")]
+        OpenWorktree(#[from] crate::open::Error),
+        #[error(transparent)]
+        FindHead(#[from] crate::reference::find::existing::Error),
+    }
+}
+
+/// Options for use in [`Repository::receive_pack()`][crate::Repository::receive_pack()].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// How to talk to the client.
+    pub mode: Mode,
+}
+
+/// A reference the client asked to update, as listed in the [`Outcome`] of [`Repository::receive_pack()`][crate::Repository::receive_pack()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Update {
+    /// The full name of the reference, as sent by the client.
+    pub name: BString,
+    /// The id the client expects the reference to have, or `None` if it expects it to not exist yet.
+    pub previous: Option<ObjectId>,
+    /// The id the reference should have, or `None` if it should be deleted.
+    pub new: Option<ObjectId>,
+    /// The reason for rejecting the update as reported to the client, or `None` if the reference was updated.
+    pub rejection: Option<String>,
+}
+
+/// The outcome of [`Repository::receive_pack()`][crate::Repository::receive_pack()].
+#[derive(Debug, Default)]
+pub struct Outcome {
+    /// All references the client asked to update, in the order it sent them.
+    pub updates: Vec<Update>,
+    /// Information about the pack and index that were written into the object database, or `None` if the client didn't send a pack
+    /// as it only deleted references, or if the pack was empty.
+    pub pack: Option<git_pack::bundle::write::Outcome>,
+}
@@ -0,0 +1,63 @@
+use git_hash::ObjectId;
+
+use crate::{bstr::BString, serve::Mode};
+
+/// The error returned by [`Repository::upload_pack()`][crate::Repository::upload_pack()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not communicate with the client")]
+    Io(#[from] std::io::Error),
+    #[error("The client sent an unexpected line: {line:?}")]
+    UnexpectedLine { line: BString },
+    #[error("The client asked for {id} which isn't advertised")]
+    NotOurRef { id: ObjectId },
+    #[error(transparent)]
+    AdvertiseRefs(#[from] super::advertise::Error),
+    #[error(transparent)]
+    TraverseCommon(#[from] git_traverse::commit::ancestors::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    FindCommit(#[from] git_odb::find::existing_iter::Error<git_odb::store::find::Error>),
+    #[error(transparent)]
+    PeelObject(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    CountObjects(
+        #[from]
+        git_pack::data::output::count::objects::Error<
+            git_pack::find::existing::Error<git_odb::store::find::Error>,
+            std::convert::Infallible,
+        >,
+    ),
+    #[error(transparent)]
+    CreateEntries(#[from] git_pack::data::output::entry::iter_from_counts::Error<git_odb::store::find::Error>),
+    #[error(transparent)]
+    WritePack(
+        #[from]
+        git_pack::data::output::bytes::Error<
+            git_pack::data::output::entry::iter_from_counts::Error<git_odb::store::find::Error>,
+        >,
+    ),
+    #[error("The operation was interrupted")]
+    Interrupted,
+}
+
+/// Options for use in [`Repository::upload_pack()`][crate::Repository::upload_pack()].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// How to talk to the client.
+    pub mode: Mode,
+}
+
+/// The outcome of [`Repository::upload_pack()`][crate::Repository::upload_pack()].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The objects the client asked for, which is empty if it didn't ask for anything.
+    pub wants: Vec<ObjectId>,
+    /// The objects the client said it has which are also in this repository.
+    pub common: Vec<ObjectId>,
+    /// The amount of objects in the pack that was sent, or `None` if no pack was sent as the client isn't done negotiating yet
+    /// or didn't ask for anything.
+    pub num_objects: Option<u32>,
+}
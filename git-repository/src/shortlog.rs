@@ -0,0 +1,87 @@
+//! Aggregate commits by their authors or committers, similar to `git shortlog`.
+use crate::bstr::BString;
+
+/// The error returned by [`Repository::shortlog()`][crate::Repository::shortlog()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindExistingObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    MergeBase(#[from] crate::revision::merge_base::Error),
+    #[error(transparent)]
+    Walk(#[from] git_traverse::commit::ancestors::Error),
+    #[error(transparent)]
+    Decode(#[from] git_object::decode::Error),
+}
+
+/// Determine which identity of a commit to attribute it to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Group {
+    /// Attribute commits to their author, like `git shortlog` does by default.
+    Author,
+    /// Attribute commits to their committer, like `git shortlog --committer`.
+    Committer,
+}
+
+impl Default for Group {
+    fn default() -> Self {
+        Group::Author
+    }
+}
+
+/// Options for use in [`Repository::shortlog()`][crate::Repository::shortlog()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// The identity of each commit to attribute it to.
+    pub group: Group,
+    /// If true, contributors with the same name but different email addresses are kept apart, like `git shortlog -e`.
+    pub email: bool,
+    /// If true, collect the summary of each commit for each of its contributors.
+    pub summaries: bool,
+    /// If true, also attribute commits to everyone listed in their `Co-authored-by` trailers.
+    pub co_authors: bool,
+    /// If true, map names and email addresses with the repository's [mailmap][crate::Repository::open_mailmap()]
+    /// before grouping.
+    ///
+    /// This is enabled by default, just like in `git shortlog`.
+    #[cfg(feature = "git-mailmap")]
+    pub mailmap: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            group: Group::default(),
+            email: false,
+            summaries: false,
+            co_authors: false,
+            #[cfg(feature = "git-mailmap")]
+            mailmap: true,
+        }
+    }
+}
+
+/// A contributor along with the commits attributed to them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Contributor {
+    /// The name of the contributor.
+    pub name: BString,
+    /// The email address of the contributor, which is the first one seen unless [`Options::email`] is set.
+    pub email: BString,
+    /// The amount of commits attributed to the contributor.
+    pub commits: usize,
+    /// The summaries of all commits attributed to the contributor in traversal order, if [`Options::summaries`] is set.
+    pub summaries: Vec<BString>,
+}
+
+/// The outcome of [`Repository::shortlog()`][crate::Repository::shortlog()].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Outcome {
+    /// All contributors, sorted by the amount of their commits with the most active contributor first, and by name otherwise.
+    pub contributors: Vec<Contributor>,
+    /// The amount of commits in the range.
+    pub commits: usize,
+}
@@ -0,0 +1,76 @@
+//! Learn about changes between `HEAD`, the index and the worktree, for example to determine if a repository is dirty.
+use crate::bstr::BString;
+
+/// The error returned by [`Repository::changes()`][crate::Repository::changes()] and [`Repository::is_dirty()`][crate::Repository::is_dirty()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    MissingWorkTree(#[from] crate::worktree::require::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    PeelHead(#[from] crate::head::peel::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToTree(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    TraverseTree(#[from] git_traverse::tree::breadthfirst::Error),
+    #[error("Could not read {path:?} in the worktree")]
+    Io { path: BString, source: std::io::Error },
+}
+
+/// A change found by [`Repository::changes()`][crate::Repository::changes()].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Change {
+    /// The entry at `path` in the index differs from the one in the tree of `HEAD`, or is only present in one of them.
+    Staged {
+        /// The path of the entry relative to the worktree root.
+        path: BString,
+    },
+    /// The index has an unresolved merge conflict at `path`.
+    Conflict {
+        /// The path of the entry relative to the worktree root.
+        path: BString,
+    },
+    /// The file at `path` was marked with `git add --intent-to-add`, but wasn't added yet.
+    IntentToAdd {
+        /// The path of the entry relative to the worktree root.
+        path: BString,
+    },
+    /// The file at `path` in the worktree has a different content, kind or executable bit than the one in the index.
+    Modified {
+        /// The path of the entry relative to the worktree root.
+        path: BString,
+    },
+    /// The file at `path` in the index doesn't exist in the worktree.
+    Removed {
+        /// The path of the entry relative to the worktree root.
+        path: BString,
+    },
+}
+
+impl Change {
+    /// Return the path of the changed entry, relative to the worktree root.
+    pub fn path(&self) -> &crate::bstr::BStr {
+        match self {
+            Change::Staged { path }
+            | Change::Conflict { path }
+            | Change::IntentToAdd { path }
+            | Change::Modified { path }
+            | Change::Removed { path } => path.as_ref(),
+        }
+    }
+}
+
+/// Tell [`Repository::changes()`][crate::Repository::changes()] whether to look for more changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Continue looking for changes.
+    Continue,
+    /// Stop looking for changes right away.
+    Cancel,
+}
@@ -152,6 +152,8 @@ pub struct Repository {
     /// Particularly useful when following linked worktrees and instantiating new equally configured worktree repositories.
     pub(crate) options: crate::open::Options,
     pub(crate) index: crate::worktree::IndexStorage,
+    /// Overrides from `GIT_*` environment variables applied when opening this repository, if these were enabled.
+    pub(crate) environment: Option<Box<crate::open::EnvironmentOverrides>>,
 }
 
 /// An instance with access to everything a git repository entails, best imagined as container implementing `Sync + Send` for _most_
@@ -178,6 +180,8 @@ pub struct ThreadSafeRepository {
     pub(crate) linked_worktree_options: crate::open::Options,
     /// The index of this instances worktree.
     pub(crate) index: crate::worktree::IndexStorage,
+    /// Overrides from `GIT_*` environment variables applied when opening this repository, if these were enabled.
+    pub(crate) environment: Option<Box<crate::open::EnvironmentOverrides>>,
 }
 
 /// A remote which represents a way to interact with hosts for remote clones of the parent repository.
@@ -69,6 +69,10 @@ pub(crate) fn id(git_dir: &std::path::Path, has_common_dir: bool) -> Option<&BSt
 ///
 pub mod proxy;
 
+///
+#[cfg(feature = "worktree-stream")]
+pub mod stream;
+
 ///
 pub mod open_index {
     use crate::bstr::BString;
@@ -155,3 +159,80 @@ pub mod excludes {
         }
     }
 }
+
+///
+pub mod require {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Repository::require_worktree()`][crate::Repository::require_worktree()], and by all operations
+    /// which need a work tree when invoked on a bare repository.
+    #[derive(Debug, thiserror::Error)]
+    #[error("{operation} requires a work tree, but the repository at '{}' is bare", git_dir.display())]
+    pub struct Error {
+        /// The operation that was attempted.
+        pub operation: &'static str,
+        /// The repository without work tree.
+        pub git_dir: PathBuf,
+    }
+}
+
+///
+pub mod convert {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Repository::into_bare()`][crate::Repository::into_bare()] and
+    /// [`Repository::into_non_bare()`][crate::Repository::into_non_bare()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The linked worktree at '{}' cannot be converted, only its main repository can", git_dir.display())]
+        LinkedWorktree { git_dir: PathBuf },
+        #[error("The location of the work tree must be provided as the repository at '{}' isn't in a '.git' directory", git_dir.display())]
+        MissingWorkTreeLocation { git_dir: PathBuf },
+        #[error(transparent)]
+        ReadConfig(#[from] git_config::file::init::from_paths::Error),
+        #[error("Could not write the configuration or change the work tree skeleton")]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        Open(#[from] crate::open::Error),
+        #[error(transparent)]
+        ReadOnly(#[from] crate::read_only::Error),
+    }
+}
+
+///
+pub mod checkout {
+    /// The error returned by [`Repository::checkout_tree()`][crate::Repository::checkout_tree()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        MissingWorkTree(#[from] super::require::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        PeelToTree(#[from] crate::object::peel::to_kind::Error),
+        #[error("Could not create the index from the tree to checkout")]
+        IndexFromTree(#[from] git_index::init::from_tree::Error),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error("Could not checkout the files of the tree")]
+        Checkout(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error("Interrupted")]
+        Interrupted,
+        #[error(transparent)]
+        WriteIndex(#[from] git_index::file::write::Error),
+        #[error(transparent)]
+        ReadOnly(#[from] crate::read_only::Error),
+    }
+
+    /// Options for use in [`Repository::checkout_tree()`][crate::Repository::checkout_tree()].
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct Options {
+        /// If true, assume that the work tree contains no files, which is faster and detects collisions of paths in the tree
+        /// on case-insensitive file systems. This is the case when cloning.
+        pub destination_is_initially_empty: bool,
+        /// If true, overwrite files in the work tree even if they appear to be changed, similar to `git checkout --force`.
+        pub overwrite_existing: bool,
+    }
+}
@@ -0,0 +1,354 @@
+//! Stream the entries of a tree in the order and with the content they would have in an archive, similar to `git archive`,
+//! but without encoding them into any particular format.
+//!
+//! Entries are affected by the `export-ignore` and `export-subst` attributes as set in `.gitattributes` files of the
+//! exported tree, as well as in `$GIT_DIR/info/attributes`.
+use std::path::Path;
+
+use git_attributes::{Attributes, MatchGroup, PatternList, StateRef};
+use git_hash::ObjectId;
+use git_object::tree::EntryMode;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    object::Kind,
+};
+
+/// The error returned by [`Repository::worktree_stream()`][crate::Repository::worktree_stream()] and
+/// [`Stream::next_entry()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindExistingObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToTree(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    Decode(#[from] git_object::decode::Error),
+    #[error("Could not read the attributes at '{}'", path.display())]
+    ReadAttributes {
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
+}
+
+/// A single entry of a [`Stream`].
+#[derive(Debug, Clone, Copy)]
+pub struct Entry<'a> {
+    /// The kind of entry, which is [`EntryMode::Tree`] for directories and [`EntryMode::Commit`] for submodules.
+    pub mode: EntryMode,
+    /// The id of the object the entry points to.
+    pub id: ObjectId,
+    /// The path of the entry relative to the root of the streamed tree, without trailing slash even for directories.
+    pub path: &'a BStr,
+    data: &'a [u8],
+}
+
+impl<'a> Entry<'a> {
+    /// The content of files with `$Format:…$` placeholders expanded if necessary, the target of symlinks,
+    /// or nothing for directories and submodules.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Return a reader over our [data][Entry::data()].
+    pub fn reader(&self) -> impl std::io::Read + 'a {
+        self.data
+    }
+}
+
+/// The entries of a tree in depth-first order, with directories before their content, as created by
+/// [`Repository::worktree_stream()`][crate::Repository::worktree_stream()].
+pub struct Stream<'repo> {
+    repo: &'repo crate::Repository,
+    tree_id: ObjectId,
+    commit: Option<(ObjectId, git_object::Commit)>,
+    attributes: ExportAttributes,
+    /// The trees we are currently in, with the root tree first.
+    levels: Vec<Level>,
+    path: BString,
+    data: Vec<u8>,
+}
+
+/// The remaining entries of a tree we are traversing.
+struct Level {
+    entries: std::vec::IntoIter<(EntryMode, BString, ObjectId)>,
+    /// The length of the path to the tree itself.
+    path_len: usize,
+    attributes_pushed: bool,
+}
+
+impl<'repo> Stream<'repo> {
+    pub(crate) fn new(repo: &'repo crate::Repository, treeish: ObjectId) -> Result<Self, Error> {
+        let object = repo.find_object(treeish)?.peel_tags_to_end()?;
+        let commit = match object.kind {
+            Kind::Commit => Some((object.id, git_object::Commit::from(object.to_commit_ref()))),
+            _ => None,
+        };
+        let tree_id = object.peel_to_kind(Kind::Tree)?.id;
+        let mut stream = Stream {
+            repo,
+            tree_id,
+            commit,
+            attributes: ExportAttributes::from_git_dir(repo.git_dir())?,
+            levels: Vec::new(),
+            path: BString::default(),
+            data: Vec::new(),
+        };
+        stream.push_tree(tree_id)?;
+        Ok(stream)
+    }
+
+    /// The id of the tree whose entries are streamed.
+    pub fn tree_id(&self) -> ObjectId {
+        self.tree_id
+    }
+
+    /// The id of the commit the tree was taken from, if the stream was created from a commit.
+    pub fn commit_id(&self) -> Option<ObjectId> {
+        self.commit.as_ref().map(|(id, _)| *id)
+    }
+
+    /// The commit the tree was taken from, if the stream was created from a commit.
+    pub fn commit(&self) -> Option<&git_object::Commit> {
+        self.commit.as_ref().map(|(_, commit)| commit)
+    }
+
+    /// Return the next entry, or `None` if all entries were streamed.
+    ///
+    /// Entries with the `export-ignore` attribute are skipped, along with all their content if they are directories.
+    pub fn next_entry(&mut self) -> Result<Option<Entry<'_>>, Error> {
+        loop {
+            let level = match self.levels.last_mut() {
+                Some(level) => level,
+                None => return Ok(None),
+            };
+            let (mode, name, id) = match level.entries.next() {
+                Some(entry) => entry,
+                None => {
+                    if self.levels.pop().expect("present").attributes_pushed {
+                        self.attributes.pop();
+                    }
+                    continue;
+                }
+            };
+
+            self.path.truncate(level.path_len);
+            if !self.path.is_empty() {
+                self.path.push(b'/');
+            }
+            self.path.push_str(&name);
+            let is_dir = matches!(mode, EntryMode::Tree | EntryMode::Commit);
+            if self.attributes.ignore.is_set(self.path.as_ref(), is_dir) {
+                continue;
+            }
+
+            self.data.clear();
+            match mode {
+                EntryMode::Tree => self.push_tree(id)?,
+                EntryMode::Commit => {}
+                EntryMode::Link => self.data = self.repo.find_object(id)?.detach().data,
+                EntryMode::Blob | EntryMode::BlobExecutable => {
+                    self.data = self.repo.find_object(id)?.detach().data;
+                    if let Some((commit_id, commit)) = &self.commit {
+                        if self.attributes.subst.is_set(self.path.as_ref(), false) {
+                            self.data = substitute(&self.data, commit_id, commit);
+                        }
+                    }
+                }
+            }
+            return Ok(Some(Entry {
+                mode,
+                id,
+                path: self.path.as_ref(),
+                data: &self.data,
+            }));
+        }
+    }
+
+    /// Prepare to stream the entries of the tree with `id` located at our current path.
+    fn push_tree(&mut self, id: ObjectId) -> Result<(), Error> {
+        let tree = self.repo.find_object(id)?;
+        let entries = git_object::TreeRefIter::from_bytes(&tree.data, tree.id.kind())
+            .map(|entry| entry.map(|entry| (entry.mode, BString::from(entry.filename), entry.oid.to_owned())))
+            .collect::<Result<Vec<_>, _>>()?;
+        drop(tree);
+
+        let attributes_pushed = match entries.iter().find(|(mode, name, _)| {
+            matches!(mode, EntryMode::Blob | EntryMode::BlobExecutable) && name == ".gitattributes"
+        }) {
+            Some((_, _, id)) => {
+                let data = self.repo.find_object(*id)?.detach().data;
+                let mut dir = self.path.clone();
+                if !dir.is_empty() {
+                    dir.push(b'/');
+                }
+                self.attributes.push(dir.as_ref(), &data);
+                true
+            }
+            None => false,
+        };
+        self.levels.push(Level {
+            entries: entries.into_iter(),
+            path_len: self.path.len(),
+            attributes_pushed,
+        });
+        Ok(())
+    }
+}
+
+/// The patterns of a single attribute that affects which entries are exported and how.
+struct ExportAttribute {
+    name: &'static str,
+    /// Patterns from `$GIT_DIR/info/attributes`, which take precedence over all others.
+    info: MatchGroup<Attributes>,
+    /// Patterns from `.gitattributes` files in the exported tree, with the ones closest to the leafs last.
+    tree: MatchGroup<Attributes>,
+}
+
+impl ExportAttribute {
+    fn new(name: &'static str, info: Option<&PatternList<Attributes>>) -> Self {
+        ExportAttribute {
+            name,
+            info: MatchGroup {
+                patterns: info.map(|list| retain(list, name)).into_iter().collect(),
+            },
+            tree: MatchGroup::default(),
+        }
+    }
+
+    /// Return true if our attribute is set for the entry at `path`, which is a directory if `is_dir` is true.
+    fn is_set(&self, path: &BStr, is_dir: bool) -> bool {
+        let found = [&self.info, &self.tree].iter().find_map(|group| {
+            group
+                .pattern_matching_relative_path(path, Some(is_dir), git_glob::pattern::Case::Sensitive)
+                .and_then(|m| state(m.value, self.name))
+        });
+        found == Some(StateRef::Set)
+    }
+}
+
+/// The attributes that affect which entries are exported and how.
+struct ExportAttributes {
+    /// Entries with this attribute are not streamed.
+    ignore: ExportAttribute,
+    /// Files with this attribute have their `$Format:…$` placeholders expanded.
+    subst: ExportAttribute,
+}
+
+impl ExportAttributes {
+    fn from_git_dir(git_dir: &Path) -> Result<Self, Error> {
+        let path = git_dir.join("info").join("attributes");
+        let mut buf = Vec::new();
+        let info = PatternList::<Attributes>::from_file(path.clone(), None, true, &mut buf)
+            .map_err(|source| Error::ReadAttributes { source, path })?;
+        Ok(ExportAttributes {
+            ignore: ExportAttribute::new("export-ignore", info.as_ref()),
+            subst: ExportAttribute::new("export-subst", info.as_ref()),
+        })
+    }
+
+    /// Add the `.gitattributes` file with `data` found in the directory at `dir`, relative to the root of the tree.
+    fn push(&mut self, dir: &BStr, data: &[u8]) {
+        let mut source = git_path::from_bstr(dir).into_owned();
+        source.push(".gitattributes");
+        let list = PatternList::from_bytes(data, source, Some(Path::new("")));
+        for attribute in [&mut self.ignore, &mut self.subst] {
+            attribute.tree.patterns.push(retain(&list, attribute.name));
+        }
+    }
+
+    /// Remove the most recently [pushed][ExportAttributes::push()] attributes.
+    fn pop(&mut self) {
+        for attribute in [&mut self.ignore, &mut self.subst] {
+            attribute.tree.patterns.pop();
+        }
+    }
+}
+
+fn state<'a>(value: &'a git_attributes::Value, name: &str) -> Option<StateRef<'a>> {
+    match value {
+        git_attributes::Value::Assignments(assignments) => assignments
+            .iter()
+            .rev()
+            .find(|assignment| assignment.name.as_str() == name)
+            .map(|assignment| assignment.state.as_ref()),
+        git_attributes::Value::MacroAttributes(_) => None,
+    }
+}
+
+/// Return a copy of `list` with only the patterns that mention the attribute `name`.
+fn retain(list: &PatternList<Attributes>, name: &str) -> PatternList<Attributes> {
+    let mut list = list.clone();
+    list.patterns.retain(|mapping| state(&mapping.value, name).is_some());
+    list
+}
+
+/// Replace all `$Format:<format>$` placeholders in `data` with `format` expanded for `commit` with id `id`, like
+/// git does for files with the `export-subst` attribute.
+fn substitute(data: &[u8], id: &git_hash::oid, commit: &git_object::Commit) -> Vec<u8> {
+    const START: &[u8] = b"$Format:";
+    let mut out = Vec::with_capacity(data.len());
+    let mut rest = data;
+    while let Some(start) = rest.find(START) {
+        let format_start = start + START.len();
+        let end = match rest[format_start..].find_byte(b'$') {
+            Some(end) => format_start + end,
+            None => break,
+        };
+        out.extend_from_slice(&rest[..start]);
+        expand_format(rest[format_start..end].as_bstr(), id, commit, &mut out);
+        rest = &rest[end + 1..];
+    }
+    out.extend_from_slice(rest);
+    out
+}
+
+fn expand_format(format: &BStr, id: &git_hash::oid, commit: &git_object::Commit, out: &mut Vec<u8>) {
+    let short = |id: &git_hash::oid| id.to_hex_with_len(7).to_string();
+    let mut chars = format.iter().copied().peekable();
+    while let Some(byte) = chars.next() {
+        if byte != b'%' {
+            out.push(byte);
+            continue;
+        }
+        let mut placeholder = vec![b'%'];
+        placeholder.extend(chars.next());
+        if matches!(placeholder[1], b'a' | b'c') {
+            placeholder.extend(chars.next());
+        }
+        let (signature, field) = match placeholder[1] {
+            b'a' => (Some(&commit.author), placeholder.get(2).copied()),
+            b'c' => (Some(&commit.committer), placeholder.get(2).copied()),
+            _ => (None, None),
+        };
+        match (placeholder.as_slice(), signature, field) {
+            (b"%H", ..) => out.push_str(id.to_hex().to_string()),
+            (b"%h", ..) => out.push_str(short(id)),
+            (b"%T", ..) => out.push_str(commit.tree.to_hex().to_string()),
+            (b"%t", ..) => out.push_str(short(&commit.tree)),
+            (b"%P", ..) => out.push_str(
+                commit
+                    .parents
+                    .iter()
+                    .map(|id| id.to_hex().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            (b"%p", ..) => out.push_str(commit.parents.iter().map(|id| short(id)).collect::<Vec<_>>().join(" ")),
+            (b"%s", ..) => {
+                out.extend_from_slice(&git_object::commit::MessageRef::from_bytes(&commit.message).summary())
+            }
+            (b"%n", ..) => out.push(b'\n'),
+            (b"%%", ..) => out.push(b'%'),
+            (_, Some(signature), Some(b'n')) => out.extend_from_slice(&signature.name),
+            (_, Some(signature), Some(b'e')) => out.extend_from_slice(&signature.email),
+            (_, Some(signature), Some(b't')) => out.push_str(signature.time.seconds_since_unix_epoch.to_string()),
+            (_, Some(signature), Some(b'd')) => out.push_str(signature.time.format(git_date::time::format::DEFAULT)),
+            (_, Some(signature), Some(b'I')) => {
+                out.push_str(signature.time.format(git_date::time::format::ISO8601_STRICT))
+            }
+            (placeholder, ..) => out.extend_from_slice(placeholder),
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use git_repository as git;
+use git_repository::{
+    apply::{Error, Location, Options, Reject, Whitespace},
+    status::{Action, Change},
+};
+
+fn repo_rw() -> crate::Result<(git::Repository, tempfile::TempDir)> {
+    let tmp = git_testtools::scripted_fixture_repo_writable("make_apply_repo.sh")?;
+    Ok((git::open_opts(tmp.path().join("repo"), crate::restricted())?, tmp))
+}
+
+fn patch(tmp: &tempfile::TempDir, name: &str) -> crate::Result<Vec<u8>> {
+    Ok(std::fs::read(tmp.path().join(name))?)
+}
+
+fn read(repo: &git::Repository, path: &str) -> crate::Result<String> {
+    Ok(std::fs::read_to_string(repo.work_dir().expect("non-bare").join(path))?)
+}
+
+fn changes(repo: &git::Repository) -> crate::Result<Vec<Change>> {
+    let mut out = Vec::new();
+    repo.changes(|change| {
+        out.push(change);
+        Action::Continue
+    })?;
+    Ok(out)
+}
+
+fn modified_file() -> String {
+    (1..=20)
+        .map(|n| match n {
+            2 => "two\n".to_string(),
+            19 => "nineteen\n".to_string(),
+            n => format!("{}\n", n),
+        })
+        .collect()
+}
+
+#[test]
+fn worktree_only_by_default() -> crate::Result {
+    let (repo, tmp) = repo_rw()?;
+    let outcome = repo.apply(&patch(&tmp, "modify.patch")?, Options::default())?;
+    assert_eq!(outcome.paths, vec!["file"]);
+    assert!(outcome.rejects.is_empty());
+    assert_eq!(read(&repo, "file")?, modified_file());
+    assert_eq!(changes(&repo)?, vec![Change::Modified { path: "file".into() }]);
+    Ok(())
+}
+
+#[test]
+fn index_only_with_cached() -> crate::Result {
+    let (repo, tmp) = repo_rw()?;
+    repo.apply(
+        &patch(&tmp, "modify.patch")?,
+        Options {
+            location: Location::Index,
+            ..Default::default()
+        },
+    )?;
+    let expected: String = (1..=20).map(|n| format!("{}\n", n)).collect();
+    assert_eq!(read(&repo, "file")?, expected, "the worktree is untouched");
+    assert_eq!(
+        changes(&repo)?,
+        vec![
+            Change::Staged { path: "file".into() },
+            Change::Modified { path: "file".into() }
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn index_and_worktree_with_index() -> crate::Result {
+    let (repo, tmp) = repo_rw()?;
+    let options = Options {
+        location: Location::Both,
+        ..Default::default()
+    };
+    let outcome = repo.apply(&patch(&tmp, "changes.patch")?, options)?;
+    assert_eq!(outcome.paths, vec!["dir/renamed", "file", "keep", "new", "no-eol"]);
+    assert_eq!(read(&repo, "dir/renamed")?, "a\nb\nc\nd\n");
+    assert!(!repo.work_dir().expect("non-bare").join("dir/old").exists());
+    assert!(!repo.work_dir().expect("non-bare").join("keep").exists());
+    assert_eq!(read(&repo, "new")?, "new\n");
+    assert_eq!(read(&repo, "no-eol")?, "with newline\n");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(repo.work_dir().expect("non-bare").join("file"))?
+            .permissions()
+            .mode();
+        assert_ne!(mode & 0o111, 0, "the executable bit was set");
+    }
+    let changes = changes(&repo)?;
+    assert!(
+        changes.iter().all(|change| matches!(change, Change::Staged { .. })),
+        "index and worktree agree: {:?}",
+        changes
+    );
+    assert_eq!(changes.len(), 6, "dir/old, dir/renamed, file, keep, new, no-eol");
+
+    std::fs::write(repo.work_dir().expect("non-bare").join("new"), "changed\n")?;
+    let mut reversed = options;
+    reversed.reverse = true;
+    assert!(matches!(
+        repo.apply(&patch(&tmp, "changes.patch")?, reversed),
+        Err(Error::DoesNotMatchIndex { path }) if path == "new"
+    ));
+    Ok(())
+}
+
+#[test]
+fn reverse_undoes_a_patch() -> crate::Result {
+    let (repo, tmp) = repo_rw()?;
+    let changes_patch = patch(&tmp, "changes.patch")?;
+    repo.apply(&changes_patch, Options::default())?;
+    assert!(repo.is_dirty()?);
+    repo.apply(
+        &changes_patch,
+        Options {
+            reverse: true,
+            ..Default::default()
+        },
+    )?;
+    assert!(!repo.is_dirty()?);
+    assert!(!repo.work_dir().expect("non-bare").join("dir/renamed").exists());
+    assert_eq!(read(&repo, "no-eol")?, "no newline");
+    Ok(())
+}
+
+#[test]
+fn hunks_that_dont_apply_fail_the_patch_or_are_rejected() -> crate::Result {
+    let (repo, tmp) = repo_rw()?;
+    let original: String = (1..=20).map(|n| format!("{}\n", n)).collect();
+    let conflicting = original.replace("3\n", "three\n");
+    std::fs::write(repo.work_dir().expect("non-bare").join("file"), &conflicting)?;
+
+    let err = repo
+        .apply(&patch(&tmp, "modify.patch")?, Options::default())
+        .unwrap_err();
+    assert!(matches!(err, Error::DoesNotApply { hunk: 1, .. }));
+    assert_eq!(read(&repo, "file")?, conflicting, "nothing was changed");
+
+    let mut patches = patch(&tmp, "modify.patch")?;
+    patches.extend(patch(&tmp, "whitespace.patch")?);
+    let outcome = repo.apply(
+        &patches,
+        Options {
+            reject: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        outcome.rejects,
+        vec![
+            Reject {
+                path: "file".into(),
+                hunks: vec![1]
+            },
+            Reject {
+                path: "file".into(),
+                hunks: vec![1]
+            }
+        ],
+        "the second patch doesn't apply as the first one changed its context"
+    );
+    assert_eq!(read(&repo, "file")?, conflicting.replace("19\n", "nineteen\n"));
+    assert_eq!(
+        read(&repo, "file.rej")?,
+        "diff a/file b/file\t(rejected hunks)\n@@ -1,5 +1,5 @@\n 1\n-2\n+two\n 3\n 4\n 5\n\
+         diff a/file b/file\t(rejected hunks)\n@@ -18,3 +18,5 @@\n 18\n 19\n 20\n+trailing  \n+clean\n",
+        "rejects of all patches to the same file are kept"
+    );
+    Ok(())
+}
+
+#[test]
+fn hunks_are_found_if_lines_were_added_before_them() -> crate::Result {
+    let (repo, tmp) = repo_rw()?;
+    let original: String = (1..=20).map(|n| format!("{}\n", n)).collect();
+    let shifted = original.replace("10\n", "10\n10a\n10b\n");
+    std::fs::write(repo.work_dir().expect("non-bare").join("file"), &shifted)?;
+    repo.apply(&patch(&tmp, "modify.patch")?, Options::default())?;
+    assert_eq!(read(&repo, "file")?, modified_file().replace("10\n", "10\n10a\n10b\n"));
+    Ok(())
+}
+
+#[test]
+fn whitespace_errors() -> crate::Result {
+    let (repo, tmp) = repo_rw()?;
+    let whitespace = patch(&tmp, "whitespace.patch")?;
+    let with = |whitespace| Options {
+        whitespace,
+        ..Default::default()
+    };
+
+    assert!(matches!(
+        repo.apply(&whitespace, with(Whitespace::Error)),
+        Err(Error::Whitespace { count: 1 })
+    ));
+    assert!(!repo.is_dirty()?);
+
+    let outcome = repo.apply(&whitespace, with(Whitespace::Fix))?;
+    assert_eq!(outcome.whitespace_errors, 1);
+    assert!(read(&repo, "file")?.ends_with("20\ntrailing\nclean\n"));
+
+    let outcome = repo.apply(
+        &whitespace,
+        Options {
+            reverse: true,
+            ..with(Whitespace::Nowarn)
+        },
+    );
+    assert!(
+        matches!(outcome, Err(Error::DoesNotApply { .. })),
+        "the fixed line doesn't match the original one anymore"
+    );
+
+    let (repo, _tmp) = repo_rw()?;
+    let outcome = repo.apply(&whitespace, with(Whitespace::Warn))?;
+    assert_eq!(outcome.whitespace_errors, 1);
+    assert!(read(&repo, "file")?.ends_with("20\ntrailing  \nclean\n"));
+    Ok(())
+}
+
+#[test]
+fn paths_are_validated() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let patch = "diff --git a/.git/config b/.git/config\nnew file mode 100644\n--- /dev/null\n+++ b/.git/config\n@@ -0,0 +1 @@\n+x\n";
+    assert!(matches!(
+        repo.apply(patch.as_bytes(), Options::default()),
+        Err(Error::InvalidPath { .. })
+    ));
+    Ok(())
+}
+
+mod patch {
+    use git_repository::apply::patch::{parse, Line};
+
+    #[test]
+    fn quoted_names_and_missing_newlines() {
+        let input = "some commit message\n\ndiff --git \"a/with\\ttab\" \"b/with\\ttab\"\nindex 1..2 100644\n--- \"a/with\\ttab\"\n+++ \"b/with\\ttab\"\n@@ -1 +1 @@ section\n-old\n\\ No newline at end of file\n+new\n\\ No newline at end of file\n-- \n2.39.5\n";
+        let files = parse(input.as_bytes()).expect("valid");
+        assert_eq!(files.len(), 1);
+        let file = &files[0];
+        assert_eq!(file.old_path.as_ref().expect("set"), "with\ttab");
+        assert_eq!(file.new_path, file.old_path);
+        assert_eq!(file.hunks[0].section, "section");
+        assert_eq!(
+            file.hunks[0].lines,
+            vec![Line::Removed("old".into()), Line::Added("new".into())]
+        );
+    }
+
+    #[test]
+    fn empty_input_is_an_error() {
+        assert!(parse(b"just text\n").is_err());
+    }
+}
@@ -0,0 +1,216 @@
+#[cfg(feature = "archive")]
+mod write {
+    use std::io::Read;
+
+    use git_repository as git;
+    use git_testtools::scripted_fixture_repo_read_only;
+
+    fn repo() -> crate::Result<git::Repository> {
+        let dir = scripted_fixture_repo_read_only("make_archive_repo.sh")?;
+        Ok(git::open_opts(dir, git::open::Options::isolated())?)
+    }
+
+    struct Entry {
+        path: String,
+        mode: u32,
+        mtime: u64,
+        /// The content of files, or the target of links.
+        data: Vec<u8>,
+    }
+
+    fn tar_entries(archive: &[u8]) -> crate::Result<Vec<Entry>> {
+        let mut out = Vec::new();
+        for entry in tar::Archive::new(archive).entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.to_string_lossy().into_owned();
+            let mut data = Vec::new();
+            match entry.link_name()? {
+                Some(target) => data.extend_from_slice(target.to_string_lossy().as_bytes()),
+                None => {
+                    entry.read_to_end(&mut data)?;
+                }
+            }
+            out.push(Entry {
+                path,
+                mode: entry.header().mode()?,
+                mtime: entry.header().mtime()?,
+                data,
+            });
+        }
+        Ok(out)
+    }
+
+    #[test]
+    fn tar_from_commit_honors_export_attributes() -> crate::Result {
+        let repo = repo()?;
+        let commit = repo.head_commit()?;
+        let mut buf = Vec::new();
+        let outcome = repo.archive(
+            commit.id,
+            &mut buf,
+            git::archive::Options {
+                prefix: Some("project/".into()),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(outcome.commit_id, Some(commit.id));
+        assert_eq!(outcome.tree_id, commit.tree_id()?.detach());
+        assert_eq!(outcome.entries, 9);
+
+        let entries = tar_entries(&buf)?;
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.path.as_str(), entry.mode))
+                .collect::<Vec<_>>(),
+            vec![
+                ("project/", 0o775),
+                ("project/.gitattributes", 0o664),
+                ("project/a", 0o664),
+                ("project/dir/", 0o775),
+                ("project/dir/.gitattributes", 0o664),
+                ("project/dir/b", 0o664),
+                ("project/link", 0o777),
+                ("project/run.sh", 0o775),
+                ("project/version", 0o664),
+            ],
+            "ignored entries are skipped, and directories come before their content"
+        );
+        let commit_time = commit.time()?.seconds_since_unix_epoch as u64;
+        assert!(entries.iter().all(|entry| entry.mtime == commit_time));
+        assert_eq!(entries[6].data, b"a", "symlinks point to their target");
+        assert_eq!(
+            entries[8].data,
+            format!(
+                "commit: {}, subject: the first commit, author: author <author@example.com>\n",
+                commit.id
+            )
+            .as_bytes()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tar_gz_from_tree_does_not_substitute() -> crate::Result {
+        let repo = repo()?;
+        let tree_id = repo.head_commit()?.tree_id()?.detach();
+        let mut buf = Vec::new();
+        let outcome = repo.archive(
+            tree_id,
+            &mut buf,
+            git::archive::Options {
+                format: git::archive::Format::TarGz,
+                modification_time: Some(42),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(outcome.commit_id, None);
+        assert_eq!(outcome.entries, 8, "no prefix directory");
+
+        let mut tar = Vec::new();
+        flate2::read::GzDecoder::new(buf.as_slice()).read_to_end(&mut tar)?;
+        let entries = tar_entries(&tar)?;
+        assert!(entries.iter().all(|entry| entry.mtime == 42));
+        let version = entries.last().expect("present");
+        assert_eq!(version.path, "version");
+        assert!(
+            version.data.starts_with(b"commit: $Format:%H$"),
+            "only commits can be substituted"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn zip() -> crate::Result {
+        let repo = repo()?;
+        let mut buf = Vec::new();
+        repo.archive(
+            repo.head_id()?,
+            &mut buf,
+            git::archive::Options {
+                format: git::archive::Format::Zip,
+                prefix: Some("p/".into()),
+                ..Default::default()
+            },
+        )?;
+
+        let mut zip = zip::ZipArchive::new(std::io::Cursor::new(buf))?;
+        let mut names = zip.file_names().map(ToOwned::to_owned).collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "p/",
+                "p/.gitattributes",
+                "p/a",
+                "p/dir/",
+                "p/dir/.gitattributes",
+                "p/dir/b",
+                "p/link",
+                "p/run.sh",
+                "p/version"
+            ]
+        );
+        let mut file = zip.by_name("p/run.sh")?;
+        assert_eq!(file.unix_mode(), Some(0o100775));
+        let mut data = String::new();
+        file.read_to_string(&mut data)?;
+        assert_eq!(data, "#!/bin/sh\n");
+        Ok(())
+    }
+}
+
+#[cfg(feature = "worktree-stream")]
+mod stream {
+    use std::io::Read;
+
+    use git_repository as git;
+    use git_repository::objs::tree::EntryMode;
+    use git_testtools::scripted_fixture_repo_read_only;
+
+    #[test]
+    fn entries_are_ordered_and_honor_export_attributes() -> crate::Result {
+        let dir = scripted_fixture_repo_read_only("make_archive_repo.sh")?;
+        let repo = git::open_opts(dir, git::open::Options::isolated())?;
+        let commit = repo.head_commit()?;
+        let mut stream = repo.worktree_stream(commit.id)?;
+        assert_eq!(stream.commit_id(), Some(commit.id));
+        assert_eq!(stream.tree_id(), commit.tree_id()?.detach());
+
+        let mut entries = Vec::new();
+        while let Some(entry) = stream.next_entry()? {
+            let mut data = Vec::new();
+            entry.reader().read_to_end(&mut data)?;
+            assert_eq!(data, entry.data());
+            entries.push((entry.path.to_string(), entry.mode, data));
+        }
+        assert_eq!(
+            entries
+                .iter()
+                .map(|(path, mode, _)| (path.as_str(), *mode))
+                .collect::<Vec<_>>(),
+            vec![
+                (".gitattributes", EntryMode::Blob),
+                ("a", EntryMode::Blob),
+                ("dir", EntryMode::Tree),
+                ("dir/.gitattributes", EntryMode::Blob),
+                ("dir/b", EntryMode::Blob),
+                ("link", EntryMode::Link),
+                ("run.sh", EntryMode::BlobExecutable),
+                ("version", EntryMode::Blob),
+            ],
+            "ignored entries are skipped, and directories come before their content"
+        );
+        assert!(entries[2].2.is_empty(), "directories have no data");
+        assert_eq!(entries[5].2, b"a", "symlinks provide their target");
+        assert_eq!(
+            entries[7].2,
+            format!(
+                "commit: {}, subject: the first commit, author: author <author@example.com>\n",
+                commit.id
+            )
+            .as_bytes()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,273 @@
+use std::sync::atomic::AtomicBool;
+
+use git_features::progress;
+use git_repository as git;
+use git_repository::bundle::{self, Header, Version};
+
+fn repo() -> crate::Result<(git::Repository, std::path::PathBuf)> {
+    let repo_path = git_testtools::scripted_fixture_repo_read_only("make_bundle_repo.sh")?;
+    Ok((git::open_opts(&repo_path, crate::restricted())?, repo_path))
+}
+
+fn id(repo: &git::Repository, spec: &str) -> git::ObjectId {
+    repo.rev_parse(spec)
+        .expect("valid spec")
+        .single()
+        .expect("single object")
+        .detach()
+}
+
+fn create(
+    repo: &git::Repository,
+    refs: &[&str],
+    exclude: &[&str],
+) -> Result<(bundle::create::Outcome, Vec<u8>), bundle::create::Error> {
+    let mut out = Vec::new();
+    let outcome = repo.create_bundle(
+        refs.iter().copied(),
+        exclude.iter().map(|spec| id(repo, spec)),
+        &mut out,
+        progress::Discard,
+        &AtomicBool::default(),
+    )?;
+    Ok((outcome, out))
+}
+
+fn unbundle(repo: &git::Repository, data: &[u8]) -> Result<bundle::unbundle::Outcome, bundle::unbundle::Error> {
+    repo.unbundle(data, progress::Discard, &AtomicBool::default())
+}
+
+mod header {
+    use std::convert::TryInto;
+
+    use super::*;
+
+    #[test]
+    fn decode_bundles_written_by_git() -> crate::Result {
+        let (repo, path) = repo()?;
+        let header = Header::from_read(std::io::BufReader::new(std::fs::File::open(path.join("all.bundle"))?))?;
+        assert_eq!(header.version, Version::V2);
+        assert!(header.capabilities.is_empty());
+        assert!(header.prerequisites.is_empty());
+        assert_eq!(
+            header.refs,
+            vec![
+                bundle::Ref {
+                    id: id(&repo, "main"),
+                    name: "refs/heads/main".try_into()?,
+                },
+                bundle::Ref {
+                    id: repo.find_reference("v1")?.id().detach(),
+                    name: "refs/tags/v1".try_into()?,
+                }
+            ]
+        );
+
+        let header = Header::from_read(std::io::BufReader::new(std::fs::File::open(
+            path.join("incremental.bundle"),
+        )?))?;
+        assert_eq!(
+            header.prerequisites,
+            vec![bundle::Prerequisite {
+                id: id(&repo, "main~1"),
+                comment: "c2".into(),
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn v3_with_capabilities_roundtrips() -> crate::Result {
+        let input = b"# v3 git bundle\n@object-format=sha1\n@filter=blob:none\n-1111111111111111111111111111111111111111 subject\n2222222222222222222222222222222222222222 refs/heads/main\n\nPACK";
+        let mut read = &input[..];
+        let header = Header::from_read(&mut read)?;
+        assert_eq!(header.version, Version::V3);
+        assert_eq!(
+            header.capabilities,
+            vec![
+                ("object-format".into(), Some("sha1".into())),
+                ("filter".into(), Some("blob:none".into()))
+            ]
+        );
+        assert_eq!(read, b"PACK", "the reader is positioned at the pack");
+
+        let mut out = Vec::new();
+        header.write_to(&mut out)?;
+        assert_eq!(out, &input[..input.len() - 4]);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_input() {
+        for input in [
+            &b""[..],
+            b"# v4 git bundle\n\n",
+            b"# v2 git bundle\n2222222222222222222222222222222222222222 refs/heads/main\n",
+            b"# v2 git bundle\n@object-format=sha1\n\n",
+            b"# v3 git bundle\n@object-format=sha256\n\n",
+            b"# v2 git bundle\n2222222222222222222222222222222222222222\n\n",
+            b"# v2 git bundle\n-22222\n\n",
+        ] {
+            assert!(Header::from_read(input).is_err(), "{:?} should fail", input);
+        }
+    }
+}
+
+mod create {
+    use super::*;
+
+    #[test]
+    fn everything_and_unbundle_into_empty_repository() -> crate::Result {
+        let (repo, _) = repo()?;
+        let (outcome, data) = create(&repo, &["main", "refs/tags/v1"], &[])?;
+        assert_eq!(
+            outcome.num_objects,
+            3 /* commits */ + 4 /* trees */ + 3 /* blobs */ + 1 /* tag */
+        );
+        assert!(outcome.header.prerequisites.is_empty());
+        assert_eq!(
+            outcome.header.refs.iter().map(|r| r.name.as_bstr()).collect::<Vec<_>>(),
+            ["refs/heads/main", "refs/tags/v1"]
+        );
+        assert_eq!(
+            Header::from_read(&data[..])?,
+            outcome.header,
+            "the header is written as returned"
+        );
+
+        let tmp = tempfile::tempdir()?;
+        let other = git::init_bare(&tmp)?;
+        let unbundled = unbundle(&other, &data)?;
+        assert_eq!(unbundled.header, outcome.header);
+        assert_eq!(unbundled.write.index.num_objects, outcome.num_objects);
+        for spec in ["main", "main~1", "main~2", "main:dir/file", "main~1:file"] {
+            assert!(other.find_object(id(&repo, spec)).is_ok(), "{} was unbundled", spec);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn with_exclusions_yields_prerequisites() -> crate::Result {
+        let (repo, _) = repo()?;
+        let (outcome, data) = create(&repo, &["main", "v1"], &["main~1"])?;
+        assert_eq!(
+            outcome.header.prerequisites,
+            vec![bundle::Prerequisite {
+                id: id(&repo, "main~1"),
+                comment: "c2".into()
+            }]
+        );
+        assert_eq!(
+            outcome.header.refs.iter().map(|r| r.name.as_bstr()).collect::<Vec<_>>(),
+            ["refs/heads/main"],
+            "refs pointing to excluded commits are omitted"
+        );
+
+        let tmp = tempfile::tempdir()?;
+        let other = git::init_bare(&tmp)?;
+        match unbundle(&other, &data).unwrap_err() {
+            bundle::unbundle::Error::Verify(bundle::verify::Error::MissingPrerequisites { ids }) => {
+                assert_eq!(ids, vec![id(&repo, "main~1")])
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+
+        let (_, everything) = create(&repo, &["v1"], &[])?;
+        unbundle(&other, &everything)?;
+        unbundle(&other, &data)?;
+        assert!(other.find_object(id(&repo, "main:dir/file")).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn nothing_to_bundle_is_an_error() -> crate::Result {
+        let (repo, _) = repo()?;
+        assert!(matches!(
+            create(&repo, &["main"], &["main"]),
+            Err(bundle::create::Error::Empty)
+        ));
+        Ok(())
+    }
+}
+
+#[test]
+fn unbundle_bundle_written_by_git() -> crate::Result {
+    let (repo, path) = repo()?;
+    let tmp = tempfile::tempdir()?;
+    let other = git::init_bare(&tmp)?;
+    let outcome = unbundle(&other, &std::fs::read(path.join("all.bundle"))?)?;
+    assert_eq!(outcome.header.refs.len(), 2);
+    assert!(other.find_object(id(&repo, "main:dir/file")).is_ok());
+    Ok(())
+}
+
+#[cfg(feature = "blocking-network-client")]
+mod remote {
+    use git_repository::remote::Direction::Fetch;
+
+    use super::*;
+
+    fn bundle_url(path: &std::path::Path, name: &str) -> String {
+        path.join(name).to_str().expect("valid UTF-8").to_owned()
+    }
+
+    #[test]
+    fn clone_points_head_to_the_branch_matching_the_bundled_head() -> crate::Result {
+        let (repo, path) = repo()?;
+        let tmp = tempfile::tempdir()?;
+        let (clone, outcome) = git::prepare_clone(bundle_url(&path, "with-head.bundle").as_str(), tmp.path())?
+            .fetch_then_checkout(progress::Discard, &AtomicBool::default())?;
+
+        assert_eq!(
+            outcome.head_branch.as_ref().map(|name| name.as_bstr()),
+            Some("refs/heads/main".into()),
+            "bundles have no symbolic refs, so it's the branch with the same id as HEAD"
+        );
+        assert_eq!(clone.head_id()?, id(&repo, "main"));
+        assert_eq!(
+            clone.find_reference("refs/remotes/origin/main")?.id(),
+            id(&repo, "main")
+        );
+        assert_eq!(std::fs::read(tmp.path().join("dir").join("file"))?, b"3\n");
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_into_repository_with_all_prerequisites() -> crate::Result {
+        let (repo, path) = repo()?;
+        let tmp = tempfile::tempdir()?;
+        let (clone, _) = git::prepare_clone(bundle_url(&path, "base.bundle").as_str(), tmp.path())?
+            .bare(true)
+            .fetch_then_checkout(progress::Discard, &AtomicBool::default())?;
+        assert_eq!(clone.find_reference("refs/heads/base")?.id(), id(&repo, "main~1"));
+
+        let outcome = clone
+            .remote_at(bundle_url(&path, "incremental.bundle").as_str())?
+            .with_refspec("+refs/heads/main:refs/remotes/bundle/main", Fetch)?
+            .connect(Fetch, progress::Discard)?
+            .fetch(&AtomicBool::default(), Default::default())?;
+        assert_eq!(
+            outcome.pack.expect("new objects").index.num_objects,
+            1 /* commit */ + 2 /* trees */ + 1, /* blob */
+            "only the objects of the last commit are in the incremental bundle"
+        );
+        assert_eq!(
+            clone.find_reference("refs/remotes/bundle/main")?.id(),
+            id(&repo, "main")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn connecting_without_prerequisites_fails() -> crate::Result {
+        let (_repo, path) = repo()?;
+        let tmp = tempfile::tempdir()?;
+        let repo = git::init_bare(&tmp)?;
+        let remote = repo.remote_at(bundle_url(&path, "incremental.bundle").as_str())?;
+        assert!(matches!(
+            remote.connect(Fetch, progress::Discard),
+            Err(git::remote::connect::Error::BundlePrerequisites(_))
+        ));
+        Ok(())
+    }
+}
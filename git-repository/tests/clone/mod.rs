@@ -0,0 +1,144 @@
+#[cfg(feature = "blocking-network-client")]
+mod blocking_io {
+    use std::{num::NonZeroU32, sync::atomic::AtomicBool};
+
+    use git_features::progress;
+    use git_repository as git;
+    use git_testtools::scripted_fixture_repo_read_only;
+
+    fn remote() -> crate::Result<std::path::PathBuf> {
+        Ok(scripted_fixture_repo_read_only("make_remote_repos.sh")?.join("base"))
+    }
+
+    #[test]
+    fn fetch_then_checkout() -> crate::Result {
+        let remote_dir = remote()?;
+        let remote_repo = git::open_opts(&remote_dir, git::open::Options::isolated())?;
+        let tmp = tempfile::TempDir::new()?;
+        let (repo, outcome) = git::prepare_clone(remote_dir.to_str().expect("valid UTF-8"), tmp.path().join("clone"))?
+            .fetch_then_checkout(progress::Discard, &AtomicBool::default())?;
+
+        assert!(!repo.is_bare());
+        assert!(outcome.pack.is_some(), "a pack was received");
+        let remote = repo.find_remote("origin")?;
+        assert_eq!(
+            remote.url(git::remote::Direction::Fetch).map(|url| url.path.clone()),
+            Some(remote_dir.to_str().expect("valid UTF-8").into())
+        );
+
+        let remote_head = remote_repo.head()?;
+        let remote_branch = remote_head.referent_name().expect("on a branch").to_owned();
+        assert_eq!(outcome.head_branch.as_ref(), Some(&remote_branch));
+        let head = repo.head()?;
+        assert_eq!(
+            head.referent_name(),
+            Some(remote_branch.as_ref()),
+            "HEAD points to the same branch"
+        );
+        assert_eq!(repo.head_id()?, remote_repo.head_id()?);
+        for remote_ref in remote_repo.references()?.local_branches()? {
+            let remote_ref = remote_ref.expect("valid reference");
+            let tracking = format!("refs/remotes/origin/{}", remote_ref.name().shorten());
+            assert_eq!(
+                repo.find_reference(tracking.as_str())?.id(),
+                remote_ref.id(),
+                "every branch has a remote tracking branch"
+            );
+        }
+
+        let config = repo.config_snapshot();
+        let short_name = remote_branch.shorten().to_string();
+        assert_eq!(
+            config.string(&format!("branch.{}.remote", short_name)).as_deref(),
+            Some("origin".into())
+        );
+        assert_eq!(
+            config.string(&format!("branch.{}.merge", short_name)).as_deref(),
+            Some(remote_branch.as_bstr())
+        );
+
+        let checkout = outcome.checkout.expect("non-bare clones are checked out");
+        assert_eq!(checkout.errors.len(), 0);
+        assert_eq!(
+            std::fs::read(repo.work_dir().expect("present").join("file"))?,
+            std::fs::read(remote_dir.join("file"))?,
+            "the files of HEAD are written"
+        );
+        let index = git::index::File::at(repo.index_path(), Default::default())?;
+        assert_eq!(index.entries().len(), 1, "the index matches the tree of HEAD");
+        Ok(())
+    }
+
+    #[test]
+    fn bare_clones_mirror_branches_without_checkout() -> crate::Result {
+        let remote_dir = remote()?;
+        let remote_repo = git::open_opts(&remote_dir, git::open::Options::isolated())?;
+        let tmp = tempfile::TempDir::new()?;
+        let (repo, outcome) = git::prepare_clone(remote_dir.to_str().expect("valid UTF-8"), tmp.path())?
+            .bare(true)
+            .fetch_then_checkout(progress::Discard, &AtomicBool::default())?;
+
+        assert!(repo.is_bare());
+        assert!(outcome.checkout.is_none());
+        assert!(!repo.index_path().exists());
+        for remote_ref in remote_repo.references()?.local_branches()? {
+            let remote_ref = remote_ref.expect("valid reference");
+            assert_eq!(repo.find_reference(remote_ref.name())?.id(), remote_ref.id());
+        }
+        assert_eq!(repo.references()?.remote_branches()?.count(), 0);
+        assert_eq!(
+            repo.head()?.referent_name(),
+            remote_repo.head()?.referent_name(),
+            "HEAD points to the same branch"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn depth_creates_shallow_clones() -> crate::Result {
+        let remote_dir = scripted_fixture_repo_read_only("make_bundle_repo.sh")?;
+        let tmp = tempfile::TempDir::new()?;
+        let url = format!("file://{}", remote_dir.to_str().expect("valid UTF-8"));
+        let (repo, _outcome) = git::prepare_clone(url.as_str(), tmp.path().join("clone"))?
+            .with_depth(NonZeroU32::new(1))
+            .fetch_then_checkout(progress::Discard, &AtomicBool::default())?;
+
+        let shallow = std::fs::read_to_string(repo.git_dir().join("shallow"))?;
+        let head_id = repo.head_id()?.detach().to_string();
+        assert!(shallow.lines().any(|line| line == head_id), "HEAD is a shallow commit");
+        assert_eq!(repo.head_commit()?.parent_ids().count(), 1);
+        assert!(
+            repo.try_find_object(repo.head_commit()?.parent_ids().next().expect("one parent"))?
+                .is_none(),
+            "the parent wasn't fetched"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "sha256")]
+    fn the_object_format_of_the_remote_is_adopted() -> crate::Result {
+        let remote_dir = scripted_fixture_repo_read_only("make_sha256_repo.sh")?;
+        let remote_repo = git::open_opts(&remote_dir, git::open::Options::isolated())?;
+        let tmp = tempfile::TempDir::new()?;
+        let (repo, outcome) = git::prepare_clone(remote_dir.to_str().expect("valid UTF-8"), tmp.path().join("clone"))?
+            .fetch_then_checkout(progress::Discard, &AtomicBool::default())?;
+
+        assert_eq!(repo.object_hash(), git::hash::Kind::Sha256);
+        assert_eq!(
+            repo.config_snapshot().string("extensions.objectFormat").as_deref(),
+            Some("sha256".into())
+        );
+        assert_eq!(repo.head_id()?, remote_repo.head_id()?);
+        assert_eq!(
+            outcome.checkout.expect("non-bare clones are checked out").errors.len(),
+            0
+        );
+        assert_eq!(
+            std::fs::read(repo.work_dir().expect("present").join("dir/file"))?,
+            b"content\n",
+            "trees with SHA-256 ids are checked out"
+        );
+        Ok(())
+    }
+}
@@ -12,4 +12,18 @@ mod describe {
             assert_eq!(describe.format().unwrap().to_string(), "v2", "{:?}", filter);
         }
     }
+
+    #[test]
+    fn many_commits_can_be_described_with_a_cache() -> crate::Result {
+        let repo = named_repo("make_commit_describe_multiple_tags.sh")?;
+        let head = repo.head_commit()?;
+        let mut cache = head.describe().names(AnnotatedTags).id_as_fallback(true).into_cache()?;
+        let formats = head
+            .ancestors()
+            .all()?
+            .map(|id| -> crate::Result<_> { Ok(cache.try_format(id?)?.expect("fallback").to_string()) })
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(formats, ["v2", "v0"]);
+        Ok(())
+    }
 }
@@ -0,0 +1,28 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  git checkout -q -b main
+  seq 1 20 > file
+  mkdir dir && printf 'a\nb\nc\n' > dir/old
+  echo keep > keep
+  printf 'no newline' > no-eol
+  git add . && git commit -q -m base
+
+  sed -e 's/^2$/two/' -e 's/^19$/nineteen/' file > file.tmp && mv file.tmp file
+  git diff > ../modify.patch
+
+  git reset -q --hard
+  echo new > new && git add --intent-to-add new
+  git rm -q keep
+  git mv dir/old dir/renamed && printf 'a\nb\nc\nd\n' > dir/renamed
+  chmod +x file
+  printf 'with newline\n' > no-eol
+  git add -A && git diff --cached -M > ../changes.patch
+
+  git reset -q --hard
+  printf 'trailing  \nclean\n' >> file
+  git diff > ../whitespace.patch
+  git checkout -q file
+)
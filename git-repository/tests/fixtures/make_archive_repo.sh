@@ -0,0 +1,18 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo a > a
+echo '#!/bin/sh' > run.sh && chmod +x run.sh
+ln -s a link
+mkdir -p dir/ignored-dir && echo b > dir/b && echo c > dir/ignored-dir/c && echo d > dir/ignored
+echo 'commit: $Format:%H$, subject: $Format:%s$, author: $Format:%an <%ae>$' > version
+cat <<EOF2 > .gitattributes
+version export-subst
+ignored-dir export-ignore
+EOF2
+echo 'ignored export-ignore' > dir/.gitattributes
+
+git add . && git commit -q -m "the first commit"
@@ -0,0 +1,18 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo 1 > file && git add file && git commit -q -m c1
+echo 2 > file && git add file && git commit -q -m c2
+git tag -m "the first release" v1
+mkdir dir && echo 3 > dir/file && git add dir && git commit -q -m c3
+
+git bundle create -q all.bundle main v1
+git bundle create -q incremental.bundle main ^main~1
+git bundle create -q with-head.bundle HEAD main v1
+
+git branch base main~1
+git bundle create -q base.bundle base
+git branch -q -D base
@@ -0,0 +1,14 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+printf 'a\nb\n' > plain
+printf 'hello\n' > file.upper
+echo "*.upper filter=upper" > .gitattributes
+git add -A
+git commit -q -m "Commit"
+
+git config core.autocrlf true
+git config filter.upper.smudge "tr a-z A-Z"
+git config filter.upper.required true
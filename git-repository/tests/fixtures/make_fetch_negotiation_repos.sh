@@ -0,0 +1,49 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function tick () {
+  if test -z "${tick+set}"; then
+    tick=1112911993
+  else
+    tick=$((tick + 60))
+  fi
+  export GIT_COMMITTER_DATE="$tick -0700"
+  export GIT_AUTHOR_DATE="$tick -0700"
+}
+
+function commits () {
+  local count=${1:?} prefix=${2:?}
+  for i in $(seq "$count"); do
+    tick
+    git commit -q --allow-empty -m "$prefix $i"
+  done
+}
+
+git init -q server
+(cd server
+  git checkout -q -b main
+  commits 20 base
+)
+
+git clone -q server client
+(cd client
+  git checkout -q -b feature
+  commits 60 feature
+  git checkout -q -b topic main~5
+  commits 10 topic
+  git checkout -q main
+  commits 40 local
+)
+
+(cd server
+  commits 5 remote
+)
+
+# record how git negotiates with each algorithm, without altering the client
+for algorithm in noop consecutive skipping; do
+  cp -R client "client-$algorithm"
+  (cd "client-$algorithm"
+    GIT_TRACE_PACKET="$PWD/../$algorithm.trace" git -c protocol.version=2 -c fetch.negotiationAlgorithm="$algorithm" fetch -q --no-tags origin main
+  )
+  rm -Rf "client-$algorithm"
+done
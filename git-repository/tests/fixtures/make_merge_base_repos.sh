@@ -0,0 +1,51 @@
+#!/bin/bash
+set -eu -o pipefail
+
+tick=0
+function commit() {
+  tick=$((tick + 1))
+  GIT_AUTHOR_DATE="2000-01-01 00:00:$(printf %02d $tick) +0000" GIT_COMMITTER_DATE="2000-01-01 00:00:$(printf %02d $tick) +0000" \
+    git commit -q --allow-empty -m "$1" "${@:2}"
+  git tag "$1"
+}
+
+function merge() {
+  tick=$((tick + 1))
+  GIT_AUTHOR_DATE="2000-01-01 00:00:$(printf %02d $tick) +0000" GIT_COMMITTER_DATE="2000-01-01 00:00:$(printf %02d $tick) +0000" \
+    git merge -q --no-ff -m "$1" "${@:2}" >/dev/null
+  git tag "$1"
+}
+
+git init -q without-commit-graph
+(cd without-commit-graph
+  git checkout -q -b main
+  commit root
+  commit m1
+  commit m2
+
+  git checkout -q -b branch-a root
+  commit a1
+  git checkout -q -b branch-b root
+  commit b1
+  # criss-cross merges, both a1 and b1 are best common ancestors
+  git checkout -q branch-a
+  merge a2 b1
+  git checkout -q branch-b
+  merge b2 a1
+
+  for branch in c1 c2 c3; do
+    git checkout -q -b branch-$branch root
+    commit $branch
+  done
+  git checkout -q branch-c1
+  merge octopus c2 c3
+
+  git checkout -q --orphan branch-unrelated
+  commit unrelated
+  git checkout -q main
+)
+
+cp -R without-commit-graph with-commit-graph
+(cd with-commit-graph
+  git commit-graph write --no-progress --reachable
+)
@@ -0,0 +1,15 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q plain
+(cd plain
+  git config merge.theirs.driver 'cat %B > %A'
+  git config merge.theirs.name 'always take theirs'
+  git config merge.theirs.recursive binary
+  git config merge.fails.driver 'echo %P >> %A; exit 1'
+)
+
+git init -q with-default
+(cd with-default
+  git config merge.default union
+)
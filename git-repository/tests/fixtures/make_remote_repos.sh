@@ -171,3 +171,22 @@ EOF
     git remote get-url origin --push
   } > baseline.git
 )
+
+git init --bare push-url-rewriting
+(cd push-url-rewriting
+
+  git remote add origin https://github.com/foobar/gitoxide
+  cat <<EOF >> config
+
+[url "ssh://git@github.com/"]
+  pushInsteadOf = https://github.com/
+
+[url "https://github.com/byron/"]
+  insteadOf = https://github.com/foobar/
+EOF
+
+  {
+    git remote get-url origin
+    git remote get-url origin --push
+  } > baseline.git
+)
@@ -0,0 +1,16 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q with-replacements
+(cd with-replacements
+  git checkout -q -b main
+  echo 1 > file && git add file && git commit -q -m c1
+  echo 2 > file && git commit -q -am c2
+  echo 3 > file && git commit -q -am c3
+
+  git replace --graft HEAD~1
+  git replace HEAD:file "$(echo replaced | git hash-object -w --stdin)"
+)
+
+cp -R with-replacements disabled-by-config
+git -C disabled-by-config config core.useReplaceRefs false
@@ -0,0 +1,15 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q base
+(cd base
+  git checkout -q -b main
+  echo 1 > file && git add file && git commit -q -m c1
+  git tag -m "the first release" v1
+  echo 2 > file && git add file && git commit -q -m c2
+  git branch other
+  mkdir dir && echo 3 > dir/file && git add dir && git commit -q -m c3
+)
+
+git clone -q --bare base bare.git
+git init -q --bare empty.git
@@ -0,0 +1,9 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q --object-format=sha256
+
+mkdir dir
+echo content > dir/file
+git add -A
+git commit -q -m "Commit"
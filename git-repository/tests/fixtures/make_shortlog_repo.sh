@@ -0,0 +1,22 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function commit() {
+  local author=$1 email=$2
+  shift 2
+  GIT_AUTHOR_NAME="$author" GIT_AUTHOR_EMAIL="$email" git commit -q --allow-empty "$@"
+}
+
+git init -q
+git checkout -q -b main
+
+echo "Alice <alice@example.com> <alice@old.example.com>" >.mailmap
+git add .mailmap
+commit Alice alice@example.com -m "add mailmap"
+commit Bob bob@example.com -m "bob's first"
+commit "Alice Old" alice@old.example.com -m "from an old address"
+git tag v1
+
+commit Bob bob@example.com -m "pair programming" -m "Co-authored-by: Carol <carol@example.com>
+Co-authored-by: Bob <bob@example.com>"
+commit Carol carol@example.com -m "carol's first"
@@ -0,0 +1,30 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q clean
+(cd clean
+  git checkout -q -b main
+  echo a > a && mkdir dir && echo b > dir/b && echo c > exe && chmod +x exe
+  git add . && git commit -q -m c1
+)
+
+git clone -q clean staged
+(cd staged
+  echo changed > dir/b && git add dir/b
+)
+
+git clone -q clean intent-to-add
+(cd intent-to-add
+  echo new > new && git add --intent-to-add new
+)
+
+git clone -q clean conflict
+(cd conflict
+  git checkout -q -b other
+  echo other > a && git commit -q -am other
+  git checkout -q main
+  echo main > a && git commit -q -am main
+  git merge -q other || :
+)
+
+git init -q unborn
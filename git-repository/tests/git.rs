@@ -3,6 +3,14 @@ mod util;
 #[cfg(not(feature = "regex"))]
 use util::*;
 
+#[cfg(not(feature = "regex"))]
+mod apply;
+#[cfg(not(feature = "regex"))]
+mod archive;
+#[cfg(not(feature = "regex"))]
+mod bundle;
+#[cfg(not(feature = "regex"))]
+mod clone;
 #[cfg(not(feature = "regex"))]
 mod commit;
 #[cfg(not(feature = "regex"))]
@@ -12,6 +20,8 @@ mod id;
 #[cfg(not(feature = "regex"))]
 mod init;
 #[cfg(not(feature = "regex"))]
+mod merge;
+#[cfg(not(feature = "regex"))]
 mod object;
 #[cfg(not(feature = "regex"))]
 mod reference;
@@ -21,3 +31,7 @@ mod remote;
 mod repository;
 #[cfg(not(feature = "regex"))]
 mod revision;
+#[cfg(not(feature = "regex"))]
+mod serve;
+#[cfg(not(feature = "regex"))]
+mod status;
@@ -69,6 +69,18 @@ mod ancestors {
             "these are ordered differently"
         );
 
+        let commits_by_author_date = head
+            .ancestors()
+            .sorting(commit::Sorting::ByAuthorTimeNewestFirst)
+            .tie_break(commit::TieBreak::ObjectId)
+            .all()?
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            commits_by_author_date.len(),
+            4,
+            "all commits are returned independently of the tie-break"
+        );
+
         assert_eq!(
             head.ancestors().first_parent_only().all()?.count(),
             3,
@@ -76,4 +88,55 @@ mod ancestors {
         );
         Ok(())
     }
+
+    #[test]
+    fn since_and_until() -> crate::Result {
+        let repo = crate::repo("make_repo_with_fork_and_dates.sh")?.to_thread_local();
+        let head = repo.head()?.into_fully_peeled_id().expect("born")?;
+        let date = |seconds: u32| git_repository::actor::Time::new(seconds, 0);
+        let (start_of_2001, second_of_january_2001) = (978307200, 978393600);
+
+        let since_2001 = head
+            .ancestors()
+            .since(date(start_of_2001))
+            .all()?
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(since_2001.len(), 2, "the merge and the commit on the branch");
+
+        let until_2001 = head
+            .ancestors()
+            .until(date(start_of_2001))
+            .all()?
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            until_2001.len(),
+            2,
+            "commits before 2001 are found through newer commits"
+        );
+
+        assert_eq!(
+            head.ancestors()
+                .since(date(second_of_january_2001))
+                .until(date(second_of_january_2001))
+                .all()?
+                .count(),
+            1,
+            "both bounds are inclusive"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn with_interrupt() -> crate::Result {
+        let repo = crate::repo("make_repo_with_fork_and_dates.sh")?.to_thread_local();
+        let head = repo.head()?.into_fully_peeled_id().expect("born")?;
+        let should_interrupt = std::sync::atomic::AtomicBool::new(false);
+        let mut walk = head.ancestors().with_interrupt(&should_interrupt).all()?;
+        assert!(walk.next().is_some());
+        should_interrupt.store(true, std::sync::atomic::Ordering::Relaxed);
+        assert!(walk.next().is_none(), "the iteration stops once interrupted");
+        assert!(walk.is_interrupted);
+        assert_eq!(walk.is_shallow, None, "the iteration didn't complete");
+        Ok(())
+    }
 }
@@ -17,6 +17,108 @@ mod bare {
         Ok(())
     }
 
+    #[test]
+    fn init_with_hosting_options_configures_the_repository() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let repo = git_repository::init_bare_with(
+            tmp.path(),
+            git_repository::init::Hosting {
+                fsync: Some("committed".into()),
+                hide_refs: vec!["refs/pull/".into(), "refs/internal/".into()],
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(repo.kind(), git_repository::Kind::Bare);
+        assert_eq!(repo.object_hash(), git_repository::hash::Kind::Sha1);
+
+        let config = repo.config_snapshot();
+        assert_eq!(config.boolean("core.bare"), Some(true));
+        assert_eq!(config.boolean("core.logAllRefUpdates"), Some(false), "reflogs are off");
+        assert_eq!(config.string("core.fsync").as_deref(), Some("committed".into()));
+        assert_eq!(
+            config
+                .plumbing()
+                .strings("transfer", None, "hideRefs")
+                .expect("present")
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>(),
+            ["refs/pull/", "refs/internal/"]
+        );
+
+        let tmp = tempfile::tempdir()?;
+        let repo = git_repository::init_bare_with(
+            tmp.path(),
+            git_repository::init::Hosting {
+                reflogs: true,
+                ..Default::default()
+            },
+        )?;
+        let config = repo.config_snapshot();
+        assert_eq!(config.boolean("core.logAllRefUpdates"), Some(true));
+        assert_eq!(config.string("core.fsync"), None, "the default is kept");
+        assert_eq!(config.plumbing().strings("transfer", None, "hideRefs"), None);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "sha256")]
+    fn init_with_sha256_object_format() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let repo = git_repository::init_bare_with(
+            tmp.path(),
+            git_repository::init::Hosting {
+                object_hash: git_repository::hash::Kind::Sha256,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(repo.object_hash(), git_repository::hash::Kind::Sha256);
+        let config = repo.config_snapshot();
+        assert_eq!(config.integer("core.repositoryFormatVersion"), Some(1));
+        assert_eq!(
+            config.string("extensions.objectFormat").as_deref(),
+            Some("sha256".into())
+        );
+
+        let id = repo.write_blob(b"hello\n")?;
+        assert_eq!(
+            id.detach().to_string(),
+            "2cf8d83d9ee29543b34a87727421fdecb7e3f3a183d337639025de576db9ebb4",
+            "the same id as `git hash-object` would produce"
+        );
+
+        let repo = git_repository::open(tmp.path())?;
+        assert_eq!(repo.object_hash(), git_repository::hash::Kind::Sha256);
+        assert_eq!(repo.find_object(id)?.data, b"hello\n");
+
+        let tree_id = repo.write_object(&git_repository::objs::Tree {
+            entries: vec![git_repository::objs::tree::Entry {
+                mode: git_repository::objs::tree::EntryMode::Blob,
+                filename: "file".into(),
+                oid: id.detach(),
+            }],
+        })?;
+        let entry = repo
+            .find_object(tree_id)?
+            .into_tree()
+            .lookup_path(Some("file"))?
+            .expect("present");
+        assert_eq!(
+            entry.oid, id,
+            "trees are decoded with ids of the repository's hash kind"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn init_with_hosting_options_into_non_empty_directory_is_not_allowed() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        std::fs::write(tmp.path().join("existing.txt"), b"I was here before you")?;
+
+        assert!(git_repository::init_bare_with(tmp.path(), Default::default()).is_err());
+        Ok(())
+    }
+
     #[test]
     fn init_into_non_empty_directory_is_not_allowed() -> crate::Result {
         let tmp = tempfile::tempdir()?;
@@ -0,0 +1,193 @@
+use git_repository as git;
+use git_repository::bstr::ByteSlice;
+use git_repository::merge::{driver::Input, Driver, Resolution};
+
+fn repo(name: &str) -> git::Repository {
+    let repo_path = git_testtools::scripted_fixture_repo_read_only("make_merge_drivers_repo.sh").unwrap();
+    git::open_opts(repo_path.join(name), crate::restricted()).unwrap()
+}
+
+fn input<'a>(base: &'a str, ours: &'a str, theirs: &'a str) -> Input<'a> {
+    Input {
+        base: base.as_bytes(),
+        ours: ours.as_bytes(),
+        theirs: theirs.as_bytes(),
+        path: "file".into(),
+        marker_size: 7,
+    }
+}
+
+mod file {
+    use git_repository::merge::{
+        file::{merge, ConflictStyle, Options},
+        Resolution,
+    };
+
+    fn merged(base: &str, ours: &str, theirs: &str, conflict: ConflictStyle) -> (String, Resolution) {
+        let mut out = Vec::new();
+        let res = merge(
+            base.as_bytes(),
+            ours.as_bytes(),
+            theirs.as_bytes(),
+            &mut out,
+            Options {
+                conflict,
+                ours_label: Some(b"ours"),
+                theirs_label: Some(b"theirs"),
+                ..Default::default()
+            },
+        );
+        (String::from_utf8(out).expect("valid input is valid output"), res)
+    }
+
+    #[test]
+    fn non_overlapping_changes_are_combined() {
+        let base = "a\nb\nc\nd\ne\n";
+        let ours = "A\nb\nc\nd\ne\n";
+        let theirs = "a\nb\nc\nd\nE\n";
+        assert_eq!(
+            merged(base, ours, theirs, ConflictStyle::Markers),
+            ("A\nb\nc\nd\nE\n".into(), Resolution::Clean)
+        );
+    }
+
+    #[test]
+    fn identical_changes_on_both_sides_are_no_conflict() {
+        let base = "a\nb\nc\n";
+        let both = "a\nB\nc\n";
+        assert_eq!(
+            merged(base, both, both, ConflictStyle::Markers),
+            (both.into(), Resolution::Clean)
+        );
+    }
+
+    #[test]
+    fn conflicting_changes_are_marked() {
+        let base = "a\nb\nc\n";
+        let ours = "a\nours\nc\n";
+        let theirs = "a\ntheirs\nc\n";
+        assert_eq!(
+            merged(base, ours, theirs, ConflictStyle::Markers),
+            (
+                "a\n<<<<<<< ours\nours\n=======\ntheirs\n>>>>>>> theirs\nc\n".into(),
+                Resolution::Conflict
+            )
+        );
+        assert_eq!(
+            merged(base, ours, theirs, ConflictStyle::Ours),
+            (ours.into(), Resolution::Clean)
+        );
+        assert_eq!(
+            merged(base, ours, theirs, ConflictStyle::Theirs),
+            (theirs.into(), Resolution::Clean)
+        );
+    }
+
+    #[test]
+    fn union_keeps_lines_of_both_sides_even_without_trailing_newline() {
+        let base = "# Changelog\n";
+        let ours = "# Changelog\n- ours";
+        let theirs = "# Changelog\n- theirs\n";
+        assert_eq!(
+            merged(base, ours, theirs, ConflictStyle::Union),
+            ("# Changelog\n- ours\n- theirs\n".into(), Resolution::Clean)
+        );
+    }
+
+    #[test]
+    fn binary_detection() {
+        assert!(git_repository::merge::file::is_binary(b"a\0b"));
+        assert!(!git_repository::merge::file::is_binary(b"a\nb"));
+    }
+}
+
+#[test]
+fn driver_for_attribute_uses_builtins_by_default() {
+    use git_attributes::StateRef;
+    let repo = repo("plain");
+    let drivers = repo.merge_drivers();
+    assert_eq!(drivers.driver_for_attribute(StateRef::Set), Driver::Text);
+    assert_eq!(drivers.driver_for_attribute(StateRef::Unspecified), Driver::Text);
+    assert_eq!(drivers.driver_for_attribute(StateRef::Unset), Driver::Binary);
+    assert_eq!(drivers.driver_for_attribute(StateRef::Value("union".into())), Driver::Union);
+    assert_eq!(
+        drivers.driver_for_attribute(StateRef::Value("binary".into())),
+        Driver::Binary
+    );
+    assert_eq!(
+        drivers.driver_for_attribute(StateRef::Value("unknown".into())),
+        Driver::Text,
+        "unknown drivers fall back to text"
+    );
+}
+
+#[test]
+fn merge_default_is_used_for_set_and_unspecified_attributes() {
+    use git_attributes::StateRef;
+    let repo = repo("with-default");
+    let drivers = repo.merge_drivers();
+    assert_eq!(drivers.driver_for_attribute(StateRef::Set), Driver::Union);
+    assert_eq!(drivers.driver_for_attribute(StateRef::Unspecified), Driver::Union);
+    assert_eq!(drivers.driver_for_attribute(StateRef::Unset), Driver::Binary);
+}
+
+#[test]
+fn external_drivers_are_read_from_configuration_and_run() -> crate::Result {
+    let repo = repo("plain");
+    let drivers = repo.merge_drivers();
+    let driver = drivers.driver_by_name("theirs".into());
+    match &driver {
+        Driver::External(external) => {
+            assert_eq!(external.command, "cat %B > %A");
+            assert_eq!(external.display_name.as_ref().expect("set"), "always take theirs");
+            assert_eq!(external.recursive.as_ref().expect("set"), "binary");
+        }
+        _ => unreachable!("it's configured"),
+    }
+    let outcome = drivers.merge(&driver, input("base\n", "ours\n", "theirs\n"))?;
+    assert_eq!(outcome.data.as_bstr(), "theirs\n");
+    assert_eq!(outcome.resolution, Resolution::Clean);
+
+    let driver = drivers.driver_by_name("fails".into());
+    let outcome = drivers.merge(&driver, input("base\n", "ours\n", "theirs\n"))?;
+    assert_eq!(
+        outcome.data.as_bstr(),
+        "ours\nfile\n",
+        "the path is substituted and the result is read back"
+    );
+    assert_eq!(
+        outcome.resolution,
+        Resolution::Conflict,
+        "non-zero exit codes indicate conflicts"
+    );
+    Ok(())
+}
+
+#[test]
+fn in_process_drivers_take_precedence() -> crate::Result {
+    let repo = repo("plain");
+    let mut drivers = repo.merge_drivers();
+    drivers.register("theirs", |input: Input<'_>, out: &mut Vec<u8>| {
+        out.extend_from_slice(input.base);
+        Ok(Resolution::Clean)
+    });
+    let driver = drivers.driver_by_name("theirs".into());
+    assert_eq!(driver, Driver::InProcess { name: "theirs".into() });
+    let outcome = drivers.merge(&driver, input("base\n", "ours\n", "theirs\n"))?;
+    assert_eq!(outcome.data.as_bstr(), "base\n");
+    Ok(())
+}
+
+#[test]
+fn trivial_merges_do_not_invoke_drivers() -> crate::Result {
+    let repo = repo("plain");
+    let drivers = repo.merge_drivers();
+    let outcome = drivers.merge(&Driver::Binary, input("base\n", "base\n", "theirs\n"))?;
+    assert_eq!(outcome.data.as_bstr(), "theirs\n");
+    assert_eq!(outcome.resolution, Resolution::Clean);
+
+    let outcome = drivers.merge(&Driver::Binary, input("base\n", "ours\n", "theirs\n"))?;
+    assert_eq!(outcome.data.as_bstr(), "ours\n", "binary merges keep our side");
+    assert_eq!(outcome.resolution, Resolution::Conflict);
+    Ok(())
+}
@@ -37,11 +37,70 @@ mod commit {
     }
 }
 
+mod tree {
+    use git_repository as git;
+    use git_repository::objs::tree::{build::Builder, EntryMode};
+
+    fn nested_tree(repo: &git::Repository) -> crate::Result<git::Tree<'_>> {
+        let blob = repo.write_object(git::objs::Blob { data: b"hi".to_vec() })?.detach();
+        let b = repo
+            .write_object(Builder::new().entry(EntryMode::Blob, "file", blob).build()?)?
+            .detach();
+        let a = repo
+            .write_object(
+                Builder::new()
+                    .entry(EntryMode::Tree, "b", b)
+                    .entry(EntryMode::Blob, "other", blob)
+                    .build()?,
+            )?
+            .detach();
+        let root = repo.write_object(
+            Builder::new()
+                .entry(EntryMode::Tree, "a", a)
+                .entry(EntryMode::Blob, "top", blob)
+                .build()?,
+        )?;
+        Ok(root.object()?.into_tree())
+    }
+
+    #[test]
+    fn lookup_entry_cached_matches_lookup_path() -> crate::Result {
+        let (repo, _tmp) = crate::basic_rw_repo()?;
+        let tree = nested_tree(&repo)?;
+        let mut cache = git::object::tree::lookup::Cache::new();
+        assert!(cache.is_empty());
+
+        for path in [
+            &["top"][..],
+            &["a"],
+            &["a", "other"],
+            &["a", "b"],
+            &["a", "b", "file"],
+            &["a", "missing"],
+            &["top", "file"],
+            &["a", "b", "file", "deeper"],
+            &[],
+        ] {
+            assert_eq!(
+                tree.lookup_entry_cached(path.iter().copied(), &mut cache)?,
+                git::Tree::from_data(tree.id, tree.data.clone(), &repo).lookup_path(path.iter().copied())?,
+                "{:?}",
+                path
+            );
+        }
+        assert_eq!(cache.len(), 3, "only the root and both subtrees are cached");
+
+        cache.clear();
+        assert!(cache.is_empty());
+        Ok(())
+    }
+}
+
 #[test]
 fn object_ref_size_in_memory() {
     assert_eq!(
         std::mem::size_of::<git_repository::Object<'_>>(),
-        56,
+        if cfg!(feature = "sha256") { 72 } else { 56 },
         "the size of this structure should not changed unexpectedly"
     )
 }
@@ -50,7 +109,7 @@ fn object_ref_size_in_memory() {
 fn oid_size_in_memory() {
     assert_eq!(
         std::mem::size_of::<git_repository::Id<'_>>(),
-        32,
+        if cfg!(feature = "sha256") { 48 } else { 32 },
         "the size of this structure should not changed unexpectedly"
     )
 }
@@ -64,6 +64,26 @@ mod find {
         assert_eq!(symbolic_ref.into_fully_peeled_id()?, the_commit, "idempotency");
         Ok(())
     }
+
+    #[test]
+    fn provenance() -> crate::Result {
+        let repo = repo()?;
+        let packed = repo.find_reference("dt1")?.provenance()?.expect("still present");
+        assert_eq!(packed.source, refs::file::provenance::Source::Packed);
+        assert_eq!(packed.path, repo.git_dir().join("packed-refs"));
+        assert!(packed.modified.is_some());
+
+        let loose = repo
+            .find_reference("multi-link-target1")?
+            .provenance()?
+            .expect("still present");
+        assert_eq!(loose.source, refs::file::provenance::Source::Loose);
+        assert_eq!(
+            loose.path,
+            repo.git_dir().join("refs").join("heads").join("multi-link-target1")
+        );
+        Ok(())
+    }
 }
 
 #[test]
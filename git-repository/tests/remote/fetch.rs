@@ -0,0 +1,956 @@
+mod negotiate {
+    use std::convert::TryFrom;
+
+    use git_repository as git;
+    use git_repository::{
+        bstr::{BStr, ByteSlice},
+        remote::fetch::negotiate::Algorithm,
+        ObjectId,
+    };
+    use git_testtools::scripted_fixture_repo_read_only;
+
+    /// A round of negotiation as performed by git.
+    struct Round {
+        /// The `have` lines sent in this round, without the ones that were acknowledged in previous rounds.
+        haves: Vec<ObjectId>,
+        /// The commits acknowledged by the remote in response.
+        acks: Vec<ObjectId>,
+    }
+
+    /// Parse the packet trace of a protocol V2 fetch into its negotiation rounds.
+    fn rounds_from_trace(trace: &[u8]) -> Vec<Round> {
+        let mut rounds = Vec::<Round>::new();
+        let mut common = Vec::new();
+        for line in trace.lines() {
+            let packet = match line.find(b"packet:") {
+                Some(pos) => line[pos + b"packet:".len()..].trim(),
+                None => continue,
+            };
+            if packet == b"fetch> command=fetch" {
+                rounds.push(Round {
+                    haves: Vec::new(),
+                    acks: Vec::new(),
+                });
+            } else if let Some(hex) = packet.strip_prefix(b"fetch> have ") {
+                let id = ObjectId::from_hex(hex).expect("valid hash");
+                if !common.contains(&id) {
+                    rounds.last_mut().expect("in fetch").haves.push(id);
+                }
+            } else if let Some(hex) = packet.strip_prefix(b"fetch< ACK ") {
+                let id = ObjectId::from_hex(hex).expect("valid hash");
+                common.push(id);
+                rounds.last_mut().expect("in fetch").acks.push(id);
+            }
+        }
+        rounds
+    }
+
+    /// Negotiate like git does in protocol V2, with windows of `have` lines starting at 16 and doubling each round,
+    /// and assert we send the same `have` lines in the same rounds.
+    fn assert_negotiation_like_git(repo: &git::Repository, algorithm: Algorithm, rounds: &[Round]) -> crate::Result {
+        let mut negotiator = algorithm.into_negotiator(repo);
+        let mut tips = Vec::new();
+        for reference in repo.references()?.all()? {
+            let mut reference = reference.expect("valid reference");
+            tips.push((
+                reference.name().as_bstr().to_owned(),
+                reference.peel_to_id_in_place()?.detach(),
+            ));
+        }
+        tips.sort();
+        for (_name, id) in tips {
+            negotiator.add_tip(id)?;
+        }
+
+        let mut window = 16;
+        let mut num_rounds = 0;
+        loop {
+            let mut haves = Vec::new();
+            while haves.len() < window {
+                match negotiator.next_have() {
+                    Some(id) => haves.push(id?),
+                    None => break,
+                }
+            }
+            let round = &rounds[num_rounds];
+            num_rounds += 1;
+            assert_eq!(
+                haves, round.haves,
+                "{:?}: round {} sends the same haves as git",
+                algorithm, num_rounds
+            );
+            if haves.is_empty() {
+                break;
+            }
+            for id in &round.acks {
+                negotiator.in_common_with_remote(*id)?;
+            }
+            window *= 2;
+        }
+        assert_eq!(num_rounds, rounds.len(), "{:?}: as many rounds as git", algorithm);
+        Ok(())
+    }
+
+    fn trace(dir: &std::path::Path, algorithm: &str) -> crate::Result<Vec<Round>> {
+        Ok(rounds_from_trace(&std::fs::read(
+            dir.join(algorithm).with_extension("trace"),
+        )?))
+    }
+
+    #[test]
+    fn all_algorithms_send_the_same_haves_as_git_in_as_many_rounds() -> crate::Result {
+        let dir = scripted_fixture_repo_read_only("make_fetch_negotiation_repos.sh")?;
+        let repo = git::open_opts(dir.join("client"), git::open::Options::isolated())?;
+
+        let noop = trace(&dir, "noop")?;
+        assert_negotiation_like_git(&repo, Algorithm::Noop, &noop)?;
+        assert_eq!(noop.len(), 1, "there is nothing to negotiate");
+        assert!(noop[0].haves.is_empty());
+
+        let consecutive = trace(&dir, "consecutive")?;
+        assert_negotiation_like_git(&repo, Algorithm::Consecutive, &consecutive)?;
+        let skipping = trace(&dir, "skipping")?;
+        assert_negotiation_like_git(&repo, Algorithm::Skipping, &skipping)?;
+
+        let num_haves = |rounds: &[Round]| rounds.iter().map(|r| r.haves.len()).sum::<usize>();
+        assert_eq!(
+            (num_haves(&consecutive), consecutive.len()),
+            (121, 5),
+            "every local commit is sent until the common ones are found"
+        );
+        assert_eq!(
+            (num_haves(&skipping), skipping.len()),
+            (23, 3),
+            "skipping commits finds common ones with far fewer haves and rounds"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn algorithm_names_are_case_insensitive() {
+        for (name, expected) in [
+            ("noop", Algorithm::Noop),
+            ("consecutive", Algorithm::Consecutive),
+            ("default", Algorithm::Consecutive),
+            ("Skipping", Algorithm::Skipping),
+        ] {
+            assert_eq!(Algorithm::try_from(<&BStr>::from(name)).expect("valid"), expected);
+        }
+        let err = Algorithm::try_from(<&BStr>::from("fast")).unwrap_err();
+        assert_eq!(err.name, "fast");
+    }
+
+    #[test]
+    fn algorithm_is_configured_with_fetch_negotiation_algorithm_or_feature_experimental() -> crate::Result {
+        let mut repo = crate::remote::repo("clone");
+        assert_eq!(
+            repo.config_snapshot().fetch_negotiation_algorithm()?,
+            Algorithm::Consecutive,
+            "the default"
+        );
+
+        repo.config_snapshot_mut()
+            .set_raw_value("feature", None, "experimental", "true")?;
+        assert_eq!(
+            repo.config_snapshot().fetch_negotiation_algorithm()?,
+            Algorithm::Skipping
+        );
+
+        repo.config_snapshot_mut()
+            .set_raw_value("fetch", None, "negotiationAlgorithm", "noop")?;
+        assert_eq!(
+            repo.config_snapshot().fetch_negotiation_algorithm()?,
+            Algorithm::Noop,
+            "explicit configuration overrides the experimental default"
+        );
+
+        repo.config_snapshot_mut()
+            .set_raw_value("fetch", None, "negotiationAlgorithm", "unknown")?;
+        assert!(repo.config_snapshot().fetch_negotiation_algorithm().is_err());
+        Ok(())
+    }
+}
+
+#[test]
+fn tags_are_followed_unless_configured_otherwise_with_tag_opt() -> crate::Result {
+    use git_repository::remote::fetch::Tags;
+    let mut repo = crate::remote::repo("clone");
+    assert_eq!(repo.find_remote("origin")?.fetch_tags(), Tags::Included, "the default");
+
+    for (value, expected) in [
+        ("--no-tags", Tags::None),
+        ("--tags", Tags::All),
+        ("unknown", Tags::Included),
+    ] {
+        repo.config_snapshot_mut()
+            .set_raw_value("remote", Some("origin"), "tagOpt", value)?;
+        assert_eq!(repo.find_remote("origin")?.fetch_tags(), expected);
+    }
+    Ok(())
+}
+
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+mod update_refs {
+    use git_repository as git;
+    use git_repository::{
+        protocol::fetch::Ref,
+        remote::fetch::{
+            update_refs::{Mode, Options, Outcome},
+            DenyCurrentBranch, Tags,
+        },
+        Remote,
+    };
+    use git_testtools::{hex_to_id, scripted_fixture_repo_writable};
+
+    const A: &str = "f99771fe6a1b535783af3163eba95a927aae21d5";
+    const B: &str = "dfd0954dabef3b64f458321ef15571cc1a46d552";
+    const C: &str = "2d9d136fb0765f2e24c44a0f91984318d580d03b";
+    const G: &str = "eadd5abf749f7cf2d0281337dbb9c68c374c9e7d";
+    const B_TAG: &str = "a5f670d61db07012568973e0d050921fe4b81357";
+
+    fn repo_rw(fetch_spec: &str) -> crate::Result<(git::Repository, tempfile::TempDir)> {
+        let tmp = scripted_fixture_repo_writable("make_remote_repos.sh")?;
+        let mut repo = git::open_opts(tmp.path().join("clone"), git::open::Options::isolated())?;
+        repo.config_snapshot_mut()
+            .set_raw_value("remote", Some("origin"), "fetch", fetch_spec)?;
+        Ok((repo, tmp))
+    }
+
+    fn origin(repo: &git::Repository) -> Remote<'_> {
+        repo.find_remote("origin").expect("present")
+    }
+
+    fn branch(name: &str, hex: &str) -> Ref {
+        Ref::Direct {
+            path: format!("refs/heads/{}", name).into(),
+            object: hex_to_id(hex),
+        }
+    }
+
+    fn tag(name: &str, tag: &str, object: &str) -> Ref {
+        Ref::Peeled {
+            path: format!("refs/tags/{}", name).into(),
+            tag: hex_to_id(tag),
+            object: hex_to_id(object),
+        }
+    }
+
+    fn modes(outcome: &Outcome) -> Vec<(String, Mode)> {
+        outcome
+            .updates
+            .iter()
+            .map(|update| (update.name.as_bstr().to_string(), update.mode))
+            .collect()
+    }
+
+    fn target(repo: &git::Repository, name: &str) -> crate::Result<Option<String>> {
+        Ok(repo
+            .try_find_reference(name)?
+            .map(|r| r.target().try_id().expect("peeled").to_string()))
+    }
+
+    #[test]
+    fn new_and_fast_forwarded_refs_are_written_but_unchanged_ones_are_not() -> crate::Result {
+        let (repo, _tmp) = repo_rw("refs/heads/*:refs/remotes/origin/*")?;
+        let remote_refs = [branch("b", B), branch("new", C), branch("d", A)];
+        let outcome = origin(&repo).update_refs(
+            &remote_refs,
+            Options {
+                atomic: true,
+                ..Default::default()
+            },
+        )?;
+
+        assert_eq!(
+            modes(&outcome),
+            [
+                ("refs/remotes/origin/b".into(), Mode::NoChangeNeeded),
+                ("refs/remotes/origin/new".into(), Mode::New),
+                ("refs/remotes/origin/d".into(), Mode::FastForward),
+            ]
+        );
+        assert_eq!(outcome.edits.len(), 2);
+        assert_eq!(target(&repo, "refs/remotes/origin/new")?.as_deref(), Some(C));
+        assert_eq!(target(&repo, "refs/remotes/origin/d")?.as_deref(), Some(A));
+        let log = repo
+            .find_reference("refs/remotes/origin/d")?
+            .log_iter()
+            .rev()?
+            .expect("reflog present")
+            .next()
+            .expect("one line")?;
+        assert_eq!(log.message, "fetch origin: fast-forward");
+        Ok(())
+    }
+
+    #[test]
+    fn a_single_rejection_prevents_all_updates_only_in_atomic_mode() -> crate::Result {
+        let remote_refs = [branch("new", C), branch("c", G)];
+        for atomic in [true, false] {
+            let (repo, _tmp) = repo_rw("refs/heads/*:refs/remotes/origin/*")?;
+            let outcome = origin(&repo).update_refs(
+                &remote_refs,
+                Options {
+                    atomic,
+                    ..Default::default()
+                },
+            )?;
+            assert_eq!(
+                modes(&outcome),
+                [
+                    ("refs/remotes/origin/new".into(), Mode::New),
+                    ("refs/remotes/origin/c".into(), Mode::RejectedNonFastForward),
+                ]
+            );
+            assert_eq!(outcome.rejected().count(), 1);
+            assert_eq!(outcome.edits.len(), usize::from(!atomic));
+            assert_eq!(
+                outcome.updates.iter().map(|update| update.applied).collect::<Vec<_>>(),
+                [!atomic, false],
+                "the report tells which updates were actually written"
+            );
+            assert_eq!(
+                outcome.statistics.transactions,
+                usize::from(!atomic),
+                "non-atomic edits are still applied in a single transaction"
+            );
+            assert_eq!(
+                target(&repo, "refs/remotes/origin/new")?.is_some(),
+                !atomic,
+                "all or nothing in atomic mode"
+            );
+            assert_eq!(target(&repo, "refs/remotes/origin/c")?.as_deref(), Some(C));
+        }
+
+        let (repo, _tmp) = repo_rw("+refs/heads/*:refs/remotes/origin/*")?;
+        let outcome = origin(&repo).update_refs(
+            &remote_refs,
+            Options {
+                atomic: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            modes(&outcome),
+            [
+                ("refs/remotes/origin/new".into(), Mode::New),
+                ("refs/remotes/origin/c".into(), Mode::Forced),
+            ]
+        );
+        assert_eq!(target(&repo, "refs/remotes/origin/c")?.as_deref(), Some(G));
+        Ok(())
+    }
+
+    #[test]
+    fn the_outcome_reports_every_update_like_git_fetch() -> crate::Result {
+        let (repo, _tmp) = repo_rw("refs/heads/*:refs/remotes/origin/*")?;
+        let outcome = origin(&repo).update_refs(
+            &[
+                branch("b", B),
+                branch("new", C),
+                branch("d", A),
+                branch("c", G),
+                branch("missing", "0000000000000000000000000000000000000001"),
+            ],
+            Options::default(),
+        )?;
+        let missing = outcome.updates.last().expect("present");
+        assert!(
+            missing.previous.is_none() && !missing.applied,
+            "a rejected new reference is never written"
+        );
+        let d = outcome
+            .updates
+            .iter()
+            .find(|update| update.name.as_bstr() == "refs/remotes/origin/d")
+            .expect("present");
+        assert!(d.previous.is_some() && d.applied);
+        assert_eq!(d.new, hex_to_id(A));
+        assert_eq!(
+            outcome.to_string(),
+            " = [up to date]     b       -> origin/b\n \
+             * [new branch]     new     -> origin/new\n   \
+             82024b2..f99771f d       -> origin/d\n \
+             ! [rejected]       c       -> origin/c  (non-fast-forward)\n \
+             ! [rejected]       missing -> origin/missing  (object not found)\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn many_updates_can_be_written_into_packed_refs_at_once() -> crate::Result {
+        let remote_refs = [branch("new", C), branch("d", A)];
+        for threshold in [3, 2] {
+            let (repo, _tmp) = repo_rw("refs/heads/*:refs/remotes/origin/*")?;
+            let outcome = origin(&repo).update_refs(
+                &remote_refs,
+                Options {
+                    packed_refs_threshold: Some(threshold),
+                    ..Default::default()
+                },
+            )?;
+            let packed = threshold == 2;
+            assert_eq!(outcome.edits.len(), 2);
+            assert_eq!(outcome.statistics.transactions, 1);
+            assert_eq!(outcome.statistics.packed, packed);
+
+            for (name, expected) in [("refs/remotes/origin/new", C), ("refs/remotes/origin/d", A)] {
+                assert_eq!(target(&repo, name)?.as_deref(), Some(expected));
+                assert_eq!(
+                    repo.git_dir().join(name).is_file(),
+                    !packed,
+                    "packed references don't have a loose version anymore"
+                );
+                assert!(
+                    repo.find_reference(name)?.log_iter().all()?.is_some(),
+                    "reflogs are written either way"
+                );
+            }
+            let packed_refs = repo.refs.cached_packed_buffer()?;
+            assert_eq!(
+                packed_refs.map_or(false, |buf| buf.find("refs/remotes/origin/new").is_ok()),
+                packed
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn the_checked_out_branch_and_objects_that_are_not_present_are_never_updated() -> crate::Result {
+        let (repo, _tmp) = repo_rw("+refs/heads/*:refs/heads/*")?;
+        let outcome = origin(&repo).update_refs(
+            &[
+                branch("main", B),
+                branch("missing", "0000000000000000000000000000000000000001"),
+            ],
+            Options::default(),
+        )?;
+        assert_eq!(
+            modes(&outcome),
+            [
+                ("refs/heads/main".into(), Mode::RejectedCurrentlyCheckedOut),
+                ("refs/heads/missing".into(), Mode::RejectedSourceObjectNotFound),
+            ]
+        );
+        assert!(outcome.edits.is_empty());
+        Ok(())
+    }
+
+    fn git(dir: &std::path::Path, args: &[&str]) -> crate::Result<String> {
+        let output = std::process::Command::new("git").args(args).current_dir(dir).output()?;
+        assert!(output.status.success(), "{:?} failed", args);
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    #[test]
+    fn branches_checked_out_in_linked_worktrees_are_updated_only_if_configured() -> crate::Result {
+        let (repo, tmp) = repo_rw("+refs/heads/*:refs/heads/*")?;
+        let work_dir = repo.work_dir().expect("non-bare").to_owned();
+        let linked_work_dir = tmp.path().join("linked");
+        git(&work_dir, &["worktree", "add", "-q", "-b", "linked", "../linked", C])?;
+        let linked_work_dir = std::fs::canonicalize(linked_work_dir)?;
+        let remote_refs = [branch("linked", G)];
+
+        let outcome = origin(&repo).update_refs(&remote_refs, Options::default())?;
+        assert_eq!(
+            modes(&outcome),
+            [("refs/heads/linked".into(), Mode::RejectedCurrentlyCheckedOut)]
+        );
+        assert_eq!(
+            outcome.updates[0]
+                .worktree
+                .as_ref()
+                .map(std::fs::canonicalize)
+                .transpose()?,
+            Some(linked_work_dir.clone())
+        );
+
+        std::fs::write(linked_work_dir.join("file"), "changed")?;
+        let update_instead = Options {
+            checked_out: DenyCurrentBranch::UpdateInstead,
+            ..Default::default()
+        };
+        let outcome = origin(&repo).update_refs(&remote_refs, update_instead)?;
+        assert_eq!(
+            modes(&outcome),
+            [("refs/heads/linked".into(), Mode::RejectedWorktreeHasChanges)],
+            "changes in the worktree would be lost"
+        );
+
+        git(&linked_work_dir, &["checkout", "--", "file"])?;
+        let outcome = origin(&repo).update_refs(&remote_refs, update_instead)?;
+        assert_eq!(modes(&outcome), [("refs/heads/linked".into(), Mode::Forced)]);
+        assert!(outcome.updates[0].applied);
+        assert_eq!(target(&repo, "refs/heads/linked")?.as_deref(), Some(G));
+        assert_eq!(
+            std::fs::read_to_string(linked_work_dir.join("file"))?,
+            git(&work_dir, &["show", &format!("{}:file", G)])?,
+            "the worktree is updated along with its branch"
+        );
+        assert_eq!(git(&linked_work_dir, &["status", "--porcelain"])?, "", "and is clean");
+
+        let outcome = origin(&repo).update_refs(
+            &[branch("linked", C)],
+            Options {
+                checked_out: DenyCurrentBranch::Ignore,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(modes(&outcome), [("refs/heads/linked".into(), Mode::Forced)]);
+        assert_eq!(target(&repo, "refs/heads/linked")?.as_deref(), Some(C));
+        assert_eq!(
+            std::fs::read_to_string(linked_work_dir.join("file"))?,
+            git(&work_dir, &["show", &format!("{}:file", G)])?,
+            "the worktree is left alone"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn receive_deny_current_branch_is_parsed_from_configuration() -> crate::Result {
+        let (mut repo, _tmp) = repo_rw("+refs/heads/*:refs/heads/*")?;
+        assert_eq!(
+            repo.config_snapshot().receive_deny_current_branch()?,
+            DenyCurrentBranch::Refuse,
+            "the default"
+        );
+        for (value, expected) in [
+            ("true", DenyCurrentBranch::Refuse),
+            ("refuse", DenyCurrentBranch::Refuse),
+            ("false", DenyCurrentBranch::Ignore),
+            ("warn", DenyCurrentBranch::Warn),
+            ("updateInstead", DenyCurrentBranch::UpdateInstead),
+        ] {
+            repo.config_snapshot_mut()
+                .set_raw_value("receive", None, "denyCurrentBranch", value)?;
+            assert_eq!(repo.config_snapshot().receive_deny_current_branch()?, expected);
+        }
+        repo.config_snapshot_mut()
+            .set_raw_value("receive", None, "denyCurrentBranch", "sometimes")?;
+        assert_eq!(
+            repo.config_snapshot().receive_deny_current_branch().unwrap_err().value,
+            "sometimes"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tags_pointing_to_present_objects_are_followed() -> crate::Result {
+        let (repo, _tmp) = repo_rw("+refs/heads/*:refs/remotes/origin/*")?;
+        repo.find_reference("refs/tags/b-tag")?.delete()?;
+        let remote_refs = [
+            tag("b-tag", B_TAG, B),
+            tag("missing-tag-object", "0000000000000000000000000000000000000001", B),
+            tag("i-tag", B_TAG, B),
+        ];
+
+        let outcome = origin(&repo).update_refs(
+            &remote_refs,
+            Options {
+                tags: Tags::None,
+                ..Default::default()
+            },
+        )?;
+        assert!(outcome.updates.is_empty(), "tags are ignored entirely");
+
+        let outcome = origin(&repo).update_refs(
+            &remote_refs,
+            Options {
+                tags: Tags::All,
+                dry_run: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            modes(&outcome),
+            [
+                ("refs/tags/b-tag".into(), Mode::New),
+                (
+                    "refs/tags/missing-tag-object".into(),
+                    Mode::RejectedSourceObjectNotFound
+                ),
+                ("refs/tags/i-tag".into(), Mode::RejectedTagUpdate),
+            ]
+        );
+        assert!(outcome.edits.is_empty(), "nothing is written in a dry-run");
+
+        let outcome = origin(&repo).update_refs(&remote_refs, Options::default())?;
+        assert_eq!(
+            modes(&outcome),
+            [("refs/tags/b-tag".into(), Mode::New)],
+            "only tags whose objects are present are followed, and existing ones are left alone"
+        );
+        assert_eq!(target(&repo, "refs/tags/b-tag")?.as_deref(), Some(B_TAG));
+        Ok(())
+    }
+}
+
+mod maintenance {
+    use std::sync::atomic::AtomicBool;
+
+    use git_features::progress;
+    use git_repository as git;
+    use git_repository::remote::fetch::maintenance::{Options, Outcome};
+
+    fn source() -> crate::Result<git::Repository> {
+        let dir = git_testtools::scripted_fixture_repo_read_only("make_bundle_repo.sh")?;
+        Ok(git::open_opts(dir, crate::restricted())?)
+    }
+
+    fn id(repo: &git::Repository, spec: &str) -> git::ObjectId {
+        repo.rev_parse(spec)
+            .expect("valid spec")
+            .single()
+            .expect("single object")
+            .detach()
+    }
+
+    /// Receive a pack with all objects reachable from `refs` but not from `exclude` in `source`, just like a fetch would.
+    fn receive(
+        repo: &git::Repository,
+        source: &git::Repository,
+        refs: &[&str],
+        exclude: &[&str],
+        options: Options,
+    ) -> crate::Result<Outcome> {
+        let should_interrupt = AtomicBool::default();
+        let mut bundle = Vec::new();
+        source.create_bundle(
+            refs.iter().copied(),
+            exclude.iter().map(|spec| id(source, spec)),
+            &mut bundle,
+            progress::Discard,
+            &should_interrupt,
+        )?;
+        let unbundled = repo.unbundle(&bundle[..], progress::Discard, &should_interrupt)?;
+        Ok(repo.maintain_after_fetch(
+            unbundled.write.index_path.expect("written to disk"),
+            progress::Discard,
+            &should_interrupt,
+            options,
+        )?)
+    }
+
+    #[test]
+    fn commit_graph_is_extended_with_received_commits_and_multi_pack_index_covers_all_packs() -> crate::Result {
+        let source = source()?;
+        let tmp = tempfile::tempdir()?;
+        let repo = git::init_bare(&tmp)?;
+        let options = Options {
+            write_commit_graph: true,
+            write_multi_pack_index: true,
+        };
+        let objects_dir = repo.git_dir().join("objects");
+
+        let outcome = receive(&repo, &source, &["v1"], &[], options)?;
+        assert_eq!(outcome.commit_graph_commits, Some(2), "c1 and c2");
+        assert!(outcome.multi_pack_index_checksum.is_some());
+
+        let outcome = receive(&repo, &source, &["main"], &["main~1"], options)?;
+        assert_eq!(
+            outcome.commit_graph_commits,
+            Some(3),
+            "the existing commit-graph was extended by the received commit"
+        );
+
+        let graph = git_commitgraph::Graph::from_info_dir(objects_dir.join("info"))?;
+        assert_eq!(graph.num_commits(), 3);
+        graph.verify_integrity(|_| Ok::<_, std::convert::Infallible>(()))?;
+        let tip = graph.commit_by_id(id(&source, "main")).expect("tip is present");
+        assert_eq!(tip.generation(), 3, "generations are computed");
+        assert_eq!(tip.root_tree_id(), id(&source, "main^{tree}"));
+
+        let midx = git_odb::pack::multi_index::File::at(objects_dir.join("pack").join("multi-pack-index"))?;
+        assert_eq!(midx.num_indices(), 2, "the multi-pack-index covers both received packs");
+        assert_eq!(
+            midx.num_objects(),
+            3 /* commits */ + 4 /* trees */ + 3 /* blobs */ + 1 /* tag */
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn nothing_is_written_by_default() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let repo = git::init_bare(&tmp)?;
+
+        let outcome = receive(&repo, &source()?, &["main"], &[], Options::default())?;
+        assert_eq!(outcome, Default::default());
+        let objects_dir = repo.git_dir().join("objects");
+        assert!(!objects_dir.join("info").join("commit-graph").exists());
+        assert!(!objects_dir.join("pack").join("multi-pack-index").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn commit_graph_is_written_on_fetch_if_configured() -> crate::Result {
+        let repo = super::super::repo("clone");
+        assert_eq!(
+            repo.find_remote("origin")?.maintenance_on_fetch(),
+            Options::default(),
+            "nothing is configured by default"
+        );
+
+        let tmp = git_testtools::scripted_fixture_repo_writable("make_remote_repos.sh")?;
+        let config_path = tmp.path().join("clone").join(".git").join("config");
+        let mut config = std::fs::read(&config_path)?;
+        config.extend_from_slice(b"[fetch]\n\twriteCommitGraph = true\n");
+        std::fs::write(&config_path, config)?;
+        let repo = git::open_opts(tmp.path().join("clone"), git::open::Options::isolated())?;
+        assert_eq!(
+            repo.find_remote("origin")?.maintenance_on_fetch(),
+            Options {
+                write_commit_graph: true,
+                write_multi_pack_index: false
+            }
+        );
+        Ok(())
+    }
+}
+
+#[cfg(feature = "blocking-network-client")]
+mod blocking_io {
+    use std::{num::NonZeroU32, path::Path, sync::atomic::AtomicBool};
+
+    use git_features::progress;
+    use git_repository as git;
+    use git_repository::remote::{fetch, fetch::update_refs::Mode, Direction::Fetch};
+    use git_testtools::scripted_fixture_repo_writable;
+
+    fn empty_repo_with_remote(remote_dir: &Path, path: &Path) -> crate::Result<git::Repository> {
+        std::fs::create_dir(path)?;
+        let mut repo = git::init_bare(path)?;
+        let mut config = repo.config_snapshot_mut();
+        config.set_raw_value(
+            "remote",
+            Some("origin"),
+            "url",
+            remote_dir.to_str().expect("valid UTF-8"),
+        )?;
+        config.set_raw_value("remote", Some("origin"), "fetch", "+refs/heads/*:refs/remotes/origin/*")?;
+        drop(config);
+        Ok(repo)
+    }
+
+    fn fetch(repo: &git::Repository, options: fetch::Options) -> crate::Result<fetch::Outcome> {
+        Ok(repo
+            .find_remote("origin")?
+            .connect(Fetch, progress::Discard)?
+            .fetch(&AtomicBool::default(), options)?)
+    }
+
+    #[test]
+    fn only_missing_objects_are_received_and_tracking_branches_are_updated() -> crate::Result {
+        let tmp = scripted_fixture_repo_writable("make_remote_repos.sh")?;
+        let remote_dir = tmp.path().join("base");
+        let remote_repo = git::open_opts(&remote_dir, git::open::Options::isolated())?;
+        let repo = empty_repo_with_remote(&remote_dir, &tmp.path().join("empty"))?;
+
+        let outcome = fetch(&repo, Default::default())?;
+        assert!(outcome.pack.is_some(), "everything is received initially");
+        assert_eq!(
+            outcome.protocol_version,
+            git::protocol::transport::Protocol::V2,
+            "the default protocol version is negotiated"
+        );
+        for remote_ref in remote_repo.references()?.local_branches()? {
+            let remote_ref = remote_ref.expect("valid reference");
+            let tracking = format!("refs/remotes/origin/{}", remote_ref.name().shorten());
+            assert_eq!(repo.find_reference(tracking.as_str())?.id(), remote_ref.id());
+        }
+
+        let outcome = fetch(&repo, Default::default())?;
+        assert!(
+            outcome.pack.is_none(),
+            "there is nothing to receive if we have all objects"
+        );
+        assert!(outcome
+            .update_refs
+            .updates
+            .iter()
+            .all(|update| update.mode == Mode::NoChangeNeeded));
+
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=name", "-c", "user.email=name@example.com"])
+            .args(["commit", "--allow-empty", "-q", "-m", "new"])
+            .current_dir(&remote_dir)
+            .status()?;
+        assert!(status.success());
+        let outcome = fetch(&repo, Default::default())?;
+        assert_eq!(
+            outcome.pack.expect("new commit").index.num_objects,
+            1,
+            "negotiation lets the remote know what we have, so only the new commit is sent"
+        );
+        let head = remote_repo.head()?;
+        let branch = head.referent_name().expect("on a branch").shorten().to_string();
+        let tracking = format!("refs/remotes/origin/{}", branch);
+        assert_eq!(
+            repo.find_reference(tracking.as_str())?.id(),
+            git::open_opts(&remote_dir, git::open::Options::isolated())?.head_id()?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn stale_tracking_branches_are_pruned_as_configured() -> crate::Result {
+        let tmp = scripted_fixture_repo_writable("make_remote_repos.sh")?;
+        let remote_dir = tmp.path().join("base");
+        let mut repo = empty_repo_with_remote(&remote_dir, &tmp.path().join("empty"))?;
+        let outcome = fetch(&repo, Default::default())?;
+        assert!(outcome.prune.is_none(), "pruning isn't configured by default");
+        assert!(repo.try_find_reference("refs/remotes/origin/g")?.is_some());
+
+        let status = std::process::Command::new("git")
+            .args(["branch", "-q", "-D", "g"])
+            .current_dir(&remote_dir)
+            .status()?;
+        assert!(status.success());
+        let mut config = repo.config_snapshot_mut();
+        config.set_raw_value("fetch", None, "prune", "true")?;
+        drop(config);
+
+        let outcome = fetch(
+            &repo,
+            fetch::Options {
+                prune: fetch::Prune::Never,
+                ..Default::default()
+            },
+        )?;
+        assert!(outcome.prune.is_none(), "configuration can be overridden");
+        assert!(repo.try_find_reference("refs/remotes/origin/g")?.is_some());
+
+        let outcome = fetch(&repo, Default::default())?;
+        let deleted: Vec<_> = outcome
+            .prune
+            .as_ref()
+            .expect("fetch.prune is respected")
+            .deleted()
+            .map(|r| r.name.as_bstr().to_string())
+            .collect();
+        assert_eq!(deleted, vec!["refs/remotes/origin/g"]);
+        assert!(repo.try_find_reference("refs/remotes/origin/g")?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn with_depth_the_repository_becomes_shallow() -> crate::Result {
+        let tmp = scripted_fixture_repo_writable("make_remote_repos.sh")?;
+        let remote_dir = tmp.path().join("base");
+        let remote_repo = git::open_opts(&remote_dir, git::open::Options::isolated())?;
+        let repo = empty_repo_with_remote(&remote_dir, &tmp.path().join("empty"))?;
+
+        let outcome = fetch(
+            &repo,
+            fetch::Options {
+                depth: NonZeroU32::new(1),
+                ..Default::default()
+            },
+        )?;
+        assert!(outcome.pack.is_some());
+        let shallow = std::fs::read_to_string(repo.git_dir().join("shallow"))?;
+        let mut tip_commits = std::collections::BTreeSet::new();
+        for update in &outcome.update_refs.updates {
+            let commit = remote_repo
+                .find_object(update.new)?
+                .peel_to_kind(git::object::Kind::Commit)?;
+            tip_commits.insert(commit.id.to_string());
+        }
+        assert_eq!(
+            shallow
+                .lines()
+                .map(ToOwned::to_owned)
+                .collect::<std::collections::BTreeSet<_>>(),
+            tip_commits,
+            "each fetched tip is a shallow commit, with annotated tags peeled to the commit they point to"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async-network-client")]
+mod async_io {
+    use std::{
+        io::{Read, Write},
+        pin::Pin,
+        process::{Command, Stdio},
+        sync::atomic::AtomicBool,
+        task::{Context, Poll},
+    };
+
+    use git_features::progress;
+    use git_repository as git;
+    use git_repository::protocol::{
+        futures_io::{AsyncRead, AsyncWrite},
+        transport::{client, Protocol},
+    };
+    use git_testtools::scripted_fixture_repo_writable;
+
+    /// Blocking IO to act as the async IO of a custom transport.
+    struct BlockingIo<T>(T);
+
+    impl<T: Read + Unpin> AsyncRead for BlockingIo<T> {
+        fn poll_read(mut self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
+            Poll::Ready(self.0.read(buf))
+        }
+    }
+
+    impl<T: Write + Unpin> AsyncWrite for BlockingIo<T> {
+        fn poll_write(mut self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+            Poll::Ready(self.0.write(buf))
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(self.0.flush())
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn fetch_with_a_custom_transport() -> crate::Result {
+        let tmp = scripted_fixture_repo_writable("make_remote_repos.sh")?;
+        let remote_dir = tmp.path().join("base");
+        let path = tmp.path().join("empty");
+        std::fs::create_dir(&path)?;
+        let mut repo = git::init_bare(&path)?;
+        let mut config = repo.config_snapshot_mut();
+        config.set_raw_value(
+            "remote",
+            Some("origin"),
+            "url",
+            remote_dir.to_str().expect("valid UTF-8"),
+        )?;
+        config.set_raw_value("remote", Some("origin"), "fetch", "+refs/heads/*:refs/remotes/origin/*")?;
+        drop(config);
+
+        let mut upload_pack = Command::new("git")
+            .arg("upload-pack")
+            .arg(&remote_dir)
+            .env("GIT_PROTOCOL", "version=2")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let transport = client::git::Connection::new(
+            BlockingIo(upload_pack.stdout.take().expect("piped")),
+            BlockingIo(upload_pack.stdin.take().expect("piped")),
+            Protocol::V2,
+            remote_dir.to_str().expect("valid UTF-8"),
+            None::<(String, Option<u16>)>,
+            client::git::ConnectMode::Process,
+        );
+        let remote = repo.find_remote("origin")?;
+        let outcome = futures_lite::future::block_on(
+            remote
+                .to_connection_with_transport(transport, progress::Discard)
+                .fetch(&AtomicBool::default(), Default::default()),
+        )?;
+        upload_pack.wait()?;
+
+        assert!(outcome.pack.is_some(), "the pack was received asynchronously");
+        assert!(!outcome.update_refs.updates.is_empty());
+        Ok(())
+    }
+}
@@ -2,20 +2,15 @@
 mod blocking_io {
     use crate::remote;
     use git_features::progress;
-    use git_repository as git;
-    use git_repository::remote::Direction::Fetch;
+    use git_repository::remote::{list_refs, Direction::Fetch};
 
     #[test]
     fn all() {
-        for version in [
-            None,
-            Some(git::protocol::transport::Protocol::V2),
-            Some(git::protocol::transport::Protocol::V1),
-        ] {
+        for version in [None, Some(2), Some(1), Some(0)] {
             let mut repo = remote::repo("clone");
             if let Some(version) = version {
                 repo.config_snapshot_mut()
-                    .set_raw_value("protocol", None, "version", (version as u8).to_string().as_str())
+                    .set_raw_value("protocol", None, "version", version.to_string().as_str())
                     .unwrap();
             }
             let remote = repo.find_remote("origin").unwrap();
@@ -24,4 +19,36 @@ mod blocking_io {
             assert_eq!(refs.len(), 14, "it gets all remote refs, independently of the refspec.");
         }
     }
+
+    #[test]
+    fn filtered_by_globs_prefixes_and_short_names() {
+        for version in [2, 1] {
+            let mut repo = remote::repo("clone");
+            repo.config_snapshot_mut()
+                .set_raw_value("protocol", None, "version", version.to_string().as_str())
+                .unwrap();
+            let remote = repo.find_remote("origin").unwrap();
+            let connection = remote.connect(Fetch, progress::Discard).unwrap();
+            let refs = connection
+                .list_refs_with_options(list_refs::Options {
+                    filters: vec!["main".into(), "refs/tags/".into(), "refs/heads/[a-c]".into()],
+                })
+                .unwrap();
+            let mut names: Vec<_> = refs.iter().map(|r| r.unpack().0.to_string()).collect();
+            names.sort();
+            assert_eq!(
+                names,
+                [
+                    "refs/heads/a",
+                    "refs/heads/b",
+                    "refs/heads/c",
+                    "refs/heads/main",
+                    "refs/tags/b-tag",
+                    "refs/tags/i-tag"
+                ],
+                "protocol version {}",
+                version
+            );
+        }
+    }
 }
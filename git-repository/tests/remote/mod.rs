@@ -11,4 +11,6 @@ pub(crate) fn cow_str(s: &str) -> Cow<str> {
     Cow::Borrowed(s)
 }
 
+mod fetch;
 mod list_refs;
+mod prune;
@@ -0,0 +1,169 @@
+use git_repository as git;
+use git_repository::remote::{prune, Direction::Fetch};
+use git_testtools::scripted_fixture_repo_writable;
+
+fn clone_rw() -> crate::Result<(git::Repository, tempfile::TempDir)> {
+    let tmp = scripted_fixture_repo_writable("make_remote_repos.sh")?;
+    let repo = git::open_opts(tmp.path().join("clone"), git::open::Options::isolated())?;
+    Ok((repo, tmp))
+}
+
+fn remote_branches(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| format!("refs/heads/{}", name)).collect()
+}
+
+fn names(outcome: &prune::Outcome, status: prune::Status) -> Vec<String> {
+    outcome
+        .refs
+        .iter()
+        .filter(|r| r.status == status)
+        .map(|r| r.name.as_bstr().to_string())
+        .collect()
+}
+
+#[test]
+fn stale_tracking_refs_are_deleted_and_others_are_kept() -> crate::Result {
+    let (repo, _tmp) = clone_rw()?;
+    let remote = repo.find_remote("origin")?;
+    let remote_refs = remote_branches(&["main", "a", "b", "c", "d", "e", "f", "g", "h"]);
+    let outcome = remote.prune(
+        remote_refs.iter().map(|name| name.as_str().into()),
+        prune::Options::default(),
+    )?;
+
+    assert_eq!(
+        names(&outcome, prune::Status::Deleted),
+        ["refs/remotes/origin/i", "refs/remotes/origin/j"]
+    );
+    assert_eq!(
+        names(&outcome, prune::Status::Kept).len(),
+        9,
+        "the symbolic HEAD reference isn't considered"
+    );
+    let deleted = outcome.deleted().next().expect("present");
+    assert_eq!(deleted.remote_name, "refs/heads/i");
+
+    assert!(repo.try_find_reference("refs/remotes/origin/i")?.is_none());
+    assert!(repo.try_find_reference("refs/remotes/origin/j")?.is_none());
+    assert!(repo.try_find_reference("refs/remotes/origin/h")?.is_some());
+    assert!(
+        repo.try_find_reference("refs/tags/i-tag")?.is_some(),
+        "tags are only pruned if requested"
+    );
+    Ok(())
+}
+
+#[test]
+fn dry_run_does_not_delete_anything() -> crate::Result {
+    let (repo, _tmp) = clone_rw()?;
+    let remote = repo.find_remote("origin")?;
+    let outcome = remote.prune(
+        std::iter::empty(),
+        prune::Options {
+            dry_run: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(names(&outcome, prune::Status::Deleted).len(), 11);
+    assert!(repo.try_find_reference("refs/remotes/origin/main")?.is_some());
+    Ok(())
+}
+
+#[test]
+fn negative_refspecs_protect_local_refs() -> crate::Result {
+    let (repo, _tmp) = clone_rw()?;
+    let remote = repo.find_remote("origin")?.with_refspec("^refs/heads/j", Fetch)?;
+    let remote_refs = remote_branches(&["main", "a", "b", "c", "d", "e", "f", "g", "h", "i"]);
+    let outcome = remote.prune(
+        remote_refs.iter().map(|name| name.as_str().into()),
+        prune::Options::default(),
+    )?;
+
+    assert_eq!(names(&outcome, prune::Status::Excluded), ["refs/remotes/origin/j"]);
+    assert!(outcome.deleted().next().is_none());
+    assert!(repo.try_find_reference("refs/remotes/origin/j")?.is_some());
+    Ok(())
+}
+
+#[test]
+fn tags_are_pruned_if_requested() -> crate::Result {
+    let (repo, _tmp) = clone_rw()?;
+    let remote = repo.find_remote("origin")?;
+    let mut remote_refs = remote_branches(&["main", "a", "b", "c", "d", "e", "f", "g", "h", "i", "j"]);
+    remote_refs.push("refs/tags/b-tag".into());
+    let outcome = remote.prune(
+        remote_refs.iter().map(|name| name.as_str().into()),
+        prune::Options {
+            prune_tags: true,
+            ..Default::default()
+        },
+    )?;
+
+    assert_eq!(names(&outcome, prune::Status::Deleted), ["refs/tags/i-tag"]);
+    assert!(repo.try_find_reference("refs/tags/i-tag")?.is_none());
+    assert!(repo.try_find_reference("refs/tags/b-tag")?.is_some());
+    Ok(())
+}
+
+#[test]
+fn prune_on_fetch_is_configured_per_remote_or_globally() -> crate::Result {
+    let (mut repo, _tmp) = clone_rw()?;
+    assert_eq!(
+        repo.find_remote("origin")?.prune_on_fetch(),
+        None,
+        "pruning is off by default"
+    );
+
+    repo.config_snapshot_mut()
+        .set_raw_value("fetch", None, "prune", "true")?;
+    assert_eq!(
+        repo.find_remote("origin")?.prune_on_fetch(),
+        Some(prune::Options::default())
+    );
+
+    {
+        let mut config = repo.config_snapshot_mut();
+        config.set_raw_value("fetch", None, "pruneTags", "true")?;
+        config.set_raw_value("remote", Some("origin"), "prune", "false")?;
+    }
+    assert_eq!(
+        repo.find_remote("origin")?.prune_on_fetch(),
+        None,
+        "the remote configuration overrides the global one"
+    );
+    assert_eq!(
+        repo.find_remote("myself")?.prune_on_fetch(),
+        Some(prune::Options {
+            prune_tags: true,
+            dry_run: false
+        })
+    );
+    Ok(())
+}
+
+#[cfg(feature = "blocking-network-client")]
+mod blocking_io {
+    use git_features::progress;
+    use git_repository::remote::{prune, Direction::Fetch};
+
+    #[test]
+    fn refs_missing_on_the_remote_are_deleted() -> crate::Result {
+        let (repo, _tmp) = super::clone_rw()?;
+        let id = repo.head_id()?.detach();
+        repo.reference(
+            "refs/remotes/origin/gone",
+            id,
+            git_repository::refs::transaction::PreviousValue::MustNotExist,
+            "create stale tracking branch",
+        )?;
+
+        let remote = repo.find_remote("origin")?;
+        let outcome = remote.connect(Fetch, progress::Discard)?.prune(Default::default())?;
+        assert_eq!(
+            super::names(&outcome, prune::Status::Deleted),
+            ["refs/remotes/origin/gone"]
+        );
+        assert!(repo.try_find_reference("refs/remotes/origin/gone")?.is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+use git_repository as git;
+use git_repository::{
+    check::{Action, Finding, Options, Severity},
+    objs::tree::{build::Builder, EntryMode},
+};
+
+fn check(repo: &git::Repository, options: Options) -> crate::Result<(Vec<Finding>, git::check::Outcome)> {
+    let mut findings = Vec::new();
+    let outcome = repo.check(options, |finding| {
+        findings.push(finding);
+        Action::Continue
+    })?;
+    Ok((findings, outcome))
+}
+
+fn loose_path(repo: &git::Repository, id: git::ObjectId) -> std::path::PathBuf {
+    let hex = id.to_hex().to_string();
+    repo.git_dir().join("objects").join(&hex[..2]).join(&hex[2..])
+}
+
+#[test]
+fn healthy_repository_has_no_findings() -> crate::Result {
+    let repo = crate::basic_repo()?;
+    let (findings, outcome) = check(&repo, Options::default())?;
+    assert_eq!(findings, vec![]);
+    assert!(outcome.objects > 0);
+    assert!(outcome.references > 0, "HEAD and the main branch");
+    assert!(!outcome.cancelled);
+    Ok(())
+}
+
+#[test]
+fn dangling_and_missing_objects() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let blob = repo
+        .write_object(git::objs::Blob {
+            data: b"dangling".to_vec(),
+        })?
+        .detach();
+    let missing = git::ObjectId::from_hex(b"0000000000000000000000000000000000000001")?;
+    let tree = repo
+        .write_object(Builder::new().entry(EntryMode::Blob, "file", missing).build()?)?
+        .detach();
+
+    let (findings, outcome) = check(&repo, Options::default())?;
+    assert_eq!(
+        findings,
+        vec![
+            Finding::MissingObject {
+                id: missing,
+                referenced_by: tree
+            },
+            Finding::Dangling {
+                id: blob.min(tree),
+                kind: if blob < tree {
+                    git::object::Kind::Blob
+                } else {
+                    git::object::Kind::Tree
+                }
+            },
+            Finding::Dangling {
+                id: blob.max(tree),
+                kind: if blob < tree {
+                    git::object::Kind::Tree
+                } else {
+                    git::object::Kind::Blob
+                }
+            },
+        ]
+    );
+    assert_eq!((outcome.errors, outcome.warnings, outcome.infos), (1, 0, 2));
+
+    let (findings, _) = check(
+        &repo,
+        Options {
+            connectivity: false,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        findings,
+        vec![],
+        "connectivity is needed to find missing or dangling objects"
+    );
+    Ok(())
+}
+
+#[test]
+fn corrupt_objects() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let a = repo.write_object(git::objs::Blob { data: b"a".to_vec() })?.detach();
+    let b = repo.write_object(git::objs::Blob { data: b"b".to_vec() })?.detach();
+    let c = repo.write_object(git::objs::Blob { data: b"c".to_vec() })?.detach();
+    for id in [b, c] {
+        let path = loose_path(&repo, id);
+        let mut perm = std::fs::metadata(&path)?.permissions();
+        #[allow(clippy::permissions_set_readonly_false)]
+        perm.set_readonly(false);
+        std::fs::set_permissions(&path, perm)?;
+    }
+    std::fs::copy(loose_path(&repo, a), loose_path(&repo, b))?;
+    std::fs::write(loose_path(&repo, c), b"garbage")?;
+
+    let (findings, outcome) = check(
+        &repo,
+        Options {
+            connectivity: false,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.errors, 2);
+    assert!(findings.contains(&Finding::HashMismatch {
+        id: b,
+        kind: git::object::Kind::Blob,
+        actual: a
+    }));
+    assert!(findings
+        .iter()
+        .any(|f| matches!(f, Finding::Unreadable { id, .. } if *id == c)));
+    assert!(findings.iter().all(|f| f.severity() == Severity::Error));
+    Ok(())
+}
+
+#[test]
+fn malformed_trees_in_strict_mode() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let blob = repo.write_object(git::objs::Blob { data: b"a".to_vec() })?.detach();
+    let mut buf = Vec::new();
+    for name in ["b", "a"] {
+        buf.extend_from_slice(format!("100644 {}\0", name).as_bytes());
+        buf.extend_from_slice(blob.as_bytes());
+    }
+    let tree = git_odb::Write::write_buf(&repo.objects, git::object::Kind::Tree, &buf)?;
+
+    let (findings, _) = check(
+        &repo,
+        Options {
+            connectivity: false,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        findings,
+        vec![Finding::Malformed {
+            id: tree,
+            kind: git::object::Kind::Tree,
+            message: "Tree entries are not sorted".into()
+        }]
+    );
+    assert_eq!(findings[0].severity(), Severity::Warning);
+
+    let (findings, _) = check(
+        &repo,
+        Options {
+            connectivity: false,
+            strict: false,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(findings, vec![]);
+    Ok(())
+}
+
+#[test]
+fn cancellation() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    for data in [&b"a"[..], b"b"] {
+        repo.write_object(git::objs::Blob { data: data.to_vec() })?;
+    }
+    let mut count = 0;
+    let outcome = repo.check(Options::default(), |_| {
+        count += 1;
+        Action::Cancel
+    })?;
+    assert_eq!(count, 1);
+    assert!(outcome.cancelled);
+    assert_eq!(outcome.infos, 1);
+    Ok(())
+}
@@ -90,7 +90,7 @@ fn access_values_and_identity() {
             )
             .set("GIT_AUTHOR_NAME", "author")
             .set("GIT_AUTHOR_EMAIL", "author@email")
-            .set("GIT_AUTHOR_DATE", "1979-02-26 18:30:00")
+            .set("GIT_AUTHOR_DATE", "1979-02-26 18:30:00 +0030")
             .set("GIT_CONFIG_COUNT", "1")
             .set("GIT_CONFIG_KEY_0", "include.path")
             .set("GIT_CONFIG_VALUE_0", work_dir.join("c.config").display().to_string());
@@ -107,26 +107,57 @@ fn access_values_and_identity() {
         )
         .unwrap();
 
+        let author = repo.author().expect("configured");
         assert_eq!(
-            repo.author(),
-            Some(git_actor::SignatureRef {
+            author.signature,
+            git_actor::SignatureRef {
                 name: "author".into(),
                 email: "author@email".into(),
                 time: git_date::Time {
-                    seconds_since_unix_epoch: 42,
+                    seconds_since_unix_epoch: 288900000,
                     offset_in_seconds: 1800,
                     sign: git_date::time::Sign::Plus
                 }
-            }),
-            "the only parsesable marker time we know right now, indicating time parse success"
+            },
+            "the date is parsed from the environment"
+        );
+        assert_eq!(
+            (author.name, author.email, author.time),
+            (
+                git::identity::Source::Environment {
+                    variable: "GIT_AUTHOR_NAME"
+                },
+                git::identity::Source::Environment {
+                    variable: "GIT_AUTHOR_EMAIL"
+                },
+                git::identity::Source::Environment {
+                    variable: "GIT_AUTHOR_DATE"
+                }
+            )
         );
+        let committer = repo.committer().expect("configured");
         assert_eq!(
-            repo.committer(),
-            Some(git_actor::SignatureRef {
+            committer.signature,
+            git_actor::SignatureRef {
                 name: "committer".into(),
                 email: "committer@email".into(),
                 time: git_date::Time::now_local_or_utc()
-            })
+            }
+        );
+        assert_eq!(
+            (committer.name, committer.email, committer.time),
+            (
+                git::identity::Source::Configuration {
+                    key: "committer.name",
+                    source: git::config::Source::Local
+                },
+                git::identity::Source::Configuration {
+                    key: "committer.email",
+                    source: git::config::Source::Local
+                },
+                git::identity::Source::CurrentTime
+            ),
+            "values from included files are attributed to the including file"
         );
         assert_eq!(
             repo.user_default(),
@@ -274,3 +305,303 @@ mod config_section_mut {
         Ok(())
     }
 }
+
+#[cfg(feature = "git-credentials")]
+mod credential_helpers {
+    use std::convert::TryFrom;
+
+    use git_repository as git;
+
+    use crate::named_repo;
+
+    fn helpers(repo: &git::Repository, url: &str) -> Vec<git::credentials::Program> {
+        repo.config_snapshot()
+            .credential_helpers(&git::url::parse(url.into()).expect("valid url"))
+            .programs
+    }
+
+    #[test]
+    fn in_order_with_url_scoped_sections_and_reset() -> crate::Result {
+        let mut repo = named_repo("make_config_repo.sh")?;
+        {
+            let mut config = repo.config_snapshot_mut();
+            for (subsection, value) in [
+                (None, "first"),
+                (Some("https://example.com/org"), ""),
+                (Some("https://*.example.com"), "wildcard"),
+                (Some("https://example.com/org/"), "!scoped"),
+                (Some("ssh://example.com"), "ssh"),
+            ] {
+                config.section_mut_or_create_new("credential", subsection)?.push(
+                    git::config::parse::section::Key::try_from("helper")?,
+                    Some(value.into()),
+                );
+            }
+            config.set_raw_value("credential", Some("https://example.com"), "useHttpPath", "true")?;
+        }
+
+        let program = git::credentials::Program::from_custom_definition;
+        assert_eq!(
+            helpers(&repo, "https://example.com/org/repo.git"),
+            vec![program("!scoped")],
+            "the empty value resets previous helpers, and paths match by prefix"
+        );
+        assert_eq!(
+            helpers(&repo, "https://example.com/organization"),
+            vec![program("first")],
+            "path prefixes end at slashes"
+        );
+        assert_eq!(
+            helpers(&repo, "https://sub.example.com/org"),
+            vec![program("first"), program("wildcard")]
+        );
+        assert_eq!(helpers(&repo, "http://example.com/org"), vec![program("first")]);
+        assert!(
+            repo.config_snapshot()
+                .credential_helpers(&git::url::parse("https://example.com".into())?)
+                .use_http_path
+        );
+        Ok(())
+    }
+}
+
+#[cfg(feature = "blocking-http-transport")]
+mod http_options {
+    use std::{convert::TryFrom, time::Duration};
+
+    use git_repository as git;
+
+    use crate::named_repo;
+
+    #[test]
+    fn most_specific_url_wins_and_extra_headers_accumulate() -> crate::Result {
+        let mut repo = named_repo("make_config_repo.sh")?;
+        {
+            let mut config = repo.config_snapshot_mut();
+            config.set_raw_value("http", Some("https://example.com/org"), "proxy", "http://specific")?;
+            config.set_raw_value("http", None, "proxy", "http://general")?;
+            config.set_raw_value("http", None, "sslVerify", "false")?;
+            config.set_raw_value("http", None, "lowSpeedTime", "5")?;
+            config.set_raw_value("http", Some("https://example.com"), "lowSpeedLimit", "0")?;
+            config.set_raw_value("http", None, "followRedirects", "false")?;
+            config.set_raw_value("http", Some("https://example.com/org"), "followRedirects", "true")?;
+            for (subsection, value) in [
+                (None, "X-Dropped: 1"),
+                (Some("https://example.com"), ""),
+                (Some("https://example.com/org/"), "X-First: 1"),
+                (Some("ssh://example.com"), "X-Ssh: 1"),
+            ] {
+                config.section_mut_or_create_new("http", subsection)?.push(
+                    git::config::parse::section::Key::try_from("extraHeader")?,
+                    Some(value.into()),
+                );
+            }
+        }
+
+        let options = repo
+            .config_snapshot()
+            .http_options(&git::url::parse("https://example.com/org/repo.git".into())?);
+        assert_eq!(
+            options.proxy.as_deref(),
+            Some("http://specific"),
+            "url-scoped values win over later general ones"
+        );
+        assert!(!options.ssl_verify);
+        assert_eq!(options.low_speed_limit_bytes_per_second, 0);
+        assert_eq!(options.low_speed_time, Duration::from_secs(5));
+        assert_eq!(format!("{:?}", options.follow_redirects), "All");
+        assert_eq!(
+            options.extra_headers,
+            vec!["X-First: 1"],
+            "the empty value resets previous headers, and non-matching sections are ignored"
+        );
+
+        let options = repo
+            .config_snapshot()
+            .http_options(&git::url::parse("https://other.example.com".into())?);
+        assert_eq!(options.proxy.as_deref(), Some("http://general"));
+        assert_eq!(options.low_speed_limit_bytes_per_second, 1024, "the default is used");
+        assert_eq!(options.extra_headers, vec!["X-Dropped: 1"]);
+        assert_eq!(format!("{:?}", options.follow_redirects), "None");
+        Ok(())
+    }
+}
+
+mod fsync {
+    use git_repository as git;
+
+    fn repo_with_config(config: &str) -> crate::Result<(git::Repository, tempfile::TempDir)> {
+        let dir = tempfile::tempdir()?;
+        let git_dir = git::init(dir.path())?.git_dir().to_owned();
+        let mut file = std::fs::OpenOptions::new().append(true).open(git_dir.join("config"))?;
+        std::io::Write::write_all(&mut file, config.as_bytes())?;
+        Ok((git::open_opts(dir.path(), crate::restricted())?, dir))
+    }
+
+    #[test]
+    fn only_packs_are_flushed_by_default() -> crate::Result {
+        let (repo, _keep) = repo_with_config("")?;
+        assert_eq!(
+            repo.fsync_policy(),
+            git::fsync::Policy {
+                packs: true,
+                ..git::fsync::Policy::none()
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn components_are_added_and_removed_and_the_method_is_configurable() -> crate::Result {
+        let (repo, _keep) = repo_with_config(
+            "[core]\n\tfsync = loose-object,reference,-pack,unknown\n\tfsyncMethod = writeout-only\n",
+        )?;
+        assert_eq!(
+            repo.fsync_policy(),
+            git::fsync::Policy {
+                loose_objects: true,
+                packs: false,
+                refs: true,
+                index: false,
+                method: git::fsync::Method::WriteoutOnly,
+            },
+            "packs are flushed by default unless removed, and unknown components are ignored"
+        );
+
+        let (repo, _keep) = repo_with_config("[core]\n\tfsync = all\n\tfsync = none,index\n")?;
+        assert_eq!(
+            repo.fsync_policy(),
+            git::fsync::Policy {
+                index: true,
+                ..git::fsync::Policy::none()
+            },
+            "the last value wins, and 'none' resets the defaults"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn objects_and_references_can_be_written_while_flushing_everything() -> crate::Result {
+        let (repo, _keep) = repo_with_config("[core]\n\tfsync = all\n")?;
+        assert_eq!(repo.fsync_policy(), git::fsync::Policy::everything());
+        let empty_tree = repo.write_object(&git::objs::Tree::empty())?;
+        let author = git::actor::Signature::empty();
+        let commit = repo.commit_as(
+            "HEAD",
+            author.to_ref(),
+            author.to_ref(),
+            "initial",
+            empty_tree,
+            git::commit::NO_PARENT_IDS,
+        )?;
+        assert_eq!(repo.head_id()?, commit);
+        Ok(())
+    }
+}
+
+mod identity {
+    use git_repository as git;
+    use git_sec::{Access, Permission};
+    use git_testtools::Env;
+    use serial_test::serial;
+
+    fn repo_with_config(
+        config: &str,
+        options: git::open::Options,
+    ) -> crate::Result<(git::Repository, tempfile::TempDir)> {
+        let dir = tempfile::tempdir()?;
+        let git_dir = git::init(dir.path())?.git_dir().to_owned();
+        let mut file = std::fs::OpenOptions::new().append(true).open(git_dir.join("config"))?;
+        std::io::Write::write_all(&mut file, config.as_bytes())?;
+        Ok((git::open_opts(dir.path(), options)?, dir))
+    }
+
+    #[test]
+    #[serial]
+    fn environment_variables_override_configuration() -> crate::Result {
+        let _env = Env::new()
+            .set("GIT_COMMITTER_NAME", "env-committer")
+            .set("GIT_COMMITTER_DATE", "@1660874655 +0800");
+        let (repo, _keep) = repo_with_config(
+            "[user]\n\tname = user\n\temail = user@email\n[committer]\n\tname = committer\n",
+            git::open::Options::isolated().permissions(git::Permissions {
+                env: git::permissions::Environment {
+                    xdg_config_home: Access::resource(Permission::Deny),
+                    home: Access::resource(Permission::Deny),
+                    ..git::permissions::Environment::all()
+                },
+                ..git::Permissions::isolated()
+            }),
+        )?;
+
+        let committer = repo.committer()?;
+        assert_eq!(committer.signature.name, "env-committer");
+        assert_eq!(
+            committer.signature.email, "user@email",
+            "the user email is the fallback"
+        );
+        assert_eq!(committer.signature.time, git::actor::Time::new(1660874655, 28800));
+        assert_eq!(
+            committer.email,
+            git::identity::Source::Configuration {
+                key: "user.email",
+                source: git::config::Source::Local
+            }
+        );
+
+        let author = repo.author()?;
+        assert_eq!(author.signature.name, "user", "it's unaffected by committer variables");
+        assert_eq!(author.time, git::identity::Source::CurrentTime);
+        Ok(())
+    }
+
+    #[test]
+    fn use_config_only_prevents_guessing() -> crate::Result {
+        let (repo, _keep) = repo_with_config("[user]\n\tuseConfigOnly = true\n", git::open::Options::isolated())?;
+        assert_eq!(
+            repo.author().unwrap_err().to_string(),
+            "No name was given for the author and auto-detection is disabled by 'user.useConfigOnly'"
+        );
+        assert!(
+            matches!(
+                repo.committer(),
+                Err(git::identity::Error::AutoDetectionDisabled {
+                    role: "committer",
+                    field: "name"
+                })
+            ),
+            "the same is true for the committer"
+        );
+
+        let empty_tree = repo.write_object(&git::objs::Tree::empty())?;
+        assert!(
+            matches!(
+                repo.commit("HEAD", "initial", empty_tree, git::commit::NO_PARENT_IDS),
+                Err(git::commit::Error::Identity(_))
+            ),
+            "commits can't be created without identity"
+        );
+        assert!(
+            !repo.committer_or_default().name.is_empty(),
+            "reflogs can still be written with a guessed or default identity"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn commit_uses_the_configured_identity() -> crate::Result {
+        let (repo, _keep) = repo_with_config(
+            "[user]\n\tuseConfigOnly = true\n\tname = user\n\temail = user@email\n[author]\n\temail = author@email\n",
+            git::open::Options::isolated(),
+        )?;
+        let empty_tree = repo.write_object(&git::objs::Tree::empty())?;
+        let commit = repo.commit("HEAD", "initial", empty_tree, git::commit::NO_PARENT_IDS)?;
+        let commit = commit.object()?.into_commit();
+        let commit = commit.decode()?;
+        assert_eq!(commit.author.name, "user");
+        assert_eq!(commit.author.email, "author@email");
+        assert_eq!(commit.committer.name, "user");
+        assert_eq!(commit.committer.email, "user@email");
+        Ok(())
+    }
+}
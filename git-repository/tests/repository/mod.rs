@@ -1,16 +1,18 @@
 use git_repository::Repository;
 
+mod check;
 mod config;
 mod object;
 mod open;
 mod reference;
 mod remote;
+mod shortlog;
 mod state;
 mod worktree;
 
 #[test]
 fn size_in_memory() {
-    let expected = [744, 760];
+    let expected = [672, 688];
     let actual_size = std::mem::size_of::<Repository>();
     assert!(
         expected.contains(&actual_size),
@@ -86,6 +86,13 @@ mod find {
                 }
                 assert_eq!(commit.try_object()?.expect("exists").kind, git_object::Kind::Commit,);
             }
+            let stats = repo.cache_statistics();
+            match round {
+                1 => assert_eq!(stats.object_cache, Default::default(), "no cache, no statistics"),
+                2 => assert!(stats.object_cache.hits > 0, "repeated requests are served by the cache"),
+                _ => unreachable!("BUG"),
+            }
+            repo.reset_cache_statistics();
         }
         Ok(())
     }
@@ -111,6 +118,246 @@ mod find {
         );
         Ok(())
     }
+
+    #[test]
+    fn headers_provide_kind_and_size_without_decoding() -> crate::Result {
+        let repo = basic_repo()?;
+        for commit_id in repo.head()?.peeled()?.id().expect("born").ancestors().all()? {
+            let commit = commit_id?.object()?;
+            let header = repo.find_header(commit.id)?;
+            assert_eq!(header.kind(), commit.kind);
+            assert_eq!(header.size(), commit.data.len() as u64);
+        }
+
+        let empty_tree = git::hash::ObjectId::empty_tree(repo.object_hash());
+        assert_eq!(
+            repo.find_header(empty_tree)?.size(),
+            0,
+            "the empty tree is special-cased here as well"
+        );
+
+        let missing_id = git::hash::ObjectId::null(repo.object_hash());
+        assert!(repo.try_find_header(missing_id)?.is_none());
+        assert!(matches!(
+            repo.find_header(missing_id).expect_err("missing"),
+            git::object::find::existing::Error::NotFound { .. }
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn corrupt_objects_are_distinguished_from_missing_ones() -> crate::Result {
+        let (tmp, repo) = crate::repository::object::empty_bare_repo()?;
+        let id = repo.write_blob("content")?.detach();
+        let missing_id = repo.write_blob("missing")?.detach();
+        for id in [id, missing_id] {
+            let hex = id.to_hex().to_string();
+            let path = tmp.path().join("objects").join(&hex[..2]).join(&hex[2..]);
+            std::fs::remove_file(&path)?;
+            if id != missing_id {
+                std::fs::write(path, b"garbage")?;
+            }
+        }
+
+        assert!(repo.try_find_object(missing_id)?.is_none());
+        let err = repo.try_find_object(id).expect_err("object is present but corrupt");
+        assert!(err.is_corrupt());
+        assert!(matches!(err, git::object::find::Error::Corrupt { id: actual, .. } if actual == id));
+
+        match repo.find_object(id).expect_err("corrupt") {
+            git::object::find::existing::Error::Find(err) => assert!(err.is_corrupt()),
+            err => panic!("unexpected error: {:?}", err),
+        }
+        assert!(matches!(
+            repo.find_object(missing_id).expect_err("missing"),
+            git::object::find::existing::Error::NotFound { .. }
+        ));
+        Ok(())
+    }
+}
+
+mod objects_iter {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use git_repository as git;
+
+    use crate::basic_repo;
+
+    #[test]
+    fn unsorted_sorted_and_with_location() -> crate::Result {
+        let repo = basic_repo()?;
+        let unsorted = repo
+            .objects_iter(Default::default())?
+            .map(|item| item.map(|item| item.id))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert!(!unsorted.is_empty());
+
+        let sorted = repo
+            .objects_iter(git::object::iter::Options {
+                sorted: true,
+                with_location: true,
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        assert!(
+            sorted.windows(2).all(|w| w[0].id <= w[1].id),
+            "objects are sorted by id"
+        );
+        let mut expected = unsorted;
+        expected.sort();
+        assert_eq!(sorted.iter().map(|item| item.id).collect::<Vec<_>>(), expected);
+
+        for item in sorted {
+            match item.location.expect("location was requested") {
+                git::object::iter::Location::Loose { path } => assert!(path.is_file()),
+                git::object::iter::Location::Pack { .. } => unreachable!("the fixture has no packs"),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn chunks_can_be_processed_in_parallel() -> crate::Result {
+        let repo = basic_repo()?;
+        let expected = repo.objects_iter(Default::default())?.count();
+        let seen = AtomicUsize::default();
+        git_features::parallel::in_parallel(
+            repo.objects_iter(Default::default())?.chunks(3),
+            None,
+            |_| (),
+            |chunk, _| {
+                chunk.map(|chunk| {
+                    seen.fetch_add(chunk.len(), Ordering::SeqCst);
+                })
+            },
+            git_features::parallel::reduce::IdentityWithResult::<(), git::object::iter::Error>::default(),
+        )?;
+        assert_eq!(seen.into_inner(), expected, "all objects are seen exactly once");
+        Ok(())
+    }
+}
+
+mod pin_objects {
+    use std::{io::Write, process::Command, process::Stdio};
+
+    use crate::repository::object::empty_bare_repo;
+
+    #[test]
+    fn new_packs_are_ignored_until_unpinned() -> crate::Result {
+        let (tmp, mut repo) = empty_bare_repo()?;
+        assert!(!repo.objects_are_stale()?, "unpinned objects are never stale");
+        repo.pin_objects()?;
+        assert!(!repo.objects_are_stale()?);
+
+        let id = repo.write_blob("hello")?.detach();
+        let mut child = Command::new("git")
+            .arg("-C")
+            .arg(tmp.path())
+            .args(["pack-objects", "-q", "objects/pack/pack"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()?;
+        writeln!(child.stdin.take().expect("piped"), "{}", id)?;
+        assert!(child.wait()?.success(), "git should work");
+        let hex = id.to_hex().to_string();
+        std::fs::remove_file(tmp.path().join("objects").join(&hex[..2]).join(&hex[2..]))?;
+
+        assert!(repo.objects_are_stale()?, "a pack was added");
+        assert!(
+            repo.try_find_object(id)?.is_none(),
+            "the new pack isn't seen while pinned"
+        );
+        assert!(
+            repo.clone().try_find_object(id)?.is_none(),
+            "clones use the same snapshot"
+        );
+
+        repo.unpin_objects();
+        assert!(repo.try_find_object(id)?.is_some(), "now the new pack is picked up");
+        Ok(())
+    }
+}
+
+mod replacements {
+    use git_repository as git;
+    use git_repository::open::ReplacementObjects;
+
+    fn repo(name: &str, replacements: ReplacementObjects) -> crate::Result<git::Repository> {
+        let dir = git_testtools::scripted_fixture_repo_read_only("make_replacement_objects_repo.sh")?;
+        Ok(git::open_opts(
+            dir.join(name),
+            crate::restricted().replacement_objects(replacements),
+        )?)
+    }
+
+    fn file_content_and_history_len(repo: &git::Repository) -> crate::Result<(String, usize)> {
+        let head_id = repo.head_id()?;
+        let tree = head_id.object()?.into_commit().tree()?;
+        let blob_id = tree.lookup_path(Some("file"))?.expect("present").oid;
+        let content = repo.find_object(blob_id)?.data.clone();
+        Ok((String::from_utf8(content)?, head_id.ancestors().all()?.count()))
+    }
+
+    #[test]
+    fn are_used_transparently_by_default() -> crate::Result {
+        let repo = repo(
+            "with-replacements",
+            ReplacementObjects::UseWithEnvironmentRefPrefixOrDefault {
+                allow_disable_via_environment: false,
+            },
+        )?;
+        assert_eq!(repo.replacements().count(), 2, "a replaced blob and a grafted commit");
+        assert!(
+            repo.replacements()
+                .zip(repo.replacements().skip(1))
+                .all(|(a, b)| a.0 < b.0),
+            "replacements are sorted by the original id"
+        );
+        assert_eq!(
+            file_content_and_history_len(&repo)?,
+            ("replaced\n".into(), 2),
+            "the blob is replaced and the graft cuts history short"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_be_disabled() -> crate::Result {
+        let repo = repo("with-replacements", ReplacementObjects::Disable)?;
+        assert_eq!(repo.replacements().count(), 0);
+        assert_eq!(file_content_and_history_len(&repo)?, ("3\n".into(), 3));
+        Ok(())
+    }
+
+    #[test]
+    fn can_be_ignored_on_the_object_database_handle() -> crate::Result {
+        let mut repo = repo(
+            "with-replacements",
+            ReplacementObjects::UseWithEnvironmentRefPrefixOrDefault {
+                allow_disable_via_environment: false,
+            },
+        )?;
+        repo.objects.ignore_replacements = true;
+        assert_eq!(repo.replacements().count(), 2, "replacements are still known");
+        assert_eq!(file_content_and_history_len(&repo)?, ("3\n".into(), 3));
+        Ok(())
+    }
+
+    #[test]
+    fn are_disabled_by_configuration() -> crate::Result {
+        let repo = repo(
+            "disabled-by-config",
+            ReplacementObjects::UseWithEnvironmentRefPrefixOrDefault {
+                allow_disable_via_environment: false,
+            },
+        )?;
+        assert_eq!(
+            repo.replacements().count(),
+            0,
+            "core.useReplaceRefs = false turns them off"
+        );
+        assert_eq!(file_content_and_history_len(&repo)?, ("3\n".into(), 3));
+        Ok(())
+    }
 }
 
 mod tag {
@@ -153,7 +400,7 @@ mod commit {
         let empty_tree_id = repo.write_object(&git::objs::Tree::empty()).unwrap().detach();
         let author = git::actor::Signature::empty();
         let err = repo
-            .commit(
+            .commit_as(
                 "HEAD",
                 author.to_ref(),
                 author.to_ref(),
@@ -175,7 +422,7 @@ mod commit {
         let repo = git::init(&tmp)?;
         let empty_tree_id = repo.write_object(&git::objs::Tree::empty())?;
         let author = git::actor::Signature::empty();
-        let commit_id = repo.commit(
+        let commit_id = repo.commit_as(
             "HEAD",
             author.to_ref(),
             author.to_ref(),
@@ -219,7 +466,7 @@ mod commit {
             "try and non-try work the same"
         );
         let author = git::actor::Signature::empty();
-        let first_commit_id = repo.commit(
+        let first_commit_id = repo.commit_as(
             "HEAD",
             author.to_ref(),
             author.to_ref(),
@@ -249,7 +496,7 @@ mod commit {
         let current_commit = repo.head()?.into_fully_peeled_id().expect("born")?;
         assert_eq!(current_commit, first_commit_id, "the commit was set");
 
-        let second_commit_id = repo.commit(
+        let second_commit_id = repo.commit_as(
             "refs/heads/new-branch",
             author.to_ref(),
             author.to_ref(),
@@ -289,6 +536,7 @@ fn empty_bare_repo() -> crate::Result<(tempfile::TempDir, git::Repository)> {
         git::create::Options {
             bare: true,
             fs_capabilities: None,
+            object_hash: git::hash::Kind::Sha1,
         },
         git::open::Options::isolated(),
     )?
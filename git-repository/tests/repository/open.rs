@@ -43,3 +43,223 @@ mod submodules {
         .to_thread_local())
     }
 }
+
+mod object_format {
+    use git_repository as git;
+
+    #[test]
+    fn a_compat_object_format_is_refused() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let repo = git::init_bare(tmp.path())?;
+        let config_path = repo.git_dir().join("config");
+        let mut config = std::fs::read_to_string(&config_path)?
+            .replace("repositoryformatversion = 0", "repositoryformatversion = 1");
+        config.push_str("[extensions]\n\tcompatObjectFormat = sha256\n");
+        std::fs::write(config_path, config)?;
+
+        let err = git::open_opts(tmp.path(), crate::restricted()).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                git::open::Error::Config(git::config::Error::UnsupportedCompatObjectFormat { .. })
+            ),
+            "we can't keep the mapping between object formats up to date, so shouldn't write objects at all"
+        );
+        Ok(())
+    }
+}
+
+mod overrides {
+    use git_repository as git;
+
+    #[test]
+    fn config_overrides_take_effect_while_opening() -> crate::Result {
+        let repo_path = git_testtools::scripted_fixture_repo_read_only("make_basic_repo.sh")?;
+        let repo = git::open_opts(&repo_path, crate::restricted())?;
+        assert!(!repo.is_bare());
+
+        let repo = git::open_opts(
+            &repo_path,
+            crate::restricted().config_overrides(["core.bare=true", "core.abbrev=5"]),
+        )?;
+        assert!(
+            repo.is_bare(),
+            "the override is applied before the repository kind is determined"
+        );
+        assert_eq!(repo.config_snapshot().integer("core.abbrev"), Some(5));
+        let config = repo.config_snapshot();
+        assert_eq!(
+            config
+                .plumbing()
+                .sections_by_name("core")
+                .expect("present")
+                .last()
+                .map(|section| section.meta().source),
+            Some(git::config::Source::Cli),
+            "overrides are the last section of the cascade"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_config_overrides_fail_to_open() -> crate::Result {
+        let repo_path = git_testtools::scripted_fixture_repo_read_only("make_basic_repo.sh")?;
+        assert!(git::open_opts(&repo_path, crate::restricted().config_overrides(["invalid"])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn worktree_dir_overrides_the_implied_worktree() -> crate::Result {
+        let repo_path = git_testtools::scripted_fixture_repo_read_only("make_basic_repo.sh")?;
+        let worktree = tempfile::tempdir()?;
+        let repo = git::open_opts(&repo_path, crate::restricted().worktree_dir(worktree.path()))?;
+        assert_eq!(repo.work_dir(), Some(worktree.path()));
+        assert_eq!(repo.git_dir(), repo_path.join(".git"));
+        Ok(())
+    }
+}
+
+mod environment_overrides {
+    use git_repository as git;
+    use git_testtools::Env;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn are_applied_and_can_be_inspected() -> crate::Result {
+        let repo_path = git_testtools::scripted_fixture_repo_read_only("make_basic_repo.sh")?;
+        let git_dir = repo_path.join(".git");
+        let tmp = tempfile::tempdir()?;
+        let object_dir = tmp.path().join("objects");
+        std::fs::create_dir(&object_dir)?;
+        let index_file = tmp.path().join("alternative-index");
+        let _env = Env::new()
+            .set("GIT_DIR", git_dir.to_str().expect("valid UTF-8"))
+            .set("GIT_WORK_TREE", tmp.path().to_str().expect("valid UTF-8"))
+            .set("GIT_OBJECT_DIRECTORY", object_dir.to_str().expect("valid UTF-8"))
+            .set(
+                "GIT_ALTERNATE_OBJECT_DIRECTORIES",
+                git_dir.join("objects").to_str().expect("valid UTF-8"),
+            )
+            .set("GIT_INDEX_FILE", index_file.to_str().expect("valid UTF-8"));
+
+        let repo: git::Repository =
+            git::ThreadSafeRepository::open_with_environment_overrides("does-not-exist", Default::default())?.into();
+        assert_eq!(
+            repo.git_dir(),
+            git_dir,
+            "GIT_DIR is used instead of the fallback directory"
+        );
+        assert_eq!(repo.work_dir(), Some(tmp.path()));
+        assert_eq!(repo.index_path(), index_file);
+        assert!(
+            repo.head_commit().is_ok(),
+            "objects are found in the alternate even though the object directory is empty"
+        );
+
+        let overrides = repo.environment_overrides().expect("enabled");
+        assert_eq!(overrides.git_dir.as_deref(), Some(git_dir.as_path()));
+        assert_eq!(overrides.object_dir.as_deref(), Some(object_dir.as_path()));
+        assert_eq!(overrides.alternate_object_dirs, vec![git_dir.join("objects")]);
+        assert_eq!(overrides.common_dir, None);
+        assert!(!overrides.is_empty());
+
+        let repo = git::open(&repo_path)?;
+        assert_eq!(repo.environment_overrides(), None, "overrides are opt-in");
+        assert_eq!(repo.index_path(), git_dir.join("index"));
+        assert_eq!(repo.work_dir(), Some(repo_path.as_path()));
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn explicit_worktree_dirs_take_precedence() -> crate::Result {
+        let repo_path = git_testtools::scripted_fixture_repo_read_only("make_basic_repo.sh")?;
+        let tmp = tempfile::tempdir()?;
+        let _env = Env::new().set("GIT_WORK_TREE", "does-not-matter");
+        let repo = git::open_opts(
+            &repo_path,
+            crate::restricted()
+                .environment_overrides(true)
+                .worktree_dir(tmp.path())
+                .permissions(git::Permissions::all()),
+        )?;
+        assert_eq!(repo.work_dir(), Some(tmp.path()));
+        assert_eq!(
+            repo.environment_overrides().and_then(|env| env.worktree_dir.as_deref()),
+            Some(std::path::Path::new("does-not-matter"))
+        );
+        Ok(())
+    }
+}
+
+mod read_only {
+    use git_repository as git;
+    use git_repository::refs::transaction::PreviousValue;
+
+    #[test]
+    fn all_writes_are_refused_with_a_typed_error() -> crate::Result {
+        let tmp = git_testtools::scripted_fixture_repo_writable("make_basic_repo.sh")?;
+        let repo = git::open_opts(tmp.path(), crate::restricted().read_only(true))?;
+        assert!(repo.is_read_only());
+        let head = repo.head_id()?.detach();
+        let objects_before = std::fs::read_dir(repo.objects.store_ref().path())?.count();
+
+        assert!(matches!(
+            repo.write_blob("hello"),
+            Err(git::object::write::Error::ReadOnly(_))
+        ));
+        assert!(matches!(
+            repo.reference("refs/heads/new", head, PreviousValue::MustNotExist, "create"),
+            Err(git::reference::edit::Error::ReadOnly(_))
+        ));
+        assert!(
+            repo.find_reference("main")?.delete().is_err(),
+            "deletions are edits, too"
+        );
+        let signature = git::actor::SignatureRef {
+            name: "name".into(),
+            email: "name@example.com".into(),
+            time: Default::default(),
+        };
+        assert!(matches!(
+            repo.commit_as(
+                "HEAD",
+                signature,
+                signature,
+                "message",
+                repo.head_commit()?.tree_id()?,
+                Some(head)
+            ),
+            Err(git::commit::Error::WriteObject(git::object::write::Error::ReadOnly(_)))
+        ));
+        let err = repo.checkout_tree(
+            head,
+            git::progress::Discard,
+            &std::sync::atomic::AtomicBool::default(),
+            Default::default(),
+        );
+        assert!(matches!(err, Err(git::worktree::checkout::Error::ReadOnly(_))));
+
+        assert_eq!(
+            std::fs::read_dir(repo.objects.store_ref().path())?.count(),
+            objects_before,
+            "no object was written"
+        );
+        assert!(repo.try_find_reference("refs/heads/new")?.is_none());
+        assert_eq!(repo.head_id()?, head);
+        assert!(matches!(
+            repo.into_bare(),
+            Err(git::worktree::convert::Error::ReadOnly(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn repositories_are_writable_by_default() -> crate::Result {
+        let repo_path = git_testtools::scripted_fixture_repo_read_only("make_basic_repo.sh")?;
+        let repo = git::open_opts(repo_path, crate::restricted())?;
+        assert!(!repo.is_read_only());
+        Ok(())
+    }
+}
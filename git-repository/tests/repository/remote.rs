@@ -196,6 +196,59 @@ mod find_remote {
         Ok(())
     }
 
+    #[test]
+    fn push_instead_of_url_rewriting_applies_to_fetch_url_without_push_url() -> crate::Result {
+        let repo = remote::repo("push-url-rewriting");
+
+        let baseline = std::fs::read(repo.git_dir().join("baseline.git"))?;
+        let mut baseline = baseline.lines().filter_map(Result::ok);
+        let expected_fetch_url: BString = baseline.next().expect("fetch").into();
+        let expected_push_url: BString = baseline.next().expect("push").into();
+        assert_eq!(expected_push_url, "ssh://git@github.com/foobar/gitoxide");
+
+        let remote = repo.find_remote("origin")?;
+        assert_eq!(remote.url(Direction::Fetch).unwrap().to_bstring(), expected_fetch_url);
+        assert_eq!(remote.url(Direction::Push).unwrap().to_bstring(), expected_push_url);
+
+        let mut remote = repo.try_find_remote_without_url_rewrite("origin").expect("exists")?;
+        assert_eq!(
+            remote.url(Direction::Push).unwrap().to_bstring(),
+            "https://github.com/foobar/gitoxide"
+        );
+        remote.rewrite_urls()?;
+        assert_eq!(remote.url(Direction::Push).unwrap().to_bstring(), expected_push_url);
+
+        let remote = repo.remote_at("https://github.com/other/repo")?;
+        assert_eq!(
+            remote.url(Direction::Push).unwrap().to_bstring(),
+            "ssh://git@github.com/other/repo"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn url_rewrite_shows_the_matching_rule() {
+        let repo = remote::repo("url-rewriting");
+        let rewrite = repo.url_rewrite();
+        let m = rewrite
+            .longest_match("https://github.com/foobar/gitoxide".into(), Direction::Fetch)
+            .expect("match");
+        assert_eq!(m.base, "https://github.com/byron/");
+        assert_eq!(m.instead_of, "https://github.com/foobar/", "the longest value wins");
+        assert_eq!(m.rewritten, "https://github.com/byron/gitoxide");
+
+        let m = rewrite
+            .longest_match("file://dev/null".into(), Direction::Push)
+            .expect("match");
+        assert_eq!(m.base, "ssh://");
+        assert_eq!(m.rewritten, "ssh://dev/null");
+        assert_eq!(
+            rewrite.longest_match("file://dev/null".into(), Direction::Fetch),
+            None,
+            "pushInsteadOf is only used when pushing"
+        );
+    }
+
     #[test]
     fn bad_url_rewriting_can_be_handled_much_like_git() -> crate::Result {
         let repo = remote::repo("bad-url-rewriting");
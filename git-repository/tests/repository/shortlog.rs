@@ -0,0 +1,111 @@
+use git_repository::{
+    bstr::ByteSlice,
+    shortlog::{Group, Options, Outcome},
+};
+
+fn shortlog(spec: &str, options: Options) -> crate::Result<Outcome> {
+    let repo = crate::named_repo("make_shortlog_repo.sh")?;
+    let range = repo.rev_parse(spec)?.detach();
+    Ok(repo.shortlog(range, options)?)
+}
+
+fn counts(outcome: &Outcome) -> Vec<(&str, &str, usize)> {
+    outcome
+        .contributors
+        .iter()
+        .map(|c| (c.name.to_str().unwrap(), c.email.to_str().unwrap(), c.commits))
+        .collect()
+}
+
+#[test]
+fn commits_are_grouped_by_author_and_sorted_by_count() -> crate::Result {
+    let outcome = shortlog(
+        "HEAD",
+        Options {
+            #[cfg(feature = "git-mailmap")]
+            mailmap: false,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.commits, 5);
+    assert_eq!(
+        counts(&outcome),
+        vec![
+            ("Bob", "bob@example.com", 2),
+            ("Alice", "alice@example.com", 1),
+            ("Alice Old", "alice@old.example.com", 1),
+            ("Carol", "carol@example.com", 1)
+        ]
+    );
+    Ok(())
+}
+
+#[cfg(feature = "git-mailmap")]
+#[test]
+fn the_mailmap_is_applied_by_default() -> crate::Result {
+    let outcome = shortlog("HEAD", Options::default())?;
+    assert_eq!(
+        counts(&outcome),
+        vec![
+            ("Alice", "alice@example.com", 2),
+            ("Bob", "bob@example.com", 2),
+            ("Carol", "carol@example.com", 1)
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn ranges_exclude_commits_reachable_from_their_start() -> crate::Result {
+    let outcome = shortlog(
+        "v1..HEAD",
+        Options {
+            summaries: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.commits, 2);
+    assert_eq!(outcome.contributors.len(), 2);
+    assert_eq!(
+        outcome.contributors[0].name, "Bob",
+        "sorted by name if counts are equal"
+    );
+    assert_eq!(outcome.contributors[0].summaries, vec!["pair programming"]);
+    assert_eq!(outcome.contributors[1].summaries, vec!["carol's first"]);
+    Ok(())
+}
+
+#[test]
+fn co_authors_are_attributed_once_per_commit() -> crate::Result {
+    let outcome = shortlog(
+        "v1..HEAD",
+        Options {
+            co_authors: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        counts(&outcome),
+        vec![("Carol", "carol@example.com", 2), ("Bob", "bob@example.com", 1)],
+        "Bob listed himself as co-author, which doesn't count twice"
+    );
+    Ok(())
+}
+
+#[test]
+fn commits_can_be_grouped_by_committer() -> crate::Result {
+    let outcome = shortlog(
+        "HEAD",
+        Options {
+            group: Group::Committer,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        outcome.contributors.len(),
+        1,
+        "all commits were made by the same committer"
+    );
+    assert_eq!(outcome.contributors[0].commits, 5);
+    Ok(())
+}
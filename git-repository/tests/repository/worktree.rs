@@ -302,3 +302,177 @@ fn run_assertions(main_repo: git::Repository, should_be_bare: bool) {
         );
     }
 }
+
+mod checkout_tree {
+    use std::sync::atomic::AtomicBool;
+
+    use git_features::progress;
+    use git_repository as git;
+
+    #[test]
+    fn writes_files_and_index_of_the_given_revision() -> crate::Result {
+        let (repo, _keep) = crate::basic_rw_repo()?;
+        let work_dir = repo.work_dir().expect("non-bare").to_owned();
+        std::fs::remove_file(work_dir.join("this"))?;
+        let parent = repo.rev_parse("HEAD~1")?.single().expect("single").detach();
+
+        let outcome = repo.checkout_tree(
+            parent,
+            progress::Discard,
+            &AtomicBool::default(),
+            git::worktree::checkout::Options::default(),
+        )?;
+        assert_eq!(outcome.files_updated, 1);
+        assert!(outcome.errors.is_empty() && outcome.collisions.is_empty());
+        assert_eq!(
+            std::fs::read(work_dir.join("this"))?,
+            b"",
+            "the file is empty in the first commit"
+        );
+
+        let index = repo.open_index()?;
+        assert_eq!(index.entries().len(), 1);
+        assert_eq!(
+            index.entries()[0].id,
+            repo.rev_parse("HEAD~1:this")?.single().expect("single").detach(),
+            "the index matches the checked out tree"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn content_is_converted_as_configured() -> crate::Result {
+        let (repo, _keep) = crate::repo_rw("make_checkout_filters_repo.sh")?;
+        let work_dir = repo.work_dir().expect("non-bare").to_owned();
+        let outcome = repo.checkout_tree(
+            repo.head_id()?,
+            progress::Discard,
+            &AtomicBool::default(),
+            git::worktree::checkout::Options {
+                overwrite_existing: true,
+                ..Default::default()
+            },
+        )?;
+        assert!(outcome.errors.is_empty());
+        assert_eq!(
+            std::fs::read(work_dir.join("plain"))?,
+            b"a\r\nb\r\n",
+            "core.autocrlf is respected"
+        );
+        assert_eq!(
+            std::fs::read(work_dir.join("file.upper"))?,
+            b"HELLO\r\n",
+            "filter drivers are run after line endings are converted"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn interruptions_abort_the_operation() -> crate::Result {
+        let (repo, _keep) = crate::basic_rw_repo()?;
+        let res = repo.checkout_tree(
+            repo.head_id()?,
+            progress::Discard,
+            &AtomicBool::new(true),
+            git::worktree::checkout::Options {
+                overwrite_existing: true,
+                ..Default::default()
+            },
+        );
+        assert!(matches!(res, Err(git::worktree::checkout::Error::Interrupted)));
+        Ok(())
+    }
+
+    #[test]
+    fn bare_repositories_cannot_be_checked_out() -> crate::Result {
+        let repo = git::open_opts(
+            crate::basic_repo()?.work_dir().expect("non-bare").join("bare.git"),
+            crate::restricted(),
+        )?;
+        assert!(matches!(
+            repo.checkout_tree(
+                git::hash::ObjectId::empty_tree(repo.object_hash()),
+                progress::Discard,
+                &AtomicBool::default(),
+                Default::default()
+            ),
+            Err(git::worktree::checkout::Error::MissingWorkTree(_))
+        ));
+        Ok(())
+    }
+}
+
+mod bare {
+    use git_repository as git;
+
+    #[test]
+    fn bare_repositories_have_no_work_tree_to_require() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let repo = git::init_bare(&tmp)?;
+        let err = match repo.require_worktree("Testing") {
+            Ok(_) => unreachable!("bare repositories have no work tree"),
+            Err(err) => err,
+        };
+        assert_eq!(err.operation, "Testing");
+        assert_eq!(err.git_dir, repo.git_dir());
+        Ok(())
+    }
+
+    #[test]
+    fn into_bare_and_back() -> crate::Result {
+        let (repo, _keep) = crate::basic_rw_repo()?;
+        let work_dir = repo.work_dir().expect("non-bare").to_owned();
+        assert!(repo.require_worktree("Testing").is_ok());
+
+        let repo = repo.into_bare()?;
+        assert!(repo.is_bare());
+        assert!(repo.work_dir().is_none());
+        assert!(
+            !repo.index_path().exists(),
+            "the index is removed along with the work tree"
+        );
+        assert!(
+            work_dir.join("this").is_file(),
+            "files in the work tree are never touched"
+        );
+        assert!(matches!(
+            repo.require_worktree("Testing"),
+            Err(git::worktree::require::Error {
+                operation: "Testing",
+                ..
+            })
+        ));
+
+        let repo = repo.into_non_bare(None)?;
+        assert!(!repo.is_bare());
+        assert_eq!(
+            repo.work_dir().map(|dir| dir.canonicalize()).transpose()?,
+            Some(work_dir.canonicalize()?),
+            "the parent directory of the '.git' directory is used by default"
+        );
+        assert!(repo.config_snapshot().string("core.worktree").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn into_non_bare_needs_a_location_unless_inside_of_dot_git() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let git_dir = tmp.path().join("repo.git");
+        std::fs::create_dir(&git_dir)?;
+        let repo = git::init_bare(git_dir)?;
+        assert!(matches!(
+            repo.clone().into_non_bare(None),
+            Err(git::worktree::convert::Error::MissingWorkTreeLocation { .. })
+        ));
+
+        let work_dir = tmp.path().join("checkout");
+        let repo = repo.into_non_bare(Some(work_dir.clone()))?;
+        assert!(work_dir.is_dir(), "the work tree is created");
+        assert_eq!(
+            repo.work_dir().map(|dir| dir.canonicalize()).transpose()?,
+            Some(work_dir.canonicalize()?)
+        );
+        assert!(repo.config_snapshot().string("core.worktree").is_some());
+        Ok(())
+    }
+}
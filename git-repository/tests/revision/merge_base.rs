@@ -0,0 +1,172 @@
+use git_repository as git;
+
+fn repos() -> crate::Result<Vec<git::Repository>> {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_merge_base_repos.sh")?;
+    ["without-commit-graph", "with-commit-graph"]
+        .iter()
+        .map(|name| Ok(git::open_opts(dir.join(name), crate::restricted())?))
+        .collect()
+}
+
+fn id(repo: &git::Repository, tag: &str) -> git::ObjectId {
+    repo.rev_parse(tag)
+        .expect("valid spec")
+        .single()
+        .expect("single object")
+        .detach()
+}
+
+fn ids<'a>(repo: &git::Repository, tags: impl IntoIterator<Item = &'a str>) -> Vec<git::ObjectId> {
+    tags.into_iter().map(|tag| id(repo, tag)).collect()
+}
+
+fn detached(ids: Vec<git::Id<'_>>) -> Vec<git::ObjectId> {
+    ids.into_iter().map(git::Id::detach).collect()
+}
+
+#[test]
+fn merge_base_of_diverging_branches() -> crate::Result {
+    for repo in repos()? {
+        assert_eq!(
+            repo.merge_base(id(&repo, "m2"), id(&repo, "a1"))?.map(git::Id::detach),
+            Some(id(&repo, "root"))
+        );
+        assert_eq!(
+            repo.merge_base(id(&repo, "m1"), id(&repo, "m2"))?.map(git::Id::detach),
+            Some(id(&repo, "m1")),
+            "the ancestor itself is the merge base"
+        );
+        assert_eq!(
+            repo.merge_base(id(&repo, "m2"), id(&repo, "m2"))?.map(git::Id::detach),
+            Some(id(&repo, "m2"))
+        );
+        assert_eq!(
+            repo.merge_base(id(&repo, "m2"), id(&repo, "unrelated"))?,
+            None,
+            "there is no common history"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn merge_base_many_returns_all_best_common_ancestors_most_recent_first() -> crate::Result {
+    for repo in repos()? {
+        assert_eq!(
+            detached(repo.merge_base_many(id(&repo, "a2"), Some(id(&repo, "b2")))?),
+            ids(&repo, ["b1", "a1"]),
+            "criss-cross merges have more than one best common ancestor"
+        );
+        assert_eq!(
+            repo.merge_base(id(&repo, "a2"), id(&repo, "b2"))?.map(git::Id::detach),
+            Some(id(&repo, "b1")),
+            "the most recent one is picked"
+        );
+        assert_eq!(
+            detached(repo.merge_base_many(id(&repo, "a2"), ids(&repo, ["b1", "c1"]))?),
+            ids(&repo, ["b1"]),
+            "root is reachable from b1 and thus not a best common ancestor"
+        );
+        assert_eq!(
+            detached(repo.merge_base_many(id(&repo, "a2"), ids(&repo, ["b2", "octopus"]))?),
+            ids(&repo, ["b1", "a1"])
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn merge_base_octopus() -> crate::Result {
+    for repo in repos()? {
+        assert_eq!(
+            detached(repo.merge_base_octopus(ids(&repo, ["c1", "c2", "c3"]))?),
+            ids(&repo, ["root"])
+        );
+        assert_eq!(
+            detached(repo.merge_base_octopus(ids(&repo, ["a2", "b2", "octopus"]))?),
+            ids(&repo, ["root"])
+        );
+        assert_eq!(
+            detached(repo.merge_base_octopus(ids(&repo, ["octopus", "c2"]))?),
+            ids(&repo, ["c2"])
+        );
+        assert_eq!(
+            detached(repo.merge_base_octopus(ids(&repo, ["m2"]))?),
+            ids(&repo, ["m2"])
+        );
+        assert!(repo.merge_base_octopus(ids(&repo, ["m2", "unrelated"]))?.is_empty());
+        assert!(repo.merge_base_octopus(Vec::<git::ObjectId>::new())?.is_empty());
+    }
+    Ok(())
+}
+
+#[test]
+fn is_ancestor() -> crate::Result {
+    for repo in repos()? {
+        for (ancestor, descendant, expected) in [
+            ("root", "m2", true),
+            ("m1", "m2", true),
+            ("m2", "m2", true),
+            ("m2", "m1", false),
+            ("a1", "m2", false),
+            ("a1", "b2", true),
+            ("b1", "a2", true),
+            ("a2", "b2", false),
+            ("c3", "octopus", true),
+            ("octopus", "c3", false),
+            ("root", "unrelated", false),
+            ("unrelated", "octopus", false),
+        ] {
+            assert_eq!(
+                repo.is_ancestor(id(&repo, ancestor), id(&repo, descendant))?,
+                expected,
+                "{} -> {}",
+                ancestor,
+                descendant
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn ahead_behind() -> crate::Result {
+    for repo in repos()? {
+        for (one, two, expected) in [
+            ("m2", "m2", (0, 0)),
+            ("root", "m2", (0, 2)),
+            ("m2", "root", (2, 0)),
+            ("m2", "a2", (2, 3)),
+            ("a2", "b2", (1, 1)),
+            ("octopus", "m2", (4, 2)),
+            ("unrelated", "root", (1, 1)),
+        ] {
+            let outcome = repo.ahead_behind(id(&repo, one), id(&repo, two), Default::default())?;
+            assert_eq!((outcome.ahead, outcome.behind), expected, "{}...{}", one, two);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn ahead_behind_with_commit_budget() -> crate::Result {
+    let options = git::revision::ahead_behind::Options { max_commits: Some(1) };
+    let repos = repos()?;
+    let (without_graph, with_graph) = (&repos[0], &repos[1]);
+
+    let err = without_graph
+        .ahead_behind(id(without_graph, "octopus"), id(without_graph, "m2"), options)
+        .unwrap_err();
+    assert!(
+        matches!(err, git::revision::ahead_behind::Error::TooExpensive { max_commits: 1 }),
+        "without commit-graph every commit counts towards the budget"
+    );
+
+    let outcome = with_graph.ahead_behind(id(with_graph, "octopus"), id(with_graph, "m2"), options)?;
+    assert_eq!(
+        (outcome.ahead, outcome.behind),
+        (4, 2),
+        "commits in the commit-graph are free"
+    );
+    Ok(())
+}
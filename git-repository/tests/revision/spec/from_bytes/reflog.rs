@@ -1,7 +1,4 @@
-use git_repository::{
-    prelude::ObjectIdExt,
-    revision::{spec::parse::Error, Spec},
-};
+use git_repository::{prelude::ObjectIdExt, revision::Spec};
 use git_testtools::hex_to_id;
 
 use crate::revision::spec::from_bytes::{parse_spec, parse_spec_no_baseline, repo};
@@ -76,10 +73,24 @@ fn by_index() {
 }
 
 #[test]
-fn by_date_is_planned_until_git_date_crate_is_implements_parsing() {
-    let repo = repo("complex_graph").unwrap();
-    assert!(matches!(
-        parse_spec_no_baseline("main@{1979-02-26 18:30:00}", &repo).unwrap_err(),
-        Error::Planned { .. }
-    ));
+fn by_date() {
+    let repo = &repo("complex_graph").unwrap();
+    for (spec, expected) in [
+        ("main@{1112912200 +0000}", "a8fbd8e0c8753ef07ccdbfa93da030d53b68f0bc"),
+        ("main@{1112912113 +0000}", "a8fbd8e0c8753ef07ccdbfa93da030d53b68f0bc"),
+        (
+            "main@{2005-04-07 22:16:40 +0000}",
+            "a8fbd8e0c8753ef07ccdbfa93da030d53b68f0bc",
+        ),
+        ("main@{1 year ago}", "55e825ebe8fd2ff78cad3826afb696b96b576a7e"),
+        ("@{1 year ago}", "55e825ebe8fd2ff78cad3826afb696b96b576a7e"),
+        ("main@{1100000000 +0000}", "9f9eac6bd1cd4b4cc6a494f044b28c985a22972b"),
+    ] {
+        assert_eq!(
+            parse_spec_no_baseline(spec, repo).unwrap(),
+            Spec::from_id(hex_to_id(expected).attach(repo)),
+            "{}",
+            spec
+        );
+    }
 }
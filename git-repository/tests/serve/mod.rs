@@ -0,0 +1,307 @@
+use std::{
+    io::BufReader,
+    net::TcpListener,
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use git_features::progress;
+use git_repository as git;
+use git_repository::serve::{daemon, http::Endpoint, receive_pack, upload_pack, Mode, Service};
+
+enum Outcome {
+    UploadPack(upload_pack::Outcome),
+    ReceivePack(receive_pack::Outcome),
+}
+
+/// Serve `connections` connections to the repositories in `base` like a `git://` daemon, and return the URL of `base`
+/// along with a handle to obtain the outcome of each connection.
+fn daemon(base: PathBuf, connections: usize) -> crate::Result<(String, std::thread::JoinHandle<Vec<Outcome>>)> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("git://{}", listener.local_addr()?);
+    let handle = std::thread::spawn(move || {
+        let mut outcomes = Vec::new();
+        for stream in listener.incoming().take(connections) {
+            let stream = stream.expect("valid connection");
+            let request = daemon::Request::from_read(&stream).expect("valid request");
+            let path = request.path.to_string();
+            let repo =
+                git::open_opts(base.join(path.trim_start_matches('/')), crate::restricted()).expect("repo exists");
+            let input = BufReader::new(stream.try_clone().expect("cloneable"));
+            let should_interrupt = AtomicBool::default();
+            outcomes.push(match request.service {
+                Service::UploadPack => Outcome::UploadPack(
+                    repo.upload_pack(input, &stream, Default::default(), progress::Discard, &should_interrupt)
+                        .expect("upload-pack works"),
+                ),
+                Service::ReceivePack => Outcome::ReceivePack(
+                    repo.receive_pack(input, &stream, Default::default(), progress::Discard, &should_interrupt)
+                        .expect("receive-pack works"),
+                ),
+            });
+        }
+        outcomes
+    });
+    Ok((url, handle))
+}
+
+fn git(dir: &Path, args: &[&str]) -> crate::Result<std::process::Output> {
+    Ok(std::process::Command::new("git")
+        .args(["-c", "user.name=name", "-c", "user.email=name@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()?)
+}
+
+fn git_ok(dir: &Path, args: &[&str]) -> crate::Result<String> {
+    let output = git(dir, args)?;
+    assert!(
+        output.status.success(),
+        "{:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Return `data` as packet line.
+fn line(data: &str) -> String {
+    format!("{:04x}{}", data.len() + 4, data)
+}
+
+fn id(repo: &git::Repository, spec: &str) -> git::ObjectId {
+    repo.rev_parse(spec)
+        .expect("valid spec")
+        .single()
+        .expect("single object")
+        .detach()
+}
+
+#[test]
+fn clone_fetch_and_push_with_git() -> crate::Result {
+    let tmp = git_testtools::scripted_fixture_repo_writable("make_serve_repo.sh")?;
+    let (url, server) = daemon(tmp.path().to_owned(), 5)?;
+
+    let refs = git_ok(tmp.path(), &["ls-remote", &format!("{}/bare.git", url)])?;
+    assert!(
+        refs.contains("\tHEAD\n") && refs.contains("\trefs/tags/v1^{}\n"),
+        "{}",
+        refs
+    );
+
+    git_ok(tmp.path(), &["clone", "-q", &format!("{}/bare.git", url), "clone"])?;
+    let clone_dir = tmp.path().join("clone");
+    let bare = git::open_opts(tmp.path().join("bare.git"), crate::restricted())?;
+    let clone = git::open_opts(&clone_dir, crate::restricted())?;
+    for (cloned, served) in [("HEAD", "HEAD"), ("v1", "v1"), ("origin/other", "other")] {
+        assert_eq!(id(&clone, cloned), id(&bare, served));
+    }
+    assert_eq!(clone.head_name()?.expect("branch").as_bstr(), "refs/heads/main");
+    git_ok(&clone_dir, &["fsck", "--no-progress"])?;
+
+    git_ok(&clone_dir, &["commit", "-q", "--allow-empty", "-m", "c4"])?;
+    git_ok(&clone_dir, &["push", "-q", "origin", "main", ":other"])?;
+    assert_eq!(id(&bare, "main"), id(&clone, "main"));
+    assert!(bare.try_find_reference("other")?.is_none(), "the branch was deleted");
+
+    let base_dir = tmp.path().join("base");
+    git_ok(
+        &base_dir,
+        &[
+            "fetch",
+            "-q",
+            &format!("{}/bare.git", url),
+            "main:refs/remotes/served/main",
+        ],
+    )?;
+    let base = git::open_opts(&base_dir, crate::restricted())?;
+    assert_eq!(id(&base, "served/main"), id(&clone, "main"));
+
+    let output = git(&clone_dir, &["push", &format!("{}/base", url), "main"])?;
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("branch is currently checked out"),
+        "the checked out branch of non-bare repositories can't be changed"
+    );
+
+    let outcomes = server.join().expect("no panic");
+    assert!(matches!(&outcomes[0], Outcome::UploadPack(outcome) if outcome.wants.is_empty()));
+    assert!(matches!(&outcomes[1], Outcome::UploadPack(outcome) if outcome.common.is_empty()));
+    match &outcomes[2] {
+        Outcome::ReceivePack(outcome) => {
+            let rejections: Vec<_> = outcome.updates.iter().map(|update| &update.rejection).collect();
+            assert_eq!(rejections, vec![&None, &None]);
+            assert_eq!(outcome.pack.as_ref().expect("a new commit").index.num_objects, 1);
+        }
+        _ => unreachable!("push"),
+    }
+    assert!(
+        matches!(&outcomes[3], Outcome::UploadPack(outcome) if outcome.common.contains(&id(&base, "main"))),
+        "negotiation finds the commits both sides have"
+    );
+    match &outcomes[4] {
+        Outcome::ReceivePack(outcome) => assert_eq!(
+            outcome.updates[0].rejection.as_deref(),
+            Some("branch is currently checked out")
+        ),
+        _ => unreachable!("push"),
+    }
+    Ok(())
+}
+
+#[test]
+fn clone_empty_repository_with_git() -> crate::Result {
+    let tmp = git_testtools::scripted_fixture_repo_writable("make_serve_repo.sh")?;
+    let (url, server) = daemon(tmp.path().to_owned(), 1)?;
+    git_ok(tmp.path(), &["clone", "-q", &format!("{}/empty.git", url), "clone"])?;
+    assert!(tmp.path().join("clone/.git").is_dir());
+    let outcomes = server.join().expect("no panic");
+    assert!(matches!(&outcomes[0], Outcome::UploadPack(outcome) if outcome.wants.is_empty()));
+    Ok(())
+}
+
+#[test]
+fn upload_pack_refuses_objects_that_arent_advertised() -> crate::Result {
+    let repo_path = git_testtools::scripted_fixture_repo_read_only("make_serve_repo.sh")?;
+    let repo = git::open_opts(repo_path.join("bare.git"), crate::restricted())?;
+    let blob = id(&repo, "main:file");
+    let request = line(&format!("want {}\n", blob)) + "0000";
+    let mut out = Vec::new();
+    let err = repo
+        .upload_pack(
+            request.as_bytes(),
+            &mut out,
+            upload_pack::Options {
+                mode: Mode::StatelessRpc,
+            },
+            progress::Discard,
+            &AtomicBool::default(),
+        )
+        .unwrap_err();
+    assert!(matches!(err, upload_pack::Error::NotOurRef { id } if id == blob));
+    assert_eq!(
+        out,
+        line(&format!("ERR upload-pack: not our ref {}\n", blob)).as_bytes()
+    );
+    Ok(())
+}
+
+#[test]
+fn receive_pack_refuses_read_only_repositories() -> crate::Result {
+    let repo_path = git_testtools::scripted_fixture_repo_read_only("make_serve_repo.sh")?;
+    let repo = git::open_opts(repo_path.join("bare.git"), crate::restricted().read_only(true))?;
+    let err = repo
+        .receive_pack(
+            &b""[..],
+            Vec::new(),
+            Default::default(),
+            progress::Discard,
+            &AtomicBool::default(),
+        )
+        .unwrap_err();
+    assert!(matches!(err, receive_pack::Error::ReadOnly(_)));
+    Ok(())
+}
+
+mod http {
+    use std::sync::atomic::AtomicBool;
+
+    use git_features::progress;
+    use git_repository as git;
+    use git_repository::serve::{http::Endpoint, Service};
+
+    use super::{id, line};
+
+    #[test]
+    fn endpoints() {
+        assert_eq!(
+            Endpoint::from_request("GET", "/repo.git/info/refs", Some("service=git-upload-pack")),
+            Some(("/repo.git", Endpoint::InfoRefs(Service::UploadPack)))
+        );
+        assert_eq!(
+            Endpoint::from_request("POST", "/a/b/git-receive-pack", None),
+            Some(("/a/b", Endpoint::Service(Service::ReceivePack)))
+        );
+        assert_eq!(
+            Endpoint::from_request("GET", "/repo.git/info/refs", None),
+            None,
+            "dumb HTTP isn't supported"
+        );
+        assert_eq!(Endpoint::from_request("GET", "/repo.git/git-upload-pack", None), None);
+        assert_eq!(
+            Endpoint::Service(Service::UploadPack).content_type(),
+            "application/x-git-upload-pack-result"
+        );
+        assert_eq!(
+            Endpoint::InfoRefs(Service::ReceivePack).content_type(),
+            "application/x-git-receive-pack-advertisement"
+        );
+    }
+
+    #[test]
+    fn advertisement_and_stateless_requests() -> crate::Result {
+        let repo_path = git_testtools::scripted_fixture_repo_read_only("make_serve_repo.sh")?;
+        let repo = git::open_opts(repo_path.join("bare.git"), crate::restricted())?;
+        let serve = |endpoint, body: &[u8]| -> crate::Result<Vec<u8>> {
+            let mut out = Vec::new();
+            repo.serve_http(endpoint, body, &mut out, progress::Discard, &AtomicBool::default())?;
+            Ok(out)
+        };
+
+        let out = serve(Endpoint::InfoRefs(Service::UploadPack), b"")?;
+        let head = id(&repo, "HEAD");
+        let preamble = line("# service=git-upload-pack\n") + "0000";
+        assert!(out.starts_with(preamble.as_bytes()));
+        assert!(
+            out[preamble.len() + 4..].starts_with(format!("{} HEAD\0", head).as_bytes()),
+            "HEAD is advertised first"
+        );
+        assert!(out.ends_with(b"0000"));
+
+        let have = id(&repo, "main~1");
+        let wants_and_haves = line(&format!("want {}\n", head)) + "0000" + &line(&format!("have {}\n", have));
+        let out = serve(
+            Endpoint::Service(Service::UploadPack),
+            (wants_and_haves.clone() + "0000").as_bytes(),
+        )?;
+        assert_eq!(
+            out,
+            (line(&format!("ACK {} common\n", have)) + &line("NAK\n")).as_bytes(),
+            "without 'done', only acknowledgements are sent"
+        );
+
+        let out = serve(
+            Endpoint::Service(Service::UploadPack),
+            (wants_and_haves + &line("done\n")).as_bytes(),
+        )?;
+        let acks = line(&format!("ACK {} common\n", have)) + &line(&format!("ACK {}\n", have));
+        assert!(out.starts_with(acks.as_bytes()));
+        assert_eq!(
+            &out[acks.len()..][..4],
+            b"PACK",
+            "without side-band, the pack is sent as is"
+        );
+        Ok(())
+    }
+}
+
+#[test]
+fn daemon_requests() -> crate::Result {
+    let request = daemon::Request::from_bytes(b"git-upload-pack /project.git\0host=example.com:9418\0\0version=2\0")?;
+    assert_eq!(request.service, Service::UploadPack);
+    assert_eq!(request.path, "/project.git");
+    assert_eq!(request.host.expect("set"), "example.com:9418");
+    assert_eq!(request.extra_parameters, vec!["version=2"]);
+
+    let request = daemon::Request::from_read(line("git-receive-pack /a\0host=local\0").as_bytes())?;
+    assert_eq!(request.service, Service::ReceivePack);
+    assert!(request.extra_parameters.is_empty());
+
+    assert!(matches!(
+        daemon::Request::from_bytes(b"git-frobnicate /a\0"),
+        Err(daemon::Error::UnknownService { .. })
+    ));
+    assert!(daemon::Request::from_bytes(b"git-upload-pack\0").is_err());
+    assert!(Endpoint::from_request("PUT", "/a/git-upload-pack", None).is_none());
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use git_repository as git;
+use git_repository::status::{Action, Change};
+
+fn repo(name: &str) -> crate::Result<git::Repository> {
+    let repo_path = git_testtools::scripted_fixture_repo_read_only("make_status_repos.sh")?;
+    Ok(git::open_opts(repo_path.join(name), crate::restricted())?)
+}
+
+fn repo_rw(name: &str) -> crate::Result<(git::Repository, tempfile::TempDir)> {
+    let tmp = git_testtools::scripted_fixture_repo_writable("make_status_repos.sh")?;
+    Ok((git::open_opts(tmp.path().join(name), crate::restricted())?, tmp))
+}
+
+fn changes(repo: &git::Repository) -> crate::Result<Vec<Change>> {
+    let mut out = Vec::new();
+    repo.changes(|change| {
+        out.push(change);
+        Action::Continue
+    })?;
+    Ok(out)
+}
+
+#[test]
+fn clean_and_unborn_repositories_are_not_dirty() -> crate::Result {
+    for name in ["clean", "unborn"] {
+        let repo = repo(name)?;
+        assert!(!repo.is_dirty()?, "{}", name);
+        assert_eq!(changes(&repo)?, vec![]);
+    }
+    Ok(())
+}
+
+#[test]
+fn staged_changes() -> crate::Result {
+    let repo = repo("staged")?;
+    assert!(repo.is_dirty()?);
+    assert_eq!(changes(&repo)?, vec![Change::Staged { path: "dir/b".into() }]);
+    Ok(())
+}
+
+#[test]
+fn intent_to_add_and_conflicts() -> crate::Result {
+    assert_eq!(
+        changes(&repo("intent-to-add")?)?,
+        vec![Change::IntentToAdd { path: "new".into() }]
+    );
+    assert_eq!(
+        changes(&repo("conflict")?)?,
+        vec![Change::Conflict { path: "a".into() }],
+        "conflicts are reported once, and not compared to HEAD or the worktree"
+    );
+    Ok(())
+}
+
+#[test]
+fn worktree_changes() -> crate::Result {
+    let (repo, _tmp) = repo_rw("clean")?;
+    let work_dir = repo.work_dir().expect("non-bare");
+    std::fs::write(work_dir.join("a"), "changed")?;
+    std::fs::remove_file(work_dir.join("dir/b"))?;
+    std::fs::write(work_dir.join("untracked"), "ignored")?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(work_dir.join("exe"), std::fs::Permissions::from_mode(0o644))?;
+    }
+
+    let mut expected = vec![
+        Change::Modified { path: "a".into() },
+        Change::Removed { path: "dir/b".into() },
+    ];
+    if cfg!(unix) {
+        expected.push(Change::Modified { path: "exe".into() });
+    }
+    assert_eq!(changes(&repo)?, expected, "untracked files are ignored");
+
+    let mut seen = 0;
+    repo.changes(|_| {
+        seen += 1;
+        Action::Cancel
+    })?;
+    assert_eq!(seen, 1, "it's possible to stop at the first change");
+    Ok(())
+}
+
+#[test]
+fn bare_repositories_have_no_status() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let repo = git::init_bare(&tmp)?;
+    assert!(matches!(repo.is_dirty(), Err(git::status::Error::MissingWorkTree(_))));
+    Ok(())
+}
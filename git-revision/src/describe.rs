@@ -4,6 +4,8 @@ use std::{
 };
 
 use bstr::BStr;
+use git_hash::oid;
+use git_object::CommitRefIter;
 
 /// The positive result produced by [describe()][function::describe()].
 #[derive(Debug, Clone)]
@@ -142,18 +144,167 @@ where
     Decode(#[from] git_object::decode::Error),
 }
 
+/// A cache to describe many commits with the same [`Options`], sharing work between them.
+///
+/// Decoded commits are kept in memory to avoid looking them up more than once, and the outcome of each described commit
+/// is remembered along with the outcome of all commits on the linear stretch of history leading to the next merge or name.
+/// This is possible as a commit with a single parent is described exactly like its parent, just with a depth increased by one.
+///
+/// This makes it well suited to describe all commits of a log view, for example, which would otherwise
+/// traverse similar portions of the commit graph once per commit.
+/// Note that the memory used by the cache grows with the amount of commits it encounters.
+pub struct Cache<'name> {
+    options: Options<'name>,
+    graph: function::Graph,
+    outcomes: hash_hasher::HashedMap<git_hash::ObjectId, Option<function::NameAndDepth<'name>>>,
+    commits_seen: u32,
+}
+
+/// The positive result produced by [`Cache::describe()`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Described<'name> {
+    /// The name of the tag or branch that is closest to the commit `id`.
+    ///
+    /// If `None`, no name was found but it was requested to provide the `id` itself as fallback.
+    pub name: Option<Cow<'name, BStr>>,
+    /// The input commit object id that we describe.
+    pub id: git_hash::ObjectId,
+    /// The number of commits that are between the tag or branch with `name` and `id`.
+    pub depth: u32,
+}
+
+impl<'a> Described<'a> {
+    /// Turn this instance into a structure that can display itself in the typical `git describe` format.
+    pub fn into_format(self, hex_len: usize) -> Format<'a> {
+        Format {
+            name: self.name,
+            id: self.id,
+            hex_len,
+            depth: self.depth,
+            long: false,
+            dirty_suffix: None,
+        }
+    }
+}
+
+impl<'name> Cache<'name> {
+    /// Create a new cache to describe commits with the given `options`.
+    pub fn new(mut options: Options<'name>) -> Self {
+        options.max_candidates = options.max_candidates.min(MAX_CANDIDATES);
+        Cache {
+            options,
+            graph: Default::default(),
+            outcomes: Default::default(),
+            commits_seen: 0,
+        }
+    }
+
+    /// Return the mapping between object ids and their names this cache was created with.
+    pub fn name_by_oid(&self) -> &hash_hasher::HashedMap<git_hash::ObjectId, Cow<'name, BStr>> {
+        &self.options.name_by_oid
+    }
+
+    /// Return the amount of commits traversed so far, across all calls to [`describe()`][Cache::describe()].
+    pub fn commits_seen(&self) -> u32 {
+        self.commits_seen
+    }
+
+    /// Like [`describe()`][function::describe()], find a name for `commit` using `find` to lookup commits, but reuse
+    /// what was learned when describing previous commits.
+    pub fn describe<Find, E>(&mut self, commit: &oid, mut find: Find) -> Result<Option<Described<'name>>, Error<E>>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<Option<CommitRefIter<'b>>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let Options {
+            name_by_oid,
+            max_candidates,
+            fallback_to_oid,
+            first_parent,
+        } = &self.options;
+        let name_and_depth = if let Some(name) = name_by_oid.get(commit) {
+            Some((name.clone(), 0))
+        } else if *max_candidates == 0 || name_by_oid.is_empty() {
+            None
+        } else {
+            let mut chain = Vec::new();
+            let mut current = commit.to_owned();
+            let base = loop {
+                if let Some(outcome) = self.outcomes.get(&current) {
+                    break outcome.clone();
+                }
+                if let Some(name) = name_by_oid.get(&current) {
+                    break Some((name.clone(), 0));
+                }
+                let parents = &self
+                    .graph
+                    .get(&current, &mut find, &current)?
+                    .ok_or_else(|| Error::Find {
+                        err: None,
+                        oid: current,
+                    })?
+                    .parents;
+                let parent = match parents.len() {
+                    1 => Some(parents[0]),
+                    0 => None,
+                    _ if *first_parent => Some(parents[0]),
+                    _ => None,
+                };
+                match parent {
+                    Some(parent) if self.graph.get(&parent, &mut find, &current)?.is_some() => {
+                        chain.push(std::mem::replace(&mut current, parent));
+                    }
+                    _ => {
+                        let (outcome, commits_seen) = function::traverse(
+                            &current,
+                            &mut find,
+                            &mut self.graph,
+                            name_by_oid,
+                            *max_candidates,
+                            *first_parent,
+                        )?;
+                        self.commits_seen += commits_seen;
+                        break outcome;
+                    }
+                }
+            };
+            self.outcomes.insert(current, base.clone());
+            for (distance, id) in chain.into_iter().rev().enumerate() {
+                self.outcomes.insert(
+                    id,
+                    base.clone().map(|(name, depth)| (name, depth + distance as u32 + 1)),
+                );
+            }
+            self.outcomes.get(commit).cloned().expect("just inserted")
+        };
+
+        Ok(match name_and_depth {
+            Some((name, depth)) => Some(Described {
+                name: Some(name),
+                id: commit.to_owned(),
+                depth,
+            }),
+            None if *fallback_to_oid => Some(Described {
+                name: None,
+                id: commit.to_owned(),
+                depth: 0,
+            }),
+            None => None,
+        })
+    }
+}
+
 pub(crate) mod function {
     use std::{
         borrow::Cow,
         cmp::Ordering,
-        collections::{hash_map, HashMap, VecDeque},
+        collections::{hash_map, VecDeque},
         iter::FromIterator,
     };
 
     use bstr::BStr;
     use git_hash::oid;
     use git_object::CommitRefIter;
-    use hash_hasher::HashBuildHasher;
 
     use super::{Error, Outcome};
     use crate::describe::{Flags, Options, MAX_CANDIDATES};
@@ -163,6 +314,8 @@ pub(crate) mod function {
     ///
     /// Note that the `name_by_oid` map is returned in the [`Outcome`], which can be forcefully returned even if there was no matching
     /// candidate by setting `fallback_to_oid` to true.
+    ///
+    /// Use a [`Cache`][super::Cache] to describe many commits.
     pub fn describe<'name, Find, E>(
         commit: &oid,
         mut find: Find,
@@ -202,9 +355,105 @@ pub(crate) mod function {
             };
         }
 
-        let mut buf = Vec::new();
-        let mut parent_buf = Vec::new();
+        let (best, commits_seen) = traverse(
+            commit,
+            &mut find,
+            &mut Graph::default(),
+            &name_by_oid,
+            max_candidates,
+            first_parent,
+        )?;
+        Ok(match best {
+            Some((name, depth)) => Some(Outcome {
+                name: name.into(),
+                id: commit.to_owned(),
+                depth,
+                name_by_oid,
+                commits_seen,
+            }),
+            None if fallback_to_oid => Some(Outcome {
+                id: commit.to_owned(),
+                name: None,
+                name_by_oid,
+                depth: 0,
+                commits_seen,
+            }),
+            None => None,
+        })
+    }
+
+    /// The name of the best candidate along with the amount of commits between it and the described commit.
+    pub(crate) type NameAndDepth<'name> = (Cow<'name, BStr>, u32);
 
+    /// The information about a commit we need for traversal.
+    pub(crate) struct Commit {
+        pub parents: Vec<git_hash::ObjectId>,
+        pub time: u32,
+    }
+
+    /// Decoded commits by their id.
+    #[derive(Default)]
+    pub(crate) struct Graph {
+        commits: hash_hasher::HashedMap<git_hash::ObjectId, Commit>,
+        buf: Vec<u8>,
+    }
+
+    impl Graph {
+        /// Lookup `id` with `find` unless we know it already, or return `None` if it doesn't exist.
+        /// Lookup errors are associated with `error_oid`.
+        pub fn get<Find, E>(&mut self, id: &oid, find: &mut Find, error_oid: &oid) -> Result<Option<&Commit>, Error<E>>
+        where
+            Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<Option<CommitRefIter<'b>>, E>,
+            E: std::error::Error + Send + Sync + 'static,
+        {
+            Ok(match self.commits.entry(id.to_owned()) {
+                hash_map::Entry::Occupied(entry) => Some(entry.into_mut()),
+                hash_map::Entry::Vacant(entry) => {
+                    let commit_iter = match find(id, &mut self.buf).map_err(|err| Error::Find {
+                        err: Some(err),
+                        oid: error_oid.to_owned(),
+                    })? {
+                        Some(commit_iter) => commit_iter,
+                        None => return Ok(None),
+                    };
+                    let mut parents = Vec::new();
+                    let mut time = 0;
+                    let mut seen_author = false;
+                    for token in commit_iter {
+                        match token {
+                            Ok(git_object::commit::ref_iter::Token::Tree { .. }) => continue,
+                            Ok(git_object::commit::ref_iter::Token::Parent { id }) => parents.push(id),
+                            Ok(git_object::commit::ref_iter::Token::Author { .. }) => seen_author = true,
+                            Ok(git_object::commit::ref_iter::Token::Committer { signature }) => {
+                                time = signature.time.seconds_since_unix_epoch;
+                                break;
+                            }
+                            Ok(_unused_token) => break,
+                            // The commit time isn't required to be valid, it's only used to order the traversal.
+                            Err(_) if seen_author => break,
+                            Err(err) => return Err(err.into()),
+                        }
+                    }
+                    Some(entry.insert(Commit { parents, time }))
+                }
+            })
+        }
+    }
+
+    /// Traverse the graph starting at `commit` and return the name of the best candidate along with its depth,
+    /// as well as the amount of commits we traversed.
+    pub(crate) fn traverse<'name, Find, E>(
+        commit: &oid,
+        find: &mut Find,
+        graph: &mut Graph,
+        name_by_oid: &hash_hasher::HashedMap<git_hash::ObjectId, Cow<'name, BStr>>,
+        max_candidates: usize,
+        first_parent: bool,
+    ) -> Result<(Option<NameAndDepth<'name>>, u32), Error<E>>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<Option<CommitRefIter<'b>>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
         let mut queue = VecDeque::from_iter(Some((commit.to_owned(), u32::MAX)));
         let mut candidates = Vec::new();
         let mut commits_seen = 0;
@@ -262,9 +511,8 @@ pub(crate) mod function {
             }
 
             parents_by_date_onto_queue_and_track_names(
-                &mut find,
-                &mut buf,
-                &mut parent_buf,
+                find,
+                graph,
                 &mut queue,
                 &mut seen,
                 &commit,
@@ -274,17 +522,7 @@ pub(crate) mod function {
         }
 
         if candidates.is_empty() {
-            return if fallback_to_oid {
-                Ok(Some(Outcome {
-                    id: commit.to_owned(),
-                    name: None,
-                    name_by_oid,
-                    depth: 0,
-                    commits_seen,
-                }))
-            } else {
-                Ok(None)
-            };
+            return Ok((None, commits_seen));
         }
 
         candidates.sort_by(|a, b| {
@@ -301,29 +539,23 @@ pub(crate) mod function {
         commits_seen += finish_depth_computation(
             queue,
             find,
+            graph,
             candidates.first_mut().expect("at least one candidate"),
             seen,
-            buf,
-            parent_buf,
             first_parent,
         )?;
 
-        Ok(candidates.into_iter().next().map(|c| Outcome {
-            name: c.name.into(),
-            id: commit.to_owned(),
-            depth: c.commits_in_its_future,
-            name_by_oid,
+        Ok((
+            candidates.into_iter().next().map(|c| (c.name, c.commits_in_its_future)),
             commits_seen,
-        }))
+        ))
     }
 
-    #[allow(clippy::too_many_arguments)]
     fn parents_by_date_onto_queue_and_track_names<Find, E>(
         find: &mut Find,
-        buf: &mut Vec<u8>,
-        parent_buf: &mut Vec<u8>,
+        graph: &mut Graph,
         queue: &mut VecDeque<(git_hash::ObjectId, u32)>,
-        seen: &mut HashMap<git_hash::ObjectId, Flags, HashBuildHasher>,
+        seen: &mut hash_hasher::HashedMap<git_hash::ObjectId, Flags>,
         commit: &git_hash::oid,
         commit_flags: Flags,
         first_parent: bool,
@@ -332,45 +564,31 @@ pub(crate) mod function {
         Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<Option<CommitRefIter<'b>>, E>,
         E: std::error::Error + Send + Sync + 'static,
     {
-        let commit_iter = find(commit, buf)
-            .map_err(|err| Error::Find {
-                err: Some(err),
-                oid: commit.to_owned(),
-            })?
+        let parents = graph
+            .get(commit, find, commit)?
             .ok_or_else(|| Error::Find {
                 err: None,
                 oid: commit.to_owned(),
-            })?;
-        for token in commit_iter {
-            match token {
-                Ok(git_object::commit::ref_iter::Token::Tree { .. }) => continue,
-                Ok(git_object::commit::ref_iter::Token::Parent { id: parent_id }) => match seen.entry(parent_id) {
-                    hash_map::Entry::Vacant(entry) => {
-                        let parent = match find(&parent_id, parent_buf).map_err(|err| Error::Find {
-                            err: Some(err),
-                            oid: commit.to_owned(),
-                        })? {
-                            Some(p) => p,
-                            None => continue, // skip missing objects, they don't exist.
-                        };
-
-                        let parent_commit_date = parent
-                            .committer()
-                            .map(|committer| committer.time.seconds_since_unix_epoch)
-                            .unwrap_or_default();
-
-                        entry.insert(commit_flags);
-                        match queue.binary_search_by(|c| c.1.cmp(&parent_commit_date).reverse()) {
-                            Ok(_) => queue.push_back((parent_id, parent_commit_date)),
-                            Err(pos) => queue.insert(pos, (parent_id, parent_commit_date)),
-                        };
-                    }
-                    hash_map::Entry::Occupied(mut entry) => {
-                        *entry.get_mut() |= commit_flags;
-                    }
-                },
-                Ok(_unused_token) => break,
-                Err(err) => return Err(err.into()),
+            })?
+            .parents
+            .clone();
+        for parent_id in parents {
+            match seen.entry(parent_id) {
+                hash_map::Entry::Vacant(entry) => {
+                    let parent_commit_date = match graph.get(&parent_id, find, commit)? {
+                        Some(parent) => parent.time,
+                        None => continue, // skip missing objects, they don't exist.
+                    };
+
+                    entry.insert(commit_flags);
+                    match queue.binary_search_by(|c| c.1.cmp(&parent_commit_date).reverse()) {
+                        Ok(_) => queue.push_back((parent_id, parent_commit_date)),
+                        Err(pos) => queue.insert(pos, (parent_id, parent_commit_date)),
+                    };
+                }
+                hash_map::Entry::Occupied(mut entry) => {
+                    *entry.get_mut() |= commit_flags;
+                }
             }
             if first_parent {
                 break;
@@ -380,14 +598,12 @@ pub(crate) mod function {
         Ok(())
     }
 
-    #[allow(clippy::too_many_arguments)]
-    fn finish_depth_computation<'name, Find, E>(
+    fn finish_depth_computation<Find, E>(
         mut queue: VecDeque<(git_hash::ObjectId, u32)>,
-        mut find: Find,
-        best_candidate: &mut Candidate<'name>,
+        find: &mut Find,
+        graph: &mut Graph,
+        best_candidate: &mut Candidate<'_>,
         mut seen: hash_hasher::HashedMap<git_hash::ObjectId, Flags>,
-        mut buf: Vec<u8>,
-        mut parent_buf: Vec<u8>,
         first_parent: bool,
     ) -> Result<u32, Error<E>>
     where
@@ -410,9 +626,8 @@ pub(crate) mod function {
             }
 
             parents_by_date_onto_queue_and_track_names(
-                &mut find,
-                &mut buf,
-                &mut parent_buf,
+                find,
+                graph,
                 &mut queue,
                 &mut seen,
                 &commit,
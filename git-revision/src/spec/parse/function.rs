@@ -435,7 +435,7 @@ where
                 let time = nav
                     .to_str()
                     .ok()
-                    .and_then(git_date::parse)
+                    .and_then(|date| git_date::parse(date, Some(std::time::SystemTime::now())))
                     .ok_or_else(|| Error::Time { input: nav.into() })?;
                 delegate
                     .reflog(delegate::ReflogLookup::Date(time))
@@ -193,6 +193,58 @@ fn typical_usecases() {
     assert!(res.is_none(), "no candidate found on truncated history");
 }
 
+#[test]
+fn cache_describes_like_describe_but_traverses_less() -> crate::Result {
+    let repo = repo();
+    let commits = repo.head_commit()?.ancestors().all()?.collect::<Result<Vec<_>, _>>()?;
+    let name_by_oid: git_revision::hash_hasher::HashedMap<_, _> = vec![
+        (
+            hex_to_id("efd9a841189668f1bab5b8ebade9cd0a1b139a37"),
+            Cow::Borrowed(b"at-c5".as_bstr()),
+        ),
+        (
+            hex_to_id("9152eeee2328073cf23dcf8e90c949170b711659"),
+            b"at-b1c1".as_bstr().into(),
+        ),
+    ]
+    .into_iter()
+    .collect();
+
+    for first_parent in [false, true] {
+        let options = describe::Options {
+            name_by_oid: name_by_oid.clone(),
+            first_parent,
+            fallback_to_oid: true,
+            ..Default::default()
+        };
+        let mut cache = describe::Cache::new(options.clone());
+        let mut commits_seen_without_cache = 0;
+        for id in &commits {
+            let expected = git_revision::describe(
+                id,
+                |id, buf| repo.objects.find_commit_iter(id, buf).map(Some),
+                options.clone(),
+            )?
+            .expect("fallback is enabled");
+            commits_seen_without_cache += expected.commits_seen;
+            let actual = cache
+                .describe(id, |id, buf| repo.objects.find_commit_iter(id, buf).map(Some))?
+                .expect("fallback is enabled");
+            assert_eq!(actual.name, expected.name, "{:?} first-parent: {}", id, first_parent);
+            assert_eq!(actual.depth, expected.depth, "{:?} first-parent: {}", id, first_parent);
+            assert_eq!(actual.id, id.detach());
+            assert_eq!(actual.into_format(7).to_string(), expected.into_format(7).to_string());
+        }
+        assert!(
+            cache.commits_seen() < commits_seen_without_cache,
+            "linear history is only traversed once: {} < {}",
+            cache.commits_seen(),
+            commits_seen_without_cache
+        );
+    }
+    Ok(())
+}
+
 fn repo() -> Repository {
     let dir = git_testtools::scripted_fixture_repo_read_only("make_repo_with_branches.sh").unwrap();
     git_repository::open(dir).unwrap()
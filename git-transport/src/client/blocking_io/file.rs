@@ -74,7 +74,11 @@ impl SpawnProcessOnDemand {
             path,
             ssh_program: None,
             ssh_args: Vec::new(),
-            ssh_env: Vec::new(),
+            // Like git, let the spawned process know which version we would like to speak, it defaults to V1 otherwise.
+            ssh_env: match version {
+                Protocol::V1 => Vec::new(),
+                Protocol::V2 => vec![("GIT_PROTOCOL", format!("version={}", version as usize))],
+            },
             child: None,
             connection: None,
             desired_version: version,
@@ -101,6 +105,23 @@ impl client::TransportWithoutIO for SpawnProcessOnDemand {
     fn connection_persists_across_multiple_requests(&self) -> bool {
         true
     }
+
+    /// Terminate the process we spawned, if any, so the next handshake can spawn a new one asking for `version`.
+    fn downgrade_protocol_version(&mut self, version: Protocol) -> bool {
+        if version >= self.desired_version {
+            return false;
+        }
+        self.connection = None;
+        if let Some(mut child) = self.child.take() {
+            child.kill().ok();
+            child.wait().ok();
+        }
+        if version == Protocol::V1 {
+            self.ssh_env.retain(|(name, _)| *name != "GIT_PROTOCOL");
+        }
+        self.desired_version = version;
+        true
+    }
 }
 
 impl client::Transport for SpawnProcessOnDemand {
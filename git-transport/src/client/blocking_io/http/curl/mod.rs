@@ -13,6 +13,7 @@ pub struct Curl {
     req: SyncSender<remote::Request>,
     res: Receiver<remote::Response>,
     handle: Option<thread::JoinHandle<Result<(), curl::Error>>>,
+    config: http::Options,
 }
 
 impl Curl {
@@ -41,12 +42,16 @@ impl Curl {
         for header in headers {
             list.append(header.as_ref())?;
         }
+        for header in &self.config.extra_headers {
+            list.append(header)?;
+        }
         if self
             .req
             .send(remote::Request {
                 url: url.to_owned(),
                 headers: list,
                 upload,
+                config: self.config.clone(),
             })
             .is_err()
         {
@@ -75,6 +80,7 @@ impl Default for Curl {
             handle: Some(handle),
             req,
             res,
+            config: http::Options::default(),
         }
     }
 }
@@ -100,4 +106,14 @@ impl crate::client::http::Http for Curl {
     ) -> Result<http::PostResponse<Self::Headers, Self::ResponseBody, Self::PostBody>, http::Error> {
         self.make_request(url, headers, true)
     }
+
+    fn configure(
+        &mut self,
+        config: &dyn std::any::Any,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        if let Some(config) = config.downcast_ref::<http::Options>() {
+            self.config = config.clone();
+        }
+        Ok(())
+    }
 }
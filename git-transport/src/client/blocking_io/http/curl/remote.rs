@@ -3,7 +3,6 @@ use std::{
     io::{Read, Write},
     sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError},
     thread,
-    time::Duration,
 };
 
 use curl::easy::Easy2;
@@ -17,11 +16,18 @@ struct Handler {
     send_data: Option<pipe::Writer>,
     receive_body: Option<pipe::Reader>,
     checked_status: bool,
+    /// The status of a redirect response along with its location, which are collected until all headers were received.
+    redirect: Option<(u16, Option<String>)>,
 }
 
 impl Handler {
     fn reset(&mut self) {
         self.checked_status = false;
+        self.redirect = None;
+    }
+    fn parse_location(data: &[u8]) -> Option<String> {
+        let (name, value) = std::str::from_utf8(data).ok()?.split_once(':')?;
+        name.eq_ignore_ascii_case("location").then(|| value.trim().to_owned())
     }
     fn parse_status_inner(data: &[u8]) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
         let code = data
@@ -60,12 +66,32 @@ impl curl::easy::Handler for Handler {
     fn header(&mut self, data: &[u8]) -> bool {
         match self.send_header.as_mut() {
             Some(writer) => {
-                if self.checked_status {
+                if let Some((status, location)) = self.redirect.as_mut() {
+                    if let Some(value) = Handler::parse_location(data) {
+                        *location = Some(value);
+                    }
+                    if data == b"\r\n" || data == b"\n" {
+                        let err: Box<dyn std::error::Error + Send + Sync> = match location.take() {
+                            Some(location) => Box::new(http::Error::Redirect {
+                                status: *status,
+                                location,
+                            }),
+                            None => format!("Received HTTP status {} without a location to redirect to", status).into(),
+                        };
+                        writer.channel.send(Err(io::Error::new(io::ErrorKind::Other, err))).ok();
+                        return false;
+                    }
+                    true
+                } else if self.checked_status {
                     writer.write_all(data).is_ok()
                 } else {
                     self.checked_status = true;
                     match Handler::parse_status(data) {
                         None => true,
+                        Some((status, _)) if (300..=399).contains(&status) => {
+                            self.redirect = Some((status as u16, None));
+                            true
+                        }
                         Some((status, err)) => {
                             writer
                                 .channel
@@ -92,6 +118,7 @@ pub struct Request {
     pub url: String,
     pub headers: curl::easy::List,
     pub upload: bool,
+    pub config: http::Options,
 }
 
 pub struct Response {
@@ -110,18 +137,36 @@ pub fn new() -> (
     let handle = std::thread::spawn(move || -> Result<(), curl::Error> {
         let mut handle = Easy2::new(Handler::default());
 
-        for Request { url, headers, upload } in req_recv {
+        for Request {
+            url,
+            headers,
+            upload,
+            config,
+        } in req_recv
+        {
             handle.url(&url)?;
 
             // GitHub sends 'chunked' to avoid unknown clients to choke on the data, I suppose
             handle.post(upload)?;
             handle.http_headers(headers)?;
             handle.transfer_encoding(false)?;
-            handle.connect_timeout(Duration::from_secs(20))?;
-            // handle.proxy("http://localhost:9090")?; // DEBUG
-            let low_bytes_per_second = 1024;
-            handle.low_speed_limit(low_bytes_per_second)?;
-            handle.low_speed_time(Duration::from_secs(20))?;
+            handle.connect_timeout(config.connect_timeout)?;
+            if let Some(proxy) = &config.proxy {
+                handle.proxy(proxy)?;
+            }
+            if let Some(ca_info) = &config.ssl_ca_info {
+                handle.cainfo(ca_info)?;
+            }
+            handle.ssl_verify_peer(config.ssl_verify)?;
+            handle.ssl_verify_host(config.ssl_verify)?;
+            if let Some(cookie_file) = &config.cookie_file {
+                handle.cookie_file(cookie_file)?;
+                if config.save_cookies {
+                    handle.cookie_jar(cookie_file)?;
+                }
+            }
+            handle.low_speed_limit(config.low_speed_limit_bytes_per_second)?;
+            handle.low_speed_time(config.low_speed_time)?;
 
             let (receive_data, receive_headers, send_body) = {
                 let handler = handle.get_mut();
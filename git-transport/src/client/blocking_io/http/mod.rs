@@ -5,6 +5,7 @@ use std::{
 };
 
 use git_packetline::PacketLineRef;
+pub use options::{FollowRedirects, Options};
 pub use traits::{Error, GetResponse, Http, PostResponse};
 
 use crate::{
@@ -18,6 +19,8 @@ mod curl;
 ///
 mod traits;
 
+mod options;
+
 /// The actual http client implementation.
 #[cfg(feature = "http-client-curl")]
 pub type Impl = curl::Curl;
@@ -33,6 +36,10 @@ pub struct Transport<H: Http> {
     service: Option<Service>,
     line_provider: Option<git_packetline::StreamingPeekableIter<H::ResponseBody>>,
     identity: Option<git_sec::identity::Account>,
+    follow_redirects: FollowRedirects,
+    max_redirects: usize,
+    /// The amount of handshakes performed so far, to know which one is the initial one.
+    handshakes: usize,
 }
 
 impl Transport<Impl> {
@@ -48,19 +55,21 @@ impl Transport<Impl> {
             http: Impl::default(),
             line_provider: None,
             identity: None,
+            follow_redirects: FollowRedirects::default(),
+            max_redirects: Options::default().max_redirects,
+            handshakes: 0,
         }
     }
 }
 
 impl<H: Http> Transport<H> {
     fn check_content_type(service: Service, kind: &str, headers: <H as Http>::Headers) -> Result<(), client::Error> {
+        Self::check_content_type_of_lines(service, kind, &headers.lines().collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn check_content_type_of_lines(service: Service, kind: &str, headers: &[String]) -> Result<(), client::Error> {
         let wanted_content_type = format!("Content-Type: application/x-{}-{}", service.as_str(), kind);
-        if !headers
-            .lines()
-            .collect::<Result<Vec<_>, _>>()?
-            .iter()
-            .any(|l| l == &wanted_content_type)
-        {
+        if !headers.iter().any(|l| l == &wanted_content_type) {
             return Err(client::Error::Http(Error::Detail {
                 description: format!(
                     "Didn't find '{}' header to indicate 'smart' protocol, and 'dumb' protocol is not supported.",
@@ -89,6 +98,44 @@ impl<H: Http> Transport<H> {
     }
 }
 
+/// Return the location of the redirect that `err` indicates, if it is one.
+fn redirect_location(err: &std::io::Error) -> Option<&str> {
+    match err.get_ref()?.downcast_ref::<Error>()? {
+        Error::Redirect { location, .. } => Some(location),
+        _ => None,
+    }
+}
+
+/// Return the scheme, host and port of `url`, with default ports removed, to compare origins like browsers do.
+fn origin(url: &str) -> String {
+    let (scheme, rest) = url.split_once("://").unwrap_or(("", url));
+    let authority = rest.split(|c| c == '/' || c == '?').next().unwrap_or_default();
+    let host_and_port = authority.rsplit_once('@').map_or(authority, |(_userinfo, host)| host);
+    let host_and_port = match (scheme, host_and_port.rsplit_once(':')) {
+        ("http", Some((host, "80"))) | ("https", Some((host, "443"))) => host,
+        _ => host_and_port,
+    };
+    format!("{}://{}", scheme, host_and_port).to_ascii_lowercase()
+}
+
+/// Resolve `location` of a redirect response relative to the `url` that was requested.
+fn resolve_redirect(url: &str, location: &str) -> String {
+    if location.starts_with("http://") || location.starts_with("https://") {
+        location.to_owned()
+    } else if let Some(location) = location.strip_prefix("//") {
+        format!(
+            "{}://{}",
+            url.split_once("://").map_or("http", |(scheme, _)| scheme),
+            location
+        )
+    } else if location.starts_with('/') {
+        format!("{}{}", origin(url), location)
+    } else {
+        let path = url.split('?').next().unwrap_or(url);
+        format!("{}/{}", path.rsplit_once('/').map_or(path, |(dir, _)| dir), location)
+    }
+}
+
 fn append_url(base: &str, suffix: &str) -> String {
     let mut buf = base.to_owned();
     if base.as_bytes().last() != Some(&b'/') {
@@ -156,9 +203,33 @@ impl<H: Http> client::TransportWithoutIO for Transport<H> {
         &self.supported_versions
     }
 
+    /// As each request is a new connection, we can always start over with an older version.
+    fn downgrade_protocol_version(&mut self, version: Protocol) -> bool {
+        if version >= self.desired_version {
+            return false;
+        }
+        self.desired_version = version;
+        self.actual_version = version;
+        self.supported_versions = [version];
+        self.service = None;
+        self.line_provider = None;
+        true
+    }
+
     fn connection_persists_across_multiple_requests(&self) -> bool {
         false
     }
+
+    fn configure(
+        &mut self,
+        config: &dyn std::any::Any,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        if let Some(options) = config.downcast_ref::<Options>() {
+            self.follow_redirects = options.follow_redirects;
+            self.max_redirects = options.max_redirects;
+        }
+        self.http.configure(config)
+    }
 }
 
 impl<H: Http> client::Transport for Transport<H> {
@@ -167,7 +238,8 @@ impl<H: Http> client::Transport for Transport<H> {
         service: Service,
         extra_parameters: &'a [(&'a str, Option<&'a str>)],
     ) -> Result<client::SetServiceResponse<'_>, client::Error> {
-        let url = append_url(self.url.as_ref(), &format!("info/refs?service={}", service.as_str()));
+        let suffix = format!("info/refs?service={}", service.as_str());
+        let mut url = append_url(self.url.as_ref(), &suffix);
         let static_headers = [Cow::Borrowed(self.user_agent_header)];
         let mut dynamic_headers = Vec::<Cow<'_, str>>::new();
         if self.desired_version != Protocol::V1 || !extra_parameters.is_empty() {
@@ -192,11 +264,58 @@ impl<H: Http> client::Transport for Transport<H> {
             );
             dynamic_headers.push(format!("Git-Protocol: {}", parameters).into());
         }
-        self.add_basic_auth_if_present(&mut dynamic_headers)?;
-        let GetResponse { headers, body } = self
-            .http
-            .get(url.as_ref(), static_headers.iter().chain(&dynamic_headers))?;
-        <Transport<H>>::check_content_type(service, "advertisement", headers)?;
+        let follow_redirects = match self.follow_redirects {
+            FollowRedirects::None => false,
+            FollowRedirects::Initial => self.handshakes == 0,
+            FollowRedirects::All => true,
+        };
+        self.handshakes += 1;
+        let mut redirects = 0;
+        let body = loop {
+            let mut auth_header = Vec::new();
+            self.add_basic_auth_if_present(&mut auth_header)?;
+            let GetResponse { headers, body } = self.http.get(
+                url.as_ref(),
+                static_headers.iter().chain(&dynamic_headers).chain(&auth_header),
+            )?;
+            let location = match headers.lines().collect::<Result<Vec<_>, _>>() {
+                Ok(headers) => {
+                    <Transport<H>>::check_content_type_of_lines(service, "advertisement", &headers)?;
+                    break body;
+                }
+                Err(err) => match redirect_location(&err) {
+                    Some(location) if follow_redirects && redirects < self.max_redirects => {
+                        resolve_redirect(&url, location)
+                    }
+                    Some(location) if follow_redirects => {
+                        return Err(client::Error::Http(Error::Detail {
+                            description: format!(
+                                "Gave up after following {} redirects, the last one to {:?}",
+                                redirects, location
+                            ),
+                        }))
+                    }
+                    _ => return Err(err.into()),
+                },
+            };
+            redirects += 1;
+            if origin(&location) != origin(&url) {
+                // Like git, never send credentials to an origin other than the one they were provided for.
+                self.identity = None;
+            }
+            url = location;
+        };
+        if redirects != 0 {
+            // Like git, use the url we were redirected to for all subsequent requests of this session.
+            self.url = url
+                .strip_suffix(&suffix)
+                .map(|base| base.trim_end_matches('/').to_owned())
+                .ok_or_else(|| {
+                    client::Error::Http(Error::Detail {
+                        description: format!("Unable to update the url base from redirection to {:?}", url),
+                    })
+                })?;
+        }
 
         let line_reader = self
             .line_provider
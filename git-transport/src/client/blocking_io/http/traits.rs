@@ -6,6 +6,8 @@ pub enum Error {
     Detail { description: String },
     #[error("An IO error occurred while uploading the body of a POST request")]
     PostBody(#[from] std::io::Error),
+    #[error("Received HTTP status {status} redirecting to {location:?}")]
+    Redirect { status: u16, location: String },
 }
 
 /// The return value of [Http::get()].
@@ -38,6 +40,9 @@ impl<A, B, C> From<PostResponse<A, B, C>> for GetResponse<A, B> {
 }
 
 /// A trait to abstract the HTTP operations needed to power all git interactions: read via GET and write via POST.
+///
+/// Implementations must not follow redirects themselves, but fail reading the response headers with an [`std::io::Error`]
+/// wrapping [`Error::Redirect`] instead, which lets the caller decide if and how to follow it.
 #[allow(clippy::type_complexity)]
 pub trait Http {
     /// A type providing headers line by line.
@@ -67,4 +72,15 @@ pub trait Http {
         url: &str,
         headers: impl IntoIterator<Item = impl AsRef<str>>,
     ) -> Result<PostResponse<Self::Headers, Self::ResponseBody, Self::PostBody>, Error>;
+
+    /// Pass `config` which can be cast to the options type of the implementation, like [`Options`][super::Options], to
+    /// configure it for all subsequent requests.
+    ///
+    /// Configuration of unknown types is ignored, which is also what the default implementation does.
+    fn configure(
+        &mut self,
+        _config: &dyn std::any::Any,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        Ok(())
+    }
 }
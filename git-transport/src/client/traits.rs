@@ -41,11 +41,31 @@ pub trait TransportWithoutIO {
         &[]
     }
 
+    /// If the handshake failed while receiving the advertisement of the desired protocol version, use this method to
+    /// ask for the older `version` in the next handshake instead, which helps with servers that fail to speak newer versions.
+    ///
+    /// Returns `true` if another handshake can be performed with `version`, or `false` if the transport can't downgrade,
+    /// which is what the default implementation does.
+    fn downgrade_protocol_version(&mut self, _version: Protocol) -> bool {
+        false
+    }
+
     /// Returns true if the transport provides persistent connections across multiple requests, or false otherwise.
     /// Not being persistent implies that certain information has to be resent on each 'turn'
     /// of the fetch negotiation or that the end of interaction (i.e. no further request will be made) has to be indicated
     /// to the server for most graceful termination of the connection.
     fn connection_persists_across_multiple_requests(&self) -> bool;
+
+    /// Pass `config` to the transport to configure it, which is expected to be the options type of the transport implementation,
+    /// like [`http::Options`][crate::client::http::Options] for the HTTP transport.
+    ///
+    /// Configuration the transport doesn't know is ignored, which is also what the default implementation does.
+    fn configure(
+        &mut self,
+        _config: &dyn std::any::Any,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        Ok(())
+    }
 }
 
 // Would be nice if the box implementation could auto-forward to all implemented traits.
@@ -67,9 +87,20 @@ impl<T: TransportWithoutIO + ?Sized> TransportWithoutIO for Box<T> {
         self.deref().supported_protocol_versions()
     }
 
+    fn downgrade_protocol_version(&mut self, version: Protocol) -> bool {
+        self.deref_mut().downgrade_protocol_version(version)
+    }
+
     fn connection_persists_across_multiple_requests(&self) -> bool {
         self.deref().connection_persists_across_multiple_requests()
     }
+
+    fn configure(
+        &mut self,
+        config: &dyn std::any::Any,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        self.deref_mut().configure(config)
+    }
 }
 
 impl<T: TransportWithoutIO + ?Sized> TransportWithoutIO for &mut T {
@@ -90,7 +121,18 @@ impl<T: TransportWithoutIO + ?Sized> TransportWithoutIO for &mut T {
         self.deref().supported_protocol_versions()
     }
 
+    fn downgrade_protocol_version(&mut self, version: Protocol) -> bool {
+        self.deref_mut().downgrade_protocol_version(version)
+    }
+
     fn connection_persists_across_multiple_requests(&self) -> bool {
         self.deref().connection_persists_across_multiple_requests()
     }
+
+    fn configure(
+        &mut self,
+        config: &dyn std::any::Any,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        self.deref_mut().configure(config)
+    }
 }
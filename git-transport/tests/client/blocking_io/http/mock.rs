@@ -22,12 +22,20 @@ enum CommandResult {
 
 pub struct Server {
     pub addr: SocketAddr,
-    send_command: std::sync::mpsc::SyncSender<Command>,
+    send_command: std::sync::mpsc::Sender<Command>,
     recv_result: std::sync::mpsc::Receiver<CommandResult>,
 }
 
 impl Server {
     pub fn new(fixture: Vec<u8>) -> Self {
+        let server = Self::without_responses();
+        server.next_read_and_respond_with(fixture);
+        server
+    }
+
+    /// Create a server which doesn't respond until told to with `next_read_and_respond_with()`, which can be called
+    /// multiple times in advance to respond to consecutive requests.
+    pub fn without_responses() -> Self {
         let ports = (15411..).take(10);
         let listener = std::net::TcpListener::bind(
             ports
@@ -37,8 +45,8 @@ impl Server {
         )
         .expect("one of these ports to be free");
         let addr = listener.local_addr().expect("a local address");
-        let (send_result, recv_result) = std::sync::mpsc::sync_channel(0);
-        let (send_command, recv_commands) = std::sync::mpsc::sync_channel(0);
+        let (send_result, recv_result) = std::sync::mpsc::channel();
+        let (send_command, recv_commands) = std::sync::mpsc::channel();
         std::thread::spawn(move || {
             for command in recv_commands {
                 match command {
@@ -61,9 +69,6 @@ impl Server {
                 }
             }
         });
-        send_command
-            .send(Command::ReadAndRespond(fixture))
-            .expect("send to go through when thread is up");
         Server {
             addr,
             send_command,
@@ -99,6 +99,129 @@ fn http_error_results_in_observable_error() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn extra_headers_are_sent_with_each_request() -> crate::Result {
+    let (server, mut client) =
+        mock::serve_and_connect("v1/http-handshake.response", "path/not-important", Protocol::V1)?;
+    client
+        .configure(&http::Options {
+            extra_headers: vec!["X-Extra: value".into()],
+            ..Default::default()
+        })
+        .map_err(|err| err as crate::Error)?;
+    client.handshake(Service::UploadPack, &[])?;
+
+    assert_eq!(
+        server.received_as_string().lines().collect::<Vec<_>>(),
+        format!(
+            "GET /path/not-important/info/refs?service=git-upload-pack HTTP/1.1
+Host: 127.0.0.1:{}
+Accept: */*
+User-Agent: git/oxide-{}
+X-Extra: value
+
+",
+            server.addr.port(),
+            env!("CARGO_PKG_VERSION")
+        )
+        .lines()
+        .collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+fn redirect_to(location: &str) -> Vec<u8> {
+    format!(
+        "HTTP/1.1 301 Moved Permanently\r\nLocation: {}\r\nContent-Length: 0\r\n\r\n",
+        location
+    )
+    .into_bytes()
+}
+
+#[test]
+fn redirects_of_the_initial_request_are_followed_and_change_the_url_of_the_session() -> crate::Result {
+    let server = mock::Server::without_responses();
+    server.next_read_and_respond_with(redirect_to("/new/path/info/refs?service=git-upload-pack"));
+    server.next_read_and_respond_with(fixture_bytes("v1/http-handshake.response"));
+    let mut c = http::connect(&format!("http://{}/old/path", server.addr), Protocol::V1)?;
+    c.handshake(Service::UploadPack, &[])?;
+
+    assert!(server
+        .received_as_string()
+        .starts_with("GET /old/path/info/refs?service=git-upload-pack "));
+    assert!(server
+        .received_as_string()
+        .starts_with("GET /new/path/info/refs?service=git-upload-pack "));
+    assert_eq!(c.to_url(), format!("http://{}/new/path", server.addr));
+
+    server.next_read_and_respond_with(fixture_bytes("v1/http-handshake.response"));
+    c.request(client::WriteMode::Binary, client::MessageKind::Flush)?;
+    assert!(
+        server
+            .received_as_string()
+            .starts_with("POST /new/path/git-upload-pack "),
+        "subsequent requests use the url we were redirected to"
+    );
+    Ok(())
+}
+
+#[test]
+fn credentials_are_not_sent_to_other_origins_when_redirected() -> crate::Result {
+    let server = mock::Server::without_responses();
+    server.next_read_and_respond_with(redirect_to(&format!(
+        "http://localhost:{}/path/info/refs?service=git-upload-pack",
+        server.addr.port()
+    )));
+    server.next_read_and_respond_with(fixture_bytes("v1/http-handshake.response"));
+    let mut c = http::connect(&format!("http://{}/path", server.addr), Protocol::V1)?;
+    c.set_identity(git_sec::identity::Account {
+        username: "user".into(),
+        password: "password".into(),
+    })?;
+    c.handshake(Service::UploadPack, &[])?;
+
+    assert!(server.received_as_string().contains("Authorization: Basic"));
+    assert!(
+        !server.received_as_string().contains("Authorization"),
+        "the host differs, so the credentials aren't forwarded"
+    );
+    assert_eq!(c.to_url(), format!("http://localhost:{}/path", server.addr.port()));
+    Ok(())
+}
+
+#[test]
+fn redirects_are_limited_and_can_be_turned_off() -> crate::Result {
+    for (options, expected_error) in [
+        (
+            http::Options {
+                follow_redirects: http::FollowRedirects::None,
+                ..Default::default()
+            },
+            "Received HTTP status 301 redirecting to \"/a/info/refs?service=git-upload-pack\"",
+        ),
+        (
+            http::Options {
+                max_redirects: 1,
+                ..Default::default()
+            },
+            "Gave up after following 1 redirects, the last one to \"/b/info/refs?service=git-upload-pack\"",
+        ),
+    ] {
+        let server = mock::Server::without_responses();
+        server.next_read_and_respond_with(redirect_to("/a/info/refs?service=git-upload-pack"));
+        server.next_read_and_respond_with(redirect_to("/b/info/refs?service=git-upload-pack"));
+        let mut c = http::connect(&format!("http://{}/path", server.addr), Protocol::V1)?;
+        c.configure(&options).map_err(|err| err as crate::Error)?;
+        let err = c
+            .handshake(Service::UploadPack, &[])
+            .err()
+            .expect("redirects are not followed");
+        let message = err.source().map_or_else(|| err.to_string(), ToString::to_string);
+        assert_eq!(message, expected_error);
+    }
+    Ok(())
+}
+
 #[test]
 fn handshake_v1() -> crate::Result {
     let (server, mut c) = mock::serve_and_connect(
@@ -228,6 +351,29 @@ User-Agent: git/oxide-{}
     Ok(())
 }
 
+#[test]
+fn downgrade_to_v1_does_not_announce_a_version() -> crate::Result {
+    let (server, mut c) = mock::serve_and_connect(
+        "v1/http-handshake.response",
+        "path/not/important/due/to/mock",
+        Protocol::V2,
+    )?;
+    assert!(c.downgrade_protocol_version(Protocol::V1), "V2 can be downgraded");
+    assert!(
+        !c.downgrade_protocol_version(Protocol::V1),
+        "there is nothing to do if the version is already low enough"
+    );
+    assert_eq!(c.supported_protocol_versions(), &[Protocol::V1]);
+
+    let SetServiceResponse { actual_protocol, .. } = c.handshake(Service::UploadPack, &[])?;
+    assert_eq!(actual_protocol, Protocol::V1);
+    assert!(
+        !server.received_as_string().contains("Git-Protocol"),
+        "V1 is requested by not mentioning any version, like git does"
+    );
+    Ok(())
+}
+
 #[test]
 fn clone_v1() -> crate::Result {
     let (server, mut c) = mock::serve_and_connect(
@@ -5,6 +5,7 @@ pub struct Ancestors<Find, Predicate, StateMut> {
     state: StateMut,
     parents: Parents,
     sorting: Sorting,
+    tie_break: TieBreak,
 }
 
 /// Specify how to handle commit parents during traversal.
@@ -36,6 +37,26 @@ pub enum Sorting {
     /// This mode benefits greatly from having an object_cache in `find()`
     /// to avoid having to lookup each commit twice.
     ByCommitTimeNewestFirst,
+    /// Commits are sorted by their author time in descending order, that is newest first.
+    ///
+    /// Unlike the commit time, the author time is retained when commits are rebased or cherry-picked, which makes
+    /// it more suitable to learn about the order in which changes were made.
+    ///
+    /// # Performance
+    ///
+    /// This mode benefits greatly from having an object_cache in `find()`
+    /// to avoid having to lookup each commit twice.
+    ByAuthorTimeNewestFirst,
+    /// Commits are sorted by the priority the given function assigns to them, in descending order, that is commits
+    /// with a higher priority are returned first.
+    ///
+    /// The function receives the id of the commit along with its data, and is called once per commit.
+    ///
+    /// # Performance
+    ///
+    /// This mode benefits greatly from having an object_cache in `find()`
+    /// to avoid having to lookup each commit twice.
+    ByPriority(fn(&git_hash::oid, git_object::CommitRefIter<'_>) -> u64),
 }
 
 impl Default for Sorting {
@@ -44,6 +65,24 @@ impl Default for Sorting {
     }
 }
 
+/// Specify how to order commits that are equal according to their [`Sorting`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TieBreak {
+    /// Commits are returned in the order in which they were encountered, parents after their children and in the order
+    /// they are listed in their commit.
+    Encountered,
+    /// Commits are returned in ascending order of their object id, making the order independent of how they were encountered.
+    ///
+    /// Note that this may return a parent before the child of another commit if they are all equal.
+    ObjectId,
+}
+
+impl Default for TieBreak {
+    fn default() -> Self {
+        TieBreak::Encountered
+    }
+}
+
 ///
 pub mod ancestors {
     use std::{borrow::BorrowMut, collections::VecDeque, iter::FromIterator};
@@ -51,7 +90,7 @@ pub mod ancestors {
     use git_hash::{oid, ObjectId};
     use git_object::CommitRefIter;
 
-    use crate::commit::{Ancestors, Parents, Sorting};
+    use crate::commit::{Ancestors, Parents, Sorting, TieBreak};
 
     /// The error is part of the item returned by the [Ancestors] iterator.
     #[derive(Debug, thiserror::Error)]
@@ -66,12 +105,13 @@ pub mod ancestors {
         ObjectDecode(#[from] git_object::decode::Error),
     }
 
-    type TimeInSeconds = u32;
+    /// The time in seconds or the value provided by a custom priority function.
+    type Priority = u64;
 
     /// The state used and potentially shared by multiple graph traversals.
     #[derive(Default, Clone)]
     pub struct State {
-        next: VecDeque<(ObjectId, TimeInSeconds)>,
+        next: VecDeque<(ObjectId, Priority)>,
         buf: Vec<u8>,
         seen: hash_hasher::HashedSet<ObjectId>,
         parents_buf: Vec<u8>,
@@ -83,6 +123,42 @@ pub mod ancestors {
             self.buf.clear();
             self.seen.clear();
         }
+
+        /// Sort all queued commits by their priority, in descending order.
+        fn sort(&mut self, tie_break: TieBreak) {
+            let mut v = Vec::from_iter(std::mem::take(&mut self.next).into_iter());
+            v.sort_by(|a, b| {
+                let by_priority = a.1.cmp(&b.1).reverse();
+                match tie_break {
+                    TieBreak::Encountered => by_priority,
+                    TieBreak::ObjectId => by_priority.then_with(|| a.0.cmp(&b.0)),
+                }
+            });
+            self.next = v.into();
+        }
+    }
+
+    /// Queue `id` with `priority` behind all commits with a higher priority, and according to `tie_break` otherwise.
+    fn enqueue(next: &mut VecDeque<(ObjectId, Priority)>, id: ObjectId, priority: Priority, tie_break: TieBreak) {
+        let pos = next.partition_point(|(queued_id, queued_priority)| match tie_break {
+            TieBreak::Encountered => *queued_priority >= priority,
+            TieBreak::ObjectId => *queued_priority > priority || (*queued_priority == priority && *queued_id < id),
+        });
+        next.insert(pos, (id, priority));
+    }
+
+    /// Return the priority of the commit with `id` and data `commit_iter` according to `sorting`.
+    fn priority_of(
+        sorting: Sorting,
+        id: &oid,
+        commit_iter: CommitRefIter<'_>,
+    ) -> Result<Priority, git_object::decode::Error> {
+        Ok(match sorting {
+            Sorting::Topological => 0,
+            Sorting::ByCommitTimeNewestFirst => commit_iter.committer()?.time.seconds_since_unix_epoch.into(),
+            Sorting::ByAuthorTimeNewestFirst => commit_iter.author()?.time.seconds_since_unix_epoch.into(),
+            Sorting::ByPriority(priority) => priority(id, commit_iter),
+        })
     }
 
     impl<Find, Predicate, StateMut> Ancestors<Find, Predicate, StateMut> {
@@ -99,24 +175,32 @@ pub mod ancestors {
         StateMut: BorrowMut<State>,
         E: std::error::Error + Send + Sync + 'static,
     {
-        /// Set the sorting method, either topological or by author date
+        /// Set the sorting method, either topological, by commit or author date, or by a custom priority.
         pub fn sorting(mut self, sorting: Sorting) -> Result<Self, Error> {
             self.sorting = sorting;
             if !matches!(self.sorting, Sorting::Topological) {
                 let state = self.state.borrow_mut();
-                for (commit_id, commit_time) in state.next.iter_mut() {
+                for (commit_id, priority) in state.next.iter_mut() {
                     let commit_iter = (self.find)(commit_id, &mut state.buf).map_err(|err| Error::FindExisting {
                         oid: *commit_id,
                         source: err.into(),
                     })?;
-                    *commit_time = commit_iter.committer()?.time.seconds_since_unix_epoch;
+                    *priority = priority_of(sorting, commit_id, commit_iter)?;
                 }
-                let mut v = Vec::from_iter(std::mem::take(&mut state.next).into_iter());
-                v.sort_by(|a, b| a.1.cmp(&b.1).reverse());
-                state.next = v.into();
+                state.sort(self.tie_break);
             }
             Ok(self)
         }
+
+        /// Set how to order commits that are equal according to our [sorting][Self::sorting()], which only has an effect
+        /// if the sorting isn't topological.
+        pub fn tie_break(mut self, tie_break: TieBreak) -> Self {
+            self.tie_break = tie_break;
+            if !matches!(self.sorting, Sorting::Topological) {
+                self.state.borrow_mut().sort(tie_break);
+            }
+            self
+        }
     }
 
     impl<Find, StateMut, E> Ancestors<Find, fn(&oid) -> bool, StateMut>
@@ -183,6 +267,7 @@ pub mod ancestors {
                 state,
                 parents: Default::default(),
                 sorting: Default::default(),
+                tie_break: Default::default(),
             }
         }
     }
@@ -202,7 +287,9 @@ pub mod ancestors {
             } else {
                 match self.sorting {
                     Sorting::Topological => self.next_by_topology(),
-                    Sorting::ByCommitTimeNewestFirst => self.next_by_commit_date(),
+                    Sorting::ByCommitTimeNewestFirst | Sorting::ByAuthorTimeNewestFirst | Sorting::ByPriority(_) => {
+                        self.next_by_priority()
+                    }
                 }
             }
         }
@@ -215,10 +302,11 @@ pub mod ancestors {
         StateMut: BorrowMut<State>,
         E: std::error::Error + Send + Sync + 'static,
     {
-        fn next_by_commit_date(&mut self) -> Option<Result<ObjectId, Error>> {
+        fn next_by_priority(&mut self) -> Option<Result<ObjectId, Error>> {
+            let (sorting, tie_break) = (self.sorting, self.tie_break);
             let state = self.state.borrow_mut();
 
-            let (oid, _commit_time) = state.next.pop_front()?;
+            let (oid, _priority) = state.next.pop_front()?;
             match (self.find)(&oid, &mut state.buf) {
                 Ok(commit_iter) => {
                     let mut count = 0;
@@ -238,19 +326,10 @@ pub mod ancestors {
                                 }
 
                                 let parent = (self.find)(id.as_ref(), &mut state.parents_buf).ok();
-                                let parent_commit_time = parent
-                                    .and_then(|parent| {
-                                        parent
-                                            .committer()
-                                            .ok()
-                                            .map(|committer| committer.time.seconds_since_unix_epoch)
-                                    })
+                                let parent_priority = parent
+                                    .and_then(|parent| priority_of(sorting, &id, parent).ok())
                                     .unwrap_or_default();
-
-                                match state.next.binary_search_by(|c| c.1.cmp(&parent_commit_time).reverse()) {
-                                    Ok(_) => state.next.push_back((id, parent_commit_time)), // collision => topo-sort
-                                    Err(pos) => state.next.insert(pos, (id, parent_commit_time)), // otherwise insert by commit-time
-                                }
+                                enqueue(&mut state.next, id, parent_priority, tie_break);
 
                                 if is_first && matches!(self.parents, Parents::First) {
                                     break;
@@ -281,7 +360,7 @@ pub mod ancestors {
     {
         fn next_by_topology(&mut self) -> Option<Result<ObjectId, Error>> {
             let state = self.state.borrow_mut();
-            let (oid, _commit_time) = state.next.pop_front()?;
+            let (oid, _priority) = state.next.pop_front()?;
             match (self.find)(&oid, &mut state.buf) {
                 Ok(commit_iter) => {
                     for token in commit_iter {
@@ -236,4 +236,84 @@ mod ancestor {
         .with_parents(commit::Parents::First)
         .check()
     }
+
+    #[test]
+    fn author_date_sorted_commits() -> crate::Result {
+        TraversalAssertion::new(
+            "make_traversal_repo_for_commits_with_dates.sh",
+            &["ba912da9a89f96c695e64319eee64ed8592fd766"],
+            &[
+                "85d88ba2bdd9f9a8991f36bc40a7c77dabd9df56",
+                "7e8fd95af0fc552632b247e26c581f641bde9198",
+                "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7",
+                "134385f6d781b7e97062102c6a483440bfda2a03",
+            ],
+        )
+        .with_sorting(commit::Sorting::ByAuthorTimeNewestFirst)
+        .check()?;
+
+        TraversalAssertion::new(
+            "make_traversal_repo_for_commits_with_dates.sh",
+            &["ba912da9a89f96c695e64319eee64ed8592fd766"],
+            &[
+                "7e8fd95af0fc552632b247e26c581f641bde9198",
+                "85d88ba2bdd9f9a8991f36bc40a7c77dabd9df56",
+                "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7",
+                "134385f6d781b7e97062102c6a483440bfda2a03",
+            ],
+        )
+        .with_sorting(commit::Sorting::ByCommitTimeNewestFirst)
+        .check()
+    }
+
+    #[test]
+    fn custom_priority_sorted_commits() -> crate::Result {
+        TraversalAssertion::new(
+            "make_traversal_repo_for_commits_with_dates.sh",
+            &["ba912da9a89f96c695e64319eee64ed8592fd766"],
+            &[
+                "7e8fd95af0fc552632b247e26c581f641bde9198",
+                "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7",
+                "134385f6d781b7e97062102c6a483440bfda2a03",
+                "85d88ba2bdd9f9a8991f36bc40a7c77dabd9df56",
+            ],
+        )
+        .with_sorting(commit::Sorting::ByPriority(|_id, commit| {
+            let oldest_author_first = u32::MAX
+                - commit
+                    .author()
+                    .map(|author| author.time.seconds_since_unix_epoch)
+                    .unwrap_or_default();
+            oldest_author_first.into()
+        }))
+        .check()
+    }
+
+    #[test]
+    fn commits_with_the_same_date_are_sorted_by_tie_break() -> crate::Result {
+        let dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_commits_with_dates.sh")?;
+        let store = git_odb::at(dir.join(".git").join("objects"))?;
+        let (s1, s2) = (
+            hex_to_id("84b1a5085aad411e5b354df5454eb9e27d873515"),
+            hex_to_id("cb79959e84c14436d0d88b067f34aee1ef6b9d49"),
+        );
+        let rest = [
+            hex_to_id("9902e3c3e8f0c569b4ab295ddf473e6de763e1e7"),
+            hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03"),
+        ];
+        for (tie_break, expected) in [
+            (commit::TieBreak::Encountered, [s2, s1, rest[0], rest[1]]),
+            (commit::TieBreak::ObjectId, [s1, rest[0], rest[1], s2]),
+        ] {
+            let store = store.clone();
+            let oids = commit::Ancestors::new([s2, s1], commit::ancestors::State::default(), move |oid, buf| {
+                store.find_commit_iter(oid, buf).map(|t| t.0)
+            })
+            .sorting(commit::Sorting::ByCommitTimeNewestFirst)?
+            .tie_break(tie_break)
+            .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(oids, expected, "{:?}", tie_break);
+        }
+        Ok(())
+    }
 }
@@ -18,3 +18,19 @@ GIT_COMMITTER_DATE="2000-01-02 00:00:00 +0000" git commit -q --allow-empty -m c2
 
 # Commit from branch1 made in 2001 merged in 2002
 GIT_COMMITTER_DATE="2002-01-02 00:00:00 +0000" git merge branch1 -m m1b1 #288e509293165cb5630d08f4185bdf2445bf6170-
+
+# Commits whose author dates are in a different order than their commit dates, like after a rebase
+git checkout -q -b rebased main~1
+GIT_AUTHOR_DATE="2004-01-02 00:00:00 +0000" GIT_COMMITTER_DATE="2010-01-02 00:00:00 +0000" git commit -q --allow-empty -m r1
+git checkout -q -b rebased-other main~1
+GIT_AUTHOR_DATE="2005-01-02 00:00:00 +0000" GIT_COMMITTER_DATE="2009-01-02 00:00:00 +0000" git commit -q --allow-empty -m r2
+git checkout -q rebased
+GIT_AUTHOR_DATE="2011-01-02 00:00:00 +0000" GIT_COMMITTER_DATE="2011-01-02 00:00:00 +0000" git merge rebased-other -m m2
+
+# Commits with the same commit date
+git checkout -q -b same-date main~1
+git commit -q --allow-empty -m s1
+git checkout -q -b same-date-other main~1
+git commit -q --allow-empty -m s2
+git checkout -q same-date
+git merge same-date-other -m m3
@@ -6,6 +6,10 @@
 pub mod reference;
 pub use reference::name as refname;
 
+///
+pub mod path;
+pub use path::component as path_component;
+
 ///
 pub mod tag;
 pub use tag::name as tagname;
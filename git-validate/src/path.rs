@@ -0,0 +1,103 @@
+use bstr::{BStr, ByteSlice};
+
+///
+pub mod component {
+    use bstr::BString;
+    use quick_error::quick_error;
+
+    quick_error! {
+        /// The error returned by [`component()`][super::component()]
+        #[derive(Debug)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            Empty {
+                display("A path component must not be empty")
+            }
+            PathSeparator {
+                display("A path component must not contain a '/'")
+            }
+            Relative {
+                display("A path component must not be '.' or '..'")
+            }
+            DotGitDir {
+                display("A path component must not be a '.git' directory")
+            }
+            WindowsPathSeparator {
+                display("A path component must not contain a '\\' when protecting NTFS on Windows")
+            }
+            NtfsDotGitDir(component: BString) {
+                display("A path component must not be an alias of a '.git' directory on NTFS: '{}'", component)
+            }
+            HfsDotGitDir(component: BString) {
+                display("A path component must not be an alias of a '.git' directory on HFS+: '{}'", component)
+            }
+        }
+    }
+
+    /// Further validations of path components that protect against file systems which would interpret them as `.git` directory.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Options {
+        /// If true, protect against NTFS aliases of `.git` like `GIT~1`, `.git.` or `.git::$INDEX_ALLOCATION`, and forbid `\`
+        /// on Windows, like `core.protectNTFS` does.
+        pub protect_ntfs: bool,
+        /// If true, protect against HFS+ aliases of `.git` which contain characters that are ignored by the file system,
+        /// like `core.protectHFS` does.
+        pub protect_hfs: bool,
+    }
+}
+
+/// Assure the given `input` is a valid component of a path as stored in trees or the index, which is returned unchanged on success.
+///
+/// Components must not be empty, contain slashes, be `.` or `..`, or name a `.git` directory in any case, along with
+/// the additional protections configured in `options`.
+pub fn component(input: &BStr, options: component::Options) -> Result<&BStr, component::Error> {
+    if input.is_empty() {
+        return Err(component::Error::Empty);
+    }
+    if input.contains(&b'/') {
+        return Err(component::Error::PathSeparator);
+    }
+    if input == "." || input == ".." {
+        return Err(component::Error::Relative);
+    }
+    if input.eq_ignore_ascii_case(b".git") {
+        return Err(component::Error::DotGitDir);
+    }
+    if options.protect_ntfs {
+        if cfg!(windows) && input.contains(&b'\\') {
+            return Err(component::Error::WindowsPathSeparator);
+        }
+        if is_ntfs_dot_git(input) {
+            return Err(component::Error::NtfsDotGitDir(input.into()));
+        }
+    }
+    if options.protect_hfs && is_hfs_dot_git(input) {
+        return Err(component::Error::HfsDotGitDir(input.into()));
+    }
+    Ok(input)
+}
+
+/// NTFS ignores trailing spaces and dots, provides `GIT~1` as short name for `.git`, and refers to the directory itself
+/// when a stream is selected after a `:`, like in `.git::$INDEX_ALLOCATION`.
+fn is_ntfs_dot_git(input: &BStr) -> bool {
+    let name = input.find_byte(b':').map_or(input.as_bytes(), |pos| &input[..pos]);
+    let trimmed = name.trim_end_with(|c| c == ' ' || c == '.');
+    trimmed.eq_ignore_ascii_case(b".git") || trimmed.eq_ignore_ascii_case(b"git~1")
+}
+
+/// HFS+ ignores certain zero-width unicode code points when comparing file names.
+fn is_hfs_dot_git(input: &BStr) -> bool {
+    let mut significant = input.chars().filter(|c| !is_ignored_by_hfs(*c));
+    ['.', 'g', 'i', 't'].iter().all(|expected| {
+        significant
+            .next()
+            .map_or(false, |c| c.to_ascii_lowercase() == *expected)
+    }) && significant.next().is_none()
+}
+
+fn is_ignored_by_hfs(c: char) -> bool {
+    matches!(
+        c,
+        '\u{200c}'..='\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{206a}'..='\u{206f}' | '\u{feff}'
+    )
+}
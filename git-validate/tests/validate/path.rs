@@ -0,0 +1,100 @@
+mod component {
+    use bstr::ByteSlice;
+    use git_validate::path::component::Options;
+
+    const NO_OPTS: Options = Options {
+        protect_ntfs: false,
+        protect_hfs: false,
+    };
+    const ALL_OPTS: Options = Options {
+        protect_ntfs: true,
+        protect_hfs: true,
+    };
+
+    mod valid {
+        macro_rules! mktest {
+            ($name:ident, $input:expr) => {
+                mktest!($name, $input, ALL_OPTS);
+            };
+            ($name:ident, $input:expr, $opts:expr) => {
+                #[test]
+                fn $name() {
+                    use bstr::ByteSlice;
+                    assert!(git_validate::path::component($input.as_bstr(), $opts).is_ok())
+                }
+            };
+        }
+
+        use super::{ALL_OPTS, NO_OPTS};
+
+        mktest!(ascii, b"ascii-only_and-that");
+        mktest!(unicode, "😁👍👌".as_bytes());
+        mktest!(backslash_without_ntfs_protection, b"\\", NO_OPTS);
+        mktest!(colon_without_ntfs_protection, b".git::$INDEX_ALLOCATION", NO_OPTS);
+        mktest!(ntfs_short_name_without_protection, b"GIT~1", NO_OPTS);
+        mktest!(hfs_ignorable_without_protection, ".g\u{200c}it".as_bytes(), NO_OPTS);
+        mktest!(dot_git_with_suffix, b".gitignore");
+        mktest!(dot_git_with_prefix, b"a.git");
+        mktest!(git_without_dot, b"git");
+        mktest!(short_name_of_other_directory, b"GIT~2");
+        mktest!(dots_in_the_middle, b"..a..");
+        mktest!(colon_not_selecting_a_stream_of_dot_git, b"a:b");
+        mktest!(colon_after_dot_git_with_suffix, b".git.a:b");
+        #[cfg(not(windows))]
+        mktest!(backslash_outside_of_windows, b"a\\b");
+    }
+
+    mod invalid {
+        use git_validate::path::component::Error;
+
+        macro_rules! mktest {
+            ($name:ident, $input:expr, $expected:pat) => {
+                mktest!($name, $input, $expected, ALL_OPTS);
+            };
+            ($name:ident, $input:expr, $expected:pat, $opts:expr) => {
+                #[test]
+                fn $name() {
+                    use bstr::ByteSlice;
+                    match git_validate::path::component($input.as_bstr(), $opts) {
+                        Err($expected) => {}
+                        got => panic!("Wanted {}, got {:?}", stringify!($expected), got),
+                    }
+                }
+            };
+        }
+
+        use super::{ALL_OPTS, NO_OPTS};
+
+        mktest!(empty, b"", Error::Empty, NO_OPTS);
+        mktest!(contains_slash, b"a/b", Error::PathSeparator, NO_OPTS);
+        mktest!(dot, b".", Error::Relative, NO_OPTS);
+        mktest!(dot_dot, b"..", Error::Relative, NO_OPTS);
+        mktest!(dot_git_lower, b".git", Error::DotGitDir, NO_OPTS);
+        mktest!(dot_git_upper, b".GIT", Error::DotGitDir, NO_OPTS);
+        mktest!(dot_git_mixed, b".gIt", Error::DotGitDir, NO_OPTS);
+        #[cfg(windows)]
+        mktest!(contains_backslash, b"a\\b", Error::WindowsPathSeparator);
+        mktest!(
+            ntfs_alternate_data_stream,
+            b".git::$INDEX_ALLOCATION",
+            Error::NtfsDotGitDir(_)
+        );
+        mktest!(ntfs_short_name_stream, b"git~1 .:stream", Error::NtfsDotGitDir(_));
+        mktest!(ntfs_short_name, b"GIT~1", Error::NtfsDotGitDir(_));
+        mktest!(ntfs_short_name_lower, b"git~1", Error::NtfsDotGitDir(_));
+        mktest!(ntfs_trailing_dots_and_spaces, b".git . .", Error::NtfsDotGitDir(_));
+        mktest!(
+            hfs_zero_width_non_joiner,
+            ".g\u{200c}it".as_bytes(),
+            Error::HfsDotGitDir(_)
+        );
+        mktest!(hfs_byte_order_mark, "\u{feff}.GIT".as_bytes(), Error::HfsDotGitDir(_));
+    }
+
+    #[test]
+    fn valid_components_are_returned_unchanged() {
+        let input = b"hello".as_bstr();
+        assert_eq!(git_validate::path::component(input, ALL_OPTS).expect("valid"), input);
+        assert_eq!(git_validate::path::component(input, NO_OPTS).expect("valid"), input);
+    }
+}
@@ -42,6 +42,21 @@ impl<'a, 'paths> Platform<'a, 'paths> {
     }
 }
 
+impl<'a, 'paths> Platform<'a, 'paths> {
+    /// Return the state of the attribute `name` for the currently set path, or `None` if no pattern assigns it.
+    ///
+    /// # Panics
+    ///
+    /// If the cache was configured without attributes, or to create directories in which case only those of the
+    /// entries in the index or in directories that existed on disk are taken into account.
+    pub fn attribute(&self, name: &str) -> Option<git_attributes::StateRef<'_>> {
+        let attributes = self.parent.state.attributes_or_panic();
+        let relative_path =
+            git_path::to_unix_separators_on_windows(git_path::into_bstr(self.parent.stack.current_relative.as_path()));
+        attributes.attribute_matching_relative_path(relative_path.as_ref(), name, self.is_dir, self.parent.case)
+    }
+}
+
 impl<'a, 'paths> std::fmt::Debug for Platform<'a, 'paths> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::fmt::Debug::fmt(&self.path(), f)
@@ -63,9 +78,13 @@ where
 {
     fn push_directory(&mut self, stack: &fs::Stack) -> std::io::Result<()> {
         match &mut self.state {
-            State::CreateDirectoryAndAttributesStack { attributes: _, .. } => {
-                // TODO: attributes
-            }
+            State::CreateDirectoryAndAttributesStack { attributes, .. } => attributes.push_directory(
+                &stack.root,
+                &stack.current,
+                self.buf,
+                self.attribute_files_in_index,
+                &mut self.find,
+            )?,
             State::AttributesAndIgnoreStack { ignore, attributes: _ } => {
                 // TODO: attributes
                 ignore.push_directory(
@@ -117,8 +136,8 @@ where
 
     fn pop_directory(&mut self) {
         match &mut self.state {
-            State::CreateDirectoryAndAttributesStack { attributes: _, .. } => {
-                // TODO: attributes
+            State::CreateDirectoryAndAttributesStack { attributes, .. } => {
+                attributes.pop_directory();
             }
             State::AttributesAndIgnoreStack { attributes: _, ignore } => {
                 // TODO: attributes
@@ -11,7 +11,6 @@ type IgnoreMatchGroup = git_attributes::MatchGroup<git_attributes::Ignore>;
 
 /// State related to attributes associated with files in the repository.
 #[derive(Default, Clone)]
-#[allow(unused)]
 pub struct Attributes {
     /// Attribute patterns that match the currently set directory (in the stack).
     pub stack: AttributeMatchGroup,
@@ -197,6 +196,62 @@ impl Attributes {
     }
 }
 
+impl Attributes {
+    pub(crate) fn pop_directory(&mut self) {
+        self.stack.patterns.pop().expect("something to pop");
+    }
+
+    /// Like git does during checkout, prefer the `.gitattributes` file in the index over the one in the worktree.
+    pub(crate) fn push_directory<Find, E>(
+        &mut self,
+        root: &Path,
+        dir: &Path,
+        buf: &mut Vec<u8>,
+        attribute_files_in_index: &[PathOidMapping<'_>],
+        mut find: Find,
+    ) -> std::io::Result<()>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<git_object::BlobRef<'b>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let rela_dir = dir.strip_prefix(root).expect("dir in root");
+        let attr_path_relative = rela_dir.join(".gitattributes");
+        let attr_path_relative = git_path::to_unix_separators_on_windows(git_path::into_bstr(attr_path_relative));
+        let attr_path = dir.join(".gitattributes");
+        let list = match attribute_files_in_index.binary_search_by(|t| t.0.cmp(attr_path_relative.as_ref())) {
+            Ok(idx) => {
+                let attr_blob = find(&attribute_files_in_index[idx].1, buf)
+                    .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+                git_attributes::PatternList::from_bytes(attr_blob.data, attr_path, Some(root))
+            }
+            // Need one stack level per component so push and pop matches.
+            Err(_) => git_attributes::PatternList::from_file(attr_path, Some(root), true, buf)?.unwrap_or(
+                git_attributes::PatternList {
+                    patterns: Vec::new(),
+                    source: None,
+                    base: None,
+                },
+            ),
+        };
+        self.stack.patterns.push(list);
+        Ok(())
+    }
+
+    /// Return the state of the attribute `name` for `relative_path`, with patterns closer to it taking precedence
+    /// and the [globals][Attributes::globals] being consulted last.
+    pub(crate) fn attribute_matching_relative_path(
+        &self,
+        relative_path: &BStr,
+        name: &str,
+        is_dir: Option<bool>,
+        case: Case,
+    ) -> Option<git_attributes::StateRef<'_>> {
+        [&self.stack, &self.globals]
+            .iter()
+            .find_map(|group| group.attribute_matching_relative_path(relative_path, name, is_dir, case))
+    }
+}
+
 impl From<AttributeMatchGroup> for Attributes {
     fn from(group: AttributeMatchGroup) -> Self {
         Attributes::new(group)
@@ -252,7 +307,7 @@ impl State {
                 a2_backing.as_ref()
             }
             State::CreateDirectoryAndAttributesStack { .. } => {
-                a1_backing = [(".gitattributes".into(), true)];
+                a1_backing = [(".gitattributes".into(), false)];
                 a1_backing.as_ref()
             }
         };
@@ -289,6 +344,16 @@ impl State {
             .collect()
     }
 
+    pub(crate) fn attributes_or_panic(&self) -> &Attributes {
+        match self {
+            State::AttributesAndIgnoreStack { attributes, .. }
+            | State::CreateDirectoryAndAttributesStack { attributes, .. } => attributes,
+            State::IgnoreStack(_) => {
+                unreachable!("BUG: must not try to check attributes without it being setup")
+            }
+        }
+    }
+
     pub(crate) fn ignore_or_panic(&self) -> &Ignore {
         match self {
             State::IgnoreStack(v) => v,
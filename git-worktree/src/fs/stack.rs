@@ -106,17 +106,15 @@ impl Stack {
             self.current.push(comp);
             self.current_relative.push(comp);
             self.valid_components += 1;
-            let res = delegate.push(is_last_component, self);
-            if self.current_is_directory {
-                delegate.push_directory(self)?;
-            }
-
-            if let Err(err) = res {
+            if let Err(err) = delegate.push(is_last_component, self) {
                 self.current.pop();
                 self.current_relative.pop();
                 self.valid_components -= 1;
                 return Err(err);
             }
+            if self.current_is_directory {
+                delegate.push_directory(self)?;
+            }
         }
         Ok(())
     }
@@ -17,6 +17,31 @@ pub struct ErrorRecord {
     pub error: Box<dyn std::error::Error + Send + Sync + 'static>,
 }
 
+/// Statistics about the work done by a single thread during checkout.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ThreadStatistics {
+    /// The amount of entries the thread handled.
+    pub files: usize,
+    /// The amount of bytes the thread wrote to disk.
+    pub bytes_written: u64,
+    /// The time the thread spent checking out entries.
+    pub duration: std::time::Duration,
+}
+
+impl ThreadStatistics {
+    /// Return the amount of bytes written per second, or `None` if no time was spent at all.
+    pub fn bytes_per_second(&self) -> Option<f64> {
+        let secs = self.duration.as_secs_f64();
+        (secs > 0.0).then(|| self.bytes_written as f64 / secs)
+    }
+
+    pub(crate) fn add(&mut self, other: &ThreadStatistics) {
+        self.files += other.files;
+        self.bytes_written += other.bytes_written;
+        self.duration += other.duration;
+    }
+}
+
 pub struct Outcome {
     /// The amount of files updated, or created.
     pub files_updated: usize,
@@ -24,6 +49,11 @@ pub struct Outcome {
     pub bytes_written: u64,
     pub collisions: Vec<Collision>,
     pub errors: Vec<ErrorRecord>,
+    /// Statistics for each thread that checked out entries, in order of their thread index.
+    ///
+    /// As symlinks are always checked out by the calling thread after all other entries, an additional
+    /// entry accounts for them if more than one thread was used.
+    pub threads: Vec<ThreadStatistics>,
 }
 
 #[derive(Clone)]
@@ -60,6 +90,79 @@ pub struct Options {
     pub check_stat: bool,
     /// A group of attribute patterns that are applied globally, i.e. aren't rooted within the repository itself.
     pub attribute_globals: git_attributes::MatchGroup<Attributes>,
+    /// Validations to perform on each component of the path of an entry before it is checked out, similar to
+    /// `core.protectNTFS` and `core.protectHFS`.
+    ///
+    /// Components naming a `.git` directory, `.` or `..` are always rejected.
+    /// Defaults to protecting NTFS everywhere, and HFS on MacOS, just like git.
+    pub validate: git_validate::path::component::Options,
+    /// Conversions to apply to the content of files before writing them, as selected by their `text`, `eol`, `ident` and
+    /// `filter` attributes.
+    pub filters: Filters,
+}
+
+/// The line ending to use for text files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Eol {
+    /// A line feed, `\n`.
+    Lf,
+    /// A carriage return followed by a line feed, `\r\n`.
+    Crlf,
+}
+
+impl Default for Eol {
+    /// The native line ending of the platform, like `core.eol=native`.
+    fn default() -> Self {
+        if cfg!(windows) {
+            Eol::Crlf
+        } else {
+            Eol::Lf
+        }
+    }
+}
+
+/// The values of `core.autocrlf`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutoCrlf {
+    /// Only files with the `text` or `eol` attribute are converted.
+    Disabled,
+    /// Files without `text` attribute are treated as if it was `auto`, but line endings aren't changed on checkout.
+    Input,
+    /// Files without `text` attribute are treated as if it was `auto`, and checked out with `\r\n` line endings.
+    Enabled,
+}
+
+impl Default for AutoCrlf {
+    fn default() -> Self {
+        AutoCrlf::Disabled
+    }
+}
+
+/// A program to run on the content of files whose `filter` attribute names it, like configured in `filter.<name>`.
+#[derive(Clone, Debug)]
+pub struct FilterDriver {
+    /// The name of the driver as used in the value of the `filter` attribute.
+    pub name: BString,
+    /// The shell command receiving the content on stdin to write the content for the worktree to stdout, like `filter.<name>.smudge`.
+    ///
+    /// `%f` is replaced with the quoted path of the file relative to the root of the worktree.
+    pub smudge: Option<BString>,
+    /// If true, failing to run `smudge`, or it being unset, is an error. Otherwise the content is written unchanged.
+    pub required: bool,
+}
+
+/// Conversions to apply to the content of files before writing them into the worktree.
+///
+/// They are applied in the same order as `git` does: `ident`, line endings, then the `filter` driver.
+/// Long-running filter processes as configured with `filter.<name>.process` aren't supported.
+#[derive(Clone, Debug, Default)]
+pub struct Filters {
+    /// Determines the line endings of files without `text` or `eol` attribute, like `core.autocrlf`.
+    pub auto_crlf: AutoCrlf,
+    /// The line ending of text files without `eol` attribute if `auto_crlf` is disabled, like `core.eol`.
+    pub eol: Eol,
+    /// The drivers to use for files with the `filter` attribute.
+    pub drivers: Vec<FilterDriver>,
 }
 
 impl Default for Options {
@@ -73,6 +176,11 @@ impl Default for Options {
             check_stat: true,
             overwrite_existing: false,
             attribute_globals: Default::default(),
+            validate: git_validate::path::component::Options {
+                protect_ntfs: true,
+                protect_hfs: cfg!(target_vendor = "apple"),
+            },
+            filters: Default::default(),
         }
     }
 }
@@ -80,6 +188,19 @@ impl Default for Options {
 pub enum Error<E: std::error::Error + Send + Sync + 'static> {
     #[error("Could not convert path to UTF8: {}", .path)]
     IllformedUtf8 { path: BString },
+    #[error("The path '{}' is not safe to check out", .path)]
+    InvalidPath {
+        #[source]
+        err: git_validate::path::component::Error,
+        path: BString,
+    },
+    #[error("The filter driver '{}' failed to convert '{}'", .driver, .path)]
+    Filter {
+        #[source]
+        err: std::io::Error,
+        driver: BString,
+        path: BString,
+    },
     #[error("The clock was off when reading file related metadata after updating a file on disk")]
     Time(#[from] std::time::SystemTimeError),
     #[error("IO error while writing blob or reading file metadata or changing filetype")]
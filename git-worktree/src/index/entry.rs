@@ -1,6 +1,6 @@
 use std::{convert::TryInto, fs::OpenOptions, io::Write, path::Path, time::Duration};
 
-use bstr::BStr;
+use bstr::{BStr, ByteSlice};
 use git_hash::oid;
 use git_index::Entry;
 use io_close::Close;
@@ -26,6 +26,8 @@ pub fn checkout<Find, E>(
         },
         destination_is_initially_empty,
         overwrite_existing,
+        validate,
+        filters,
         ..
     }: index::checkout::Options,
 ) -> Result<usize, index::checkout::Error<E>>
@@ -33,11 +35,23 @@ where
     Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E>,
     E: std::error::Error + Send + Sync + 'static,
 {
+    for component in entry_path.split_str("/") {
+        git_validate::path::component(component.as_bstr(), validate).map_err(|err| {
+            index::checkout::Error::InvalidPath {
+                err,
+                path: entry_path.to_owned(),
+            }
+        })?;
+    }
     let dest_relative = git_path::try_from_bstr(entry_path).map_err(|_| index::checkout::Error::IllformedUtf8 {
         path: entry_path.to_owned(),
     })?;
     let is_dir = Some(entry.mode == git_index::entry::Mode::COMMIT || entry.mode == git_index::entry::Mode::DIR);
-    let dest = path_cache.at_path(dest_relative, is_dir, &mut *find)?.path();
+    // Filter drivers run in the root of the worktree, which can't be obtained while the cache is borrowed for `dest`.
+    let worktree_root = (!filters.drivers.is_empty()).then(|| path_cache.base().to_owned());
+    let platform = path_cache.at_path(dest_relative, is_dir, &mut *find)?;
+    let conversion = index::filter::Conversion::new(&platform, &filters);
+    let dest = platform.path();
 
     let object_size = match entry.mode {
         git_index::entry::Mode::FILE | git_index::entry::Mode::FILE_EXECUTABLE => {
@@ -46,6 +60,25 @@ where
                 oid: entry.id,
                 path: dest.to_path_buf(),
             })?;
+            let mut converted = Vec::new();
+            let data = if conversion.is_noop() {
+                obj.data
+            } else {
+                conversion
+                    .apply(
+                        obj.data,
+                        &entry.id,
+                        entry_path,
+                        worktree_root.as_deref().unwrap_or_else(|| Path::new(".")),
+                        &mut converted,
+                    )
+                    .map_err(|(err, driver)| index::checkout::Error::Filter {
+                        err,
+                        driver,
+                        path: entry_path.to_owned(),
+                    })?;
+                &converted
+            };
 
             #[cfg_attr(not(unix), allow(unused_mut))]
             let mut options = open_options(dest, destination_is_initially_empty, overwrite_existing);
@@ -59,7 +92,7 @@ where
             }
 
             let mut file = try_write_or_unlink(dest, overwrite_existing, |p| options.open(p))?;
-            file.write_all(obj.data)?;
+            file.write_all(data)?;
 
             // For possibly existing, overwritten files, we must change the file mode explicitly.
             #[cfg(unix)]
@@ -73,7 +106,7 @@ where
             //       revisit this once there is a bug to fix.
             update_fstat(entry, file.metadata()?)?;
             file.close()?;
-            obj.data.len()
+            data.len()
         }
         git_index::entry::Mode::SYMLINK => {
             let obj = find(&entry.id, buf).map_err(|err| index::checkout::Error::Find {
@@ -161,6 +194,7 @@ fn open_options(path: &Path, destination_is_initially_empty: bool, overwrite_exi
     options
         .create_new(destination_is_initially_empty && !overwrite_existing)
         .create(!destination_is_initially_empty || overwrite_existing)
+        .truncate(!destination_is_initially_empty || overwrite_existing)
         .write(true);
     options
 }
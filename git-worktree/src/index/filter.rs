@@ -0,0 +1,205 @@
+use std::{
+    io::{Read, Write},
+    path::Path,
+};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use git_attributes::StateRef;
+use git_hash::oid;
+
+use crate::{
+    fs,
+    index::checkout::{AutoCrlf, Eol, FilterDriver, Filters},
+};
+
+/// How text is detected before converting its line endings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Text {
+    /// Convert all line endings as the content is known to be text.
+    Always,
+    /// Only convert line endings if the content looks like text which wasn't committed with `\r\n` line endings.
+    Auto,
+}
+
+/// The conversions to apply to the content of a single file on its way into the worktree, in the order they are applied in.
+pub struct Conversion<'a> {
+    ident: bool,
+    to_crlf: Option<Text>,
+    driver: Option<&'a FilterDriver>,
+}
+
+impl<'a> Conversion<'a> {
+    /// Determine the conversion for the path `platform` currently points to, based on its attributes and the `filters` configuration.
+    pub fn new(platform: &fs::cache::Platform<'_, '_>, filters: &'a Filters) -> Self {
+        let ident = matches!(platform.attribute("ident"), Some(StateRef::Set));
+        let eol = match platform.attribute("eol") {
+            Some(StateRef::Value(v)) if v == "lf" => Some(Eol::Lf),
+            Some(StateRef::Value(v)) if v == "crlf" => Some(Eol::Crlf),
+            _ => None,
+        };
+        let text = match platform.attribute("text") {
+            Some(StateRef::Set) => Some(Text::Always),
+            Some(StateRef::Unset) => None,
+            Some(StateRef::Value(v)) if v == "auto" => Some(Text::Auto),
+            _ if eol.is_some() => Some(Text::Always),
+            _ => (filters.auto_crlf != AutoCrlf::Disabled).then(|| Text::Auto),
+        };
+        let output_eol = eol.unwrap_or(match filters.auto_crlf {
+            AutoCrlf::Enabled => Eol::Crlf,
+            AutoCrlf::Input => Eol::Lf,
+            AutoCrlf::Disabled => filters.eol,
+        });
+        let driver = match platform.attribute("filter") {
+            Some(StateRef::Value(name)) => filters.drivers.iter().find(|d| d.name == name),
+            _ => None,
+        };
+        Conversion {
+            ident,
+            to_crlf: text.filter(|_| output_eol == Eol::Crlf),
+            driver,
+        }
+    }
+
+    /// Return true if the content would be written as is.
+    pub fn is_noop(&self) -> bool {
+        !self.ident && self.to_crlf.is_none() && self.driver.is_none()
+    }
+
+    /// Convert `data` of the object with `id` at `rela_path` for writing it into the worktree at `worktree_root`,
+    /// placing the result into `out`.
+    ///
+    /// Errors are only returned for drivers that are required, along with the name of the driver that failed.
+    pub fn apply(
+        &self,
+        data: &[u8],
+        id: &oid,
+        rela_path: &BStr,
+        worktree_root: &Path,
+        out: &mut Vec<u8>,
+    ) -> Result<(), (std::io::Error, BString)> {
+        out.clear();
+        out.extend_from_slice(data);
+        if self.ident {
+            ident_to_worktree(out, id);
+        }
+        if let Some(text) = self.to_crlf {
+            lf_to_crlf(out, text);
+        }
+        if let Some(driver) = self.driver {
+            match run_smudge(driver, out, rela_path, worktree_root) {
+                Ok(filtered) => *out = filtered,
+                Err(err) if driver.required => return Err((err, driver.name.clone())),
+                Err(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replace all `$Id$` and previously expanded `$Id: … $` with `$Id: <id> $`, like git does.
+fn ident_to_worktree(buf: &mut Vec<u8>, id: &oid) {
+    if buf.find(b"$Id").is_none() {
+        return;
+    }
+    let mut out = Vec::with_capacity(buf.len());
+    let mut rest = buf.as_slice();
+    while let Some(pos) = rest.find(b"$Id") {
+        out.extend_from_slice(&rest[..pos]);
+        rest = &rest[pos + 3..];
+        let end = match rest.first() {
+            Some(b'$') => Some(1),
+            Some(b':') => rest[1..]
+                .find_byteset(b"$\n")
+                .filter(|end| rest[1 + end] == b'$')
+                .map(|end| end + 2),
+            _ => None,
+        };
+        match end {
+            Some(end) => {
+                out.extend_from_slice(b"$Id: ");
+                out.push_str(id.to_hex().to_string());
+                out.extend_from_slice(b" $");
+                rest = &rest[end..];
+            }
+            None => out.extend_from_slice(b"$Id"),
+        }
+    }
+    out.extend_from_slice(rest);
+    *buf = out;
+}
+
+/// Turn all lone `\n` into `\r\n`, unless `text` is [`Text::Auto`] and the content already contains `\r` or seems binary.
+fn lf_to_crlf(buf: &mut Vec<u8>, text: Text) {
+    let has_lf = buf.contains(&b'\n');
+    if !has_lf || (text == Text::Auto && (buf.contains(&b'\r') || buf.contains(&0))) {
+        return;
+    }
+    let mut out = Vec::with_capacity(buf.len() + buf.len() / 16);
+    let mut previous = None;
+    for &b in buf.iter() {
+        if b == b'\n' && previous != Some(b'\r') {
+            out.push(b'\r');
+        }
+        out.push(b);
+        previous = Some(b);
+    }
+    *buf = out;
+}
+
+/// Run the smudge command of `driver` with `data` on stdin in `worktree_root`, replacing `%f` with the quoted `rela_path`,
+/// and return its output.
+fn run_smudge(driver: &FilterDriver, data: &[u8], rela_path: &BStr, worktree_root: &Path) -> std::io::Result<Vec<u8>> {
+    let smudge = driver.smudge.as_ref().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("filter.{}.smudge is not configured", driver.name),
+        )
+    })?;
+    let mut command = BString::default();
+    let mut chars = smudge.iter().copied();
+    while let Some(b) = chars.next() {
+        if b != b'%' {
+            command.push(b);
+            continue;
+        }
+        match chars.next() {
+            Some(b'f') => command.push_str(git_quote::single(rela_path)),
+            Some(other) => {
+                command.push(b'%');
+                command.push(other);
+            }
+            None => command.push(b'%'),
+        }
+    }
+
+    let mut child = std::process::Command::new(if cfg!(windows) { "sh.exe" } else { "sh" })
+        .arg("-c")
+        .arg(git_path::from_bstr(command.as_bstr()).as_ref())
+        .current_dir(worktree_root)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()?;
+    // Write from another thread to not deadlock when the filter produces more output than the pipe can buffer.
+    let writer = {
+        let mut stdin = child.stdin.take().expect("configured");
+        let data = data.to_owned();
+        std::thread::spawn(move || stdin.write_all(&data))
+    };
+    let mut out = Vec::with_capacity(data.len());
+    child.stdout.take().expect("configured").read_to_end(&mut out)?;
+    let status = child.wait()?;
+    let write_res = writer.join().expect("writer doesn't panic");
+    if !status.success() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            format!("'{}' failed with {}", smudge, status),
+        ));
+    }
+    // Filters are free to not read their input, which is only a problem if they fail.
+    if let Err(err) = write_res {
+        if err.kind() != std::io::ErrorKind::BrokenPipe {
+            return Err(err);
+        }
+    }
+    Ok(out)
+}
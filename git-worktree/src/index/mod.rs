@@ -7,6 +7,7 @@ use crate::fs;
 
 pub mod checkout;
 pub(crate) mod entry;
+pub(crate) mod filter;
 
 /// Note that interruption still produce an `Ok(…)` value, so the caller should look at `should_interrupt` to communicate the outcome.
 /// `dir` is the directory into which to checkout the `index`.
@@ -67,16 +68,23 @@ where
         find,
         options,
         num_files: &num_files,
+        thread_index: 0,
     };
 
-    let chunk::Outcome {
-        mut collisions,
-        mut errors,
-        mut bytes_written,
-        delayed,
-    } = if num_threads == 1 {
+    let (
+        chunk::Outcome {
+            mut collisions,
+            mut errors,
+            mut bytes_written,
+            delayed,
+            ..
+        },
+        mut threads,
+    ) = if num_threads == 1 {
         let entries_with_paths = interrupt::Iter::new(index.entries_mut_with_paths_in(paths), should_interrupt);
-        chunk::process(entries_with_paths, files, bytes, &mut ctx)?
+        let outcome = chunk::process(entries_with_paths, files, bytes, &mut ctx)?;
+        let statistics = outcome.statistics;
+        (outcome, vec![statistics])
     } else {
         let entries_with_paths = interrupt::Iter::new(index.entries_mut_with_paths_in(paths), should_interrupt);
         in_parallel(
@@ -87,7 +95,11 @@ where
             thread_limit,
             {
                 let ctx = ctx.clone();
-                move |_| (progress::Discard, progress::Discard, ctx.clone())
+                move |thread_index| {
+                    let mut ctx = ctx.clone();
+                    ctx.thread_index = thread_index;
+                    (progress::Discard, progress::Discard, ctx)
+                }
             },
             |chunk, (files, bytes, ctx)| chunk::process(chunk.into_iter(), files, bytes, ctx),
             chunk::Reduce {
@@ -95,13 +107,16 @@ where
                 bytes,
                 num_files: &num_files,
                 aggregate: Default::default(),
+                threads: Vec::with_capacity(num_threads),
                 marker: Default::default(),
             },
         )?
     };
 
+    let start = std::time::Instant::now();
+    let mut delayed_statistics = checkout::ThreadStatistics::default();
     for (entry, entry_path) in delayed {
-        bytes_written += chunk::checkout_entry_handle_result(
+        let object_size = chunk::checkout_entry_handle_result(
             entry,
             entry_path,
             &mut errors,
@@ -110,6 +125,15 @@ where
             bytes,
             &mut ctx,
         )? as u64;
+        bytes_written += object_size;
+        delayed_statistics.files += 1;
+        delayed_statistics.bytes_written += object_size;
+    }
+    delayed_statistics.duration = start.elapsed();
+    match threads.as_mut_slice() {
+        [statistics] if num_threads == 1 => statistics.add(&delayed_statistics),
+        _ if delayed_statistics.files != 0 => threads.push(delayed_statistics),
+        _ => {}
     }
 
     Ok(checkout::Outcome {
@@ -117,6 +141,7 @@ where
         collisions,
         errors,
         bytes_written,
+        threads,
     })
 }
 
@@ -148,6 +173,8 @@ mod chunk {
             pub bytes: &'a mut P2,
             pub num_files: &'a AtomicUsize,
             pub aggregate: super::Outcome<'entry>,
+            /// Statistics for each thread, indexed by thread.
+            pub threads: Vec<checkout::ThreadStatistics>,
             pub marker: PhantomData<E>,
         }
 
@@ -159,7 +186,7 @@ mod chunk {
         {
             type Input = Result<super::Outcome<'entry>, checkout::Error<E>>;
             type FeedProduce = ();
-            type Output = super::Outcome<'entry>;
+            type Output = (super::Outcome<'entry>, Vec<checkout::ThreadStatistics>);
             type Error = checkout::Error<E>;
 
             fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
@@ -169,7 +196,13 @@ mod chunk {
                     delayed,
                     errors,
                     collisions,
+                    thread_index,
+                    statistics,
                 } = item;
+                if self.threads.len() <= thread_index {
+                    self.threads.resize(thread_index + 1, Default::default());
+                }
+                self.threads[thread_index].add(&statistics);
                 self.aggregate.bytes_written += bytes_written;
                 self.aggregate.delayed.extend(delayed);
                 self.aggregate.errors.extend(errors);
@@ -182,7 +215,7 @@ mod chunk {
             }
 
             fn finalize(self) -> Result<Self::Output, Self::Error> {
-                Ok(self.aggregate)
+                Ok((self.aggregate, self.threads))
             }
         }
     }
@@ -194,6 +227,9 @@ mod chunk {
         pub errors: Vec<checkout::ErrorRecord>,
         pub delayed: Vec<(&'a mut git_index::Entry, &'a BStr)>,
         pub bytes_written: u64,
+        /// The index of the thread that produced this outcome.
+        pub thread_index: usize,
+        pub statistics: checkout::ThreadStatistics,
     }
 
     #[derive(Clone)]
@@ -205,6 +241,8 @@ mod chunk {
         /// We keep these shared so that there is the chance for printing numbers that aren't looking like
         /// multiple of chunk sizes. Purely cosmetic. Otherwise it's the same as `files`.
         pub num_files: &'a AtomicUsize,
+        /// The index of the thread we are running on.
+        pub thread_index: usize,
     }
 
     pub fn process<'entry, Find, E>(
@@ -217,10 +255,12 @@ mod chunk {
         Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E> + Clone,
         E: std::error::Error + Send + Sync + 'static,
     {
+        let start = std::time::Instant::now();
         let mut delayed = Vec::new();
         let mut collisions = Vec::new();
         let mut errors = Vec::new();
         let mut bytes_written = 0;
+        let mut files_handled = 0;
 
         for (entry, entry_path) in entries_with_paths {
             // TODO: write test for that
@@ -243,6 +283,7 @@ mod chunk {
             bytes_written +=
                 checkout_entry_handle_result(entry, entry_path, &mut errors, &mut collisions, files, bytes, ctx)?
                     as u64;
+            files_handled += 1;
         }
 
         Ok(Outcome {
@@ -250,6 +291,12 @@ mod chunk {
             errors,
             collisions,
             delayed,
+            thread_index: ctx.thread_index,
+            statistics: checkout::ThreadStatistics {
+                files: files_handled,
+                bytes_written,
+                duration: start.elapsed(),
+            },
         })
     }
 
@@ -266,6 +313,7 @@ mod chunk {
             buf,
             options,
             num_files,
+            thread_index: _,
         }: &mut Context<'_, '_, Find>,
     ) -> Result<usize, checkout::Error<E>>
     where
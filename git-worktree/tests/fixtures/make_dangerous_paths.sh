@@ -0,0 +1,15 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+# Paths that NTFS or HFS+ would resolve to the `.git` directory, which git refuses to check out.
+empty_oid=$(git hash-object -w --stdin </dev/null)
+hfs_dot_git=$(printf '.g\xe2\x80\x8cit')
+
+git -c core.protectNTFS=false -c core.protectHFS=false update-index --index-info <<-EOF
+100644 $empty_oid	$hfs_dot_git/hooks/post-checkout
+100644 $empty_oid	GIT~1/hooks/post-checkout
+100644 $empty_oid	a
+100644 $empty_oid	dir/b
+EOF
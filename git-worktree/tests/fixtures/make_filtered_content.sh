@@ -0,0 +1,27 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+# Add the content before the attributes to avoid it being converted already.
+printf 'a\nb\r\nc\n' > text.crlf
+printf 'a\nb\n' > binary.crlf
+printf '$Id$\n$Id: outdated $\n$Id\n' > file.id
+printf 'hello\n' > file.upper
+printf 'hello\n' > file.fail
+printf 'a\nb\n' > plain
+printf 'a\0\nb\n' > plain-binary
+mkdir dir
+printf 'a\nb\n' > dir/nested
+git add -A
+
+cat >.gitattributes <<ATTRS
+*.crlf eol=crlf
+binary.crlf -text
+*.id ident
+*.upper filter=upper
+*.fail filter=fail
+ATTRS
+echo "nested text eol=crlf" > dir/.gitattributes
+git add -A
+git commit -q -m "Commit"
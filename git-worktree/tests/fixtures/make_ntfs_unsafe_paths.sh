@@ -0,0 +1,13 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+# Paths that are only special on NTFS, of which only the alternate data stream of `.git` must not be checked out elsewhere.
+empty_oid=$(git hash-object -w --stdin </dev/null)
+
+git -c core.protectNTFS=false update-index --index-info <<-EOF
+100644 $empty_oid	.git::\$INDEX_ALLOCATION/hooks/post-checkout
+100644 $empty_oid	a:b
+100644 $empty_oid	dir/x\\y
+EOF
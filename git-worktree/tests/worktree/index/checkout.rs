@@ -10,7 +10,11 @@ use std::{
 use git_features::progress;
 use git_object::bstr::ByteSlice;
 use git_odb::FindExt;
-use git_worktree::{fs::Capabilities, index, index::checkout::Collision};
+use git_worktree::{
+    fs::Capabilities,
+    index,
+    index::checkout::{AutoCrlf, Collision, Eol, FilterDriver, Filters},
+};
 use tempfile::TempDir;
 
 use crate::fixture_path;
@@ -85,6 +89,33 @@ fn writes_through_symlinks_are_prevented_even_if_overwriting_is_allowed() {
     };
 }
 
+#[test]
+fn overwriting_files_with_shorter_content_truncates_them() {
+    let mut opts = opts_from_probe();
+    opts.overwrite_existing = true;
+    opts.destination_is_initially_empty = false;
+    let (_source_tree, destination, _index, outcome) = checkout_index_in_tmp_dir_opts(
+        opts,
+        "make_mixed_without_submodules",
+        |_| true,
+        |d| {
+            std::fs::write(
+                d.join("executable"),
+                b"content that is longer than the one in the index",
+            )?;
+            Ok(())
+        },
+    )
+    .unwrap();
+
+    assert!(outcome.collisions.is_empty());
+    assert_eq!(
+        std::fs::read(destination.path().join("executable")).unwrap(),
+        b"content",
+        "no trailing bytes of the previous file remain"
+    );
+}
+
 #[test]
 fn overwriting_files_and_lone_directories_works() {
     let mut opts = opts_from_probe();
@@ -232,6 +263,172 @@ fn keep_going_collects_results() {
     assert!(outcome.collisions.is_empty());
 }
 
+#[test]
+fn paths_aliasing_the_git_dir_are_rejected() {
+    let mut opts = opts_from_probe();
+    opts.keep_going = true;
+    opts.validate.protect_ntfs = true;
+    opts.validate.protect_hfs = true;
+    let (_source_tree, destination, _index, outcome) =
+        checkout_index_in_tmp_dir(opts.clone(), "make_dangerous_paths").unwrap();
+
+    let mut errors = outcome
+        .errors
+        .iter()
+        .map(|r| r.path.to_str_lossy().into_owned())
+        .collect::<Vec<_>>();
+    errors.sort();
+    assert_eq!(
+        errors,
+        [".g\u{200c}it/hooks/post-checkout", "GIT~1/hooks/post-checkout"],
+        "both the HFS+ and the NTFS alias are caught"
+    );
+    assert!(outcome.collisions.is_empty());
+    assert_eq!(
+        stripped_prefix(&destination, &dir_structure(&destination)),
+        paths(["a", "dir/b"])
+    );
+    assert!(!destination.path().join("GIT~1").exists());
+    assert!(!destination.path().join(".g\u{200c}it").exists());
+
+    opts.keep_going = false;
+    let err = checkout_index_in_tmp_dir(opts, "make_dangerous_paths")
+        .err()
+        .expect("dangerous paths abort the checkout");
+    assert!(matches!(
+        err.downcast_ref::<index::checkout::Error<git_odb::find::existing_object::Error<git_odb::store::find::Error>>>(
+        ),
+        Some(index::checkout::Error::InvalidPath { .. })
+    ));
+}
+
+#[test]
+#[cfg(unix)]
+fn paths_only_special_on_windows_are_checked_out_despite_ntfs_protection() {
+    let mut opts = opts_from_probe();
+    opts.keep_going = true;
+    assert!(opts.validate.protect_ntfs, "NTFS is protected by default");
+    let (_source_tree, destination, _index, outcome) =
+        checkout_index_in_tmp_dir(opts, "make_ntfs_unsafe_paths").unwrap();
+
+    let errors = outcome
+        .errors
+        .iter()
+        .map(|r| r.path.to_str_lossy().into_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        [".git::$INDEX_ALLOCATION/hooks/post-checkout"],
+        "the alternate data stream of the '.git' directory is still rejected"
+    );
+    assert_eq!(
+        stripped_prefix(&destination, &dir_structure(&destination)),
+        paths(["a:b", "dir/x\\y"])
+    );
+}
+
+#[test]
+fn content_is_converted_according_to_attributes_and_filter_drivers() {
+    let mut opts = opts_from_probe();
+    opts.filters = Filters {
+        auto_crlf: AutoCrlf::Disabled,
+        eol: Eol::Lf,
+        drivers: vec![
+            driver("upper", Some("tr a-z A-Z"), false),
+            driver("fail", Some("exit 1"), false),
+        ],
+    };
+    let (_source_tree, destination, index, outcome) = checkout_index_in_tmp_dir(opts, "make_filtered_content").unwrap();
+    assert!(outcome.errors.is_empty());
+
+    let read = |path: &str| std::fs::read(destination.path().join(path)).unwrap();
+    assert_eq!(read("text.crlf"), b"a\r\nb\r\nc\r\n", "lone line feeds are converted");
+    assert_eq!(read("binary.crlf"), b"a\nb\n", "-text takes precedence over eol=crlf");
+    assert_eq!(read("dir/nested"), b"a\r\nb\r\n", "nested attribute files are used");
+    assert_eq!(read("plain"), b"a\nb\n", "without attributes, there is no conversion");
+    let id = index
+        .entries()
+        .iter()
+        .find(|e| e.path(&index) == "file.id")
+        .expect("present")
+        .id;
+    assert_eq!(
+        read("file.id"),
+        format!("$Id: {id} $\n$Id: {id} $\n$Id\n", id = id).as_bytes(),
+        "identifiers are expanded, even if they were expanded before"
+    );
+    assert_eq!(read("file.upper"), b"HELLO\n", "filter drivers are applied");
+    assert_eq!(
+        read("file.fail"),
+        b"hello\n",
+        "failing drivers that aren't required leave the content unchanged"
+    );
+    assert_eq!(
+        outcome.bytes_written,
+        index
+            .entries()
+            .iter()
+            .map(|e| read(e.path(&index).to_str().unwrap()).len() as u64)
+            .sum::<u64>(),
+        "the size of the converted content is reported"
+    );
+}
+
+#[test]
+fn auto_crlf_converts_text_files_without_attributes() {
+    let mut opts = opts_from_probe();
+    opts.filters.auto_crlf = AutoCrlf::Enabled;
+    let (_source_tree, destination, _index, outcome) =
+        checkout_index_in_tmp_dir(opts, "make_filtered_content").unwrap();
+    assert!(outcome.errors.is_empty());
+
+    let read = |path: &str| std::fs::read(destination.path().join(path)).unwrap();
+    assert_eq!(read("plain"), b"a\r\nb\r\n");
+    assert_eq!(read("plain-binary"), b"a\0\nb\n", "binary files are detected");
+    assert_eq!(read("binary.crlf"), b"a\nb\n", "-text is still respected");
+}
+
+#[test]
+fn failing_required_filter_drivers_fail_the_checkout_of_the_file() {
+    let mut opts = opts_from_probe();
+    opts.keep_going = true;
+    opts.filters.drivers = vec![driver("upper", None, true), driver("fail", Some("exit 1"), true)];
+    let (_source_tree, destination, _index, outcome) =
+        checkout_index_in_tmp_dir(opts, "make_filtered_content").unwrap();
+
+    let errors = outcome
+        .errors
+        .iter()
+        .map(|r| r.path.to_str_lossy().into_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        ["file.fail", "file.upper"],
+        "missing smudge commands count as failure"
+    );
+    assert!(!destination.path().join("file.fail").exists());
+}
+
+#[test]
+fn thread_statistics_account_for_all_entries() {
+    let opts = opts_from_probe();
+    let (_source_tree, _destination, index, outcome) =
+        checkout_index_in_tmp_dir(opts, "make_mixed_without_submodules").unwrap();
+
+    assert!(!outcome.threads.is_empty());
+    assert_eq!(
+        outcome.threads.iter().map(|t| t.files).sum::<usize>(),
+        index.entries().len()
+    );
+    assert_eq!(
+        outcome.threads.iter().map(|t| t.bytes_written).sum::<u64>(),
+        outcome.bytes_written
+    );
+    if !multi_threaded() {
+        assert_eq!(outcome.threads.len(), 1, "everything happens on the calling thread");
+    }
+}
+
 #[test]
 fn no_case_related_collisions_on_case_sensitive_filesystem() {
     let opts = opts_from_probe();
@@ -433,6 +630,14 @@ fn opts_from_probe() -> index::checkout::Options {
     }
 }
 
+fn driver(name: &str, smudge: Option<&str>, required: bool) -> FilterDriver {
+    FilterDriver {
+        name: name.into(),
+        smudge: smudge.map(Into::into),
+        required,
+    }
+}
+
 fn paths<'a>(p: impl IntoIterator<Item = &'a str>) -> Vec<PathBuf> {
     p.into_iter().map(PathBuf::from).collect()
 }
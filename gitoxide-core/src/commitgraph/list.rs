@@ -0,0 +1,84 @@
+use std::{io, path::Path};
+
+use anyhow::{Context, Result};
+use git_commitgraph::{file, graph, Graph};
+
+use crate::{
+    commitgraph::{commit_info, info, layers},
+    OutputFormat,
+};
+
+/// Print all layers of the commit-graph at `path` along with all of their commits, in the order of their graph position.
+pub fn list(path: impl AsRef<Path>, format: OutputFormat, mut out: impl io::Write) -> Result<()> {
+    let graph = Graph::at(path).context("Could not open commit graph")?;
+    let layers = layers(&graph);
+    let mut commits = Vec::with_capacity(graph.num_commits() as usize);
+    let mut layer_start = 0;
+    for (layer, file) in layers.iter().zip(graph.iter_files()) {
+        for file_pos in 0..file.num_commits() {
+            let commit = file.commit_at(file::Position(file_pos));
+            commits.push(commit_info(&commit, graph::Position(layer_start + file_pos), layer)?);
+        }
+        layer_start += file.num_commits();
+    }
+
+    match format {
+        OutputFormat::Human => print_human_output(&mut out, &layers, &commits)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => {
+            #[derive(serde::Serialize)]
+            struct Listing<'a> {
+                layers: &'a [info::Layer],
+                commits: &'a [info::Commit],
+            }
+            crate::write_json(
+                out,
+                &Listing {
+                    layers: &layers,
+                    commits: &commits,
+                },
+            )?
+        }
+    }
+    Ok(())
+}
+
+fn print_human_output(out: &mut impl io::Write, layers: &[info::Layer], commits: &[info::Commit]) -> io::Result<()> {
+    for layer in layers {
+        print_layer(out, layer)?;
+    }
+    for commit in commits {
+        writeln!(
+            out,
+            "{:>6} {} layer {} generation {} time {} parents [{}]",
+            commit.position,
+            commit.id,
+            commit.layer,
+            commit.generation,
+            commit.commit_time,
+            commit
+                .parents
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )?;
+    }
+    Ok(())
+}
+
+pub(crate) fn print_layer(out: &mut impl io::Write, layer: &info::Layer) -> io::Result<()> {
+    writeln!(
+        out,
+        "layer {}: {} ({} commits, {} base graphs, {})",
+        layer.index,
+        layer.path.display(),
+        layer.num_commits,
+        layer.base_graph_count,
+        if layer.has_bloom_filters {
+            "with bloom filters"
+        } else {
+            "without bloom filters"
+        }
+    )
+}
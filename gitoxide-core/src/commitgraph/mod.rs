@@ -1 +1,66 @@
+pub mod list;
+pub mod query;
 pub mod verify;
+
+mod info {
+    use std::path::PathBuf;
+
+    /// A file of the commit-graph, which is one layer of a split commit-graph chain.
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+    pub struct Layer {
+        pub index: usize,
+        pub path: PathBuf,
+        pub num_commits: u32,
+        pub base_graph_count: u8,
+        pub has_bloom_filters: bool,
+    }
+
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+    pub struct Commit {
+        pub id: String,
+        /// The position in the whole graph, as used to refer to parents.
+        pub position: u32,
+        pub layer: usize,
+        pub generation: u32,
+        pub commit_time: u64,
+        pub root_tree: String,
+        pub parents: Vec<u32>,
+        pub has_bloom_filter: bool,
+    }
+}
+
+use git_commitgraph::Graph;
+
+fn layers(graph: &Graph) -> Vec<info::Layer> {
+    graph
+        .iter_files()
+        .enumerate()
+        .map(|(index, file)| info::Layer {
+            index,
+            path: file.path().into(),
+            num_commits: file.num_commits(),
+            base_graph_count: file.base_graph_count(),
+            has_bloom_filters: file.has_bloom_filters(),
+        })
+        .collect()
+}
+
+fn commit_info(
+    commit: &git_commitgraph::file::Commit<'_>,
+    position: git_commitgraph::graph::Position,
+    layer: &info::Layer,
+) -> anyhow::Result<info::Commit> {
+    Ok(info::Commit {
+        id: commit.id().to_string(),
+        position: position.0,
+        layer: layer.index,
+        generation: commit.generation(),
+        commit_time: commit.committer_timestamp(),
+        root_tree: commit.root_tree_id().to_string(),
+        parents: commit
+            .iter_parents()
+            .map(|parent| parent.map(|pos| pos.0))
+            .collect::<Result<_, _>>()?,
+        has_bloom_filter: layer.has_bloom_filters,
+    })
+}
@@ -0,0 +1,72 @@
+use std::{io, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use git_commitgraph::Graph;
+use git_repository::ObjectId;
+
+use crate::{
+    commitgraph::{commit_info, info, layers},
+    OutputFormat,
+};
+
+/// Print everything the commit-graph at `path` knows about each commit in `ids`, or fail if one of them isn't in the graph.
+pub fn query(path: impl AsRef<Path>, ids: Vec<ObjectId>, format: OutputFormat, mut out: impl io::Write) -> Result<()> {
+    let graph = Graph::at(path).context("Could not open commit graph")?;
+    let layers = layers(&graph);
+    let commits = ids
+        .iter()
+        .map(|id| {
+            let position = graph
+                .lookup(id)
+                .ok_or_else(|| anyhow!("Commit {} is not contained in the commit graph", id))?;
+            let mut layer_end = 0;
+            let layer = layers
+                .iter()
+                .find(|layer| {
+                    layer_end += layer.num_commits;
+                    position.0 < layer_end
+                })
+                .expect("position is within one of the layers");
+            commit_info(&graph.commit_at(position), position, layer)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    match format {
+        OutputFormat::Human => print_human_output(&mut out, &graph, &layers, &commits)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => crate::write_json(out, &commits)?,
+    }
+    Ok(())
+}
+
+fn print_human_output(
+    out: &mut impl io::Write,
+    graph: &Graph,
+    layers: &[info::Layer],
+    commits: &[info::Commit],
+) -> io::Result<()> {
+    for commit in commits {
+        writeln!(out, "{}", commit.id)?;
+        writeln!(out, "\tposition: {}", commit.position)?;
+        write!(out, "\t")?;
+        super::list::print_layer(out, &layers[commit.layer])?;
+        writeln!(out, "\tgeneration: {}", commit.generation)?;
+        writeln!(out, "\tcommit time: {}", commit.commit_time)?;
+        writeln!(out, "\troot tree: {}", commit.root_tree)?;
+        writeln!(out, "\tparents:")?;
+        for parent in &commit.parents {
+            writeln!(
+                out,
+                "\t\t{} at position {}",
+                graph.id_at(git_commitgraph::graph::Position(*parent)),
+                parent
+            )?;
+        }
+        writeln!(
+            out,
+            "\tchanged-path bloom filter: {}",
+            if commit.has_bloom_filter { "yes" } else { "no" }
+        )?;
+    }
+    Ok(())
+}
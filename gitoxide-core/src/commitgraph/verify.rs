@@ -50,7 +50,7 @@ where
     match output_statistics {
         Some(OutputFormat::Human) => drop(print_human_output(&mut out, &stats)),
         #[cfg(feature = "serde1")]
-        Some(OutputFormat::Json) => serde_json::to_writer_pretty(out, &stats)?,
+        Some(OutputFormat::Json) => crate::write_json(out, &stats)?,
         _ => {}
     }
 
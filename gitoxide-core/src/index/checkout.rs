@@ -82,6 +82,7 @@ pub fn checkout_exclusive(
         collisions,
         files_updated,
         bytes_written,
+        threads,
     } = match repo {
         Some(repo) => git::worktree::index::checkout(
             &mut index,
@@ -122,6 +123,20 @@ pub fn checkout_exclusive(
 
     files.show_throughput(start);
     bytes.show_throughput(start);
+    if threads.len() > 1 {
+        for (thread_index, stats) in threads.iter().enumerate() {
+            progress.info(format!(
+                "thread {}: {} files, {}/s",
+                thread_index,
+                stats.files,
+                git_features::progress::bytes().unwrap().display(
+                    stats.bytes_per_second().unwrap_or_default() as usize,
+                    None,
+                    None
+                )
+            ));
+        }
+    }
 
     progress.done(format!(
         "Created {} {} files{} ({})",
@@ -14,7 +14,7 @@ pub fn entries(
 
     #[cfg(feature = "serde1")]
     if let Json = format {
-        out.write_all(b"[\n")?;
+        writeln!(out, "{{\"format_version\":{},\"data\":[", crate::JSON_FORMAT_VERSION)?;
     }
 
     let mut entries = file.entries().iter().peekable();
@@ -28,7 +28,7 @@ pub fn entries(
 
     #[cfg(feature = "serde1")]
     if let Json = format {
-        out.write_all(b"]\n")?;
+        out.write_all(b"]}\n")?;
     }
     Ok(())
 }
@@ -81,7 +81,7 @@ pub fn information(
         #[cfg(feature = "serde1")]
         Json => {
             let info = information::Collection::try_from_file(parse_file(index_path, object_hash)?, extension_details)?;
-            serde_json::to_writer_pretty(out, &info)?;
+            crate::write_json(out, &info)?;
             Ok(())
         }
     }
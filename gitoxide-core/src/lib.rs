@@ -1,3 +1,22 @@
+//! The library implementing all capabilities of the `gix` and `ein` binaries.
+//!
+//! Most operations write human-readable or JSON output to an `impl std::io::Write`, as suitable for a command-line tool.
+//! The following operations are also available as library functions that return typed outcomes instead,
+//! to allow embedding them into other programs without spawning a `gix` process or parsing its output:
+//!
+//! * [`repository::verify::run()`] verifies the integrity of a repository.
+//! * `repository::clone::run()` clones a repository, with the `blocking-client` feature.
+//! * `pack::receive::run()` fetches a pack from a remote, with the `blocking-client` or `async-client` feature.
+//! * [`pack::create::run()`] creates a pack from objects of a repository.
+//!
+//! Their options and outcomes are part of the public API, with breaking changes being reflected in the version of this crate.
+//!
+//! ## JSON Output
+//!
+//! All JSON output is an object whose `format_version` is the [`JSON_FORMAT_VERSION`] and whose `data` is the actual
+//! output of the operation, like `{"format_version": 1, "data": […]}`. New fields may be added at any time, but changing
+//! or removing fields increments the version, so programs can rely on the shape of `data` for a given `format_version`.
+//!
 //! ## Feature Flags
 #![cfg_attr(
     feature = "document-features",
@@ -27,6 +46,27 @@ impl OutputFormat {
     }
 }
 
+/// The version of the schema of all JSON output, which is incremented whenever fields are changed or removed, but not
+/// when fields are added.
+pub const JSON_FORMAT_VERSION: u32 = 1;
+
+/// Write `value` to `out` as pretty-printed JSON, wrapped into an object along with the [`JSON_FORMAT_VERSION`].
+#[cfg(feature = "serde1")]
+pub fn write_json(out: impl std::io::Write, value: &impl serde::Serialize) -> serde_json::Result<()> {
+    #[derive(serde::Serialize)]
+    struct Versioned<'a, T> {
+        format_version: u32,
+        data: &'a T,
+    }
+    serde_json::to_writer_pretty(
+        out,
+        &Versioned {
+            format_version: JSON_FORMAT_VERSION,
+            data: value,
+        },
+    )
+}
+
 impl FromStr for OutputFormat {
     type Err = String;
 
@@ -52,6 +92,7 @@ pub mod mailmap;
 pub mod organize;
 pub mod pack;
 pub mod repository;
+pub mod serve;
 
 #[cfg(all(feature = "async-client", feature = "blocking-client"))]
 compile_error!("Cannot set both 'blocking-client' and 'async-client' features as they are mutually exclusive");
@@ -1,4 +1,10 @@
-use std::{ffi::OsStr, io, path::Path, str::FromStr, time::Instant};
+use std::{
+    ffi::OsStr,
+    io,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Instant,
+};
 
 use anyhow::anyhow;
 use git_repository as git;
@@ -62,7 +68,7 @@ impl From<ObjectExpansion> for pack::data::output::count::objects::ObjectExpansi
     }
 }
 
-/// A general purpose context for many operations provided here
+/// The context for [`create()`], which prints the [`Outcome`] of [`run()`] to `out`.
 pub struct Context<W> {
     /// The way input objects should be handled
     pub expansion: ObjectExpansion,
@@ -96,12 +102,43 @@ pub struct Context<W> {
     pub out: W,
 }
 
+/// Options for [`run()`], see [`Context`] for their meaning.
+pub struct Options {
+    pub expansion: ObjectExpansion,
+    pub nondeterministic_thread_count: Option<usize>,
+    pub thin: bool,
+    pub thread_limit: Option<usize>,
+    pub pack_cache_size_in_bytes: usize,
+    pub object_cache_size_in_bytes: usize,
+}
+
+/// The outcome of [`run()`].
+pub struct Outcome {
+    /// The hash of the created pack, which is also its name.
+    pub pack_hash: ObjectId,
+    /// The path to the created pack, or `None` if no output directory was given and the pack was discarded.
+    pub pack_path: Option<PathBuf>,
+    /// Statistics about counting and creating pack entries.
+    pub statistics: Statistics,
+}
+
+impl Outcome {
+    /// The name of the pack file, based on its hash.
+    pub fn pack_name(&self) -> String {
+        format!("{}.pack", self.pack_hash)
+    }
+}
+
+/// Create a pack from all objects reachable from `tips`, or from the objects listed by hexadecimal id in `input` if set,
+/// taken from the repository at `repository_path`, and write it into `output_directory` if set.
+///
+/// If `output_directory` is unset, the pack is computed but discarded.
 pub fn create<W>(
     repository_path: impl AsRef<Path>,
     tips: impl IntoIterator<Item = impl AsRef<OsStr>>,
     input: Option<impl io::BufRead + Send + 'static>,
     output_directory: Option<impl AsRef<Path>>,
-    mut progress: impl Progress,
+    progress: impl Progress,
     Context {
         expansion,
         nondeterministic_thread_count,
@@ -116,6 +153,46 @@ pub fn create<W>(
 where
     W: std::io::Write,
 {
+    let outcome = run(
+        repository_path,
+        tips,
+        input,
+        output_directory,
+        progress,
+        Options {
+            expansion,
+            nondeterministic_thread_count,
+            thin,
+            thread_limit,
+            pack_cache_size_in_bytes,
+            object_cache_size_in_bytes,
+        },
+    )?;
+    if outcome.pack_path.is_none() {
+        writeln!(out, "{}", outcome.pack_name())?;
+    }
+    if let Some(format) = statistics {
+        print(outcome.statistics, format, out)?;
+    }
+    Ok(())
+}
+
+/// Like [`create()`], but return the [`Outcome`] instead of printing it.
+pub fn run(
+    repository_path: impl AsRef<Path>,
+    tips: impl IntoIterator<Item = impl AsRef<OsStr>>,
+    input: Option<impl io::BufRead + Send + 'static>,
+    output_directory: Option<impl AsRef<Path>>,
+    mut progress: impl Progress,
+    Options {
+        expansion,
+        nondeterministic_thread_count,
+        thin,
+        thread_limit,
+        pack_cache_size_in_bytes,
+        object_cache_size_in_bytes,
+    }: Options,
+) -> anyhow::Result<Outcome> {
     let repo = git::discover(repository_path)?.into_sync();
     progress.init(Some(2), progress::steps());
     let tips = tips.into_iter();
@@ -292,29 +369,32 @@ where
         .into_inner()
         .digest()
         .expect("iteration is done");
-    let pack_name = format!("{}.pack", hash);
-    if let (Some(pack_file), Some(dir)) = (named_tempfile_store.take(), output_directory) {
-        pack_file.persist(dir.as_ref().join(pack_name))?;
-    } else {
-        writeln!(out, "{}", pack_name)?;
-    }
+    let pack_path = match (named_tempfile_store.take(), output_directory) {
+        (Some(pack_file), Some(dir)) => {
+            let pack_path = dir.as_ref().join(format!("{}.pack", hash));
+            pack_file.persist(&pack_path)?;
+            Some(pack_path)
+        }
+        _ => None,
+    };
     stats.entries = in_order_entries.inner.finalize()?;
 
     write_progress.show_throughput(start);
     entries_progress.show_throughput(start);
 
-    if let Some(format) = statistics {
-        print(stats, format, out)?;
-    }
     progress.inc();
-    Ok(())
+    Ok(Outcome {
+        pack_hash: hash,
+        pack_path,
+        statistics: stats,
+    })
 }
 
 fn print(stats: Statistics, format: OutputFormat, out: impl std::io::Write) -> anyhow::Result<()> {
     match format {
         OutputFormat::Human => human_output(stats, out).map_err(Into::into),
         #[cfg(feature = "serde1")]
-        OutputFormat::Json => serde_json::to_writer_pretty(out, &stats).map_err(Into::into),
+        OutputFormat::Json => crate::write_json(out, &stats).map_err(Into::into),
     }
 }
 
@@ -363,11 +443,14 @@ fn human_output(
     Ok(())
 }
 
-#[derive(Default)]
+/// Statistics about the creation of a pack.
+#[derive(Default, Debug, Clone)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
-struct Statistics {
-    counts: pack::data::output::count::objects::Outcome,
-    entries: pack::data::output::entry::iter_from_counts::Outcome,
+pub struct Statistics {
+    /// Statistics about the counting phase, which finds all objects to put into the pack.
+    pub counts: pack::data::output::count::objects::Outcome,
+    /// Statistics about the phase creating pack entries from the counted objects.
+    pub entries: pack::data::output::entry::iter_from_counts::Outcome,
 }
 
 pub mod input_iteration {
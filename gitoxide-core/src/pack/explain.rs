@@ -0,0 +1,135 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use git_repository as git;
+
+use crate::OutputFormat;
+
+#[cfg(feature = "serde1")]
+mod info {
+    use std::path::PathBuf;
+
+    #[derive(serde::Serialize)]
+    pub struct Explanation {
+        pub id: String,
+        pub pack: PathBuf,
+        pub kind: String,
+        pub object_size: u64,
+        pub chain: Vec<Link>,
+    }
+
+    #[derive(serde::Serialize)]
+    pub struct Link {
+        pub id: String,
+        pub pack_offset: u64,
+        pub kind: &'static str,
+        pub decompressed_size: u64,
+        pub entry_size: u64,
+    }
+}
+
+/// Explain how the object with `id` is stored in the pack at `path`, which may be a `.pack` or `.idx` file or a directory
+/// containing packs, like `.git/objects/pack`.
+pub fn explain(
+    path: PathBuf,
+    id: git::ObjectId,
+    object_hash: git::hash::Kind,
+    format: OutputFormat,
+    mut out: impl std::io::Write,
+) -> anyhow::Result<()> {
+    let (bundle, outcome) = find(&path, id, object_hash)?
+        .with_context(|| format!("Object {} is not contained in any pack at '{}'", id, path.display()))?;
+    match format {
+        OutputFormat::Human => {
+            let first = &outcome.chain[0];
+            writeln!(out, "{}", id)?;
+            writeln!(out, "\tpack: {}", bundle.pack.path().display())?;
+            writeln!(out, "\tkind: {}", outcome.kind)?;
+            writeln!(out, "\tsize: {}", outcome.object_size)?;
+            writeln!(out, "\tpack offset: {}", first.pack_offset)?;
+            writeln!(out, "\tcompressed size: {}", first.entry_size)?;
+            writeln!(
+                out,
+                "\tdelta chain: {} deltas, {} bytes in pack",
+                outcome.num_deltas(),
+                outcome.chain_size()
+            )?;
+            for link in &outcome.chain {
+                writeln!(
+                    out,
+                    "\t\t{} at {}: {}, {} bytes decompressed, {} bytes in pack",
+                    link.id,
+                    link.pack_offset,
+                    entry_kind(link.header),
+                    link.decompressed_size,
+                    link.entry_size
+                )?;
+            }
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => crate::write_json(
+            out,
+            &info::Explanation {
+                id: id.to_hex().to_string(),
+                pack: bundle.pack.path().to_owned(),
+                kind: outcome.kind.to_string(),
+                object_size: outcome.object_size,
+                chain: outcome
+                    .chain
+                    .iter()
+                    .map(|link| info::Link {
+                        id: link.id.to_hex().to_string(),
+                        pack_offset: link.pack_offset,
+                        kind: entry_kind(link.header),
+                        decompressed_size: link.decompressed_size,
+                        entry_size: link.entry_size,
+                    })
+                    .collect(),
+            },
+        )?,
+    }
+    Ok(())
+}
+
+fn find(
+    path: &Path,
+    id: git::ObjectId,
+    object_hash: git::hash::Kind,
+) -> anyhow::Result<Option<(git::odb::pack::Bundle, git::odb::pack::bundle::explain::Outcome)>> {
+    let paths = if path.is_dir() {
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            let entry_path = entry?.path();
+            if entry_path.extension() == Some("idx".as_ref()) {
+                paths.push(entry_path);
+            }
+        }
+        if paths.is_empty() {
+            bail!("No pack index file found in '{}'", path.display());
+        }
+        paths.sort();
+        paths
+    } else {
+        vec![path.to_owned()]
+    };
+    for path in paths {
+        let bundle = git::odb::pack::Bundle::at(&path, object_hash)
+            .with_context(|| format!("Could not open pack at '{}'", path.display()))?;
+        if let Some(outcome) = bundle.explain(id)? {
+            return Ok(Some((bundle, outcome)));
+        }
+    }
+    Ok(None)
+}
+
+fn entry_kind(header: git::odb::pack::data::entry::Header) -> &'static str {
+    use git::odb::pack::data::entry::Header::*;
+    match header {
+        Commit => "commit",
+        Tree => "tree",
+        Blob => "blob",
+        Tag => "tag",
+        OfsDelta { .. } => "ofs-delta",
+        RefDelta { .. } => "ref-delta",
+    }
+}
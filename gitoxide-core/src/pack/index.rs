@@ -87,6 +87,7 @@ pub fn from_pack(
         iteration_mode: ctx.iteration_mode.into(),
         index_kind: pack::index::Version::default(),
         object_hash: ctx.object_hash,
+        fsync: None,
     };
     let out = ctx.out;
     let format = ctx.format;
@@ -118,7 +119,7 @@ pub fn from_pack(
     match format {
         OutputFormat::Human => drop(human_output(out, res)),
         #[cfg(feature = "serde1")]
-        OutputFormat::Json => serde_json::to_writer_pretty(out, &res)?,
+        OutputFormat::Json => crate::write_json(out, &res)?,
     };
     Ok(())
 }
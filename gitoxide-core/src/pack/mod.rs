@@ -1,3 +1,4 @@
+pub mod explain;
 pub mod explode;
 pub mod index;
 pub mod multi_index;
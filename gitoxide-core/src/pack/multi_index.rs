@@ -62,7 +62,7 @@ pub fn info(
     #[cfg(feature = "serde1")]
     {
         let file = git::odb::pack::multi_index::File::at(&multi_index_path)?;
-        serde_json::to_writer_pretty(
+        crate::write_json(
             out,
             &info::Statistics {
                 path: multi_index_path,
@@ -20,22 +20,84 @@ use git_repository::{
 
 use crate::OutputFormat;
 
+pub use git_repository::remote::fetch::maintenance::Options as Maintenance;
+
 pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
 
+/// The context for [`receive()`], which prints the [`Outcome`] of [`run()`] to `out` in the given `format`.
 pub struct Context<W> {
     pub thread_limit: Option<usize>,
     pub format: OutputFormat,
     pub should_interrupt: Arc<AtomicBool>,
     pub out: W,
     pub object_hash: git_repository::hash::Kind,
+    /// The maintenance to perform in the repository owning `directory` after the pack was written into it.
+    pub maintenance: Maintenance,
+}
+
+/// Options for [`run()`].
+pub struct Options {
+    /// If set, don't use more than this amount of threads to index the received pack.
+    pub thread_limit: Option<usize>,
+    /// If set to true, the operation will be interrupted as soon as possible.
+    pub should_interrupt: Arc<AtomicBool>,
+    /// The kind of hash the remote repository uses.
+    pub object_hash: git_repository::hash::Kind,
+    /// The maintenance to perform in the repository owning `directory` after the pack was written into it.
+    pub maintenance: Maintenance,
+}
+
+/// The outcome of [`run()`].
+pub struct Outcome {
+    /// Information about the pack that was received and its index.
+    pub pack: pack::bundle::write::Outcome,
+    /// All references advertised by the remote.
+    pub refs: Vec<Ref>,
 }
 
-struct CloneDelegate<W> {
-    ctx: Context<W>,
+struct CloneDelegate {
+    options: Options,
     directory: Option<PathBuf>,
     refs_directory: Option<PathBuf>,
     ref_filter: Option<&'static [&'static str]>,
     wanted_refs: Vec<BString>,
+    outcome: Option<Outcome>,
+}
+
+impl<W> Context<W> {
+    fn into_options_and_output(self) -> (Options, OutputFormat, W) {
+        let Context {
+            thread_limit,
+            format,
+            should_interrupt,
+            out,
+            object_hash,
+            maintenance,
+        } = self;
+        (
+            Options {
+                thread_limit,
+                should_interrupt,
+                object_hash,
+                maintenance,
+            },
+            format,
+            out,
+        )
+    }
+}
+
+fn print_outcome(outcome: Option<Outcome>, format: OutputFormat, mut out: impl io::Write) -> anyhow::Result<()> {
+    let Outcome { pack, refs } = match outcome {
+        Some(outcome) => outcome,
+        None => return Ok(()),
+    };
+    match format {
+        OutputFormat::Human => drop(print(&mut out, pack, &refs)),
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => crate::write_json(&mut out, &JsonOutcome::from_outcome_and_refs(pack, &refs))?,
+    };
+    Ok(())
 }
 static FILTER: &[&str] = &["HEAD", "refs/tags", "refs/heads"];
 
@@ -46,7 +108,7 @@ fn remote_supports_ref_in_want(server: &Capabilities) -> bool {
         .unwrap_or(false)
 }
 
-impl<W> protocol::fetch::DelegateBlocking for CloneDelegate<W> {
+impl protocol::fetch::DelegateBlocking for CloneDelegate {
     fn prepare_ls_refs(
         &mut self,
         server: &Capabilities,
@@ -120,10 +182,10 @@ mod blocking_io {
         Progress,
     };
 
-    use super::{receive_pack_blocking, CloneDelegate, Context};
+    use super::{print_outcome, receive_pack_blocking, CloneDelegate, Context, Options, Outcome};
     use crate::net;
 
-    impl<W: io::Write> protocol::fetch::Delegate for CloneDelegate<W> {
+    impl protocol::fetch::Delegate for CloneDelegate {
         fn receive_pack(
             &mut self,
             input: impl BufRead,
@@ -131,67 +193,87 @@ mod blocking_io {
             refs: &[Ref],
             _previous_response: &Response,
         ) -> io::Result<()> {
-            receive_pack_blocking(
+            self.outcome = Some(receive_pack_blocking(
                 self.directory.take(),
                 self.refs_directory.take(),
-                &mut self.ctx,
+                &self.options,
                 input,
                 progress,
                 refs,
-            )
+            )?);
+            Ok(())
         }
     }
 
-    pub fn receive<P: Progress, W: io::Write>(
+    /// Receive a pack from the remote at `url` and write it along with its index into `directory`, or only verify it if unset.
+    ///
+    /// All references are received unless `wanted_refs` is non-empty, and they are written as loose references into
+    /// `refs_directory` if set. Return `None` if the remote didn't send a pack.
+    pub fn run<P: Progress>(
         protocol: Option<net::Protocol>,
         url: &str,
         directory: Option<PathBuf>,
         refs_directory: Option<PathBuf>,
         wanted_refs: Vec<BString>,
         progress: P,
-        ctx: Context<W>,
-    ) -> anyhow::Result<()> {
+        options: Options,
+    ) -> anyhow::Result<Option<Outcome>> {
         let transport = net::connect(url, protocol.unwrap_or_default().into())?;
-        let delegate = CloneDelegate {
-            ctx,
+        let mut delegate = CloneDelegate {
+            options,
             directory,
             refs_directory,
             ref_filter: None,
             wanted_refs,
+            outcome: None,
         };
         protocol::fetch(
             transport,
-            delegate,
+            &mut delegate,
             protocol::credentials::helper,
             progress,
             protocol::FetchConnection::TerminateOnSuccessfulCompletion,
         )?;
-        Ok(())
+        Ok(delegate.outcome)
+    }
+
+    /// Like [`run()`], but print the outcome to `ctx.out` in the format of `ctx.format`.
+    pub fn receive<P: Progress, W: io::Write>(
+        protocol: Option<net::Protocol>,
+        url: &str,
+        directory: Option<PathBuf>,
+        refs_directory: Option<PathBuf>,
+        wanted_refs: Vec<BString>,
+        progress: P,
+        ctx: Context<W>,
+    ) -> anyhow::Result<()> {
+        let (options, format, out) = ctx.into_options_and_output();
+        let outcome = run(protocol, url, directory, refs_directory, wanted_refs, progress, options)?;
+        print_outcome(outcome, format, out)
     }
 }
 
 #[cfg(feature = "blocking-client")]
-pub use blocking_io::receive;
+pub use blocking_io::{receive, run};
 
 #[cfg(feature = "async-client")]
 mod async_io {
-    use std::{io, io::BufRead, path::PathBuf};
+    use std::{io, path::PathBuf};
 
     use async_trait::async_trait;
     use futures_io::AsyncBufRead;
     use git_repository::{
-        bstr::{BString, ByteSlice},
-        odb::pack,
+        bstr::BString,
         protocol,
         protocol::fetch::{Ref, Response},
         Progress,
     };
 
-    use super::{print, receive_pack_blocking, write_raw_refs, CloneDelegate, Context};
-    use crate::{net, OutputFormat};
+    use super::{print_outcome, receive_pack_blocking, CloneDelegate, Context, Options, Outcome};
+    use crate::net;
 
     #[async_trait(?Send)]
-    impl<W: io::Write + Send + 'static> protocol::fetch::Delegate for CloneDelegate<W> {
+    impl protocol::fetch::Delegate for CloneDelegate {
         async fn receive_pack(
             &mut self,
             input: impl AsyncBufRead + Unpin + 'async_trait,
@@ -199,50 +281,72 @@ mod async_io {
             refs: &[Ref],
             _previous_response: &Response,
         ) -> io::Result<()> {
-            receive_pack_blocking(
+            self.outcome = Some(receive_pack_blocking(
                 self.directory.take(),
                 self.refs_directory.take(),
-                &mut self.ctx,
+                &self.options,
                 futures_lite::io::BlockOn::new(input),
                 progress,
-                &refs,
-            )
+                refs,
+            )?);
+            Ok(())
         }
     }
 
-    pub async fn receive<P: Progress, W: io::Write + Send + 'static>(
+    /// Receive a pack from the remote at `url` and write it along with its index into `directory`, or only verify it if unset.
+    ///
+    /// All references are received unless `wanted_refs` is non-empty, and they are written as loose references into
+    /// `refs_directory` if set. Return `None` if the remote didn't send a pack.
+    pub async fn run<P: Progress + 'static>(
         protocol: Option<net::Protocol>,
         url: &str,
         directory: Option<PathBuf>,
         refs_directory: Option<PathBuf>,
         wanted_refs: Vec<BString>,
         progress: P,
-        ctx: Context<W>,
-    ) -> anyhow::Result<()> {
+        options: Options,
+    ) -> anyhow::Result<Option<Outcome>> {
         let transport = net::connect(url.to_string(), protocol.unwrap_or_default().into()).await?;
         let mut delegate = CloneDelegate {
-            ctx,
+            options,
             directory,
             refs_directory,
             ref_filter: None,
             wanted_refs,
+            outcome: None,
         };
         blocking::unblock(move || {
             futures_lite::future::block_on(protocol::fetch(
                 transport,
-                delegate,
+                &mut delegate,
                 protocol::credentials::helper,
                 progress,
                 protocol::FetchConnection::TerminateOnSuccessfulCompletion,
             ))
+            .map(|()| delegate.outcome)
         })
-        .await?;
-        Ok(())
+        .await
+        .map_err(Into::into)
+    }
+
+    /// Like [`run()`], but print the outcome to `ctx.out` in the format of `ctx.format`.
+    pub async fn receive<P: Progress + 'static, W: io::Write + Send + 'static>(
+        protocol: Option<net::Protocol>,
+        url: &str,
+        directory: Option<PathBuf>,
+        refs_directory: Option<PathBuf>,
+        wanted_refs: Vec<BString>,
+        progress: P,
+        ctx: Context<W>,
+    ) -> anyhow::Result<()> {
+        let (options, format, out) = ctx.into_options_and_output();
+        let outcome = run(protocol, url, directory, refs_directory, wanted_refs, progress, options).await?;
+        print_outcome(outcome, format, out)
     }
 }
 
 #[cfg(feature = "async-client")]
-pub use self::async_io::receive;
+pub use self::async_io::{receive, run};
 
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct JsonBundleWriteOutcome {
@@ -320,34 +424,60 @@ fn write_raw_refs(refs: &[Ref], directory: PathBuf) -> std::io::Result<()> {
     Ok(())
 }
 
-fn receive_pack_blocking<W: io::Write>(
+/// Perform `maintenance` in the repository whose `objects/pack` directory contains the pack index at `index_path`.
+fn maintain_after_receive(
+    index_path: &std::path::Path,
+    maintenance: Maintenance,
+    should_interrupt: &AtomicBool,
+) -> anyhow::Result<()> {
+    let git_dir = index_path
+        .parent()
+        .and_then(|pack_dir| pack_dir.parent())
+        .and_then(|objects_dir| objects_dir.parent())
+        .ok_or_else(|| anyhow::anyhow!("Pack directory must be located at '<git-dir>/objects/pack' for maintenance"))?;
+    let repo = git::open(git_dir)?;
+    repo.maintain_after_fetch(index_path, git::progress::Discard, should_interrupt, maintenance)?;
+    Ok(())
+}
+
+fn receive_pack_blocking(
     mut directory: Option<PathBuf>,
     mut refs_directory: Option<PathBuf>,
-    ctx: &mut Context<W>,
+    options: &Options,
     input: impl io::BufRead,
     progress: impl Progress,
     refs: &[Ref],
-) -> io::Result<()> {
-    let options = pack::bundle::write::Options {
-        thread_limit: ctx.thread_limit,
+) -> io::Result<Outcome> {
+    let write_options = pack::bundle::write::Options {
+        thread_limit: options.thread_limit,
         index_kind: pack::index::Version::V2,
         iteration_mode: pack::data::input::Mode::Verify,
-        object_hash: ctx.object_hash,
+        object_hash: options.object_hash,
+        fsync: None,
     };
-    let outcome =
-        pack::Bundle::write_to_directory(input, directory.take(), progress, &ctx.should_interrupt, None, options)
-            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+    let outcome = pack::Bundle::write_to_directory(
+        input,
+        directory.take(),
+        progress,
+        &options.should_interrupt,
+        None,
+        write_options,
+    )
+    .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+
+    if let Some(index_path) = outcome.index_path.as_ref() {
+        if options.maintenance != Default::default() {
+            maintain_after_receive(index_path, options.maintenance, &options.should_interrupt)
+                .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        }
+    }
 
     if let Some(directory) = refs_directory.take() {
         write_raw_refs(refs, directory)?;
     }
 
-    match ctx.format {
-        OutputFormat::Human => drop(print(&mut ctx.out, outcome, refs)),
-        #[cfg(feature = "serde1")]
-        OutputFormat::Json => {
-            serde_json::to_writer_pretty(&mut ctx.out, &JsonOutcome::from_outcome_and_refs(outcome, refs))?
-        }
-    };
-    Ok(())
+    Ok(Outcome {
+        pack: outcome,
+        refs: refs.to_owned(),
+    })
 }
@@ -176,7 +176,7 @@ where
                             }
                         },
                         #[cfg(feature = "serde1")]
-                        Some(OutputFormat::Json) => serde_json::to_writer_pretty(out, &multi_index.index_names().iter().zip(res.pack_traverse_statistics).collect::<Vec<_>>())?,
+                        Some(OutputFormat::Json) => crate::write_json(out, &multi_index.index_names().iter().zip(res.pack_traverse_statistics).collect::<Vec<_>>())?,
                         _ => {}
                     };
                     return Ok(())
@@ -194,7 +194,7 @@ where
         match output_statistics {
             Some(OutputFormat::Human) => drop(print_statistics(&mut out, stats)),
             #[cfg(feature = "serde1")]
-            Some(OutputFormat::Json) => serde_json::to_writer_pretty(out, stats)?,
+            Some(OutputFormat::Json) => crate::write_json(out, stats)?,
             _ => {}
         };
     }
@@ -0,0 +1,121 @@
+use std::{io, io::Read, path::PathBuf, str::FromStr};
+
+use anyhow::bail;
+use git_repository as git;
+use git_repository::apply::{Location, Outcome};
+
+use crate::OutputFormat;
+
+/// What to do with whitespace errors in added lines.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Whitespace {
+    Nowarn,
+    Warn,
+    Fix,
+    Error,
+}
+
+impl Whitespace {
+    pub fn variants() -> &'static [&'static str] {
+        &["nowarn", "warn", "fix", "error"]
+    }
+}
+
+impl FromStr for Whitespace {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s_lc = s.to_ascii_lowercase();
+        Ok(match s_lc.as_str() {
+            "nowarn" => Whitespace::Nowarn,
+            "warn" => Whitespace::Warn,
+            "fix" => Whitespace::Fix,
+            "error" => Whitespace::Error,
+            _ => return Err(format!("Invalid whitespace action: '{}'", s)),
+        })
+    }
+}
+
+impl From<Whitespace> for git::apply::Whitespace {
+    fn from(v: Whitespace) -> Self {
+        match v {
+            Whitespace::Nowarn => git::apply::Whitespace::Nowarn,
+            Whitespace::Warn => git::apply::Whitespace::Warn,
+            Whitespace::Fix => git::apply::Whitespace::Fix,
+            Whitespace::Error => git::apply::Whitespace::Error,
+        }
+    }
+}
+
+pub struct Options {
+    pub format: OutputFormat,
+    /// If true, apply to the index only.
+    pub cached: bool,
+    /// If true, apply to the index and the worktree.
+    pub index: bool,
+    /// If true, undo the changes of the patches.
+    pub reverse: bool,
+    /// If true, write hunks that don't apply to `.rej` files instead of failing.
+    pub reject: bool,
+    pub whitespace: Whitespace,
+}
+
+/// Apply all `patches`, or the patch on stdin if there is none, to `repo` and report rejected hunks and whitespace errors to `err`.
+pub fn apply(
+    repo: git::Repository,
+    patches: Vec<PathBuf>,
+    mut err: impl io::Write,
+    Options {
+        format,
+        cached,
+        index,
+        reverse,
+        reject,
+        whitespace,
+    }: Options,
+) -> anyhow::Result<Outcome> {
+    if format != OutputFormat::Human {
+        bail!("JSON output isn't implemented yet");
+    }
+    let mut patch = Vec::new();
+    if patches.is_empty() {
+        io::stdin().read_to_end(&mut patch)?;
+    }
+    for path in patches {
+        patch.extend(std::fs::read(path)?);
+    }
+
+    let outcome = repo.apply(
+        &patch,
+        git::apply::Options {
+            location: if cached {
+                Location::Index
+            } else if index {
+                Location::Both
+            } else {
+                Location::Worktree
+            },
+            reverse,
+            reject,
+            whitespace: whitespace.into(),
+        },
+    )?;
+    if outcome.whitespace_errors != 0 {
+        writeln!(
+            err,
+            "warning: {} line(s) {} whitespace errors",
+            outcome.whitespace_errors,
+            if whitespace == Whitespace::Fix {
+                "applied after fixing"
+            } else {
+                "add"
+            }
+        )?;
+    }
+    for rejected in &outcome.rejects {
+        for hunk in &rejected.hunks {
+            writeln!(err, "Rejected hunk #{} of {}", hunk, rejected.path)?;
+        }
+    }
+    Ok(outcome)
+}
@@ -0,0 +1,76 @@
+use std::{io, path::PathBuf};
+
+use anyhow::{bail, Context};
+use git_repository as git;
+
+pub struct Options {
+    /// The kind of archive to write, or `None` to derive it from the extension of the output file, falling back to `tar`.
+    pub format: Option<git::archive::Format>,
+    /// A path to prepend to all entries in the archive, like `project/`.
+    pub prefix: Option<String>,
+}
+
+/// Write the tree of `treeish`, or of `HEAD` if unset, as archive to `output`, or to `out` if unset.
+pub fn archive(
+    repo: git::Repository,
+    treeish: Option<&str>,
+    output: Option<PathBuf>,
+    mut out: impl io::Write,
+    Options { format, prefix }: Options,
+) -> anyhow::Result<()> {
+    let id = match treeish {
+        Some(spec) => repo
+            .rev_parse(spec)?
+            .single()
+            .context("Need single revision revspec")?
+            .detach(),
+        None => repo.head_id()?.detach(),
+    };
+    let format = match format {
+        Some(format) => format,
+        None => match output
+            .as_deref()
+            .and_then(|path| path.file_name())
+            .and_then(|n| n.to_str())
+        {
+            Some(name) => format_from_file_name(name)?,
+            None => git::archive::Format::Tar,
+        },
+    };
+    let options = git::archive::Options {
+        format,
+        prefix: prefix.map(Into::into),
+        modification_time: None,
+    };
+    match output {
+        Some(path) => {
+            let file = std::fs::File::create(&path).with_context(|| format!("Could not create {:?}", path))?;
+            let outcome = repo.archive(id, io::BufWriter::new(file), options)?;
+            writeln!(
+                out,
+                "wrote {} entries of tree {} to {:?}",
+                outcome.entries, outcome.tree_id, path
+            )?;
+        }
+        None => {
+            repo.archive(id, out, options)?;
+        }
+    }
+    Ok(())
+}
+
+fn format_from_file_name(name: &str) -> anyhow::Result<git::archive::Format> {
+    use git::archive::Format;
+    Ok(if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Format::TarGz
+    } else if name.ends_with(".zip") {
+        Format::Zip
+    } else if name.ends_with(".tar") {
+        Format::Tar
+    } else {
+        bail!(
+            "Cannot derive the archive format from {:?}, use --format to set it",
+            name
+        )
+    })
+}
@@ -0,0 +1,119 @@
+use std::{num::NonZeroU32, path::PathBuf, sync::atomic::AtomicBool};
+
+use anyhow::Context;
+use git_repository as git;
+
+use crate::OutputFormat;
+
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
+
+/// Options for [`clone()`] and [`run()`].
+pub struct Options {
+    /// If true, create a bare repository without checkout.
+    pub bare: bool,
+    /// If set, fetch only this many commits of each branch.
+    pub depth: Option<NonZeroU32>,
+    /// The name of the remote to create, or `origin` if unset.
+    pub remote_name: Option<String>,
+}
+
+/// The outcome of [`run()`].
+#[derive(Debug)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+pub struct Outcome {
+    /// The directory the repository was cloned into, which is its `.git` directory if it is bare, or its worktree otherwise.
+    pub directory: PathBuf,
+    /// The amount of objects received from the remote, or `None` if the remote repository is empty.
+    pub objects_received: Option<u32>,
+    /// The amount of local references created from the references of the remote.
+    pub refs_created: usize,
+    /// The full name of the branch `HEAD` points to, or `None` if it is detached.
+    pub head_branch: Option<String>,
+    /// The amount of files written into the worktree, or `None` if nothing was checked out.
+    pub files_checked_out: Option<usize>,
+}
+
+/// Clone the repository at `url` into `directory`, or into a directory named after the last component of `url` if unset,
+/// and print what happened to `out` in the given `format`.
+pub fn clone(
+    url: git::Url,
+    directory: Option<PathBuf>,
+    progress: impl git::Progress,
+    mut out: impl std::io::Write,
+    should_interrupt: &AtomicBool,
+    format: OutputFormat,
+    options: Options,
+) -> anyhow::Result<()> {
+    let (_repo, outcome) = run(url, directory, progress, should_interrupt, options)?;
+    match format {
+        OutputFormat::Human => {
+            match outcome.objects_received {
+                Some(num_objects) => writeln!(out, "received {} objects", num_objects)?,
+                None => writeln!(out, "the remote repository is empty")?,
+            }
+            writeln!(out, "created {} references", outcome.refs_created)?;
+            match outcome.head_branch {
+                Some(branch) => writeln!(out, "HEAD points to {}", branch)?,
+                None if outcome.files_checked_out.is_some() => writeln!(out, "HEAD is detached")?,
+                None => {}
+            }
+            if let Some(files) = outcome.files_checked_out {
+                writeln!(out, "checked out {} files into {:?}", files, outcome.directory)?;
+            }
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => crate::write_json(out, &outcome)?,
+    }
+    Ok(())
+}
+
+/// Like [`clone()`], but return the cloned repository along with the [`Outcome`] instead of printing it.
+pub fn run(
+    url: git::Url,
+    directory: Option<PathBuf>,
+    mut progress: impl git::Progress,
+    should_interrupt: &AtomicBool,
+    Options {
+        bare,
+        depth,
+        remote_name,
+    }: Options,
+) -> anyhow::Result<(git::Repository, Outcome)> {
+    let directory = match directory {
+        Some(directory) => directory,
+        None => directory_from_url(&url, bare)?,
+    };
+    progress.info(format!("Cloning {} into {:?}", url.to_bstring(), directory));
+    let mut prepare = git::clone::PrepareClone::new(url, directory)?
+        .bare(bare)
+        .with_depth(depth);
+    if let Some(name) = remote_name {
+        prepare = prepare.with_remote_name(name);
+    }
+    let (repo, outcome) = prepare.fetch_then_checkout(progress, should_interrupt)?;
+
+    let outcome = Outcome {
+        directory: repo.work_dir().unwrap_or_else(|| repo.git_dir()).to_owned(),
+        objects_received: outcome.pack.map(|pack| pack.index.num_objects),
+        refs_created: outcome.update_refs.edits.len(),
+        head_branch: outcome.head_branch.map(|branch| branch.as_bstr().to_string()),
+        files_checked_out: outcome.checkout.map(|checkout| checkout.files_updated),
+    };
+    Ok((repo, outcome))
+}
+
+fn directory_from_url(url: &git::Url, bare: bool) -> anyhow::Result<PathBuf> {
+    let path = url.path.to_string();
+    let name = path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .map(|name| name.trim_end_matches(".git"))
+        .filter(|name| !name.is_empty())
+        .with_context(|| format!("Could not derive a directory name from {:?}, please specify it", path))?;
+    Ok(if bare {
+        format!("{}.git", name).into()
+    } else {
+        name.into()
+    })
+}
@@ -1,11 +1,114 @@
-use anyhow::{bail, Result};
+use std::{borrow::Cow, convert::TryFrom, path::PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
 use git_repository as git;
+use git_repository::bstr::{BStr, BString, ByteSlice};
 
 use crate::OutputFormat;
 
+/// The configuration files to read from or write to, akin to `--system`, `--global`, `--local` and `--worktree` in git.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// The system-wide configuration, typically `/etc/gitconfig`.
+    System,
+    /// The configuration of the current user, `~/.gitconfig` or `$XDG_CONFIG_HOME/git/config`.
+    Global,
+    /// The configuration of the repository, `.git/config`.
+    Local,
+    /// The configuration of the current worktree, `.git/config.worktree`.
+    Worktree,
+}
+
+impl Scope {
+    /// Return true if `source` is within `scope`, with all sources being in scope if it is `None`.
+    fn contains(scope: Option<Scope>, source: git::config::Source) -> bool {
+        use git::config::Source;
+        match scope {
+            Some(scope) => matches!(
+                (scope, source),
+                (Scope::System, Source::System)
+                    | (Scope::Global, Source::Git | Source::User)
+                    | (Scope::Local, Source::Local)
+                    | (Scope::Worktree, Source::Worktree)
+            ),
+            None => true,
+        }
+    }
+
+    /// Return the source and path of the file to write to in this scope.
+    fn writable_location(&self, repo: &git::Repository) -> Result<(git::config::Source, PathBuf)> {
+        use git::config::Source;
+        let mut env_var = |name: &str| std::env::var_os(name);
+        Ok(match self {
+            Scope::Local => (Source::Local, repo.common_dir().join("config")),
+            Scope::Worktree => (Source::Worktree, repo.git_dir().join("config.worktree")),
+            Scope::System => (
+                Source::System,
+                Source::System
+                    .storage_location(&mut env_var)
+                    .context("The system configuration is disabled by GIT_CONFIG_NOSYSTEM")?
+                    .into_owned(),
+            ),
+            Scope::Global => {
+                // Like git, prefer `~/.gitconfig` unless only the XDG location exists.
+                let user = Source::User.storage_location(&mut env_var);
+                let xdg = Source::Git.storage_location(&mut env_var);
+                match (user, xdg) {
+                    (Some(user), Some(xdg)) if !user.is_file() && xdg.is_file() => (Source::Git, xdg.into_owned()),
+                    (Some(user), _) => (Source::User, user.into_owned()),
+                    (None, Some(xdg)) => (Source::Git, xdg.into_owned()),
+                    (None, None) => {
+                        bail!("Could not determine the location of the global configuration as HOME isn't set")
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// The type to interpret values as before printing them, similar to `--type` in git.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    /// Print `true` or `false`.
+    Bool,
+    /// Print the value as decimal number, with suffixes like `k` applied.
+    Int,
+    /// Print the value as path with `~` and `%(prefix)` expanded.
+    Path,
+}
+
+impl std::str::FromStr for ValueType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "bool" => ValueType::Bool,
+            "int" => ValueType::Int,
+            "path" => ValueType::Path,
+            unknown => return Err(format!("Unknown value type: {:?}", unknown)),
+        })
+    }
+}
+
+pub mod list {
+    pub struct Options {
+        /// If set, only show values from configuration files in this scope.
+        pub scope: Option<super::Scope>,
+        /// Print each value on its own line, prefixed with the file it was read from.
+        pub show_origin: bool,
+        /// Print each value on its own line, prefixed with its scope.
+        pub show_scope: bool,
+    }
+}
+
 pub fn list(
     repo: git::Repository,
     filters: Vec<String>,
+    list::Options {
+        scope,
+        show_origin,
+        show_scope,
+    }: list::Options,
     format: OutputFormat,
     mut out: impl std::io::Write,
 ) -> Result<()> {
@@ -15,19 +118,37 @@ pub fn list(
     let repo = git::open_opts(repo.git_dir(), repo.open_options().clone().lossy_config(false))?;
     let config = repo.config_snapshot();
     let config = config.plumbing();
-    if let Some(frontmatter) = config.frontmatter() {
+    let flat = show_origin || show_scope;
+    if let Some(frontmatter) = config.frontmatter().filter(|_| !flat && scope.is_none()) {
         for event in frontmatter {
             event.write_to(&mut out)?;
         }
     }
     let filters: Vec<_> = filters.into_iter().map(Filter::new).collect();
     let mut last_meta = None;
-    let mut it = config.sections_and_postmatter().peekable();
+    let mut it = config
+        .sections_and_postmatter()
+        .filter(|(section, _)| Scope::contains(scope, section.meta().source))
+        .peekable();
     while let Some((section, matter)) = it.next() {
         if !filters.is_empty() && !filters.iter().any(|filter| filter.matches_section(section)) {
             continue;
         }
 
+        if flat {
+            for (key, value) in section.body().clone() {
+                write_origin(section.meta(), show_origin, show_scope, &mut out)?;
+                writeln!(
+                    out,
+                    "{}.{}={}",
+                    section_key_prefix(section),
+                    key.as_ref().to_ascii_lowercase(),
+                    git::config::value::normalize(value)
+                )?;
+            }
+            continue;
+        }
+
         let meta = section.meta();
         if last_meta.map_or(true, |last| last != meta) {
             write_meta(meta, &mut out)?;
@@ -102,3 +223,304 @@ fn write_meta(meta: &git::config::file::Metadata, out: &mut impl std::io::Write)
             .unwrap_or_default()
     )
 }
+
+pub mod get {
+    pub struct Options {
+        /// If set, only read values from configuration files in this scope.
+        pub scope: Option<super::Scope>,
+        /// Print all values of a multi-valued key instead of only the last one.
+        pub all: bool,
+        /// If set, interpret values as the given type and print them in canonical form.
+        pub value_type: Option<super::ValueType>,
+        /// Prefix each value with the file it was read from.
+        pub show_origin: bool,
+        /// Prefix each value with its scope.
+        pub show_scope: bool,
+    }
+}
+
+/// Print the value of `key`, or all of its values if `all` is set, optionally only those matching the regular
+/// expression `value_pattern`, which is negated if it starts with `!`.
+pub fn get(
+    repo: git::Repository,
+    key: &str,
+    value_pattern: Option<&str>,
+    get::Options {
+        scope,
+        all,
+        value_type,
+        show_origin,
+        show_scope,
+    }: get::Options,
+    mut out: impl std::io::Write,
+) -> Result<()> {
+    let key = parse_key(key)?;
+    let pattern = value_pattern.map(ValuePattern::new).transpose()?;
+    let config = repo.config_snapshot();
+    let config = config.plumbing();
+    let subsection_name = key.subsection_name.map(|name| name.as_bytes().as_bstr());
+    let mut values: Vec<_> = config
+        .sections_by_name(key.section_name)
+        .into_iter()
+        .flatten()
+        .filter(|section| {
+            section.header().subsection_name() == subsection_name && Scope::contains(scope, section.meta().source)
+        })
+        .flat_map(|section| {
+            let meta = section.meta();
+            section
+                .body()
+                .values(key.value_name)
+                .into_iter()
+                .map(move |value| (value, meta))
+        })
+        .filter(|(value, _)| ValuePattern::matches(pattern.as_ref(), value.as_ref()))
+        .collect();
+    if values.is_empty() {
+        bail!("No value found for '{}'", key_name(&key));
+    }
+    if !all {
+        values.drain(..values.len() - 1);
+    }
+
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let install_dir = repo.install_dir().ok();
+    let interpolate = git::config::path::interpolate::Context {
+        git_install_dir: install_dir.as_deref(),
+        home_dir: home.as_deref(),
+        ..Default::default()
+    };
+    for (value, meta) in values {
+        write_origin(meta, show_origin, show_scope, &mut out)?;
+        match value_type {
+            None => out.write_all(value.as_ref())?,
+            Some(ValueType::Bool) => write!(out, "{}", git::config::Boolean::try_from(value)?)?,
+            Some(ValueType::Int) => write!(
+                out,
+                "{}",
+                git::config::Integer::try_from(value.as_ref())?
+                    .to_decimal()
+                    .ok_or_else(|| anyhow!("Integer overflow in value of '{}'", key_name(&key)))?
+            )?,
+            Some(ValueType::Path) => write!(
+                out,
+                "{}",
+                git::config::Path::from(value).interpolate(interpolate)?.display()
+            )?,
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+pub mod set {
+    pub struct Options {
+        /// The configuration file to change, or the one of the repository if unset.
+        pub scope: Option<super::Scope>,
+        /// Replace all matching values of a multi-valued key with the new value.
+        pub all: bool,
+        /// Add the value as new value without altering existing ones.
+        pub add: bool,
+    }
+}
+
+/// Set `key` to `value` in the configuration file of `scope`, replacing the existing value or only those matching
+/// `value_pattern`, a regular expression which is negated if it starts with `!`.
+pub fn set(
+    repo: git::Repository,
+    key: &str,
+    value: &str,
+    value_pattern: Option<&str>,
+    set::Options { scope, all, add }: set::Options,
+) -> Result<()> {
+    let key = parse_key(key)?;
+    let pattern = value_pattern.map(ValuePattern::new).transpose()?;
+    let (mut file, path) = load_for_writing(&repo, scope.unwrap_or(Scope::Local))?;
+
+    let mut needs_push = true;
+    if !add {
+        if let Ok(mut values) = file.raw_values_mut(key.section_name, key.subsection_name, key.value_name) {
+            let matching = matching_indices(&values.get()?, pattern.as_ref());
+            match matching.as_slice() {
+                [] => {}
+                [index] => {
+                    values.set_at(*index, value);
+                    needs_push = false;
+                }
+                [first, rest @ ..] if all => {
+                    for index in rest.iter().rev() {
+                        values.delete(*index);
+                    }
+                    values.set_at(*first, value);
+                    needs_push = false;
+                }
+                _ => bail!(
+                    "'{}' has multiple values, use --all to replace all of them or a value pattern to select one",
+                    key_name(&key)
+                ),
+            }
+        }
+    }
+    if needs_push {
+        file.section_mut_or_create_new(key.section_name, key.subsection_name)?
+            .push(
+                git::config::parse::section::Key::try_from(key.value_name.to_owned())?,
+                Some(value.into()),
+            );
+    }
+    write_back(&file, path)
+}
+
+pub mod unset {
+    pub struct Options {
+        /// The configuration file to change, or the one of the repository if unset.
+        pub scope: Option<super::Scope>,
+        /// Remove all matching values of a multi-valued key.
+        pub all: bool,
+    }
+}
+
+/// Remove `key` from the configuration file of `scope`, or only its values matching `value_pattern`, a regular expression
+/// which is negated if it starts with `!`.
+pub fn unset(
+    repo: git::Repository,
+    key: &str,
+    value_pattern: Option<&str>,
+    unset::Options { scope, all }: unset::Options,
+) -> Result<()> {
+    let key = parse_key(key)?;
+    let pattern = value_pattern.map(ValuePattern::new).transpose()?;
+    let (mut file, path) = load_for_writing(&repo, scope.unwrap_or(Scope::Local))?;
+    {
+        let mut values = file
+            .raw_values_mut(key.section_name, key.subsection_name, key.value_name)
+            .map_err(|_| anyhow!("'{}' is not set in {:?}", key_name(&key), path))?;
+        let matching = matching_indices(&values.get()?, pattern.as_ref());
+        match matching.as_slice() {
+            [] => bail!("No value of '{}' matches in {:?}", key_name(&key), path),
+            [_] => {}
+            _ if all => {}
+            _ => bail!(
+                "'{}' has multiple values, use --all to remove all of them or a value pattern to select one",
+                key_name(&key)
+            ),
+        }
+        for index in matching.into_iter().rev() {
+            values.delete(index);
+        }
+    }
+    write_back(&file, path)
+}
+
+/// A regular expression to select values of multi-valued keys, which selects all non-matching values if prefixed with `!`.
+struct ValuePattern {
+    regex: regex::bytes::Regex,
+    negated: bool,
+}
+
+impl ValuePattern {
+    fn new(pattern: &str) -> Result<Self> {
+        let (pattern, negated) = match pattern.strip_prefix('!') {
+            Some(pattern) => (pattern, true),
+            None => (pattern, false),
+        };
+        Ok(ValuePattern {
+            regex: regex::bytes::Regex::new(pattern)
+                .with_context(|| format!("Invalid value pattern: {:?}", pattern))?,
+            negated,
+        })
+    }
+
+    /// Return true if `value` matches `pattern`, with all values matching if it is `None`.
+    fn matches(pattern: Option<&ValuePattern>, value: &BStr) -> bool {
+        match pattern {
+            Some(pattern) => pattern.regex.is_match(value) != pattern.negated,
+            None => true,
+        }
+    }
+}
+
+fn matching_indices(values: &[Cow<'_, BStr>], pattern: Option<&ValuePattern>) -> Vec<usize> {
+    values
+        .iter()
+        .enumerate()
+        .filter(|(_, value)| ValuePattern::matches(pattern, value.as_ref()))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+fn parse_key(input: &str) -> Result<git::config::parse::Key<'_>> {
+    git::config::parse::key(input).ok_or_else(|| {
+        anyhow!(
+            "Invalid key {:?}, expected 'section.key' or 'section.subsection.key'",
+            input
+        )
+    })
+}
+
+fn key_name(key: &git::config::parse::Key<'_>) -> String {
+    match key.subsection_name {
+        Some(subsection) => format!("{}.{}.{}", key.section_name, subsection, key.value_name),
+        None => format!("{}.{}", key.section_name, key.value_name),
+    }
+}
+
+fn section_key_prefix(section: &git::config::file::Section<'_>) -> BString {
+    let header = section.header();
+    let mut prefix = header.name().to_ascii_lowercase();
+    if let Some(subsection) = header.subsection_name() {
+        prefix.push(b'.');
+        prefix.extend_from_slice(subsection);
+    }
+    prefix.into()
+}
+
+/// Load the configuration file in `scope` without resolving includes, or an empty one if it doesn't exist yet.
+fn load_for_writing(repo: &git::Repository, scope: Scope) -> Result<(git::config::File<'static>, PathBuf)> {
+    let (source, path) = scope.writable_location(repo)?;
+    let file = if path.is_file() {
+        git::config::File::from_path_no_includes(path.clone(), source)
+            .with_context(|| format!("Could not read configuration file at {:?}", path))?
+    } else {
+        git::config::File::new(git::config::file::Metadata::from(source).at(path.clone()))
+    };
+    Ok((file, path))
+}
+
+fn write_back(file: &git::config::File<'static>, path: PathBuf) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty() && !dir.is_dir()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut lock = git::lock::File::acquire_to_update_resource(&path, git::lock::acquire::Fail::Immediately, None)
+        .with_context(|| format!("Could not lock {:?} for writing", path))?;
+    file.write_to(&mut lock)?;
+    lock.commit().map_err(|err| err.error)?;
+    Ok(())
+}
+
+fn write_origin(
+    meta: &git::config::file::Metadata,
+    show_origin: bool,
+    show_scope: bool,
+    out: &mut impl std::io::Write,
+) -> std::io::Result<()> {
+    use git::config::Source;
+    if show_scope {
+        let scope = match meta.source {
+            Source::System => "system",
+            Source::Git | Source::User => "global",
+            Source::Local => "local",
+            Source::Worktree => "worktree",
+            Source::Env | Source::Cli | Source::Api => "command",
+        };
+        write!(out, "{}\t", scope)?;
+    }
+    if show_origin {
+        match (&meta.path, meta.source) {
+            (Some(path), _) => write!(out, "file:{}\t", path.display())?,
+            (None, Source::Env | Source::Cli) => write!(out, "command line:\t")?,
+            (None, _) => write!(out, "memory:\t")?,
+        }
+    }
+    Ok(())
+}
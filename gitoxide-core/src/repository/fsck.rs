@@ -0,0 +1,112 @@
+use std::{io, str::FromStr};
+
+use anyhow::bail;
+use git_repository as git;
+use git_repository::check::{Action, Outcome};
+
+use crate::OutputFormat;
+
+/// The least severe findings to print.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn variants() -> &'static [&'static str] {
+        &["info", "warning", "error"]
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s_lc = s.to_ascii_lowercase();
+        Ok(match s_lc.as_str() {
+            "info" => Severity::Info,
+            "warning" => Severity::Warning,
+            "error" => Severity::Error,
+            _ => return Err(format!("Invalid severity: '{}'", s)),
+        })
+    }
+}
+
+impl From<Severity> for git::check::Severity {
+    fn from(v: Severity) -> Self {
+        match v {
+            Severity::Info => git::check::Severity::Info,
+            Severity::Warning => git::check::Severity::Warning,
+            Severity::Error => git::check::Severity::Error,
+        }
+    }
+}
+
+pub struct Options {
+    pub format: OutputFormat,
+    /// Findings less severe than this aren't printed.
+    pub min_severity: Severity,
+    /// If false, don't recompute the hash of each object.
+    pub verify_hashes: bool,
+    /// If false, don't check connectivity and don't report dangling objects.
+    pub connectivity: bool,
+    /// If true, print all unreachable objects, not only dangling ones.
+    pub unreachable: bool,
+}
+
+/// Check the integrity of `repo` and print all findings at least as severe as `min_severity` to `out`, one per line.
+pub fn check(
+    repo: git::Repository,
+    mut out: impl io::Write,
+    mut err: impl io::Write,
+    Options {
+        format,
+        min_severity,
+        verify_hashes,
+        connectivity,
+        unreachable,
+    }: Options,
+) -> anyhow::Result<Outcome> {
+    if format != OutputFormat::Human {
+        bail!("JSON output isn't implemented yet");
+    }
+    let min_severity = git::check::Severity::from(min_severity);
+    let mut write_err = None;
+    let outcome = repo.check(
+        git::check::Options {
+            verify_hashes,
+            connectivity,
+            unreachable,
+            ..Default::default()
+        },
+        |finding| {
+            let severity = finding.severity();
+            if severity < min_severity {
+                return Action::Continue;
+            }
+            let severity = match severity {
+                git::check::Severity::Info => "info",
+                git::check::Severity::Warning => "warning",
+                git::check::Severity::Error => "error",
+            };
+            match writeln!(out, "{}: {}", severity, finding) {
+                Ok(()) => Action::Continue,
+                Err(err) => {
+                    write_err = Some(err);
+                    Action::Cancel
+                }
+            }
+        },
+    )?;
+    if let Some(err) = write_err {
+        return Err(err.into());
+    }
+    writeln!(
+        err,
+        "Checked {} objects and {} references: {} errors, {} warnings",
+        outcome.objects, outcome.references, outcome.errors, outcome.warnings
+    )?;
+    Ok(outcome)
+}
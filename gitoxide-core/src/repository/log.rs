@@ -0,0 +1,471 @@
+use std::{collections::HashMap, io, path::PathBuf};
+
+use anyhow::{bail, Context};
+use git_repository as git;
+use git_repository::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    prelude::{FindExt, ObjectIdExt},
+};
+
+use crate::OutputFormat;
+
+pub struct Options {
+    pub format: OutputFormat,
+    /// A template with placeholders like `%H` or `%s` to print for each commit, or the default multi-line format if unset.
+    pub pretty: Option<String>,
+    /// Only follow the first parent of merge commits.
+    pub first_parent: bool,
+    /// How to format dates.
+    pub date: DateFormat,
+    /// If true, print the paths changed by each commit compared to its first parent.
+    pub stat: bool,
+    /// Print at most this many commits.
+    pub max_count: Option<usize>,
+    /// If not empty, only print commits that change a file at or below one of these paths, relative to the current directory.
+    pub paths: Vec<PathBuf>,
+    /// If true, print paths relative to the work tree instead of the current directory.
+    pub full_paths: bool,
+}
+
+/// The way dates are printed, similar to `git log --date`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateFormat {
+    Default,
+    Short,
+    Iso,
+    IsoStrict,
+    Rfc,
+    Unix,
+    Raw,
+}
+
+impl std::str::FromStr for DateFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "default" => DateFormat::Default,
+            "short" => DateFormat::Short,
+            "iso" | "iso8601" => DateFormat::Iso,
+            "iso-strict" | "iso8601-strict" => DateFormat::IsoStrict,
+            "rfc" | "rfc2822" => DateFormat::Rfc,
+            "unix" => DateFormat::Unix,
+            "raw" => DateFormat::Raw,
+            unknown => return Err(format!("Unknown date format: {:?}", unknown)),
+        })
+    }
+}
+
+impl DateFormat {
+    fn format(&self, time: git::actor::Time) -> String {
+        use git::date::time::format;
+        match self {
+            DateFormat::Default => time.format(format::DEFAULT),
+            DateFormat::Short => time.format(format::SHORT),
+            DateFormat::Iso => time.format(format::ISO8601),
+            DateFormat::IsoStrict => time.format(format::ISO8601_STRICT),
+            DateFormat::Rfc => time.format(format::RFC2822),
+            DateFormat::Unix => time.format(format::UNIX),
+            DateFormat::Raw => time.format(format::RAW),
+        }
+    }
+}
+
+/// Print all commits reachable from `rev_spec`, or from `HEAD` if unset, newest first.
+///
+/// Changed paths are relative to the current directory, unless `full_paths` is set.
+pub fn log(
+    mut repo: git::Repository,
+    rev_spec: Option<&str>,
+    mut out: impl io::Write,
+    Options {
+        format,
+        pretty,
+        first_parent,
+        date,
+        stat,
+        max_count,
+        paths,
+        full_paths,
+    }: Options,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("JSON output isn't implemented yet");
+    }
+    repo.object_cache_size_if_unset(4 * 1024 * 1024);
+    let tip = match rev_spec {
+        Some(spec) => {
+            repo.rev_parse(spec)?
+                .single()
+                .context("Need single revision revspec")?
+                .object()?
+                .try_into_commit()?
+                .id
+        }
+        None => repo.head_commit()?.id,
+    };
+    let template = pretty.as_deref().map(parse_template).transpose()?;
+    let decorations = decorations(&repo)?;
+    let paths = repo_relative_paths(&repo, paths)?;
+    let prefix = super::display_prefix(&repo, full_paths);
+
+    let mut walk = tip
+        .attach(&repo)
+        .ancestors()
+        .sorting(git::traverse::commit::Sorting::ByCommitTimeNewestFirst);
+    if first_parent {
+        walk = walk.first_parent_only();
+    }
+    let mut buf = Vec::new();
+    let mut diff_state = git::diff::tree::State::default();
+    let mut shown = 0;
+    for id in walk.all()? {
+        if shown == max_count.unwrap_or(usize::MAX) {
+            break;
+        }
+        let commit = id?.object()?.try_into_commit()?;
+        let decoded = commit.decode()?;
+        let changes = if stat || !paths.is_empty() {
+            changed_paths(&repo, &decoded, &mut diff_state)?
+        } else {
+            Vec::new()
+        };
+        if !paths.is_empty()
+            && !changes
+                .iter()
+                .any(|(_, changed)| paths.iter().any(|path| is_at_or_below(changed.as_ref(), path.as_ref())))
+        {
+            continue;
+        }
+        buf.clear();
+        match template.as_ref() {
+            Some(template) => {
+                write_template(template, &commit.id, &decoded, &decorations, date, &repo, &mut buf)?;
+                buf.push(b'\n');
+            }
+            None => {
+                if shown != 0 {
+                    buf.push(b'\n');
+                }
+                write_default(&commit.id, &decoded, &decorations, date, &mut buf)?;
+            }
+        }
+        if stat {
+            write_stat(&changes, prefix.as_ref().map(AsRef::as_ref), &mut buf)?;
+        }
+        out.write_all(&buf)?;
+        shown += 1;
+    }
+    Ok(())
+}
+
+/// A part of a `--format` template.
+enum Token {
+    Literal(BString),
+    Placeholder(Placeholder),
+}
+
+#[derive(Clone, Copy)]
+enum Placeholder {
+    Hash,
+    ShortHash,
+    TreeHash,
+    ShortTreeHash,
+    ParentHashes,
+    ShortParentHashes,
+    AuthorName,
+    AuthorEmail,
+    AuthorDate,
+    CommitterName,
+    CommitterEmail,
+    CommitterDate,
+    Subject,
+    Body,
+    Decorations,
+}
+
+fn parse_template(template: &str) -> anyhow::Result<Vec<Token>> {
+    use Placeholder::*;
+    let mut tokens = Vec::new();
+    let mut literal = BString::default();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            literal.push_char(c);
+            continue;
+        }
+        let mut next = || {
+            chars
+                .next()
+                .context("Template must not end with an unfinished '%' placeholder")
+        };
+        let placeholder = match next()? {
+            '%' => {
+                literal.push(b'%');
+                continue;
+            }
+            'n' => {
+                literal.push(b'\n');
+                continue;
+            }
+            'H' => Hash,
+            'h' => ShortHash,
+            'T' => TreeHash,
+            't' => ShortTreeHash,
+            'P' => ParentHashes,
+            'p' => ShortParentHashes,
+            's' => Subject,
+            'b' => Body,
+            'd' => Decorations,
+            c @ ('a' | 'c') => {
+                let is_author = c == 'a';
+                match (next()?, is_author) {
+                    ('n', true) => AuthorName,
+                    ('e', true) => AuthorEmail,
+                    ('d', true) => AuthorDate,
+                    ('n', false) => CommitterName,
+                    ('e', false) => CommitterEmail,
+                    ('d', false) => CommitterDate,
+                    (unknown, _) => bail!("Unknown placeholder '%{}{}'", c, unknown),
+                }
+            }
+            unknown => bail!("Unknown placeholder '%{}'", unknown),
+        };
+        if !literal.is_empty() {
+            tokens.push(Token::Literal(std::mem::take(&mut literal)));
+        }
+        tokens.push(Token::Placeholder(placeholder));
+    }
+    if !literal.is_empty() {
+        tokens.push(Token::Literal(literal));
+    }
+    Ok(tokens)
+}
+
+fn write_template(
+    template: &[Token],
+    id: &git::oid,
+    commit: &git::objs::CommitRef<'_>,
+    decorations: &Decorations,
+    date: DateFormat,
+    repo: &git::Repository,
+    out: &mut Vec<u8>,
+) -> anyhow::Result<()> {
+    use Placeholder::*;
+    let short = |id: git::ObjectId| -> anyhow::Result<String> { Ok(id.attach(repo).shorten()?.to_string()) };
+    for token in template {
+        let placeholder = match token {
+            Token::Literal(text) => {
+                out.extend_from_slice(text);
+                continue;
+            }
+            Token::Placeholder(placeholder) => placeholder,
+        };
+        match placeholder {
+            Hash => out.extend_from_slice(id.to_hex().to_string().as_bytes()),
+            ShortHash => out.extend_from_slice(short(id.to_owned())?.as_bytes()),
+            TreeHash => out.extend_from_slice(commit.tree().to_hex().to_string().as_bytes()),
+            ShortTreeHash => out.extend_from_slice(short(commit.tree())?.as_bytes()),
+            ParentHashes | ShortParentHashes => {
+                for (idx, parent) in commit.parents().enumerate() {
+                    if idx != 0 {
+                        out.push(b' ');
+                    }
+                    if matches!(placeholder, ParentHashes) {
+                        out.extend_from_slice(parent.to_hex().to_string().as_bytes());
+                    } else {
+                        out.extend_from_slice(short(parent)?.as_bytes());
+                    }
+                }
+            }
+            AuthorName => out.extend_from_slice(commit.author.name),
+            AuthorEmail => out.extend_from_slice(commit.author.email),
+            AuthorDate => out.extend_from_slice(date.format(commit.author.time).as_bytes()),
+            CommitterName => out.extend_from_slice(commit.committer.name),
+            CommitterEmail => out.extend_from_slice(commit.committer.email),
+            CommitterDate => out.extend_from_slice(date.format(commit.committer.time).as_bytes()),
+            Subject => out.extend_from_slice(&commit.message().summary()),
+            Body => {
+                if let Some(body) = commit.message().body {
+                    out.extend_from_slice(body);
+                }
+            }
+            Decorations => {
+                if let Some(names) = decorations.get(id) {
+                    out.extend_from_slice(b" (");
+                    out.extend_from_slice(names.as_slice());
+                    out.push(b')');
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_default(
+    id: &git::oid,
+    commit: &git::objs::CommitRef<'_>,
+    decorations: &Decorations,
+    date: DateFormat,
+    out: &mut Vec<u8>,
+) -> io::Result<()> {
+    use std::io::Write;
+    write!(out, "commit {}", id)?;
+    if let Some(names) = decorations.get(id) {
+        write!(out, " ({})", names)?;
+    }
+    writeln!(out)?;
+    let parents: Vec<_> = commit.parents().collect();
+    if parents.len() > 1 {
+        write!(out, "Merge:")?;
+        for parent in parents {
+            write!(out, " {}", parent.to_hex_with_len(7))?;
+        }
+        writeln!(out)?;
+    }
+    writeln!(out, "Author: {} <{}>", commit.author.name, commit.author.email)?;
+    writeln!(out, "Date:   {}", date.format(commit.author.time))?;
+    writeln!(out)?;
+    for line in commit.message.trim_end().lines() {
+        if line.is_empty() {
+            writeln!(out)?;
+        } else {
+            writeln!(out, "    {}", line.as_bstr())?;
+        }
+    }
+    Ok(())
+}
+
+/// Return the paths that changed between the tree of `commit` and the one of its first parent, or all paths for root commits,
+/// along with the kind of change.
+fn changed_paths(
+    repo: &git::Repository,
+    commit: &git::objs::CommitRef<'_>,
+    state: &mut git::diff::tree::State,
+) -> anyhow::Result<Vec<(&'static str, BString)>> {
+    let mut parent_tree_buf = Vec::new();
+    let mut tree_buf = Vec::new();
+    let parent_tree = match commit.parents().next() {
+        Some(parent) => {
+            let tree_id = repo
+                .objects
+                .find_commit_iter(parent, &mut parent_tree_buf)?
+                .tree_id()
+                .context("BUG: commit without tree")?;
+            Some(repo.objects.find_tree_iter(tree_id, &mut parent_tree_buf)?)
+        }
+        None => None,
+    };
+    let tree = repo.objects.find_tree_iter(commit.tree(), &mut tree_buf)?;
+    let mut recorder = git::diff::tree::Recorder::default();
+    git::diff::tree::Changes::from(parent_tree).needed_to_obtain(
+        tree,
+        state,
+        |oid, buf| repo.objects.find_tree_iter(oid, buf),
+        &mut recorder,
+    )?;
+
+    use git::diff::tree::recorder::Change;
+    Ok(recorder
+        .records
+        .into_iter()
+        .filter_map(|change| match change {
+            Change::Addition { entry_mode, path, .. } if !entry_mode.is_tree() => Some(("added", path)),
+            Change::Deletion { entry_mode, path, .. } if !entry_mode.is_tree() => Some(("deleted", path)),
+            Change::Modification { entry_mode, path, .. } if !entry_mode.is_tree() => Some(("modified", path)),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Write `changes` as obtained by [`changed_paths()`], with paths relative to `prefix` if set.
+///
+/// Note that the amount of changed lines isn't shown as there is no line-diff yet.
+fn write_stat(changes: &[(&'static str, BString)], prefix: Option<&BStr>, out: &mut Vec<u8>) -> anyhow::Result<()> {
+    use std::io::Write;
+    let changes: Vec<_> = changes
+        .iter()
+        .map(|(kind, path)| {
+            let path = match prefix {
+                Some(prefix) => git::path::relativize_with_prefix(path.as_ref(), prefix),
+                None => path.as_bstr().into(),
+            };
+            (kind, path)
+        })
+        .collect();
+    let width = changes.iter().map(|(_, path)| path.len()).max().unwrap_or(0);
+    writeln!(out)?;
+    for (kind, path) in &changes {
+        writeln!(out, " {:width$} | {}", path.to_str_lossy(), kind, width = width)?;
+    }
+    writeln!(
+        out,
+        " {} file{} changed",
+        changes.len(),
+        if changes.len() == 1 { "" } else { "s" }
+    )?;
+    Ok(())
+}
+
+/// Turn `paths` relative to the current directory into paths relative to the work tree of `repo`, or take them as is
+/// in bare repositories.
+fn repo_relative_paths(repo: &git::Repository, paths: Vec<PathBuf>) -> anyhow::Result<Vec<BString>> {
+    match repo.work_dir() {
+        Some(work_dir) => {
+            let cwd = std::env::current_dir()?;
+            paths
+                .iter()
+                .map(|path| Ok(git::path::to_repo_relative(path, work_dir, &cwd)?))
+                .collect()
+        }
+        None => paths
+            .into_iter()
+            .map(|path| Ok(git::path::to_unix_separators_on_windows(git::path::try_into_bstr(path)?).into_owned()))
+            .collect(),
+    }
+}
+
+/// Return true if `path` is `dir` itself or located below it, with an empty `dir` containing all paths.
+fn is_at_or_below(path: &BStr, dir: &BStr) -> bool {
+    dir.is_empty() || (path.starts_with(dir) && (path.len() == dir.len() || path[dir.len()] == b'/'))
+}
+
+/// The names of all references pointing to a commit, formatted like `HEAD -> main, tag: v1.0`.
+type Decorations = HashMap<git::ObjectId, BString>;
+
+fn decorations(repo: &git::Repository) -> anyhow::Result<Decorations> {
+    let mut out = Decorations::new();
+    let mut add = |id: git::ObjectId, name: &BStr| {
+        let names = out.entry(id).or_default();
+        if !names.is_empty() {
+            names.push_str(", ");
+        }
+        names.push_str(name);
+    };
+    let head = repo.head()?;
+    let head_branch = head.referent_name().map(|name| name.to_owned());
+    if let Some(id) = head.id() {
+        match head_branch.as_ref() {
+            Some(branch) => add(id.detach(), format!("HEAD -> {}", branch.shorten()).as_str().into()),
+            None => add(id.detach(), "HEAD".into()),
+        }
+    }
+    for reference in repo.references()?.all()? {
+        let mut reference = match reference {
+            Ok(reference) => reference,
+            Err(_) => continue,
+        };
+        let name = reference.name().to_owned();
+        let id = match reference.peel_to_id_in_place() {
+            Ok(id) => id.detach(),
+            Err(_) => continue,
+        };
+        match name.category() {
+            Some(git::refs::Category::Tag) => add(id, format!("tag: {}", name.shorten()).as_str().into()),
+            Some(git::refs::Category::LocalBranch) if head_branch.as_ref() == Some(&name) => {}
+            Some(git::refs::Category::LocalBranch | git::refs::Category::RemoteBranch) => add(id, name.shorten()),
+            _ => {}
+        }
+    }
+    Ok(out)
+}
@@ -1,5 +1,6 @@
 use std::io;
 
+use anyhow::bail;
 use git_repository as git;
 #[cfg(feature = "serde1")]
 use git_repository::mailmap::Entry;
@@ -44,10 +45,55 @@ pub fn entries(
     }
 
     #[cfg(feature = "serde1")]
-    serde_json::to_writer_pretty(
+    crate::write_json(
         out,
         &mailmap.entries().into_iter().map(JsonEntry::from).collect::<Vec<_>>(),
     )?;
 
     Ok(())
 }
+
+pub fn check(
+    repo: git::Repository,
+    contacts: impl IntoIterator<Item = String>,
+    format: OutputFormat,
+    mut out: impl io::Write,
+    mut err: impl io::Write,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("JSON output isn't implemented yet");
+    }
+
+    let mut mailmap = git::mailmap::Snapshot::default();
+    if let Err(e) = repo.open_mailmap_into(&mut mailmap) {
+        writeln!(err, "Error while loading mailmap, the first error is: {}", e).ok();
+    }
+
+    for contact in contacts {
+        let (name, email) = parse_contact(&contact)?;
+        let resolved = mailmap.try_resolve_ref(git::actor::SignatureRef {
+            name: name.into(),
+            email: email.into(),
+            time: Default::default(),
+        });
+        let name = resolved.as_ref().and_then(|r| r.name).unwrap_or_else(|| name.into());
+        let email = resolved.as_ref().and_then(|r| r.email).unwrap_or_else(|| email.into());
+        if name.is_empty() {
+            writeln!(out, "<{}>", email)?;
+        } else {
+            writeln!(out, "{} <{}>", name, email)?;
+        }
+    }
+    Ok(())
+}
+
+/// Split `contact` in the form of `Name <email>`, `<email>` or `email` into name and email.
+fn parse_contact(contact: &str) -> anyhow::Result<(&str, &str)> {
+    Ok(match contact.find('<') {
+        Some(start) => match contact[start + 1..].find('>') {
+            Some(end) => (contact[..start].trim(), contact[start + 1..][..end].trim()),
+            None => bail!("Contact {:?} lacks the closing '>' of its email", contact),
+        },
+        None => ("", contact.trim()),
+    })
+}
@@ -9,17 +9,37 @@ pub fn init(directory: Option<PathBuf>) -> Result<git::discover::repository::Pat
         git::create::Options {
             bare: false,
             fs_capabilities: None,
+            object_hash: git::hash::Kind::Sha1,
         },
     )
     .with_context(|| "Repository initialization failed")
 }
 
+/// Return the location of the current directory within the work tree of `repo` to display paths relative to, like `git` does
+/// when invoked from a sub-directory, or `None` if `full_paths` is set or the current directory isn't inside of the work tree.
+fn display_prefix(repo: &git::Repository, full_paths: bool) -> Option<git::bstr::BString> {
+    if full_paths {
+        return None;
+    }
+    let prefix = repo.prefix()?.ok()?;
+    let prefix = git::path::into_bstr(prefix);
+    Some(git::path::to_unix_separators_on_windows(prefix).into_owned())
+}
+
+pub mod apply;
+pub mod archive;
+#[cfg(feature = "blocking-client")]
+pub mod clone;
 pub mod commit;
 pub mod config;
 pub mod exclude;
+pub mod fsck;
+pub mod log;
 pub mod mailmap;
 pub mod odb;
+pub mod refs;
 pub mod remote;
 pub mod revision;
+pub mod status;
 pub mod tree;
 pub mod verify;
@@ -42,7 +42,7 @@ pub fn info(
 
     #[cfg(feature = "serde1")]
     {
-        serde_json::to_writer_pretty(out, &stats)?;
+        crate::write_json(out, &stats)?;
     }
 
     Ok(())
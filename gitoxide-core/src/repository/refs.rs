@@ -0,0 +1,209 @@
+pub mod edit {
+    use std::{convert::TryInto, io};
+
+    use anyhow::{bail, Context};
+    use git_repository as git;
+    use git_repository::{
+        bstr::{BString, ByteSlice},
+        refs::{
+            transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+            Target,
+        },
+    };
+
+    use crate::OutputFormat;
+
+    pub struct Options {
+        pub format: OutputFormat,
+        /// If set, check that all edits can be applied but don't change any reference.
+        pub dry_run: bool,
+        /// The message to write into the reference logs.
+        pub message: Option<String>,
+    }
+
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+    struct Outcome {
+        name: String,
+        action: &'static str,
+        previous: Option<String>,
+        new: Option<String>,
+    }
+
+    /// Read edits line by line from `input` in the format used by `git update-ref --stdin`, and apply them all in a single
+    /// transaction so that either all or none of them take effect.
+    ///
+    /// Supported lines are `update <ref> <new> [<old>]`, `create <ref> <new>` and `delete <ref> [<old>]`, where
+    /// `<new>` and `<old>` are revision specs or hexadecimal object ids, and the null id as `<old>` means the reference
+    /// must not exist yet.
+    pub fn apply(
+        repo: git::Repository,
+        input: impl io::BufRead,
+        mut out: impl io::Write,
+        Options {
+            format,
+            dry_run,
+            message,
+        }: Options,
+    ) -> anyhow::Result<()> {
+        let message: BString = message.unwrap_or_default().into();
+        let mut edits = Vec::new();
+        for (line_number, line) in input.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let edit = parse_line(&repo, line, message.clone())
+                .with_context(|| format!("Could not parse line {}: {:?}", line_number + 1, line))?;
+            edits.push(edit);
+        }
+
+        let outcomes: Vec<_> = if dry_run {
+            let outcomes = edits
+                .iter()
+                .map(|edit| {
+                    let previous = repo
+                        .try_find_reference(edit.name.as_ref())?
+                        .map(|r| target_to_string(&r.inner.target));
+                    Ok(outcome(edit, previous))
+                })
+                .collect::<anyhow::Result<_>>()?;
+            // Dropping the prepared transaction releases all locks without changing anything.
+            repo.refs
+                .transaction()
+                .prepare(edits, git::lock::acquire::Fail::Immediately)?;
+            outcomes
+        } else {
+            repo.edit_references(
+                edits,
+                git::lock::acquire::Fail::Immediately,
+                repo.committer_or_default(),
+            )?
+            .iter()
+            .map(|edit| {
+                let previous = match &edit.change {
+                    Change::Update { expected, .. } | Change::Delete { expected, .. } => match expected {
+                        PreviousValue::MustExistAndMatch(target) => Some(target_to_string(target)),
+                        _ => None,
+                    },
+                };
+                outcome(edit, previous)
+            })
+            .collect()
+        };
+
+        match format {
+            OutputFormat::Human => {
+                for Outcome {
+                    name,
+                    action,
+                    previous,
+                    new,
+                } in outcomes
+                {
+                    let action = if dry_run {
+                        format!("would {}", action)
+                    } else {
+                        format!("{}d", action)
+                    };
+                    let previous = previous.as_deref().unwrap_or("none");
+                    match new {
+                        Some(new) => writeln!(out, "{} {}: {} -> {}", action, name, previous, new)?,
+                        None => writeln!(out, "{} {}: {}", action, name, previous)?,
+                    }
+                }
+            }
+            #[cfg(feature = "serde1")]
+            OutputFormat::Json => crate::write_json(out, &outcomes)?,
+        }
+        Ok(())
+    }
+
+    fn outcome(edit: &RefEdit, previous: Option<String>) -> Outcome {
+        let (action, new) = match &edit.change {
+            Change::Update { new, .. } => ("update", Some(target_to_string(new))),
+            Change::Delete { .. } => ("delete", None),
+        };
+        Outcome {
+            name: edit.name.as_bstr().to_str_lossy().into_owned(),
+            action,
+            previous,
+            new,
+        }
+    }
+
+    fn target_to_string(target: &Target) -> String {
+        match target {
+            Target::Peeled(id) => id.to_string(),
+            Target::Symbolic(name) => name.as_bstr().to_str_lossy().into_owned(),
+        }
+    }
+
+    fn parse_line(repo: &git::Repository, line: &str, message: BString) -> anyhow::Result<RefEdit> {
+        let mut tokens = line.split_whitespace();
+        let command = tokens.next().expect("non-empty line");
+        let name = tokens.next().context("A reference name is required")?;
+        let name: git::refs::FullName = name.try_into()?;
+        let args: Vec<_> = tokens.collect();
+        let log = LogChange {
+            mode: RefLog::AndReference,
+            force_create_reflog: false,
+            message,
+        };
+        let change = match (command, args.as_slice()) {
+            ("update", [new]) => Change::Update {
+                log,
+                expected: PreviousValue::Any,
+                new: Target::Peeled(resolve(repo, new)?.context("The new value must not be the null id")?),
+            },
+            ("update", [new, old]) => Change::Update {
+                log,
+                expected: match resolve(repo, old)? {
+                    Some(old) => PreviousValue::MustExistAndMatch(Target::Peeled(old)),
+                    None => PreviousValue::MustNotExist,
+                },
+                new: Target::Peeled(resolve(repo, new)?.context("The new value must not be the null id")?),
+            },
+            ("create", [new]) => Change::Update {
+                log,
+                expected: PreviousValue::MustNotExist,
+                new: Target::Peeled(resolve(repo, new)?.context("The new value must not be the null id")?),
+            },
+            ("delete", []) => Change::Delete {
+                expected: PreviousValue::MustExist,
+                log: RefLog::AndReference,
+            },
+            ("delete", [old]) => Change::Delete {
+                expected: PreviousValue::MustExistAndMatch(Target::Peeled(
+                    resolve(repo, old)?.context("The old value of a deleted reference must not be the null id")?,
+                )),
+                log: RefLog::AndReference,
+            },
+            ("update" | "create" | "delete", _) => bail!("Unexpected amount of arguments for '{}'", command),
+            _ => bail!(
+                "Unknown command '{}', expected one of 'update', 'create' or 'delete'",
+                command
+            ),
+        };
+        Ok(RefEdit {
+            change,
+            name,
+            deref: true,
+        })
+    }
+
+    /// Turn `spec` into the object id it names, or `None` if it is the null id.
+    fn resolve(repo: &git::Repository, spec: &str) -> anyhow::Result<Option<git::ObjectId>> {
+        if let Ok(id) = git::ObjectId::from_hex(spec.as_bytes()) {
+            if id.is_null() {
+                return Ok(None);
+            }
+        }
+        Ok(Some(
+            repo.rev_parse(spec)?
+                .single()
+                .with_context(|| format!("Revision '{}' must name a single object", spec))?
+                .detach(),
+        ))
+    }
+}
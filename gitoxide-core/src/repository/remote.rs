@@ -14,6 +14,8 @@ mod net {
             pub format: OutputFormat,
             pub name: Option<String>,
             pub url: Option<git_repository::Url>,
+            /// Only list references matching these filters, see `git::remote::list_refs::Options::filters`.
+            pub filters: Vec<git_repository::bstr::BString>,
         }
 
         pub(crate) use super::print;
@@ -24,7 +26,12 @@ mod net {
         repo: git::Repository,
         mut progress: impl git::Progress,
         out: impl std::io::Write,
-        refs::Context { format, name, url }: refs::Context,
+        refs::Context {
+            format,
+            name,
+            url,
+            filters,
+        }: refs::Context,
     ) -> anyhow::Result<()> {
         use anyhow::Context;
         let remote = match (name, url) {
@@ -46,15 +53,13 @@ mod net {
         let refs = remote
             .connect(git::remote::Direction::Fetch, progress)
             .await?
-            .list_refs()
+            .list_refs_with_options(git::remote::list_refs::Options { filters })
             .await?;
 
         match format {
             OutputFormat::Human => drop(print(out, &refs)),
             #[cfg(feature = "serde1")]
-            OutputFormat::Json => {
-                serde_json::to_writer_pretty(out, &refs.into_iter().map(JsonRef::from).collect::<Vec<_>>())?
-            }
+            OutputFormat::Json => crate::write_json(out, &refs.into_iter().map(JsonRef::from).collect::<Vec<_>>())?,
         };
         Ok(())
     }
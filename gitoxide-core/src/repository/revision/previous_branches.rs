@@ -16,7 +16,7 @@ pub fn function(repo: git::Repository, mut out: impl std::io::Write, format: Out
         }
         #[cfg(feature = "serde1")]
         OutputFormat::Json => {
-            serde_json::to_writer_pretty(&mut out, &branches)?;
+            crate::write_json(&mut out, &branches)?;
         }
     }
     Ok(())
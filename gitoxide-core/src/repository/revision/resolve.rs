@@ -47,7 +47,7 @@ pub(crate) mod function {
                 if explain {
                     anyhow::bail!("Explanations are only for human consumption")
                 }
-                serde_json::to_writer_pretty(
+                crate::write_json(
                     &mut out,
                     &specs
                         .into_iter()
@@ -0,0 +1,64 @@
+use std::io;
+
+use anyhow::bail;
+use git_repository as git;
+use git_repository::status::{Action, Change};
+
+use crate::OutputFormat;
+
+pub struct Options {
+    pub format: OutputFormat,
+    /// If true, print nothing and stop at the first change.
+    pub quiet: bool,
+    /// If true, print paths relative to the work tree instead of the current directory.
+    pub full_paths: bool,
+}
+
+/// Print all changes between `HEAD`, the index and the worktree of `repo` to `out`, and return `true` if there was at least one.
+///
+/// Paths are relative to the current directory, unless `full_paths` is set.
+pub fn show(
+    repo: git::Repository,
+    mut out: impl io::Write,
+    Options {
+        format,
+        quiet,
+        full_paths,
+    }: Options,
+) -> anyhow::Result<bool> {
+    if format != OutputFormat::Human {
+        bail!("JSON output isn't implemented yet");
+    }
+    if quiet {
+        return Ok(repo.is_dirty()?);
+    }
+
+    let prefix = super::display_prefix(&repo, full_paths);
+    let mut is_dirty = false;
+    let mut write_err = None;
+    repo.changes(|change| {
+        is_dirty = true;
+        let status = match change {
+            Change::Staged { .. } => "M ",
+            Change::Conflict { .. } => "UU",
+            Change::IntentToAdd { .. } => " A",
+            Change::Modified { .. } => " M",
+            Change::Removed { .. } => " D",
+        };
+        let path = match prefix.as_ref() {
+            Some(prefix) => git::path::relativize_with_prefix(change.path(), prefix.as_ref()),
+            None => change.path().into(),
+        };
+        match writeln!(out, "{} {}", status, path) {
+            Ok(()) => Action::Continue,
+            Err(err) => {
+                write_err = Some(err);
+                Action::Cancel
+            }
+        }
+    })?;
+    if let Some(err) = write_err {
+        return Err(err.into());
+    }
+    Ok(is_dirty)
+}
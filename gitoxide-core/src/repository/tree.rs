@@ -1,8 +1,11 @@
-use std::{borrow::Cow, io};
+use std::io;
 
-use anyhow::bail;
+use anyhow::Context;
 use git_repository as git;
-use git_repository::{prelude::ObjectIdExt, Tree};
+use git_repository::{
+    bstr::{BStr, BString, ByteSlice},
+    Tree,
+};
 
 use crate::OutputFormat;
 
@@ -17,7 +20,7 @@ mod entries {
     use git_repository as git;
     use git_repository::bstr::{ByteSlice, ByteVec};
 
-    use crate::repository::tree::format_entry;
+    use crate::repository::tree::{matches, may_match_within, Entry};
 
     #[cfg_attr(feature = "serde1", derive(serde::Serialize))]
     #[derive(Default)]
@@ -36,17 +39,23 @@ mod entries {
     pub struct Traverse<'repo, 'a> {
         pub stats: Statistics,
         repo: Option<&'repo git::Repository>,
-        out: Option<&'a mut dyn std::io::Write>,
+        pathspecs: &'a [git::path::Spec],
+        entries: Option<&'a mut Vec<Entry>>,
         path: BString,
         path_deque: VecDeque<BString>,
     }
 
     impl<'repo, 'a> Traverse<'repo, 'a> {
-        pub fn new(repo: Option<&'repo git::Repository>, out: Option<&'a mut dyn std::io::Write>) -> Self {
+        pub fn new(
+            repo: Option<&'repo git::Repository>,
+            pathspecs: &'a [git::path::Spec],
+            entries: Option<&'a mut Vec<Entry>>,
+        ) -> Self {
             Traverse {
                 stats: Default::default(),
                 repo,
-                out,
+                pathspecs,
+                entries,
                 path: BString::default(),
                 path_deque: VecDeque::new(),
             }
@@ -87,20 +96,26 @@ mod entries {
         }
 
         fn visit_tree(&mut self, _entry: &EntryRef<'_>) -> Action {
+            if !may_match_within(self.pathspecs, self.path.as_bstr()) {
+                return Action::Skip;
+            }
             self.stats.num_trees += 1;
             Action::Continue
         }
 
         fn visit_nontree(&mut self, entry: &EntryRef<'_>) -> Action {
             use git::objs::tree::EntryMode::*;
+            if !matches(self.pathspecs, self.path.as_bstr()) {
+                return Action::Continue;
+            }
             let size = self
                 .repo
-                .and_then(|repo| repo.find_object(entry.oid).map(|o| o.data.len()).ok());
-            if let Some(out) = &mut self.out {
-                format_entry(out, entry, self.path.as_bstr(), size).ok();
+                .and_then(|repo| repo.find_header(entry.oid).map(|h| h.size()).ok());
+            if let Some(entries) = &mut self.entries {
+                entries.push(Entry::new(entry, self.path.clone(), size));
             }
             if let Some(size) = size {
-                self.stats.num_bytes += size as u64;
+                self.stats.num_bytes += size;
             }
 
             match entry.mode {
@@ -115,6 +130,61 @@ mod entries {
     }
 }
 
+/// A single entry of a listed tree.
+pub struct Entry {
+    pub mode: git::objs::tree::EntryMode,
+    pub id: git::ObjectId,
+    /// The size of the object, if it was requested and is a blob.
+    pub size: Option<u64>,
+    /// The path of the entry relative to the listed tree.
+    pub path: BString,
+}
+
+impl Entry {
+    fn new(entry: &git::objs::tree::EntryRef<'_>, path: BString, size: Option<u64>) -> Self {
+        Entry {
+            mode: entry.mode,
+            id: entry.oid.to_owned(),
+            size,
+            path,
+        }
+    }
+
+    /// The name of the kind of object the entry points to, like `git ls-tree` would print it.
+    fn kind(&self) -> &'static str {
+        use git::objs::tree::EntryMode::*;
+        match self.mode {
+            Tree => "tree",
+            Commit => "commit",
+            Blob | BlobExecutable | Link => "blob",
+        }
+    }
+}
+
+#[cfg(feature = "serde1")]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+struct JsonEntry {
+    mode: String,
+    kind: &'static str,
+    id: String,
+    #[cfg_attr(feature = "serde1", serde(skip_serializing_if = "Option::is_none"))]
+    size: Option<u64>,
+    path: String,
+}
+
+#[cfg(feature = "serde1")]
+impl From<&Entry> for JsonEntry {
+    fn from(v: &Entry) -> Self {
+        JsonEntry {
+            mode: format!("{:06o}", v.mode as u16),
+            kind: v.kind(),
+            id: v.id.to_string(),
+            size: v.size,
+            path: v.path.to_str_lossy().into_owned(),
+        }
+    }
+}
+
 #[cfg_attr(not(feature = "serde1"), allow(unused_variables))]
 pub fn info(
     repo: git::Repository,
@@ -130,81 +200,107 @@ pub fn info(
 
     let tree = treeish_to_tree(treeish, &repo)?;
 
-    let mut delegate = entries::Traverse::new(extended.then(|| &repo), None);
+    let mut delegate = entries::Traverse::new(extended.then(|| &repo), &[], None);
     tree.traverse().breadthfirst(&mut delegate)?;
 
     #[cfg(feature = "serde1")]
     {
         delegate.stats.bytes = extended.then(|| delegate.stats.num_bytes);
-        serde_json::to_writer_pretty(out, &delegate.stats)?;
+        crate::write_json(out, &delegate.stats)?;
     }
 
     Ok(())
 }
 
+/// List the entries of `treeish`, or of the tree at `HEAD`, similar to `git ls-tree`, with the size of blobs if `extended` is set.
+///
+/// If `recursive` is set, the entries of all subtrees are listed instead of the subtrees themselves, as with `git ls-tree -r`.
+/// Only entries matching one of the given `pathspecs` are listed, or all entries if there are none.
 pub fn entries(
     repo: git::Repository,
     treeish: Option<&str>,
     recursive: bool,
     extended: bool,
+    pathspecs: Vec<git::path::Spec>,
     format: OutputFormat,
     mut out: impl io::Write,
 ) -> anyhow::Result<()> {
-    if format != OutputFormat::Human {
-        bail!("Only human output format is supported at the moment");
-    }
-
     let tree = treeish_to_tree(treeish, &repo)?;
 
+    let mut entries = Vec::new();
     if recursive {
-        let mut delegate = entries::Traverse::new(extended.then(|| &repo), Some(&mut out));
+        let mut delegate = entries::Traverse::new(extended.then(|| &repo), &pathspecs, Some(&mut entries));
         tree.traverse().breadthfirst(&mut delegate)?;
+        // Sorting full paths yields the same order as a depth-first traversal.
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
     } else {
         for entry in tree.iter() {
             let entry = entry?;
-            format_entry(
-                &mut out,
-                &entry.inner,
-                entry.inner.filename,
-                extended
-                    .then(|| entry.id().object().map(|o| o.data.len()))
-                    .transpose()?,
-            )?;
+            if !matches(&pathspecs, entry.inner.filename) {
+                continue;
+            }
+            let size =
+                (extended && !entry.inner.mode.is_tree() && entry.inner.mode != git::objs::tree::EntryMode::Commit)
+                    .then(|| repo.find_header(entry.inner.oid).map(|h| h.size()))
+                    .transpose()?;
+            entries.push(Entry::new(&entry.inner, entry.inner.filename.to_owned(), size));
         }
     }
 
+    match format {
+        OutputFormat::Human => {
+            for entry in &entries {
+                format_entry(&mut out, entry, extended)?;
+            }
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => crate::write_json(&mut out, &entries.iter().map(JsonEntry::from).collect::<Vec<_>>())?,
+    }
     Ok(())
 }
 
 fn treeish_to_tree<'repo>(treeish: Option<&str>, repo: &'repo git::Repository) -> anyhow::Result<Tree<'repo>> {
     Ok(match treeish {
-        Some(hex) => git::hash::ObjectId::from_hex(hex.as_bytes())
-            .map(|id| id.attach(repo))?
+        Some(spec) => repo
+            .rev_parse(spec)?
+            .single()
+            .context("Need single revision revspec")?
             .object()?
-            .try_into_tree()?,
+            .peel_to_kind(git::object::Kind::Tree)?
+            .into_tree(),
         None => repo.head()?.peel_to_commit_in_place()?.tree()?,
     })
 }
 
-fn format_entry(
-    mut out: impl io::Write,
-    entry: &git::objs::tree::EntryRef<'_>,
-    filename: &git::bstr::BStr,
-    size: Option<usize>,
-) -> std::io::Result<()> {
-    use git::objs::tree::EntryMode::*;
-    writeln!(
-        out,
-        "{} {}{} {}",
-        match entry.mode {
-            Tree => "TREE",
-            Blob => "BLOB",
-            BlobExecutable => " EXE",
-            Link => "LINK",
-            Commit => "SUBM",
-        },
-        entry.oid,
-        size.map(|s| Cow::Owned(format!(" {}", s))).unwrap_or_else(|| "".into()),
-        filename
-    )
+/// Return true if `path` matches one of `pathspecs`, either directly, as a file in a directory named by a pathspec, or as a glob.
+fn matches(pathspecs: &[git::path::Spec], path: &BStr) -> bool {
+    pathspecs.is_empty()
+        || pathspecs.iter().flat_map(|spec| spec.items()).any(|spec| {
+            let spec = spec.strip_suffix(b"/").unwrap_or(spec).as_bstr();
+            path == spec
+                || (path.starts_with(spec) && path.get(spec.len()) == Some(&b'/'))
+                || git::glob::wildmatch(spec, path, git::glob::wildmatch::Mode::empty())
+        })
+}
+
+/// Return true if entries within the directory at `path` may match one of `pathspecs`.
+fn may_match_within(pathspecs: &[git::path::Spec], path: &BStr) -> bool {
+    pathspecs.is_empty()
+        || pathspecs.iter().flat_map(|spec| spec.items()).any(|spec| {
+            let literal_prefix = &spec[..spec.find_byteset(b"*?[\\").unwrap_or(spec.len())];
+            let mut dir = BString::from(path);
+            dir.push(b'/');
+            literal_prefix.starts_with(&dir) || dir.starts_with(literal_prefix)
+        })
+}
+
+fn format_entry(mut out: impl io::Write, entry: &Entry, extended: bool) -> std::io::Result<()> {
+    write!(out, "{:06o} {} {}", entry.mode as u16, entry.kind(), entry.id)?;
+    if extended {
+        match entry.size {
+            Some(size) => write!(out, " {:>7}", size)?,
+            None => write!(out, " {:>7}", "-")?,
+        }
+    }
+    writeln!(out, "\t{}", entry.path)
 }
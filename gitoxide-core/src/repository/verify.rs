@@ -1,11 +1,11 @@
-use std::sync::atomic::AtomicBool;
+use std::{path::PathBuf, sync::atomic::AtomicBool};
 
 use git_repository as git;
-use git_repository::Progress;
+use git_repository::{odb::store::verify::integrity, Progress};
 
 use crate::{pack, OutputFormat};
 
-/// A general purpose context for many operations provided here
+/// The context for [`integrity()`], which prints the [`Outcome`] of [`run()`] to `out`.
 pub struct Context {
     /// If set, provide statistics to `out` in the given format
     pub output_statistics: Option<OutputFormat>,
@@ -17,8 +17,34 @@ pub struct Context {
     pub algorithm: pack::verify::Algorithm,
 }
 
+/// Options for [`run()`].
+pub struct Options {
+    /// If set, don't use more than this amount of threads.
+    /// Otherwise, usually use as many threads as there are logical cores.
+    /// A value of 0 is interpreted as no-limit
+    pub thread_limit: Option<usize>,
+    /// How thoroughly each object in a pack should be verified.
+    pub verify_mode: pack::verify::Mode,
+    /// Whether to traverse packs in a way that is faster or uses less memory.
+    pub algorithm: pack::verify::Algorithm,
+}
+
+/// The outcome of [`run()`].
+#[derive(Debug)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+pub struct Outcome {
+    /// Statistics for each verified loose object database.
+    pub loose_object_stores: Vec<integrity::LooseObjectStatistics>,
+    /// Statistics for each verified pack index and its pack.
+    pub index_statistics: Vec<integrity::IndexStatistics>,
+    /// The path to the index of the worktree, if there is one and it was verified as well.
+    pub worktree_index: Option<PathBuf>,
+}
+
 pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
 
+/// Verify the integrity of all objects in `repo` along with the index of its worktree, if present, and print
+/// statistics to `out` if requested.
 pub fn integrity(
     repo: git::Repository,
     mut out: impl std::io::Write,
@@ -32,7 +58,45 @@ pub fn integrity(
     }: Context,
 ) -> anyhow::Result<()> {
     #[cfg_attr(not(feature = "serde1"), allow(unused))]
-    let mut outcome = repo.objects.store_ref().verify_integrity(
+    let outcome = run(
+        repo,
+        progress,
+        should_interrupt,
+        Options {
+            thread_limit,
+            verify_mode,
+            algorithm,
+        },
+    )?;
+    match output_statistics {
+        Some(OutputFormat::Human) => writeln!(out, "Human output is currently unsupported, use JSON instead")?,
+        #[cfg(feature = "serde1")]
+        Some(OutputFormat::Json) => {
+            crate::write_json(
+                out,
+                &serde_json::json!({
+                    "index_statistics" : outcome.index_statistics,
+                    "loose_object-stores" : outcome.loose_object_stores
+                }),
+            )?;
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+/// Like [`integrity()`], but return the [`Outcome`] instead of printing it.
+pub fn run(
+    repo: git::Repository,
+    progress: impl Progress,
+    should_interrupt: &AtomicBool,
+    Options {
+        thread_limit,
+        verify_mode,
+        algorithm,
+    }: Options,
+) -> anyhow::Result<Outcome> {
+    let mut outcome = repo.verify_integrity(
         progress,
         should_interrupt,
         git_repository::odb::pack::index::verify::integrity::Options {
@@ -43,6 +107,7 @@ pub fn integrity(
             make_pack_lookup_cache: || git_repository::odb::pack::cache::Never,
         },
     )?;
+    let mut worktree_index = None;
     if let Some(index) = repo.worktree().map(|wt| wt.index()).transpose()? {
         index.verify_integrity()?;
         index.verify_entries()?;
@@ -52,20 +117,11 @@ pub fn integrity(
             move |oid, buf: &mut Vec<u8>| objects.find_tree_iter(oid, buf).ok()
         })?;
         outcome.progress.info(format!("Index at '{}' OK", index.path.display()));
+        worktree_index = Some(index.path.clone());
     }
-    match output_statistics {
-        Some(OutputFormat::Human) => writeln!(out, "Human output is currently unsupported, use JSON instead")?,
-        #[cfg(feature = "serde1")]
-        Some(OutputFormat::Json) => {
-            serde_json::to_writer_pretty(
-                out,
-                &serde_json::json!({
-                    "index_statistics" : outcome.index_statistics,
-                    "loose_object-stores" : outcome.loose_object_stores
-                }),
-            )?;
-        }
-        None => {}
-    }
-    Ok(())
+    Ok(Outcome {
+        loose_object_stores: outcome.loose_object_stores,
+        index_statistics: outcome.index_statistics,
+        worktree_index,
+    })
 }
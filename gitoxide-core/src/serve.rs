@@ -0,0 +1,341 @@
+use std::{
+    io::{BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::{Component, Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Context};
+use git_repository as git;
+use git_repository::serve::{daemon, http::Endpoint, Service};
+
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
+
+/// The port `git://` URLs without a port connect to.
+pub const DEFAULT_DAEMON_PORT: u16 = 9418;
+/// The port to listen on for smart HTTP if none is given.
+pub const DEFAULT_HTTP_PORT: u16 = 8080;
+
+pub struct Options {
+    /// The directory all repositories are located in, with the path of each request being relative to it.
+    pub base_path: PathBuf,
+    /// If `true`, serve all repositories, not only those with a `git-daemon-export-ok` file in their `.git` directory.
+    pub export_all: bool,
+    /// If `true`, allow clients to push into all repositories that are served.
+    pub enable_receive_pack: bool,
+    /// Speak smart HTTP instead of the `git://` protocol.
+    pub http: bool,
+    /// The address to listen on, like `0.0.0.0:9418`, or `None` to listen on the default port on `localhost` only.
+    pub listen: Option<String>,
+}
+
+/// Serve the repositories in `options.base_path` to clients connecting to `options.listen` until `should_interrupt` is set,
+/// similar to `git daemon` or `git http-backend` behind a web server, and log each request to `err`.
+///
+/// Connections are served one at a time.
+pub fn run(
+    Options {
+        base_path,
+        export_all,
+        enable_receive_pack,
+        http,
+        listen,
+    }: Options,
+    mut progress: impl git::Progress,
+    mut err: impl Write,
+    should_interrupt: &AtomicBool,
+) -> anyhow::Result<()> {
+    let listen = listen.unwrap_or_else(|| {
+        format!(
+            "127.0.0.1:{}",
+            if http { DEFAULT_HTTP_PORT } else { DEFAULT_DAEMON_PORT }
+        )
+    });
+    let listener = TcpListener::bind(&listen).with_context(|| format!("Could not listen on '{}'", listen))?;
+    listener.set_nonblocking(true)?;
+    progress.info(format!(
+        "Serving '{}' on {}://{}",
+        base_path.display(),
+        if http { "http" } else { "git" },
+        listener.local_addr()?
+    ));
+    let server = Server {
+        base_path,
+        export_all,
+        enable_receive_pack,
+    };
+    while !should_interrupt.load(Ordering::Relaxed) {
+        let stream = match listener.accept() {
+            Ok((stream, _address)) => stream,
+            Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(100));
+                continue;
+            }
+            Err(err) => return Err(err.into()),
+        };
+        stream.set_nonblocking(false)?;
+        let result = if http {
+            server.http(stream, &mut progress, should_interrupt)
+        } else {
+            server.daemon(stream, &mut progress, should_interrupt)
+        };
+        match result {
+            Ok(request) => writeln!(err, "{}", request)?,
+            Err(error) => writeln!(err, "{:#}", error)?,
+        }
+    }
+    Ok(())
+}
+
+struct Server {
+    base_path: PathBuf,
+    export_all: bool,
+    enable_receive_pack: bool,
+}
+
+impl Server {
+    /// Serve a single `git://` connection and return a description of its request and how it went.
+    fn daemon(
+        &self,
+        stream: TcpStream,
+        progress: &mut impl git::Progress,
+        should_interrupt: &AtomicBool,
+    ) -> anyhow::Result<String> {
+        let request = daemon::Request::from_read(&stream)?;
+        let description = format!("{} {}", request.service.as_str(), request.path);
+        let repo = match self.open(&request.path.to_string(), request.service) {
+            Ok(repo) => repo,
+            Err(err) => {
+                let line = format!("ERR {}: {}\n", description, err);
+                (&stream).write_all(format!("{:04x}{}", line.len() + 4, line).as_bytes())?;
+                return Err(err.context(description));
+            }
+        };
+        let input = BufReader::new(stream.try_clone()?);
+        let progress = progress.add_child(description.clone());
+        match request.service {
+            Service::UploadPack => {
+                repo.upload_pack(input, &stream, Default::default(), progress, should_interrupt)
+                    .with_context(|| description.clone())?;
+            }
+            Service::ReceivePack => {
+                repo.receive_pack(input, &stream, Default::default(), progress, should_interrupt)
+                    .with_context(|| description.clone())?;
+            }
+        }
+        Ok(format!("{}: ok", description))
+    }
+
+    /// Serve a single smart HTTP request and return a description of it and its response.
+    fn http(
+        &self,
+        stream: TcpStream,
+        progress: &mut impl git::Progress,
+        should_interrupt: &AtomicBool,
+    ) -> anyhow::Result<String> {
+        let mut input = BufReader::new(stream.try_clone()?);
+        let mut out = &stream;
+        let request = http::Request::from_read(&mut input)?;
+        let description = format!("{} {}", request.method, request.target);
+        let respond = |mut out: &TcpStream, status: &str| -> anyhow::Result<String> {
+            write!(
+                out,
+                "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            )?;
+            Ok(format!("{}: {}", description, status))
+        };
+
+        let (path, query) = match request.target.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (request.target.as_str(), None),
+        };
+        let (repository, endpoint) = match Endpoint::from_request(&request.method, path, query) {
+            Some(endpoint) => endpoint,
+            None => return respond(out, "404 Not Found"),
+        };
+        if endpoint.request_content_type().is_some()
+            && request.header("content-type") != endpoint.request_content_type().as_deref()
+        {
+            return respond(out, "415 Unsupported Media Type");
+        }
+        let repo = match self.open(repository, endpoint.service()) {
+            Ok(repo) => repo,
+            Err(_) if endpoint.service() == Service::ReceivePack && !self.enable_receive_pack => {
+                return respond(out, "403 Forbidden")
+            }
+            Err(_) => return respond(out, "404 Not Found"),
+        };
+
+        if request
+            .header("expect")
+            .map_or(false, |value| value.eq_ignore_ascii_case("100-continue"))
+        {
+            out.write_all(b"HTTP/1.1 100 Continue\r\n\r\n")?;
+        }
+        let body: Box<dyn Read + '_> = if request
+            .header("transfer-encoding")
+            .map_or(false, |value| value.eq_ignore_ascii_case("chunked"))
+        {
+            Box::new(http::Chunked::new(&mut input))
+        } else {
+            let len = request.header("content-length").unwrap_or("0");
+            Box::new(
+                (&mut input).take(
+                    len.parse()
+                        .with_context(|| format!("Invalid content length '{}'", len))?,
+                ),
+            )
+        };
+        let body: Box<dyn Read + '_> = match request.header("content-encoding") {
+            Some("gzip") | Some("x-gzip") => Box::new(flate2::read::GzDecoder::new(body)),
+            Some(encoding) => bail!("{}: unsupported content encoding '{}'", description, encoding),
+            None => body,
+        };
+
+        write!(
+            out,
+            "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+            endpoint.content_type()
+        )?;
+        repo.serve_http(
+            endpoint,
+            BufReader::new(body),
+            &mut out,
+            progress.add_child(description.clone()),
+            should_interrupt,
+        )
+        .with_context(|| description.clone())?;
+        Ok(format!("{}: 200 OK", description))
+    }
+
+    /// Open the repository at `path` relative to our base path for use with `service`, if it is allowed.
+    fn open(&self, path: &str, service: Service) -> anyhow::Result<git::Repository> {
+        if service == Service::ReceivePack && !self.enable_receive_pack {
+            bail!("pushing is disabled");
+        }
+        let path = Path::new(path.trim_start_matches('/'));
+        if path
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+        {
+            bail!("invalid path");
+        }
+        let path = self.base_path.join(path);
+        let repo = git::open(&path)
+            .or_else(|_| git::open(path.with_extension("git")))
+            .map_err(|_| anyhow!("repository not found"))?;
+        if !self.export_all && !repo.git_dir().join("git-daemon-export-ok").is_file() {
+            bail!("repository not exported");
+        }
+        Ok(repo)
+    }
+}
+
+mod http {
+    use std::io::{BufRead, Read};
+
+    use anyhow::bail;
+
+    /// The request line and headers of an HTTP request.
+    pub struct Request {
+        pub method: String,
+        pub target: String,
+        headers: Vec<(String, String)>,
+    }
+
+    impl Request {
+        /// Read the request line and headers from `input`, leaving the body unread.
+        pub fn from_read(input: &mut impl BufRead) -> anyhow::Result<Self> {
+            let mut line = String::new();
+            input.read_line(&mut line)?;
+            let mut tokens = line.split_whitespace();
+            let (method, target) = match (tokens.next(), tokens.next(), tokens.next()) {
+                (Some(method), Some(target), Some(version)) if version.starts_with("HTTP/1.") => {
+                    (method.to_owned(), target.to_owned())
+                }
+                _ => bail!("Invalid HTTP request line {:?}", line.trim_end()),
+            };
+            let mut headers = Vec::new();
+            loop {
+                line.clear();
+                if input.read_line(&mut line)? == 0 {
+                    bail!("Unexpected end of HTTP headers");
+                }
+                let header = line.trim_end();
+                if header.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.split_once(':') {
+                    headers.push((name.trim().to_ascii_lowercase(), value.trim().to_owned()));
+                }
+            }
+            Ok(Request {
+                method,
+                target,
+                headers,
+            })
+        }
+
+        /// Return the value of the header with the lower-case `name`.
+        pub fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find_map(|(header, value)| (header == name).then(|| value.as_str()))
+        }
+    }
+
+    /// A reader of a body sent with `Transfer-Encoding: chunked`.
+    pub struct Chunked<R> {
+        inner: R,
+        remaining: usize,
+        done: bool,
+    }
+
+    impl<R> Chunked<R> {
+        pub fn new(inner: R) -> Self {
+            Chunked {
+                inner,
+                remaining: 0,
+                done: false,
+            }
+        }
+    }
+
+    impl<R: BufRead> Read for Chunked<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            use std::io::{Error, ErrorKind};
+            if self.done || buf.is_empty() {
+                return Ok(0);
+            }
+            if self.remaining == 0 {
+                let mut line = String::new();
+                self.inner.read_line(&mut line)?;
+                let size = line.trim_end().split(';').next().unwrap_or_default();
+                self.remaining = usize::from_str_radix(size, 16)
+                    .map_err(|_| Error::new(ErrorKind::InvalidData, format!("invalid chunk size {:?}", size)))?;
+                if self.remaining == 0 {
+                    self.done = true;
+                    loop {
+                        line.clear();
+                        if self.inner.read_line(&mut line)? == 0 || line.trim_end().is_empty() {
+                            return Ok(0);
+                        }
+                    }
+                }
+            }
+            let max = buf.len().min(self.remaining);
+            let read = self.inner.read(&mut buf[..max])?;
+            if read == 0 {
+                return Err(ErrorKind::UnexpectedEof.into());
+            }
+            self.remaining -= read;
+            if self.remaining == 0 {
+                let mut crlf = [0; 2];
+                self.inner.read_exact(&mut crlf)?;
+            }
+            Ok(read)
+        }
+    }
+}
@@ -0,0 +1,14 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+echo content >file
+mkdir dir
+echo other >dir/file
+git add .
+git commit -q -m "initial"
+git gc -q
+echo loose >loose
+git add loose
+git commit -q -m "loose object"
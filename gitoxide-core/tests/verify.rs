@@ -0,0 +1,35 @@
+use std::sync::atomic::AtomicBool;
+
+use git_repository as git;
+use gitoxide_core::{pack, repository::verify};
+
+type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[test]
+fn run_verifies_all_objects_and_the_worktree_index() -> Result {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_repo_with_worktree_index.sh")?;
+    let repo = git::open(&dir)?;
+    let outcome = verify::run(
+        repo,
+        git::progress::Discard,
+        &AtomicBool::default(),
+        verify::Options {
+            thread_limit: Some(1),
+            verify_mode: pack::verify::Mode::HashCrc32DecodeEncode,
+            algorithm: pack::verify::Algorithm::LessTime,
+        },
+    )?;
+
+    assert_eq!(outcome.index_statistics.len(), 1, "a single pack was written by `git gc`");
+    assert_eq!(outcome.loose_object_stores.len(), 1);
+    assert_eq!(
+        outcome.loose_object_stores[0].statistics.num_objects,
+        3,
+        "a blob, a tree and a commit were added after `git gc`"
+    );
+    assert_eq!(
+        outcome.worktree_index.as_deref(),
+        Some(dir.join(".git").join("index").as_path())
+    );
+    Ok(())
+}
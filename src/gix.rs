@@ -14,7 +14,12 @@ use anyhow::Result;
 
 #[cfg(feature = "pretty-cli")]
 fn main() -> Result<()> {
-    plumbing::main()
+    // Exit only after everything was dropped and all progress renderers were shut down.
+    let exit_code = plumbing::main()?;
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
+    Ok(())
 }
 
 #[cfg(not(feature = "pretty-cli"))]
@@ -7,7 +7,7 @@ use std::{
     },
 };
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use clap::Parser;
 use git_repository::bstr::io::BufReadExt;
 use gitoxide_core as core;
@@ -15,8 +15,10 @@ use gitoxide_core::pack::verify;
 
 use crate::plumbing::options::remote;
 use crate::{
-    plumbing::options::{commit, config, exclude, free, mailmap, odb, revision, tree, Args, Subcommands},
-    shared::pretty::prepare_and_run,
+    plumbing::options::{
+        archive, commit, config, exclude, free, log, mailmap, odb, refs, revision, tree, Args, Subcommands,
+    },
+    shared::{pretty::prepare_and_run, ProgressFormat, ProgressMode},
 };
 
 #[cfg(feature = "gitoxide-core-async-client")]
@@ -48,16 +50,29 @@ pub mod async_util {
     }
 }
 
-pub fn main() -> Result<()> {
+/// Run the command-line and return the exit code the process should have, which is non-zero if a command failed without error,
+/// like `status --exit-code` with changes.
+pub fn main() -> Result<i32> {
     let args: Args = Args::parse_from(git_repository::env::args_os());
     let thread_limit = args.threads;
     let verbose = args.verbose;
     let format = args.format;
+    if let Some(version) = args.format_version {
+        if version != core::JSON_FORMAT_VERSION {
+            anyhow::bail!(
+                "JSON format version {} is unsupported, this version of gix produces version {}",
+                version,
+                core::JSON_FORMAT_VERSION
+            );
+        }
+    }
     let cmd = args.cmd;
     let object_hash = args.object_hash;
     let config = args.config;
     use git_repository as git;
     let repository = args.repository;
+    let git_dir = args.git_dir.map(|dir| repository.join(dir));
+    let work_tree = args.work_tree.map(|dir| repository.join(dir));
     enum Mode {
         Strict,
         Lenient,
@@ -65,32 +80,43 @@ pub fn main() -> Result<()> {
     let repository = move |mode: Mode| -> Result<git::Repository> {
         let mut mapping: git::sec::trust::Mapping<git::open::Options> = Default::default();
         let toggle = matches!(mode, Mode::Strict);
-        mapping.full = mapping.full.strict_config(toggle);
-        mapping.reduced = mapping.reduced.strict_config(toggle);
-        let mut repo = git::ThreadSafeRepository::discover_opts(repository, Default::default(), mapping)
-            .map(git::Repository::from)
-            .map(|r| r.apply_environment())?;
-        if !config.is_empty() {
-            repo.config_snapshot_mut()
-                .apply_cli_overrides(config)
-                .context("Unable to parse command-line configuration")?;
-        }
-        Ok(repo)
+        let configure = |options: git::open::Options| {
+            let options = options.strict_config(toggle).config_overrides(config.clone());
+            match &work_tree {
+                Some(work_tree) => options.worktree_dir(work_tree),
+                None => options,
+            }
+        };
+        mapping.full = configure(mapping.full);
+        mapping.reduced = configure(mapping.reduced);
+        let repo = match &git_dir {
+            Some(git_dir) => {
+                let trust = git::sec::Trust::from_path_ownership(git_dir)?;
+                mapping.into_value_by_level(trust).open(git_dir)?
+            }
+            None => git::ThreadSafeRepository::discover_opts(&repository, Default::default(), mapping)?,
+        };
+        Ok(git::Repository::from(repo).apply_environment())
     };
 
     let progress;
     let progress_keep_open;
     #[cfg(feature = "prodash-render-tui")]
     {
-        progress = args.progress;
+        progress = ProgressMode::from(args.progress);
         progress_keep_open = args.progress_keep_open;
     }
     #[cfg(not(feature = "prodash-render-tui"))]
     {
-        progress = false;
+        progress = ProgressMode::Line;
         progress_keep_open = false;
     }
+    let progress = match args.progress_format {
+        ProgressFormat::Human => progress,
+        ProgressFormat::JsonLines => ProgressMode::JsonLines,
+    };
 
+    let mut process_exit_code = 0;
     let should_interrupt = Arc::new(AtomicBool::new(false));
     git_repository::interrupt::init_handler({
         let should_interrupt = Arc::clone(&should_interrupt);
@@ -101,7 +127,7 @@ pub fn main() -> Result<()> {
         #[cfg_attr(feature = "small", allow(unused_variables))]
         Subcommands::Remote(remote::Platform { name, url, cmd }) => match cmd {
             #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
-            remote::Subcommands::Refs => {
+            remote::Subcommands::Refs { filters } => {
                 #[cfg(feature = "gitoxide-core-blocking-client")]
                 {
                     prepare_and_run(
@@ -115,7 +141,12 @@ pub fn main() -> Result<()> {
                                 repository(Mode::Lenient)?,
                                 progress,
                                 out,
-                                core::repository::remote::refs::Context { name, url, format },
+                                core::repository::remote::refs::Context {
+                                    name,
+                                    url,
+                                    format,
+                                    filters,
+                                },
                             )
                         },
                     )
@@ -131,20 +162,223 @@ pub fn main() -> Result<()> {
                         repository(Mode::Lenient)?,
                         progress,
                         std::io::stdout(),
-                        core::repository::remote::refs::Context { name, url, format },
+                        core::repository::remote::refs::Context {
+                            name,
+                            url,
+                            format,
+                            filters,
+                        },
                     ))
                 }
             }
         },
-        Subcommands::Config(config::Platform { filter }) => prepare_and_run(
-            "config-list",
+        #[cfg(feature = "gitoxide-core-blocking-client")]
+        Subcommands::Clone(crate::plumbing::options::clone::Platform {
+            bare,
+            depth,
+            origin,
+            url,
+            directory,
+        }) => prepare_and_run(
+            "clone",
+            verbose,
+            progress,
+            progress_keep_open,
+            core::repository::clone::PROGRESS_RANGE,
+            move |progress, out, _err| {
+                core::repository::clone::clone(
+                    url,
+                    directory,
+                    progress,
+                    out,
+                    &should_interrupt,
+                    format,
+                    core::repository::clone::Options {
+                        bare,
+                        depth,
+                        remote_name: origin,
+                    },
+                )
+            },
+        ),
+        Subcommands::Log(log::Platform {
+            pretty,
+            first_parent,
+            date,
+            stat,
+            max_count,
+            full_paths,
+            rev_spec,
+            paths,
+        }) => prepare_and_run(
+            "log",
             verbose,
             progress,
             progress_keep_open,
             None,
-            move |_progress, out, _err| core::repository::config::list(repository(Mode::Lenient)?, filter, format, out),
-        )
-        .map(|_| ()),
+            move |_progress, out, _err| {
+                core::repository::log::log(
+                    repository(Mode::Lenient)?,
+                    rev_spec.as_deref(),
+                    out,
+                    core::repository::log::Options {
+                        format,
+                        pretty,
+                        first_parent,
+                        date,
+                        stat,
+                        max_count,
+                        paths,
+                        full_paths,
+                    },
+                )
+            },
+        ),
+        Subcommands::Archive(archive::Platform {
+            format: archive_format,
+            prefix,
+            output,
+            treeish,
+        }) => prepare_and_run(
+            "archive",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                core::repository::archive::archive(
+                    repository(Mode::Lenient)?,
+                    treeish.as_deref(),
+                    output,
+                    out,
+                    core::repository::archive::Options {
+                        format: archive_format,
+                        prefix,
+                    },
+                )
+            },
+        ),
+        Subcommands::Config(config::Platform { filter, cmd }) => match cmd {
+            None => prepare_and_run(
+                "config-list",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::config::list(
+                        repository(Mode::Lenient)?,
+                        filter,
+                        core::repository::config::list::Options {
+                            scope: None,
+                            show_origin: false,
+                            show_scope: false,
+                        },
+                        format,
+                        out,
+                    )
+                },
+            ),
+            Some(config::Subcommands::List { scope, origin, filter }) => prepare_and_run(
+                "config-list",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::config::list(
+                        repository(Mode::Lenient)?,
+                        filter,
+                        core::repository::config::list::Options {
+                            scope: scope.into_scope(),
+                            show_origin: origin.show_origin,
+                            show_scope: origin.show_scope,
+                        },
+                        format,
+                        out,
+                    )
+                },
+            ),
+            Some(config::Subcommands::Get {
+                scope,
+                origin,
+                all,
+                value_type,
+                key,
+                value_pattern,
+            }) => prepare_and_run(
+                "config-get",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::config::get(
+                        repository(Mode::Lenient)?,
+                        &key,
+                        value_pattern.as_deref(),
+                        core::repository::config::get::Options {
+                            scope: scope.into_scope(),
+                            all,
+                            value_type,
+                            show_origin: origin.show_origin,
+                            show_scope: origin.show_scope,
+                        },
+                        out,
+                    )
+                },
+            ),
+            Some(config::Subcommands::Set {
+                scope,
+                all,
+                add,
+                key,
+                value,
+                value_pattern,
+            }) => prepare_and_run(
+                "config-set",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, _out, _err| {
+                    core::repository::config::set(
+                        repository(Mode::Lenient)?,
+                        &key,
+                        &value,
+                        value_pattern.as_deref(),
+                        core::repository::config::set::Options {
+                            scope: scope.into_scope(),
+                            all,
+                            add,
+                        },
+                    )
+                },
+            ),
+            Some(config::Subcommands::Unset {
+                scope,
+                all,
+                key,
+                value_pattern,
+            }) => prepare_and_run(
+                "config-unset",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, _out, _err| {
+                    core::repository::config::unset(
+                        repository(Mode::Lenient)?,
+                        &key,
+                        value_pattern.as_deref(),
+                        core::repository::config::unset::Options {
+                            scope: scope.into_scope(),
+                            all,
+                        },
+                    )
+                },
+            ),
+        },
         Subcommands::Free(subcommands) => match subcommands {
             free::Subcommands::CommitGraph(subcommands) => match subcommands {
                 free::commitgraph::Subcommands::Verify { path, statistics } => prepare_and_run(
@@ -166,7 +400,50 @@ pub fn main() -> Result<()> {
                     },
                 )
                 .map(|_| ()),
+                free::commitgraph::Subcommands::List { path } => prepare_and_run(
+                    "commitgraph-list",
+                    verbose,
+                    progress,
+                    progress_keep_open,
+                    None,
+                    move |_progress, out, _err| core::commitgraph::list::list(path, format, out),
+                ),
+                free::commitgraph::Subcommands::Query { path, ids } => prepare_and_run(
+                    "commitgraph-query",
+                    verbose,
+                    progress,
+                    progress_keep_open,
+                    None,
+                    move |_progress, out, _err| core::commitgraph::query::query(path, ids, format, out),
+                ),
             },
+            free::Subcommands::Serve(free::serve::Platform {
+                http,
+                listen,
+                export_all,
+                enable_receive_pack,
+                base_path,
+            }) => prepare_and_run(
+                "serve",
+                verbose,
+                progress,
+                progress_keep_open,
+                core::serve::PROGRESS_RANGE,
+                move |progress, _out, err| {
+                    core::serve::run(
+                        core::serve::Options {
+                            base_path,
+                            export_all,
+                            enable_receive_pack,
+                            http,
+                            listen,
+                        },
+                        progress,
+                        err,
+                        &should_interrupt,
+                    )
+                },
+            ),
             free::Subcommands::Index(free::index::Platform {
                 object_hash,
                 index_path,
@@ -299,6 +576,8 @@ pub fn main() -> Result<()> {
                     directory,
                     refs,
                     refs_directory,
+                    write_commit_graph,
+                    write_multi_pack_index,
                 } => {
                     let (_handle, progress) =
                         async_util::prepare(verbose, "pack-receive", core::pack::receive::PROGRESS_RANGE);
@@ -315,6 +594,10 @@ pub fn main() -> Result<()> {
                             out: std::io::stdout(),
                             should_interrupt,
                             object_hash,
+                            maintenance: core::pack::receive::Maintenance {
+                                write_commit_graph,
+                                write_multi_pack_index,
+                            },
                         },
                     );
                     return futures_lite::future::block_on(fut);
@@ -326,6 +609,8 @@ pub fn main() -> Result<()> {
                     directory,
                     refs,
                     refs_directory,
+                    write_commit_graph,
+                    write_multi_pack_index,
                 } => prepare_and_run(
                     "pack-receive",
                     verbose,
@@ -346,6 +631,10 @@ pub fn main() -> Result<()> {
                                 should_interrupt,
                                 out,
                                 object_hash,
+                                maintenance: core::pack::receive::Maintenance {
+                                    write_commit_graph,
+                                    write_multi_pack_index,
+                                },
                             },
                         )
                     },
@@ -380,6 +669,14 @@ pub fn main() -> Result<()> {
                         )
                     },
                 ),
+                free::pack::Subcommands::Explain { path, id } => prepare_and_run(
+                    "pack-explain",
+                    verbose,
+                    progress,
+                    progress_keep_open,
+                    None,
+                    move |_progress, out, _err| core::pack::explain::explain(path, id, object_hash, format, out),
+                ),
                 free::pack::Subcommands::Verify {
                     args:
                         free::pack::VerifyOptions {
@@ -616,6 +913,7 @@ pub fn main() -> Result<()> {
                 treeish,
                 recursive,
                 extended,
+                pathspecs,
             } => prepare_and_run(
                 "tree-entries",
                 verbose,
@@ -628,6 +926,7 @@ pub fn main() -> Result<()> {
                         treeish.as_deref(),
                         recursive,
                         extended,
+                        pathspecs,
                         format,
                         out,
                     )
@@ -680,7 +979,130 @@ pub fn main() -> Result<()> {
                     core::repository::mailmap::entries(repository(Mode::Lenient)?, format, out, err)
                 },
             ),
+            mailmap::Subcommands::Check { contacts } => prepare_and_run(
+                "mailmap-check",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, err| {
+                    core::repository::mailmap::check(repository(Mode::Lenient)?, contacts, format, out, err)
+                },
+            ),
         },
+        Subcommands::Refs(cmd) => match cmd {
+            refs::Subcommands::Edit { dry_run, message } => prepare_and_run(
+                "refs-edit",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::refs::edit::apply(
+                        repository(Mode::Strict)?,
+                        stdin_or_bail()?,
+                        out,
+                        core::repository::refs::edit::Options {
+                            format,
+                            dry_run,
+                            message,
+                        },
+                    )
+                },
+            ),
+        },
+        Subcommands::Apply {
+            cached,
+            index,
+            reverse,
+            reject,
+            whitespace,
+            patches,
+        } => prepare_and_run(
+            "apply",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, _out, err| {
+                core::repository::apply::apply(
+                    repository(Mode::Strict)?,
+                    patches,
+                    err,
+                    core::repository::apply::Options {
+                        format,
+                        cached,
+                        index,
+                        reverse,
+                        reject,
+                        whitespace,
+                    },
+                )
+            },
+        )
+        .map(|outcome| {
+            if !outcome.rejects.is_empty() {
+                process_exit_code = 1;
+            }
+        }),
+        Subcommands::Status {
+            exit_code,
+            quiet,
+            full_paths,
+        } => prepare_and_run(
+            "status",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                core::repository::status::show(
+                    repository(Mode::Strict)?,
+                    out,
+                    core::repository::status::Options {
+                        format,
+                        quiet,
+                        full_paths,
+                    },
+                )
+            },
+        )
+        .map(|is_dirty| {
+            if is_dirty && (exit_code || quiet) {
+                process_exit_code = 1;
+            }
+        }),
+        Subcommands::Fsck {
+            min_severity,
+            skip_hashes,
+            no_connectivity,
+            unreachable,
+        } => prepare_and_run(
+            "fsck",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, err| {
+                core::repository::fsck::check(
+                    repository(Mode::Strict)?,
+                    out,
+                    err,
+                    core::repository::fsck::Options {
+                        format,
+                        min_severity,
+                        verify_hashes: !skip_hashes,
+                        connectivity: !no_connectivity,
+                        unreachable,
+                    },
+                )
+            },
+        )
+        .map(|outcome| {
+            if outcome.errors > 0 {
+                process_exit_code = 1;
+            }
+        }),
         Subcommands::Exclude(cmd) => match cmd {
             exclude::Subcommands::Query {
                 patterns,
@@ -717,7 +1139,7 @@ pub fn main() -> Result<()> {
             ),
         },
     }?;
-    Ok(())
+    Ok(process_exit_code)
 }
 
 fn stdin_or_bail() -> Result<std::io::BufReader<std::io::Stdin>> {
@@ -9,14 +9,30 @@ use gitoxide_core as core;
 #[clap(subcommand_required = true)]
 #[clap(arg_required_else_help = true)]
 pub struct Args {
-    /// The repository to access.
+    /// The repository to access, discovered by searching upwards from this directory unless `--git-dir` is set.
     #[clap(short = 'r', long, default_value = ".")]
     pub repository: PathBuf,
 
+    /// The `.git` directory of the repository to access without discovery, similar to `git --git-dir`.
+    ///
+    /// Relative paths are interpreted relative to `--repository`.
+    #[clap(long)]
+    pub git_dir: Option<PathBuf>,
+
+    /// The worktree of the repository to access, overriding the one implied by the `.git` directory
+    /// and `core.worktree`, similar to `git --work-tree`.
+    ///
+    /// Relative paths are interpreted relative to `--repository`.
+    #[clap(long)]
+    pub work_tree: Option<PathBuf>,
+
     /// Add these values to the configuration in the form of `key=value` or `key`.
     ///
     /// For example, if `key` is `core.abbrev`, set configuration like `[core] abbrev = key`,
     /// or `remote.origin.url = foo` to set `[remote "origin"] url = foo`.
+    ///
+    /// These apply to all sub-commands operating on a repository and take precedence over all other configuration,
+    /// even while the repository is opened, similar to `git -c`.
     #[clap(long, short = 'c', parse(try_from_os_str = git::env::os_str_to_bstring))]
     pub config: Vec<BString>,
 
@@ -42,6 +58,18 @@ pub struct Args {
     #[clap(long, conflicts_with("verbose"), requires("progress"))]
     pub progress_keep_open: bool,
 
+    /// Determine the format in which progress is displayed.
+    ///
+    /// With `json-lines`, snapshots of all tasks with their id, parent, name, step, maximum and unit as well as new
+    /// messages are written to stderr as one JSON object per line, allowing other programs to display progress themselves.
+    #[clap(
+        long,
+        default_value = "human",
+        possible_values(crate::shared::ProgressFormat::variants())
+    )]
+    #[cfg_attr(feature = "prodash-render-tui", clap(conflicts_with("progress")))]
+    pub progress_format: crate::shared::ProgressFormat,
+
     /// Determine the format to use when outputting statistics.
     #[clap(
         long,
@@ -51,6 +79,13 @@ pub struct Args {
     )]
     pub format: core::OutputFormat,
 
+    /// The version of the JSON schema the output must have, which fails if this `gix` can't produce it.
+    ///
+    /// All JSON output is an object with the version in `format_version` and the actual output in `data`. Scripts can
+    /// pin the version they were written for to be sure fields won't change, while new fields may be added at any time.
+    #[clap(long, value_name = "N")]
+    pub format_version: Option<u32>,
+
     /// The object format to assume when reading files that don't inherently know about it, or when writing files.
     #[clap(long, default_value_t = git_repository::hash::Kind::default(), possible_values(&["SHA1"]))]
     pub object_hash: git_repository::hash::Kind,
@@ -81,27 +116,234 @@ pub enum Subcommands {
     /// Interact with the mailmap.
     #[clap(subcommand)]
     Mailmap(mailmap::Subcommands),
+    /// Show the commits reachable from a revision, newest first, similar to `git log`.
+    Log(log::Platform),
+    /// Write the tree of a revision as `tar`, `tar.gz` or `zip` archive, similar to `git archive`.
+    Archive(archive::Platform),
     /// Interact with the remote hosts.
     Remote(remote::Platform),
+    /// Interact with references.
+    #[clap(subcommand)]
+    Refs(refs::Subcommands),
     /// Interact with the exclude files like .gitignore.
     #[clap(subcommand)]
     Exclude(exclude::Subcommands),
+    /// Apply patches as produced by `git diff` to the worktree, the index or both, similar to `git apply`.
+    Apply {
+        /// Apply the patch to the index only, leaving the worktree untouched.
+        #[clap(long)]
+        cached: bool,
+        /// Apply the patch to the index and the worktree, which must match the index for all patched files.
+        #[clap(long, conflicts_with("cached"))]
+        index: bool,
+        /// Apply the patch in reverse.
+        #[clap(long, short = 'R')]
+        reverse: bool,
+        /// Apply the hunks that apply and write those that don't to `<path>.rej`, instead of changing nothing.
+        #[clap(long)]
+        reject: bool,
+        /// What to do with added lines that end in whitespace.
+        #[clap(
+            long,
+            default_value = "warn",
+            possible_values(core::repository::apply::Whitespace::variants())
+        )]
+        whitespace: core::repository::apply::Whitespace,
+        /// The patch files to apply, or the patch on stdin if none is given.
+        patches: Vec<std::path::PathBuf>,
+    },
+    /// Show changes between `HEAD`, the index and the worktree, without untracked files.
+    Status {
+        /// Exit with 1 if there are changes and with 0 otherwise, similar to `git diff --exit-code`.
+        #[clap(long)]
+        exit_code: bool,
+        /// Print nothing and stop at the first change, for a quick answer to whether the repository is dirty. Implies `--exit-code`.
+        #[clap(long, short = 'q')]
+        quiet: bool,
+        /// Print paths relative to the work tree instead of the current directory.
+        #[clap(long)]
+        full_paths: bool,
+    },
     Config(config::Platform),
+    /// Check the integrity and connectivity of the repository, similar to `git fsck`.
+    ///
+    /// Exits with 1 if there are findings with error severity.
+    Fsck {
+        /// Don't print findings that are less severe than this.
+        #[clap(
+            long,
+            short = 's',
+            default_value = "info",
+            possible_values(core::repository::fsck::Severity::variants())
+        )]
+        min_severity: core::repository::fsck::Severity,
+        /// Don't recompute the hash of each object, which is faster.
+        #[clap(long)]
+        skip_hashes: bool,
+        /// Don't check that referenced objects exist and don't report dangling objects.
+        #[clap(long)]
+        no_connectivity: bool,
+        /// Report all unreachable objects, not only dangling ones.
+        #[clap(long, conflicts_with("no-connectivity"))]
+        unreachable: bool,
+    },
+    /// Clone a repository into a new directory, with checkout unless it's bare.
+    #[cfg(feature = "gitoxide-core-blocking-client")]
+    Clone(clone::Platform),
     /// Subcommands that need no git repository to run.
     #[clap(subcommand)]
     Free(free::Subcommands),
 }
 
 pub mod config {
+    use gitoxide_core::repository::config::{Scope, ValueType};
+
     /// Print all entries in a configuration file or access other sub-commands
     #[derive(Debug, clap::Parser)]
-    #[clap(subcommand_required(false))]
+    #[clap(subcommand_required(false), args_conflicts_with_subcommands(true))]
     pub struct Platform {
         /// The filter terms to limit the output to matching sections and subsections only.
         ///
         /// Typical filters are `branch` or `remote.origin` or `remote.or*` - git-style globs are supported
         /// and comparisons are case-insensitive.
         pub filter: Vec<String>,
+
+        /// Subcommands
+        #[clap(subcommand)]
+        pub cmd: Option<Subcommands>,
+    }
+
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// Print all entries, optionally limited to one scope and with their origin.
+        List {
+            #[clap(flatten)]
+            scope: ScopeArgs,
+            #[clap(flatten)]
+            origin: OriginArgs,
+            /// The filter terms to limit the output to matching sections and subsections only, like `remote.or*`.
+            filter: Vec<String>,
+        },
+        /// Print the last value of a key, like `core.bare` or `remote.origin.url`, and fail if it isn't set.
+        Get {
+            #[clap(flatten)]
+            scope: ScopeArgs,
+            #[clap(flatten)]
+            origin: OriginArgs,
+            /// Print all values of a multi-valued key.
+            #[clap(long)]
+            all: bool,
+            /// Interpret the values as 'bool', 'int' or 'path' and print them in canonical form.
+            #[clap(long = "type", value_name = "TYPE")]
+            value_type: Option<ValueType>,
+            /// The key to print the value of.
+            key: String,
+            /// A regular expression to only print matching values, or non-matching values if prefixed with `!`.
+            value_pattern: Option<String>,
+        },
+        /// Set a key to the given value, in the configuration of the repository unless another scope is specified.
+        Set {
+            #[clap(flatten)]
+            scope: ScopeArgs,
+            /// Replace all values of a multi-valued key, or all values matching the value pattern, with a single one.
+            #[clap(long)]
+            all: bool,
+            /// Add the value to a key without altering its existing values.
+            #[clap(long, conflicts_with_all(&["all", "value-pattern"]))]
+            add: bool,
+            /// The key to set, like `core.bare` or `remote.origin.url`.
+            key: String,
+            /// The value to set.
+            value: String,
+            /// A regular expression to only replace matching values, or non-matching values if prefixed with `!`.
+            value_pattern: Option<String>,
+        },
+        /// Remove a key from the configuration of the repository unless another scope is specified.
+        Unset {
+            #[clap(flatten)]
+            scope: ScopeArgs,
+            /// Remove all values of a multi-valued key, or all values matching the value pattern.
+            #[clap(long)]
+            all: bool,
+            /// The key to remove.
+            key: String,
+            /// A regular expression to only remove matching values, or non-matching values if prefixed with `!`.
+            value_pattern: Option<String>,
+        },
+    }
+
+    #[derive(Debug, clap::Args)]
+    #[clap(group(clap::ArgGroup::new("scope").args(&["system", "global", "local", "worktree"])))]
+    pub struct ScopeArgs {
+        /// Use the system-wide configuration.
+        #[clap(long)]
+        system: bool,
+        /// Use the configuration of the current user.
+        #[clap(long)]
+        global: bool,
+        /// Use the configuration of the repository.
+        #[clap(long)]
+        local: bool,
+        /// Use the configuration of the current worktree.
+        #[clap(long)]
+        worktree: bool,
+    }
+
+    impl ScopeArgs {
+        pub fn into_scope(self) -> Option<Scope> {
+            if self.system {
+                Some(Scope::System)
+            } else if self.global {
+                Some(Scope::Global)
+            } else if self.local {
+                Some(Scope::Local)
+            } else if self.worktree {
+                Some(Scope::Worktree)
+            } else {
+                None
+            }
+        }
+    }
+
+    #[derive(Debug, clap::Args)]
+    pub struct OriginArgs {
+        /// Prefix each value with the file it was read from.
+        #[clap(long)]
+        pub show_origin: bool,
+        /// Prefix each value with its scope, one of 'system', 'global', 'local', 'worktree' or 'command'.
+        #[clap(long)]
+        pub show_scope: bool,
+    }
+}
+
+#[cfg(feature = "gitoxide-core-blocking-client")]
+pub mod clone {
+    use std::{num::NonZeroU32, path::PathBuf};
+
+    use git_repository as git;
+
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// Create a bare repository whose branches mirror the ones of the remote, without worktree.
+        #[clap(long)]
+        pub bare: bool,
+
+        /// Fetch only the given amount of commits of each branch to create a shallow clone.
+        #[clap(long)]
+        pub depth: Option<NonZeroU32>,
+
+        /// The name of the remote to clone from, instead of `origin`.
+        #[clap(long, short = 'o')]
+        pub origin: Option<String>,
+
+        /// The URL of the repository to clone.
+        #[clap(parse(try_from_os_str = std::convert::TryFrom::try_from))]
+        pub url: git::Url,
+
+        /// The directory to clone into, which must not exist or be empty.
+        ///
+        /// Defaults to a directory named after the last component of the URL.
+        pub directory: Option<PathBuf>,
     }
 }
 
@@ -130,7 +372,93 @@ pub mod remote {
     pub enum Subcommands {
         /// Print all references available on the remote
         #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
-        Refs,
+        Refs {
+            /// Only print references matching this glob like `refs/heads/feature/*`, prefix like `refs/tags/` or short name
+            /// like `main`. Can be given multiple times.
+            ///
+            /// With protocol V2, the server only sends matching references which is much faster for remotes with many of them.
+            #[clap(long = "filter", value_name = "PATTERN", parse(try_from_os_str = git::env::os_str_to_bstring))]
+            filters: Vec<git::bstr::BString>,
+        },
+    }
+}
+
+pub mod archive {
+    use std::path::PathBuf;
+
+    use git_repository as git;
+
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// The kind of archive to write, one of 'tar', 'tar.gz' or 'zip'.
+        ///
+        /// If unset, it's derived from the extension of the output file, or 'tar' if writing to stdout.
+        #[clap(long, parse(try_from_str = parse_format))]
+        pub format: Option<git::archive::Format>,
+
+        /// A path to prepend to all entries in the archive, like 'project/'.
+        #[clap(long)]
+        pub prefix: Option<String>,
+
+        /// The file to write the archive to, instead of stdout.
+        #[clap(long, short = 'o')]
+        pub output: Option<PathBuf>,
+
+        /// The commit, tag or tree to archive, or `HEAD` if unset.
+        pub treeish: Option<String>,
+    }
+
+    fn parse_format(name: &str) -> Result<git::archive::Format, String> {
+        use git::archive::Format;
+        Ok(match name {
+            "tar" => Format::Tar,
+            "tar.gz" | "tgz" => Format::TarGz,
+            "zip" => Format::Zip,
+            _ => return Err(format!("Unknown archive format: {:?}", name)),
+        })
+    }
+}
+
+pub mod log {
+    use gitoxide_core::repository::log::DateFormat;
+
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// Print each commit using the given template instead of the default format.
+        ///
+        /// Placeholders are `%H` and `%h` for the (abbreviated) commit hash, `%T` and `%t` for the tree hash, `%P` and `%p`
+        /// for the parent hashes, `%an`, `%ae` and `%ad` for the author name, email and date, `%cn`, `%ce` and `%cd` for
+        /// the committer, `%s` for the subject, `%b` for the body, `%d` for the names of references pointing to the commit,
+        /// `%n` for a newline and `%%` for a literal `%`.
+        #[clap(long = "format", visible_alias = "pretty", value_name = "TEMPLATE")]
+        pub pretty: Option<String>,
+
+        /// Only follow the first parent of merge commits.
+        #[clap(long)]
+        pub first_parent: bool,
+
+        /// How to display dates, one of 'default', 'short', 'iso', 'iso-strict', 'rfc', 'unix' or 'raw'.
+        #[clap(long, default_value = "default")]
+        pub date: DateFormat,
+
+        /// Show the paths changed by each commit compared to its first parent.
+        #[clap(long)]
+        pub stat: bool,
+
+        /// Show at most the given amount of commits.
+        #[clap(long, short = 'n')]
+        pub max_count: Option<usize>,
+
+        /// Print paths relative to the work tree instead of the current directory.
+        #[clap(long)]
+        pub full_paths: bool,
+
+        /// The revision to start from, or `HEAD` if unset.
+        pub rev_spec: Option<String>,
+
+        /// Only show commits that change files at or below these paths, relative to the current directory.
+        #[clap(last = true)]
+        pub paths: Vec<std::path::PathBuf>,
     }
 }
 
@@ -139,6 +467,30 @@ pub mod mailmap {
     pub enum Subcommands {
         /// Print all entries in configured mailmaps, inform about errors as well.
         Entries,
+        /// Print the canonical name and email of contacts as mapped by the configured mailmaps, similar to `git check-mailmap`.
+        Check {
+            /// The contacts to look up, either as `Name <email>`, `<email>` or just `email`.
+            #[clap(required = true)]
+            contacts: Vec<String>,
+        },
+    }
+}
+
+pub mod refs {
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// Apply all reference edits read from stdin in a single transaction, similar to `git update-ref --stdin`.
+        ///
+        /// Each line is one of `update <ref> <new> [<old>]`, `create <ref> <new>` or `delete <ref> [<old>]`.
+        /// Values are revision specs or object ids, and the null id as `<old>` means the reference must not exist.
+        Edit {
+            /// Check that all edits can be applied and print them, but don't change any reference.
+            #[clap(long, short = 'n')]
+            dry_run: bool,
+            /// The message to write into the reference logs.
+            #[clap(long, short = 'm')]
+            message: Option<String>,
+        },
     }
 }
 
@@ -153,24 +505,31 @@ pub mod odb {
 }
 
 pub mod tree {
+    use git_repository as git;
+
     #[derive(Debug, clap::Subcommand)]
     pub enum Subcommands {
-        /// Print entries in a given tree
+        /// Print entries in a given tree, similar to `git ls-tree`.
+        #[clap(visible_alias = "ls")]
         Entries {
-            /// Traverse the entire tree and its subtrees respectively, not only this tree.
+            /// List the entries of all subtrees instead of the subtrees themselves, like `git ls-tree -r`.
             #[clap(long, short = 'r')]
             recursive: bool,
 
-            /// Provide files size as well. This is expensive as the object is decoded entirely.
-            #[clap(long, short = 'e')]
+            /// Provide the size of blobs as well, like `git ls-tree -l`. Sizes are read from object headers.
+            #[clap(long, short = 'e', visible_short_alias = 'l')]
             extended: bool,
 
             /// The tree to traverse, or the tree at `HEAD` if unspecified.
             treeish: Option<String>,
+
+            /// Only list entries matching these paths, which may be directories or globs.
+            #[clap(parse(try_from_os_str = std::convert::TryFrom::try_from))]
+            pathspecs: Vec<git::path::Spec>,
         },
         /// Provide information about a tree.
         Info {
-            /// Provide files size as well. This is expensive as the object is decoded entirely.
+            /// Provide files size as well. Sizes are read from object headers.
             #[clap(long, short = 'e')]
             extended: bool,
             /// The tree to traverse, or the tree at `HEAD` if unspecified.
@@ -264,6 +623,8 @@ pub mod free {
         Pack(pack::Subcommands),
         /// Subcommands for interacting with a worktree index, typically at .git/index
         Index(index::Platform),
+        /// Serve repositories for fetching and pushing via `git://` or smart HTTP, similar to `git daemon`.
+        Serve(serve::Platform),
     }
 
     ///
@@ -280,6 +641,19 @@ pub mod free {
                 #[clap(long, short = 's')]
                 statistics: bool,
             },
+            /// List all layers of a commit graph along with each commit's position, generation number, commit time and parent positions.
+            List {
+                /// The path to '.git/objects/info/', '.git/objects/info/commit-graphs/', or '.git/objects/info/commit-graph' to list.
+                path: PathBuf,
+            },
+            /// Show everything a commit graph knows about the given commits, including their layer and bloom filter presence.
+            Query {
+                /// The path to '.git/objects/info/', '.git/objects/info/commit-graphs/', or '.git/objects/info/commit-graph' to query.
+                path: PathBuf,
+                /// The full hexadecimal ids of the commits to look up.
+                #[clap(required = true)]
+                ids: Vec<git_repository::ObjectId>,
+            },
         }
     }
 
@@ -436,6 +810,18 @@ pub mod free {
                 #[clap(long = "reference", short = 'r')]
                 refs: Vec<String>,
 
+                /// Add the received commits to the commit-graph of the repository owning `directory`.
+                ///
+                /// Requires `directory` to be the `objects/pack` directory of a repository.
+                #[clap(long, requires = "directory")]
+                write_commit_graph: bool,
+
+                /// Rewrite the multi-pack-index of the repository owning `directory` to include the received pack.
+                ///
+                /// Requires `directory` to be the `objects/pack` directory of a repository.
+                #[clap(long, requires = "directory")]
+                write_multi_pack_index: bool,
+
                 /// The directory into which to write the received pack and index.
                 ///
                 /// If unset, they will be discarded.
@@ -479,6 +865,13 @@ pub mod free {
                 /// The path into which all objects should be written. Commonly '.git/objects'
                 object_path: Option<PathBuf>,
             },
+            /// Show which pack contains an object, where it is located and the delta chain needed to decode it.
+            Explain {
+                /// The '.pack' or '.idx' file to search, or a directory containing them like '.git/objects/pack'.
+                path: PathBuf,
+                /// The full hexadecimal id of the object to explain.
+                id: git_repository::ObjectId,
+            },
             /// Verify the integrity of a pack, index or multi-index file
             Verify {
                 #[clap(flatten)]
@@ -595,6 +988,30 @@ pub mod free {
         }
     }
 
+    ///
+    pub mod serve {
+        use std::path::PathBuf;
+
+        #[derive(Debug, clap::Parser)]
+        pub struct Platform {
+            /// Speak smart HTTP instead of the `git://` protocol.
+            #[clap(long)]
+            pub http: bool,
+            /// The address to listen on, like `0.0.0.0:9418`, instead of port 9418, or 8080 with `--http`, on localhost.
+            #[clap(long, short = 'l')]
+            pub listen: Option<String>,
+            /// Serve all repositories, not only those with a `git-daemon-export-ok` file in their `.git` directory.
+            #[clap(long)]
+            pub export_all: bool,
+            /// Allow clients to push into the repositories that are served, without authentication.
+            #[clap(long)]
+            pub enable_receive_pack: bool,
+            /// The directory containing the repositories to serve, with the path of each request being relative to it.
+            #[clap(default_value = ".")]
+            pub base_path: PathBuf,
+        }
+    }
+
     ///
     pub mod mailmap {
         use std::path::PathBuf;
@@ -28,6 +28,228 @@ pub fn progress_tree() -> std::sync::Arc<prodash::Tree> {
     .into()
 }
 
+/// The format in which progress is displayed.
+#[allow(unused)] // Squelch warning because it's only used in plumbing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressFormat {
+    /// Render progress for humans using the line renderer or the terminal user interface.
+    Human,
+    /// Write progress as one JSON object per line to stderr, for consumption by other programs.
+    JsonLines,
+}
+
+#[allow(unused)]
+impl ProgressFormat {
+    pub fn variants() -> &'static [&'static str] {
+        &["human", "json-lines"]
+    }
+}
+
+impl std::str::FromStr for ProgressFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s_lc = s.to_ascii_lowercase();
+        Ok(match s_lc.as_str() {
+            "human" => ProgressFormat::Human,
+            "json-lines" => ProgressFormat::JsonLines,
+            _ => return Err(format!("Invalid progress format: '{}'", s)),
+        })
+    }
+}
+
+/// Determine how [`pretty::prepare_and_run()`] displays progress.
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressMode {
+    /// Use the line renderer if verbose, or display nothing.
+    Line,
+    /// Bring up the terminal user interface.
+    Tui,
+    /// Write progress as JSON lines to stderr, see [`json_lines`].
+    JsonLines,
+}
+
+impl From<bool> for ProgressMode {
+    /// Convert the toggle for the terminal user interface into a mode.
+    fn from(tui: bool) -> Self {
+        if tui {
+            ProgressMode::Tui
+        } else {
+            ProgressMode::Line
+        }
+    }
+}
+
+/// A progress renderer writing snapshots of the progress tree and new messages as JSON objects, one per line.
+///
+/// Each line is either a snapshot of all tasks like
+/// `{"kind":"tasks","tasks":[{"id":"…","parent":null,"level":1,"name":"clone","step":5,"max":10,"unit":"objects","state":"running"}]}`,
+/// emitted only if it changed since the last one, or a message like
+/// `{"kind":"message","time":1660000000000,"level":"info","origin":"clone","message":"…"}`.
+/// Task ids are stable for the lifetime of the task and `parent` refers to the id of the task containing it, if any.
+#[cfg(feature = "prodash-render-line")]
+pub mod json_lines {
+    use std::{
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+        io::Write,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Weak,
+        },
+        time::{Duration, SystemTime},
+    };
+
+    use prodash::{messages::MessageLevel, progress};
+    use serde_json::json;
+
+    /// A handle to the rendering thread which stops rendering when shut down.
+    pub struct JoinHandle {
+        inner: std::thread::JoinHandle<()>,
+        should_stop: Arc<AtomicBool>,
+    }
+
+    impl JoinHandle {
+        /// Render a final snapshot, then stop the rendering thread and wait for it to finish.
+        pub fn shutdown_and_wait(self) {
+            self.should_stop.store(true, Ordering::SeqCst);
+            self.inner.thread().unpark();
+            self.inner.join().ok();
+        }
+    }
+
+    /// Render all tasks of `progress` whose level is within `levels` to `out` `frames_per_second` times per second,
+    /// until the returned handle is shut down or `progress` is dropped.
+    pub fn render(
+        mut out: impl Write + Send + 'static,
+        progress: Weak<prodash::Tree>,
+        levels: std::ops::RangeInclusive<progress::key::Level>,
+        frames_per_second: f32,
+    ) -> JoinHandle {
+        let should_stop = Arc::new(AtomicBool::new(false));
+        let inner = std::thread::spawn({
+            let should_stop = Arc::clone(&should_stop);
+            move || {
+                let interval = Duration::from_secs_f32(1.0 / frames_per_second);
+                let mut tasks = Vec::new();
+                let mut messages = Vec::new();
+                let mut message_state = None;
+                let mut previous_line = String::new();
+                loop {
+                    let stop = should_stop.load(Ordering::SeqCst);
+                    let progress = match progress.upgrade() {
+                        Some(progress) => progress,
+                        None => break,
+                    };
+                    message_state = Some(progress.copy_new_messages(&mut messages, message_state));
+                    progress.sorted_snapshot(&mut tasks);
+                    drop(progress);
+
+                    let mut lines = String::new();
+                    for message in messages.drain(..) {
+                        lines.push_str(&message_line(&message));
+                    }
+                    let line = tasks_line(&tasks, &levels);
+                    if line != previous_line {
+                        lines.push_str(&line);
+                        previous_line = line;
+                    }
+                    if out.write_all(lines.as_bytes()).and_then(|_| out.flush()).is_err() || stop {
+                        break;
+                    }
+                    std::thread::park_timeout(interval);
+                }
+            }
+        });
+        JoinHandle { inner, should_stop }
+    }
+
+    fn tasks_line(
+        tasks: &[(progress::Key, progress::Task)],
+        levels: &std::ops::RangeInclusive<progress::key::Level>,
+    ) -> String {
+        let mut parents: Vec<String> = Vec::new();
+        let mut out = Vec::new();
+        for (key, task) in tasks {
+            let level = key.level();
+            let id = id_of(key);
+            parents.truncate(level.saturating_sub(1) as usize);
+            let parent = parents.last().cloned();
+            parents.push(id.clone());
+            if !levels.contains(&level) {
+                continue;
+            }
+            let mut entry = json!({
+                "id": id,
+                "parent": parent,
+                "level": level,
+                "name": task.name,
+                "step": null,
+                "max": null,
+                "unit": null,
+                "state": null,
+            });
+            if let Some(value) = &task.progress {
+                let step = value.step.load(Ordering::SeqCst);
+                let mut unit = String::new();
+                let unit = match &value.unit {
+                    Some(u) if u.as_display_value().display_unit(&mut unit, step).is_ok() => Some(unit.trim()),
+                    _ => None,
+                };
+                entry["step"] = json!(step);
+                entry["max"] = json!(value.done_at);
+                entry["unit"] = json!(unit);
+                match value.state {
+                    progress::State::Running => entry["state"] = json!("running"),
+                    progress::State::Blocked(reason, _) => {
+                        entry["state"] = json!("blocked");
+                        entry["reason"] = json!(reason);
+                    }
+                    progress::State::Halted(reason, _) => {
+                        entry["state"] = json!("halted");
+                        entry["reason"] = json!(reason);
+                    }
+                }
+            }
+            out.push(entry);
+        }
+        to_line(json!({ "kind": "tasks", "tasks": out }))
+    }
+
+    fn message_line(message: &prodash::messages::Message) -> String {
+        let time = message
+            .time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        let level = match message.level {
+            MessageLevel::Info => "info",
+            MessageLevel::Failure => "failure",
+            MessageLevel::Success => "success",
+        };
+        to_line(json!({
+            "kind": "message",
+            "time": time,
+            "level": level,
+            "origin": message.origin,
+            "message": message.message,
+        }))
+    }
+
+    fn to_line(value: serde_json::Value) -> String {
+        let mut line = value.to_string();
+        line.push('\n');
+        line
+    }
+
+    fn id_of(key: &progress::Key) -> String {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
+    }
+}
+
 #[cfg(not(feature = "prodash-render-line"))]
 pub struct LogCreator;
 
@@ -50,13 +272,13 @@ pub mod pretty {
     use anyhow::Result;
     use git_features::progress;
 
-    use crate::shared::ProgressRange;
+    use crate::shared::{ProgressMode, ProgressRange};
 
     #[cfg(feature = "small")]
     pub fn prepare_and_run<T>(
         name: &str,
         verbose: bool,
-        progress: bool,
+        progress: impl Into<ProgressMode>,
         #[cfg_attr(not(feature = "prodash-render-tui"), allow(unused_variables))] progress_keep_open: bool,
         range: impl Into<Option<ProgressRange>>,
         run: impl FnOnce(
@@ -65,22 +287,45 @@ pub mod pretty {
             &mut dyn std::io::Write,
         ) -> Result<T>,
     ) -> Result<T> {
-        crate::shared::init_env_logger();
+        let progress = progress.into();
+        // Messages are part of the JSON lines already, and logging them by default would break the format.
+        if !matches!(progress, ProgressMode::JsonLines) {
+            crate::shared::init_env_logger();
+        }
 
+        let range = range.into();
         match (verbose, progress) {
-            (false, false) => {
+            (_, ProgressMode::JsonLines) => {
+                let progress = crate::shared::progress_tree();
+                let sub_progress = progress.add_child(name);
+
+                use crate::shared::{self, STANDARD_RANGE};
+                let handle = shared::json_lines::render(
+                    stderr(),
+                    std::sync::Arc::downgrade(&progress),
+                    range.unwrap_or(STANDARD_RANGE),
+                    shared::DEFAULT_FRAME_RATE,
+                );
+
+                let mut out = Vec::<u8>::new();
+                let res = run(progress::DoOrDiscard::from(Some(sub_progress)), &mut out, &mut stderr());
+                handle.shutdown_and_wait();
+                std::io::Write::write_all(&mut stdout(), &out)?;
+                res
+            }
+            (false, ProgressMode::Line) => {
                 let stdout = stdout();
                 let mut stdout_lock = stdout.lock();
                 let stderr = stderr();
                 let mut stderr_lock = stderr.lock();
                 run(progress::DoOrDiscard::from(None), &mut stdout_lock, &mut stderr_lock)
             }
-            (true, false) => {
+            (true, ProgressMode::Line) => {
                 let progress = crate::shared::progress_tree();
                 let sub_progress = progress.add_child(name);
 
                 use crate::shared::{self, STANDARD_RANGE};
-                let handle = shared::setup_line_renderer_range(&progress, range.into().unwrap_or(STANDARD_RANGE));
+                let handle = shared::setup_line_renderer_range(&progress, range.unwrap_or(STANDARD_RANGE));
 
                 let mut out = Vec::<u8>::new();
                 let res = run(progress::DoOrDiscard::from(Some(sub_progress)), &mut out, &mut stderr());
@@ -89,7 +334,7 @@ pub mod pretty {
                 res
             }
             #[cfg(not(feature = "prodash-render-tui"))]
-            (true, true) | (false, true) => {
+            (_, ProgressMode::Tui) => {
                 unreachable!("BUG: This branch can't be run without a TUI built-in")
             }
         }
@@ -99,7 +344,7 @@ pub mod pretty {
     pub fn prepare_and_run<T: Send + 'static>(
         name: &str,
         verbose: bool,
-        progress: bool,
+        progress: impl Into<ProgressMode>,
         #[cfg_attr(not(feature = "prodash-render-tui"), allow(unused_variables))] progress_keep_open: bool,
         range: impl Into<Option<ProgressRange>>,
         run: impl FnOnce(
@@ -112,20 +357,39 @@ pub mod pretty {
     ) -> Result<T> {
         crate::shared::init_env_logger();
 
-        match (verbose, progress) {
-            (false, false) => {
+        let range = range.into();
+        match (verbose, progress.into()) {
+            (_, ProgressMode::JsonLines) => {
+                let progress = crate::shared::progress_tree();
+                let sub_progress = progress.add_child(name);
+
+                use crate::shared::{self, STANDARD_RANGE};
+                let handle = shared::json_lines::render(
+                    stderr(),
+                    std::sync::Arc::downgrade(&progress),
+                    range.unwrap_or(STANDARD_RANGE),
+                    shared::DEFAULT_FRAME_RATE,
+                );
+
+                let mut out = Vec::<u8>::new();
+                let res = run(progress::DoOrDiscard::from(Some(sub_progress)), &mut out, &mut stderr());
+                handle.shutdown_and_wait();
+                std::io::Write::write_all(&mut stdout(), &out)?;
+                res
+            }
+            (false, ProgressMode::Line) => {
                 let stdout = stdout();
                 let mut stdout_lock = stdout.lock();
                 let stderr = stderr();
                 let mut stderr_lock = stderr.lock();
                 run(progress::DoOrDiscard::from(None), &mut stdout_lock, &mut stderr_lock)
             }
-            (true, false) => {
+            (true, ProgressMode::Line) => {
                 let progress = crate::shared::progress_tree();
                 let sub_progress = progress.add_child(name);
 
                 use crate::shared::{self, STANDARD_RANGE};
-                let handle = shared::setup_line_renderer_range(&progress, range.into().unwrap_or(STANDARD_RANGE));
+                let handle = shared::setup_line_renderer_range(&progress, range.unwrap_or(STANDARD_RANGE));
 
                 let mut out = Vec::<u8>::new();
                 let res = run(progress::DoOrDiscard::from(Some(sub_progress)), &mut out, &mut stderr());
@@ -134,11 +398,11 @@ pub mod pretty {
                 res
             }
             #[cfg(not(feature = "prodash-render-tui"))]
-            (true, true) | (false, true) => {
+            (_, ProgressMode::Tui) => {
                 unreachable!("BUG: This branch can't be run without a TUI built-in")
             }
             #[cfg(feature = "prodash-render-tui")]
-            (true, true) | (false, true) => {
+            (_, ProgressMode::Tui) => {
                 use std::io::Write;
 
                 use crate::shared;
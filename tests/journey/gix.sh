@@ -65,10 +65,101 @@ title "gix (with repository)"
           expect_run $SUCCESSFULLY "$exe_plumbing" --format json verify --statistics
         }
       )
+      (with "--format json and an unsupported --format-version"
+        it "fails as it can't produce the requested JSON schema" && {
+          WITH_SNAPSHOT="$snapshot/unsupported-format-version-failure" \
+          expect_run $WITH_FAILURE "$exe_plumbing" --format json --format-version 2 verify --statistics
+        }
+      )
       fi
     )
   )
 
+  title "gix log"
+  (when "running 'log'"
+    snapshot="$snapshot/log"
+    (small-repo-in-sandbox
+      it "prints the subject and reference names of each commit along with the changed paths" && {
+        WITH_SNAPSHOT="$snapshot/format-and-stat" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" log --format '%s%d' --stat
+      }
+    )
+  )
+
+  title "gix archive"
+  (when "running 'archive'"
+    snapshot="$snapshot/archive"
+    (small-repo-in-sandbox
+      it "writes the tree of HEAD to the given file" && {
+        WITH_SNAPSHOT="$snapshot/to-file" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" archive --prefix project/ -o archive.tar
+      }
+      it "writes an archive readable by tar" && {
+        WITH_SNAPSHOT="$snapshot/to-file-listing" \
+        expect_run $SUCCESSFULLY tar tf archive.tar
+      }
+    )
+  )
+
+  title "gix refs edit"
+  (when "running 'refs edit'"
+    snapshot="$snapshot/refs/edit"
+    (small-repo-in-sandbox
+      it "shows the edits it would apply without changing references" && {
+        WITH_SNAPSHOT="$snapshot/dry-run" \
+        expect_run_sh $SUCCESSFULLY "printf 'update refs/heads/dev main\ncreate refs/heads/new dev\ndelete refs/tags/unannotated\n' | '$exe_plumbing' refs edit --dry-run"
+        expect_run $WITH_FAILURE git rev-parse --verify --quiet refs/heads/new
+      }
+      it "applies all edits in one transaction" && {
+        WITH_SNAPSHOT="$snapshot/apply" \
+        expect_run_sh $SUCCESSFULLY "printf 'update refs/heads/dev main\ncreate refs/heads/new dev\ndelete refs/tags/unannotated\n' | '$exe_plumbing' refs edit -m 'scripted'"
+      }
+      it "applies none of the edits if one of them fails" && {
+        WITH_SNAPSHOT="$snapshot/create-existing-failure" \
+        expect_run_sh $WITH_FAILURE "printf 'delete refs/heads/dev\ncreate refs/heads/new main\n' | '$exe_plumbing' refs edit"
+        expect_run $SUCCESSFULLY git rev-parse --verify --quiet refs/heads/dev
+      }
+      it "reports the line of malformed edits" && {
+        WITH_SNAPSHOT="$snapshot/unknown-command-failure" \
+        expect_run_sh $WITH_FAILURE "printf 'update refs/heads/dev main\nmove refs/heads/dev refs/heads/main\n' | '$exe_plumbing' refs edit"
+      }
+    )
+  )
+
+  title "gix config"
+  (when "running 'config'"
+    snapshot="$snapshot/config"
+    (small-repo-in-sandbox
+      it "sets values in the repository configuration" && {
+        expect_run $SUCCESSFULLY "$exe_plumbing" config set remote.origin.fetch '+refs/heads/*:refs/remotes/origin/*'
+        expect_run $SUCCESSFULLY "$exe_plumbing" config set --add remote.origin.fetch '+refs/tags/*:refs/tags/*'
+        expect_run $SUCCESSFULLY "$exe_plumbing" config set core.bigFileThreshold 2k
+      }
+      it "gets all values of a multi-valued key" && {
+        WITH_SNAPSHOT="$snapshot/get-all" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" config get --all remote.origin.fetch
+      }
+      it "gets values as the given type along with their scope" && {
+        WITH_SNAPSHOT="$snapshot/get-typed-with-scope" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" config get --show-scope --type int core.bigFileThreshold
+      }
+      it "refuses to replace multiple values with one" && {
+        WITH_SNAPSHOT="$snapshot/set-multi-value-failure" \
+        expect_run $WITH_FAILURE "$exe_plumbing" config set remote.origin.fetch '+refs/heads/main:refs/remotes/origin/main'
+      }
+      it "replaces and removes values matching a pattern" && {
+        expect_run $SUCCESSFULLY "$exe_plumbing" config set remote.origin.fetch '+refs/heads/main:refs/remotes/origin/main' 'heads'
+        expect_run $SUCCESSFULLY "$exe_plumbing" config unset remote.origin.fetch 'tags'
+        WITH_SNAPSHOT="$snapshot/get-after-unset" \
+        expect_run $SUCCESSFULLY git config --get-all remote.origin.fetch
+      }
+      it "fails to get unset values" && {
+        WITH_SNAPSHOT="$snapshot/get-missing-failure" \
+        expect_run $WITH_FAILURE "$exe_plumbing" config get --local remote.origin.url
+      }
+    )
+  )
+
   title "gix remote"
   (when "running 'remote'"
     snapshot="$snapshot/remote"
@@ -489,6 +580,34 @@ title "gix (with repository)"
       )
     )
 
+    title "gix free pack explain"
+    (with "the 'explain' sub-command"
+      snapshot="$snapshot/explain"
+      (sandbox
+        cp -R "$fixtures/packs" .
+        (with "a deltified object in a directory of packs"
+          it "shows its delta chain" && {
+            WITH_SNAPSHOT="$snapshot/delta-success" \
+            expect_run $SUCCESSFULLY "$exe_plumbing" free pack explain packs 8858983d81b0eef76eb55d21a0d96b7b16846eca
+          }
+        )
+        if test "$kind" = "max"; then
+        (with "--format json"
+          it "shows its delta chain" && {
+            WITH_SNAPSHOT="$snapshot/delta-json-success" \
+            expect_run $SUCCESSFULLY "$exe_plumbing" --format json free pack explain packs/pack-11fdfa9e156ab73caae3b6da867192221f2089c2.idx 8858983d81b0eef76eb55d21a0d96b7b16846eca
+          }
+        )
+        fi
+        (with "an object that isn't contained in any pack"
+          it "fails" && {
+            WITH_SNAPSHOT="$snapshot/missing-failure" \
+            expect_run $WITH_FAILURE "$exe_plumbing" free pack explain packs 0000000000000000000000000000000000000000
+          }
+        )
+      )
+    )
+
     title "gix free pack verify"
     (with "the 'verify' sub-command"
       snapshot="$snapshot/verify"
@@ -498,6 +617,12 @@ title "gix (with repository)"
           WITH_SNAPSHOT="$snapshot/success" \
           expect_run $SUCCESSFULLY "$exe_plumbing" free pack verify "$PACK_FILE"
         }
+        (with "progress in the json-lines format"
+          with_program jq
+          it "writes one JSON object per line to stderr, each being a task snapshot or a message" && {
+            expect_run_sh $SUCCESSFULLY "'$exe_plumbing' --progress-format json-lines free pack verify '$PACK_FILE' 2>&1 >/dev/null | jq -se 'any(.kind == \"tasks\") and all(.kind == \"tasks\" or .kind == \"message\")' >/dev/null"
+          }
+        )
       )
       (with "a valid pack INDEX file"
         MULTI_PACK_INDEX="$fixtures/packs/pack-11fdfa9e156ab73caae3b6da867192221f2089c2.idx"
@@ -637,5 +762,37 @@ title "gix (with repository)"
         )
       )
     )
+    title "gix free commit-graph list"
+    (with "the 'list' sub-command"
+      snapshot="$snapshot/list"
+
+      (small-repo-in-sandbox
+        (with "a commit-graph file"
+          git commit-graph write --reachable
+          it "lists the layer and all commits" && {
+            WITH_SNAPSHOT="$snapshot/success" \
+            expect_run $SUCCESSFULLY "$exe_plumbing" free commit-graph list .git/objects/info
+          }
+        )
+      )
+    )
+    title "gix free commit-graph query"
+    (with "the 'query' sub-command"
+      snapshot="$snapshot/query"
+
+      (small-repo-in-sandbox
+        (with "a commit-graph file"
+          git commit-graph write --reachable
+          it "shows information about the given commit" && {
+            WITH_SNAPSHOT="$snapshot/success" \
+            expect_run $SUCCESSFULLY "$exe_plumbing" free commit-graph query .git/objects/info "$(git rev-parse HEAD)"
+          }
+          it "fails for commits not in the graph" && {
+            WITH_SNAPSHOT="$snapshot/failure-unknown-commit" \
+            expect_run $WITH_FAILURE "$exe_plumbing" free commit-graph query .git/objects/info 0000000000000000000000000000000000000000
+          }
+        )
+      )
+    )
   )
 )
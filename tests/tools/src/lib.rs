@@ -21,6 +21,9 @@ use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 pub use tempfile;
 
+/// Create repositories from a declarative description of their commits, trees and references, without invoking `git`.
+pub mod spec;
+
 /// A result type to allow using the try operator `?` in unit tests.
 ///
 /// Use it like so:
@@ -162,6 +165,10 @@ pub fn fixture_bytes(path: impl AsRef<Path>) -> Vec<u8> {
 /// If a script result doesn't exist, these will be checked first and extracted if present, which they are by default.
 /// This behaviour can be prohibited by setting the `GITOXIDE_TEST_IGNORE_ARCHIVES` to any value.
 ///
+/// Archives are created deterministically, with entries in a stable order and normalized modification times, ownership
+/// and permissions, so regenerating them on another platform doesn't change them unless the script output changes.
+/// Nested repositories and submodules are supported as well.
+///
 /// To speed CI up, one can add these archives to the repository. It's absoutely recommended to use `git-lfs` for that to
 /// not bloat the repository size.
 ///
@@ -370,9 +377,7 @@ fn create_archive_if_not_on_ci(source_dir: &Path, archive: &Path, script_identit
         let mut buf = Vec::<u8>::new();
         {
             let mut ar = tar::Builder::new(&mut buf);
-            ar.mode(tar::HeaderMode::Deterministic);
-            ar.follow_symlinks(false);
-            ar.append_dir_all(".", source_dir)?;
+            append_dir_deterministically(&mut ar, source_dir, source_dir)?;
             ar.finish()?;
         }
         let archive = std::fs::OpenOptions::new()
@@ -391,6 +396,101 @@ fn create_archive_if_not_on_ci(source_dir: &Path, archive: &Path, script_identit
     res
 }
 
+/// The modification time of all archive entries, which is the same on all platforms, unlike the one of [`tar::HeaderMode::Deterministic`].
+const ARCHIVE_MTIME: u64 = 1153704088;
+
+/// Add all entries in `dir` below `root` to `ar` in an order and with metadata that is the same on all platforms, so archives
+/// only change if the content produced by a script changes.
+///
+/// Modification times and ownership are normalized, and permissions are `0o755` for directories and executable files and `0o644`
+/// otherwise. As there is no executable bit on Windows, files starting with `#!` are considered executable there.
+/// The absolute `gitdir: <path>` in `.git` files of nested repositories and submodules is made relative, so it stays valid
+/// wherever the archive is extracted.
+fn append_dir_deterministically<W: std::io::Write>(
+    ar: &mut tar::Builder<W>,
+    root: &Path,
+    dir: &Path,
+) -> std::io::Result<()> {
+    let mut entries = std::fs::read_dir(dir)?.collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let rela_path = path.strip_prefix(root).expect("entries are below root");
+        let file_type = entry.file_type()?;
+        let mut header = tar::Header::new_gnu();
+        header.set_mtime(ARCHIVE_MTIME);
+        header.set_uid(0);
+        header.set_gid(0);
+        if file_type.is_dir() {
+            header.set_entry_type(tar::EntryType::Directory);
+            header.set_mode(0o755);
+            header.set_size(0);
+            ar.append_data(&mut header, rela_path, std::io::empty())?;
+            append_dir_deterministically(ar, root, &path)?;
+        } else if file_type.is_symlink() {
+            header.set_entry_type(tar::EntryType::Symlink);
+            header.set_mode(0o777);
+            header.set_size(0);
+            let target = std::fs::read_link(&path)?;
+            ar.append_link(&mut header, rela_path, target.to_string_lossy().replace('\\', "/"))?;
+        } else {
+            let mut data = std::fs::read(&path)?;
+            if entry.file_name() == ".git" {
+                data = relative_git_dir_link(data, path.parent().expect("files are in a directory"));
+            }
+            header.set_entry_type(tar::EntryType::Regular);
+            header.set_mode(if is_executable(&entry.metadata()?, &data) {
+                0o755
+            } else {
+                0o644
+            });
+            header.set_size(data.len() as u64);
+            ar.append_data(&mut header, rela_path, &*data)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &std::fs::Metadata, _data: &[u8]) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o100 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_meta: &std::fs::Metadata, data: &[u8]) -> bool {
+    data.starts_with(b"#!")
+}
+
+/// If `data` is the content of a `.git` file in `dir` with an absolute `gitdir: <path>`, return it with the path relative to `dir`.
+fn relative_git_dir_link(data: Vec<u8>, dir: &Path) -> Vec<u8> {
+    let git_dir = match data
+        .strip_prefix(b"gitdir: ")
+        .and_then(|path| path.trim_end().to_path().ok())
+    {
+        Some(path) if path.is_absolute() => path,
+        _ => return data,
+    };
+    let (git_dir, dir) = match (git_dir.canonicalize(), dir.canonicalize()) {
+        (Ok(git_dir), Ok(dir)) => (git_dir, dir),
+        _ => return data,
+    };
+    let common = git_dir
+        .components()
+        .zip(dir.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if common == 0 {
+        return data;
+    }
+    let mut relative = PathBuf::new();
+    for _ in dir.components().skip(common) {
+        relative.push("..");
+    }
+    relative.extend(git_dir.components().skip(common));
+    format!("gitdir: {}\n", relative.to_string_lossy().replace('\\', "/")).into_bytes()
+}
+
 fn is_excluded(archive: &Path) -> bool {
     let mut lut = EXCLUDE_LUT.lock();
     lut.as_mut()
@@ -486,6 +586,15 @@ fn extract_archive(
         if path.to_str() == Some(META_DIR_NAME) || path.parent().and_then(|p| p.to_str()) == Some(META_DIR_NAME) {
             continue;
         }
+        #[cfg(windows)]
+        if entry.header().entry_type() == tar::EntryType::Symlink {
+            // Creating symlinks requires privileges, so do what `git` does with `core.symlinks = false` instead.
+            let target = entry.link_name()?.expect("symlinks have a target").into_owned();
+            let path = destination_dir.join(path);
+            std::fs::create_dir_all(path.parent().expect("entries are in a directory"))?;
+            std::fs::write(path, target.to_string_lossy().as_bytes())?;
+            continue;
+        }
         entry.unpack_in(&destination_dir)?;
     }
     Ok((archive_identity, platform))
@@ -557,6 +666,67 @@ mod tests {
         );
     }
 
+    fn archive(dir: &Path) -> std::io::Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        let mut ar = tar::Builder::new(&mut buf);
+        append_dir_deterministically(&mut ar, dir, dir)?;
+        ar.finish()?;
+        drop(ar);
+        Ok(buf)
+    }
+
+    #[test]
+    fn archives_do_not_depend_on_creation_order_or_time() -> crate::Result {
+        let (first, second) = (tempfile::TempDir::new()?, tempfile::TempDir::new()?);
+        for (dir, names) in [(first.path(), ["b", "a", "c"]), (second.path(), ["c", "a", "b"])] {
+            for name in names {
+                std::fs::create_dir_all(dir.join(name).join("sub"))?;
+                std::fs::write(dir.join(name).join("sub").join("file"), name)?;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(archive(first.path())?, archive(second.path())?);
+
+        let paths_and_modes = tar::Archive::new(&*archive(first.path())?)
+            .entries()?
+            .map(|entry| {
+                let entry = entry?;
+                Ok((entry.path()?.display().to_string(), entry.header().mode()?))
+            })
+            .collect::<std::io::Result<Vec<_>>>()?;
+        assert_eq!(
+            paths_and_modes[..3],
+            [
+                ("a".into(), 0o755),
+                ("a/sub".into(), 0o755),
+                ("a/sub/file".into(), 0o644)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn absolute_git_dir_links_become_relative() -> crate::Result {
+        let root = tempfile::TempDir::new()?;
+        let modules = root.path().join(".git").join("modules").join("sub");
+        let submodule = root.path().join("sub");
+        std::fs::create_dir_all(&modules)?;
+        std::fs::create_dir_all(&submodule)?;
+
+        let link = format!("gitdir: {}\n", modules.display()).into_bytes();
+        assert_eq!(
+            relative_git_dir_link(link, &submodule).as_bstr(),
+            "gitdir: ../.git/modules/sub\n"
+        );
+        let relative_link = b"gitdir: ../.git/modules/sub\n".to_vec();
+        assert_eq!(
+            relative_git_dir_link(relative_link.clone(), &submodule),
+            relative_link,
+            "relative links are kept"
+        );
+        Ok(())
+    }
+
     #[test]
     fn parse_version_with_trailing_newline() {
         assert_eq!(
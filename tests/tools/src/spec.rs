@@ -0,0 +1,481 @@
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use bstr::{BString, ByteSlice};
+use git_object::{tree::EntryMode, WriteTo};
+
+use crate::Result;
+
+/// A directory tree given inline, with files at `/` separated paths whose parent directories are created as needed.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Tree {
+    entries: BTreeMap<BString, Node>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Blob { mode: EntryMode, data: BString },
+    Tree(Tree),
+}
+
+impl Tree {
+    /// Create a new empty tree.
+    pub fn new() -> Self {
+        Tree::default()
+    }
+
+    /// Add a regular file at `path` with `content`.
+    pub fn file(self, path: &str, content: impl Into<BString>) -> Self {
+        self.add(path, EntryMode::Blob, content.into())
+    }
+
+    /// Add an executable file at `path` with `content`.
+    pub fn executable(self, path: &str, content: impl Into<BString>) -> Self {
+        self.add(path, EntryMode::BlobExecutable, content.into())
+    }
+
+    /// Add a symbolic link at `path` pointing to `target`.
+    pub fn symlink(self, path: &str, target: &str) -> Self {
+        self.add(path, EntryMode::Link, target.into())
+    }
+
+    fn add(mut self, path: &str, mode: EntryMode, data: BString) -> Self {
+        self.insert(path, Node::Blob { mode, data });
+        self
+    }
+
+    fn insert(&mut self, path: &str, node: Node) {
+        match path.split_once('/') {
+            Some((dir, rest)) => {
+                let subtree = self
+                    .entries
+                    .entry(dir.into())
+                    .or_insert_with(|| Node::Tree(Tree::default()));
+                if let Node::Blob { .. } = subtree {
+                    *subtree = Node::Tree(Tree::default());
+                }
+                if let Node::Tree(tree) = subtree {
+                    tree.insert(rest, node)
+                }
+            }
+            None => {
+                self.entries.insert(path.into(), node);
+            }
+        }
+    }
+
+    /// Call `cb` with the worktree-relative path, mode and data of each blob in this tree, depth-first in index order.
+    fn visit_blobs(&self, prefix: &mut BString, cb: &mut dyn FnMut(&BString, EntryMode, &BString)) {
+        let mut names: Vec<_> = self.entries.iter().collect();
+        names.sort_by_key(|(name, node)| sort_key(name, node));
+        for (name, node) in names {
+            let prev_len = prefix.len();
+            if !prefix.is_empty() {
+                prefix.push(b'/');
+            }
+            prefix.extend_from_slice(name);
+            match node {
+                Node::Blob { mode, data } => cb(prefix, *mode, data),
+                Node::Tree(tree) => tree.visit_blobs(prefix, cb),
+            }
+            prefix.truncate(prev_len);
+        }
+    }
+}
+
+/// Git sorts tree entries as if directories had a trailing slash.
+fn sort_key(name: &BString, node: &Node) -> BString {
+    let mut key = name.clone();
+    if let Node::Tree(_) = node {
+        key.push(b'/');
+    }
+    key
+}
+
+/// A repository given as declarative description of its commits and references, which can be
+/// [written][Repo::write_to()] to disk without invoking `git`.
+///
+/// Commits and references are written in the order they were added. Commits are identified by a name which can be used
+/// to refer to them as parents, as reference targets, and to learn their id from the [`Outcome`] once written.
+///
+/// All commits use the same author and committer identities and dates as scripted fixtures do, which makes the ids of the
+/// produced objects stable.
+#[derive(Debug, Clone)]
+pub struct Repo {
+    bare: bool,
+    commits: Vec<(String, Commit)>,
+    references: Vec<(String, String)>,
+    head: String,
+}
+
+#[derive(Debug, Clone)]
+struct Commit {
+    message: String,
+    parents: Vec<String>,
+    tree: Tree,
+}
+
+/// The result of [`Repo::write_to()`].
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The path to the `.git` directory, or the repository itself if it is bare.
+    pub git_dir: PathBuf,
+    /// The path to the work tree, or `None` if the repository is bare.
+    pub work_dir: Option<PathBuf>,
+    /// The ids of all commits by the name they were given.
+    pub commits: BTreeMap<String, git_hash::ObjectId>,
+}
+
+/// Initialization
+impl Repo {
+    /// A repository with a work tree whose `HEAD` points to `refs/heads/main`.
+    pub fn new() -> Self {
+        Repo {
+            bare: false,
+            commits: Vec::new(),
+            references: Vec::new(),
+            head: "ref: refs/heads/main".into(),
+        }
+    }
+
+    /// A bare repository whose `HEAD` points to `refs/heads/main`.
+    pub fn bare() -> Self {
+        Repo {
+            bare: true,
+            ..Repo::new()
+        }
+    }
+}
+
+impl Default for Repo {
+    fn default() -> Self {
+        Repo::new()
+    }
+}
+
+/// Description
+impl Repo {
+    /// Add a commit called `name` with `message`, the commits named `parents` and the given `tree`.
+    ///
+    /// Parents must have been added before.
+    pub fn commit(mut self, name: &str, message: &str, parents: &[&str], tree: Tree) -> Self {
+        self.commits.push((
+            name.into(),
+            Commit {
+                message: message.into(),
+                parents: parents.iter().map(|p| (*p).to_owned()).collect(),
+                tree,
+            },
+        ));
+        self
+    }
+
+    /// Add the reference with full `name`, like `refs/heads/main`, pointing to the commit named `target`.
+    pub fn reference(mut self, name: &str, target: &str) -> Self {
+        self.references.push((name.into(), target.into()));
+        self
+    }
+
+    /// Let `HEAD` point to the reference with full `name`, like `refs/heads/feature`.
+    pub fn head_to_reference(mut self, name: &str) -> Self {
+        self.head = format!("ref: {}", name);
+        self
+    }
+
+    /// Detach `HEAD` and let it point to the commit named `target`.
+    pub fn head_to_commit(mut self, target: &str) -> Self {
+        self.head = target.into();
+        self
+    }
+}
+
+/// Creation
+impl Repo {
+    /// Write the repository into the existing directory `dir`, and return where it was written along with the ids of all commits.
+    ///
+    /// Repositories with work tree get all files of the commit `HEAD` points to checked out, with an index to match.
+    pub fn write_to(&self, dir: impl AsRef<Path>) -> Result<Outcome> {
+        let dir = dir.as_ref();
+        let (git_dir, work_dir) = if self.bare {
+            (dir.to_owned(), None)
+        } else {
+            (dir.join(".git"), Some(dir.to_owned()))
+        };
+        for sub_dir in ["objects/info", "objects/pack", "refs/heads", "refs/tags", "info"] {
+            std::fs::create_dir_all(git_dir.join(sub_dir))?;
+        }
+        std::fs::write(
+            git_dir.join("config"),
+            format!(
+                "[core]\n\trepositoryformatversion = 0\n\tfilemode = {}\n\tbare = {}\n{}",
+                !cfg!(windows),
+                self.bare,
+                if self.bare { "" } else { "\tlogallrefupdates = true\n" }
+            ),
+        )?;
+
+        let mut commits = BTreeMap::new();
+        let mut trees = BTreeMap::new();
+        for (name, commit) in &self.commits {
+            let tree = write_tree(&git_dir, &commit.tree)?;
+            let signature = |name: &str, seconds_since_unix_epoch| git_actor::Signature {
+                name: name.into(),
+                email: format!("{}@example.com", name).into(),
+                time: git_actor::Time {
+                    seconds_since_unix_epoch,
+                    offset_in_seconds: 0,
+                    sign: git_actor::Sign::Plus,
+                },
+            };
+            let mut data = Vec::new();
+            git_object::Commit {
+                tree,
+                parents: commit
+                    .parents
+                    .iter()
+                    .map(|parent| lookup(&commits, parent))
+                    .collect::<Result<_>>()?,
+                author: signature("author", 946684800),
+                committer: signature("committer", 946771200),
+                encoding: None,
+                message: format!("{}\n", commit.message).into(),
+                extra_headers: Vec::new(),
+            }
+            .write_to(&mut data)?;
+            let id = write_object(&git_dir, git_object::Kind::Commit, &data)?;
+            commits.insert(name.clone(), id);
+            trees.insert(name.clone(), &commit.tree);
+        }
+
+        for (name, target) in &self.references {
+            let path = git_dir.join(name);
+            std::fs::create_dir_all(path.parent().expect("references are in a directory"))?;
+            std::fs::write(path, format!("{}\n", lookup(&commits, target)?))?;
+        }
+        let head_tree = match self.head.strip_prefix("ref: ") {
+            Some(head_ref) => {
+                std::fs::write(git_dir.join("HEAD"), format!("{}\n", self.head))?;
+                self.references
+                    .iter()
+                    .rev()
+                    .find(|(name, _)| name == head_ref)
+                    .and_then(|(_, target)| trees.get(target).copied())
+            }
+            None => {
+                std::fs::write(git_dir.join("HEAD"), format!("{}\n", lookup(&commits, &self.head)?))?;
+                trees.get(&self.head).copied()
+            }
+        };
+
+        if let (Some(work_dir), Some(tree)) = (&work_dir, head_tree) {
+            checkout(&git_dir, work_dir, tree)?;
+        }
+        Ok(Outcome {
+            git_dir,
+            work_dir,
+            commits,
+        })
+    }
+
+    /// Like [`write_to()`][Repo::write_to()], but writes into a new temporary directory which is removed when dropped.
+    pub fn write_to_tempdir(&self) -> Result<(tempfile::TempDir, Outcome)> {
+        let dir = tempfile::TempDir::new()?;
+        let outcome = self.write_to(dir.path())?;
+        Ok((dir, outcome))
+    }
+}
+
+fn lookup(commits: &BTreeMap<String, git_hash::ObjectId>, name: &str) -> Result<git_hash::ObjectId> {
+    commits.get(name).copied().ok_or_else(|| {
+        format!(
+            "There is no commit named {:?} - it must be added before it is used",
+            name
+        )
+        .into()
+    })
+}
+
+/// Trees are encoded by hand as git sorts directories as if they had a trailing slash, which `git_object::Tree` doesn't know.
+fn write_tree(git_dir: &Path, tree: &Tree) -> Result<git_hash::ObjectId> {
+    let mut entries = Vec::with_capacity(tree.entries.len());
+    for (name, node) in &tree.entries {
+        let (mode, oid) = match node {
+            Node::Blob { mode, data } => (*mode, write_object(git_dir, git_object::Kind::Blob, data)?),
+            Node::Tree(tree) => (EntryMode::Tree, write_tree(git_dir, tree)?),
+        };
+        entries.push((sort_key(name, node), name, mode, oid));
+    }
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut data = Vec::new();
+    for (_, name, mode, oid) in entries {
+        data.extend_from_slice(mode.as_bytes());
+        data.push(b' ');
+        data.extend_from_slice(name);
+        data.push(0);
+        data.extend_from_slice(oid.as_bytes());
+    }
+    write_object(git_dir, git_object::Kind::Tree, &data)
+}
+
+fn write_object(git_dir: &Path, kind: git_object::Kind, data: &[u8]) -> Result<git_hash::ObjectId> {
+    let mut object = git_object::encode::loose_header(kind, data.len()).to_vec();
+    object.extend_from_slice(data);
+    let mut hasher = git_features::hash::hasher(git_hash::Kind::Sha1);
+    hasher.update(&object);
+    let id = hasher.digest();
+
+    let hex = id.to_hex().to_string();
+    let path = git_dir.join("objects").join(&hex[..2]).join(&hex[2..]);
+    if !path.is_file() {
+        std::fs::create_dir_all(path.parent().expect("object files are in a directory"))?;
+        let mut out = git_features::zlib::stream::deflate::Write::new(Vec::new());
+        out.write_all(&object)?;
+        out.flush()?;
+        std::fs::write(path, out.into_inner())?;
+    }
+    Ok(id)
+}
+
+/// Write all files in `tree` into `work_dir` along with an index file in version 2 to match.
+///
+/// The index contains no stat information, which makes `git` refresh it when it is used the first time.
+fn checkout(git_dir: &Path, work_dir: &Path, tree: &Tree) -> Result<()> {
+    let mut index = Vec::new();
+    let mut num_entries = 0u32;
+    let mut err = None;
+    tree.visit_blobs(&mut BString::default(), &mut |rela_path, mode, data| {
+        let res = (|| -> Result<()> {
+            let path = work_dir.join(rela_path.to_path()?);
+            std::fs::create_dir_all(path.parent().expect("files are in a directory"))?;
+            match mode {
+                #[cfg(unix)]
+                EntryMode::Link => std::os::unix::fs::symlink(data.to_path()?, &path)?,
+                _ => std::fs::write(&path, data)?,
+            }
+            #[cfg(unix)]
+            if mode == EntryMode::BlobExecutable {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+            }
+
+            let entry_start = index.len();
+            index.extend_from_slice(&[0; 6 * 4]); // ctime, mtime, dev, ino
+            index.extend_from_slice(&(mode as u32).to_be_bytes());
+            index.extend_from_slice(&[0; 2 * 4]); // uid, gid
+            index.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            index.extend_from_slice(write_object(git_dir, git_object::Kind::Blob, data)?.as_bytes());
+            index.extend_from_slice(&(rela_path.len().min(0xfff) as u16).to_be_bytes());
+            index.extend_from_slice(rela_path);
+            let padding = 8 - (index.len() - entry_start) % 8;
+            index.resize(index.len() + padding, 0);
+            num_entries += 1;
+            Ok(())
+        })();
+        if let Err(e) = res {
+            err.get_or_insert(e);
+        }
+    });
+    if let Some(err) = err {
+        return Err(err);
+    }
+
+    let mut file = b"DIRC".to_vec();
+    file.extend_from_slice(&2u32.to_be_bytes());
+    file.extend_from_slice(&num_entries.to_be_bytes());
+    file.extend_from_slice(&index);
+    let mut hasher = git_features::hash::hasher(git_hash::Kind::Sha1);
+    hasher.update(&file);
+    file.extend_from_slice(hasher.digest().as_bytes());
+    std::fs::write(git_dir.join("index"), file)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let out = std::process::Command::new("git")
+            .current_dir(dir)
+            .args(args)
+            .env("GIT_AUTHOR_DATE", "2000-01-01 00:00:00 +0000")
+            .env("GIT_AUTHOR_EMAIL", "author@example.com")
+            .env("GIT_AUTHOR_NAME", "author")
+            .env("GIT_COMMITTER_DATE", "2000-01-02 00:00:00 +0000")
+            .env("GIT_COMMITTER_EMAIL", "committer@example.com")
+            .env("GIT_COMMITTER_NAME", "committer")
+            .output()
+            .expect("git can be executed");
+        assert!(out.status.success(), "{:?} failed: {}", args, out.stderr.as_bstr());
+        out.stdout.to_str_lossy().trim().to_owned()
+    }
+
+    #[test]
+    fn commits_references_and_worktree_are_valid_for_git() -> Result {
+        let (_tmp, out) = Repo::new()
+            .commit(
+                "first",
+                "initial",
+                &[],
+                Tree::new().file("a", "a\n").file("dir/b", "b\n"),
+            )
+            .commit(
+                "second",
+                "more",
+                &["first"],
+                Tree::new()
+                    .file("a", "changed\n")
+                    .file("dir/b", "b\n")
+                    .file("dir.txt", "sorted before dir/\n")
+                    .executable("dir/run", "#!/bin/sh\n")
+                    .symlink("link", "a"),
+            )
+            .reference("refs/heads/main", "second")
+            .reference("refs/tags/v1", "first")
+            .write_to_tempdir()?;
+
+        let work_dir = out.work_dir.as_deref().expect("not bare");
+        git(work_dir, &["fsck", "--strict", "--no-dangling"]);
+        assert_eq!(git(work_dir, &["rev-parse", "main"]), out.commits["second"].to_string());
+        assert_eq!(git(work_dir, &["rev-parse", "v1"]), out.commits["first"].to_string());
+        assert_eq!(git(work_dir, &["rev-parse", "HEAD^"]), out.commits["first"].to_string());
+        assert_eq!(
+            git(work_dir, &["status", "--porcelain"]),
+            "",
+            "worktree and index match HEAD"
+        );
+        assert_eq!(std::fs::read(work_dir.join("a"))?, b"changed\n");
+        Ok(())
+    }
+
+    #[test]
+    fn object_ids_are_stable_and_match_scripted_fixtures() -> Result {
+        let (_tmp, out) = Repo::bare()
+            .commit("c1", "c1", &[], Tree::new())
+            .reference("refs/heads/main", "c1")
+            .head_to_commit("c1")
+            .write_to_tempdir()?;
+        git(&out.git_dir, &["fsck", "--strict"]);
+        assert_eq!(
+            out.commits["c1"].to_string(),
+            git(
+                &out.git_dir,
+                &["commit-tree", "4b825dc642cb6eb9a060e54bf8d69288fbee4904", "-m", "c1"]
+            ),
+            "the same identities and dates are used"
+        );
+        assert!(out.work_dir.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_commits_are_an_error() {
+        assert!(Repo::bare()
+            .reference("refs/heads/main", "missing")
+            .write_to_tempdir()
+            .is_err());
+    }
+}